@@ -0,0 +1,407 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use glam::{Mat4, Quat, Vec3};
+use log::error;
+
+use crate::{
+    Shared, SharedAccess,
+    animations::Animation,
+    types::{DeltaTime, ids::MeshId, transform::Transform},
+};
+
+/// How a channel moves between two keyframes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Linear,
+    /// Holds each keyframe's value until the next one.
+    Step,
+}
+
+/// The node property a channel drives, with one value per keyframe.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyframeValues {
+    Translation(Vec<Vec3>),
+    Rotation(Vec<Quat>),
+    Scale(Vec<Vec3>),
+}
+
+impl KeyframeValues {
+    fn len(&self) -> usize {
+        match self {
+            Self::Translation(values) | Self::Scale(values) => values.len(),
+            Self::Rotation(values) => values.len(),
+        }
+    }
+}
+
+/// Keyframes of one property of one node, with times in seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyframeChannel {
+    times: Vec<f32>,
+    values: KeyframeValues,
+    interpolation: Interpolation,
+}
+
+impl KeyframeChannel {
+    pub fn new(
+        times: Vec<f32>,
+        values: KeyframeValues,
+        interpolation: Interpolation,
+    ) -> Result<Self> {
+        if times.is_empty() {
+            return Err(anyhow!("A keyframe channel needs at least one keyframe"));
+        }
+        if times.len() != values.len() {
+            return Err(anyhow!(
+                "Keyframe channel has {} times but {} values",
+                times.len(),
+                values.len()
+            ));
+        }
+        if times.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(anyhow!("Keyframe times must not decrease"));
+        }
+        Ok(Self {
+            times,
+            values,
+            interpolation,
+        })
+    }
+
+    /// Time of the last keyframe.
+    pub fn end_time(&self) -> f32 {
+        self.times[self.times.len() - 1]
+    }
+
+    /// Writes the channel's value at `time` into `transform`. Before the first and after the last
+    /// keyframe the nearest one holds.
+    pub fn apply(&self, time: f32, transform: &mut Transform) {
+        let (index, factor) = self.locate(time);
+        match &self.values {
+            KeyframeValues::Translation(values) => {
+                transform.position = self.sample(values, index, factor, Vec3::lerp);
+            }
+            KeyframeValues::Rotation(values) => {
+                transform.rotation = self.sample(values, index, factor, Quat::slerp).normalize();
+            }
+            KeyframeValues::Scale(values) => {
+                transform.scale = self.sample(values, index, factor, Vec3::lerp);
+            }
+        }
+    }
+
+    /// The keyframe at or before `time` and how far, in `0.0..1.0`, `time` is towards the next.
+    fn locate(&self, time: f32) -> (usize, f32) {
+        let next = self.times.partition_point(|&keyframe| keyframe <= time);
+        if next == 0 {
+            return (0, 0.0);
+        }
+        if next == self.times.len() {
+            return (next - 1, 0.0);
+        }
+        let (start, end) = (self.times[next - 1], self.times[next]);
+        (next - 1, (time - start) / (end - start))
+    }
+
+    fn sample<T: Copy>(
+        &self,
+        values: &[T],
+        index: usize,
+        factor: f32,
+        lerp: fn(T, T, f32) -> T,
+    ) -> T {
+        match self.interpolation {
+            Interpolation::Step => values[index],
+            Interpolation::Linear if factor > 0.0 => lerp(values[index], values[index + 1], factor),
+            Interpolation::Linear => values[index],
+        }
+    }
+}
+
+/// A node on the way from the scene root to an animated mesh: its rest pose and the channels that
+/// move it. Nodes without channels keep their rest pose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatedNode {
+    pub rest: Transform,
+    pub channels: Vec<KeyframeChannel>,
+}
+
+impl AnimatedNode {
+    pub fn new(rest: Transform, channels: Vec<KeyframeChannel>) -> Self {
+        Self { rest, channels }
+    }
+
+    fn local_at(&self, time: f32) -> Transform {
+        let mut local = self.rest;
+        for channel in &self.channels {
+            channel.apply(time, &mut local);
+        }
+        local
+    }
+}
+
+/// Plays imported keyframes on a mesh, looping after the last keyframe of any channel. The mesh
+/// transform is rebuilt from every node on its path, so animated parents move their children.
+#[derive(Debug, Clone)]
+pub struct KeyframeAnimation {
+    pub id: MeshId,
+    transform: Shared<Transform>,
+    path: Arc<[AnimatedNode]>,
+    duration: f32,
+    time: f32,
+    offset: Vec3,
+}
+
+impl KeyframeAnimation {
+    /// `path` runs from the root node down to the mesh's own node.
+    pub fn new(id: MeshId, transform: Shared<Transform>, path: Vec<AnimatedNode>) -> Self {
+        let duration = path
+            .iter()
+            .flat_map(|node| &node.channels)
+            .map(KeyframeChannel::end_time)
+            .fold(0.0, f32::max);
+        Self {
+            id,
+            transform,
+            path: path.into(),
+            duration,
+            time: 0.0,
+            offset: Vec3::ZERO,
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// The mesh's world transform `time` seconds into the animation.
+    pub fn pose_at(&self, time: f32) -> Transform {
+        let world = self.path.iter().fold(Mat4::IDENTITY, |world, node| {
+            world * node.local_at(time).get_matrix()
+        });
+        let (scale, rotation, translation) = world.to_scale_rotation_translation();
+        Transform::new(translation + self.offset, rotation, scale)
+    }
+
+    fn write_pose(&self) -> Result<()> {
+        let pose = self.pose_at(self.time);
+        self.transform
+            .try_write_shared(|transform| *transform = pose)
+            .map_err(|_e| anyhow!("Failed to aquire lock acquisition!"))
+    }
+}
+
+impl Animation for KeyframeAnimation {
+    fn animate(&mut self, _target: Option<&Transform>, delta: DeltaTime) -> Result<()> {
+        self.time += delta;
+        if self.duration > 0.0 {
+            self.time = self.time.rem_euclid(self.duration);
+        }
+        self.write_pose()
+    }
+
+    fn reset(&mut self) {
+        self.time = 0.0;
+        if let Err(e) = self.write_pose() {
+            error!("Failed to reset animation with id {:?}: {}", self.id, e);
+        }
+    }
+
+    fn duplicate(
+        &self,
+        id: MeshId,
+        transform: Shared<Transform>,
+        offset: Vec3,
+    ) -> Option<Box<dyn Animation>> {
+        Some(Box::new(Self {
+            id,
+            transform,
+            time: 0.0,
+            offset: self.offset + offset,
+            ..self.clone()
+        }))
+    }
+
+    fn get_id(&self) -> &MeshId {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::{
+        shared,
+        util::test_support::{assert_quat_eq, assert_vec3_eq},
+    };
+
+    fn translation_channel(interpolation: Interpolation) -> KeyframeChannel {
+        KeyframeChannel::new(
+            vec![0.0, 1.0, 3.0],
+            KeyframeValues::Translation(vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 4.0, 0.0)]),
+            interpolation,
+        )
+        .unwrap()
+    }
+
+    fn sample(channel: &KeyframeChannel, time: f32) -> Transform {
+        let mut transform = Transform::default();
+        channel.apply(time, &mut transform);
+        transform
+    }
+
+    #[test]
+    fn test_linear_channel_interpolates_between_keyframes() {
+        let channel = translation_channel(Interpolation::Linear);
+
+        assert_vec3_eq(
+            sample(&channel, 0.5).position,
+            Vec3::new(0.5, 0.0, 0.0),
+            "first span",
+        );
+        assert_vec3_eq(
+            sample(&channel, 2.0).position,
+            Vec3::new(1.0, 2.0, 0.0),
+            "second span",
+        );
+        assert_vec3_eq(sample(&channel, 1.0).position, Vec3::X, "on a keyframe");
+    }
+
+    #[test]
+    fn test_step_channel_holds_each_keyframe() {
+        let channel = translation_channel(Interpolation::Step);
+
+        assert_vec3_eq(
+            sample(&channel, 0.99).position,
+            Vec3::ZERO,
+            "before the second key",
+        );
+        assert_vec3_eq(
+            sample(&channel, 2.5).position,
+            Vec3::X,
+            "before the third key",
+        );
+    }
+
+    #[test]
+    fn test_channel_clamps_outside_its_keyframes() {
+        let channel = translation_channel(Interpolation::Linear);
+
+        assert_vec3_eq(
+            sample(&channel, -1.0).position,
+            Vec3::ZERO,
+            "before the start",
+        );
+        assert_vec3_eq(
+            sample(&channel, 10.0).position,
+            Vec3::new(1.0, 4.0, 0.0),
+            "after the end",
+        );
+    }
+
+    #[test]
+    fn test_rotation_channel_slerps() {
+        let channel = KeyframeChannel::new(
+            vec![0.0, 1.0],
+            KeyframeValues::Rotation(vec![Quat::IDENTITY, Quat::from_rotation_y(PI / 2.0)]),
+            Interpolation::Linear,
+        )
+        .unwrap();
+
+        assert_quat_eq(
+            sample(&channel, 0.5).rotation,
+            Quat::from_rotation_y(PI / 4.0),
+            "halfway rotation",
+        );
+    }
+
+    #[test]
+    fn test_channel_rejects_mismatched_or_unordered_keyframes() {
+        let values = KeyframeValues::Scale(vec![Vec3::ONE, Vec3::ONE]);
+
+        assert!(KeyframeChannel::new(vec![0.0], values.clone(), Interpolation::Linear).is_err());
+        assert!(KeyframeChannel::new(vec![1.0, 0.0], values, Interpolation::Linear).is_err());
+        assert!(
+            KeyframeChannel::new(vec![], KeyframeValues::Scale(vec![]), Interpolation::Step)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_animation_composes_the_path_and_loops() {
+        let parent = AnimatedNode::new(
+            Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE),
+            vec![
+                KeyframeChannel::new(
+                    vec![0.0, 1.0, 2.0],
+                    KeyframeValues::Rotation(vec![
+                        Quat::IDENTITY,
+                        Quat::from_rotation_y(PI / 2.0),
+                        Quat::from_rotation_y(PI),
+                    ]),
+                    Interpolation::Linear,
+                )
+                .unwrap(),
+            ],
+        );
+        let child = AnimatedNode::new(
+            Transform::new(Vec3::X, Quat::IDENTITY, Vec3::ONE),
+            Vec::new(),
+        );
+        let transform = shared(Transform::default());
+        let mut animation = KeyframeAnimation::new(
+            MeshId("Child".to_string()),
+            transform.clone(),
+            vec![parent, child],
+        );
+        assert_eq!(animation.duration(), 2.0);
+
+        animation.animate(None, 1.0).unwrap();
+        assert_vec3_eq(
+            transform.read_shared(|t| t.position),
+            Vec3::new(0.0, 0.0, -1.0),
+            "child swung a quarter turn by its parent",
+        );
+
+        animation.animate(None, 2.0).unwrap();
+        assert_vec3_eq(
+            transform.read_shared(|t| t.position),
+            Vec3::new(0.0, 0.0, -1.0),
+            "one full loop later",
+        );
+
+        animation.reset();
+        assert_vec3_eq(transform.read_shared(|t| t.position), Vec3::X, "reset pose");
+    }
+
+    #[test]
+    fn test_duplicate_plays_shifted_on_its_own_transform() {
+        let node = AnimatedNode::new(
+            Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE),
+            vec![translation_channel(Interpolation::Linear)],
+        );
+        let original_transform = shared(Transform::default());
+        let original = KeyframeAnimation::new(
+            MeshId("Original".to_string()),
+            original_transform.clone(),
+            vec![node],
+        );
+        let copy_transform = shared(Transform::default());
+
+        let mut copy = original
+            .duplicate(MeshId("Copy".to_string()), copy_transform.clone(), Vec3::Z)
+            .unwrap();
+        copy.animate(None, 0.5).unwrap();
+
+        assert_eq!(copy.get_id(), &MeshId("Copy".to_string()));
+        assert_vec3_eq(
+            copy_transform.read_shared(|t| t.position),
+            Vec3::new(0.5, 0.0, 1.0),
+            "copy position",
+        );
+        assert_eq!(original_transform.read_shared(|t| t.position), Vec3::ZERO);
+    }
+}
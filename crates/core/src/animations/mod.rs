@@ -1,15 +1,38 @@
-use crate::types::{DeltaTime, DeltaTime64, ids::MeshId, transform::Transform};
+use crate::{
+    MaybeSend, Shared,
+    types::{DeltaTime, DeltaTime64, ids::MeshId, transform::Transform},
+};
 use anyhow::{Result, anyhow};
+use glam::Vec3;
 
+pub mod keyframes;
 pub mod trajectory;
 
 pub const NEUTRAL_SPEED: f32 = 1.0;
 
+/// The parameters a trajectory was built from, enough to build it again e.g. from a saved scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrajectoryDescriptor {
+    Linear {
+        start_position: Vec3,
+        yaw_radians: f32,
+        pitch_radians: f32,
+        distance: f32,
+        speed: f32,
+        looping: bool,
+        reversing: bool,
+    },
+    Circular {
+        radius: f32,
+        speed: f32,
+    },
+}
+
 /// A trait to implement when specific trajectory path are to be implemented.
 /// The animate(...) most likely uses a try_write on a Shared<Transform>> which could
 /// panic but should be handled gracefully. Nonetheless you can match the result to get the
 /// error that occurs when try_write fails to acquire the lock.
-pub trait Animation {
+pub trait Animation: MaybeSend {
     fn get_id(&self) -> &MeshId;
     /// t: Option<&Transform> is a target transform
     /// when an animation allows an animated object to hover around another
@@ -17,6 +40,21 @@ pub trait Animation {
     /// in other it can be a different Transform from a different object.
     fn animate(&mut self, t: Option<&Transform>, delta: DeltaTime) -> Result<()>;
     fn reset(&mut self);
+    /// Fresh copy of this animation driving another mesh, with its path shifted by `offset`.
+    /// Animations that cannot be retargeted return None.
+    fn duplicate(
+        &self,
+        _id: MeshId,
+        _transform: Shared<Transform>,
+        _offset: Vec3,
+    ) -> Option<Box<dyn Animation>> {
+        None
+    }
+
+    /// How to build this animation again. Animations that cannot be described return None.
+    fn descriptor(&self) -> Option<TrajectoryDescriptor> {
+        None
+    }
 }
 
 pub struct Animator {
@@ -27,6 +65,17 @@ pub struct Animator {
     animation: Box<dyn Animation>,
 }
 
+impl std::fmt::Debug for Animator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Animator")
+            .field("id", &self.id)
+            .field("elapsed_time", &self.elapsed_time)
+            .field("speed_multiplier", &self.speed_multiplier)
+            .field("is_currently_playing", &self.is_currently_playing)
+            .finish_non_exhaustive()
+    }
+}
+
 impl Animator {
     pub fn new(speed_multiplier: f32, animation: Box<dyn Animation>) -> Result<Self> {
         Ok(Self {
@@ -83,6 +132,27 @@ impl Animator {
     pub fn get_id(&self) -> &MeshId {
         &self.id
     }
+
+    pub fn descriptor(&self) -> Option<TrajectoryDescriptor> {
+        self.animation.descriptor()
+    }
+
+    /// Animator with the same speed and play state for a duplicated mesh, starting from zero.
+    pub fn duplicate(
+        &self,
+        id: MeshId,
+        transform: Shared<Transform>,
+        offset: Vec3,
+    ) -> Option<Animator> {
+        let animation = self.animation.duplicate(id, transform, offset)?;
+        Some(Self {
+            id: animation.get_id().to_owned(),
+            elapsed_time: 0.0,
+            speed_multiplier: self.speed_multiplier,
+            is_currently_playing: self.is_currently_playing,
+            animation,
+        })
+    }
 }
 
 #[cfg(test)]
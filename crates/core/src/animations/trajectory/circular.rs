@@ -1,11 +1,11 @@
-use std::f32::consts::PI;
-
 use anyhow::{Result, anyhow};
+use glam::Vec3;
 
 use crate::{
     Shared, SharedAccess,
-    animations::Animation,
+    animations::{Animation, TrajectoryDescriptor},
     types::{DeltaTime, ids::MeshId, transform::Transform},
+    util::float::normalize_angle_0_tau,
 };
 
 #[derive(Default, Clone)]
@@ -51,7 +51,7 @@ impl Animation for CircularTrajectory {
                 transform.position.z = self.radius * f32::sin(self.angle);
             }
         })?;
-        self.angle = (self.angle + self.speed * delta) % (2.0 * PI);
+        self.angle = normalize_angle_0_tau(self.angle + self.speed * delta);
         Ok(())
     }
 
@@ -59,9 +59,31 @@ impl Animation for CircularTrajectory {
         self.angle = 0f32;
     }
 
+    /// The circle is centred on the world origin or the animate target, so `offset` is ignored.
+    fn duplicate(
+        &self,
+        id: MeshId,
+        transform: Shared<Transform>,
+        _offset: Vec3,
+    ) -> Option<Box<dyn Animation>> {
+        Some(Box::new(Self {
+            id,
+            transform,
+            angle: 0.0,
+            ..self.clone()
+        }))
+    }
+
     fn get_id(&self) -> &MeshId {
         &self.id
     }
+
+    fn descriptor(&self) -> Option<TrajectoryDescriptor> {
+        Some(TrajectoryDescriptor::Circular {
+            radius: self.radius,
+            speed: self.speed,
+        })
+    }
 }
 
 #[cfg(test)]
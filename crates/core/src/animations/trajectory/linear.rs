@@ -5,7 +5,7 @@ use log::error;
 use crate::{
     Shared, SharedAccess,
     animations::{
-        Animation,
+        Animation, TrajectoryDescriptor,
         trajectory::{Direction, calculate_direction_vector},
     },
     types::{DeltaTime, ids::MeshId, transform::Transform},
@@ -111,6 +111,22 @@ impl Animation for LinearTrajectory {
         Ok(())
     }
 
+    fn duplicate(
+        &self,
+        id: MeshId,
+        transform: Shared<Transform>,
+        offset: Vec3,
+    ) -> Option<Box<dyn Animation>> {
+        Some(Box::new(Self {
+            id,
+            transform,
+            start_position: self.start_position + offset,
+            progress: Self::ZERO_PROGRESS,
+            direction: Direction::FORWARDS,
+            ..self.clone()
+        }))
+    }
+
     fn reset(&mut self) {
         match self
             .transform
@@ -129,6 +145,18 @@ impl Animation for LinearTrajectory {
     fn get_id(&self) -> &MeshId {
         &self.id
     }
+
+    fn descriptor(&self) -> Option<TrajectoryDescriptor> {
+        Some(TrajectoryDescriptor::Linear {
+            start_position: self.start_position,
+            yaw_radians: self.yaw_radians,
+            pitch_radians: self.pitch_radians,
+            distance: self.distance,
+            speed: self.speed,
+            looping: self.looping,
+            reversing: self.reversing,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -233,4 +261,68 @@ mod tests {
         let pos = transform.read_shared(|t| t.position);
         assert_eq!(pos, start_pos);
     }
+
+    #[test]
+    fn test_linear_trajectory_duplicate_drives_only_the_copy() {
+        let original_transform = shared::<Transform>(Transform::default());
+        let mut original = LinearTrajectory::new_deconstructed_mesh(
+            MeshId("Original".to_string()),
+            original_transform.clone(),
+            Vec3::ZERO,
+            0.0,
+            0.0,
+            10.0,
+            5.0,
+            false,
+            true,
+        )
+        .unwrap();
+        original.animate(None, 1.0).unwrap();
+
+        let copy_transform = shared::<Transform>(Transform::default());
+        let mut copy = original
+            .duplicate(
+                MeshId("Copy".to_string()),
+                copy_transform.clone(),
+                Vec3::new(0.0, 0.0, 2.0),
+            )
+            .unwrap();
+        copy.animate(None, 1.0).unwrap();
+
+        assert_eq!(copy.get_id(), &MeshId("Copy".to_string()));
+        let copy_position = copy_transform.read_shared(|t| t.position);
+        assert!((copy_position - Vec3::new(5.0, 0.0, 2.0)).length() < 0.001);
+        let original_position = original_transform.read_shared(|t| t.position);
+        assert!((original_position - Vec3::new(5.0, 0.0, 0.0)).length() < 0.001);
+    }
+
+    #[test]
+    fn test_linear_trajectory_descriptor_ignores_progress() {
+        let mut trajectory = LinearTrajectory::new_deconstructed_mesh(
+            MeshId("Test".to_string()),
+            shared(Transform::default()),
+            Vec3::new(0.0, 1.0, 0.0),
+            0.5,
+            0.25,
+            3.0,
+            2.0,
+            true,
+            false,
+        )
+        .unwrap();
+        trajectory.animate(None, 1.0).unwrap();
+
+        assert_eq!(
+            trajectory.descriptor(),
+            Some(TrajectoryDescriptor::Linear {
+                start_position: Vec3::new(0.0, 1.0, 0.0),
+                yaw_radians: 0.5,
+                pitch_radians: 0.25,
+                distance: 3.0,
+                speed: 2.0,
+                looping: true,
+                reversing: false,
+            })
+        );
+    }
 }
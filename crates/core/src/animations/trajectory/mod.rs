@@ -1,10 +1,15 @@
 use glam::Vec3;
 
+use crate::util::float::wrap_angle;
+
 pub mod circular;
 pub mod linear;
 pub mod stationary;
 
+/// Unit vector for `yaw_radians` around +Y, measured from +X towards +Z, and `pitch_radians` up.
 pub fn calculate_direction_vector(yaw_radians: f32, pitch_radians: f32) -> Vec3 {
+    // Yaw set directly is not wrapped, and trig on large angles loses precision.
+    let yaw_radians = wrap_angle(yaw_radians);
     let x = pitch_radians.cos() * yaw_radians.cos();
     let y = pitch_radians.sin();
     let z = pitch_radians.cos() * yaw_radians.sin();
@@ -19,41 +24,43 @@ pub enum Direction {
 
 #[cfg(test)]
 mod tests {
+    use std::f32::consts::TAU;
+
     use super::*;
+    use crate::util::test_support::assert_vec3_eq;
 
     #[test]
     fn test_direction_vector_zero_angles() {
         let dir = calculate_direction_vector(0.0, 0.0);
-        assert!((dir.x - 1.0).abs() < 0.001);
-        assert!(dir.y.abs() < 0.001);
-        assert!(dir.z.abs() < 0.001);
+        assert_vec3_eq(dir, Vec3::X, "zero angles");
     }
 
     #[test]
     fn test_direction_vector_90_yaw() {
         let dir = calculate_direction_vector(90.0_f32.to_radians(), 0.0);
-        assert!(dir.x.abs() < 0.001);
-        assert!((dir.z - 1.0).abs() < 0.001);
-        assert!(dir.y.abs() < 0.001);
+        assert_vec3_eq(dir, Vec3::Z, "90 degree yaw");
     }
 
     #[test]
     fn test_direction_vector_90_pitch() {
         let dir = calculate_direction_vector(0.0, 90.0_f32.to_radians());
-        assert!(dir.x.abs() < 0.001);
-        assert!(dir.z.abs() < 0.001);
-        assert!((dir.y - 1.0).abs() < 0.001);
+        assert_vec3_eq(dir, Vec3::Y, "90 degree pitch");
     }
 
     #[test]
     fn test_direction_vector_90_pitch_90_yaw() {
         let dir = calculate_direction_vector(90.0_f32.to_radians(), 90.0_f32.to_radians());
-        assert!(dir.x.abs() < 0.001, "x: {:?} < 0.001", dir.x.abs());
-        assert!(dir.z.abs() < 0.001, "z: {:?} < 0.001", dir.z.abs());
-        assert!(
-            (dir.y - 1.0).abs() < 0.001,
-            "y: {:?} - 1.0 < 0.001",
-            dir.y.abs()
+        assert_vec3_eq(dir, Vec3::Y, "90 degree pitch and yaw");
+    }
+
+    #[test]
+    fn test_direction_vector_ignores_full_yaw_turns() {
+        let yaw = 30.0_f32.to_radians();
+        let dir = calculate_direction_vector(yaw + 3.0 * TAU, 0.0);
+        assert_vec3_eq(
+            dir,
+            calculate_direction_vector(yaw, 0.0),
+            "yaw three turns further",
         );
     }
 }
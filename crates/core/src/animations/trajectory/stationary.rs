@@ -1,6 +1,8 @@
 use anyhow::Ok;
+use glam::Vec3;
 
 use crate::{
+    Shared,
     animations::Animation,
     types::{DeltaTime, ids::MeshId, transform::Transform},
 };
@@ -17,6 +19,15 @@ impl Animation for StationaryTrajectory {
     // Empty as you don't need this to do anything as it's stationary.
     fn reset(&mut self) {}
 
+    fn duplicate(
+        &self,
+        id: MeshId,
+        _transform: Shared<Transform>,
+        _offset: Vec3,
+    ) -> Option<Box<dyn Animation>> {
+        Some(Box::new(Self { id }))
+    }
+
     fn get_id(&self) -> &MeshId {
         &self.id
     }
@@ -1,11 +1,16 @@
+use anyhow::{Result, anyhow};
 use glam::{Mat4, Vec3};
+use winit::dpi::PhysicalPosition;
 
 use crate::{
     animations::trajectory::calculate_direction_vector,
+    components::camera::data_structures::CameraProjection,
+    geometry::ray::{Ray, ray_from_screen},
     types::{
         Size,
         base::Id,
         camera::{Pitch, Yaw},
+        generation::Generation,
     },
 };
 
@@ -19,16 +24,22 @@ pub struct Camera {
     pub fovy: f32,
     pub znear: f32,
     pub zfar: f32,
+    pub projection: CameraProjection,
     pub yaw: Yaw,
     pub pitch: Pitch,
     pub speed: f32,
     pub sensitivity: f32,
     pub smoothing_factor: f32,
     pub precalculated_smoothing: f32,
+    generation: Generation,
 }
 
 impl Camera {
     const DEFAULT_ASPECT_RATIO: f32 = 1.0;
+    pub const MIN_FOV_DEGREES: f32 = 1.0;
+    pub const MAX_FOV_DEGREES: f32 = 179.0;
+    /// How close to the up vector a view direction may get before the view matrix degenerates.
+    const PARALLEL_EPSILON: f32 = 1e-6;
 
     pub fn aspect_ratio_from_size(size: Size) -> f32 {
         if size.height == 0 {
@@ -61,12 +72,14 @@ impl Camera {
             fovy,
             znear,
             zfar,
+            projection: CameraProjection::Perspective,
             yaw,
             pitch,
             speed,
             sensitivity,
             smoothing_factor,
             precalculated_smoothing: 1.0 - smoothing_factor,
+            generation: Generation::next(),
         }
     }
 
@@ -76,18 +89,73 @@ impl Camera {
         } else {
             Self::DEFAULT_ASPECT_RATIO
         };
+        self.mark_changed();
     }
 
     pub fn set_aspect_from_size(&mut self, size: Size) {
         self.set_aspect(Self::aspect_ratio_from_size(size));
     }
 
+    /// Points the camera from `eye` at `target` and derives yaw and pitch from that direction, so
+    /// mouse look continues from the new orientation. Invalid input leaves the camera untouched.
+    pub fn look_at(&mut self, eye: Vec3, target: Vec3) -> Result<()> {
+        let direction = Self::view_direction_between(eye, target, self.up)?;
+        self.eye = eye;
+        self.target = target;
+        self.yaw = Yaw::new(direction.z.atan2(direction.x));
+        self.pitch = Pitch::new(direction.y.asin());
+        self.mark_changed();
+        Ok(())
+    }
+
+    /// Normalized direction from `eye` to `target`, if the pair can build a view matrix with `up`.
+    pub fn view_direction_between(eye: Vec3, target: Vec3, up: Vec3) -> Result<Vec3> {
+        if !eye.is_finite() || !target.is_finite() {
+            return Err(anyhow!(
+                "Camera eye {eye} and target {target} must be finite"
+            ));
+        }
+        let direction = (target - eye).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return Err(anyhow!("Camera eye and target must not be the same point"));
+        }
+        if direction.cross(up).length_squared() < Self::PARALLEL_EPSILON {
+            return Err(anyhow!(
+                "Camera view direction {direction} must not be parallel to the up vector {up}"
+            ));
+        }
+        Ok(direction)
+    }
+
+    /// Vertical field of view in radians for `degrees`, which has to lie within
+    /// [`Self::MIN_FOV_DEGREES`] and [`Self::MAX_FOV_DEGREES`].
+    pub fn fovy_from_degrees(degrees: f32) -> Result<f32> {
+        if degrees.is_finite() && (Self::MIN_FOV_DEGREES..=Self::MAX_FOV_DEGREES).contains(&degrees)
+        {
+            Ok(degrees.to_radians())
+        } else {
+            Err(anyhow!(
+                "Field of view {degrees} must be between {} and {} degrees",
+                Self::MIN_FOV_DEGREES,
+                Self::MAX_FOV_DEGREES
+            ))
+        }
+    }
+
+    pub fn set_fov_degrees(&mut self, degrees: f32) -> Result<()> {
+        self.fovy = Self::fovy_from_degrees(degrees)?;
+        self.mark_changed();
+        Ok(())
+    }
+
     pub fn update_yaw(&mut self, yaw_delta: f32) {
         self.yaw.update(yaw_delta);
+        self.mark_changed();
     }
 
     pub fn update_pitch(&mut self, pitch_delta: f32) {
         self.pitch.update(pitch_delta);
+        self.mark_changed();
     }
 
     pub fn move_camera(&mut self, yaw_delta: f32, pitch_delta: f32) {
@@ -103,12 +171,46 @@ impl Camera {
         );
         let forward = calculate_direction_vector(*self.yaw, *self.pitch);
         self.target = self.eye + forward;
+        self.mark_changed();
+    }
+
+    /// Changes with every mutation that goes through a method. Code that writes the public fields
+    /// directly has to call [`Self::mark_changed`] so the camera uniform is uploaded again.
+    pub fn generation(&self) -> Generation {
+        self.generation
+    }
+
+    pub fn mark_changed(&mut self) {
+        self.generation.bump();
+    }
+
+    pub fn build_projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            CameraProjection::Perspective => {
+                Mat4::perspective_rh(self.fovy, self.aspect, self.znear, self.zfar)
+            }
+            CameraProjection::Orthographic { height } => {
+                let half_height = height * 0.5;
+                let half_width = half_height * self.aspect;
+                Mat4::orthographic_rh(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.znear,
+                    self.zfar,
+                )
+            }
+        }
     }
 
     pub fn build_view_proj_matrix(&self) -> Mat4 {
         let view = Mat4::look_at_rh(self.eye, self.target, self.up);
-        let proj = Mat4::perspective_rh(self.fovy, self.aspect, self.znear, self.zfar);
-        proj * view
+        self.build_projection_matrix() * view
+    }
+
+    pub fn screen_to_ray(&self, pos: PhysicalPosition<f64>, viewport: Size) -> Result<Ray> {
+        ray_from_screen(self, pos.x as f32, pos.y as f32, viewport)
     }
 }
 
@@ -117,11 +219,13 @@ mod tests {
     use glam::Vec3;
 
     use crate::{
-        components::camera::camera::Camera,
+        animations::trajectory::calculate_direction_vector,
+        components::camera::{camera::Camera, data_structures::CameraProjection},
         types::{
             Size,
             camera::{Pitch, Yaw},
         },
+        util::float::{approx_eq, wrap_angle},
     };
 
     fn create_test_camera() -> Camera {
@@ -220,23 +324,22 @@ mod tests {
         camera_low.move_camera(yaw_delta, pitch_delta);
         camera_high.move_camera(yaw_delta, pitch_delta);
 
-        // Controller with 2x sensitivity should rotate more (accounting for smoothing)
+        // With smoothing the turns are 2.5 and 5.0 radians; the larger one wraps past PI.
         let yaw_change_low = *camera_low.yaw;
         let yaw_change_high = *camera_high.yaw;
-
+        assert!(approx_eq(yaw_change_low, wrap_angle(2.5), 1e-6));
         assert!(
-            yaw_change_high > yaw_change_low,
-            "Higher sensitivity should produce larger rotation. Low: {}, High: {}",
-            yaw_change_low,
+            approx_eq(yaw_change_high, wrap_angle(5.0), 1e-6),
+            "Yaw should wrap into (-PI, PI], got: {}",
             yaw_change_high
         );
 
-        // The ratio should be close to 2.0 (accounting for smoothing factor)
-        let ratio = yaw_change_high / yaw_change_low;
+        // Modulo a full turn the higher sensitivity still rotates twice as far
+        let ratio_error = wrap_angle(yaw_change_high - 2.0 * yaw_change_low);
         assert!(
-            (ratio - 2.0).abs() < 0.1,
-            "Sensitivity scaling should be approximately 2x, got ratio: {}",
-            ratio
+            approx_eq(ratio_error, 0.0, 1e-5),
+            "Sensitivity scaling should be 2x modulo a full turn, off by: {}",
+            ratio_error
         );
     }
 
@@ -328,4 +431,88 @@ mod tests {
             1.0
         );
     }
+
+    #[test]
+    fn test_orthographic_projection_differs_from_perspective() {
+        let mut camera = create_test_camera();
+        let perspective = camera.build_projection_matrix().to_cols_array();
+
+        camera.projection = CameraProjection::Orthographic { height: 10.0 };
+
+        assert_ne!(
+            perspective,
+            camera.build_projection_matrix().to_cols_array(),
+            "Orthographic projection should not match the perspective projection"
+        );
+    }
+
+    #[test]
+    fn test_look_at_keeps_yaw_and_pitch_in_sync() {
+        let mut camera = create_test_camera();
+        let eye = Vec3::new(3.0, 4.0, -2.0);
+        let target = Vec3::new(-1.0, 0.5, 6.0);
+
+        camera.look_at(eye, target).unwrap();
+
+        assert_eq!(camera.eye, eye);
+        assert_eq!(camera.target, target);
+        let direction = calculate_direction_vector(*camera.yaw, *camera.pitch);
+        assert!(
+            direction.abs_diff_eq((target - eye).normalize(), 1e-5),
+            "Yaw and pitch should describe the new view direction, got {direction}"
+        );
+    }
+
+    #[test]
+    fn test_look_at_rejects_invalid_input_without_changing_the_camera() {
+        let mut camera = create_test_camera();
+        let (eye, target) = (camera.eye, camera.target);
+
+        assert!(
+            camera
+                .look_at(Vec3::new(f32::NAN, 0.0, 0.0), target)
+                .is_err()
+        );
+        assert!(camera.look_at(Vec3::ONE, Vec3::ONE).is_err());
+        assert!(
+            camera
+                .look_at(Vec3::ZERO, Vec3::new(0.0, 5.0, 0.0))
+                .is_err()
+        );
+
+        assert_eq!(camera.eye, eye);
+        assert_eq!(camera.target, target);
+    }
+
+    #[test]
+    fn test_set_fov_degrees_validates_range() {
+        let mut camera = create_test_camera();
+
+        camera.set_fov_degrees(60.0).unwrap();
+        assert!((camera.fovy - 60.0_f32.to_radians()).abs() < 1e-6);
+
+        assert!(camera.set_fov_degrees(0.0).is_err());
+        assert!(camera.set_fov_degrees(180.0).is_err());
+        assert!(camera.set_fov_degrees(f32::NAN).is_err());
+        assert!((camera.fovy - 60.0_f32.to_radians()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_generation_changes_only_when_the_camera_is_mutated() {
+        let mut camera = create_test_camera();
+        let idle = camera.generation();
+
+        camera.build_view_proj_matrix();
+        assert_eq!(camera.generation(), idle);
+        assert!(camera.look_at(Vec3::ZERO, Vec3::ZERO).is_err());
+        assert_eq!(camera.generation(), idle);
+
+        camera.move_camera(10.0, 0.0);
+        let moved = camera.generation();
+        assert_ne!(moved, idle);
+
+        camera.eye = Vec3::new(1.0, 2.0, 3.0);
+        camera.mark_changed();
+        assert_ne!(camera.generation(), moved);
+    }
 }
@@ -12,6 +12,15 @@ pub enum CameraMode {
     ORBIT,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum CameraProjection {
+    #[default]
+    Perspective,
+    Orthographic {
+        height: f32,
+    },
+}
+
 #[derive(Debug)]
 pub struct CameraAxes {
     pub forward: Vec3,
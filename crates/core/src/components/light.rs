@@ -1,3 +1,5 @@
+use std::mem::{offset_of, size_of};
+
 use bytemuck::{Pod, Zeroable};
 use glam::Vec3;
 use wgpu::{
@@ -5,12 +7,17 @@ use wgpu::{
     BindGroupLayoutEntry, Buffer, BufferBinding, Device, ShaderStages,
 };
 
-use crate::{Shared, SharedAccess, traits::BindGroupProvider, types::transform::Transform};
+use crate::{
+    Shared, SharedAccess,
+    traits::{BindGroupProvider, WgslLayout},
+    types::{generation::Generation, transform::Transform},
+};
 
 #[derive(Debug, Clone)]
 pub struct LightSource {
     pub transform: Shared<Transform>,
     color: Vec3,
+    generation: Generation,
 }
 
 #[repr(C)]
@@ -21,25 +28,62 @@ pub struct GpuLightSource {
     _padding_2: f32,
 }
 
+const _: () = {
+    assert!(size_of::<GpuLightSource>() == 64);
+    assert!(offset_of!(GpuLightSource, transform) == 0);
+    assert!(offset_of!(GpuLightSource, color) == size_of::<Transform>());
+};
+
+impl WgslLayout for GpuLightSource {
+    const WGSL_NAMES: &'static [&'static str] = &["Light"];
+    const MEMBERS: &'static [(&'static str, usize)] = &[
+        ("transform", offset_of!(GpuLightSource, transform)),
+        ("color", offset_of!(GpuLightSource, color)),
+    ];
+}
+
 impl LightSource {
     pub fn new(transform: Shared<Transform>, color: Vec3) -> LightSource {
-        Self { transform, color }
+        Self {
+            transform,
+            color,
+            generation: Generation::next(),
+        }
+    }
+
+    pub fn color(&self) -> Vec3 {
+        self.color
     }
 
     pub fn update_color(&mut self, color: Vec3) {
         self.color = color;
+        self.mark_changed();
+    }
+
+    /// The transform is shared with the light's mesh, so whoever moves it has to call
+    /// [`Self::mark_changed`].
+    pub fn generation(&self) -> Generation {
+        self.generation
+    }
+
+    pub fn mark_changed(&mut self) {
+        self.generation.bump();
     }
 
     pub fn to_gpu(&self) -> Option<GpuLightSource> {
         self.transform
-            .try_read_shared(|t| t.clone())
-            .map(|t| GpuLightSource {
-                transform: t,
-                color: self.color,
-                _padding_2: 0.0,
-            })
+            .try_read_shared(|transform| self.to_gpu_with(transform))
             .ok()
     }
+
+    /// The uniform for this light placed at `transform`, for callers that already hold the lock.
+    pub fn to_gpu_with(&self, transform: &Transform) -> GpuLightSource {
+        GpuLightSource {
+            transform: *transform,
+            color: self.color,
+            _padding_2: 0.0,
+        }
+    }
 }
 
 impl BindGroupProvider for LightSource {
@@ -1,12 +1,33 @@
+use glam::Vec3;
+
 use crate::{
     components::{LightType, camera::data_structures::CameraAnimationRequest},
     types::shared::{AssetBundleInformation, AssetInformation},
 };
 
+/// Answers an asset request with the created mesh ids, or with the import error message.
+pub type AssetReply = Box<dyn FnOnce(Result<Vec<String>, String>)>;
+
 pub enum Event {
     AnimateCamera(CameraAnimationRequest),
     StopCameraAnimation,
     AssetUpload(AssetInformation, LightType),
     AssetBundleUpload(AssetBundleInformation, LightType),
     Resize(f64, f64),
+    SetCamera {
+        eye: Vec3,
+        target: Vec3,
+    },
+    SetCameraFov {
+        degrees: f32,
+    },
+    FocusAsset(String),
+    SelectAsset(String),
+    ClearSelection,
+    AddAsset {
+        name: String,
+        bytes: Vec<u8>,
+        light_type: LightType,
+        reply: AssetReply,
+    },
 }
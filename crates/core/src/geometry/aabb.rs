@@ -0,0 +1,135 @@
+use glam::{Mat4, Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.min(point), max.max(point))
+        });
+        Some(Self { min, max })
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn extents(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn corners(&self) -> [Vec3; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    pub fn transformed(&self, matrix: &Mat4) -> Self {
+        Self::from_points(
+            self.corners()
+                .into_iter()
+                .map(|corner| matrix.transform_point3(corner)),
+        )
+        .unwrap_or(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_points_encloses_all_points() {
+        let aabb = Aabb::from_points([
+            Vec3::new(1.0, -2.0, 3.0),
+            Vec3::new(-1.0, 4.0, 0.0),
+            Vec3::new(0.5, 0.0, -5.0),
+        ])
+        .unwrap();
+
+        assert_eq!(aabb.min, Vec3::new(-1.0, -2.0, -5.0));
+        assert_eq!(aabb.max, Vec3::new(1.0, 4.0, 3.0));
+    }
+
+    #[test]
+    fn test_from_points_rejects_empty_input() {
+        assert!(Aabb::from_points(std::iter::empty()).is_none());
+    }
+
+    #[test]
+    fn test_new_orders_min_and_max() {
+        let aabb = Aabb::new(Vec3::ONE, -Vec3::ONE);
+
+        assert_eq!(aabb.min, -Vec3::ONE);
+        assert_eq!(aabb.max, Vec3::ONE);
+    }
+
+    #[test]
+    fn test_transformed_applies_translation_and_scale() {
+        let aabb = Aabb::new(-Vec3::ONE, Vec3::ONE);
+        let matrix =
+            Mat4::from_translation(Vec3::new(10.0, 0.0, 0.0)) * Mat4::from_scale(Vec3::splat(2.0));
+
+        let transformed = aabb.transformed(&matrix);
+
+        assert_eq!(transformed.min, Vec3::new(8.0, -2.0, -2.0));
+        assert_eq!(transformed.max, Vec3::new(12.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn test_transformed_refits_rotated_corners() {
+        let aabb = Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5));
+        let matrix = Mat4::from_rotation_y(std::f32::consts::FRAC_PI_4);
+
+        let transformed = aabb.transformed(&matrix);
+
+        let half_diagonal = 0.5 * std::f32::consts::SQRT_2;
+        assert!(
+            (transformed.max - Vec3::new(half_diagonal, 0.5, half_diagonal))
+                .abs()
+                .max_element()
+                < 1e-5,
+            "{transformed:?}"
+        );
+        assert!(
+            (transformed.min + transformed.max).abs().max_element() < 1e-5,
+            "{transformed:?}"
+        );
+    }
+
+    #[test]
+    fn test_transformed_applies_non_uniform_scale() {
+        let aabb = Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5));
+        let matrix = Mat4::from_scale(Vec3::new(1.0, 2.0, -4.0));
+
+        let transformed = aabb.transformed(&matrix);
+
+        assert_eq!(transformed.min, Vec3::new(-0.5, -1.0, -2.0));
+        assert_eq!(transformed.max, Vec3::new(0.5, 1.0, 2.0));
+    }
+}
@@ -1,3 +1,5 @@
+use glam::Vec4;
+
 use crate::geometry::vertices::Vertex;
 
 #[repr(C)]
@@ -23,4 +25,11 @@ impl Mesh {
             indices,
         }
     }
+
+    /// Importers fill missing vertex colors with white, so all-white colors count as none.
+    pub fn has_vertex_colors(&self) -> bool {
+        self.vertices
+            .iter()
+            .any(|vertex| vertex.colors != Vec4::ONE)
+    }
 }
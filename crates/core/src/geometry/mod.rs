@@ -1,3 +1,4 @@
+pub mod aabb;
 pub mod mesh;
 pub mod node;
 pub mod ray;
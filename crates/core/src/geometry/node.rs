@@ -63,6 +63,31 @@ impl NodeGraph {
             self.flatten_nodes(*child_node_id, &world, out);
         }
     }
+
+    /// The nodes from a root down to the node holding each mesh, in the order of [`Self::flatten`].
+    pub fn mesh_paths(&self) -> Vec<Vec<&Node>> {
+        let mut result = Vec::new();
+        let mut path = Vec::new();
+        for root_id in &self.root_ids {
+            self.collect_mesh_paths(*root_id, &mut path, &mut result);
+        }
+        result
+    }
+
+    fn collect_mesh_paths<'a>(
+        &'a self,
+        node_id: NodeId,
+        path: &mut Vec<&'a Node>,
+        out: &mut Vec<Vec<&'a Node>>,
+    ) {
+        let node = &self.nodes[*node_id];
+        path.push(node);
+        out.extend(node.meshes.iter().map(|_| path.clone()));
+        for child_node_id in &node.children_ids {
+            self.collect_mesh_paths(*child_node_id, path, out);
+        }
+        path.pop();
+    }
 }
 
 pub struct Node {
@@ -1,9 +1,11 @@
 use glam::{Quat, Vec3};
 
 use super::{Node, NodeGraph, NodeId, NodeMetadata};
-use crate::{geometry::mesh::Mesh, types::transform::Transform};
-
-const EPSILON: f32 = 1e-6;
+use crate::{
+    geometry::mesh::Mesh,
+    types::transform::Transform,
+    util::test_support::{assert_quat_eq, assert_vec3_eq},
+};
 
 fn test_mesh(name: &str) -> Mesh {
     Mesh::new(Some(name.to_string()), None, vec![], vec![])
@@ -13,28 +15,6 @@ fn test_transform(x: f32, y: f32, z: f32) -> Transform {
     Transform::new(Vec3::new(x, y, z), Quat::IDENTITY, Vec3::ONE)
 }
 
-fn assert_vec3_eq(actual: Vec3, expected: Vec3, message: &str) {
-    assert!(
-        (actual - expected).length() < EPSILON,
-        "{}: expected {:?}, got {:?}",
-        message,
-        expected,
-        actual
-    );
-}
-
-fn assert_quat_eq(actual: Quat, expected: Quat, message: &str) {
-    let dot = actual.dot(expected).abs();
-    assert!(
-        (dot - 1.0).abs() < EPSILON,
-        "{}: expected {:?}, got {:?} (dot: {})",
-        message,
-        expected,
-        actual,
-        dot
-    );
-}
-
 #[test]
 fn flatten_empty_graph_returns_empty_vec() {
     let graph = NodeGraph::new(vec![], vec![]);
@@ -235,3 +215,53 @@ fn flatten_accumulates_parent_child_and_grandchild_transforms() {
         "grandchild world position should include parent and child translation",
     );
 }
+
+#[test]
+fn mesh_paths_follow_flatten_order_and_skip_mesh_less_nodes() {
+    let graph = NodeGraph {
+        root_ids: vec![NodeId(0)],
+        nodes: vec![
+            Node {
+                metadata: NodeMetadata::new(Some("pivot".to_string()), Some(0)),
+                local_transform: test_transform(10.0, 0.0, 0.0),
+                meshes: vec![],
+                children_ids: vec![NodeId(1), NodeId(2)],
+                parent_id: None,
+            },
+            Node {
+                metadata: NodeMetadata::new(Some("left".to_string()), Some(1)),
+                local_transform: test_transform(-1.0, 0.0, 0.0),
+                meshes: vec![test_mesh("left_a"), test_mesh("left_b")],
+                children_ids: vec![],
+                parent_id: Some(NodeId(0)),
+            },
+            Node {
+                metadata: NodeMetadata::new(Some("right".to_string()), Some(2)),
+                local_transform: test_transform(1.0, 0.0, 0.0),
+                meshes: vec![test_mesh("right")],
+                children_ids: vec![],
+                parent_id: Some(NodeId(0)),
+            },
+        ],
+    };
+
+    let names = graph
+        .mesh_paths()
+        .iter()
+        .map(|path| {
+            path.iter()
+                .map(|node| node.metadata.name.as_deref().unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(graph.flatten().len(), names.len());
+    assert_eq!(
+        names,
+        [
+            vec!["pivot", "left"],
+            vec!["pivot", "left"],
+            vec!["pivot", "right"]
+        ]
+    );
+}
@@ -1,17 +1,191 @@
 use anyhow::{Result, anyhow};
 use glam::{Vec2, Vec3, Vec4, Vec4Swizzles};
 
-use crate::{components::camera::camera::Camera, types::Size};
+use crate::{
+    components::camera::{camera::Camera, data_structures::CameraProjection},
+    geometry::aabb::Aabb,
+    types::{Size, transform::Transform},
+};
 
-pub struct Ray(Vec3, Vec3);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleHit {
+    pub distance: f32,
+    pub barycentric: Vec3,
+    pub triangle_index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    origin: Vec3,
+    dir: Vec3,
+}
 
 impl Ray {
+    const PARALLEL_EPSILON: f32 = 1e-8;
+    const PLANE_PARALLEL_EPSILON: f32 = 1e-6;
+
+    pub fn new(origin: Vec3, dir: Vec3) -> Self {
+        Self {
+            origin,
+            dir: dir.normalize(),
+        }
+    }
+
     pub fn origin(&self) -> Vec3 {
-        self.0
+        self.origin
     }
 
     pub fn direction(&self) -> Vec3 {
-        self.1
+        self.dir
+    }
+
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.dir * t
+    }
+
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min = 0.0_f32;
+        let mut t_max = f32::INFINITY;
+
+        for axis in 0..3 {
+            let origin = self.origin[axis];
+            let dir = self.dir[axis];
+            let (min, max) = (aabb.min[axis], aabb.max[axis]);
+
+            if dir.abs() < Self::PARALLEL_EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let inverse_dir = 1.0 / dir;
+            let near = (min - origin) * inverse_dir;
+            let far = (max - origin) * inverse_dir;
+            t_min = t_min.max(near.min(far));
+            t_max = t_max.min(near.max(far));
+
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        Some(t_min)
+    }
+
+    /// Distance to the plane through `point` with the given `normal`. Rays running parallel to the
+    /// plane or only meeting it behind their origin miss.
+    pub fn intersect_plane(&self, point: Vec3, normal: Vec3) -> Option<f32> {
+        let denominator = self.dir.dot(normal);
+        if denominator.abs() < Self::PLANE_PARALLEL_EPSILON {
+            return None;
+        }
+
+        let t = (point - self.origin).dot(normal) / denominator;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Distance to the first point on the sphere around `center`, or 0 when the ray starts inside.
+    pub fn intersect_sphere(&self, center: Vec3, radius: f32) -> Option<f32> {
+        let to_center = center - self.origin;
+        let along = to_center.dot(self.dir);
+        let miss_squared = to_center.length_squared() - along * along;
+        let radius_squared = radius * radius;
+        if miss_squared > radius_squared {
+            return None;
+        }
+
+        let half_chord = (radius_squared - miss_squared).sqrt();
+        let far = along + half_chord;
+        (far >= 0.0).then_some((along - half_chord).max(0.0))
+    }
+
+    /// Tests against an AABB given in the local space of `transform`. The ray is moved into local
+    /// space without renormalizing, so the returned distance stays in world units.
+    pub fn intersect_transformed_aabb(&self, aabb: &Aabb, transform: &Transform) -> Option<f32> {
+        self.to_local_space(transform)?.intersect_aabb(aabb)
+    }
+
+    pub fn to_local_space(&self, transform: &Transform) -> Option<Ray> {
+        let inverse = transform.get_matrix().inverse();
+        let local = Ray {
+            origin: inverse.transform_point3(self.origin),
+            dir: inverse.transform_vector3(self.dir),
+        };
+
+        (local.origin.is_finite() && local.dir.is_finite() && local.dir != Vec3::ZERO)
+            .then_some(local)
+    }
+
+    /// Möller–Trumbore. Returns the hit distance and the `(u, v)` weights of `b` and `c`.
+    /// Counter-clockwise triangles facing the ray are front faces.
+    pub fn intersect_triangle(
+        &self,
+        a: Vec3,
+        b: Vec3,
+        c: Vec3,
+        cull_back_faces: bool,
+    ) -> Option<(f32, Vec2)> {
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let p = self.dir.cross(edge2);
+        let determinant = edge1.dot(p);
+
+        if cull_back_faces && determinant < Self::PARALLEL_EPSILON {
+            return None;
+        }
+        if determinant.abs() < Self::PARALLEL_EPSILON {
+            return None;
+        }
+
+        let inverse_determinant = 1.0 / determinant;
+        let s = self.origin - a;
+        let u = s.dot(p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(edge1);
+        let v = self.dir.dot(q) * inverse_determinant;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(q) * inverse_determinant;
+        (t >= 0.0).then_some((t, Vec2::new(u, v)))
+    }
+
+    pub fn intersect_triangles(
+        &self,
+        positions: &[Vec3],
+        indices: &[u32],
+        cull_back_faces: bool,
+    ) -> Option<TriangleHit> {
+        let mut nearest: Option<TriangleHit> = None;
+
+        for (triangle_index, triangle) in indices.chunks_exact(3).enumerate() {
+            let (Some(&a), Some(&b), Some(&c)) = (
+                positions.get(triangle[0] as usize),
+                positions.get(triangle[1] as usize),
+                positions.get(triangle[2] as usize),
+            ) else {
+                continue;
+            };
+
+            let Some((distance, uv)) = self.intersect_triangle(a, b, c, cull_back_faces) else {
+                continue;
+            };
+
+            if nearest.is_none_or(|hit| distance < hit.distance) {
+                nearest = Some(TriangleHit {
+                    distance,
+                    barycentric: Vec3::new(1.0 - uv.x - uv.y, uv.x, uv.y),
+                    triangle_index,
+                });
+            }
+        }
+
+        nearest
     }
 }
 
@@ -25,6 +199,26 @@ pub fn screen_to_ndc(x: f32, y: f32, size: Size) -> Option<Vec2> {
     ))
 }
 
+pub fn ndc_to_screen(ndc: Vec2, size: Size) -> Vec2 {
+    Vec2::new(
+        (ndc.x + 1.0) * 0.5 * size.width as f32,
+        (1.0 - ndc.y) * 0.5 * size.height as f32,
+    )
+}
+
+pub fn world_to_screen(camera: &Camera, point: Vec3, size: Size) -> Option<Vec2> {
+    if size.width == 0 || size.height == 0 {
+        return None;
+    }
+
+    let clip = camera.build_view_proj_matrix() * point.extend(1.0);
+    if clip.w <= f32::EPSILON {
+        return None;
+    }
+
+    Some(ndc_to_screen(clip.xy() / clip.w, size))
+}
+
 pub fn ndc_to_world(camera: &Camera, ndc: Vec2, depth: f32) -> Option<Vec3> {
     if !ndc.is_finite() || !depth.is_finite() || !(0.0..=1.0).contains(&depth) {
         return None;
@@ -51,7 +245,14 @@ pub fn ray_from_screen(camera: &Camera, x: f32, y: f32, size: Size) -> Result<Ra
     let world_near = ndc_to_world(camera, ndc, 0.0)
         .ok_or_else(|| anyhow!("Failed to unproject NDC coordinates: {ndc:?}"))?;
 
-    Ok(Ray(camera.eye, (world_near - camera.eye).normalize()))
+    match camera.projection {
+        CameraProjection::Perspective => Ok(Ray::new(camera.eye, world_near - camera.eye)),
+        CameraProjection::Orthographic { .. } => {
+            let world_far = ndc_to_world(camera, ndc, 1.0)
+                .ok_or_else(|| anyhow!("Failed to unproject NDC coordinates: {ndc:?}"))?;
+            Ok(Ray::new(world_near, world_far - world_near))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,16 @@
-use glam::{Vec2, Vec3};
+use glam::{Quat, Vec2, Vec3};
+use winit::dpi::PhysicalPosition;
 
 use crate::{
-    components::camera::camera::Camera,
-    geometry::ray::{ndc_to_world, ray_from_screen, screen_to_ndc},
+    components::camera::{camera::Camera, data_structures::CameraProjection},
+    geometry::{
+        aabb::Aabb,
+        ray::{Ray, ndc_to_world, ray_from_screen, screen_to_ndc, world_to_screen},
+    },
     types::{
         Size,
         camera::{Pitch, Yaw},
+        transform::Transform,
     },
 };
 
@@ -205,3 +210,336 @@ fn ray_direction_matches_across_same_aspect_resolutions() {
         );
     }
 }
+
+#[test]
+fn screen_to_ray_center_pixel_follows_eye_to_target() {
+    let size = test_size();
+    let camera = create_test_camera(size);
+    let ray = camera
+        .screen_to_ray(PhysicalPosition::new(960.0, 540.0), size)
+        .unwrap();
+
+    assert_eq!(ray.origin(), camera.eye);
+    assert_vec3_near(ray.direction(), (camera.target - camera.eye).normalize());
+}
+
+#[test]
+fn screen_to_ray_corner_pixels_match_frustum_corners() {
+    let size = test_size();
+    let camera = create_test_camera(size);
+    let half_height = (camera.fovy * 0.5).tan();
+    let half_width = half_height * camera.aspect;
+    let cases = [
+        (
+            "top left",
+            0.0,
+            0.0,
+            Vec3::new(-half_width, half_height, -1.0),
+        ),
+        (
+            "top right",
+            1920.0,
+            0.0,
+            Vec3::new(half_width, half_height, -1.0),
+        ),
+        (
+            "bottom left",
+            0.0,
+            1080.0,
+            Vec3::new(-half_width, -half_height, -1.0),
+        ),
+        (
+            "bottom right",
+            1920.0,
+            1080.0,
+            Vec3::new(half_width, -half_height, -1.0),
+        ),
+    ];
+
+    for (name, x, y, corner) in cases {
+        let ray = camera
+            .screen_to_ray(PhysicalPosition::new(x, y), size)
+            .unwrap();
+
+        assert!(
+            (ray.direction() - corner.normalize()).length() < EPSILON,
+            "{name} ray mismatch: actual={:?}, expected={:?}",
+            ray.direction(),
+            corner.normalize()
+        );
+    }
+}
+
+#[test]
+fn screen_to_ray_orthographic_rays_are_parallel() {
+    let size = test_size();
+    let mut camera = create_test_camera(size);
+    camera.projection = CameraProjection::Orthographic { height: 10.0 };
+
+    let center = camera
+        .screen_to_ray(PhysicalPosition::new(960.0, 540.0), size)
+        .unwrap();
+    let corner = camera
+        .screen_to_ray(PhysicalPosition::new(0.0, 0.0), size)
+        .unwrap();
+
+    assert_vec3_near(center.origin(), Vec3::new(0.0, 0.0, 9.9));
+    assert_vec3_near(center.direction(), Vec3::new(0.0, 0.0, -1.0));
+    assert_vec3_near(corner.origin(), Vec3::new(-5.0 * camera.aspect, 5.0, 9.9));
+    assert_vec3_near(corner.direction(), Vec3::new(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn ray_at_walks_along_normalized_direction() {
+    let ray = Ray::new(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -4.0));
+
+    assert_vec3_near(ray.direction(), Vec3::new(0.0, 0.0, -1.0));
+    assert_vec3_near(ray.at(2.0), Vec3::new(1.0, 0.0, -2.0));
+}
+
+fn unit_box() -> Aabb {
+    Aabb::new(-Vec3::ONE, Vec3::ONE)
+}
+
+fn assert_hit_distance(hit: Option<f32>, expected: f32, name: &str) {
+    let distance = hit.unwrap_or_else(|| panic!("{name} should hit the box"));
+    assert!(
+        (distance - expected).abs() < EPSILON,
+        "{name} distance mismatch: actual={distance}, expected={expected}"
+    );
+}
+
+#[test]
+fn intersect_aabb_hits_box_in_front() {
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z);
+
+    assert_hit_distance(ray.intersect_aabb(&unit_box()), 4.0, "front");
+}
+
+#[test]
+fn intersect_aabb_misses_box_to_the_side() {
+    let ray = Ray::new(Vec3::new(3.0, 0.0, 5.0), Vec3::NEG_Z);
+
+    assert!(ray.intersect_aabb(&unit_box()).is_none());
+}
+
+#[test]
+fn intersect_aabb_misses_box_behind_origin() {
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::Z);
+
+    assert!(ray.intersect_aabb(&unit_box()).is_none());
+}
+
+#[test]
+fn intersect_aabb_returns_zero_when_origin_is_inside() {
+    let ray = Ray::new(Vec3::new(0.25, -0.5, 0.0), Vec3::new(1.0, 2.0, -3.0));
+
+    assert_hit_distance(ray.intersect_aabb(&unit_box()), 0.0, "inside");
+}
+
+#[test]
+fn intersect_aabb_hits_grazing_edge() {
+    let ray = Ray::new(Vec3::new(1.0, 1.0, 5.0), Vec3::NEG_Z);
+
+    assert_hit_distance(ray.intersect_aabb(&unit_box()), 4.0, "grazing edge");
+}
+
+#[test]
+fn intersect_aabb_handles_axis_parallel_rays_outside_slab() {
+    let ray = Ray::new(Vec3::new(0.0, 2.0, 5.0), Vec3::NEG_Z);
+
+    assert!(ray.intersect_aabb(&unit_box()).is_none());
+}
+
+#[test]
+fn intersect_aabb_handles_negative_direction_components() {
+    let ray = Ray::new(Vec3::new(5.0, 5.0, 5.0), Vec3::new(-1.0, -1.0, -1.0));
+
+    assert_hit_distance(
+        ray.intersect_aabb(&unit_box()),
+        Vec3::splat(4.0).length(),
+        "diagonal",
+    );
+}
+
+#[test]
+fn intersect_transformed_aabb_uses_world_distance() {
+    let transform = Transform::new(Vec3::new(10.0, 0.0, 0.0), Quat::IDENTITY, Vec3::splat(2.0));
+    let ray = Ray::new(Vec3::new(10.0, 0.0, 10.0), Vec3::NEG_Z);
+
+    assert_hit_distance(
+        ray.intersect_transformed_aabb(&unit_box(), &transform),
+        8.0,
+        "scaled and translated",
+    );
+    assert!(
+        Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z)
+            .intersect_transformed_aabb(&unit_box(), &transform)
+            .is_none()
+    );
+}
+
+#[test]
+fn intersect_transformed_aabb_respects_rotation() {
+    let transform = Transform::new(
+        Vec3::ZERO,
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_4),
+        Vec3::ONE,
+    );
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z);
+
+    assert_hit_distance(
+        ray.intersect_transformed_aabb(&unit_box(), &transform),
+        5.0 - std::f32::consts::SQRT_2,
+        "rotated box",
+    );
+}
+
+fn unit_quad() -> (Vec<Vec3>, Vec<u32>) {
+    (
+        vec![
+            Vec3::new(-0.5, -0.5, 0.0),
+            Vec3::new(0.5, -0.5, 0.0),
+            Vec3::new(0.5, 0.5, 0.0),
+            Vec3::new(-0.5, 0.5, 0.0),
+        ],
+        vec![0, 1, 2, 0, 2, 3],
+    )
+}
+
+#[test]
+fn intersect_triangles_hits_unit_quad() {
+    let (positions, indices) = unit_quad();
+    let ray = Ray::new(Vec3::new(0.25, -0.25, 3.0), Vec3::NEG_Z);
+
+    let hit = ray
+        .intersect_triangles(&positions, &indices, false)
+        .unwrap();
+
+    assert!((hit.distance - 3.0).abs() < EPSILON);
+    assert_eq!(hit.triangle_index, 0);
+    assert!((hit.barycentric.element_sum() - 1.0).abs() < EPSILON);
+    assert_vec3_near(
+        positions[0] * hit.barycentric.x
+            + positions[1] * hit.barycentric.y
+            + positions[2] * hit.barycentric.z,
+        Vec3::new(0.25, -0.25, 0.0),
+    );
+}
+
+#[test]
+fn intersect_triangles_misses_outside_unit_quad() {
+    let (positions, indices) = unit_quad();
+    let ray = Ray::new(Vec3::new(0.75, 0.0, 3.0), Vec3::NEG_Z);
+
+    assert!(
+        ray.intersect_triangles(&positions, &indices, false)
+            .is_none()
+    );
+}
+
+#[test]
+fn intersect_triangles_culls_back_faces_only_when_requested() {
+    let (positions, indices) = unit_quad();
+    let ray = Ray::new(Vec3::new(0.0, 0.1, -3.0), Vec3::Z);
+
+    assert!(
+        ray.intersect_triangles(&positions, &indices, false)
+            .is_some()
+    );
+    assert!(
+        ray.intersect_triangles(&positions, &indices, true)
+            .is_none()
+    );
+}
+
+#[test]
+fn intersect_triangles_returns_nearest_of_two_triangles() {
+    let positions = vec![
+        Vec3::new(-1.0, -1.0, -5.0),
+        Vec3::new(1.0, -1.0, -5.0),
+        Vec3::new(0.0, 1.0, -5.0),
+        Vec3::new(-1.0, -1.0, -2.0),
+        Vec3::new(1.0, -1.0, -2.0),
+        Vec3::new(0.0, 1.0, -2.0),
+    ];
+    let indices = vec![0, 1, 2, 3, 4, 5];
+    let ray = Ray::new(Vec3::ZERO, Vec3::NEG_Z);
+
+    let hit = ray
+        .intersect_triangles(&positions, &indices, false)
+        .unwrap();
+
+    assert_eq!(hit.triangle_index, 1);
+    assert!((hit.distance - 2.0).abs() < EPSILON);
+}
+
+#[test]
+fn world_to_screen_round_trips_screen_to_ray() {
+    let size = test_size();
+    let camera = create_test_camera(size);
+    let ray = ray_from_screen(&camera, 400.0, 300.0, size).unwrap();
+
+    let screen = world_to_screen(&camera, ray.at(5.0), size).unwrap();
+
+    assert!(
+        (screen - Vec2::new(400.0, 300.0)).length() < 0.01,
+        "round trip mismatch: {screen:?}"
+    );
+}
+
+#[test]
+fn world_to_screen_rejects_points_behind_camera() {
+    let size = test_size();
+    let camera = create_test_camera(size);
+
+    assert!(world_to_screen(&camera, Vec3::new(0.0, 0.0, 20.0), size).is_none());
+}
+
+#[test]
+fn intersect_plane_hits_ground_plane() {
+    let ray = Ray::new(Vec3::new(0.0, 4.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
+
+    let distance = ray.intersect_plane(Vec3::ZERO, Vec3::Y).unwrap();
+
+    assert!((ray.at(distance) - Vec3::new(4.0, 0.0, 0.0)).length() < EPSILON);
+}
+
+#[test]
+fn intersect_plane_rejects_parallel_rays() {
+    let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::X);
+
+    assert!(ray.intersect_plane(Vec3::ZERO, Vec3::Y).is_none());
+}
+
+#[test]
+fn intersect_plane_rejects_planes_behind_origin() {
+    let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, -1.0));
+
+    assert!(ray.intersect_plane(Vec3::ZERO, Vec3::Y).is_none());
+}
+
+#[test]
+fn intersect_sphere_returns_distance_to_near_surface() {
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z);
+
+    let distance = ray.intersect_sphere(Vec3::ZERO, 0.5).unwrap();
+
+    assert!((distance - 9.5).abs() < EPSILON);
+}
+
+#[test]
+fn intersect_sphere_misses_rays_passing_beside_or_behind() {
+    let beside = Ray::new(Vec3::new(0.6, 0.0, 10.0), Vec3::NEG_Z);
+    let away = Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::Z);
+
+    assert!(beside.intersect_sphere(Vec3::ZERO, 0.5).is_none());
+    assert!(away.intersect_sphere(Vec3::ZERO, 0.5).is_none());
+}
+
+#[test]
+fn intersect_sphere_from_inside_hits_at_origin() {
+    let ray = Ray::new(Vec3::new(0.1, 0.0, 0.0), Vec3::X);
+
+    assert_eq!(ray.intersect_sphere(Vec3::ZERO, 0.5), Some(0.0));
+}
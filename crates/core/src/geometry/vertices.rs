@@ -1,3 +1,5 @@
+use std::mem::{offset_of, size_of};
+
 use bytemuck::{Pod, Zeroable};
 use glam::{Vec2, Vec3, Vec4};
 use wgpu::VertexBufferLayout;
@@ -14,6 +16,9 @@ pub struct Vertex {
 }
 
 impl Vertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 4] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32x3, 3 => Float32x4];
+
     pub fn new(position: Vec3, tex_coords: Vec2, normals: Vec3, colors: Vec4) -> Self {
         Self {
             position,
@@ -24,13 +29,22 @@ impl Vertex {
     }
 }
 
+// The attributes are tightly packed, unlike the WGSL struct they feed.
+const _: () = {
+    let attributes = &Vertex::ATTRIBUTES;
+    assert!(size_of::<Vertex>() == 48);
+    assert!(attributes[0].offset == offset_of!(Vertex, position) as wgpu::BufferAddress);
+    assert!(attributes[1].offset == offset_of!(Vertex, tex_coords) as wgpu::BufferAddress);
+    assert!(attributes[2].offset == offset_of!(Vertex, normals) as wgpu::BufferAddress);
+    assert!(attributes[3].offset == offset_of!(Vertex, colors) as wgpu::BufferAddress);
+};
+
 impl BufferLayoutProvider for Vertex {
     fn vertex_buffer_layout() -> VertexBufferLayout<'static> {
-        const ATTRIBS: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32x3, 3 => Float32x4];
         wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            array_stride: size_of::<Self>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &ATTRIBS,
+            attributes: &Self::ATTRIBUTES,
         }
     }
 }
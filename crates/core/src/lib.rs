@@ -17,6 +17,7 @@ pub mod events;
 pub mod geometry;
 pub mod traits;
 pub mod types;
+pub mod util;
 
 #[cfg(target_arch = "wasm32")]
 pub type Shared<T> = Rc<RefCell<T>>;
@@ -24,6 +25,18 @@ pub type Shared<T> = Rc<RefCell<T>>;
 #[cfg(not(target_arch = "wasm32"))]
 pub type Shared<T> = Arc<RwLock<T>>;
 
+/// `Send` on native targets, where [`Shared`] may cross threads, and no bound on the web, where
+/// it is single-threaded. Lets trait objects holding shared state stay `Send` natively.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
 pub fn shared<T>(elem: T) -> Shared<T> {
     #[cfg(not(target_arch = "wasm32"))]
     {
@@ -35,6 +48,18 @@ pub fn shared<T>(elem: T) -> Shared<T> {
     }
 }
 
+/// Whether both handles point at the same shared value.
+pub fn same_shared<T>(a: &Shared<T>, b: &Shared<T>) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Arc::ptr_eq(a, b)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Rc::ptr_eq(a, b)
+    }
+}
+
 pub trait SharedAccess<T> {
     fn read_shared<F>(&self, f: impl FnOnce(&T) -> F) -> F;
     fn write_shared<F>(&self, f: impl FnOnce(&mut T) -> F) -> F;
@@ -4,6 +4,14 @@ pub trait BufferLayoutProvider {
     fn vertex_buffer_layout() -> VertexBufferLayout<'static>;
 }
 
+/// A `#[repr(C)]` struct uploaded as the WGSL struct(s) named in [`Self::WGSL_NAMES`]. Offsets
+/// are listed under the WGSL member names and leave out padding fields, so they can be compared
+/// against the shader declarations.
+pub trait WgslLayout: Sized {
+    const WGSL_NAMES: &'static [&'static str];
+    const MEMBERS: &'static [(&'static str, usize)];
+}
+
 pub trait BindGroupProvider {
     fn bind_group_layout(device: &Device) -> BindGroupLayout;
     fn bind_group(
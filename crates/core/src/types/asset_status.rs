@@ -0,0 +1,101 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetStatus {
+    Loading,
+    Ready,
+    Failed,
+}
+
+impl AssetStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Loading => "loading",
+            Self::Ready => "ready",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// One load state transition of an asset, handed to JS status listeners as
+/// `{ id, status: "loading" | "ready" | "failed", message, stage, progress }`.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetStatusEvent {
+    pub id: String,
+    pub status: String,
+    /// The importer error for failed loads, unchanged.
+    pub message: Option<String>,
+    /// The step a loading asset reached, set on progress reports only.
+    pub stage: Option<String>,
+    /// How much of `stage` is done, from 0 to 1.
+    pub progress: Option<f32>,
+}
+
+impl AssetStatusEvent {
+    pub fn new(id: String, status: AssetStatus, message: Option<String>) -> Self {
+        Self {
+            id,
+            status: status.as_str().to_string(),
+            message,
+            stage: None,
+            progress: None,
+        }
+    }
+
+    pub fn loading(id: String) -> Self {
+        Self::new(id, AssetStatus::Loading, None)
+    }
+
+    /// A `"loading"` event reporting that `fraction` of `stage` is done.
+    pub fn progress(id: String, stage: &str, fraction: f32) -> Self {
+        Self {
+            stage: Some(stage.to_string()),
+            progress: Some(fraction),
+            ..Self::loading(id)
+        }
+    }
+
+    pub fn ready(id: String) -> Self {
+        Self::new(id, AssetStatus::Ready, None)
+    }
+
+    pub fn failed(id: String, message: String) -> Self {
+        Self::new(id, AssetStatus::Failed, Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_event_keeps_the_error_message() {
+        let event = AssetStatusEvent::failed(
+            "monkey".to_string(),
+            "Failed to parse glTF: unexpected end of file".to_string(),
+        );
+
+        assert_eq!(event.id, "monkey");
+        assert_eq!(event.status, "failed");
+        assert_eq!(
+            event.message.as_deref(),
+            Some("Failed to parse glTF: unexpected end of file")
+        );
+        assert_eq!(
+            AssetStatusEvent::loading("monkey".to_string()).status,
+            "loading"
+        );
+        assert_eq!(AssetStatusEvent::ready("monkey".to_string()).message, None);
+    }
+
+    #[test]
+    fn test_progress_event_is_a_loading_event_with_its_stage() {
+        let event = AssetStatusEvent::progress("monkey".to_string(), "parsing", 0.5);
+
+        assert_eq!(event.status, "loading");
+        assert_eq!(event.stage.as_deref(), Some("parsing"));
+        assert_eq!(event.progress, Some(0.5));
+        assert_eq!(AssetStatusEvent::loading("monkey".to_string()).stage, None);
+    }
+}
@@ -1,9 +1,6 @@
-use std::{
-    f32::consts::{PI, TAU},
-    ops::Deref,
-};
+use std::ops::Deref;
 
-use crate::types::F32_ZERO;
+use crate::util::float::{F32_ZERO, wrap_angle};
 
 fn smoothing_interpolation(
     prev_value: f32,
@@ -40,11 +37,7 @@ impl Yaw {
             smoothing_factor,
         );
 
-        if self.value > PI {
-            self.value = self.value - TAU + smoothed_delta_interpolation;
-        } else {
-            self.value += smoothed_delta_interpolation;
-        }
+        self.value = wrap_angle(self.value + smoothed_delta_interpolation);
         self.previous_delta = smoothed_delta_interpolation;
     }
 }
@@ -103,7 +96,10 @@ impl Deref for Pitch {
 
 #[cfg(test)]
 mod tests {
+    use std::f32::consts::TAU;
+
     use super::*;
+    use crate::util::test_support::assert_f32_eq;
 
     #[test]
     fn test_yaw_add_positive_delta() {
@@ -112,17 +108,17 @@ mod tests {
 
         // Add positive delta with smoothing factor 0.5
         // one_minus_smoothing = 1.0 - 0.5 = 0.5
-        yaw.add(10.0, 0.5, 0.5);
+        yaw.add(2.0, 0.5, 0.5);
 
-        // First add: smoothed_delta = 0.0 * 0.5 + 10.0 * 0.5 = 5.0
-        // value = 0.0 + 5.0 = 5.0
+        // First add: smoothed_delta = 0.0 * 0.5 + 2.0 * 0.5 = 1.0
+        // value = 0.0 + 1.0 = 1.0
         assert!(
             *yaw > initial_value,
             "Yaw should increase with positive delta. Initial: {}, New: {}",
             initial_value,
             *yaw
         );
-        assert_eq!(*yaw, 5.0, "Yaw should be 5.0 after first smoothed add");
+        assert_eq!(*yaw, 1.0, "Yaw should be 1.0 after first smoothed add");
     }
 
     #[test]
@@ -131,17 +127,28 @@ mod tests {
         let initial_value = *yaw;
 
         // Add negative delta with smoothing factor 0.5
-        yaw.add(-10.0, 0.5, 0.5);
+        yaw.add(-2.0, 0.5, 0.5);
 
-        // First add: smoothed_delta = 0.0 * 0.5 + (-10.0) * 0.5 = -5.0
-        // value = 0.0 + (-5.0) = -5.0
+        // First add: smoothed_delta = 0.0 * 0.5 + (-2.0) * 0.5 = -1.0
+        // value = 0.0 + (-1.0) = -1.0
         assert!(
             *yaw < initial_value,
             "Yaw should decrease with negative delta. Initial: {}, New: {}",
             initial_value,
             *yaw
         );
-        assert_eq!(*yaw, -5.0, "Yaw should be -5.0 after first smoothed add");
+        assert_eq!(*yaw, -1.0, "Yaw should be -1.0 after first smoothed add");
+    }
+
+    #[test]
+    fn test_yaw_add_wraps_in_both_directions() {
+        let mut yaw = Yaw::new(3.0);
+        yaw.add(0.5, 0.0, 1.0);
+        assert_f32_eq(*yaw, 3.5 - TAU, "Yaw should wrap past +PI");
+
+        let mut yaw = Yaw::new(-3.0);
+        yaw.add(-0.5, 0.0, 1.0);
+        assert_f32_eq(*yaw, TAU - 3.5, "Yaw should wrap past -PI");
     }
 
     #[test]
@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Change stamp for CPU-side state that is mirrored into a GPU buffer. Stamps are unique across
+/// the process, so a replaced value never looks unchanged to a buffer that uploaded its
+/// predecessor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Generation(u64);
+
+impl Generation {
+    pub fn next() -> Self {
+        Self(NEXT_GENERATION.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn bump(&mut self) {
+        *self = Self::next();
+    }
+}
+
+impl Default for Generation {
+    fn default() -> Self {
+        Self::next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generations_are_unique_and_bumping_changes_them() {
+        let first = Generation::next();
+        let mut second = Generation::next();
+        assert_ne!(first, second);
+
+        let before = second;
+        second.bump();
+        assert_ne!(second, before);
+        assert_eq!(before, before.clone());
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+/// Index into a [`SlotMap`] plus the generation of the slot it was issued for. Once the slot is
+/// freed its generation moves on, so a handle kept past removal is detected as stale instead of
+/// silently pointing at whatever reuses the slot.
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(index: u32, generation: u32) -> Self {
+        Self {
+            index,
+            generation,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// Implemented by hand so handles are `Copy` and comparable whatever `T` is.
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+/// Hands out [`Handle`]s and recycles the indices of freed ones.
+pub struct HandleAllocator<T> {
+    generations: Vec<u32>,
+    free: Vec<u32>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> HandleAllocator<T> {
+    pub fn new() -> Self {
+        Self {
+            generations: Vec::new(),
+            free: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn allocate(&mut self) -> Handle<T> {
+        if let Some(index) = self.free.pop() {
+            return Handle::new(index, self.generations[index as usize]);
+        }
+        let index = u32::try_from(self.generations.len()).expect("more than u32::MAX handles");
+        self.generations.push(0);
+        Handle::new(index, 0)
+    }
+
+    /// Retires `handle`. Returns false if it was already stale.
+    pub fn free(&mut self, handle: Handle<T>) -> bool {
+        if !self.is_alive(handle) {
+            return false;
+        }
+        let generation = &mut self.generations[handle.index as usize];
+        *generation = generation.wrapping_add(1);
+        self.free.push(handle.index);
+        true
+    }
+
+    pub fn is_alive(&self, handle: Handle<T>) -> bool {
+        self.generations.get(handle.index as usize) == Some(&handle.generation)
+    }
+
+    pub fn len(&self) -> usize {
+        self.generations.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for HandleAllocator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for HandleAllocator<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandleAllocator")
+            .field("slots", &self.generations.len())
+            .field("free", &self.free.len())
+            .finish()
+    }
+}
+
+/// Values addressed by `Handle<K>`. Lookups are a bounds and generation check instead of a hash,
+/// which is what per-frame iteration wants.
+pub struct SlotMap<K, V> {
+    allocator: HandleAllocator<K>,
+    slots: Vec<Option<V>>,
+}
+
+impl<K, V> SlotMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            allocator: HandleAllocator::new(),
+            slots: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, value: V) -> Handle<K> {
+        let handle = self.allocator.allocate();
+        let index = handle.index as usize;
+        if index == self.slots.len() {
+            self.slots.push(Some(value));
+        } else {
+            self.slots[index] = Some(value);
+        }
+        handle
+    }
+
+    pub fn get(&self, handle: Handle<K>) -> Option<&V> {
+        if !self.allocator.is_alive(handle) {
+            return None;
+        }
+        self.slots.get(handle.index as usize)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<K>) -> Option<&mut V> {
+        if !self.allocator.is_alive(handle) {
+            return None;
+        }
+        self.slots.get_mut(handle.index as usize)?.as_mut()
+    }
+
+    pub fn remove(&mut self, handle: Handle<K>) -> Option<V> {
+        if !self.allocator.free(handle) {
+            return None;
+        }
+        self.slots[handle.index as usize].take()
+    }
+
+    pub fn contains(&self, handle: Handle<K>) -> bool {
+        self.allocator.is_alive(handle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.allocator.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allocator.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<K>, &V)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let value = slot.as_ref()?;
+            Some((
+                Handle::new(index as u32, self.allocator.generations[index]),
+                value,
+            ))
+        })
+    }
+}
+
+impl<K, V> Default for SlotMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for SlotMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Mesh;
+
+    #[test]
+    fn test_freed_handles_go_stale_when_the_slot_is_reused() {
+        let mut allocator = HandleAllocator::<Mesh>::new();
+        let first = allocator.allocate();
+        assert!(allocator.free(first));
+        assert!(!allocator.free(first));
+
+        let reused = allocator.allocate();
+
+        assert_eq!(reused.index(), first.index());
+        assert_ne!(reused, first);
+        assert!(allocator.is_alive(reused));
+        assert!(!allocator.is_alive(first));
+        assert_eq!(allocator.len(), 1);
+    }
+
+    #[test]
+    fn test_stale_handles_do_not_reach_the_new_value() {
+        let mut slots = SlotMap::<Mesh, &str>::new();
+        let cube = slots.insert("cube");
+        assert_eq!(slots.remove(cube), Some("cube"));
+
+        let suzanne = slots.insert("suzanne");
+
+        assert_eq!(slots.get(cube), None);
+        assert_eq!(slots.remove(cube), None);
+        assert_eq!(slots.get(suzanne), Some(&"suzanne"));
+        assert_eq!(slots.len(), 1);
+    }
+
+    #[test]
+    fn test_iteration_yields_live_handles() {
+        let mut slots = SlotMap::<Mesh, u32>::new();
+        let a = slots.insert(1);
+        let b = slots.insert(2);
+        let c = slots.insert(3);
+        slots.remove(b);
+        *slots.get_mut(c).unwrap() = 30;
+
+        assert_eq!(slots.iter().collect::<Vec<_>>(), vec![(a, &1), (c, &30)]);
+    }
+}
@@ -29,6 +29,29 @@ impl UniformResourceId for UniformBufferId {
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StorageBufferId {
+    pub id: String,
+}
+
+impl StorageBufferId {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl BaseId for StorageBufferId {
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl UniformResourceId for StorageBufferId {
+    fn get(&self) -> &str {
+        self.get_id()
+    }
+}
+
 #[derive(Default, Debug, Eq, PartialEq, Clone, Hash)]
 pub struct MeshId(pub String);
 
@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// A shared value was locked elsewhere and the caller chose to skip the work instead of waiting.
+/// Travels inside [`anyhow::Error`], so callers that can retry next frame downcast to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockBusy {
+    resource: String,
+}
+
+impl LockBusy {
+    pub fn new(resource: impl Into<String>) -> Self {
+        Self {
+            resource: resource.into(),
+        }
+    }
+
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+}
+
+impl fmt::Display for LockBusy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is locked elsewhere", self.resource)
+    }
+}
+
+impl std::error::Error for LockBusy {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_busy_survives_a_round_trip_through_anyhow() {
+        let error = anyhow::Error::new(LockBusy::new("Light Uniform Buffer"));
+
+        assert_eq!(
+            error.to_string(),
+            "Light Uniform Buffer is locked elsewhere"
+        );
+        assert_eq!(
+            error.downcast_ref::<LockBusy>().map(LockBusy::resource),
+            Some("Light Uniform Buffer")
+        );
+    }
+}
@@ -1,25 +1,32 @@
 use std::ops::Deref;
 
 use anyhow::Result;
-use wgpu::{Buffer, Queue};
+use bytemuck::{Pod, bytes_of};
+use wgpu::{Buffer, BufferAddress, Queue};
 
-use crate::{Shared, types::transform::Transform};
+use crate::{
+    Shared, SharedAccess,
+    types::{lock_busy::LockBusy, transform::Transform},
+};
 
+pub mod asset_status;
 pub mod base;
 pub mod camera;
+pub mod generation;
+pub mod handle;
 pub mod ids;
 pub mod import_diagnostic;
+pub mod lock_busy;
 pub mod mouse_delta;
 pub mod shared;
+pub mod sync_cell;
 pub mod transform;
 pub mod upload_status;
+pub mod viewport;
 
 pub type DeltaTime = f32;
 pub type DeltaTime64 = f64;
 
-pub const F32_ZERO: f32 = 0.0;
-pub const F64_ZERO: f64 = 0.0;
-
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Size {
     pub width: u32,
@@ -58,11 +65,41 @@ pub trait BaseBuffer {
     fn get_id_as_string(&self) -> &str;
 }
 
+/// The part of [`Queue`] that buffer uploads need, so uploads can be observed in tests.
+pub trait QueueWriter {
+    fn write_buffer(&self, buffer: &Buffer, offset: BufferAddress, data: &[u8]);
+}
+
+impl QueueWriter for Queue {
+    fn write_buffer(&self, buffer: &Buffer, offset: BufferAddress, data: &[u8]) {
+        Queue::write_buffer(self, buffer, offset, data);
+    }
+}
+
 pub trait TransformBuffer: Deref + BaseBuffer {
     fn get_transform(&self) -> Shared<Transform>;
-    fn update_buffer_transform(&mut self, queue: &Queue, data: &[u8]) -> Result<()> {
+    fn update_buffer_transform(&mut self, queue: &impl QueueWriter, data: &[u8]) -> Result<()> {
         let buffer = self.get_buffer();
         queue.write_buffer(buffer, 0, data);
         Ok(())
     }
+
+    /// Writes the model matrix of the current transform at offset 0.
+    fn upload_current(&mut self, queue: &impl QueueWriter) -> Result<()> {
+        self.upload_with(queue, Transform::get_matrix)
+    }
+
+    /// Writes whatever `payload` derives from the current transform at offset 0. Fails with
+    /// [`LockBusy`] instead of blocking when the transform is locked elsewhere.
+    fn upload_with<T: Pod>(
+        &mut self,
+        queue: &impl QueueWriter,
+        payload: impl FnOnce(&Transform) -> T,
+    ) -> Result<()> {
+        let data = self
+            .get_transform()
+            .try_read_shared(payload)
+            .map_err(|_| LockBusy::new(self.get_id_as_string()))?;
+        self.update_buffer_transform(queue, bytes_of(&data))
+    }
 }
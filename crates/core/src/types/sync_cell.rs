@@ -0,0 +1,48 @@
+use parking_lot::Mutex;
+
+/// A [`Cell`](std::cell::Cell) for `Copy` values that can be shared between threads, so small
+/// caches do not make the GPU objects holding them `!Sync`.
+#[derive(Debug, Default)]
+pub struct SyncCell<T: Copy>(Mutex<T>);
+
+impl<T: Copy> SyncCell<T> {
+    pub fn new(value: T) -> Self {
+        Self(Mutex::new(value))
+    }
+
+    pub fn get(&self) -> T {
+        *self.0.lock()
+    }
+
+    pub fn set(&self, value: T) {
+        *self.0.lock() = value;
+    }
+
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.0.lock(), value)
+    }
+}
+
+/// Clones hold a copy of the current value and change independently afterwards.
+impl<T: Copy> Clone for SyncCell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_start_from_the_current_value_and_then_diverge() {
+        let cell = SyncCell::new(1);
+        assert_eq!(cell.replace(2), 1);
+
+        let clone = cell.clone();
+        clone.set(3);
+
+        assert_eq!(cell.get(), 2);
+        assert_eq!(clone.get(), 3);
+    }
+}
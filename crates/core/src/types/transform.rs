@@ -1,8 +1,12 @@
+use std::mem::{offset_of, size_of};
+
 use bytemuck::{Pod, Zeroable};
 use glam::{Mat4, Quat, Vec3};
 
+use crate::{traits::WgslLayout, types::sync_cell::SyncCell};
+
 #[repr(C)]
-#[derive(Debug, Default, Copy, Clone, Pod, Zeroable)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Pod, Zeroable)]
 pub struct Transform {
     pub position: Vec3,
     _padding1: f32,
@@ -11,6 +15,23 @@ pub struct Transform {
     _padding2: f32,
 }
 
+// WGSL aligns `vec3<f32>` to 16 bytes; the padding fields have to reproduce that.
+const _: () = {
+    assert!(size_of::<Transform>() == 48);
+    assert!(offset_of!(Transform, position) == 0);
+    assert!(offset_of!(Transform, rotation) == 16);
+    assert!(offset_of!(Transform, scale) == 32);
+};
+
+impl WgslLayout for Transform {
+    const WGSL_NAMES: &'static [&'static str] = &["Transform"];
+    const MEMBERS: &'static [(&'static str, usize)] = &[
+        ("translation", offset_of!(Transform, position)),
+        ("rotation", offset_of!(Transform, rotation)),
+        ("scale", offset_of!(Transform, scale)),
+    ];
+}
+
 impl Transform {
     pub fn new(position: Vec3, rotation: Quat, scale: Vec3) -> Transform {
         Self {
@@ -37,38 +58,41 @@ impl Transform {
             * Mat4::from_quat(self.rotation)
             * Mat4::from_scale(self.scale)
     }
+
+    /// This transform, taken as local to `parent`, expressed in the parent's space.
+    pub fn parented_to(&self, parent: &Transform) -> Transform {
+        let (scale, rotation, translation) =
+            (parent.get_matrix() * self.get_matrix()).to_scale_rotation_translation();
+        Transform::new(translation, rotation, scale)
+    }
+}
+
+/// Keeps the matrix of the last transform it was asked about, so objects that did not move since
+/// skip rebuilding it every frame.
+#[derive(Debug, Default, Clone)]
+pub struct MatrixCache {
+    cached: SyncCell<Option<(Transform, Mat4)>>,
+}
+
+impl MatrixCache {
+    pub fn matrix(&self, transform: &Transform) -> Mat4 {
+        match self.cached.get() {
+            Some((cached, matrix)) if cached == *transform => matrix,
+            _ => {
+                let matrix = transform.get_matrix();
+                self.cached.set(Some((*transform, matrix)));
+                matrix
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_support::{EPSILON, assert_quat_eq, assert_vec3_eq};
     use std::f32::consts::PI;
 
-    const EPSILON: f32 = 1e-6;
-
-    fn assert_vec3_eq(a: Vec3, b: Vec3, msg: &str) {
-        assert!(
-            (a - b).length() < EPSILON,
-            "{}: expected {:?}, got {:?}",
-            msg,
-            b,
-            a
-        );
-    }
-
-    fn assert_quat_eq(a: Quat, b: Quat, msg: &str) {
-        // Quaternions q and -q represent the same rotation
-        let dot = a.dot(b).abs();
-        assert!(
-            (dot - 1.0).abs() < EPSILON,
-            "{}: expected {:?}, got {:?} (dot: {})",
-            msg,
-            b,
-            a,
-            dot
-        );
-    }
-
     #[test]
     fn test_identity_transform_produces_identity_matrix() {
         let transform = Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE);
@@ -364,4 +388,37 @@ mod tests {
 
         assert_vec3_eq(transformed, expected, "Transformed point");
     }
+
+    #[test]
+    fn test_matrix_cache_follows_transform_changes() {
+        let cache = MatrixCache::default();
+        let mut transform = Transform::new(Vec3::X, Quat::IDENTITY, Vec3::ONE);
+        assert_eq!(cache.matrix(&transform), transform.get_matrix());
+        assert_eq!(cache.matrix(&transform), transform.get_matrix());
+
+        transform.translate(Vec3::Y);
+        transform.rotate(Quat::from_rotation_z(PI / 3.0));
+
+        assert_eq!(cache.matrix(&transform), transform.get_matrix());
+    }
+
+    #[test]
+    fn test_parented_to_applies_the_parent_after_the_child() {
+        let child = Transform::new(Vec3::new(2.0, 0.0, 0.0), Quat::IDENTITY, Vec3::ONE);
+        let parent = Transform::new(
+            Vec3::new(10.0, 0.0, 0.0),
+            Quat::from_rotation_z(PI / 2.0),
+            Vec3::splat(2.0),
+        );
+
+        let placed = child.parented_to(&parent);
+
+        assert_vec3_eq(
+            placed.position,
+            Vec3::new(10.0, 4.0, 0.0),
+            "child offset is rotated and scaled by the parent",
+        );
+        assert_quat_eq(placed.rotation, parent.rotation, "inherited rotation");
+        assert_vec3_eq(placed.scale, Vec3::splat(2.0), "inherited scale");
+    }
 }
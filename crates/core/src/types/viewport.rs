@@ -0,0 +1,127 @@
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition};
+
+use crate::types::Size;
+
+/// The render surface in physical pixels together with the window's DPI scale factor. Cursor
+/// positions, picking and the surface all work in physical pixels; logical values are derived
+/// from here so both always agree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    size: Size,
+    scale_factor: f64,
+}
+
+impl Viewport {
+    pub const DEFAULT_SCALE_FACTOR: f64 = 1.0;
+
+    /// Non-positive or non-finite scale factors fall back to 1.0.
+    pub fn new(size: Size, scale_factor: f64) -> Self {
+        Self {
+            size,
+            scale_factor: sanitize_scale_factor(scale_factor),
+        }
+    }
+
+    pub fn physical_size(&self) -> Size {
+        self.size
+    }
+
+    pub fn logical_size(&self) -> LogicalSize<f64> {
+        LogicalSize::new(
+            f64::from(self.size.width) / self.scale_factor,
+            f64::from(self.size.height) / self.scale_factor,
+        )
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    pub fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = sanitize_scale_factor(scale_factor);
+    }
+
+    pub fn to_physical_position(&self, position: LogicalPosition<f64>) -> PhysicalPosition<f64> {
+        position.to_physical(self.scale_factor)
+    }
+
+    pub fn to_logical_position(&self, position: PhysicalPosition<f64>) -> LogicalPosition<f64> {
+        position.to_logical(self.scale_factor)
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new(
+            Size {
+                width: 1920,
+                height: 1080,
+            },
+            Self::DEFAULT_SCALE_FACTOR,
+        )
+    }
+}
+
+fn sanitize_scale_factor(scale_factor: f64) -> f64 {
+    if scale_factor > 0.0 && scale_factor.is_finite() {
+        scale_factor
+    } else {
+        Viewport::DEFAULT_SCALE_FACTOR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Size = Size {
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn test_positions_convert_at_common_scale_factors() {
+        for (scale_factor, logical, physical) in [
+            (1.0, (100.0, 50.0), (100.0, 50.0)),
+            (1.5, (100.0, 50.0), (150.0, 75.0)),
+            (2.0, (100.0, 50.0), (200.0, 100.0)),
+        ] {
+            let viewport = Viewport::new(SIZE, scale_factor);
+            let logical = LogicalPosition::new(logical.0, logical.1);
+            let physical = PhysicalPosition::new(physical.0, physical.1);
+
+            assert_eq!(viewport.to_physical_position(logical), physical);
+            assert_eq!(viewport.to_logical_position(physical), logical);
+        }
+    }
+
+    #[test]
+    fn test_logical_size_is_physical_size_divided_by_scale_factor() {
+        assert_eq!(
+            Viewport::new(SIZE, 1.0).logical_size(),
+            LogicalSize::new(1920.0, 1080.0)
+        );
+        assert_eq!(
+            Viewport::new(SIZE, 1.5).logical_size(),
+            LogicalSize::new(1280.0, 720.0)
+        );
+        assert_eq!(
+            Viewport::new(SIZE, 2.0).logical_size(),
+            LogicalSize::new(960.0, 540.0)
+        );
+    }
+
+    #[test]
+    fn test_invalid_scale_factor_falls_back_to_one() {
+        assert_eq!(Viewport::new(SIZE, 0.0).scale_factor(), 1.0);
+        assert_eq!(Viewport::new(SIZE, f64::NAN).scale_factor(), 1.0);
+
+        let mut viewport = Viewport::new(SIZE, 2.0);
+        viewport.set_scale_factor(-1.0);
+        assert_eq!(viewport.scale_factor(), 1.0);
+    }
+}
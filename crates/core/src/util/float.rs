@@ -0,0 +1,92 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{Quat, Vec3};
+
+pub const F32_ZERO: f32 = 0.0;
+pub const F64_ZERO: f64 = 0.0;
+
+pub fn approx_eq(a: f32, b: f32, epsilon: f32) -> bool {
+    (a - b).abs() <= epsilon
+}
+
+/// Compares by distance, so `epsilon` bounds the length of the difference.
+pub fn vec3_approx_eq(a: Vec3, b: Vec3, epsilon: f32) -> bool {
+    (a - b).length() <= epsilon
+}
+
+/// `q` and `-q` describe the same rotation, so both compare equal.
+pub fn quat_approx_eq(a: Quat, b: Quat, epsilon: f32) -> bool {
+    approx_eq(a.dot(b).abs(), 1.0, epsilon)
+}
+
+/// `angle` moved into `(-π, π]`, e.g. the shortest signed difference between two angles.
+pub fn wrap_angle(angle: f32) -> f32 {
+    // Angles already in range come back untouched instead of picking up rounding error.
+    if angle > -PI && angle <= PI {
+        return angle;
+    }
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    if wrapped <= -PI {
+        wrapped + TAU
+    } else {
+        wrapped
+    }
+}
+
+/// `angle` moved into `[0, 2π)`.
+pub fn normalize_angle_0_tau(angle: f32) -> f32 {
+    let normalized = angle.rem_euclid(TAU);
+    // Tiny negative angles round up to exactly TAU.
+    if normalized >= TAU { 0.0 } else { normalized }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::EPSILON;
+
+    #[test]
+    fn test_approx_eq_respects_epsilon() {
+        assert!(approx_eq(1.0, 1.0 + 1e-7, EPSILON));
+        assert!(!approx_eq(1.0, 1.1, EPSILON));
+        assert!(approx_eq(1.0, 1.1, 0.2));
+    }
+
+    #[test]
+    fn test_vec3_approx_eq_uses_distance() {
+        assert!(vec3_approx_eq(Vec3::ONE, Vec3::ONE + 1e-7, EPSILON));
+        assert!(!vec3_approx_eq(Vec3::ZERO, Vec3::new(0.6, 0.6, 0.6), 1.0));
+        assert!(vec3_approx_eq(Vec3::ZERO, Vec3::new(0.5, 0.5, 0.5), 1.0));
+    }
+
+    #[test]
+    fn test_quat_approx_eq_treats_negated_quaternions_as_equal() {
+        let rotation = Quat::from_rotation_y(0.75);
+
+        assert!(quat_approx_eq(rotation, -rotation, EPSILON));
+        assert!(!quat_approx_eq(rotation, Quat::IDENTITY, EPSILON));
+    }
+
+    #[test]
+    fn test_wrap_angle_lands_in_half_open_range() {
+        assert!(approx_eq(wrap_angle(1.5 * PI), -0.5 * PI, EPSILON));
+        assert!(approx_eq(wrap_angle(-1.5 * PI), 0.5 * PI, EPSILON));
+        assert!(approx_eq(wrap_angle(0.25), 0.25, EPSILON));
+        assert!(approx_eq(wrap_angle(5.0 * TAU + 0.25), 0.25, 1e-4));
+        assert_eq!(wrap_angle(1.0), 1.0);
+        assert_eq!(wrap_angle(PI), PI);
+        assert_eq!(wrap_angle(-PI), PI);
+    }
+
+    #[test]
+    fn test_normalize_angle_0_tau_lands_in_half_open_range() {
+        assert!(approx_eq(
+            normalize_angle_0_tau(-0.5 * PI),
+            1.5 * PI,
+            EPSILON
+        ));
+        assert!(approx_eq(normalize_angle_0_tau(TAU + 0.25), 0.25, EPSILON));
+        assert_eq!(normalize_angle_0_tau(TAU), 0.0);
+        assert_eq!(normalize_angle_0_tau(-f32::EPSILON * 1e-3), 0.0);
+    }
+}
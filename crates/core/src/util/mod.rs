@@ -0,0 +1,3 @@
+pub mod float;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
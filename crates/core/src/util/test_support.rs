@@ -0,0 +1,49 @@
+//! Assertions shared by the test suites of this and the dependent crates, which enable the
+//! `test-support` feature.
+
+use glam::{Quat, Vec2, Vec3, Vec4};
+
+use crate::util::float::{approx_eq, quat_approx_eq, vec3_approx_eq};
+
+pub const EPSILON: f32 = 1e-6;
+
+#[track_caller]
+pub fn assert_f32_eq(actual: f32, expected: f32, message: &str) {
+    assert!(
+        approx_eq(actual, expected, EPSILON),
+        "{message}: expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[track_caller]
+pub fn assert_vec2_eq(actual: Vec2, expected: Vec2, message: &str) {
+    assert!(
+        (actual - expected).length() <= EPSILON,
+        "{message}: expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[track_caller]
+pub fn assert_vec3_eq(actual: Vec3, expected: Vec3, message: &str) {
+    assert!(
+        vec3_approx_eq(actual, expected, EPSILON),
+        "{message}: expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[track_caller]
+pub fn assert_vec4_eq(actual: Vec4, expected: Vec4, message: &str) {
+    assert!(
+        (actual - expected).length() <= EPSILON,
+        "{message}: expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[track_caller]
+pub fn assert_quat_eq(actual: Quat, expected: Quat, message: &str) {
+    assert!(
+        quat_approx_eq(actual, expected, EPSILON),
+        "{message}: expected {expected:?}, got {actual:?} (dot: {})",
+        actual.dot(expected).abs()
+    );
+}
@@ -0,0 +1,43 @@
+struct Camera {
+    view_projection_matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+    inverse_view_projection_matrix: mat4x4<f32>,
+}
+
+struct Model {
+    model_matrix: mat4x4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+@group(1) @binding(0)
+var<uniform> model: Model;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normals: vec3<f32>,
+    @location(3) colors: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = camera.view_projection_matrix * model.model_matrix * vec4<f32>(vertex.position, 1.0);
+    out.color = vertex.colors;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,57 @@
+struct Camera {
+    view_projection_matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+    inverse_view_projection_matrix: mat4x4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct BillboardInput {
+    @location(0) position: vec3<f32>,
+    @location(1) radius: f32,
+    @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) corner: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+// Two triangles spanning the quad, in units of the radius.
+const CORNERS = array<vec2<f32>, 6>(
+    vec2<f32>(-1.0, -1.0),
+    vec2<f32>(1.0, -1.0),
+    vec2<f32>(1.0, 1.0),
+    vec2<f32>(-1.0, -1.0),
+    vec2<f32>(1.0, 1.0),
+    vec2<f32>(-1.0, 1.0),
+);
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    billboard: BillboardInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let corner = CORNERS[vertex_index];
+    let offset = (camera.right * corner.x + camera.up * corner.y) * billboard.radius;
+    out.clip_position = camera.view_projection_matrix * vec4<f32>(billboard.position + offset, 1.0);
+    out.corner = corner;
+    out.color = billboard.color;
+    return out;
+}
+
+// A filled disc with a dark rim, so white lights stay visible against bright backgrounds.
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let distance = length(in.corner);
+    if distance > 1.0 {
+        discard;
+    }
+    let rim = step(0.8, distance);
+    return vec4<f32>(mix(in.color.rgb, in.color.rgb * 0.2, rim), 1.0);
+}
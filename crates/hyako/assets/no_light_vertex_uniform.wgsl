@@ -1,5 +1,9 @@
 struct Camera {
-    view_projection_matrix: mat4x4<f32>
+    view_projection_matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+    inverse_view_projection_matrix: mat4x4<f32>,
 }
 
 struct Model {
@@ -0,0 +1,48 @@
+struct Camera {
+    view_projection_matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+    inverse_view_projection_matrix: mat4x4<f32>,
+}
+
+struct Outline {
+    model_matrix: mat4x4<f32>,
+    color: vec4<f32>,
+    thickness: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+@group(1) @binding(0)
+var<uniform> outline: Outline;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normals: vec3<f32>,
+    @location(3) colors: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+// Inflates the mesh along its world-space normals; only the back faces of this shell are drawn,
+// so it shows up as a rim around the silhouette of the original mesh.
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let world_position = outline.model_matrix * vec4<f32>(vertex.position, 1.0);
+    let world_normal = normalize((outline.model_matrix * vec4<f32>(vertex.normals, 0.0)).xyz);
+    let inflated = world_position.xyz + world_normal * outline.thickness;
+    out.clip_position = camera.view_projection_matrix * vec4<f32>(inflated, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return outline.color;
+}
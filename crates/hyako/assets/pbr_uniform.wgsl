@@ -0,0 +1,149 @@
+struct Camera {
+    view_projection_matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+    inverse_view_projection_matrix: mat4x4<f32>,
+}
+
+struct Model {
+    model_matrix: mat4x4<f32>,
+}
+
+struct Material {
+    base_color_factor: vec4<f32>,
+    metallic_factor: f32,
+    roughness_factor: f32,
+    emissive_factor: vec3<f32>,
+    albedo_source: u32,
+}
+
+struct Transform {
+    translation: vec3<f32>,
+    rotation: vec4<f32>,
+    scale: vec3<f32>
+}
+
+struct Light {
+    transform: Transform,
+    color: vec3<f32>,
+}
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normals: vec3<f32>,
+    @location(3) colors: vec4<f32>
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(1) world_position: vec3<f32>,
+    @location(2) tex_coords: vec2<f32>,
+    @location(3) world_normal: vec3<f32>,
+    @location(4) colors: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+@group(1) @binding(0)
+var<uniform> light: Light;
+@group(2) @binding(0)
+var<uniform> model: Model;
+@group(3) @binding(0)
+var<uniform> material: Material;
+@group(3) @binding(1)
+var base_color_texture: texture_2d<f32>;
+@group(3) @binding(2)
+var base_color_sampler: sampler;
+@group(3) @binding(3)
+var metallic_roughness_texture: texture_2d<f32>;
+@group(3) @binding(4)
+var metallic_roughness_sampler: sampler;
+
+const PI: f32 = 3.14159265;
+const LIGHT_INTENSITY: f32 = 4.0;
+const AMBIENT: f32 = 0.03;
+// Perfectly smooth surfaces turn the GGX lobe into a point no pixel hits.
+const MIN_ROUGHNESS: f32 = 0.045;
+
+const ALBEDO_VERTEX_COLOR: u32 = 0u;
+const ALBEDO_BASE_COLOR_FACTOR: u32 = 1u;
+
+// The albedo source is resolved on the CPU, see `ShadingSource`. Textures are tinted by the factor.
+fn albedo(vertex_color: vec4<f32>, sampled_base_color: vec4<f32>) -> vec4<f32> {
+    switch material.albedo_source {
+        case ALBEDO_VERTEX_COLOR: {
+            return vertex_color;
+        }
+        case ALBEDO_BASE_COLOR_FACTOR: {
+            return material.base_color_factor;
+        }
+        default: {
+            return material.base_color_factor * sampled_base_color;
+        }
+    }
+}
+
+@vertex
+fn vs_main(
+    mesh: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let world_position = model.model_matrix * vec4<f32>(mesh.position, 1.0);
+    out.world_position = world_position.xyz;
+    // Exact for rotations and uniform scale, which is all the editor produces.
+    out.world_normal = (model.model_matrix * vec4<f32>(mesh.normals, 0.0)).xyz;
+    out.tex_coords = mesh.tex_coords;
+    out.colors = mesh.colors;
+    out.clip_position = camera.view_projection_matrix * world_position;
+    return out;
+}
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let alpha = roughness * roughness;
+    let alpha2 = alpha * alpha;
+    let denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    return alpha2 / (PI * denominator * denominator);
+}
+
+fn geometry_schlick_ggx(n_dot_x: f32, roughness: f32) -> f32 {
+    let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let sampled_base_color = textureSample(base_color_texture, base_color_sampler, in.tex_coords);
+    let base_color = albedo(in.colors, sampled_base_color);
+    // glTF packs roughness into green and metalness into blue.
+    let metallic_roughness = textureSample(metallic_roughness_texture, metallic_roughness_sampler, in.tex_coords);
+    let metallic = clamp(material.metallic_factor * metallic_roughness.b, 0.0, 1.0);
+    let roughness = clamp(material.roughness_factor * metallic_roughness.g, MIN_ROUGHNESS, 1.0);
+
+    let n = normalize(in.world_normal);
+    let v = normalize(camera.position - in.world_position);
+    let to_light = light.transform.translation - in.world_position;
+    let distance2 = max(dot(to_light, to_light), 0.0001);
+    let l = to_light * inverseSqrt(distance2);
+    let h = normalize(v + l);
+
+    let n_dot_l = max(dot(n, l), 0.0);
+    let n_dot_v = max(dot(n, v), 0.0001);
+    let n_dot_h = max(dot(n, h), 0.0);
+    let h_dot_v = max(dot(h, v), 0.0);
+
+    let f0 = mix(vec3<f32>(0.04), base_color.rgb, metallic);
+    let fresnel = fresnel_schlick(h_dot_v, f0);
+    let geometry = geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness);
+    let specular = distribution_ggx(n_dot_h, roughness) * geometry * fresnel / (4.0 * n_dot_v * max(n_dot_l, 0.0001));
+    let diffuse = (1.0 - fresnel) * (1.0 - metallic) * base_color.rgb / PI;
+
+    let radiance = light.color * LIGHT_INTENSITY / distance2;
+    let color = (diffuse + specular) * radiance * n_dot_l + AMBIENT * base_color.rgb + material.emissive_factor;
+    return vec4<f32>(color, base_color.a);
+}
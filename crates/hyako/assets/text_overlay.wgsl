@@ -0,0 +1,48 @@
+@group(0) @binding(0)
+var atlas_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var atlas_sampler: sampler;
+
+struct GlyphInput {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) uv_min: vec2<f32>,
+    @location(3) uv_max: vec2<f32>,
+    @location(4) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+// Two triangles spanning the cell, from its top-left corner.
+const CORNERS = array<vec2<f32>, 6>(
+    vec2<f32>(0.0, 0.0),
+    vec2<f32>(0.0, 1.0),
+    vec2<f32>(1.0, 1.0),
+    vec2<f32>(0.0, 0.0),
+    vec2<f32>(1.0, 1.0),
+    vec2<f32>(1.0, 0.0),
+);
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    glyph: GlyphInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let corner = CORNERS[vertex_index];
+    out.clip_position = vec4<f32>(glyph.position + glyph.size * corner, 0.0, 1.0);
+    out.uv = mix(glyph.uv_min, glyph.uv_max, corner);
+    out.color = glyph.color;
+    return out;
+}
+
+// The atlas stores coverage in alpha, so the glyph takes its color from the instance.
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(atlas_texture, atlas_sampler, in.uv).a;
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
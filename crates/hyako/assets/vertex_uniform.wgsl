@@ -1,5 +1,9 @@
 struct Camera {
-    view_projection_matrix: mat4x4<f32>
+    view_projection_matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+    inverse_view_projection_matrix: mat4x4<f32>,
 }
 
 struct Model {
@@ -8,6 +12,10 @@ struct Model {
 
 struct Material {
     base_color_factor: vec4<f32>,
+    metallic_factor: f32,
+    roughness_factor: f32,
+    emissive_factor: vec3<f32>,
+    albedo_source: u32,
 }
 
 struct Transform {
@@ -50,6 +58,24 @@ var base_color_texture: texture_2d<f32>;
 @group(3) @binding(2)
 var base_color_sampler: sampler;
 
+const ALBEDO_VERTEX_COLOR: u32 = 0u;
+const ALBEDO_BASE_COLOR_FACTOR: u32 = 1u;
+
+// The albedo source is resolved on the CPU, see `ShadingSource`. Textures are tinted by the factor.
+fn albedo(vertex_color: vec4<f32>, sampled_base_color: vec4<f32>) -> vec4<f32> {
+    switch material.albedo_source {
+        case ALBEDO_VERTEX_COLOR: {
+            return vertex_color;
+        }
+        case ALBEDO_BASE_COLOR_FACTOR: {
+            return material.base_color_factor;
+        }
+        default: {
+            return material.base_color_factor * sampled_base_color;
+        }
+    }
+}
+
 @vertex
 fn vs_main(
     mesh: VertexInput,
@@ -81,6 +107,6 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var specular_intensity = pow(clamp(NdotH, 0.0, 1.0), 2.0);
     var specular = specular_intensity * color * 1.0 / distance;
     let sampled_base_color = textureSample(base_color_texture, base_color_sampler, in.tex_coords);
-    let base_color = in.colors * material.base_color_factor * sampled_base_color;
+    let base_color = albedo(in.colors, sampled_base_color);
     return vec4<f32>(specular, 1.0) + vec4(diffuse, 1.0) * base_color;
 }
@@ -0,0 +1,213 @@
+use std::{path::PathBuf, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use hyakou_core::types::Size;
+use log::LevelFilter;
+
+use crate::headless::HeadlessOptions;
+
+/// Command line of the native binary, e.g.
+/// `hyako model.gltf other.glb --size 1600x900 --log-level info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliArgs {
+    /// glTF files loaded into an otherwise empty scene.
+    pub models: Vec<PathBuf>,
+    /// Scene file replacing the built-in demo scene.
+    pub scene: Option<PathBuf>,
+    /// Window size, or the render size of a headless run.
+    pub size: Option<Size>,
+    pub log_level: LevelFilter,
+    pub headless: bool,
+    pub frames: Option<u32>,
+    /// Where a headless run writes its last frame as PNG.
+    pub output: Option<PathBuf>,
+}
+
+impl Default for CliArgs {
+    fn default() -> Self {
+        Self {
+            models: Vec::new(),
+            scene: None,
+            size: None,
+            log_level: Self::DEFAULT_LOG_LEVEL,
+            headless: false,
+            frames: None,
+            output: None,
+        }
+    }
+}
+
+impl CliArgs {
+    pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Debug;
+    pub const USAGE: &str = "usage: hyako [MODEL.gltf...] [--scene FILE] [--size WIDTHxHEIGHT] [--log-level LEVEL] [--headless [--frames N] [--out FILE.png]]";
+
+    /// Parses `args` without the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| {
+                args.next()
+                    .ok_or_else(|| anyhow!("`{flag}` needs a value\n{}", Self::USAGE))
+            };
+            match arg.as_str() {
+                "--headless" => cli.headless = true,
+                "--frames" => {
+                    let frames = value("--frames")?;
+                    cli.frames = Some(
+                        frames
+                            .parse()
+                            .map_err(|_| anyhow!("`{frames}` is not a frame count"))?,
+                    );
+                }
+                "--size" => cli.size = Some(parse_size(&value("--size")?)?),
+                "--scene" => cli.scene = Some(PathBuf::from(value("--scene")?)),
+                "--out" | "--output" => cli.output = Some(PathBuf::from(value(&arg)?)),
+                "--log-level" => {
+                    let level = value("--log-level")?;
+                    cli.log_level = LevelFilter::from_str(&level).map_err(|_| {
+                        anyhow!(
+                            "`{level}` is not a log level (off, error, warn, info, debug, trace)"
+                        )
+                    })?;
+                }
+                flag if flag.starts_with("--") => {
+                    return Err(anyhow!("Unknown argument `{flag}`\n{}", Self::USAGE));
+                }
+                model => cli.models.push(PathBuf::from(model)),
+            }
+        }
+
+        cli.validate()?;
+        Ok(cli)
+    }
+
+    /// Options for a windowless run, or `None` without `--headless`.
+    pub fn headless_options(&self) -> Option<HeadlessOptions> {
+        let defaults = HeadlessOptions::default();
+        self.headless.then(|| HeadlessOptions {
+            frames: self.frames.unwrap_or(defaults.frames),
+            size: self.size.unwrap_or(defaults.size),
+            models: self.models.clone(),
+            scene: self.scene.clone(),
+            output: self.output.clone(),
+            ..defaults
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.scene.is_some() && !self.models.is_empty() {
+            return Err(anyhow!(
+                "`--scene` replaces the whole scene and cannot be combined with model paths; \
+                 list the models in the scene file instead"
+            ));
+        }
+        if !self.headless {
+            for (flag, given) in [
+                ("--frames", self.frames.is_some()),
+                ("--out", self.output.is_some()),
+            ] {
+                if given {
+                    return Err(anyhow!("`{flag}` only applies together with `--headless`"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_size(value: &str) -> Result<Size> {
+    let parsed = value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+    match parsed {
+        Some((width, height)) if width > 0 && height > 0 => Ok(Size { width, height }),
+        _ => Err(anyhow!("`{value}` is not a size like 1280x720")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(values: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse(values.iter().map(|value| value.to_string()))
+    }
+
+    fn error(values: &[&str]) -> String {
+        parse(values).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_no_arguments_keep_the_defaults() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_positional_arguments_are_models() {
+        let cli = parse(&[
+            "Suzanne.gltf",
+            "--size",
+            "1600x900",
+            "Cube.glb",
+            "--log-level",
+            "info",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli.models,
+            [PathBuf::from("Suzanne.gltf"), PathBuf::from("Cube.glb")]
+        );
+        assert_eq!(
+            cli.size,
+            Some(Size {
+                width: 1600,
+                height: 900
+            })
+        );
+        assert_eq!(cli.log_level, LevelFilter::Info);
+        assert_eq!(cli.headless_options(), None);
+    }
+
+    #[test]
+    fn test_headless_options_take_the_shared_flags() {
+        let cli = parse(&[
+            "--headless",
+            "--scene",
+            "demo.ron",
+            "--frames",
+            "5",
+            "--out",
+            "frame.png",
+        ])
+        .unwrap();
+
+        let options = cli.headless_options().unwrap();
+
+        assert_eq!(options.frames, 5);
+        assert_eq!(options.scene, Some(PathBuf::from("demo.ron")));
+        assert_eq!(options.output, Some(PathBuf::from("frame.png")));
+        assert_eq!(options.size, HeadlessOptions::default().size);
+    }
+
+    #[test]
+    fn test_scene_and_models_conflict() {
+        assert!(error(&["--scene", "demo.ron", "Cube.gltf"]).contains("cannot be combined"));
+    }
+
+    #[test]
+    fn test_headless_only_flags_need_headless() {
+        assert!(error(&["--frames", "3"]).contains("--headless"));
+        assert!(error(&["--out", "frame.png"]).contains("--headless"));
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        assert!(error(&["--size", "wide"]).contains("not a size"));
+        assert!(error(&["--log-level", "loud"]).contains("not a log level"));
+        assert!(error(&["--scene"]).contains("needs a value"));
+        assert!(error(&["--fast"]).contains("Unknown argument"));
+    }
+}
@@ -1,37 +1,56 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
 use hyakou_core::{
-    Shared, SharedAccess, components::LightType, types::import_diagnostic::ImportDiagnostic,
+    Shared, SharedAccess,
+    components::LightType,
+    events::AssetReply,
+    types::{asset_status::AssetStatusEvent, import_diagnostic::ImportDiagnostic},
 };
 use log::{debug, error, warn};
 
 use crate::{
     flow::{FlowCommandSender, RendererCommand},
-    gpu::glTF::ImportedScene,
-    renderer::SceneRenderer,
+    gpu::{glTF::ImportedScene, render_mesh::MeshUsage},
+    renderer::{SceneRenderer, handlers::file_drop::asset_id_from_path},
 };
 
+#[cfg(target_arch = "wasm32")]
+use crate::flow::ListenerRegistry;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_futures::spawn_local;
 
 pub struct AssetUploadController {
     commands: FlowCommandSender,
+    pending_replies: HashMap<String, AssetReply>,
     #[cfg(target_arch = "wasm32")]
     upload_status_callback: Shared<Option<js_sys::Function>>,
+    #[cfg(target_arch = "wasm32")]
+    asset_status_listeners: Shared<ListenerRegistry<js_sys::Function>>,
 }
 
 impl AssetUploadController {
     #[cfg(not(target_arch = "wasm32"))]
     pub fn new(commands: FlowCommandSender) -> Self {
-        Self { commands }
+        Self {
+            commands,
+            pending_replies: HashMap::new(),
+        }
     }
 
     #[cfg(target_arch = "wasm32")]
     pub fn new(
         commands: FlowCommandSender,
         upload_status_callback: Shared<Option<js_sys::Function>>,
+        asset_status_listeners: Shared<ListenerRegistry<js_sys::Function>>,
     ) -> Self {
         Self {
             commands,
+            pending_replies: HashMap::new(),
             upload_status_callback,
+            asset_status_listeners,
         }
     }
 
@@ -42,6 +61,8 @@ impl AssetUploadController {
         asset_type: LightType,
         bytes: Vec<u8>,
     ) {
+        self.fire_asset_status(AssetStatusEvent::loading(id.clone()));
+
         #[cfg(not(target_arch = "wasm32"))]
         {
             use crate::gpu::glTF::GLTFLoader;
@@ -62,7 +83,7 @@ impl AssetUploadController {
                     self.send_command(RendererCommand::AssetUploadFailed {
                         id,
                         file_name,
-                        error: upload_error.to_string(),
+                        error: format!("{upload_error:#}"),
                     });
                 }
             }
@@ -71,11 +92,26 @@ impl AssetUploadController {
         #[cfg(target_arch = "wasm32")]
         {
             let commands = self.commands.clone();
+            let listeners = self.asset_status_listeners.clone();
             spawn_local(async move {
                 use crate::gpu::glTF::GLTFLoader;
                 let gltf_loader = GLTFLoader::new();
+                let progress_id = id.clone();
                 let parsed_node_graph = gltf_loader
-                    .load_from_bytes_with_label(bytes, file_name.clone())
+                    .load_from_bytes_with_progress(
+                        bytes,
+                        file_name.clone(),
+                        &mut |stage, fraction| {
+                            notify_asset_status(
+                                &listeners,
+                                AssetStatusEvent::progress(
+                                    progress_id.clone(),
+                                    stage.as_str(),
+                                    fraction,
+                                ),
+                            );
+                        },
+                    )
                     .await;
                 let next_command = match parsed_node_graph {
                     Ok(node_graph) => RendererCommand::ApplyParsedAsset {
@@ -87,7 +123,7 @@ impl AssetUploadController {
                     Err(upload_error) => RendererCommand::AssetUploadFailed {
                         id,
                         file_name,
-                        error: upload_error.to_string(),
+                        error: format!("{upload_error:#}"),
                     },
                 };
 
@@ -98,6 +134,40 @@ impl AssetUploadController {
         }
     }
 
+    /// Uploads `bytes` under an id derived from `name` and answers `reply` once the asset was
+    /// applied or failed to import.
+    pub fn handle_add_asset_requested(
+        &mut self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        name: String,
+        asset_type: LightType,
+        bytes: Vec<u8>,
+        reply: AssetReply,
+    ) {
+        let pending_replies = &self.pending_replies;
+        let id = renderer_slot
+            .try_read_shared(|renderer_slot| {
+                renderer_slot.as_ref().map(|renderer| {
+                    asset_id_from_path(Path::new(&name), |mesh_id| {
+                        renderer.asset_manager.contains(mesh_id)
+                            || pending_replies
+                                .keys()
+                                .any(|pending_id| format!("{pending_id}_0") == mesh_id)
+                    })
+                })
+            })
+            .ok()
+            .flatten();
+
+        let id = match id {
+            Some(Ok(id)) => id,
+            Some(Err(id_error)) => return reply(Err(id_error.to_string())),
+            None => return reply(Err("Renderer is not ready yet".to_string())),
+        };
+        self.pending_replies.insert(id.clone(), reply);
+        self.handle_asset_upload_requested(id, name, asset_type, bytes);
+    }
+
     pub fn handle_asset_bundle_upload_requested(
         &self,
         id: String,
@@ -105,6 +175,8 @@ impl AssetUploadController {
         asset_type: LightType,
         files: Vec<(String, Vec<u8>)>,
     ) {
+        self.fire_asset_status(AssetStatusEvent::loading(id.clone()));
+
         #[cfg(not(target_arch = "wasm32"))]
         {
             use crate::gpu::glTF::GLTFLoader;
@@ -124,7 +196,7 @@ impl AssetUploadController {
                     self.send_command(RendererCommand::AssetUploadFailed {
                         id,
                         file_name,
-                        error: upload_error.to_string(),
+                        error: format!("{upload_error:#}"),
                     });
                 }
             }
@@ -147,7 +219,7 @@ impl AssetUploadController {
                     Err(upload_error) => RendererCommand::AssetUploadFailed {
                         id,
                         file_name,
-                        error: upload_error.to_string(),
+                        error: format!("{upload_error:#}"),
                     },
                 };
 
@@ -159,7 +231,7 @@ impl AssetUploadController {
     }
 
     pub fn handle_apply_parsed_asset(
-        &self,
+        &mut self,
         renderer_slot: &Shared<Option<SceneRenderer>>,
         id: String,
         file_name: String,
@@ -169,28 +241,100 @@ impl AssetUploadController {
         let upload_id = id.clone();
         let upload_file_name = file_name.clone();
         let diagnostics = imported_scene.diagnostics.clone();
-        let success = renderer_slot
+        let not_ready = || format!("Renderer was not ready to apply `{file_name}`");
+        let uploaded_ids = renderer_slot
             .try_write_shared(|renderer_slot| {
                 let Some(renderer) = renderer_slot.as_mut() else {
                     warn!("Dropping parsed asset `{id}` because renderer is not ready");
-                    return false;
+                    return Err(not_ready());
                 };
 
-                renderer
+                let meshes = renderer
                     .asset_manager
-                    .upload_imported_scene(id, asset_type, imported_scene);
-                true
+                    .upload_imported_scene(id, asset_type, MeshUsage::Static, imported_scene)
+                    .map_err(|upload_error| {
+                        format!("Failed to apply `{file_name}`: {upload_error}")
+                    })?;
+                let mut uploaded_ids: Vec<String> =
+                    meshes.iter().map(|mesh| mesh.id.0.clone()).collect();
+                uploaded_ids.sort();
+                Ok(uploaded_ids)
             })
-            .unwrap_or(false);
+            .unwrap_or_else(|_| Err(not_ready()));
 
-        if success {
-            debug!("Successfully loaded asset: {file_name}");
-            self.fire_upload_status_success(upload_id, upload_file_name, diagnostics);
+        let reply = self.pending_replies.remove(&upload_id);
+        match uploaded_ids {
+            Ok(uploaded_ids) => {
+                debug!("Successfully loaded asset: {file_name}");
+                self.fire_asset_status(AssetStatusEvent::ready(upload_id.clone()));
+                self.fire_upload_status_success(upload_id, upload_file_name, diagnostics);
+                if let Some(reply) = reply {
+                    reply(Ok(uploaded_ids));
+                }
+            }
+            Err(message) => {
+                self.fire_asset_status(AssetStatusEvent::failed(upload_id, message.clone()));
+                if let Some(reply) = reply {
+                    reply(Err(message));
+                }
+            }
         }
     }
 
-    pub fn handle_asset_upload_failed(&self, id: String, file_name: String, error: String) {
+    /// Imports a file dropped onto the window. Each file of a multi-file drop arrives as its own
+    /// command, so one failing import does not affect the others.
+    pub fn handle_file_dropped(
+        &self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        path: PathBuf,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use crate::renderer::handlers::file_drop::import_dropped_file;
+
+            let _ = renderer_slot.try_write_shared(|renderer_slot| {
+                let Some(renderer) = renderer_slot.as_mut() else {
+                    warn!(
+                        "Dropping `{}` because renderer is not ready",
+                        path.display()
+                    );
+                    return;
+                };
+
+                match pollster::block_on(import_dropped_file(
+                    &mut renderer.asset_manager,
+                    &renderer.camera,
+                    &path,
+                )) {
+                    Ok(mesh_ids) => {
+                        debug!("Imported dropped file {}: {mesh_ids:?}", path.display())
+                    }
+                    Err(import_error) => {
+                        error!(
+                            "Failed to import dropped file {}: {import_error:#}",
+                            path.display()
+                        )
+                    }
+                }
+            });
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = renderer_slot;
+            warn!(
+                "Ignoring dropped file {}: use the upload API on the web",
+                path.display()
+            );
+        }
+    }
+
+    pub fn handle_asset_upload_failed(&mut self, id: String, file_name: String, error: String) {
         error!("Asset upload failed for `{id}` ({file_name}): {error}");
+        if let Some(reply) = self.pending_replies.remove(&id) {
+            reply(Err(error.clone()));
+        }
+        self.fire_asset_status(AssetStatusEvent::failed(id.clone(), error.clone()));
         self.fire_upload_status_error(id, file_name, error);
     }
 
@@ -246,4 +390,30 @@ impl AssetUploadController {
 
     #[cfg(not(target_arch = "wasm32"))]
     fn fire_upload_status_error(&self, _upload_id: String, _file_name: String, _error: String) {}
+
+    #[cfg(target_arch = "wasm32")]
+    fn fire_asset_status(&self, event: AssetStatusEvent) {
+        notify_asset_status(&self.asset_status_listeners, event);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fire_asset_status(&self, _event: AssetStatusEvent) {}
+}
+
+#[cfg(target_arch = "wasm32")]
+fn notify_asset_status(
+    listeners: &Shared<ListenerRegistry<js_sys::Function>>,
+    event: AssetStatusEvent,
+) {
+    use wasm_bindgen::JsValue;
+
+    // Copied out first so listeners may register or dispose listeners while being called.
+    let listeners: Vec<js_sys::Function> = listeners
+        .try_read_shared(|listeners| listeners.iter().cloned().collect())
+        .unwrap_or_default();
+    for listener in listeners {
+        if let Err(err) = listener.call1(&JsValue::NULL, &event.clone().into()) {
+            warn!("Failed to invoke asset status listener: {err:?}");
+        }
+    }
 }
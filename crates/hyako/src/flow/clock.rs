@@ -0,0 +1,69 @@
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+use hyakou_core::{Shared, SharedAccess, shared};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Source of the current time for frame deltas and frame pacing. `std::time::Instant` panics on
+/// wasm32-unknown-unknown, so the web build reads `web_time::Instant` instead.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when advanced. Clones share the same time, so a test can keep one
+/// and hand the other to the code under test.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Shared<Instant>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            now: shared(Instant::now()),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.write_shared(|now| *now += duration);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.read_shared(|now| *now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_clones_share_time() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+        let start = clock.now();
+
+        handle.advance(Duration::from_millis(16));
+
+        assert_eq!(clock.now().duration_since(start), Duration::from_millis(16));
+    }
+}
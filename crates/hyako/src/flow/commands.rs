@@ -1,23 +1,79 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
-use crate::gpu::glTF::ImportedScene;
+use crate::{
+    gpu::glTF::ImportedScene,
+    renderer::{
+        handlers::pointer_handler::PointerKind, settings::RendererSettings,
+        util::asset_root::AssetRoot,
+    },
+};
+use glam::Vec3;
 use hyakou_core::{
     components::{LightType, camera::data_structures::CameraAnimationRequest},
+    events::AssetReply,
     types::mouse_delta::MouseButton,
 };
-use winit::{keyboard::KeyCode, window::Window};
+use winit::{
+    event::TouchPhase,
+    keyboard::KeyCode,
+    window::{Window, WindowId},
+};
 
 pub enum RendererCommand {
-    WindowCreated(Arc<Window>),
+    WindowCreated {
+        window: Arc<Window>,
+        transparent: bool,
+        settings: RendererSettings,
+        asset_root: AssetRoot,
+    },
+    /// The app was suspended; the surface has to be released.
+    Suspended,
+    /// The app resumed with an existing window; the surface has to be recreated.
+    Resumed,
+    /// The GPU device was lost; device, surface and every GPU resource have to be rebuilt.
+    RecoverDevice,
+    ViewCreated {
+        window: Arc<Window>,
+    },
+    ViewClosed {
+        window_id: WindowId,
+    },
+    ViewResized {
+        window_id: WindowId,
+        width: f64,
+        height: f64,
+    },
+    RedrawView {
+        window_id: WindowId,
+    },
     AnimateCamera(CameraAnimationRequest),
     StopCameraAnimation,
+    SetCamera {
+        eye: Vec3,
+        target: Vec3,
+    },
+    SetCameraFov {
+        degrees: f32,
+    },
+    FocusAsset(String),
+    SelectAsset(String),
+    ClearSelection,
     CursorInWindow {
         is_inside: bool,
     },
+    /// Physical pixels, the same space as the surface size.
     CursorMoved {
         x: f64,
         y: f64,
     },
+    /// A finger or pen contact, in physical pixels.
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+        kind: PointerKind,
+    },
     KeyboardInput {
         key: KeyCode,
         pressed: bool,
@@ -37,6 +93,14 @@ pub enum RendererCommand {
         asset_type: LightType,
         bytes: Vec<u8>,
     },
+    /// Like [`Self::AssetUploadRequested`], with the asset id derived from `name` and the outcome
+    /// reported through `reply`.
+    AddAssetRequested {
+        name: String,
+        asset_type: LightType,
+        bytes: Vec<u8>,
+        reply: AssetReply,
+    },
     AssetBundleUploadRequested {
         id: String,
         file_name: String,
@@ -49,17 +113,36 @@ pub enum RendererCommand {
         asset_type: LightType,
         imported_scene: ImportedScene,
     },
+    FileDropped {
+        path: PathBuf,
+    },
+    /// Applies the runtime part of new settings; restart-only fields are reported and ignored.
+    ApplySettings(RendererSettings),
+    /// Replaces the scene with the given glTF files.
+    LoadModels {
+        paths: Vec<PathBuf>,
+    },
+    /// Replaces the scene with the one described by a scene file.
+    LoadSceneFile {
+        path: PathBuf,
+    },
     AssetUploadFailed {
         id: String,
         file_name: String,
         error: String,
     },
-    Redraw {
+    /// One fixed-length simulation step.
+    Update {
         dt: f64,
     },
+    Redraw {
+        interpolation_alpha: f64,
+    },
     Resize {
-        dt: f64,
         height: f64,
         width: f64,
     },
+    ScaleFactorChanged {
+        scale_factor: f64,
+    },
 }
@@ -0,0 +1,122 @@
+use hyakou_core::types::DeltaTime64;
+
+/// Simulation steps owed for one rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedSteps {
+    pub steps: u32,
+    /// How far the leftover time reaches into the next step, in `0.0..1.0`. Meant for
+    /// interpolating between the last two simulation states when rendering.
+    pub alpha: f64,
+}
+
+/// Accumulates real frame time and hands it out as whole simulation steps of a fixed length.
+#[derive(Debug)]
+pub struct FixedTimestep {
+    step_seconds: DeltaTime64,
+    accumulator: DeltaTime64,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_RATE_HZ)
+    }
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_RATE_HZ: f64 = 60.0;
+
+    pub fn new(rate_hz: f64) -> Self {
+        let mut timestep = Self {
+            step_seconds: 1.0 / Self::DEFAULT_RATE_HZ,
+            accumulator: 0.0,
+        };
+        timestep.set_rate(rate_hz);
+        timestep
+    }
+
+    pub fn step_seconds(&self) -> DeltaTime64 {
+        self.step_seconds
+    }
+
+    /// Non-positive or non-finite rates are ignored.
+    pub fn set_rate(&mut self, rate_hz: f64) {
+        if rate_hz > 0.0 && rate_hz.is_finite() {
+            self.step_seconds = 1.0 / rate_hz;
+        }
+    }
+
+    /// Adds a frame's worth of time. The caller is expected to clamp `frame_seconds` first, which
+    /// also bounds how many steps a single slow frame can owe.
+    pub fn advance(&mut self, frame_seconds: DeltaTime64) -> FixedSteps {
+        self.accumulator += frame_seconds.max(0.0);
+
+        let mut steps = 0;
+        while self.accumulator >= self.step_seconds {
+            self.accumulator -= self.step_seconds;
+            steps += 1;
+        }
+
+        FixedSteps {
+            steps,
+            alpha: self.accumulator / self.step_seconds,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn test_irregular_frames_produce_whole_steps_and_carry_remainder() {
+        let mut timestep = FixedTimestep::new(50.0);
+
+        let step_counts: Vec<u32> = [0.005, 0.031, 0.006, 0.05, 0.012]
+            .into_iter()
+            .map(|frame_seconds| timestep.advance(frame_seconds).steps)
+            .collect();
+
+        // Running totals 5, 36, 42, 92, 104 ms against 20 ms steps.
+        assert_eq!(step_counts, vec![0, 1, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_alpha_reports_fraction_of_next_step() {
+        let mut timestep = FixedTimestep::new(100.0);
+
+        let fixed_steps = timestep.advance(0.025);
+
+        assert_eq!(fixed_steps.steps, 2);
+        assert!((fixed_steps.alpha - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_frames_faster_than_simulation_rate_skip_updates() {
+        let mut timestep = FixedTimestep::default();
+        let frame_seconds = 1.0 / 144.0;
+
+        let total_steps: u32 = (0..144)
+            .map(|_| timestep.advance(frame_seconds).steps)
+            .sum();
+
+        // One second of 144 Hz frames still simulates at 60 Hz, give or take rounding.
+        assert!((59..=60).contains(&total_steps));
+    }
+
+    #[test]
+    fn test_invalid_rate_is_ignored_and_reset_clears_remainder() {
+        let mut timestep = FixedTimestep::new(0.0);
+        assert!((timestep.step_seconds() - 1.0 / FixedTimestep::DEFAULT_RATE_HZ).abs() < EPSILON);
+
+        timestep.advance(0.01);
+        timestep.reset();
+
+        assert_eq!(timestep.advance(0.0).alpha, 0.0);
+    }
+}
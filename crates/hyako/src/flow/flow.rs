@@ -1,14 +1,19 @@
 use std::sync::mpsc::{Receiver, channel};
 
 use hyakou_core::Shared;
+#[cfg(target_arch = "wasm32")]
+use hyakou_core::SharedAccess;
 use log::{debug, warn};
+use winit::dpi::PhysicalPosition;
 
+#[cfg(target_arch = "wasm32")]
+use crate::{flow::ListenerRegistry, renderer::handlers::selection::SelectionEvent};
 use crate::{
     flow::{
-        AssetUploadController, FlowCommandSender, FrameComposer, InputController, RenderController,
-        RendererCommand,
+        AssetUploadController, FlowCommandSender, FrameComposer, InputController, PendingInput,
+        RenderController, RendererCommand,
     },
-    renderer::SceneRenderer,
+    renderer::{SceneRenderer, actions::AppActions},
 };
 
 pub struct FlowController {
@@ -17,6 +22,11 @@ pub struct FlowController {
     frame_composer: FrameComposer,
     input_controller: InputController,
     asset_upload_controller: AssetUploadController,
+    pending_input: PendingInput,
+    exit_requested: bool,
+    window_requested: bool,
+    #[cfg(target_arch = "wasm32")]
+    selection_listeners: Shared<ListenerRegistry<js_sys::Function>>,
 }
 
 #[derive(Clone)]
@@ -37,6 +47,9 @@ impl FlowController {
             frame_composer: FrameComposer::new(),
             input_controller: InputController::new(commands.clone()),
             asset_upload_controller: AssetUploadController::new(commands.clone()),
+            pending_input: PendingInput::new(),
+            exit_requested: false,
+            window_requested: false,
         };
 
         (controller, FlowHandle::new(commands))
@@ -45,6 +58,8 @@ impl FlowController {
     #[cfg(target_arch = "wasm32")]
     pub fn new_pair(
         upload_status_callback: Shared<Option<js_sys::Function>>,
+        asset_status_listeners: Shared<ListenerRegistry<js_sys::Function>>,
+        selection_listeners: Shared<ListenerRegistry<js_sys::Function>>,
     ) -> (Self, FlowHandle) {
         let (tx, rx) = channel::<RendererCommand>();
         let commands = FlowCommandSender::new(tx);
@@ -56,7 +71,12 @@ impl FlowController {
             asset_upload_controller: AssetUploadController::new(
                 commands.clone(),
                 upload_status_callback,
+                asset_status_listeners,
             ),
+            pending_input: PendingInput::new(),
+            exit_requested: false,
+            window_requested: false,
+            selection_listeners,
         };
 
         (controller, FlowHandle::new(commands))
@@ -69,7 +89,41 @@ impl FlowController {
         self.render_controller.handle_egui_window_event(event)
     }
 
+    pub fn egui_wants_pointer_input(&self) -> bool {
+        self.render_controller.egui_wants_pointer_input()
+    }
+
+    pub fn take_exit_request(&mut self) -> bool {
+        std::mem::take(&mut self.exit_requested)
+    }
+
+    /// Whether an additional window was requested; only the app state can create windows.
+    pub fn take_window_request(&mut self) -> bool {
+        std::mem::take(&mut self.window_requested)
+    }
+
+    /// Whether the GPU device was lost since the last call; the app state answers with
+    /// [`RendererCommand::RecoverDevice`].
+    pub fn take_device_lost(&mut self) -> bool {
+        self.render_controller.take_device_lost()
+    }
+
+    /// Releases GPU resources while the window is still alive.
+    pub fn shutdown(&mut self) {
+        self.render_controller.shutdown();
+    }
+
+    pub fn pending_input_len(&self) -> usize {
+        self.pending_input.len()
+    }
+
     pub fn drain_commands(&mut self) {
+        self.replay_pending_input();
+        self.handle_queued_commands();
+        self.publish_selection_events();
+    }
+
+    fn handle_queued_commands(&mut self) {
         for _ in 0..Self::MAX_COMMANDS_PER_TICK {
             let command = match self.rx.try_recv() {
                 Ok(command) => command,
@@ -84,25 +138,134 @@ impl FlowController {
         );
     }
 
+    /// Reports selection changes from any source (clicks, shortcuts, the JS API) once all queued
+    /// commands ran. Selecting what is already selected queues no event, so a listener that
+    /// selects in response cannot loop.
+    fn publish_selection_events(&mut self) {
+        for selection_event in self.render_controller.take_selection_events() {
+            debug!("Selection event: {selection_event:?}");
+            #[cfg(target_arch = "wasm32")]
+            self.notify_selection_listeners(&selection_event);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn notify_selection_listeners(&self, selection_event: &SelectionEvent) {
+        use wasm_bindgen::JsValue;
+
+        let selected = match selection_event {
+            SelectionEvent::Changed { primary, .. } => JsValue::from_str(primary),
+            SelectionEvent::Cleared => JsValue::NULL,
+        };
+        let listeners: Vec<js_sys::Function> = self
+            .selection_listeners
+            .try_read_shared(|listeners| listeners.iter().cloned().collect())
+            .unwrap_or_default();
+        for listener in listeners {
+            if let Err(err) = listener.call1(&JsValue::NULL, &selected) {
+                warn!("Failed to invoke selection listener: {err:?}");
+            }
+        }
+    }
+
+    fn apply_app_actions(&mut self) {
+        for action in self.input_controller.take_app_actions() {
+            match action {
+                AppActions::Quit => self.exit_requested = true,
+                AppActions::ToggleFullscreen => self.render_controller.toggle_fullscreen(),
+                AppActions::OpenWindow => self.window_requested = true,
+                AppActions::Screenshot => self.render_controller.capture_screenshot(),
+                // Applied to the renderer directly; without one there is nothing to show.
+                AppActions::ToggleStatsOverlay => {}
+            }
+        }
+    }
+
+    fn replay_pending_input(&mut self) {
+        if self.pending_input.is_empty() || !self.render_controller.has_renderer() {
+            return;
+        }
+
+        for command in self.pending_input.take() {
+            self.handle_command(command);
+        }
+    }
+
     fn handle_command(&mut self, command: RendererCommand) {
+        if PendingInput::should_buffer(&command) && !self.render_controller.has_renderer() {
+            self.pending_input.push(command);
+            return;
+        }
+
         match command {
-            RendererCommand::WindowCreated(window) => {
-                self.render_controller.handle_window_created(window)
+            RendererCommand::WindowCreated {
+                window,
+                transparent,
+                settings,
+                asset_root,
+            } => self.render_controller.handle_window_created(
+                window,
+                transparent,
+                settings,
+                asset_root,
+            ),
+            RendererCommand::ViewCreated { window } => self.render_controller.add_view(window),
+            RendererCommand::ViewClosed { window_id } => {
+                self.render_controller.close_view(window_id)
+            }
+            RendererCommand::ViewResized {
+                window_id,
+                width,
+                height,
+            } => self.render_controller.resize_view(window_id, width, height),
+            RendererCommand::RedrawView { window_id } => {
+                self.render_controller.render_view(window_id)
             }
+            RendererCommand::Suspended => self.render_controller.suspend(),
+            RendererCommand::Resumed => self.render_controller.resume(),
+            RendererCommand::RecoverDevice => self.render_controller.recover_device(),
             RendererCommand::AnimateCamera(request) => {
                 self.render_controller.animate_camera(request)
             }
             RendererCommand::StopCameraAnimation => self.render_controller.stop_camera_animation(),
+            RendererCommand::SetCamera { eye, target } => {
+                self.render_controller.set_camera(eye, target)
+            }
+            RendererCommand::SetCameraFov { degrees } => {
+                self.render_controller.set_camera_fov(degrees)
+            }
+            RendererCommand::FocusAsset(id) => self.render_controller.focus_asset(&id),
+            RendererCommand::SelectAsset(id) => self.render_controller.select_asset(id),
+            RendererCommand::ClearSelection => self.render_controller.clear_selection(),
             RendererCommand::CursorInWindow { is_inside } => {
                 self.input_controller.handle_cursor_in_window(is_inside)
             }
             RendererCommand::CursorMoved { x, y } => {
-                self.input_controller.handle_cursor_moved(x, y);
+                let renderer = self.render_controller.renderer();
+                self.input_controller.handle_cursor_moved(&renderer, x, y);
+            }
+            RendererCommand::Touch {
+                id,
+                phase,
+                x,
+                y,
+                kind,
+            } => {
+                let renderer = self.render_controller.renderer();
+                self.input_controller.handle_touch(
+                    &renderer,
+                    self.render_controller.window(),
+                    id,
+                    phase,
+                    PhysicalPosition::new(x, y),
+                    kind,
+                );
             }
             RendererCommand::KeyboardInput { key, pressed } => {
                 let renderer = self.render_controller.renderer();
                 self.input_controller
                     .handle_keyboard_input(&renderer, key, pressed);
+                self.apply_app_actions();
             }
             RendererCommand::MouseMotion { dx, dy, dt } => {
                 let renderer = self.render_controller.renderer();
@@ -126,6 +289,18 @@ impl FlowController {
             } => self
                 .asset_upload_controller
                 .handle_asset_upload_requested(id, file_name, asset_type, bytes),
+            RendererCommand::AddAssetRequested {
+                name,
+                asset_type,
+                bytes,
+                reply,
+            } => self.asset_upload_controller.handle_add_asset_requested(
+                &self.render_controller.renderer(),
+                name,
+                asset_type,
+                bytes,
+                reply,
+            ),
             RendererCommand::AssetBundleUploadRequested {
                 id,
                 file_name,
@@ -146,6 +321,16 @@ impl FlowController {
                 asset_type,
                 imported_scene,
             ),
+            RendererCommand::FileDropped { path } => self
+                .asset_upload_controller
+                .handle_file_dropped(&self.render_controller.renderer(), path),
+            RendererCommand::ApplySettings(settings) => {
+                self.render_controller.apply_settings(&settings)
+            }
+            RendererCommand::LoadModels { paths } => self.render_controller.load_models(&paths),
+            RendererCommand::LoadSceneFile { path } => {
+                self.render_controller.load_scene_file(&path)
+            }
             RendererCommand::AssetUploadFailed {
                 id,
                 file_name,
@@ -153,13 +338,17 @@ impl FlowController {
             } => self
                 .asset_upload_controller
                 .handle_asset_upload_failed(id, file_name, error),
-            RendererCommand::Redraw { dt } => self
+            RendererCommand::Update { dt } => self.render_controller.update(dt),
+            RendererCommand::Redraw {
+                interpolation_alpha,
+            } => self
                 .render_controller
-                .render_frame(&mut self.frame_composer, dt),
-            RendererCommand::Resize { dt, width, height } => {
+                .render_frame(&mut self.frame_composer, interpolation_alpha),
+            RendererCommand::Resize { width, height } => {
                 self.render_controller.handle_resize(width, height);
-                self.render_controller
-                    .render_frame(&mut self.frame_composer, dt);
+            }
+            RendererCommand::ScaleFactorChanged { scale_factor } => {
+                self.render_controller.set_scale_factor(scale_factor)
             }
         }
     }
@@ -176,3 +365,72 @@ impl FlowHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::{SharedAccess, types::mouse_delta::MouseButton};
+    use winit::keyboard::KeyCode;
+
+    use super::*;
+    use crate::renderer::test_harness::RenderHarness;
+
+    #[test]
+    fn test_input_before_renderer_is_buffered_without_panicking() {
+        let (mut flow_controller, flow_handle) = FlowController::new_pair();
+
+        flow_handle.send(RendererCommand::CursorMoved { x: 10.0, y: 20.0 });
+        flow_handle.send(RendererCommand::KeyboardInput {
+            key: KeyCode::KeyW,
+            pressed: true,
+        });
+        flow_handle.send(RendererCommand::MouseButton {
+            button: MouseButton::Left,
+            pressed: true,
+        });
+        flow_controller.drain_commands();
+
+        assert_eq!(flow_controller.pending_input_len(), 2);
+
+        // Still no renderer: a later drain keeps the buffer instead of dropping it.
+        flow_controller.drain_commands();
+        assert_eq!(flow_controller.pending_input_len(), 2);
+    }
+
+    #[test]
+    fn test_buffered_input_reaches_the_input_controller_once_the_renderer_exists() {
+        let (mut flow_controller, flow_handle) = FlowController::new_pair();
+        flow_handle.send(RendererCommand::KeyboardInput {
+            key: KeyCode::KeyW,
+            pressed: true,
+        });
+        flow_handle.send(RendererCommand::MouseButton {
+            button: MouseButton::Left,
+            pressed: true,
+        });
+        flow_controller.drain_commands();
+        assert!(
+            !flow_controller
+                .input_controller
+                .keyboard_handler_mut()
+                .is_pressed(KeyCode::KeyW)
+        );
+
+        let Some(harness) = RenderHarness::new(
+            "test_buffered_input_reaches_the_input_controller_once_the_renderer_exists",
+        ) else {
+            return;
+        };
+        flow_controller
+            .get_renderer()
+            .write_shared(|renderer_slot| *renderer_slot = Some(harness.renderer));
+        flow_controller.drain_commands();
+
+        assert_eq!(flow_controller.pending_input_len(), 0);
+        assert!(
+            flow_controller
+                .input_controller
+                .keyboard_handler_mut()
+                .is_pressed(KeyCode::KeyW)
+        );
+    }
+}
@@ -1,30 +1,42 @@
-use crate::{
-    gui::{EguiRenderer, panels::camera_panel::CameraPanel},
-    renderer::SceneRenderer,
-    renderer::frame::FrameTarget,
+#[cfg(feature = "egui")]
+use crate::gui::{
+    EguiRenderer,
+    panels::{camera_panel::CameraPanel, scene_panel::ScenePanel},
 };
+use crate::{renderer::SceneRenderer, renderer::frame::FrameTarget};
 
 pub struct FrameComposer {
+    #[cfg(feature = "egui")]
     camera_panel: CameraPanel,
+    #[cfg(feature = "egui")]
+    scene_panel: ScenePanel,
 }
 
 impl FrameComposer {
     pub fn new() -> Self {
         Self {
+            #[cfg(feature = "egui")]
             camera_panel: CameraPanel::new(2.0),
+            #[cfg(feature = "egui")]
+            scene_panel: ScenePanel::new(),
         }
     }
 
+    /// Renders the scene, then egui on top of it into the same encoder. Panel edits reach the
+    /// scene on the next frame.
     pub fn compose_frame(
         &mut self,
         target: &mut FrameTarget<'_>,
         renderer: &mut SceneRenderer,
-        mut egui_renderer: Option<&mut EguiRenderer>,
+        #[cfg(feature = "egui")] mut egui_renderer: Option<&mut EguiRenderer>,
+        interpolation_alpha: f64,
     ) {
-        renderer.render_scene(target);
+        renderer.render_scene(target, interpolation_alpha);
+        #[cfg(feature = "egui")]
         if let Some(egui_renderer) = egui_renderer.as_mut() {
             egui_renderer.render(target, |ui| {
                 self.camera_panel.show(ui.ctx());
+                self.scene_panel.show(ui.ctx(), renderer);
             });
         }
     }
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedrawDecision {
+    /// Request a redraw now.
+    Redraw,
+    /// Sleep until the given instant, then check again.
+    WaitUntil(Instant),
+    /// Stop redrawing until a window event wakes the loop.
+    Wait,
+}
+
+/// Decides how often frames are requested based on window visibility and focus.
+#[derive(Debug)]
+pub struct FramePacing {
+    suspended: bool,
+    occluded: bool,
+    minimized: bool,
+    focused: bool,
+    low_power_when_unfocused: bool,
+    unfocused_frame_interval: Duration,
+    last_redraw: Option<Instant>,
+    resumed: bool,
+    coalesce_redraws: bool,
+    redraw_in_flight: bool,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FramePacing {
+    pub const DEFAULT_UNFOCUSED_FPS: f64 = 10.0;
+
+    pub fn new() -> Self {
+        Self {
+            suspended: false,
+            occluded: false,
+            minimized: false,
+            focused: true,
+            low_power_when_unfocused: true,
+            unfocused_frame_interval: Duration::from_secs_f64(1.0 / Self::DEFAULT_UNFOCUSED_FPS),
+            last_redraw: None,
+            resumed: false,
+            coalesce_redraws: false,
+            redraw_in_flight: false,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.suspended || self.occluded || self.minimized
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_suspended(&mut self, suspended: bool) {
+        self.update_visibility(|pacing| pacing.suspended = suspended);
+    }
+
+    pub fn set_occluded(&mut self, occluded: bool) {
+        self.update_visibility(|pacing| pacing.occluded = occluded);
+    }
+
+    /// A zero-area resize is how most platforms report minimizing.
+    pub fn set_minimized(&mut self, minimized: bool) {
+        self.update_visibility(|pacing| pacing.minimized = minimized);
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    pub fn set_low_power_when_unfocused(&mut self, enabled: bool) {
+        self.low_power_when_unfocused = enabled;
+    }
+
+    pub fn set_unfocused_fps(&mut self, fps: f64) {
+        if fps > 0.0 && fps.is_finite() {
+            self.unfocused_frame_interval = Duration::from_secs_f64(1.0 / fps);
+        }
+    }
+
+    /// Holds back further redraws until the requested one was rendered, so a loop that wakes
+    /// several times per frame (like the browser's, paced by requestAnimationFrame) still runs
+    /// exactly one update and render per frame.
+    pub fn set_coalesce_redraws(&mut self, enabled: bool) {
+        self.coalesce_redraws = enabled;
+        self.redraw_in_flight = false;
+    }
+
+    pub fn frame_rendered(&mut self) {
+        self.redraw_in_flight = false;
+    }
+
+    /// Returns true once after rendering resumes from a pause, so the caller can restart its
+    /// frame clock instead of feeding the whole pause into the next delta.
+    pub fn take_resumed(&mut self) -> bool {
+        std::mem::take(&mut self.resumed)
+    }
+
+    pub fn poll(&mut self, now: Instant) -> RedrawDecision {
+        if self.is_paused() || self.redraw_in_flight {
+            return RedrawDecision::Wait;
+        }
+
+        let throttled = !self.focused && self.low_power_when_unfocused;
+        if let Some(next_redraw) = self
+            .last_redraw
+            .filter(|_| throttled)
+            .map(|last_redraw| last_redraw + self.unfocused_frame_interval)
+            .filter(|next_redraw| now < *next_redraw)
+        {
+            return RedrawDecision::WaitUntil(next_redraw);
+        }
+
+        self.last_redraw = Some(now);
+        self.redraw_in_flight = self.coalesce_redraws;
+        RedrawDecision::Redraw
+    }
+
+    fn update_visibility(&mut self, update: impl FnOnce(&mut Self)) {
+        let was_paused = self.is_paused();
+        update(self);
+        if was_paused && !self.is_paused() {
+            self.resumed = true;
+            // A hidden canvas never receives the frame it asked for.
+            self.redraw_in_flight = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occluded_window_stops_redrawing_until_visible() {
+        let mut pacing = FramePacing::new();
+        let now = Instant::now();
+
+        pacing.set_occluded(true);
+        assert_eq!(pacing.poll(now), RedrawDecision::Wait);
+        assert!(!pacing.take_resumed());
+
+        pacing.set_occluded(false);
+        assert!(pacing.take_resumed());
+        assert!(!pacing.take_resumed());
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+    }
+
+    #[test]
+    fn test_minimize_and_occlusion_both_have_to_clear() {
+        let mut pacing = FramePacing::new();
+
+        pacing.set_minimized(true);
+        pacing.set_occluded(true);
+        pacing.set_minimized(false);
+        assert!(pacing.is_paused());
+        assert!(!pacing.take_resumed());
+
+        pacing.set_occluded(false);
+        assert!(!pacing.is_paused());
+        assert!(pacing.take_resumed());
+    }
+
+    #[test]
+    fn test_suspended_app_waits_until_resumed() {
+        let mut pacing = FramePacing::new();
+        let now = Instant::now();
+
+        pacing.set_suspended(true);
+        assert_eq!(pacing.poll(now), RedrawDecision::Wait);
+
+        pacing.set_suspended(false);
+        assert!(pacing.take_resumed());
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+    }
+
+    #[test]
+    fn test_unfocused_window_throttles_to_low_power_cadence() {
+        let mut pacing = FramePacing::new();
+        let start = Instant::now();
+        let interval = Duration::from_secs_f64(1.0 / FramePacing::DEFAULT_UNFOCUSED_FPS);
+
+        pacing.set_focused(false);
+        assert_eq!(pacing.poll(start), RedrawDecision::Redraw);
+        assert_eq!(
+            pacing.poll(start + interval / 2),
+            RedrawDecision::WaitUntil(start + interval)
+        );
+        assert_eq!(pacing.poll(start + interval), RedrawDecision::Redraw);
+
+        pacing.set_focused(true);
+        assert_eq!(
+            pacing.poll(start + interval + Duration::from_millis(1)),
+            RedrawDecision::Redraw
+        );
+    }
+
+    #[test]
+    fn test_unfocused_window_keeps_full_rate_when_low_power_disabled() {
+        let mut pacing = FramePacing::new();
+        let now = Instant::now();
+
+        pacing.set_low_power_when_unfocused(false);
+        pacing.set_focused(false);
+
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+    }
+
+    #[test]
+    fn test_coalesced_redraws_wait_for_the_requested_frame() {
+        let mut pacing = FramePacing::new();
+        let now = Instant::now();
+        pacing.set_coalesce_redraws(true);
+
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+        assert_eq!(pacing.poll(now), RedrawDecision::Wait);
+
+        pacing.frame_rendered();
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+    }
+
+    #[test]
+    fn test_coalesced_redraw_is_released_when_visible_again() {
+        let mut pacing = FramePacing::new();
+        let now = Instant::now();
+        pacing.set_coalesce_redraws(true);
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+
+        pacing.set_occluded(true);
+        pacing.set_occluded(false);
+
+        assert_eq!(pacing.poll(now), RedrawDecision::Redraw);
+    }
+}
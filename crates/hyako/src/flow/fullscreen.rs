@@ -0,0 +1,68 @@
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+/// Windowed geometry captured when entering fullscreen, restored on the way back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowedGeometry {
+    pub size: PhysicalSize<u32>,
+    pub position: Option<PhysicalPosition<i32>>,
+}
+
+#[derive(Debug, Default)]
+pub struct FullscreenState {
+    windowed: Option<WindowedGeometry>,
+}
+
+impl FullscreenState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.windowed.is_some()
+    }
+
+    /// Remembers the windowed geometry. Entering twice keeps the first geometry, since the second
+    /// call would only capture the fullscreen size.
+    pub fn enter(&mut self, size: PhysicalSize<u32>, position: Option<PhysicalPosition<i32>>) {
+        self.windowed
+            .get_or_insert(WindowedGeometry { size, position });
+    }
+
+    /// Leaves fullscreen and hands back the geometry to restore.
+    pub fn exit(&mut self) -> Option<WindowedGeometry> {
+        self.windowed.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windowed_geometry_survives_round_trip() {
+        let mut fullscreen = FullscreenState::new();
+        let size = PhysicalSize::new(1280, 720);
+        let position = Some(PhysicalPosition::new(40, 60));
+
+        fullscreen.enter(size, position);
+        assert!(fullscreen.is_fullscreen());
+
+        assert_eq!(fullscreen.exit(), Some(WindowedGeometry { size, position }));
+        assert!(!fullscreen.is_fullscreen());
+        assert_eq!(fullscreen.exit(), None);
+    }
+
+    #[test]
+    fn test_entering_twice_keeps_original_windowed_size() {
+        let mut fullscreen = FullscreenState::new();
+        let windowed = PhysicalSize::new(1280, 720);
+
+        fullscreen.enter(windowed, None);
+        fullscreen.enter(PhysicalSize::new(2560, 1440), None);
+
+        assert_eq!(
+            fullscreen.exit().map(|geometry| geometry.size),
+            Some(windowed)
+        );
+    }
+}
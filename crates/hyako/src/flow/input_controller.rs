@@ -2,8 +2,10 @@ use hyakou_core::{
     Shared, SharedAccess,
     types::mouse_delta::{MouseAction, MouseButton, MouseDelta, MousePosition, MouseState},
 };
-use log::error;
+use log::{debug, error};
 use winit::{
+    dpi::PhysicalPosition,
+    event::TouchPhase,
     keyboard::KeyCode,
     window::{CursorGrabMode, Window},
 };
@@ -12,7 +14,14 @@ use crate::{
     flow::FlowCommandSender,
     renderer::{
         SceneRenderer,
-        handlers::{InputEvent, keyboard_handler::KeyboardHandler, mouse_handler::MouseHandler},
+        actions::{Action, AppActions, GizmoActions, SelectionActions, SimulationActions},
+        gizmo::GizmoMode,
+        handlers::{
+            InputEvent,
+            keyboard_handler::KeyboardHandler,
+            mouse_handler::MouseHandler,
+            pointer_handler::{Pointer, PointerEvent, PointerHandler, PointerKind},
+        },
     },
 };
 
@@ -20,25 +29,66 @@ pub struct InputController {
     _commands: FlowCommandSender,
     keyboard_handler: KeyboardHandler,
     mouse_handler: MouseHandler,
+    pointer_handler: PointerHandler,
     mouse_delta: MouseDelta,
+    click_origin: Option<MousePosition>,
+    pending_app_actions: Vec<AppActions>,
 }
 
 impl InputController {
+    const CLICK_DRAG_THRESHOLD_PIXELS: f64 = 4.0;
+
     pub fn new(commands: FlowCommandSender) -> Self {
         Self {
             _commands: commands,
             keyboard_handler: KeyboardHandler::new(),
             mouse_handler: MouseHandler::new(),
+            pointer_handler: PointerHandler::new(),
             mouse_delta: MouseDelta::default(),
+            click_origin: None,
+            pending_app_actions: Vec::new(),
         }
     }
 
+    /// Leaving the window only clears the on-window flag; the last position is kept so later
+    /// events keep referring to where the cursor actually was.
     pub fn handle_cursor_in_window(&mut self, is_inside: bool) {
         self.mouse_delta.set_is_mouse_on_window(is_inside);
     }
 
-    pub fn handle_cursor_moved(&mut self, x: f64, y: f64) {
-        self.mouse_delta.position = MousePosition::new(x, y);
+    pub fn cursor_position(&self) -> PhysicalPosition<f64> {
+        PhysicalPosition::new(self.mouse_delta.position.x(), self.mouse_delta.position.y())
+    }
+
+    pub fn is_cursor_on_window(&self) -> bool {
+        self.mouse_delta.is_mouse_on_window()
+    }
+
+    pub fn handle_cursor_moved(
+        &mut self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        x: f64,
+        y: f64,
+    ) {
+        let events = self
+            .pointer_handler
+            .cursor_moved(PhysicalPosition::new(x, y));
+        self.apply_pointer_events(renderer_slot, None, events);
+    }
+
+    /// A finger or pen on the surface, in physical pixels. The first contact drives selection and
+    /// camera drags like the left mouse button.
+    pub fn handle_touch(
+        &mut self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        window: Option<&Window>,
+        id: u64,
+        phase: TouchPhase,
+        position: PhysicalPosition<f64>,
+        kind: PointerKind,
+    ) {
+        let events = self.pointer_handler.touch(id, phase, position, kind);
+        self.apply_pointer_events(renderer_slot, window, events);
     }
 
     pub fn handle_keyboard_input(
@@ -48,15 +98,37 @@ impl InputController {
         pressed: bool,
     ) {
         let events = self.keyboard_handler.handle_key(key, pressed);
-        let _ = renderer_slot.try_write_shared(|renderer_slot| {
-            let Some(renderer) = renderer_slot.as_mut() else {
-                return;
-            };
+        let shift_held = self.is_shift_pressed();
+        let app_actions = renderer_slot
+            .try_write_shared(|renderer_slot| {
+                let Some(renderer) = renderer_slot.as_mut() else {
+                    return events
+                        .into_iter()
+                        .filter_map(|input_event| match input_event {
+                            InputEvent::ActionStarted(Action::App(action)) => Some(action),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                };
 
-            for input_event in events {
-                Self::handle_input_event(renderer, input_event);
-            }
-        });
+                events
+                    .into_iter()
+                    .filter_map(|input_event| {
+                        Self::handle_input_event(renderer, input_event, shift_held)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        self.pending_app_actions.extend(app_actions);
+    }
+
+    pub fn keyboard_handler_mut(&mut self) -> &mut KeyboardHandler {
+        &mut self.keyboard_handler
+    }
+
+    /// App-level actions (quit, fullscreen) the input could not apply to the renderer itself.
+    pub fn take_app_actions(&mut self) -> Vec<AppActions> {
+        std::mem::take(&mut self.pending_app_actions)
     }
 
     pub fn handle_mouse_motion(
@@ -87,6 +159,78 @@ impl InputController {
         button: MouseButton,
         pressed: bool,
     ) {
+        let events = self.pointer_handler.mouse_button(button, pressed);
+        self.apply_pointer_events(renderer_slot, window, events);
+    }
+
+    fn apply_pointer_events(
+        &mut self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        window: Option<&Window>,
+        events: impl IntoIterator<Item = PointerEvent>,
+    ) {
+        for event in events {
+            match event {
+                PointerEvent::Moved { pointer, dx, dy } => {
+                    self.pointer_moved(renderer_slot, &pointer, dx, dy)
+                }
+                PointerEvent::Pressed { pointer, button } => {
+                    self.pointer_button(renderer_slot, window, &pointer, button, true)
+                }
+                PointerEvent::Released { pointer, button } => {
+                    self.pointer_button(renderer_slot, window, &pointer, button, false)
+                }
+                PointerEvent::Cancelled { .. } => self.pointer_cancelled(renderer_slot),
+            }
+        }
+    }
+
+    fn pointer_moved(
+        &mut self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        pointer: &Pointer,
+        dx: f64,
+        dy: f64,
+    ) {
+        let position = pointer.position;
+        self.mouse_delta.position = MousePosition::new(position.x, position.y);
+        self.mouse_delta.set_is_mouse_on_window(true);
+
+        let gizmo_dragging = renderer_slot
+            .try_write_shared(|renderer_slot| {
+                let Some(renderer) = renderer_slot.as_mut() else {
+                    return false;
+                };
+
+                if renderer.gizmo().is_dragging() {
+                    renderer.update_gizmo_drag(position, self.is_shift_pressed());
+                    return true;
+                }
+                if renderer.placement().is_active() {
+                    renderer.request_world_position(position);
+                }
+                false
+            })
+            .unwrap_or(false);
+
+        // Mouse drags arrive as raw device motion; touch and pen contacts only have positions.
+        if pointer.kind != PointerKind::Mouse
+            && pointer.is_pressed(MouseButton::Left)
+            && !gizmo_dragging
+        {
+            self.handle_mouse_motion(renderer_slot, dx, dy, 0.0);
+        }
+    }
+
+    fn pointer_button(
+        &mut self,
+        renderer_slot: &Shared<Option<SceneRenderer>>,
+        window: Option<&Window>,
+        pointer: &Pointer,
+        button: MouseButton,
+        pressed: bool,
+    ) {
+        self.mouse_delta.position = MousePosition::new(pointer.position.x, pointer.position.y);
         self.mouse_delta.state = MouseState::new(
             button,
             if pressed {
@@ -96,7 +240,7 @@ impl InputController {
             },
         );
 
-        if let Some(window) = window {
+        if let Some(window) = window.filter(|_| pointer.kind == PointerKind::Mouse) {
             let grab_mode = CursorGrabMode::None;
 
             if let Err(cursor_error) = window.set_cursor_grab(grab_mode) {
@@ -105,21 +249,158 @@ impl InputController {
 
             window.set_cursor_visible(!pressed);
         }
-
+        let is_click = self.track_click(button, pressed);
+        let cursor_position = self.cursor_position();
         let events = self.mouse_handler.handle_button(button, pressed);
+        let shift_held = self.is_shift_pressed();
+        let ctrl_held = self.is_ctrl_pressed();
+        let gizmo_drag_started = renderer_slot
+            .try_write_shared(|renderer_slot| {
+                let Some(renderer) = renderer_slot.as_mut() else {
+                    return false;
+                };
+
+                if button == MouseButton::Left {
+                    if pressed && renderer.begin_gizmo_drag(cursor_position) {
+                        return true;
+                    }
+                    if !pressed && renderer.gizmo().is_dragging() {
+                        renderer.end_gizmo_drag();
+                        for input_event in events {
+                            Self::handle_input_event(renderer, input_event, shift_held);
+                        }
+                        return false;
+                    }
+                }
+
+                for input_event in events {
+                    Self::handle_input_event(renderer, input_event, shift_held);
+                }
+
+                if is_click {
+                    if renderer.placement().is_active() {
+                        renderer.place_at_screen_position(cursor_position);
+                    } else {
+                        renderer.select_at_screen_position(cursor_position, ctrl_held);
+                    }
+                }
+                false
+            })
+            .unwrap_or(false);
+
+        if gizmo_drag_started {
+            self.click_origin = None;
+        }
+    }
+
+    fn pointer_cancelled(&mut self, renderer_slot: &Shared<Option<SceneRenderer>>) {
+        self.click_origin = None;
+        let events = self.mouse_handler.handle_button(MouseButton::Left, false);
+        let shift_held = self.is_shift_pressed();
         let _ = renderer_slot.try_write_shared(|renderer_slot| {
             let Some(renderer) = renderer_slot.as_mut() else {
                 return;
             };
 
+            if renderer.gizmo().is_dragging() {
+                renderer.cancel_gizmo_drag();
+            }
             for input_event in events {
-                Self::handle_input_event(renderer, input_event);
+                Self::handle_input_event(renderer, input_event, shift_held);
             }
         });
     }
 
-    fn handle_input_event(renderer: &mut SceneRenderer, event: InputEvent) {
+    fn track_click(&mut self, button: MouseButton, pressed: bool) -> bool {
+        if button != MouseButton::Left {
+            return false;
+        }
+
+        if pressed {
+            self.click_origin = Some(self.mouse_delta.position.clone());
+            return false;
+        }
+
+        let Some(origin) = self.click_origin.take() else {
+            return false;
+        };
+        let dx = self.mouse_delta.position.x() - origin.x();
+        let dy = self.mouse_delta.position.y() - origin.y();
+        dx.hypot(dy) <= Self::CLICK_DRAG_THRESHOLD_PIXELS
+    }
+
+    fn is_shift_pressed(&self) -> bool {
+        let modifiers = self.keyboard_handler.get_pressed_modifiers();
+        modifiers.contains(&KeyCode::ShiftLeft) || modifiers.contains(&KeyCode::ShiftRight)
+    }
+
+    fn is_ctrl_pressed(&self) -> bool {
+        let modifiers = self.keyboard_handler.get_pressed_modifiers();
+        modifiers.contains(&KeyCode::ControlLeft) || modifiers.contains(&KeyCode::ControlRight)
+    }
+
+    /// Applies `event` to the renderer and returns any app-level action left for the caller.
+    fn handle_input_event(
+        renderer: &mut SceneRenderer,
+        event: InputEvent,
+        shift_held: bool,
+    ) -> Option<AppActions> {
         match event {
+            // Quit backs out one step at a time: an active drag, then the selection, then the app.
+            InputEvent::ActionStarted(Action::App(AppActions::Quit)) => {
+                if renderer.gizmo().is_dragging() {
+                    renderer.cancel_gizmo_drag();
+                } else if !renderer.selection().is_empty() {
+                    renderer.clear_selection();
+                } else {
+                    return Some(AppActions::Quit);
+                }
+            }
+            InputEvent::ActionStarted(Action::App(AppActions::ToggleStatsOverlay)) => {
+                let shown = renderer.toggle_stats_overlay();
+                debug!("Stats overlay {}", if shown { "shown" } else { "hidden" });
+            }
+            InputEvent::ActionStarted(Action::App(action)) => return Some(action),
+            InputEvent::ActionEnded(Action::App(_)) => {}
+            InputEvent::ActionStarted(Action::Gizmo(GizmoActions::Cancel)) => {
+                if renderer.gizmo().is_dragging() {
+                    renderer.cancel_gizmo_drag();
+                } else {
+                    renderer.clear_selection();
+                }
+            }
+            InputEvent::ActionStarted(Action::Gizmo(GizmoActions::TranslateMode)) => {
+                renderer.set_gizmo_mode(GizmoMode::Translate);
+            }
+            InputEvent::ActionStarted(Action::Gizmo(GizmoActions::RotateMode)) => {
+                renderer.set_gizmo_mode(GizmoMode::Rotate);
+            }
+            InputEvent::ActionStarted(Action::Gizmo(GizmoActions::ScaleMode)) => {
+                renderer.set_gizmo_mode(GizmoMode::Scale);
+            }
+            InputEvent::ActionStarted(Action::Nudge(action)) => {
+                renderer.nudge_selected(action, shift_held);
+            }
+            InputEvent::ActionEnded(Action::Nudge(_)) => {}
+            InputEvent::ActionStarted(Action::Selection(SelectionActions::Delete)) => {
+                renderer.delete_selected();
+            }
+            InputEvent::ActionStarted(Action::Selection(SelectionActions::Duplicate)) => {
+                renderer.duplicate_selected();
+            }
+            InputEvent::ActionStarted(Action::Selection(SelectionActions::TogglePlacement)) => {
+                renderer.toggle_placement_mode();
+            }
+            InputEvent::ActionStarted(Action::Selection(SelectionActions::CycleShadingSource)) => {
+                renderer.cycle_selected_shading_source();
+            }
+            InputEvent::ActionEnded(Action::Selection(_)) => {}
+            InputEvent::ActionStarted(Action::Simulation(SimulationActions::TogglePause)) => {
+                let paused = renderer.toggle_simulation_pause();
+                debug!("Simulation {}", if paused { "paused" } else { "resumed" });
+            }
+            InputEvent::ActionEnded(Action::Simulation(_)) => {}
+            InputEvent::ActionEnded(Action::Gizmo(_)) => {}
             InputEvent::ActionStarted(action) => {
                 renderer.camera_handler.handle_action(&action, true);
             }
@@ -127,5 +408,95 @@ impl InputController {
                 renderer.camera_handler.handle_action(&action, false);
             }
         }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use hyakou_core::shared;
+
+    use super::*;
+
+    fn input_controller() -> InputController {
+        let (tx, _rx) = channel();
+        InputController::new(FlowCommandSender::new(tx))
+    }
+
+    #[test]
+    fn test_cursor_position_survives_keyboard_events() {
+        let mut input_controller = input_controller();
+        let renderer_slot: Shared<Option<SceneRenderer>> = shared(None);
+
+        input_controller.handle_cursor_in_window(true);
+        input_controller.handle_cursor_moved(&renderer_slot, 320.0, 240.0);
+        input_controller.handle_keyboard_input(&renderer_slot, KeyCode::KeyW, true);
+        input_controller.handle_keyboard_input(&renderer_slot, KeyCode::KeyW, false);
+
+        assert_eq!(
+            input_controller.cursor_position(),
+            PhysicalPosition::new(320.0, 240.0)
+        );
+        assert!(input_controller.is_cursor_on_window());
+    }
+
+    #[test]
+    fn test_cursor_leaving_window_keeps_last_position() {
+        let mut input_controller = input_controller();
+        let renderer_slot: Shared<Option<SceneRenderer>> = shared(None);
+
+        input_controller.handle_cursor_moved(&renderer_slot, 12.0, 34.0);
+        input_controller.handle_cursor_in_window(false);
+
+        assert!(!input_controller.is_cursor_on_window());
+        assert_eq!(
+            input_controller.cursor_position(),
+            PhysicalPosition::new(12.0, 34.0)
+        );
+    }
+
+    #[test]
+    fn test_touch_and_pen_contacts_move_the_cursor_like_a_mouse() {
+        for kind in [PointerKind::Touch, PointerKind::Pen] {
+            let mut input_controller = input_controller();
+            let renderer_slot: Shared<Option<SceneRenderer>> = shared(None);
+            let position = PhysicalPosition::new(40.0, 60.0);
+
+            input_controller.handle_touch(
+                &renderer_slot,
+                None,
+                1,
+                TouchPhase::Started,
+                PhysicalPosition::new(10.0, 10.0),
+                kind,
+            );
+            input_controller.handle_touch(
+                &renderer_slot,
+                None,
+                1,
+                TouchPhase::Moved,
+                position,
+                kind,
+            );
+            input_controller.handle_touch(
+                &renderer_slot,
+                None,
+                1,
+                TouchPhase::Ended,
+                position,
+                kind,
+            );
+
+            assert_eq!(input_controller.cursor_position(), position, "{kind:?}");
+            assert!(input_controller.is_cursor_on_window());
+            assert!(
+                input_controller
+                    .mouse_handler
+                    .get_pressed_buttons()
+                    .is_empty()
+            );
+        }
     }
 }
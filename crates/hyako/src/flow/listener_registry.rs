@@ -0,0 +1,83 @@
+/// Callbacks registered from the outside, each under the id handed out on registration so it can
+/// be removed again without affecting the others.
+#[derive(Debug)]
+pub struct ListenerRegistry<L> {
+    next_id: u64,
+    listeners: Vec<(u64, L)>,
+}
+
+impl<L> ListenerRegistry<L> {
+    pub fn new() -> Self {
+        Self {
+            next_id: 0,
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, listener: L) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.listeners.push((id, listener));
+        id
+    }
+
+    /// Returns whether a listener was registered under `id`.
+    pub fn unregister(&mut self, id: u64) -> bool {
+        let count = self.listeners.len();
+        self.listeners.retain(|(listener_id, _)| *listener_id != id);
+        self.listeners.len() != count
+    }
+
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &L> {
+        self.listeners.iter().map(|(_, listener)| listener)
+    }
+}
+
+impl<L> Default for ListenerRegistry<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listeners_are_kept_until_unregistered() {
+        let mut registry = ListenerRegistry::new();
+        let first = registry.register("first");
+        let second = registry.register("second");
+
+        assert_ne!(first, second);
+        assert_eq!(
+            registry.iter().copied().collect::<Vec<_>>(),
+            ["first", "second"]
+        );
+
+        assert!(registry.unregister(first));
+        assert!(!registry.unregister(first));
+        assert_eq!(registry.iter().copied().collect::<Vec<_>>(), ["second"]);
+    }
+
+    #[test]
+    fn test_ids_are_not_reused_after_unregistering() {
+        let mut registry = ListenerRegistry::new();
+        let first = registry.register(1);
+        registry.unregister(first);
+
+        let second = registry.register(2);
+
+        assert_ne!(first, second);
+        assert!(!registry.unregister(first));
+        assert_eq!(registry.len(), 1);
+    }
+}
@@ -1,15 +1,33 @@
 pub mod asset_upload_controller;
+pub mod clock;
 pub mod command_sender;
 pub mod commands;
+pub mod fixed_timestep;
 pub mod flow;
 pub mod frame_composer;
+pub mod frame_pacing;
+pub mod fullscreen;
 pub mod input_controller;
+pub mod listener_registry;
+pub mod pending_input;
 pub mod render_controller;
+pub mod screenshots;
+pub mod title_stats;
+pub mod window_views;
 
 pub use asset_upload_controller::AssetUploadController;
+pub use clock::{Clock, ManualClock, SystemClock};
 pub use command_sender::FlowCommandSender;
 pub use commands::RendererCommand;
+pub use fixed_timestep::{FixedSteps, FixedTimestep};
 pub use flow::{FlowController, FlowHandle};
 pub use frame_composer::FrameComposer;
+pub use frame_pacing::{FramePacing, RedrawDecision};
+pub use fullscreen::FullscreenState;
 pub use input_controller::InputController;
+pub use listener_registry::ListenerRegistry;
+pub use pending_input::PendingInput;
 pub use render_controller::RenderController;
+pub use screenshots::ScreenshotNames;
+pub use title_stats::TitleStats;
+pub use window_views::WindowViews;
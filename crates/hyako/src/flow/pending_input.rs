@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use log::warn;
+
+use crate::flow::RendererCommand;
+
+/// Input commands that arrived before the renderer existed, replayed in order once it does.
+#[derive(Default)]
+pub struct PendingInput {
+    commands: VecDeque<RendererCommand>,
+}
+
+impl PendingInput {
+    pub const CAPACITY: usize = 64;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `command` needs a renderer to have any effect and should wait for one.
+    pub fn should_buffer(command: &RendererCommand) -> bool {
+        matches!(
+            command,
+            RendererCommand::KeyboardInput { .. } | RendererCommand::MouseButton { .. }
+        )
+    }
+
+    /// Queues `command`, dropping the oldest entry once [`Self::CAPACITY`] is reached.
+    pub fn push(&mut self, command: RendererCommand) {
+        if self.commands.len() == Self::CAPACITY {
+            warn!("Pre-initialization input buffer is full; dropping the oldest event");
+            self.commands.pop_front();
+        }
+        self.commands.push_back(command);
+    }
+
+    pub fn take(&mut self) -> VecDeque<RendererCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::KeyCode;
+
+    use super::*;
+
+    fn key(key: KeyCode) -> RendererCommand {
+        RendererCommand::KeyboardInput { key, pressed: true }
+    }
+
+    fn key_code(command: &RendererCommand) -> Option<KeyCode> {
+        match command {
+            RendererCommand::KeyboardInput { key, .. } => Some(*key),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_only_renderer_bound_input_is_buffered() {
+        assert!(PendingInput::should_buffer(&key(KeyCode::KeyW)));
+        assert!(!PendingInput::should_buffer(
+            &RendererCommand::CursorMoved { x: 1.0, y: 2.0 }
+        ));
+        assert!(!PendingInput::should_buffer(&RendererCommand::Redraw {
+            interpolation_alpha: 0.0
+        }));
+    }
+
+    #[test]
+    fn test_take_replays_in_arrival_order() {
+        let mut pending = PendingInput::new();
+        pending.push(key(KeyCode::KeyW));
+        pending.push(key(KeyCode::KeyA));
+
+        let replayed: Vec<_> = pending.take().iter().filter_map(key_code).collect();
+
+        assert_eq!(replayed, vec![KeyCode::KeyW, KeyCode::KeyA]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_full_buffer_drops_oldest() {
+        let mut pending = PendingInput::new();
+        pending.push(key(KeyCode::Escape));
+        for _ in 0..PendingInput::CAPACITY {
+            pending.push(key(KeyCode::KeyW));
+        }
+
+        let commands = pending.take();
+
+        assert_eq!(commands.len(), PendingInput::CAPACITY);
+        assert!(
+            commands
+                .iter()
+                .all(|command| key_code(command) == Some(KeyCode::KeyW))
+        );
+    }
+}
@@ -1,26 +1,49 @@
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use glam::Vec3;
 use hyakou_core::{
     Shared, SharedAccess, components::camera::data_structures::CameraAnimationRequest, shared,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use log::info;
 use log::{error, warn};
-use winit::window::Window;
+use winit::window::{Fullscreen, Window, WindowId};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_futures::spawn_local;
 
+#[cfg(feature = "egui")]
+use crate::gui::EguiRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{flow::ScreenshotNames, renderer::offscreen::PendingCapture};
 use crate::{
-    flow::{FlowCommandSender, FrameComposer},
-    gui::EguiRenderer,
-    renderer::{SceneRenderer, surface_frame_controller::SurfaceFrameController},
+    flow::{FlowCommandSender, FrameComposer, FullscreenState},
+    renderer::{
+        SceneRenderer,
+        handlers::{
+            file_drop::{focus_camera_on, world_bounds},
+            selection::SelectionEvent,
+        },
+        settings::RendererSettings,
+        surface_frame_controller::SurfaceFrameController,
+        util::asset_root::AssetRoot,
+    },
 };
 
 pub struct RenderController {
     _commands: FlowCommandSender,
     surface_frame_controller: SurfaceFrameController,
     renderer: Shared<Option<SceneRenderer>>,
+    #[cfg(feature = "egui")]
     egui_renderer: Shared<Option<EguiRenderer>>,
     window: Option<Arc<Window>>,
+    fullscreen: FullscreenState,
+    device_loss_reported: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    screenshots: ScreenshotNames,
 }
 
 impl RenderController {
@@ -29,8 +52,13 @@ impl RenderController {
             _commands: commands,
             surface_frame_controller: SurfaceFrameController::new(),
             renderer: shared(None),
+            #[cfg(feature = "egui")]
             egui_renderer: shared(None),
             window: None,
+            fullscreen: FullscreenState::new(),
+            device_loss_reported: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            screenshots: ScreenshotNames::default(),
         }
     }
 
@@ -38,10 +66,17 @@ impl RenderController {
         self.renderer.clone()
     }
 
+    pub fn has_renderer(&self) -> bool {
+        self.renderer
+            .try_read_shared(|renderer_slot| renderer_slot.is_some())
+            .unwrap_or(false)
+    }
+
     pub fn window(&self) -> Option<&Window> {
         self.window.as_deref()
     }
 
+    #[cfg(feature = "egui")]
     pub fn handle_egui_window_event(&mut self, event: &winit::event::WindowEvent) -> bool {
         self.egui_renderer
             .try_write_shared(|egui_renderer| {
@@ -52,18 +87,51 @@ impl RenderController {
             .unwrap_or(false)
     }
 
-    pub fn handle_window_created(&mut self, window: Arc<Window>) {
+    /// Without egui nothing consumes window events.
+    #[cfg(not(feature = "egui"))]
+    pub fn handle_egui_window_event(&mut self, _event: &winit::event::WindowEvent) -> bool {
+        false
+    }
+
+    /// Whether egui is using the pointer, e.g. dragging a slider, so raw device input should not
+    /// also steer the camera.
+    #[cfg(feature = "egui")]
+    pub fn egui_wants_pointer_input(&self) -> bool {
+        self.egui_renderer
+            .try_read_shared(|egui_renderer| {
+                egui_renderer
+                    .as_ref()
+                    .is_some_and(EguiRenderer::wants_pointer_input)
+            })
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(feature = "egui"))]
+    pub fn egui_wants_pointer_input(&self) -> bool {
+        false
+    }
+
+    pub fn handle_window_created(
+        &mut self,
+        window: Arc<Window>,
+        transparent: bool,
+        settings: RendererSettings,
+        asset_root: AssetRoot,
+    ) {
         self.window = Some(window.clone());
 
-        let has_renderer = self
-            .renderer
-            .read_shared(|renderer_slot| renderer_slot.is_some());
-        if has_renderer {
+        if self.has_renderer() {
+            self.resume();
             return;
         }
 
         #[cfg(not(target_arch = "wasm32"))]
-        match pollster::block_on(SceneRenderer::new(window)) {
+        match pollster::block_on(SceneRenderer::new(
+            window,
+            transparent,
+            settings,
+            &asset_root,
+        )) {
             Ok(renderer) => {
                 let _ = self
                     .renderer
@@ -74,14 +142,14 @@ impl RenderController {
             }
         }
 
-        #[cfg(not(target_arch = "wasm32"))]
+        #[cfg(all(feature = "egui", not(target_arch = "wasm32")))]
         self.create_egui_renderer();
 
         #[cfg(target_arch = "wasm32")]
         {
             let renderer_slot = self.renderer.clone();
             spawn_local(async move {
-                match SceneRenderer::new(window.clone()).await {
+                match SceneRenderer::new(window.clone(), transparent, settings, &asset_root).await {
                     Ok(renderer) => {
                         let Some(()) = renderer_slot
                             .try_write_shared(|slot| *slot = Some(renderer))
@@ -102,18 +170,219 @@ impl RenderController {
         }
     }
 
+    pub fn suspend(&mut self) {
+        if let Err(lock_error) = self.renderer.try_write_shared(|renderer_slot| {
+            if let Some(renderer) = renderer_slot.as_mut() {
+                renderer.suspend_surface();
+            }
+        }) {
+            error!("Failed to acquire renderer lock during suspend: {lock_error:?}");
+        }
+    }
+
+    /// Recreates the surface of an existing renderer for the current window.
+    pub fn resume(&mut self) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        if let Err(lock_error) = self.renderer.try_write_shared(|renderer_slot| {
+            let Some(renderer) = renderer_slot.as_mut() else {
+                return;
+            };
+            if let Err(surface_error) = renderer.recreate_surface(window) {
+                error!("Failed to recreate render surface on resume: {surface_error:?}");
+            }
+        }) {
+            error!("Failed to acquire renderer lock during resume: {lock_error:?}");
+        }
+    }
+
+    /// Reports a device loss once. A failed recovery leaves the device lost, so it is not
+    /// reported again until a recovery succeeds.
+    pub fn take_device_lost(&mut self) -> bool {
+        let lost = self
+            .renderer
+            .try_read_shared(|renderer_slot| {
+                renderer_slot
+                    .as_ref()
+                    .is_some_and(|renderer| renderer.is_device_lost())
+            })
+            .unwrap_or(false);
+        let newly_lost = lost && !self.device_loss_reported;
+        self.device_loss_reported = lost;
+        newly_lost
+    }
+
+    /// Rebuilds the renderer's device and GPU resources for the current window, keeping the
+    /// scene state.
+    pub fn recover_device(&mut self) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        warn!("Recovering renderer after GPU device loss");
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.with_renderer("recovering the device", |renderer| {
+                if let Err(recover_error) = pollster::block_on(renderer.recover_device(window)) {
+                    error!("Failed to recover from device loss: {recover_error:?}");
+                }
+            });
+            #[cfg(feature = "egui")]
+            self.create_egui_renderer();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let Ok(Some(mut renderer)) = self.renderer.try_write_shared(|slot| slot.take()) else {
+                warn!("Renderer slot is busy or empty; skipping device recovery");
+                return;
+            };
+            let renderer_slot = self.renderer.clone();
+            spawn_local(async move {
+                if let Err(recover_error) = renderer.recover_device(window.clone()).await {
+                    error!("Failed to recover from device loss: {recover_error:?}");
+                }
+                if renderer_slot
+                    .try_write_shared(|slot| *slot = Some(renderer))
+                    .is_err()
+                {
+                    warn!("Renderer recovered but flow slot was busy; dropping it");
+                    return;
+                }
+                window.request_redraw();
+            });
+        }
+    }
+
+    pub fn add_view(&mut self, window: Arc<Window>) {
+        self.with_renderer("adding a view", |renderer| {
+            if let Err(view_error) = renderer.add_view(window) {
+                error!("Failed to open additional view: {view_error:?}");
+            }
+        });
+    }
+
+    pub fn close_view(&mut self, window_id: WindowId) {
+        self.with_renderer("closing a view", |renderer| {
+            renderer.remove_view(window_id);
+        });
+    }
+
+    pub fn resize_view(&mut self, window_id: WindowId, width: f64, height: f64) {
+        let size = SurfaceFrameController::size_from_dimensions(width, height);
+        self.with_renderer("resizing a view", |renderer| {
+            renderer.resize_view(window_id, size);
+        });
+    }
+
+    pub fn render_view(&mut self, window_id: WindowId) {
+        self.with_renderer("rendering a view", |renderer| {
+            if let Err(render_error) = renderer.render_view(window_id) {
+                error!("View frame composition failed: {render_error:?}");
+            }
+        });
+    }
+
+    fn with_renderer(&self, operation: &str, action: impl FnOnce(&mut SceneRenderer)) {
+        if let Err(lock_error) = self.renderer.try_write_shared(|renderer_slot| {
+            if let Some(renderer) = renderer_slot.as_mut() {
+                action(renderer);
+            }
+        }) {
+            error!("Failed to acquire renderer lock while {operation}: {lock_error:?}");
+        }
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen.is_fullscreen()
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        self.set_fullscreen(!self.fullscreen.is_fullscreen());
+    }
+
+    /// Switches between windowed and borderless fullscreen on the current monitor, restoring the
+    /// windowed size and position on the way back. On the web winit requests fullscreen for the
+    /// canvas element. The new size reaches the renderer through the regular resize path.
+    pub fn set_fullscreen(&mut self, enabled: bool) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        if enabled == self.fullscreen.is_fullscreen() {
+            return;
+        }
+
+        if enabled {
+            self.fullscreen
+                .enter(window.inner_size(), window.outer_position().ok());
+            window.set_fullscreen(Some(Fullscreen::Borderless(window.current_monitor())));
+            return;
+        }
+
+        window.set_fullscreen(None);
+        let Some(windowed) = self.fullscreen.exit() else {
+            return;
+        };
+        if let Some(size) = window.request_inner_size(windowed.size) {
+            self.handle_resize(f64::from(size.width), f64::from(size.height));
+        }
+        if let Some(position) = windowed.position {
+            window.set_outer_position(position);
+        }
+    }
+
+    /// Saves the current view as PNG into the screenshots directory. Reading the frame back and
+    /// encoding it happen on a background thread; failures are logged, never fatal.
+    pub fn capture_screenshot(&mut self) {
+        #[cfg(target_arch = "wasm32")]
+        warn!("Screenshots are not supported on the web");
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut capture = None;
+            self.with_renderer("capturing a screenshot", |renderer| {
+                capture = Some((renderer.begin_capture(), renderer.get_device()));
+            });
+            let Some((pending, device)) = capture else {
+                return;
+            };
+
+            let path = self
+                .screenshots
+                .next_path(std::time::SystemTime::now(), std::path::Path::exists);
+            let spawn_result = std::thread::Builder::new()
+                .name("screenshot".to_string())
+                .spawn(move || match save_screenshot(pending, &device, &path) {
+                    Ok(()) => info!("Saved screenshot to {}", path.display()),
+                    Err(save_error) => error!("Failed to save screenshot: {save_error:#}"),
+                });
+            if let Err(spawn_error) = spawn_result {
+                error!("Failed to start screenshot thread: {spawn_error:?}");
+            }
+        }
+    }
+
+    /// Drops egui and the scene renderer before the window so their surfaces are released first.
+    pub fn shutdown(&mut self) {
+        #[cfg(feature = "egui")]
+        if let Err(lock_error) = self.egui_renderer.try_write_shared(|slot| *slot = None) {
+            warn!("Failed to release egui renderer during shutdown: {lock_error:?}");
+        }
+        if let Err(lock_error) = self.renderer.try_write_shared(|slot| *slot = None) {
+            warn!("Failed to release renderer during shutdown: {lock_error:?}");
+        }
+        self.window = None;
+    }
+
     pub fn handle_resize(&mut self, width: f64, height: f64) {
-        let surface_frame_controller = &mut self.surface_frame_controller;
         if let Err(lock_error) = self.renderer.try_write_shared(|renderer| {
             let Some(renderer) = renderer.as_mut() else {
                 return;
             };
 
             let size = SurfaceFrameController::size_from_dimensions(width, height);
-            renderer.set_camera_aspect_from_size(size);
-            if let Err(resize_error) =
-                surface_frame_controller.resize(renderer.render_context_mut(), size)
-            {
+            if let Err(resize_error) = renderer.resize(size) {
                 error!("Failed to resize renderer: {resize_error:?}");
             }
         }) {
@@ -121,10 +390,27 @@ impl RenderController {
         }
     }
 
-    pub fn render_frame(&mut self, frame_composer: &mut FrameComposer, dt: f64) {
-        let Some(window) = self.window.clone() else {
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.with_renderer("changing the scale factor", |renderer| {
+            renderer.set_scale_factor(scale_factor);
+        });
+    }
+
+    pub fn update(&mut self, dt: f64) {
+        if let Err(lock_error) = self.renderer.try_write_shared(|renderer_slot| {
+            if let Some(renderer) = renderer_slot.as_mut() {
+                renderer.update(dt);
+            }
+        }) {
+            warn!("Skipping simulation step because renderer slot is busy: {lock_error:?}");
+        }
+    }
+
+    #[cfg(feature = "egui")]
+    pub fn render_frame(&mut self, frame_composer: &mut FrameComposer, interpolation_alpha: f64) {
+        if self.window.is_none() {
             return;
-        };
+        }
         let surface_frame_controller = &mut self.surface_frame_controller;
         let _ = self.renderer.try_write_shared(|renderer_slot| {
             let Some(renderer) = renderer_slot.as_mut() else {
@@ -134,11 +420,10 @@ impl RenderController {
             let render_result = self.egui_renderer.try_write_shared(|egui_renderer| {
                 Self::render_locked_frame(
                     surface_frame_controller,
-                    &window,
                     frame_composer,
                     renderer,
                     egui_renderer.as_mut(),
-                    dt,
+                    interpolation_alpha,
                 )
             });
 
@@ -153,11 +438,10 @@ impl RenderController {
                     );
                     if let Err(render_error) = Self::render_locked_frame(
                         surface_frame_controller,
-                        &window,
                         frame_composer,
                         renderer,
                         None,
-                        dt,
+                        interpolation_alpha,
                     ) {
                         error!("Renderer frame composition failed: {render_error:?}");
                     }
@@ -166,39 +450,62 @@ impl RenderController {
         });
     }
 
+    #[cfg(not(feature = "egui"))]
+    pub fn render_frame(&mut self, frame_composer: &mut FrameComposer, interpolation_alpha: f64) {
+        if self.window.is_none() {
+            return;
+        }
+        let surface_frame_controller = &mut self.surface_frame_controller;
+        let _ = self.renderer.try_write_shared(|renderer_slot| {
+            let Some(renderer) = renderer_slot.as_mut() else {
+                return;
+            };
+            if let Err(render_error) = Self::render_locked_frame(
+                surface_frame_controller,
+                frame_composer,
+                renderer,
+                interpolation_alpha,
+            ) {
+                error!("Renderer frame composition failed: {render_error:?}");
+            }
+        });
+    }
+
     fn render_locked_frame(
         surface_frame_controller: &mut SurfaceFrameController,
-        window: &Window,
         frame_composer: &mut FrameComposer,
         renderer: &mut SceneRenderer,
-        mut egui_renderer: Option<&mut EguiRenderer>,
-        dt: f64,
+        #[cfg(feature = "egui")] mut egui_renderer: Option<&mut EguiRenderer>,
+        interpolation_alpha: f64,
     ) -> anyhow::Result<()> {
-        renderer.update(dt);
-
         let Some(mut frame) =
-            surface_frame_controller.begin_frame(window, renderer.render_context_mut())?
+            surface_frame_controller.begin_frame(renderer.render_context_mut())?
         else {
             return Ok(());
         };
 
         {
             let mut target = frame.target();
+            #[cfg(feature = "egui")]
             frame_composer.compose_frame(
                 &mut target,
                 renderer,
                 egui_renderer.as_mut().map(|renderer| &mut **renderer),
+                interpolation_alpha,
             );
+            #[cfg(not(feature = "egui"))]
+            frame_composer.compose_frame(&mut target, renderer, interpolation_alpha);
         }
 
         let finish_result =
             surface_frame_controller.finish_frame(renderer.render_context_mut(), frame);
 
+        #[cfg(feature = "egui")]
         if let Some(egui_renderer) = egui_renderer.as_mut() {
             egui_renderer.free_textures_after_submit();
         }
 
-        finish_result
+        Ok(finish_result?)
     }
 
     pub fn animate_camera(&mut self, request: CameraAnimationRequest) {
@@ -226,27 +533,183 @@ impl RenderController {
         });
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
+    /// Places the camera, cancelling any camera animation so it does not pull the camera back.
+    pub fn set_camera(&mut self, eye: Vec3, target: Vec3) {
+        self.with_renderer("setting the camera", |renderer| {
+            renderer
+                .camera_handler
+                .state
+                .stop_camera_animation(&renderer.camera.id);
+            if let Err(camera_error) = renderer.camera.look_at(eye, target) {
+                warn!("Ignoring camera placement: {camera_error}");
+            }
+        });
+    }
+
+    pub fn set_camera_fov(&mut self, degrees: f32) {
+        self.with_renderer("setting the field of view", |renderer| {
+            if let Err(fov_error) = renderer.camera.set_fov_degrees(degrees) {
+                warn!("Ignoring field of view: {fov_error}");
+            }
+        });
+    }
+
+    pub fn apply_settings(&mut self, settings: &RendererSettings) {
+        self.with_renderer("applying renderer settings", |renderer| {
+            let change = renderer.apply_settings(settings);
+            if change.needs_restart() {
+                warn!(
+                    "{} only take effect after a restart",
+                    change.restart_required.join(", ")
+                );
+            }
+        });
+    }
+
+    /// Replaces the scene with the glTF files at `paths`, laid out side by side.
+    pub fn load_models(&mut self, paths: &[PathBuf]) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.with_renderer("loading models", |renderer| {
+            use crate::renderer::handlers::file_drop::import_models;
+
+            renderer.clear_scene();
+            match pollster::block_on(import_models(
+                &mut renderer.asset_manager,
+                &mut renderer.camera,
+                paths,
+            )) {
+                Ok(mesh_ids) => info!(
+                    "Loaded {} meshes from {} models",
+                    mesh_ids.len(),
+                    paths.len()
+                ),
+                Err(import_error) => error!("Failed to load models: {import_error:#}"),
+            }
+        });
+
+        #[cfg(target_arch = "wasm32")]
+        warn!(
+            "Ignoring {} model paths: models are uploaded on the web",
+            paths.len()
+        );
+    }
+
+    /// Replaces the scene with the scene file at `path`. Entries that fail to load are logged and
+    /// the rest of the scene is kept.
+    pub fn load_scene_file(&mut self, path: &Path) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.with_renderer("loading a scene file", |renderer| match pollster::block_on(
+            renderer.load_scene_file(path),
+        ) {
+            Ok(()) => info!("Loaded scene file {}", path.display()),
+            Err(scene_error) => error!("{scene_error}"),
+        });
+
+        #[cfg(target_arch = "wasm32")]
+        warn!(
+            "Ignoring scene file {}: scene files are not loaded on the web",
+            path.display()
+        );
+    }
+
+    /// Frames the mesh `id` from the current viewing direction.
+    pub fn focus_asset(&mut self, id: &str) {
+        self.with_renderer("focusing an asset", |renderer| {
+            let Ok(mesh) = renderer.asset_manager.get(id.to_string()) else {
+                warn!("Cannot focus unknown asset `{id}`");
+                return;
+            };
+            let Some(bounds) = world_bounds(&[mesh]) else {
+                warn!("Cannot focus asset `{id}` without bounds");
+                return;
+            };
+            renderer
+                .camera_handler
+                .state
+                .stop_camera_animation(&renderer.camera.id);
+            if let Err(focus_error) = focus_camera_on(&mut renderer.camera, &bounds) {
+                warn!("Failed to focus asset `{id}`: {focus_error}");
+            }
+        });
+    }
+
+    pub fn select_asset(&mut self, id: String) {
+        self.with_renderer("selecting an asset", |renderer| {
+            if renderer.asset_manager.contains(&id) {
+                renderer.selection_mut().select(id);
+            } else {
+                warn!("Cannot select unknown asset `{id}`");
+            }
+        });
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.with_renderer("clearing the selection", SceneRenderer::clear_selection);
+    }
+
+    pub fn take_selection_events(&self) -> Vec<SelectionEvent> {
+        self.renderer
+            .try_write_shared(|renderer_slot| {
+                renderer_slot
+                    .as_mut()
+                    .map(SceneRenderer::take_selection_events)
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    }
+
+    #[cfg(all(feature = "egui", not(target_arch = "wasm32")))]
     fn create_egui_renderer(&mut self) {
-        let egui_renderer = self
-            .renderer
-            .try_read_shared(|renderer| {
-                if let Some(renderer) = renderer {
-                    use egui_wgpu::RendererOptions;
-                    Some(EguiRenderer::new(
-                        renderer.get_device().clone(),
-                        self.window.as_ref().unwrap().clone(),
-                        renderer.get_surface_configuration().format,
-                        RendererOptions::default(),
-                    ))
-                } else {
-                    error!("Renderer is not initialized yet!");
-                    None
-                }
+        use egui_wgpu::RendererOptions;
+
+        let Some(window) = self.window.clone() else {
+            error!("Cannot create the egui renderer without a window");
+            return;
+        };
+        let created = self.renderer.try_read_shared(|renderer| {
+            renderer.as_ref().map(|renderer| {
+                EguiRenderer::new(
+                    renderer.get_device().clone(),
+                    window,
+                    renderer.get_surface_configuration().format,
+                    RendererOptions::default(),
+                )
             })
-            .unwrap();
-        self.egui_renderer
-            .try_write_shared(|slot| *slot = egui_renderer)
-            .unwrap();
+        });
+        let egui_renderer = match created {
+            Ok(Some(egui_renderer)) => egui_renderer,
+            Ok(None) => {
+                error!("Renderer is not initialized yet!");
+                return;
+            }
+            Err(lock_error) => {
+                error!(
+                    "Failed to acquire renderer lock while creating the egui renderer: {lock_error:?}"
+                );
+                return;
+            }
+        };
+        if let Err(lock_error) = self
+            .egui_renderer
+            .try_write_shared(|slot| *slot = Some(egui_renderer))
+        {
+            error!("Failed to store the egui renderer: {lock_error:?}");
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_screenshot(
+    pending: PendingCapture,
+    device: &wgpu::Device,
+    path: &std::path::Path,
+) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let frame = pending.wait(device)?;
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)
+            .with_context(|| format!("Failed to create `{}`", directory.display()))?;
     }
+    frame.save_png(path)
 }
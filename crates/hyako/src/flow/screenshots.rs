@@ -0,0 +1,142 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Picks where screenshots are written: `screenshot_<UTC timestamp>_<n>.png` in one directory,
+/// where `n` counts up per session and skips names that already exist.
+#[derive(Debug)]
+pub struct ScreenshotNames {
+    directory: PathBuf,
+    next_index: u32,
+}
+
+impl ScreenshotNames {
+    pub const DIRECTORY_NAME: &str = "screenshots";
+
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            next_index: 1,
+        }
+    }
+
+    /// `screenshots/` next to the executable, or in the working directory if that is unknown.
+    pub fn default_directory() -> PathBuf {
+        std::env::current_exe()
+            .ok()
+            .and_then(|executable| executable.parent().map(Path::to_path_buf))
+            .unwrap_or_default()
+            .join(Self::DIRECTORY_NAME)
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn next_path(&mut self, now: SystemTime, is_taken: impl Fn(&Path) -> bool) -> PathBuf {
+        loop {
+            let path = self
+                .directory
+                .join(screenshot_file_name(now, self.next_index));
+            self.next_index += 1;
+            if !is_taken(&path) {
+                return path;
+            }
+        }
+    }
+}
+
+impl Default for ScreenshotNames {
+    fn default() -> Self {
+        Self::new(Self::default_directory())
+    }
+}
+
+pub fn screenshot_file_name(now: SystemTime, index: u32) -> String {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_date_from_days(seconds / 86_400);
+    let time_of_day = seconds % 86_400;
+    format!(
+        "screenshot_{year:04}{month:02}{day:02}-{:02}{:02}{:02}_{index:03}.png",
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60
+    )
+}
+
+/// Proleptic Gregorian date for a day count since 1970-01-01.
+fn civil_date_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    // 2024-02-29 13:05:09 UTC.
+    const LEAP_DAY_SECONDS: u64 = 1_709_211_909;
+
+    #[test]
+    fn test_file_name_contains_utc_timestamp_and_index() {
+        let now = UNIX_EPOCH + Duration::from_secs(LEAP_DAY_SECONDS);
+
+        assert_eq!(
+            screenshot_file_name(now, 7),
+            "screenshot_20240229-130509_007.png"
+        );
+        assert_eq!(
+            screenshot_file_name(UNIX_EPOCH, 1),
+            "screenshot_19700101-000000_001.png"
+        );
+    }
+
+    #[test]
+    fn test_indices_increase_within_the_same_second() {
+        let mut names = ScreenshotNames::new("shots");
+        let now = UNIX_EPOCH + Duration::from_secs(LEAP_DAY_SECONDS);
+
+        let first = names.next_path(now, |_| false);
+        let second = names.next_path(now, |_| false);
+
+        assert_ne!(first, second);
+        assert!(first.ends_with("screenshot_20240229-130509_001.png"));
+        assert!(second.ends_with("screenshot_20240229-130509_002.png"));
+    }
+
+    #[test]
+    fn test_existing_files_are_skipped() {
+        let mut names = ScreenshotNames::new("shots");
+        let now = UNIX_EPOCH + Duration::from_secs(LEAP_DAY_SECONDS);
+        let taken = [
+            Path::new("shots").join("screenshot_20240229-130509_001.png"),
+            Path::new("shots").join("screenshot_20240229-130509_002.png"),
+        ];
+
+        let path = names.next_path(now, |candidate| taken.iter().any(|t| t == candidate));
+
+        assert_eq!(
+            path,
+            Path::new("shots").join("screenshot_20240229-130509_003.png")
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Averages frame timings over a fixed window and produces a window title once per window.
+#[derive(Debug)]
+pub struct TitleStats {
+    base_title: String,
+    window_start: Option<Instant>,
+    frames: u32,
+    frame_seconds_total: f64,
+    last_reading: Option<TitleReading>,
+    paused: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TitleReading {
+    fps: f64,
+    frame_ms: f64,
+    draw_calls: usize,
+}
+
+impl TitleStats {
+    pub const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(base_title: impl Into<String>) -> Self {
+        Self {
+            base_title: base_title.into(),
+            window_start: None,
+            frames: 0,
+            frame_seconds_total: 0.0,
+            last_reading: None,
+            paused: false,
+        }
+    }
+
+    pub fn base_title(&self) -> &str {
+        &self.base_title
+    }
+
+    /// Marks the simulation as paused or running. Returns the new title right away when the state
+    /// changed, rather than waiting for the next averaging window.
+    pub fn set_paused(&mut self, paused: bool) -> Option<String> {
+        if self.paused == paused {
+            return None;
+        }
+        self.paused = paused;
+        Some(self.current_title())
+    }
+
+    pub fn current_title(&self) -> String {
+        match self.last_reading {
+            Some(reading) => format_title(
+                &self.base_title,
+                reading.fps,
+                reading.frame_ms,
+                reading.draw_calls,
+                self.paused,
+            ),
+            None if self.paused => format!("{} | Paused", self.base_title),
+            None => self.base_title.clone(),
+        }
+    }
+
+    /// Records a presented frame. Returns the new title when the averaging window is complete.
+    pub fn record_frame(
+        &mut self,
+        now: Instant,
+        frame_seconds: f64,
+        draw_calls: usize,
+    ) -> Option<String> {
+        let window_start = *self.window_start.get_or_insert(now);
+        self.frames += 1;
+        self.frame_seconds_total += frame_seconds;
+
+        let elapsed = now.duration_since(window_start);
+        if elapsed < Self::UPDATE_INTERVAL {
+            return None;
+        }
+
+        let fps = f64::from(self.frames) / elapsed.as_secs_f64();
+        let frame_ms = self.frame_seconds_total / f64::from(self.frames) * 1000.0;
+        self.window_start = Some(now);
+        self.frames = 0;
+        self.frame_seconds_total = 0.0;
+        self.last_reading = Some(TitleReading {
+            fps,
+            frame_ms,
+            draw_calls,
+        });
+        Some(self.current_title())
+    }
+}
+
+pub fn format_title(
+    base_title: &str,
+    fps: f64,
+    frame_ms: f64,
+    draw_calls: usize,
+    paused: bool,
+) -> String {
+    let title = format!("{base_title} | {fps:.0} FPS | {frame_ms:.2} ms | {draw_calls} draws");
+    if paused {
+        format!("{title} | Paused")
+    } else {
+        title
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_is_only_produced_once_per_interval() {
+        let mut stats = TitleStats::new("Hyakou");
+        let start = Instant::now();
+
+        assert!(stats.record_frame(start, 0.016, 3).is_none());
+        assert!(
+            stats
+                .record_frame(start + Duration::from_millis(500), 0.016, 3)
+                .is_none()
+        );
+        assert!(
+            stats
+                .record_frame(start + TitleStats::UPDATE_INTERVAL, 0.016, 3)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_title_averages_frames_in_window() {
+        let mut stats = TitleStats::new("Hyakou");
+        let start = Instant::now();
+        let frame = Duration::from_millis(20);
+
+        let mut title = None;
+        for index in 0..=50 {
+            let frame_seconds = if index % 2 == 0 { 0.01 } else { 0.03 };
+            title = stats.record_frame(start + frame * index, frame_seconds, 7);
+        }
+
+        // 51 frames over one second, alternating 10 ms and 30 ms.
+        assert_eq!(
+            title.as_deref(),
+            Some("Hyakou | 51 FPS | 19.80 ms | 7 draws")
+        );
+    }
+
+    #[test]
+    fn test_window_resets_after_update() {
+        let mut stats = TitleStats::new("Hyakou");
+        let start = Instant::now();
+
+        stats.record_frame(start, 0.5, 1);
+        stats.record_frame(start + TitleStats::UPDATE_INTERVAL, 0.5, 1);
+        let next = start + TitleStats::UPDATE_INTERVAL;
+
+        assert!(
+            stats
+                .record_frame(next + Duration::from_millis(10), 0.01, 1)
+                .is_none()
+        );
+        assert_eq!(
+            stats.record_frame(next + TitleStats::UPDATE_INTERVAL, 0.01, 1),
+            Some(format_title("Hyakou", 2.0, 10.0, 1, false))
+        );
+    }
+
+    #[test]
+    fn test_pausing_updates_title_immediately() {
+        let mut stats = TitleStats::new("Hyakou");
+        let start = Instant::now();
+
+        assert_eq!(stats.set_paused(true).as_deref(), Some("Hyakou | Paused"));
+        assert_eq!(stats.set_paused(true), None);
+
+        stats.record_frame(start, 0.01, 2);
+        assert_eq!(
+            stats.record_frame(start + TitleStats::UPDATE_INTERVAL, 0.01, 2),
+            Some(format_title("Hyakou", 2.0, 10.0, 2, true))
+        );
+        assert_eq!(
+            stats.set_paused(false),
+            Some(format_title("Hyakou", 2.0, 10.0, 2, false))
+        );
+    }
+}
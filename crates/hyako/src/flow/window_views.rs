@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use winit::window::WindowId;
+
+/// Open windows by id. The primary window drives input and the main surface; closing it ends the
+/// app, while secondary windows only add views of the scene and can be closed on their own.
+#[derive(Debug)]
+pub struct WindowViews<W> {
+    primary: Option<WindowId>,
+    windows: HashMap<WindowId, W>,
+}
+
+impl<W> Default for WindowViews<W> {
+    fn default() -> Self {
+        Self {
+            primary: None,
+            windows: HashMap::new(),
+        }
+    }
+}
+
+impl<W> WindowViews<W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_primary(&mut self, window_id: WindowId, window: W) {
+        self.primary = Some(window_id);
+        self.windows.insert(window_id, window);
+    }
+
+    pub fn add_secondary(&mut self, window_id: WindowId, window: W) {
+        self.windows.insert(window_id, window);
+    }
+
+    pub fn primary(&self) -> Option<&W> {
+        self.primary
+            .and_then(|window_id| self.windows.get(&window_id))
+    }
+
+    pub fn is_primary(&self, window_id: WindowId) -> bool {
+        self.primary == Some(window_id)
+    }
+
+    pub fn get(&self, window_id: WindowId) -> Option<&W> {
+        self.windows.get(&window_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&WindowId, &W)> {
+        self.windows.iter()
+    }
+
+    pub fn remove(&mut self, window_id: WindowId) -> Option<W> {
+        if self.is_primary(window_id) {
+            self.primary = None;
+        }
+        self.windows.remove(&window_id)
+    }
+
+    pub fn clear(&mut self) {
+        self.primary = None;
+        self.windows.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_windows_track_primary_and_secondary() {
+        let mut windows = WindowViews::new();
+        let main = WindowId::from(1);
+        let inspector = WindowId::from(2);
+
+        windows.set_primary(main, "main");
+        windows.add_secondary(inspector, "inspector");
+
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows.primary(), Some(&"main"));
+        assert!(windows.is_primary(main));
+        assert!(!windows.is_primary(inspector));
+        assert_eq!(windows.get(inspector), Some(&"inspector"));
+    }
+
+    #[test]
+    fn test_closing_secondary_window_keeps_primary() {
+        let mut windows = WindowViews::new();
+        let main = WindowId::from(1);
+        let inspector = WindowId::from(2);
+        windows.set_primary(main, "main");
+        windows.add_secondary(inspector, "inspector");
+
+        assert_eq!(windows.remove(inspector), Some("inspector"));
+
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows.primary(), Some(&"main"));
+        assert_eq!(windows.remove(inspector), None);
+    }
+
+    #[test]
+    fn test_removing_primary_clears_it() {
+        let mut windows = WindowViews::new();
+        let main = WindowId::from(1);
+        windows.set_primary(main, "main");
+
+        windows.remove(main);
+
+        assert!(windows.primary().is_none());
+        assert!(windows.is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+use std::mem::{offset_of, size_of};
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Vec3, Vec4};
+use hyakou_core::traits::BufferLayoutProvider;
+use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexStepMode};
+
+/// Per-instance data of a camera-facing marker quad, one per light. `radius` is the half size of
+/// the quad in world units.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct BillboardInstance {
+    pub position: Vec3,
+    pub radius: f32,
+    pub color: Vec4,
+}
+
+impl BillboardInstance {
+    const ATTRIBUTES: [VertexAttribute; 3] = wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32,
+        2 => Float32x4,
+    ];
+
+    pub fn new(position: Vec3, radius: f32, color: Vec4) -> Self {
+        Self {
+            position,
+            radius,
+            color,
+        }
+    }
+}
+
+const _: () = {
+    let attributes = &BillboardInstance::ATTRIBUTES;
+    assert!(size_of::<BillboardInstance>() == 32);
+    assert!(attributes[0].offset == offset_of!(BillboardInstance, position) as BufferAddress);
+    assert!(attributes[1].offset == offset_of!(BillboardInstance, radius) as BufferAddress);
+    assert!(attributes[2].offset == offset_of!(BillboardInstance, color) as BufferAddress);
+};
+
+impl BufferLayoutProvider for BillboardInstance {
+    fn vertex_buffer_layout() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: size_of::<Self>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
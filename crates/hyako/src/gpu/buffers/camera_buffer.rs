@@ -1,6 +1,11 @@
+use std::mem::offset_of;
+
 use bytemuck::{Pod, Zeroable};
-use glam::Mat4;
-use hyakou_core::{components::camera::camera::Camera, traits::BindGroupProvider};
+use glam::{Mat4, Vec3};
+use hyakou_core::{
+    components::camera::camera::Camera,
+    traits::{BindGroupProvider, WgslLayout},
+};
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, Buffer, BufferBinding, Device, ShaderStages,
@@ -10,17 +15,56 @@ use wgpu::{
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct CameraUniform {
     pub view_projection_matrix: Mat4,
+    /// World-space eye position, for view-dependent shading.
+    pub position: Vec3,
+    _padding: f32,
+    /// World-space camera axes, for geometry that faces the viewer like billboards.
+    pub right: Vec3,
+    _padding_right: f32,
+    pub up: Vec3,
+    _padding_up: f32,
+    /// Maps normalized device coordinates back to world space, e.g. a read back depth texel.
+    pub inverse_view_projection_matrix: Mat4,
+}
+
+impl WgslLayout for CameraUniform {
+    const WGSL_NAMES: &'static [&'static str] = &["Camera"];
+    const MEMBERS: &'static [(&'static str, usize)] = &[
+        (
+            "view_projection_matrix",
+            offset_of!(CameraUniform, view_projection_matrix),
+        ),
+        ("position", offset_of!(CameraUniform, position)),
+        ("right", offset_of!(CameraUniform, right)),
+        ("up", offset_of!(CameraUniform, up)),
+        (
+            "inverse_view_projection_matrix",
+            offset_of!(CameraUniform, inverse_view_projection_matrix),
+        ),
+    ];
 }
 
 impl CameraUniform {
     pub fn new() -> CameraUniform {
         Self {
             view_projection_matrix: Mat4::IDENTITY,
+            position: Vec3::ZERO,
+            _padding: 0.0,
+            right: Vec3::X,
+            _padding_right: 0.0,
+            up: Vec3::Y,
+            _padding_up: 0.0,
+            inverse_view_projection_matrix: Mat4::IDENTITY,
         }
     }
 
     pub fn update(&mut self, camera: &Camera) {
         self.view_projection_matrix = camera.build_view_proj_matrix();
+        self.inverse_view_projection_matrix = self.view_projection_matrix.inverse();
+        self.position = camera.eye;
+        let view = Mat4::look_at_rh(camera.eye, camera.target, camera.up);
+        self.right = view.row(0).truncate();
+        self.up = view.row(1).truncate();
     }
 }
 
@@ -30,7 +74,7 @@ impl BindGroupProvider for CameraUniform {
             label: Some("Camera Buffer"),
             entries: &[BindGroupLayoutEntry {
                 binding: 0,
-                visibility: ShaderStages::VERTEX,
+                visibility: ShaderStages::VERTEX_FRAGMENT,
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     has_dynamic_offset: false,
@@ -0,0 +1,62 @@
+use std::mem::{offset_of, size_of};
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Vec2, Vec4};
+use hyakou_core::traits::BufferLayoutProvider;
+use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexStepMode};
+
+use crate::renderer::text_overlay::glyph_layout::GlyphQuad;
+
+/// Per-instance data of one overlay character. `position` is the top-left corner and `size` the
+/// extent in clip space, so the shader needs no viewport uniform.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct GlyphInstance {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+    pub color: Vec4,
+}
+
+impl GlyphInstance {
+    const ATTRIBUTES: [VertexAttribute; 5] = wgpu::vertex_attr_array![
+        0 => Float32x2,
+        1 => Float32x2,
+        2 => Float32x2,
+        3 => Float32x2,
+        4 => Float32x4,
+    ];
+
+    /// Converts a pixel-space `quad` on a target of `target_size` pixels to clip space.
+    pub fn new(quad: &GlyphQuad, target_size: Vec2, color: Vec4) -> Self {
+        let to_clip = Vec2::new(2.0, -2.0) / target_size;
+        Self {
+            position: quad.position * to_clip + Vec2::new(-1.0, 1.0),
+            size: quad.size * to_clip,
+            uv_min: quad.uv_min,
+            uv_max: quad.uv_max,
+            color,
+        }
+    }
+}
+
+const _: () = {
+    let attributes = &GlyphInstance::ATTRIBUTES;
+    assert!(size_of::<GlyphInstance>() == 48);
+    assert!(attributes[0].offset == offset_of!(GlyphInstance, position) as BufferAddress);
+    assert!(attributes[1].offset == offset_of!(GlyphInstance, size) as BufferAddress);
+    assert!(attributes[2].offset == offset_of!(GlyphInstance, uv_min) as BufferAddress);
+    assert!(attributes[3].offset == offset_of!(GlyphInstance, uv_max) as BufferAddress);
+    assert!(attributes[4].offset == offset_of!(GlyphInstance, color) as BufferAddress);
+};
+
+impl BufferLayoutProvider for GlyphInstance {
+    fn vertex_buffer_layout() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: size_of::<Self>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::mem::{offset_of, size_of};
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec4};
+use hyakou_core::traits::BufferLayoutProvider;
+use wgpu::{
+    Buffer, BufferAddress, BufferDescriptor, BufferUsages, Device, Queue, VertexAttribute,
+    VertexBufferLayout, VertexStepMode,
+};
+
+use crate::gpu::buffers::{pool::BufferPool, storage::StorageLayout};
+
+/// Per-instance vertex data for the instanced shader variant. `color` tints the mesh and is free
+/// to carry an id in later passes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct InstanceData {
+    pub model_matrix: Mat4,
+    pub color: Vec4,
+}
+
+impl InstanceData {
+    pub const FIRST_SHADER_LOCATION: u32 = 4;
+    const ATTRIBUTES: [VertexAttribute; 5] = wgpu::vertex_attr_array![
+        4 => Float32x4,
+        5 => Float32x4,
+        6 => Float32x4,
+        7 => Float32x4,
+        8 => Float32x4,
+    ];
+
+    pub fn new(model_matrix: Mat4, color: Vec4) -> Self {
+        Self {
+            model_matrix,
+            color,
+        }
+    }
+}
+
+impl Default for InstanceData {
+    fn default() -> Self {
+        Self::new(Mat4::IDENTITY, Vec4::ONE)
+    }
+}
+
+// The attribute list has to describe the Rust struct exactly, or the shader reads garbage.
+const _: () = {
+    let column = size_of::<Vec4>() as BufferAddress;
+    let attributes = &InstanceData::ATTRIBUTES;
+    assert!(size_of::<InstanceData>() == 80);
+    assert!(offset_of!(InstanceData, model_matrix) == 0);
+    assert!(attributes[0].offset == 0);
+    assert!(attributes[1].offset == column);
+    assert!(attributes[2].offset == 2 * column);
+    assert!(attributes[3].offset == 3 * column);
+    assert!(attributes[4].offset == offset_of!(InstanceData, color) as BufferAddress);
+    assert!(attributes[0].shader_location == InstanceData::FIRST_SHADER_LOCATION);
+    assert!(attributes[4].shader_location == InstanceData::FIRST_SHADER_LOCATION + 4);
+};
+
+impl BufferLayoutProvider for InstanceData {
+    fn vertex_buffer_layout() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: size_of::<Self>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Vertex buffer of [`InstanceData`], bound next to the mesh's vertex buffer for instanced draws.
+#[derive(Debug)]
+pub struct InstanceBuffer {
+    label: String,
+    buffer: Buffer,
+    layout: StorageLayout,
+    len: u32,
+}
+
+impl InstanceBuffer {
+    const USAGE: BufferUsages = BufferUsages::VERTEX.union(BufferUsages::COPY_DST);
+
+    pub fn new(label: impl Into<String>, device: &Device, capacity: u64) -> Self {
+        let label = label.into();
+        let layout = Self::layout(capacity);
+        Self {
+            buffer: Self::create_buffer(&label, device, layout),
+            label,
+            layout,
+            len: 0,
+        }
+    }
+
+    /// Uploads one frame's worth of instances into a buffer from `pool`, which takes it back once
+    /// the frame has been submitted.
+    pub fn transient(
+        pool: &mut BufferPool,
+        device: &Device,
+        queue: &Queue,
+        instances: &[InstanceData],
+    ) -> Self {
+        let stride = size_of::<InstanceData>() as u64;
+        let buffer = pool.acquire(device, Self::USAGE, stride * instances.len() as u64);
+        queue.write_buffer(&buffer, 0, bytemuck::cast_slice(instances));
+        Self {
+            label: "Transient Instance Buffer".to_string(),
+            layout: Self::layout(buffer.size() / stride),
+            buffer,
+            len: instances.len() as u32,
+        }
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Number of instances from the last write, i.e. the instance count to draw.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.layout.capacity()
+    }
+
+    /// Replaces the buffer contents with `instances`, reallocating first when they do not fit.
+    pub fn write_instances(&mut self, device: &Device, queue: &Queue, instances: &[InstanceData]) {
+        if let Some(capacity) = self.layout.grown_capacity(instances.len() as u64) {
+            self.layout = Self::layout(capacity);
+            self.buffer = Self::create_buffer(&self.label, device, self.layout);
+        }
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(instances));
+        self.len = instances.len() as u32;
+    }
+
+    fn layout(capacity: u64) -> StorageLayout {
+        StorageLayout::new(size_of::<InstanceData>() as u64, capacity)
+            .expect("InstanceData has an aligned, non-zero size")
+    }
+
+    fn create_buffer(label: &str, device: &Device, layout: StorageLayout) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(label),
+            size: layout.byte_size(),
+            usage: Self::USAGE,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_layout_steps_per_instance_with_the_struct_stride() {
+        let layout = InstanceData::vertex_buffer_layout();
+
+        assert_eq!(layout.step_mode, VertexStepMode::Instance);
+        assert_eq!(layout.array_stride, 80);
+        assert_eq!(
+            layout
+                .attributes
+                .iter()
+                .map(|attribute| attribute.shader_location)
+                .collect::<Vec<_>>(),
+            vec![4, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn test_instance_bytes_follow_matrix_columns_then_color() {
+        let instance = InstanceData::new(
+            Mat4::from_cols(
+                Vec4::splat(1.0),
+                Vec4::splat(2.0),
+                Vec4::splat(3.0),
+                Vec4::splat(4.0),
+            ),
+            Vec4::new(0.5, 0.25, 0.125, 1.0),
+        );
+
+        let floats: &[f32] = bytemuck::cast_slice(bytemuck::bytes_of(&instance));
+
+        assert_eq!(&floats[0..4], &[1.0; 4]);
+        assert_eq!(&floats[12..16], &[4.0; 4]);
+        assert_eq!(&floats[16..20], &[0.5, 0.25, 0.125, 1.0]);
+    }
+}
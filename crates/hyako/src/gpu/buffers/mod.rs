@@ -1,3 +1,11 @@
+pub mod billboard;
 pub mod camera_buffer;
+pub mod glyph;
+pub mod instance;
 pub mod model_matrix;
+pub mod outline;
+pub mod picking;
+pub mod pool;
+pub mod staging;
+pub mod storage;
 pub mod uniform;
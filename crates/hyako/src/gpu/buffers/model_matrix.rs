@@ -1,6 +1,8 @@
+use std::mem::offset_of;
+
 use bytemuck::{Pod, Zeroable};
 use glam::Mat4;
-use hyakou_core::traits::BindGroupProvider;
+use hyakou_core::traits::{BindGroupProvider, WgslLayout};
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, Buffer, BufferBinding, Device, ShaderStages,
@@ -12,6 +14,14 @@ pub struct ModelMatrixUniform {
     pub model_matrix: Mat4,
 }
 
+/// Bound as a uniform (`Model`) or pushed as immediates (`Immediate`), depending on the
+/// [`ModelMatrixBindingMode`](hyakou_core::types::ModelMatrixBindingMode).
+impl WgslLayout for ModelMatrixUniform {
+    const WGSL_NAMES: &'static [&'static str] = &["Model", "Immediate"];
+    const MEMBERS: &'static [(&'static str, usize)] =
+        &[("model_matrix", offset_of!(ModelMatrixUniform, model_matrix))];
+}
+
 impl ModelMatrixUniform {
     pub fn new(model_matrix: Mat4) -> Self {
         Self { model_matrix }
@@ -0,0 +1,88 @@
+use std::mem::offset_of;
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec4};
+use hyakou_core::traits::{BindGroupProvider, WgslLayout};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, Buffer, BufferBinding, Device, ShaderStages,
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct OutlineUniform {
+    pub model_matrix: Mat4,
+    pub color: Vec4,
+    pub thickness: f32,
+    _padding: [f32; 3],
+}
+
+impl WgslLayout for OutlineUniform {
+    const WGSL_NAMES: &'static [&'static str] = &["Outline"];
+    const MEMBERS: &'static [(&'static str, usize)] = &[
+        ("model_matrix", offset_of!(OutlineUniform, model_matrix)),
+        ("color", offset_of!(OutlineUniform, color)),
+        ("thickness", offset_of!(OutlineUniform, thickness)),
+    ];
+}
+
+impl OutlineUniform {
+    pub fn new(model_matrix: Mat4, color: Vec4, thickness: f32) -> Self {
+        Self {
+            model_matrix,
+            color,
+            thickness,
+            _padding: [0.0; 3],
+        }
+    }
+}
+
+impl BindGroupProvider for OutlineUniform {
+    fn bind_group_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Outline Buffer"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        })
+    }
+
+    fn bind_group(
+        device: &Device,
+        buffer: &Buffer,
+        bind_group_layout: &BindGroupLayout,
+    ) -> BindGroup {
+        device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Outline Bind Group"),
+            layout: bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(BufferBinding {
+                    buffer,
+                    offset: 0,
+                    size: None,
+                }),
+            }],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline_uniform_is_16_byte_aligned() {
+        let uniform = OutlineUniform::new(Mat4::IDENTITY, Vec4::ONE, 0.05);
+
+        assert_eq!(std::mem::size_of::<OutlineUniform>() % 16, 0);
+        assert_eq!(bytemuck::bytes_of(&uniform).len(), 96);
+    }
+}
@@ -0,0 +1,74 @@
+use std::mem::offset_of;
+
+use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
+use hyakou_core::traits::{BindGroupProvider, WgslLayout};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, Buffer, BufferBinding, Device, ShaderStages,
+};
+
+/// Per-mesh data of the picking pass. `object_id` is what the mesh writes into the id target;
+/// zero is reserved for the background.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct PickingUniform {
+    pub model_matrix: Mat4,
+    pub object_id: u32,
+    _padding: [u32; 3],
+}
+
+impl WgslLayout for PickingUniform {
+    const WGSL_NAMES: &'static [&'static str] = &["Picking"];
+    const MEMBERS: &'static [(&'static str, usize)] = &[
+        ("model_matrix", offset_of!(PickingUniform, model_matrix)),
+        ("object_id", offset_of!(PickingUniform, object_id)),
+    ];
+}
+
+impl PickingUniform {
+    pub fn new(model_matrix: Mat4, object_id: u32) -> Self {
+        Self {
+            model_matrix,
+            object_id,
+            _padding: [0; 3],
+        }
+    }
+}
+
+impl BindGroupProvider for PickingUniform {
+    fn bind_group_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Picking Buffer"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        })
+    }
+
+    fn bind_group(
+        device: &Device,
+        buffer: &Buffer,
+        bind_group_layout: &BindGroupLayout,
+    ) -> BindGroup {
+        device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Picking Bind Group"),
+            layout: bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(BufferBinding {
+                    buffer,
+                    offset: 0,
+                    size: None,
+                }),
+            }],
+        })
+    }
+}
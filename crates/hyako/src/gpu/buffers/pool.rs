@@ -0,0 +1,229 @@
+use std::collections::{HashMap, VecDeque};
+
+use wgpu::{Buffer, BufferAddress, BufferDescriptor, BufferUsages, Device};
+
+/// Creates the buffers a [`BufferPool`] hands out. Implemented for [`Device`]; tests plug in a
+/// counting fake.
+pub trait BufferAllocator {
+    type Buffer: Clone;
+
+    fn create_buffer(&self, usage: BufferUsages, size: BufferAddress) -> Self::Buffer;
+}
+
+impl BufferAllocator for Device {
+    type Buffer = Buffer;
+
+    fn create_buffer(&self, usage: BufferUsages, size: BufferAddress) -> Buffer {
+        Device::create_buffer(
+            self,
+            &BufferDescriptor {
+                label: Some("Pooled Buffer"),
+                size,
+                usage,
+                mapped_at_creation: false,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PoolKey {
+    usage: BufferUsages,
+    size_class: BufferAddress,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// Requests served by a reclaimed buffer.
+    pub hits: u64,
+    /// Requests that had to create a buffer.
+    pub misses: u64,
+    /// Buffers the pool owns, in use or not.
+    pub allocated: usize,
+    /// Most buffers handed out but not yet reclaimed at any one time.
+    pub high_water_mark: usize,
+}
+
+/// Short-lived buffers for per-frame data such as instances. A buffer handed out in frame `n`
+/// is reused no earlier than frame `n + frames_in_flight`, by which point the submission reading
+/// it has completed. Requests are rounded up to power-of-two size classes, so differently sized
+/// uploads still share buffers.
+#[derive(Debug)]
+pub struct BufferPool<B = Buffer> {
+    frame: u64,
+    frames_in_flight: u64,
+    in_flight: VecDeque<(u64, PoolKey, B)>,
+    available: HashMap<PoolKey, Vec<B>>,
+    stats: BufferPoolStats,
+}
+
+impl<B: Clone> BufferPool<B> {
+    pub const DEFAULT_FRAMES_IN_FLIGHT: u64 = 2;
+    pub const MIN_SIZE_CLASS: BufferAddress = 256;
+
+    pub fn new(frames_in_flight: u64) -> Self {
+        Self {
+            frame: 0,
+            frames_in_flight: frames_in_flight.max(1),
+            in_flight: VecDeque::new(),
+            available: HashMap::new(),
+            stats: BufferPoolStats::default(),
+        }
+    }
+
+    /// The buffer size a request of `size` bytes is served with.
+    pub fn size_class(size: BufferAddress) -> BufferAddress {
+        size.max(Self::MIN_SIZE_CLASS).next_power_of_two()
+    }
+
+    /// A buffer of at least `size` bytes with exactly `usage`, valid until the end of the frame.
+    pub fn acquire<A>(&mut self, allocator: &A, usage: BufferUsages, size: BufferAddress) -> B
+    where
+        A: BufferAllocator<Buffer = B> + ?Sized,
+    {
+        let key = PoolKey {
+            usage,
+            size_class: Self::size_class(size),
+        };
+        let buffer = match self.available.get_mut(&key).and_then(Vec::pop) {
+            Some(buffer) => {
+                self.stats.hits += 1;
+                buffer
+            }
+            None => {
+                self.stats.misses += 1;
+                self.stats.allocated += 1;
+                allocator.create_buffer(usage, key.size_class)
+            }
+        };
+        self.in_flight.push_back((self.frame, key, buffer.clone()));
+        self.stats.high_water_mark = self.stats.high_water_mark.max(self.in_flight.len());
+        buffer
+    }
+
+    /// Call once the frame's work was submitted. Reclaims the buffers of frames old enough that
+    /// the GPU is done with them.
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+        while let Some((frame, _, _)) = self.in_flight.front() {
+            if frame + self.frames_in_flight > self.frame {
+                break;
+            }
+            let (_, key, buffer) = self.in_flight.pop_front().expect("front was just checked");
+            self.available.entry(key).or_default().push(buffer);
+        }
+    }
+
+    pub fn in_use(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    pub fn stats(&self) -> BufferPoolStats {
+        self.stats
+    }
+}
+
+impl<B: Clone> Default for BufferPool<B> {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_FRAMES_IN_FLIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Buffers are `(id, usage, size)` so tests can tell which one they got back.
+    #[derive(Default)]
+    struct FakeAllocator {
+        created: RefCell<Vec<(usize, BufferUsages, BufferAddress)>>,
+    }
+
+    impl BufferAllocator for FakeAllocator {
+        type Buffer = (usize, BufferUsages, BufferAddress);
+
+        fn create_buffer(&self, usage: BufferUsages, size: BufferAddress) -> Self::Buffer {
+            let mut created = self.created.borrow_mut();
+            let buffer = (created.len(), usage, size);
+            created.push(buffer);
+            buffer
+        }
+    }
+
+    const INSTANCES: BufferUsages = BufferUsages::VERTEX.union(BufferUsages::COPY_DST);
+
+    #[test]
+    fn test_requests_round_up_to_power_of_two_size_classes() {
+        assert_eq!(BufferPool::<()>::size_class(1), 256);
+        assert_eq!(BufferPool::<()>::size_class(256), 256);
+        assert_eq!(BufferPool::<()>::size_class(257), 512);
+        assert_eq!(BufferPool::<()>::size_class(80 * 100), 8192);
+    }
+
+    #[test]
+    fn test_buffers_are_reused_only_after_their_frames_in_flight() {
+        let allocator = FakeAllocator::default();
+        let mut pool = BufferPool::new(2);
+        let first = pool.acquire(&allocator, INSTANCES, 100);
+
+        pool.end_frame();
+        let second = pool.acquire(&allocator, INSTANCES, 100);
+        assert_ne!(second, first);
+
+        pool.end_frame();
+        let third = pool.acquire(&allocator, INSTANCES, 200);
+        assert_eq!(third, first);
+
+        assert_eq!(
+            pool.stats(),
+            BufferPoolStats {
+                hits: 1,
+                misses: 2,
+                allocated: 2,
+                high_water_mark: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_usage_and_size_class_are_never_mixed() {
+        let allocator = FakeAllocator::default();
+        let mut pool = BufferPool::new(1);
+        pool.acquire(&allocator, INSTANCES, 100);
+        pool.end_frame();
+
+        let uniform = pool.acquire(
+            &allocator,
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            100,
+        );
+        let larger = pool.acquire(&allocator, INSTANCES, 1000);
+        let reused = pool.acquire(&allocator, INSTANCES, 64);
+
+        assert_eq!(uniform.1, BufferUsages::UNIFORM | BufferUsages::COPY_DST);
+        assert_eq!(larger.2, 1024);
+        assert_eq!(reused.0, 0);
+        assert_eq!(pool.stats().misses, 3);
+        assert_eq!(pool.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_high_water_mark_tracks_the_busiest_frame() {
+        let allocator = FakeAllocator::default();
+        let mut pool = BufferPool::new(1);
+        for _ in 0..3 {
+            pool.acquire(&allocator, INSTANCES, 16);
+        }
+        pool.end_frame();
+        assert_eq!(pool.in_use(), 0);
+
+        pool.acquire(&allocator, INSTANCES, 16);
+        pool.end_frame();
+
+        assert_eq!(pool.stats().high_water_mark, 3);
+        assert_eq!(pool.stats().allocated, 3);
+        assert_eq!(pool.stats().hits, 1);
+    }
+}
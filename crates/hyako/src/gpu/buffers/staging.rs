@@ -0,0 +1,125 @@
+use std::cell::{Cell, RefCell};
+
+use hyakou_core::types::QueueWriter;
+use wgpu::{
+    Buffer, BufferAddress, BufferSize, CommandEncoder, CommandEncoderDescriptor, Device, Queue,
+    util::StagingBelt,
+};
+
+/// Batches buffer writes through a [`StagingBelt`] instead of letting every
+/// `Queue::write_buffer` allocate its own staging copy. Writes are recorded into one encoder and
+/// land when [`Self::submit`] runs. Chunks are recalled with `map_async`, so nothing blocks on
+/// the web.
+pub struct UploadBelt {
+    device: Device,
+    belt: RefCell<StagingBelt>,
+    encoder: RefCell<Option<CommandEncoder>>,
+    staged_bytes: Cell<u64>,
+}
+
+impl UploadBelt {
+    /// Large enough for the per-frame uniforms without a second chunk.
+    pub const DEFAULT_CHUNK_SIZE: BufferAddress = 64 * 1024;
+
+    pub fn new(device: Device, chunk_size: BufferAddress) -> Self {
+        Self {
+            belt: RefCell::new(StagingBelt::new(device.clone(), chunk_size)),
+            device,
+            encoder: RefCell::new(None),
+            staged_bytes: Cell::new(0),
+        }
+    }
+
+    /// Whether writes are waiting for [`Self::submit`].
+    pub fn has_pending(&self) -> bool {
+        self.encoder.borrow().is_some()
+    }
+
+    /// Bytes written since the last submit.
+    pub fn staged_bytes(&self) -> u64 {
+        self.staged_bytes.get()
+    }
+
+    /// Finishes the belt, submits the recorded copies and recalls the chunks. Returns false when
+    /// nothing was written.
+    pub fn submit(&mut self, queue: &Queue) -> bool {
+        let Some(encoder) = self.encoder.get_mut().take() else {
+            return false;
+        };
+        let belt = self.belt.get_mut();
+        belt.finish();
+        queue.submit(std::iter::once(encoder.finish()));
+        belt.recall();
+        self.staged_bytes.set(0);
+        true
+    }
+}
+
+impl QueueWriter for UploadBelt {
+    /// `offset` and the length of `data` have to be multiples of [`wgpu::COPY_BUFFER_ALIGNMENT`].
+    fn write_buffer(&self, buffer: &Buffer, offset: BufferAddress, data: &[u8]) {
+        let Some(size) = BufferSize::new(data.len() as u64) else {
+            return;
+        };
+        let mut encoder = self.encoder.borrow_mut();
+        let encoder = encoder.get_or_insert_with(|| {
+            self.device
+                .create_command_encoder(&CommandEncoderDescriptor {
+                    label: Some("Upload Belt Encoder"),
+                })
+        });
+        self.belt
+            .borrow_mut()
+            .write_buffer(encoder, buffer, offset, size)
+            .copy_from_slice(data);
+        self.staged_bytes.set(self.staged_bytes.get() + size.get());
+    }
+}
+
+impl std::fmt::Debug for UploadBelt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadBelt")
+            .field("pending", &self.has_pending())
+            .field("staged_bytes", &self.staged_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::bytes_of;
+    use glam::Vec4;
+    use wgpu::{BufferDescriptor, BufferUsages};
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+        wrappers::MockSurfaceProvider,
+    };
+
+    #[test]
+    fn test_writes_are_batched_until_submit() {
+        if !gpu_tests_enabled("test_writes_are_batched_until_submit") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let buffer = ctx.device.create_buffer(&BufferDescriptor {
+            label: Some("Upload Target"),
+            size: 64,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut belt = UploadBelt::new((*ctx.device).clone(), UploadBelt::DEFAULT_CHUNK_SIZE);
+        assert!(!belt.submit(&ctx.queue));
+
+        belt.write_buffer(&buffer, 0, bytes_of(&Vec4::ONE));
+        belt.write_buffer(&buffer, 16, bytes_of(&Vec4::ZERO));
+        belt.write_buffer(&buffer, 32, &[]);
+
+        assert!(belt.has_pending());
+        assert_eq!(belt.staged_bytes(), 32);
+        assert!(belt.submit(&ctx.queue));
+        assert!(!belt.has_pending());
+        assert_eq!(belt.staged_bytes(), 0);
+    }
+}
@@ -0,0 +1,304 @@
+use std::{mem::size_of, ops::Deref};
+
+use anyhow::{Result, anyhow};
+use bytemuck::Pod;
+use hyakou_core::types::{
+    BaseBuffer, BaseId, QueueWriter,
+    ids::{StorageBufferId, UniformResourceId},
+};
+use wgpu::{Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Queue};
+
+/// What a write did to the underlying buffer. After a reallocation every bind group that
+/// references the buffer still points at the old one and has to be rebuilt.
+#[must_use]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageWrite {
+    InPlace,
+    Reallocated,
+}
+
+impl StorageWrite {
+    pub fn requires_rebind(self) -> bool {
+        self == Self::Reallocated
+    }
+}
+
+/// Element stride and capacity of a storage buffer, in elements unless a name says bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageLayout {
+    stride: u64,
+    capacity: u64,
+}
+
+impl StorageLayout {
+    /// `stride` has to be a non-zero multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`] so element
+    /// writes stay aligned.
+    pub fn new(stride: u64, capacity: u64) -> Result<Self> {
+        if stride == 0 || !stride.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) {
+            return Err(anyhow!(
+                "Storage element stride {stride} must be a non-zero multiple of {}",
+                wgpu::COPY_BUFFER_ALIGNMENT
+            ));
+        }
+        Ok(Self {
+            stride,
+            capacity: capacity.max(1),
+        })
+    }
+
+    pub fn stride(&self) -> u64 {
+        self.stride
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    pub fn byte_size(&self) -> u64 {
+        self.stride * self.capacity
+    }
+
+    pub fn byte_offset(&self, element: u64) -> u64 {
+        self.stride * element
+    }
+
+    /// The capacity to reallocate to so `required` elements fit, doubling to keep growth
+    /// amortized, or `None` when they already fit.
+    pub fn grown_capacity(&self, required: u64) -> Option<u64> {
+        if required <= self.capacity {
+            return None;
+        }
+        Some(required.max(self.capacity.saturating_mul(2)))
+    }
+}
+
+/// A `STORAGE` buffer holding an array of equally sized elements that grows when written past
+/// its end.
+#[derive(Debug)]
+pub struct StorageBuffer {
+    id: StorageBufferId,
+    buffer: Buffer,
+    layout: StorageLayout,
+    len: u64,
+}
+
+impl Deref for StorageBuffer {
+    type Target = Buffer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl StorageBuffer {
+    const USAGE: BufferUsages = BufferUsages::STORAGE
+        .union(BufferUsages::COPY_DST)
+        .union(BufferUsages::COPY_SRC);
+
+    pub fn new(id: StorageBufferId, device: &Device, layout: StorageLayout) -> Self {
+        Self {
+            buffer: Self::create_buffer(&id, device, layout),
+            id,
+            layout,
+            len: 0,
+        }
+    }
+
+    /// A buffer sized for `capacity` elements of `T`.
+    pub fn with_capacity<T: Pod>(
+        id: StorageBufferId,
+        device: &Device,
+        capacity: u64,
+    ) -> Result<Self> {
+        let layout = StorageLayout::new(size_of::<T>() as u64, capacity)?;
+        Ok(Self::new(id, device, layout))
+    }
+
+    pub fn id(&self) -> &StorageBufferId {
+        &self.id
+    }
+
+    pub fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
+    /// One past the highest element written so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes `elements` starting at element `offset`, growing the buffer first if they do not
+    /// fit. Existing contents survive a reallocation.
+    pub fn write_elements<T: Pod>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        offset: u64,
+        elements: &[T],
+    ) -> Result<StorageWrite> {
+        self.stage_elements(device, queue, queue, offset, elements)
+    }
+
+    /// Same as [`Self::write_elements`], but the elements go through `writer`, e.g. an
+    /// [`UploadBelt`](crate::gpu::buffers::staging::UploadBelt). A growth copy is still submitted
+    /// on `queue` right away, so it lands before the staged elements.
+    pub fn stage_elements<T: Pod>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        writer: &impl QueueWriter,
+        offset: u64,
+        elements: &[T],
+    ) -> Result<StorageWrite> {
+        if size_of::<T>() as u64 != self.layout.stride() {
+            return Err(anyhow!(
+                "Element size {} does not match the stride {} of storage buffer `{}`",
+                size_of::<T>(),
+                self.layout.stride(),
+                self.id.get()
+            ));
+        }
+
+        let end = offset + elements.len() as u64;
+        let write = match self.layout.grown_capacity(end) {
+            Some(capacity) => {
+                self.reallocate(device, queue, capacity)?;
+                StorageWrite::Reallocated
+            }
+            None => StorageWrite::InPlace,
+        };
+
+        // Staged writes land before the next submission, so the copy above has to be submitted
+        // first or it would overwrite them with the old contents.
+        writer.write_buffer(
+            &self.buffer,
+            self.layout.byte_offset(offset),
+            bytemuck::cast_slice(elements),
+        );
+        self.len = self.len.max(end);
+        Ok(write)
+    }
+
+    fn reallocate(&mut self, device: &Device, queue: &Queue, capacity: u64) -> Result<()> {
+        let layout = StorageLayout::new(self.layout.stride(), capacity)?;
+        let buffer = Self::create_buffer(&self.id, device, layout);
+
+        if self.len > 0 {
+            let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Storage Buffer Growth Encoder"),
+            });
+            encoder.copy_buffer_to_buffer(
+                &self.buffer,
+                0,
+                &buffer,
+                0,
+                self.layout.byte_offset(self.len),
+            );
+            queue.submit(std::iter::once(encoder.finish()));
+        }
+
+        self.buffer = buffer;
+        self.layout = layout;
+        Ok(())
+    }
+
+    fn create_buffer(id: &StorageBufferId, device: &Device, layout: StorageLayout) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(id.get()),
+            size: layout.byte_size(),
+            usage: Self::USAGE,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+impl BaseBuffer for StorageBuffer {
+    fn get_buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    fn get_id_as_string(&self) -> &str {
+        self.id.get()
+    }
+
+    fn get_id_cloned(&self) -> Box<dyn BaseId> {
+        Box::new(self.id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec4;
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+        wrappers::MockSurfaceProvider,
+    };
+
+    #[test]
+    fn test_layout_converts_elements_to_bytes() {
+        let layout = StorageLayout::new(16, 8).unwrap();
+
+        assert_eq!(layout.byte_size(), 128);
+        assert_eq!(layout.byte_offset(0), 0);
+        assert_eq!(layout.byte_offset(3), 48);
+    }
+
+    #[test]
+    fn test_layout_rejects_unaligned_or_empty_strides() {
+        assert!(StorageLayout::new(0, 4).is_err());
+        assert!(StorageLayout::new(6, 4).is_err());
+        assert_eq!(StorageLayout::new(12, 0).unwrap().capacity(), 1);
+    }
+
+    #[test]
+    fn test_growth_doubles_or_jumps_to_the_required_capacity() {
+        let layout = StorageLayout::new(16, 4).unwrap();
+
+        assert_eq!(layout.grown_capacity(4), None);
+        assert_eq!(layout.grown_capacity(5), Some(8));
+        assert_eq!(layout.grown_capacity(20), Some(20));
+    }
+
+    #[test]
+    fn test_only_reallocation_requires_a_rebind() {
+        assert!(StorageWrite::Reallocated.requires_rebind());
+        assert!(!StorageWrite::InPlace.requires_rebind());
+    }
+
+    #[test]
+    fn test_writing_past_the_end_reallocates() {
+        if !gpu_tests_enabled("test_writing_past_the_end_reallocates") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut buffer = StorageBuffer::with_capacity::<Vec4>(
+            StorageBufferId::new("Test Storage".to_string()),
+            &ctx.device,
+            2,
+        )
+        .unwrap();
+
+        let write = buffer
+            .write_elements(&ctx.device, &ctx.queue, 0, &[Vec4::ONE; 2])
+            .unwrap();
+        assert_eq!(write, StorageWrite::InPlace);
+
+        let write = buffer
+            .write_elements(&ctx.device, &ctx.queue, 2, &[Vec4::ZERO; 3])
+            .unwrap();
+        assert!(write.requires_rebind());
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(buffer.size(), 5 * 16);
+        assert!(
+            buffer
+                .write_elements(&ctx.device, &ctx.queue, 0, &[1.0_f32])
+                .is_err()
+        );
+    }
+}
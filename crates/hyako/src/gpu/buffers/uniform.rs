@@ -4,6 +4,7 @@ use hyakou_core::{
     Shared,
     types::{
         BaseBuffer, BaseId, TransformBuffer,
+        generation::Generation,
         ids::{UniformBufferId, UniformResourceId},
         transform::Transform,
     },
@@ -19,6 +20,7 @@ pub struct UniformBuffer {
     id: UniformBufferId,
     buffer: Buffer,
     transform: Shared<Transform>,
+    uploaded_generation: Option<Generation>,
 }
 
 impl Deref for UniformBuffer {
@@ -44,8 +46,23 @@ impl UniformBuffer {
                 usage: BufferUsages::COPY_DST | BufferUsages::UNIFORM,
             }),
             transform,
+            uploaded_generation: None,
         }
     }
+
+    pub fn id(&self) -> &UniformBufferId {
+        &self.id
+    }
+
+    /// Whether the buffer holds something older than `generation`. A fresh buffer is always stale,
+    /// since the generation of its initial contents is unknown.
+    pub fn is_stale(&self, generation: Generation) -> bool {
+        self.uploaded_generation != Some(generation)
+    }
+
+    pub fn mark_uploaded(&mut self, generation: Generation) {
+        self.uploaded_generation = Some(generation);
+    }
 }
 
 impl BaseBuffer for UniformBuffer {
@@ -66,3 +83,89 @@ impl TransformBuffer for UniformBuffer {
         self.transform.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use bytemuck::bytes_of;
+    use glam::{Mat4, Vec3};
+    use hyakou_core::{
+        SharedAccess, shared,
+        types::{QueueWriter, lock_busy::LockBusy},
+    };
+    use wgpu::BufferAddress;
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+        wrappers::MockSurfaceProvider,
+    };
+
+    /// Records writes instead of submitting them, so the uploaded bytes can be inspected.
+    #[derive(Default)]
+    struct RecordingQueue {
+        writes: RefCell<Vec<(BufferAddress, Vec<u8>)>>,
+    }
+
+    impl QueueWriter for RecordingQueue {
+        fn write_buffer(&self, _buffer: &Buffer, offset: BufferAddress, data: &[u8]) {
+            self.writes.borrow_mut().push((offset, data.to_vec()));
+        }
+    }
+
+    fn uniform_buffer(device: &Device, transform: Shared<Transform>) -> UniformBuffer {
+        UniformBuffer::new(
+            UniformBufferId::new("Test Transform".to_string()),
+            device,
+            bytes_of(&Mat4::IDENTITY),
+            transform,
+        )
+    }
+
+    #[test]
+    fn test_upload_current_writes_the_transform_matrix() {
+        if !gpu_tests_enabled("test_upload_current_writes_the_transform_matrix") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let transform = shared(Transform::default());
+        transform.write_shared(|t| t.translate(Vec3::new(1.0, 2.0, 3.0)));
+        let expected = transform.read_shared(Transform::get_matrix);
+        let mut buffer = uniform_buffer(&ctx.device, transform);
+        let queue = RecordingQueue::default();
+
+        buffer.upload_current(&queue).unwrap();
+        buffer
+            .upload_with(&queue, |transform| transform.position)
+            .unwrap();
+
+        assert_eq!(
+            queue.writes.into_inner(),
+            vec![
+                (0, bytes_of(&expected).to_vec()),
+                (0, bytes_of(&Vec3::new(1.0, 2.0, 3.0)).to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upload_skips_a_locked_transform_with_lock_busy() {
+        if !gpu_tests_enabled("test_upload_skips_a_locked_transform_with_lock_busy") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let transform = shared(Transform::default());
+        let mut buffer = uniform_buffer(&ctx.device, transform.clone());
+        let queue = RecordingQueue::default();
+
+        let result = transform.write_shared(|_| buffer.upload_current(&queue));
+
+        let error = result.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<LockBusy>().map(LockBusy::resource),
+            Some("Test Transform")
+        );
+        assert!(queue.writes.borrow().is_empty());
+    }
+}
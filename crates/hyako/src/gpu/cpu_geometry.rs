@@ -0,0 +1,221 @@
+use std::mem::size_of;
+
+use glam::Vec3;
+use hyakou_core::geometry::{
+    aabb::Aabb,
+    ray::{Ray, TriangleHit},
+    vertices::Vertex,
+};
+
+/// How much of a mesh's geometry stays in CPU memory after it was uploaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum CpuGeometryRetention {
+    /// Bounds only. Enough for framing, but meshes cannot be picked.
+    AabbOnly,
+    /// Positions and indices, so meshes can be picked by raycast.
+    #[default]
+    PositionsAndIndicesOnly,
+    /// Complete vertices as well, so the GPU buffers can be re-uploaded after device loss with
+    /// any in-place edits intact.
+    Full,
+}
+
+/// The CPU-side geometry a [`RenderMesh`](crate::gpu::render_mesh::RenderMesh) keeps, trimmed to
+/// a [`CpuGeometryRetention`]. Meshes share it behind an `Arc`, so duplicates cost nothing extra.
+#[derive(Debug, Clone, Default)]
+pub struct CpuGeometry {
+    retention: CpuGeometryRetention,
+    vertices: Vec<Vertex>,
+    positions: Vec<Vec3>,
+    indices: Vec<u32>,
+    /// Kept at every retention, like the bounds.
+    vertex_count: usize,
+    local_aabb: Option<Aabb>,
+}
+
+impl CpuGeometry {
+    pub fn new(vertices: &[Vertex], indices: &[u32], retention: CpuGeometryRetention) -> Self {
+        let positions: Vec<Vec3> = vertices.iter().map(|vertex| vertex.position).collect();
+        let local_aabb = Aabb::from_points(positions.iter().copied());
+        Self {
+            retention: CpuGeometryRetention::Full,
+            vertices: vertices.to_vec(),
+            positions,
+            indices: indices.to_vec(),
+            vertex_count: vertices.len(),
+            local_aabb,
+        }
+        .trimmed(retention)
+    }
+
+    /// A copy holding no more than `retention` allows. Dropped data cannot come back, so asking
+    /// for more than is retained keeps what there is.
+    pub fn trimmed(&self, retention: CpuGeometryRetention) -> Self {
+        let retention = self.retention().min(retention);
+        let keep_positions = retention >= CpuGeometryRetention::PositionsAndIndicesOnly;
+        Self {
+            retention,
+            vertices: if retention == CpuGeometryRetention::Full {
+                self.vertices.clone()
+            } else {
+                Vec::new()
+            },
+            positions: if keep_positions {
+                self.positions.clone()
+            } else {
+                Vec::new()
+            },
+            indices: if keep_positions {
+                self.indices.clone()
+            } else {
+                Vec::new()
+            },
+            vertex_count: self.vertex_count,
+            local_aabb: self.local_aabb,
+        }
+    }
+
+    pub fn retention(&self) -> CpuGeometryRetention {
+        self.retention
+    }
+
+    /// `None` unless the retention is [`CpuGeometryRetention::Full`].
+    pub fn vertices(&self) -> Option<&[Vertex]> {
+        (self.retention() == CpuGeometryRetention::Full).then_some(self.vertices.as_slice())
+    }
+
+    pub fn indices(&self) -> Option<&[u32]> {
+        self.has_triangles().then_some(self.indices.as_slice())
+    }
+
+    pub fn positions(&self) -> Option<&[Vec3]> {
+        self.has_triangles().then_some(self.positions.as_slice())
+    }
+
+    /// Vertices the geometry had when uploaded, even if they were not retained.
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    pub fn local_aabb(&self) -> Option<Aabb> {
+        self.local_aabb
+    }
+
+    /// Nearest triangle `local_ray` hits. Always `None` when triangles were not retained.
+    pub fn raycast(&self, local_ray: &Ray, cull_back_faces: bool) -> Option<TriangleHit> {
+        local_ray.intersect_aabb(self.local_aabb.as_ref()?)?;
+        local_ray.intersect_triangles(self.positions()?, self.indices()?, cull_back_faces)
+    }
+
+    /// Heap memory held for this geometry.
+    pub fn byte_size(&self) -> usize {
+        self.vertices.len() * size_of::<Vertex>()
+            + self.positions.len() * size_of::<Vec3>()
+            + self.indices.len() * size_of::<u32>()
+    }
+
+    /// Applies new vertices, keeping only what the current retention allows.
+    pub fn set_vertices(&mut self, vertices: &[Vertex]) {
+        let indices = std::mem::take(&mut self.indices);
+        *self = Self::new(vertices, &indices, self.retention());
+    }
+
+    pub fn set_indices(&mut self, indices: &[u32]) {
+        if self.has_triangles() {
+            self.indices = indices.to_vec();
+        }
+    }
+
+    fn has_triangles(&self) -> bool {
+        self.retention() >= CpuGeometryRetention::PositionsAndIndicesOnly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> (Vec<Vertex>, Vec<u32>) {
+        let vertex = |x: f32, y: f32| Vertex {
+            position: Vec3::new(x, y, 0.0),
+            ..Vertex::default()
+        };
+        (
+            vec![
+                vertex(-1.0, -1.0),
+                vertex(1.0, -1.0),
+                vertex(1.0, 1.0),
+                vertex(-1.0, 1.0),
+            ],
+            vec![0, 1, 2, 0, 2, 3],
+        )
+    }
+
+    fn ray_at_quad() -> Ray {
+        Ray::new(Vec3::new(0.25, 0.25, 5.0), Vec3::NEG_Z)
+    }
+
+    #[test]
+    fn test_retention_policy_decides_what_is_kept() {
+        let (vertices, indices) = quad();
+
+        let full = CpuGeometry::new(&vertices, &indices, CpuGeometryRetention::Full);
+        let picking = CpuGeometry::new(
+            &vertices,
+            &indices,
+            CpuGeometryRetention::PositionsAndIndicesOnly,
+        );
+        let bounds = CpuGeometry::new(&vertices, &indices, CpuGeometryRetention::AabbOnly);
+
+        assert_eq!(full.vertices().map(<[Vertex]>::len), Some(4));
+        assert!(picking.vertices().is_none());
+        assert_eq!(picking.indices(), Some(indices.as_slice()));
+        assert!(bounds.positions().is_none());
+        assert!(bounds.indices().is_none());
+        for geometry in [&full, &picking, &bounds] {
+            assert_eq!(
+                geometry.local_aabb(),
+                Aabb::from_points(vertices.iter().map(|vertex| vertex.position))
+            );
+        }
+        for geometry in [&full, &picking, &bounds] {
+            assert_eq!(geometry.vertex_count(), 4);
+        }
+        assert!(full.byte_size() > picking.byte_size());
+        assert_eq!(bounds.byte_size(), 0);
+    }
+
+    #[test]
+    fn test_geometry_without_vertices_has_no_bounds() {
+        let empty = CpuGeometry::new(&[], &[], CpuGeometryRetention::Full);
+
+        assert_eq!(empty.local_aabb(), None);
+        assert_eq!(empty.vertex_count(), 0);
+    }
+
+    #[test]
+    fn test_raycast_needs_retained_triangles() {
+        let (vertices, indices) = quad();
+
+        let picking = CpuGeometry::new(
+            &vertices,
+            &indices,
+            CpuGeometryRetention::PositionsAndIndicesOnly,
+        );
+        let bounds = picking.trimmed(CpuGeometryRetention::AabbOnly);
+
+        assert!(picking.raycast(&ray_at_quad(), false).is_some());
+        assert!(bounds.raycast(&ray_at_quad(), false).is_none());
+    }
+
+    #[test]
+    fn test_trimmed_geometry_cannot_grow_back() {
+        let (vertices, indices) = quad();
+        let bounds = CpuGeometry::new(&vertices, &indices, CpuGeometryRetention::AabbOnly);
+
+        let widened = bounds.trimmed(CpuGeometryRetention::Full);
+
+        assert_eq!(widened.retention(), CpuGeometryRetention::AabbOnly);
+        assert!(widened.vertices().is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use glam::{Quat, Vec3};
+use gltf::animation::{Interpolation as GltfInterpolation, util::ReadOutputs};
+use hyakou_core::{
+    animations::keyframes::{AnimatedNode, Interpolation, KeyframeChannel, KeyframeValues},
+    geometry::node::NodeGraph,
+};
+
+use super::types::ImportedAnimation;
+
+/// Reads the transform channels of the asset's first animation, keyed by glTF node index. Other
+/// animations and morph target weights are reported as diagnostics instead.
+pub(super) fn load_node_channels(
+    gltf: &gltf::Gltf,
+    buffer_data: &[Vec<u8>],
+    asset_label: &str,
+) -> Result<HashMap<usize, Vec<KeyframeChannel>>> {
+    let mut channels: HashMap<usize, Vec<KeyframeChannel>> = HashMap::new();
+    let Some(animation) = gltf.animations().next() else {
+        return Ok(channels);
+    };
+
+    for (channel_index, channel) in animation.channels().enumerate() {
+        let describe = || {
+            format!(
+                "asset `{asset_label}`, animation {}, channel {channel_index}",
+                animation.index()
+            )
+        };
+        let interpolation = match channel.sampler().interpolation() {
+            GltfInterpolation::Linear => Interpolation::Linear,
+            GltfInterpolation::Step => Interpolation::Step,
+            GltfInterpolation::CubicSpline => {
+                return Err(anyhow!(
+                    "Unsupported CUBICSPLINE interpolation in {}",
+                    describe()
+                ));
+            }
+        };
+
+        let reader = channel.reader(|buffer| buffer_data.get(buffer.index()).map(Vec::as_slice));
+        let times = reader
+            .read_inputs()
+            .ok_or_else(|| anyhow!("Missing keyframe times in {}", describe()))?
+            .collect::<Vec<_>>();
+        let values = match reader
+            .read_outputs()
+            .ok_or_else(|| anyhow!("Missing keyframe values in {}", describe()))?
+        {
+            ReadOutputs::Translations(translations) => {
+                KeyframeValues::Translation(translations.map(Vec3::from_array).collect())
+            }
+            ReadOutputs::Rotations(rotations) => KeyframeValues::Rotation(
+                rotations
+                    .into_f32()
+                    .map(|rotation| Quat::from_array(rotation).normalize())
+                    .collect(),
+            ),
+            ReadOutputs::Scales(scales) => {
+                KeyframeValues::Scale(scales.map(Vec3::from_array).collect())
+            }
+            ReadOutputs::MorphTargetWeights(_) => continue,
+        };
+
+        let keyframes = KeyframeChannel::new(times, values, interpolation)
+            .map_err(|error| anyhow!("Invalid keyframes in {}: {error}", describe()))?;
+        channels
+            .entry(channel.target().node().index())
+            .or_default()
+            .push(keyframes);
+    }
+
+    Ok(channels)
+}
+
+/// One animation per flattened mesh that has a channel on its own node or any ancestor.
+pub(super) fn build_mesh_animations(
+    node_graph: &NodeGraph,
+    channels: &HashMap<usize, Vec<KeyframeChannel>>,
+) -> Vec<ImportedAnimation> {
+    let node_channels = |metadata_index: Option<usize>| {
+        metadata_index.and_then(|node_index| channels.get(&node_index))
+    };
+
+    node_graph
+        .mesh_paths()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, path)| {
+            path.iter()
+                .any(|node| node_channels(node.metadata.source_index).is_some())
+        })
+        .map(|(mesh_index, path)| ImportedAnimation {
+            mesh_index,
+            path: path
+                .into_iter()
+                .map(|node| {
+                    AnimatedNode::new(
+                        node.local_transform,
+                        node_channels(node.metadata.source_index)
+                            .cloned()
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect(),
+        })
+        .collect()
+}
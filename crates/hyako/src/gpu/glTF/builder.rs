@@ -10,7 +10,17 @@ use hyakou_core::{
     types::{import_diagnostic::ImportDiagnostic, transform::Transform},
 };
 
+use thiserror::Error;
+
 use super::diagnostics::{collect_document_diagnostics, collect_node_diagnostics};
+use crate::gpu::load_progress::LoadStage;
+
+/// The asset parsed, but none of its nodes reference a mesh.
+#[derive(Debug, Error)]
+#[error("glTF asset `{asset}` contains no renderable meshes")]
+pub struct NoRenderableMeshes {
+    pub asset: String,
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct PrimitiveContext {
@@ -22,15 +32,39 @@ pub(crate) struct PrimitiveContext {
     primitive_index: usize,
 }
 
+/// Reports [`LoadStage::BuildingVertices`] once per primitive built, out of every primitive the
+/// scene's nodes reference.
+struct PrimitiveProgress<'a> {
+    built: usize,
+    total: usize,
+    report: &'a mut dyn FnMut(LoadStage, f32),
+}
+
+impl PrimitiveProgress<'_> {
+    fn primitive_built(&mut self) {
+        self.built += 1;
+        (self.report)(
+            LoadStage::BuildingVertices,
+            self.built as f32 / self.total as f32,
+        );
+    }
+}
+
 pub(super) fn build_node_graph(
     gltf: &gltf::Gltf,
     buffer_data: &[Vec<u8>],
     asset_label: &str,
+    progress: &mut dyn FnMut(LoadStage, f32),
 ) -> Result<(NodeGraph, Vec<ImportDiagnostic>)> {
     let mut diagnostics = collect_document_diagnostics(gltf, asset_label);
     let root_nodes = collect_root_nodes(gltf);
     let mut nodes = Vec::new();
     let mut root_ids = Vec::new();
+    let mut progress = PrimitiveProgress {
+        built: 0,
+        total: root_nodes.iter().map(count_primitives).sum(),
+        report: progress,
+    };
 
     for root_node in root_nodes {
         root_ids.push(build_node_recursive(
@@ -40,13 +74,15 @@ pub(super) fn build_node_graph(
             &mut diagnostics,
             buffer_data,
             asset_label,
+            &mut progress,
         )?);
     }
 
     if nodes.iter().all(|node| node.meshes.is_empty()) {
-        return Err(anyhow!(
-            "glTF asset `{asset_label}` contains no renderable meshes"
-        ));
+        return Err(NoRenderableMeshes {
+            asset: asset_label.to_string(),
+        }
+        .into());
     }
 
     Ok((NodeGraph::new(nodes, root_ids), diagnostics))
@@ -60,6 +96,15 @@ fn collect_root_nodes<'a>(gltf: &'a gltf::Gltf) -> Vec<gltf::Node<'a>> {
     }
 }
 
+/// Primitives of `gltf_node` and its descendants, counting a mesh once per node using it.
+fn count_primitives(gltf_node: &gltf::Node<'_>) -> usize {
+    let own = gltf_node.mesh().map_or(0, |mesh| mesh.primitives().len());
+    own + gltf_node
+        .children()
+        .map(|child| count_primitives(&child))
+        .sum::<usize>()
+}
+
 fn build_node_recursive(
     gltf_node: gltf::Node<'_>,
     parent_id: Option<NodeId>,
@@ -67,10 +112,11 @@ fn build_node_recursive(
     diagnostics: &mut Vec<ImportDiagnostic>,
     buffer_data: &[Vec<u8>],
     asset_label: &str,
+    progress: &mut PrimitiveProgress<'_>,
 ) -> Result<NodeId> {
     collect_node_diagnostics(&gltf_node, diagnostics, asset_label);
     let local_transform = build_local_transform(&gltf_node);
-    let meshes = build_meshes_for_node(&gltf_node, buffer_data, asset_label)?;
+    let meshes = build_meshes_for_node(&gltf_node, buffer_data, asset_label, progress)?;
     let node_id = NodeId(nodes.len());
 
     nodes.push(Node {
@@ -91,6 +137,7 @@ fn build_node_recursive(
                 diagnostics,
                 buffer_data,
                 asset_label,
+                progress,
             )
         })
         .collect::<Result<Vec<_>>>()?;
@@ -114,6 +161,7 @@ fn build_meshes_for_node(
     gltf_node: &gltf::Node<'_>,
     buffer_data: &[Vec<u8>],
     asset_label: &str,
+    progress: &mut PrimitiveProgress<'_>,
 ) -> Result<Vec<Mesh>> {
     let Some(mesh) = gltf_node.mesh() else {
         return Ok(vec![]);
@@ -134,6 +182,7 @@ fn build_meshes_for_node(
             &primitive_context,
             buffer_data,
         )?);
+        progress.primitive_built();
     }
 
     Ok(meshes)
@@ -195,12 +244,7 @@ fn build_triangle_meshes(
         Some(normal) => normal
             .map(|iter| Vec3::new(iter[0], iter[1], iter[2]))
             .collect::<Vec<_>>(),
-        None => {
-            return Err(anyhow!(
-                "Missing NORMAL attribute in {}",
-                primitive_context.describe()
-            ));
-        }
+        None => generate_normals(&positions, &indices),
     };
     ensure_attribute_count("NORMAL", normals.len(), vertex_count, primitive_context)?;
 
@@ -239,6 +283,25 @@ fn build_triangle_meshes(
     }])
 }
 
+/// Smooth per-vertex normals for primitives without a NORMAL attribute: each triangle adds its
+/// area-weighted face normal to its three vertices. Vertices no triangle gives a direction to
+/// point up, so every normal is unit length.
+pub(crate) fn generate_normals(positions: &[Vec3], indices: &[u32]) -> Vec<Vec3> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| index as usize);
+        let face_normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        normals[a] += face_normal;
+        normals[b] += face_normal;
+        normals[c] += face_normal;
+    }
+
+    normals
+        .into_iter()
+        .map(|normal| normal.normalize_or(Vec3::Y))
+        .collect()
+}
+
 fn ensure_attribute_count(
     attribute_name: &str,
     actual_count: usize,
@@ -1,3 +1,4 @@
+use gltf::animation::Property;
 use hyakou_core::types::import_diagnostic::{
     ImportDiagnostic, ImportMeshContext, ImportNodeContext,
 };
@@ -8,12 +9,12 @@ pub(super) fn collect_document_diagnostics(
 ) -> Vec<ImportDiagnostic> {
     let mut diagnostics = Vec::new();
 
-    for animation in gltf.animations() {
+    for animation in gltf.animations().skip(1) {
         let animation_name = animation.name().unwrap_or("unnamed");
         diagnostics.push(ImportDiagnostic::warning(
             "animation",
             format!(
-                "This glTF contains animation data {} `{animation_name}` for asset `{asset_label}`. Hyakou currently imports static meshes, but does not import glTF animations.",
+                "This glTF contains animation data {} `{animation_name}` for asset `{asset_label}`. Hyakou currently plays the first animation of an asset, but does not import further animations.",
                 animation.index()
             ),
             None,
@@ -21,6 +22,24 @@ pub(super) fn collect_document_diagnostics(
         ));
     }
 
+    let morph_weight_channels = gltf
+        .animations()
+        .next()
+        .into_iter()
+        .flat_map(|animation| animation.channels())
+        .filter(|channel| matches!(channel.target().property(), Property::MorphTargetWeights))
+        .count();
+    if morph_weight_channels > 0 {
+        diagnostics.push(ImportDiagnostic::warning(
+            "animation",
+            format!(
+                "This glTF animates morph target weights in {morph_weight_channels} channel(s) for asset `{asset_label}`. Hyakou currently imports translation, rotation and scale channels, but does not import morph target animation."
+            ),
+            None,
+            None,
+        ));
+    }
+
     for extension in gltf.extensions_required() {
         diagnostics.push(ImportDiagnostic::warning(
             "required extension",
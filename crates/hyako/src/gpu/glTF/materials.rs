@@ -1,5 +1,5 @@
 use anyhow::{Result, anyhow};
-use glam::Vec4;
+use glam::{Vec3, Vec4};
 
 use super::types::{
     ImportedAlphaMode, ImportedMagFilter, ImportedMaterial, ImportedMinFilter, ImportedSampler,
@@ -38,8 +38,16 @@ fn import_material(material: gltf::Material<'_>) -> Result<ImportedMaterial> {
             .base_color_texture()
             .map(import_texture_ref)
             .transpose()?,
+        metallic_factor: pbr.metallic_factor(),
+        roughness_factor: pbr.roughness_factor(),
+        metallic_roughness_texture: pbr
+            .metallic_roughness_texture()
+            .map(import_texture_ref)
+            .transpose()?,
+        emissive_factor: Vec3::from_array(material.emissive_factor()),
         alpha_mode: import_alpha_mode(material.alpha_mode()),
         alpha_cutoff: material.alpha_cutoff(),
+        double_sided: material.double_sided(),
     })
 }
 
@@ -76,7 +84,7 @@ fn import_sampler(sampler: gltf::texture::Sampler<'_>) -> ImportedSampler {
 fn import_texture_ref(info: gltf::texture::Info<'_>) -> Result<ImportedTextureRef> {
     if info.tex_coord() != 0 {
         return Err(anyhow!(
-            "Unsupported base color texture coordinate set `TEXCOORD_{}`; only `TEXCOORD_0` is supported",
+            "Unsupported texture coordinate set `TEXCOORD_{}`; only `TEXCOORD_0` is supported",
             info.tex_coord()
         ));
     }
@@ -4,18 +4,26 @@ use std::{
 };
 
 use anyhow::{Result, anyhow};
+use tracing::instrument;
 
+use super::load_progress::LoadStage;
+
+mod animations;
 mod builder;
 mod diagnostics;
 mod materials;
 mod resources;
 mod types;
 
+pub use builder::NoRenderableMeshes;
+pub(crate) use builder::generate_normals;
 #[cfg(test)]
 pub(super) use builder::{PrimitiveContext, ensure_indices_in_range};
+pub(crate) use resources::read_bytes;
 pub use types::{
-    ImportedAlphaMode, ImportedImage, ImportedMagFilter, ImportedMaterial, ImportedMinFilter,
-    ImportedSampler, ImportedScene, ImportedTexture, ImportedTextureRef, ImportedWrapMode,
+    ImportedAlphaMode, ImportedAnimation, ImportedImage, ImportedMagFilter, ImportedMaterial,
+    ImportedMinFilter, ImportedSampler, ImportedScene, ImportedTexture, ImportedTextureRef,
+    ImportedWrapMode,
 };
 
 #[derive(Debug, Clone)]
@@ -34,13 +42,28 @@ impl GLTFLoader {
     }
 
     pub async fn load_from_path(&self, path: &Path) -> Result<ImportedScene> {
+        self.load_from_path_with_progress(path, &mut |_, _| {})
+            .await
+    }
+
+    /// Like [`Self::load_from_path`], reporting each [`LoadStage`] up to building vertices to
+    /// `progress`.
+    #[instrument(name = "GLTFLoader::load_from_path", skip_all, fields(path = %path.display()))]
+    pub async fn load_from_path_with_progress(
+        &self,
+        path: &Path,
+        progress: &mut impl FnMut(LoadStage, f32),
+    ) -> Result<ImportedScene> {
+        progress(LoadStage::ReadingBytes, 0.0);
         let slice = resources::read_asset(path).await?;
+        progress(LoadStage::ReadingBytes, 1.0);
         let context = ImportContext {
             asset_label: path.display().to_string(),
             buffer_base_dir: path.parent().map(Path::to_path_buf),
             bundled_files: None,
         };
-        self.load_from_bytes_with_context(slice, context).await
+        self.load_from_bytes_with_context(slice, context, progress)
+            .await
     }
 
     pub async fn load_from_bytes(&self, slice: Vec<u8>) -> Result<ImportedScene> {
@@ -52,13 +75,26 @@ impl GLTFLoader {
         &self,
         slice: Vec<u8>,
         asset_label: impl Into<String>,
+    ) -> Result<ImportedScene> {
+        self.load_from_bytes_with_progress(slice, asset_label, &mut |_, _| {})
+            .await
+    }
+
+    /// Like [`Self::load_from_bytes_with_label`], reporting each [`LoadStage`] from parsing to
+    /// building vertices to `progress`.
+    pub async fn load_from_bytes_with_progress(
+        &self,
+        slice: Vec<u8>,
+        asset_label: impl Into<String>,
+        progress: &mut impl FnMut(LoadStage, f32),
     ) -> Result<ImportedScene> {
         let context = ImportContext {
             asset_label: asset_label.into(),
             buffer_base_dir: None,
             bundled_files: None,
         };
-        self.load_from_bytes_with_context(slice, context).await
+        self.load_from_bytes_with_context(slice, context, progress)
+            .await
     }
 
     pub async fn load_from_file_bundle(
@@ -76,14 +112,18 @@ impl GLTFLoader {
             bundled_files: Some(bundled_files),
         };
 
-        self.load_from_bytes_with_context(entry_file, context).await
+        self.load_from_bytes_with_context(entry_file, context, &mut |_, _| {})
+            .await
     }
 
+    #[instrument(name = "GLTFLoader::import", skip_all, fields(asset = %context.asset_label))]
     async fn load_from_bytes_with_context(
         &self,
         slice: Vec<u8>,
         context: ImportContext,
+        progress: &mut impl FnMut(LoadStage, f32),
     ) -> Result<ImportedScene> {
+        progress(LoadStage::Parsing, 0.0);
         let gltf = gltf::Gltf::from_slice(&slice).map_err(|error| {
             anyhow!(
                 "Failed to parse glTF asset `{}`: {error}",
@@ -91,15 +131,19 @@ impl GLTFLoader {
             )
         })?;
 
-        let buffer_data = resources::load_buffers(&gltf, &context).await?;
+        let buffer_data = resources::load_buffers(&gltf, &context, progress).await?;
         let (images, image_diagnostics) =
             resources::load_images(&gltf, &buffer_data, &context).await?;
         let textures = materials::load_textures(&gltf);
         let samplers = materials::load_samplers(&gltf);
         let materials = materials::load_materials(&gltf)?;
+        progress(LoadStage::Parsing, 1.0);
         let (node_graph, mut diagnostics) =
-            builder::build_node_graph(&gltf, &buffer_data, &context.asset_label)?;
+            builder::build_node_graph(&gltf, &buffer_data, &context.asset_label, progress)?;
         diagnostics.extend(image_diagnostics);
+        let node_channels =
+            animations::load_node_channels(&gltf, &buffer_data, &context.asset_label)?;
+        let animations = animations::build_mesh_animations(&node_graph, &node_channels);
 
         Ok(ImportedScene::new(
             node_graph,
@@ -108,6 +152,7 @@ impl GLTFLoader {
             images,
             textures,
             samplers,
+            animations,
         ))
     }
 }
@@ -4,11 +4,13 @@ use std::{
 };
 
 use anyhow::{Context, Result, anyhow};
+use futures::{StreamExt, stream::FuturesUnordered};
 use hyakou_core::types::import_diagnostic::ImportDiagnostic;
 use image::{DynamicImage, ImageFormat};
 
 use super::ImportContext;
 use super::types::ImportedImage;
+use crate::gpu::load_progress::LoadStage;
 
 pub(super) async fn read_asset(path: &Path) -> Result<Vec<u8>> {
     read_bytes(path)
@@ -44,9 +46,12 @@ pub(super) fn resolve_uploaded_file<'a>(
     bundled_files.get(&normalized_name)
 }
 
+/// Loads every buffer concurrently, reporting [`LoadStage::Parsing`] as each one arrives. The
+/// stage only completes once the images are loaded as well, so the last buffer stays below 1.
 pub(super) async fn load_buffers(
     gltf: &gltf::Gltf,
     context: &ImportContext,
+    progress: &mut impl FnMut(LoadStage, f32),
 ) -> Result<Vec<Vec<u8>>> {
     let buffer_async_handles = gltf.buffers().map(async |buffer| {
         let buffer_index = buffer.index();
@@ -71,9 +76,22 @@ pub(super) async fn load_buffers(
         }?;
 
         ensure_buffer_length(buffer_index, buffer.length(), data, context)
+            .map(|data| (buffer_index, data))
     });
 
-    futures::future::try_join_all(buffer_async_handles).await
+    let mut pending: FuturesUnordered<_> = buffer_async_handles.collect();
+    let mut buffers = vec![Vec::new(); pending.len()];
+    let mut loaded = 0;
+    while let Some(result) = pending.next().await {
+        let (buffer_index, data) = result?;
+        buffers[buffer_index] = data;
+        loaded += 1;
+        progress(
+            LoadStage::Parsing,
+            loaded as f32 / (buffers.len() + 1) as f32,
+        );
+    }
+    Ok(buffers)
 }
 
 pub(super) async fn load_images(
@@ -382,7 +400,7 @@ fn ensure_buffer_length(
 }
 
 #[cfg(target_arch = "wasm32")]
-async fn read_bytes(path: &Path) -> Result<Vec<u8>> {
+pub(crate) async fn read_bytes(path: &Path) -> Result<Vec<u8>> {
     use gloo_net::http::Request;
 
     let path = path
@@ -410,7 +428,8 @@ async fn read_bytes(path: &Path) -> Result<Vec<u8>> {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-async fn read_bytes(path: &Path) -> Result<Vec<u8>> {
+pub(crate) async fn read_bytes(path: &Path) -> Result<Vec<u8>> {
+    // Context keeps the `io::Error` reachable for callers that downcast to it.
     std::fs::read(path)
-        .map_err(|error| anyhow!("Failed to read glTF resource `{}`: {error}", path.display()))
+        .with_context(|| format!("Failed to read glTF resource `{}`", path.display()))
 }
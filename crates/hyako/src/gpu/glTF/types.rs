@@ -1,5 +1,8 @@
-use glam::Vec4;
-use hyakou_core::{geometry::node::NodeGraph, types::import_diagnostic::ImportDiagnostic};
+use glam::{Vec3, Vec4};
+use hyakou_core::{
+    animations::keyframes::AnimatedNode, geometry::node::NodeGraph,
+    types::import_diagnostic::ImportDiagnostic,
+};
 
 pub struct ImportedScene {
     pub node_graph: NodeGraph,
@@ -8,6 +11,7 @@ pub struct ImportedScene {
     pub images: Vec<ImportedImage>,
     pub textures: Vec<ImportedTexture>,
     pub samplers: Vec<ImportedSampler>,
+    pub animations: Vec<ImportedAnimation>,
 }
 
 impl ImportedScene {
@@ -18,6 +22,7 @@ impl ImportedScene {
         images: Vec<ImportedImage>,
         textures: Vec<ImportedTexture>,
         samplers: Vec<ImportedSampler>,
+        animations: Vec<ImportedAnimation>,
     ) -> Self {
         Self {
             node_graph,
@@ -26,18 +31,33 @@ impl ImportedScene {
             images,
             textures,
             samplers,
+            animations,
         }
     }
 }
 
+/// Keyframes moving the mesh at `mesh_index` of [`NodeGraph::flatten`].
+#[derive(Debug, Clone)]
+pub struct ImportedAnimation {
+    pub mesh_index: usize,
+    /// From the root node down to the mesh's own node.
+    pub path: Vec<AnimatedNode>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ImportedMaterial {
     pub index: usize,
     pub name: Option<String>,
     pub base_color_factor: Vec4,
     pub base_color_texture: Option<ImportedTextureRef>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    /// Roughness in the green channel, metalness in blue.
+    pub metallic_roughness_texture: Option<ImportedTextureRef>,
+    pub emissive_factor: Vec3,
     pub alpha_mode: ImportedAlphaMode,
     pub alpha_cutoff: Option<f32>,
+    pub double_sided: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -47,7 +67,7 @@ pub enum ImportedAlphaMode {
     Blend,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ImportedTextureRef {
     pub texture_index: usize,
     pub tex_coord: u32,
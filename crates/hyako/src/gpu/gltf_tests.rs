@@ -1,15 +1,28 @@
 use std::{
     fs,
     path::PathBuf,
+    sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use glam::{Vec2, Vec3, Vec4};
+use hyakou_core::{
+    animations::keyframes::KeyframeAnimation,
+    types::ids::MeshId,
+    util::test_support::{assert_vec2_eq, assert_vec3_eq, assert_vec4_eq},
+};
+use parking_lot::Mutex;
+use tracing::{
+    Subscriber,
+    span::{Attributes, Id},
+};
+use tracing_subscriber::{
+    Layer,
+    layer::{Context, SubscriberExt},
+};
 
 use super::*;
 
-const EPSILON: f32 = 1e-6;
-
 fn loader() -> GLTFLoader {
     GLTFLoader::new()
 }
@@ -37,6 +50,16 @@ fn load_from_bundle(entry_name: &str, file_names: &[&str]) -> Result<ImportedSce
     pollster::block_on(loader().load_from_file_bundle(entry_name, files))
 }
 
+/// Collects the names of all spans opened while a test runs.
+#[derive(Clone, Default)]
+struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+impl<S: Subscriber> Layer<S> for SpanNames {
+    fn on_new_span(&self, attributes: &Attributes<'_>, _id: &Id, _context: Context<'_, S>) {
+        self.0.lock().push(attributes.metadata().name());
+    }
+}
+
 fn load_glb_from_path(bytes: Vec<u8>) -> Result<ImportedScene> {
     let suffix = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -131,27 +154,6 @@ fn primitive_context() -> PrimitiveContext {
     )
 }
 
-fn assert_vec2_eq(actual: Vec2, expected: Vec2, message: &str) {
-    assert!(
-        (actual - expected).length() < EPSILON,
-        "{message}: expected {expected:?}, got {actual:?}"
-    );
-}
-
-fn assert_vec3_eq(actual: Vec3, expected: Vec3, message: &str) {
-    assert!(
-        (actual - expected).length() < EPSILON,
-        "{message}: expected {expected:?}, got {actual:?}"
-    );
-}
-
-fn assert_vec4_eq(actual: Vec4, expected: Vec4, message: &str) {
-    assert!(
-        (actual - expected).length() < EPSILON,
-        "{message}: expected {expected:?}, got {actual:?}"
-    );
-}
-
 #[test]
 fn test_ensure_indices_in_range_rejects_out_of_range_index() {
     let context = primitive_context();
@@ -195,11 +197,52 @@ fn test_load_from_bytes_rejects_malformed_bytes() {
 }
 
 #[test]
-fn test_load_from_path_rejects_missing_normals() {
-    assert_loader_error_contains(
-        load_from_path("missing_normal.gltf"),
-        "Missing NORMAL attribute in asset",
-    );
+fn test_load_from_path_generates_missing_normals() {
+    let imported_scene = load_from_path("missing_normal.gltf").unwrap();
+
+    let mesh_nodes = imported_scene.node_graph.flatten();
+
+    assert_eq!(mesh_nodes.len(), 1);
+    assert_eq!(mesh_nodes[0].vertices.len(), 36);
+    for vertex in &mesh_nodes[0].vertices {
+        assert!(
+            (vertex.normals.length() - 1.0).abs() < 1e-5,
+            "generated normal {:?} is not unit length",
+            vertex.normals
+        );
+        // The cube's faces share no vertices, so each normal is its face's axis, pointing out.
+        assert!(vertex.normals.dot(vertex.position) > 0.0);
+        assert!(vertex.normals.abs().max_element() > 0.999);
+    }
+}
+
+#[test]
+fn test_generated_normals_are_smoothed_across_shared_vertices() {
+    // Two triangles folded along the shared edge 1-2: one faces +Z, the other +X.
+    let positions = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(1.0, 1.0, 0.0),
+        Vec3::new(1.0, 0.0, -1.0),
+    ];
+    let indices = [0, 1, 2, 1, 3, 2];
+
+    let normals = generate_normals(&positions, &indices);
+
+    assert_vec3_eq(normals[0], Vec3::Z, "+Z triangle corner");
+    assert_vec3_eq(normals[3], Vec3::X, "+X triangle corner");
+    let shared = Vec3::new(1.0, 0.0, 1.0).normalize();
+    assert_vec3_eq(normals[1], shared, "normal on the shared edge");
+    assert_vec3_eq(normals[2], shared, "normal on the shared edge");
+}
+
+#[test]
+fn test_generated_normals_of_unreferenced_vertices_are_unit_length() {
+    let positions = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::splat(5.0)];
+
+    let normals = generate_normals(&positions, &[0, 1, 2]);
+
+    assert_vec3_eq(normals[3], Vec3::Y, "unreferenced vertex normal");
 }
 
 #[test]
@@ -214,6 +257,46 @@ fn test_load_from_path_rejects_missing_positions_with_context() {
     );
 }
 
+#[test]
+fn test_load_from_path_imports_animation_channels_along_the_node_path() {
+    let imported_scene = load_from_path("animated_hierarchy.gltf").unwrap();
+
+    assert_eq!(imported_scene.animations.len(), 1);
+    let animation = &imported_scene.animations[0];
+    assert_eq!(animation.mesh_index, 0);
+    assert_eq!(animation.path.len(), 2);
+    assert_eq!(animation.path[0].channels.len(), 1, "spinner rotation");
+    assert_eq!(animation.path[1].channels.len(), 1, "triangle translation");
+
+    let keyframes = KeyframeAnimation::new(
+        MeshId("Triangle".to_string()),
+        hyakou_core::shared(Default::default()),
+        animation.path.clone(),
+    );
+    assert_eq!(keyframes.duration(), 1.0);
+    assert_vec3_eq(
+        keyframes.pose_at(0.0).position,
+        Vec3::new(2.0, 0.0, 0.0),
+        "rest position",
+    );
+    assert_vec3_eq(
+        keyframes.pose_at(1.0).position,
+        Vec3::new(0.0, 0.0, -3.0),
+        "stepped translation swung a quarter turn by the parent",
+    );
+}
+
+#[test]
+fn test_load_from_bytes_rejects_cubic_spline_animation() {
+    let gltf = include_str!("../../assets/gltf/test_fixtures/animated_hierarchy.gltf")
+        .replace("\"STEP\"", "\"CUBICSPLINE\"");
+
+    assert_loader_error_contains(
+        load_from_bytes(gltf.into_bytes()),
+        "Unsupported CUBICSPLINE interpolation in asset `in-memory glTF asset`, animation 0, channel 1",
+    );
+}
+
 #[test]
 fn test_load_from_path_rejects_unsupported_primitive_mode() {
     assert_loader_error_contains(
@@ -222,6 +305,18 @@ fn test_load_from_path_rejects_unsupported_primitive_mode() {
     );
 }
 
+#[test]
+fn test_load_from_path_rejects_tex_coord_count_mismatch() {
+    assert_loader_error_contains(
+        load_from_path("mismatched_tex_coords.gltf"),
+        "Attribute `TEXCOORD_0` count mismatch in asset",
+    );
+    assert_loader_error_contains(
+        load_from_path("mismatched_tex_coords.gltf"),
+        "primitive 0: expected 3, got 2",
+    );
+}
+
 #[test]
 fn test_load_from_path_reports_missing_external_sidecar() {
     assert_loader_error_contains(
@@ -289,6 +384,13 @@ fn test_load_from_path_reads_vertex_colors_defaults_tex_coords_and_base_color()
         Vec4::new(0.25, 0.5, 0.75, 1.0),
         "base color factor",
     );
+    assert_eq!(imported_scene.materials[0].metallic_factor, 1.0);
+    assert_eq!(imported_scene.materials[0].roughness_factor, 1.0);
+    assert!(
+        imported_scene.materials[0]
+            .metallic_roughness_texture
+            .is_none()
+    );
 }
 
 #[test]
@@ -337,6 +439,23 @@ fn test_load_from_bytes_reads_data_uri_buffer() {
     );
 }
 
+#[test]
+fn test_load_from_bytes_rejects_invalid_base64_buffer_with_its_index() {
+    let bytes = br#"{
+        "asset": { "version": "2.0" },
+        "buffers": [
+            { "byteLength": 4, "uri": "data:application/octet-stream;base64,AAAAAA==" },
+            { "byteLength": 4, "uri": "data:application/octet-stream;base64,!!not base64!!" }
+        ]
+    }"#
+    .to_vec();
+
+    assert_loader_error_contains(
+        load_from_bytes(bytes),
+        "Failed to decode data URI buffer 1 in asset `in-memory glTF asset`",
+    );
+}
+
 #[test]
 fn test_load_from_path_reads_glb_embedded_buffer() {
     let imported_scene = load_glb_from_path(vertex_colors_glb_bytes()).unwrap();
@@ -394,6 +513,14 @@ fn test_load_from_path_reads_inline_material_texture_image_and_sampler() {
         "inline textured base color factor",
     );
     assert_eq!(material.alpha_mode, ImportedAlphaMode::Blend);
+    assert_eq!(material.metallic_factor, 0.25);
+    assert_eq!(material.roughness_factor, 0.6);
+    assert_eq!(
+        material
+            .metallic_roughness_texture
+            .map(|texture| texture.texture_index),
+        Some(texture_ref.texture_index)
+    );
     assert_eq!(texture_ref.tex_coord, 0);
     assert_eq!(texture.name.as_deref(), Some("PixelTexture"));
     assert_eq!(image.name.as_deref(), Some("InlinePixel"));
@@ -535,3 +662,42 @@ fn test_load_from_bytes_rejects_asset_without_renderable_meshes() {
         "glTF asset `empty.gltf` contains no renderable meshes",
     );
 }
+
+#[test]
+fn test_load_from_path_opens_import_spans() {
+    let span_names = SpanNames::default();
+    let subscriber = tracing_subscriber::registry().with(span_names.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+        load_from_path("scene_hierarchy.gltf").unwrap();
+    });
+
+    let names = span_names.0.lock();
+    assert!(names.contains(&"GLTFLoader::load_from_path"), "{names:?}");
+    assert!(names.contains(&"GLTFLoader::import"), "{names:?}");
+}
+
+#[test]
+fn test_load_from_path_reports_progress_stage_by_stage() {
+    let mut reports = Vec::new();
+
+    pollster::block_on(loader().load_from_path_with_progress(
+        &fixture_path("scene_hierarchy.gltf"),
+        &mut |stage, fraction| reports.push((stage, fraction)),
+    ))
+    .unwrap();
+
+    // One buffer, and one primitive used by each of the two mesh nodes.
+    assert_eq!(
+        reports,
+        [
+            (LoadStage::ReadingBytes, 0.0),
+            (LoadStage::ReadingBytes, 1.0),
+            (LoadStage::Parsing, 0.0),
+            (LoadStage::Parsing, 0.5),
+            (LoadStage::Parsing, 1.0),
+            (LoadStage::BuildingVertices, 0.5),
+            (LoadStage::BuildingVertices, 1.0),
+        ]
+    );
+}
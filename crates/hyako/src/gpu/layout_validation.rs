@@ -0,0 +1,225 @@
+//! Checks that the `#[repr(C)]` structs written to GPU buffers match the WGSL structs the shaders
+//! read them as. A mismatch does not fail validation in wgpu, it just renders garbage, so the
+//! struct invariants are asserted at compile time and the member offsets are compared against the
+//! parsed shaders in tests.
+
+use std::mem::{align_of, size_of};
+
+use hyakou_core::{
+    components::light::GpuLightSource, traits::WgslLayout, types::transform::Transform,
+};
+
+use crate::gpu::{
+    buffers::{
+        camera_buffer::CameraUniform, model_matrix::ModelMatrixUniform, outline::OutlineUniform,
+        picking::PickingUniform,
+    },
+    material::MaterialUniform,
+};
+
+/// WGSL rounds uniform structs up to 16 bytes, so the Rust side has to carry that padding too.
+/// Members have to be 4-byte aligned, in bounds and in declaration order.
+const fn assert_uniform_layout<T: WgslLayout>() {
+    assert!(
+        size_of::<T>().is_multiple_of(16),
+        "uniform structs are padded to 16 bytes"
+    );
+    assert!(align_of::<T>() <= 16);
+    assert!(!T::WGSL_NAMES.is_empty());
+    let mut index = 0;
+    while index < T::MEMBERS.len() {
+        let offset = T::MEMBERS[index].1;
+        assert!(offset % 4 == 0);
+        assert!(offset < size_of::<T>());
+        assert!(index == 0 || offset > T::MEMBERS[index - 1].1);
+        index += 1;
+    }
+}
+
+const _: () = {
+    assert_uniform_layout::<CameraUniform>();
+    assert_uniform_layout::<ModelMatrixUniform>();
+    assert_uniform_layout::<MaterialUniform>();
+    assert_uniform_layout::<OutlineUniform>();
+    assert_uniform_layout::<PickingUniform>();
+    assert_uniform_layout::<Transform>();
+    assert_uniform_layout::<GpuLightSource>();
+};
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::{geometry::vertices::Vertex, traits::BufferLayoutProvider};
+    use naga::{Binding, Module, StructMember, TypeInner};
+    use wgpu::VertexBufferLayout;
+
+    use super::*;
+    use crate::gpu::buffers::{
+        billboard::BillboardInstance, glyph::GlyphInstance, instance::InstanceData,
+    };
+
+    const SHADERS: [(&str, &str); 12] = [
+        ("vertex.wgsl", include_str!("../../assets/vertex.wgsl")),
+        (
+            "vertex_uniform.wgsl",
+            include_str!("../../assets/vertex_uniform.wgsl"),
+        ),
+        (
+            "vertex_instanced.wgsl",
+            include_str!("../../assets/vertex_instanced.wgsl"),
+        ),
+        (
+            "no_light_vertex.wgsl",
+            include_str!("../../assets/no_light_vertex.wgsl"),
+        ),
+        (
+            "no_light_vertex_uniform.wgsl",
+            include_str!("../../assets/no_light_vertex_uniform.wgsl"),
+        ),
+        ("pbr.wgsl", include_str!("../../assets/pbr.wgsl")),
+        (
+            "pbr_uniform.wgsl",
+            include_str!("../../assets/pbr_uniform.wgsl"),
+        ),
+        ("outline.wgsl", include_str!("../../assets/outline.wgsl")),
+        ("picking.wgsl", include_str!("../../assets/picking.wgsl")),
+        ("gizmo.wgsl", include_str!("../../assets/gizmo.wgsl")),
+        (
+            "light_billboard.wgsl",
+            include_str!("../../assets/light_billboard.wgsl"),
+        ),
+        (
+            "text_overlay.wgsl",
+            include_str!("../../assets/text_overlay.wgsl"),
+        ),
+    ];
+
+    struct HostLayout {
+        rust_name: &'static str,
+        wgsl_names: &'static [&'static str],
+        size: usize,
+        members: &'static [(&'static str, usize)],
+    }
+
+    fn host<T: WgslLayout>() -> HostLayout {
+        HostLayout {
+            rust_name: std::any::type_name::<T>(),
+            wgsl_names: T::WGSL_NAMES,
+            size: size_of::<T>(),
+            members: T::MEMBERS,
+        }
+    }
+
+    fn parse(shader: &str, source: &str) -> Module {
+        naga::front::wgsl::parse_str(source)
+            .unwrap_or_else(|error| panic!("{shader}: {}", error.emit_to_string(source)))
+    }
+
+    fn wgsl_struct<'a>(module: &'a Module, name: &str) -> Option<(&'a [StructMember], u32)> {
+        module.types.iter().find_map(|(_, ty)| match &ty.inner {
+            TypeInner::Struct { members, span } if ty.name.as_deref() == Some(name) => {
+                Some((members.as_slice(), *span))
+            }
+            _ => None,
+        })
+    }
+
+    fn vertex_input_bytes(inner: &TypeInner) -> u64 {
+        match *inner {
+            TypeInner::Scalar(scalar) => scalar.width as u64,
+            TypeInner::Vector { size, scalar } => size as u64 * scalar.width as u64,
+            ref other => panic!("unexpected vertex input type {other:?}"),
+        }
+    }
+
+    fn assert_vertex_input_matches(
+        shader: &str,
+        module: &Module,
+        wgsl_name: &str,
+        layout: VertexBufferLayout<'_>,
+    ) {
+        let (members, _) = wgsl_struct(module, wgsl_name)
+            .unwrap_or_else(|| panic!("{shader} does not declare `{wgsl_name}`"));
+        for member in members {
+            let Some(Binding::Location { location, .. }) = &member.binding else {
+                continue;
+            };
+            let attribute = layout
+                .attributes
+                .iter()
+                .find(|attribute| attribute.shader_location == *location)
+                .unwrap_or_else(|| {
+                    panic!("{shader}: `{wgsl_name}` reads location {location}, which is not in the buffer layout")
+                });
+            assert_eq!(
+                vertex_input_bytes(&module.types[member.ty].inner),
+                attribute.format.size(),
+                "{shader}: `{wgsl_name}` location {location} does not match the buffer layout"
+            );
+        }
+    }
+
+    #[test]
+    fn test_uniform_structs_match_their_wgsl_declarations() {
+        let modules: Vec<(&str, Module)> = SHADERS
+            .iter()
+            .map(|(shader, source)| (*shader, parse(shader, source)))
+            .collect();
+        let layouts = [
+            host::<CameraUniform>(),
+            host::<ModelMatrixUniform>(),
+            host::<MaterialUniform>(),
+            host::<OutlineUniform>(),
+            host::<PickingUniform>(),
+            host::<Transform>(),
+            host::<GpuLightSource>(),
+        ];
+
+        for layout in layouts {
+            let mut declarations = 0;
+            for (shader, module) in &modules {
+                for wgsl_name in layout.wgsl_names {
+                    let Some((members, span)) = wgsl_struct(module, wgsl_name) else {
+                        continue;
+                    };
+                    declarations += 1;
+                    let context = format!("{} as `{wgsl_name}` in {shader}", layout.rust_name);
+                    let offsets: Vec<(&str, usize)> = members
+                        .iter()
+                        .map(|member| {
+                            (
+                                member.name.as_deref().unwrap_or_default(),
+                                member.offset as usize,
+                            )
+                        })
+                        .collect();
+
+                    assert_eq!(span as usize, layout.size, "{context}: size");
+                    assert_eq!(offsets, layout.members, "{context}: member offsets");
+                }
+            }
+            assert!(declarations > 0, "no shader declares {}", layout.rust_name);
+        }
+    }
+
+    #[test]
+    fn test_vertex_inputs_match_the_buffer_layouts() {
+        let inputs = [
+            ("VertexInput", Vertex::vertex_buffer_layout()),
+            ("InstanceInput", InstanceData::vertex_buffer_layout()),
+            ("BillboardInput", BillboardInstance::vertex_buffer_layout()),
+            ("GlyphInput", GlyphInstance::vertex_buffer_layout()),
+        ];
+        for (shader, source) in SHADERS {
+            let module = parse(shader, source);
+
+            let mut declared = 0;
+            for (wgsl_name, layout) in &inputs {
+                if wgsl_struct(&module, wgsl_name).is_some() {
+                    assert_vertex_input_matches(shader, &module, wgsl_name, layout.clone());
+                    declared += 1;
+                }
+            }
+            assert!(declared > 0, "{shader} declares no vertex input");
+        }
+    }
+}
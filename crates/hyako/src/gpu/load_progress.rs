@@ -0,0 +1,27 @@
+//! Progress reports for asset loads, so a large file can drive a progress bar instead of stalling
+//! the window without feedback.
+
+/// A step of an asset load. Loads report them in this order, each with the fraction of the step
+/// done from 0 to 1. Loads from bytes already in memory start at [`LoadStage::Parsing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadStage {
+    /// Reading the file itself.
+    ReadingBytes,
+    /// Parsing the document and loading the buffers and images it references.
+    Parsing,
+    /// Building vertices, advancing once per primitive.
+    BuildingVertices,
+    /// Creating GPU buffers, advancing once per mesh.
+    UploadingBuffers,
+}
+
+impl LoadStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadingBytes => "reading",
+            Self::Parsing => "parsing",
+            Self::BuildingVertices => "building",
+            Self::UploadingBuffers => "uploading",
+        }
+    }
+}
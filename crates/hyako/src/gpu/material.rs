@@ -1,9 +1,12 @@
-use std::rc::Rc;
+use std::{mem::offset_of, sync::Arc};
 
+use anyhow::Result;
 use bytemuck::{Pod, Zeroable};
+use glam::{Vec3, Vec4};
 use hyakou_core::{
-    shared,
-    types::{ids::UniformBufferId, transform::Transform},
+    Shared, SharedAccess, shared,
+    traits::WgslLayout,
+    types::{QueueWriter, ids::UniformBufferId, transform::Transform},
 };
 use wgpu::{
     AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
@@ -15,7 +18,8 @@ use wgpu::{
 use crate::gpu::{
     buffers::uniform::UniformBuffer,
     glTF::{
-        ImportedMagFilter, ImportedMaterial, ImportedMinFilter, ImportedSampler, ImportedWrapMode,
+        ImportedAlphaMode, ImportedMagFilter, ImportedMaterial, ImportedMinFilter, ImportedSampler,
+        ImportedTextureRef, ImportedWrapMode,
     },
     texture::Texture,
 };
@@ -24,18 +28,214 @@ use crate::gpu::{
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct MaterialUniform {
     pub base_color_factor: [f32; 4],
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    _padding: [f32; 2],
+    pub emissive_factor: [f32; 3],
+    /// A [`ShadingSource`] resolved to [`ShadingSource::gpu_value`].
+    pub albedo_source: u32,
+}
+
+impl WgslLayout for MaterialUniform {
+    const WGSL_NAMES: &'static [&'static str] = &["Material"];
+    const MEMBERS: &'static [(&'static str, usize)] = &[
+        (
+            "base_color_factor",
+            offset_of!(MaterialUniform, base_color_factor),
+        ),
+        (
+            "metallic_factor",
+            offset_of!(MaterialUniform, metallic_factor),
+        ),
+        (
+            "roughness_factor",
+            offset_of!(MaterialUniform, roughness_factor),
+        ),
+        (
+            "emissive_factor",
+            offset_of!(MaterialUniform, emissive_factor),
+        ),
+        ("albedo_source", offset_of!(MaterialUniform, albedo_source)),
+    ];
+}
+
+/// Where a mesh's albedo comes from. Handy to see whether a model actually relies on its vertex
+/// colors, material factor or texture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShadingSource {
+    VertexColor,
+    BaseColorFactor,
+    /// The base color texture tinted by the factor, as glTF specifies.
+    Texture,
+    /// Texture if the material has one, else vertex colors if the mesh has any, else the factor.
+    #[default]
+    Auto,
+}
+
+impl ShadingSource {
+    pub const ALL: [ShadingSource; 4] = [
+        ShadingSource::Auto,
+        ShadingSource::Texture,
+        ShadingSource::VertexColor,
+        ShadingSource::BaseColorFactor,
+    ];
+
+    /// The concrete source to shade with. Never returns [`ShadingSource::Auto`].
+    pub fn resolve(self, has_texture: bool, has_vertex_colors: bool) -> ShadingSource {
+        match self {
+            ShadingSource::Auto if has_texture => ShadingSource::Texture,
+            ShadingSource::Auto if has_vertex_colors => ShadingSource::VertexColor,
+            ShadingSource::Auto => ShadingSource::BaseColorFactor,
+            source => source,
+        }
+    }
+
+    /// The next source in [`Self::ALL`], wrapping around.
+    pub fn next(self) -> ShadingSource {
+        let index = Self::ALL
+            .iter()
+            .position(|source| *source == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Matches the `ALBEDO_*` constants in the lit shaders.
+    pub fn gpu_value(self) -> u32 {
+        match self {
+            ShadingSource::VertexColor => 0,
+            ShadingSource::BaseColorFactor => 1,
+            ShadingSource::Texture | ShadingSource::Auto => 2,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShadingSource::VertexColor => "Vertex color",
+            ShadingSource::BaseColorFactor => "Base color factor",
+            ShadingSource::Texture => "Texture",
+            ShadingSource::Auto => "Auto",
+        }
+    }
+}
+
+/// How a mesh's surface is shaded. Every mesh owns one behind a [`Shared`], like its transform,
+/// so it can be tweaked at runtime; edits made through
+/// [`AssetHandler::set_material`](crate::renderer::handlers::asset_handler::AssetHandler::set_material)
+/// are uploaded with the next frame's uniforms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub base_color: Vec4,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: Vec3,
+    pub alpha_mode: ImportedAlphaMode,
+    pub alpha_cutoff: Option<f32>,
+    pub double_sided: bool,
+    pub shading_source: ShadingSource,
+    /// Whether the mesh carries vertex colors, which [`ShadingSource::Auto`] prefers over the
+    /// factor. Set from the geometry when the mesh is uploaded.
+    pub has_vertex_colors: bool,
+    /// Textures of the source asset bound when the mesh was uploaded. Changing them does not
+    /// rebind anything yet.
+    pub base_color_texture: Option<ImportedTextureRef>,
+    pub metallic_roughness_texture: Option<ImportedTextureRef>,
+    dirty: bool,
+}
+
+/// The glTF defaults: opaque, fully metallic and fully rough white.
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            base_color: Vec4::ONE,
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: Vec3::ZERO,
+            alpha_mode: ImportedAlphaMode::Opaque,
+            alpha_cutoff: None,
+            double_sided: false,
+            shading_source: ShadingSource::Auto,
+            has_vertex_colors: false,
+            base_color_texture: None,
+            metallic_roughness_texture: None,
+            dirty: false,
+        }
+    }
+}
+
+impl From<&ImportedMaterial> for Material {
+    fn from(material: &ImportedMaterial) -> Self {
+        Self {
+            base_color: material.base_color_factor,
+            metallic: material.metallic_factor,
+            roughness: material.roughness_factor,
+            emissive: material.emissive_factor,
+            alpha_mode: material.alpha_mode,
+            alpha_cutoff: material.alpha_cutoff,
+            double_sided: material.double_sided,
+            shading_source: ShadingSource::Auto,
+            has_vertex_colors: false,
+            base_color_texture: material.base_color_texture,
+            metallic_roughness_texture: material.metallic_roughness_texture,
+            dirty: false,
+        }
+    }
+}
+
+impl Material {
+    pub fn uniform(&self) -> MaterialUniform {
+        MaterialUniform::new(
+            self.base_color.to_array(),
+            self.metallic,
+            self.roughness,
+            self.emissive.to_array(),
+            self.resolved_shading_source(),
+        )
+    }
+
+    pub fn resolved_shading_source(&self) -> ShadingSource {
+        self.shading_source
+            .resolve(self.base_color_texture.is_some(), self.has_vertex_colors)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// The uniform to upload if anything changed since the last call.
+    pub fn take_dirty_uniform(&mut self) -> Option<MaterialUniform> {
+        std::mem::take(&mut self.dirty).then(|| self.uniform())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct GpuMaterial {
     pub uniform_buffer: UniformBuffer,
     pub bind_group: BindGroup,
-    pub texture: Rc<Texture>,
+    pub texture: Arc<Texture>,
+    pub metallic_roughness_texture: Arc<Texture>,
+    bind_group_layout: BindGroupLayout,
 }
 
 impl MaterialUniform {
-    pub fn new(base_color_factor: [f32; 4]) -> Self {
-        Self { base_color_factor }
+    pub fn new(
+        base_color_factor: [f32; 4],
+        metallic_factor: f32,
+        roughness_factor: f32,
+        emissive_factor: [f32; 3],
+        albedo_source: ShadingSource,
+    ) -> Self {
+        Self {
+            base_color_factor,
+            metallic_factor,
+            roughness_factor,
+            _padding: [0.0; 2],
+            emissive_factor,
+            albedo_source: albedo_source.gpu_value(),
+        }
     }
 }
 
@@ -70,6 +270,22 @@ impl GpuMaterial {
                     ty: BindingType::Sampler(SamplerBindingType::Filtering),
                     count: None,
                 },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
             ],
         })
     }
@@ -78,10 +294,11 @@ impl GpuMaterial {
         device: &Device,
         bind_group_layout: &BindGroupLayout,
         label: &str,
-        material: &ImportedMaterial,
-        texture: Rc<Texture>,
+        material: &Material,
+        texture: Arc<Texture>,
+        metallic_roughness_texture: Arc<Texture>,
     ) -> Self {
-        let uniform = MaterialUniform::new(material.base_color_factor.to_array());
+        let uniform = material.uniform();
         let uniform_buffer = UniformBuffer::new(
             UniformBufferId::new(format!("Material Uniform Buffer: {label}")),
             device,
@@ -108,6 +325,14 @@ impl GpuMaterial {
                     binding: 2,
                     resource: BindingResource::Sampler(&texture.sampler),
                 },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::TextureView(&metallic_roughness_texture.view),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::Sampler(&metallic_roughness_texture.sampler),
+                },
             ],
         });
 
@@ -115,8 +340,36 @@ impl GpuMaterial {
             uniform_buffer,
             bind_group,
             texture,
+            metallic_roughness_texture,
+            bind_group_layout: bind_group_layout.clone(),
         }
     }
+
+    /// A separate uniform buffer and bind group for `material`, sharing this one's textures.
+    pub fn instantiate(&self, device: &Device, label: &str, material: &Material) -> Self {
+        Self::new(
+            device,
+            &self.bind_group_layout,
+            label,
+            material,
+            self.texture.clone(),
+            self.metallic_roughness_texture.clone(),
+        )
+    }
+
+    /// Writes `material` into the uniform buffer if it changed since the last upload. Fails
+    /// without uploading while the material is locked elsewhere; it stays dirty until the next try.
+    pub fn upload_if_dirty(
+        &self,
+        writer: &impl QueueWriter,
+        material: &Shared<Material>,
+    ) -> Result<bool> {
+        let Some(uniform) = material.try_write_shared(Material::take_dirty_uniform)? else {
+            return Ok(false);
+        };
+        writer.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+        Ok(true)
+    }
 }
 
 pub fn default_sampler_descriptor(label: &str) -> wgpu::SamplerDescriptor<'_> {
@@ -175,3 +428,180 @@ fn imported_min_filter_to_wgpu(filter: ImportedMinFilter) -> (FilterMode, Mipmap
         ImportedMinFilter::LinearMipmapLinear => (FilterMode::Linear, MipmapFilterMode::Linear),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use wgpu::{Buffer, BufferAddress};
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+        wrappers::MockSurfaceProvider,
+    };
+
+    #[derive(Default)]
+    struct RecordingQueue {
+        writes: RefCell<Vec<Vec<u8>>>,
+    }
+
+    impl QueueWriter for RecordingQueue {
+        fn write_buffer(&self, _buffer: &Buffer, _offset: BufferAddress, data: &[u8]) {
+            self.writes.borrow_mut().push(data.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_default_material_matches_gltf_defaults() {
+        let material = Material::default();
+
+        assert_eq!(material.base_color, Vec4::ONE);
+        assert_eq!(material.metallic, 1.0);
+        assert_eq!(material.roughness, 1.0);
+        assert_eq!(material.emissive, Vec3::ZERO);
+        assert_eq!(material.alpha_mode, ImportedAlphaMode::Opaque);
+        assert!(!material.double_sided);
+        assert!(!material.is_dirty());
+    }
+
+    #[test]
+    fn test_imported_material_fills_every_field() {
+        let imported = ImportedMaterial {
+            index: 3,
+            name: Some("Brass".to_string()),
+            base_color_factor: Vec4::new(0.9, 0.7, 0.3, 1.0),
+            base_color_texture: Some(ImportedTextureRef {
+                texture_index: 1,
+                tex_coord: 0,
+            }),
+            metallic_factor: 0.8,
+            roughness_factor: 0.35,
+            metallic_roughness_texture: None,
+            emissive_factor: Vec3::new(0.1, 0.0, 0.0),
+            alpha_mode: ImportedAlphaMode::Mask,
+            alpha_cutoff: Some(0.4),
+            double_sided: true,
+        };
+
+        let material = Material::from(&imported);
+
+        assert_eq!(material.base_color, imported.base_color_factor);
+        assert_eq!(material.metallic, 0.8);
+        assert_eq!(material.roughness, 0.35);
+        assert_eq!(material.emissive, Vec3::new(0.1, 0.0, 0.0));
+        assert_eq!(material.alpha_mode, ImportedAlphaMode::Mask);
+        assert_eq!(material.alpha_cutoff, Some(0.4));
+        assert!(material.double_sided);
+        assert_eq!(material.base_color_texture, imported.base_color_texture);
+    }
+
+    #[test]
+    fn test_auto_shading_prefers_texture_then_vertex_colors_then_factor() {
+        let auto = ShadingSource::Auto;
+
+        assert_eq!(auto.resolve(true, true), ShadingSource::Texture);
+        assert_eq!(auto.resolve(true, false), ShadingSource::Texture);
+        assert_eq!(auto.resolve(false, true), ShadingSource::VertexColor);
+        assert_eq!(auto.resolve(false, false), ShadingSource::BaseColorFactor);
+    }
+
+    #[test]
+    fn test_explicit_shading_source_ignores_what_is_available() {
+        for source in [
+            ShadingSource::VertexColor,
+            ShadingSource::BaseColorFactor,
+            ShadingSource::Texture,
+        ] {
+            assert_eq!(source.resolve(false, false), source);
+            assert_eq!(source.resolve(true, true), source);
+        }
+    }
+
+    #[test]
+    fn test_shading_source_cycles_through_every_source() {
+        let mut source = ShadingSource::default();
+        let mut seen = Vec::new();
+        for _ in 0..ShadingSource::ALL.len() {
+            seen.push(source);
+            source = source.next();
+        }
+
+        assert_eq!(source, ShadingSource::Auto);
+        assert_eq!(seen, ShadingSource::ALL);
+    }
+
+    #[test]
+    fn test_uniform_carries_the_resolved_shading_source() {
+        let mut material = Material {
+            has_vertex_colors: true,
+            ..Material::default()
+        };
+        assert_eq!(
+            material.uniform().albedo_source,
+            ShadingSource::VertexColor.gpu_value()
+        );
+
+        material.base_color_texture = Some(ImportedTextureRef {
+            texture_index: 0,
+            tex_coord: 0,
+        });
+        assert_eq!(
+            material.uniform().albedo_source,
+            ShadingSource::Texture.gpu_value()
+        );
+
+        material.shading_source = ShadingSource::BaseColorFactor;
+        assert_eq!(
+            material.uniform().albedo_source,
+            ShadingSource::BaseColorFactor.gpu_value()
+        );
+    }
+
+    #[test]
+    fn test_dirty_uniform_is_taken_once() {
+        let mut material = Material::default();
+        assert!(material.take_dirty_uniform().is_none());
+
+        material.roughness = 0.25;
+        material.mark_dirty();
+        let uniform = material.take_dirty_uniform().unwrap();
+
+        assert_eq!(uniform.roughness_factor, 0.25);
+        assert_eq!(uniform.base_color_factor, [1.0; 4]);
+        assert!(!material.is_dirty());
+        assert!(material.take_dirty_uniform().is_none());
+    }
+
+    #[test]
+    fn test_upload_if_dirty_only_writes_changed_materials() {
+        if !gpu_tests_enabled("test_upload_if_dirty_only_writes_changed_materials") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let fallbacks = ctx.fallback_textures();
+        let material = shared(Material::default());
+        let gpu_material = GpuMaterial::new(
+            &ctx.device,
+            &ctx.material_bind_group_layout,
+            "Tinted",
+            &material.read_shared(|m| m.clone()),
+            fallbacks.base_color.clone(),
+            fallbacks.metallic_roughness.clone(),
+        );
+        let queue = RecordingQueue::default();
+
+        assert!(!gpu_material.upload_if_dirty(&queue, &material).unwrap());
+        material.write_shared(|m| {
+            m.base_color = Vec4::new(1.0, 0.0, 0.0, 1.0);
+            m.mark_dirty();
+        });
+        assert!(gpu_material.upload_if_dirty(&queue, &material).unwrap());
+        assert!(!gpu_material.upload_if_dirty(&queue, &material).unwrap());
+
+        let writes = queue.writes.borrow();
+        assert_eq!(writes.len(), 1);
+        let uploaded: MaterialUniform = bytemuck::pod_read_unaligned(&writes[0]);
+        assert_eq!(uploaded.base_color_factor, [1.0, 0.0, 0.0, 1.0]);
+    }
+}
@@ -1,8 +1,12 @@
 pub mod buffers;
+pub mod cpu_geometry;
 pub mod drawables;
 #[allow(non_snake_case)]
 pub mod glTF;
+pub mod layout_validation;
+pub mod load_progress;
 pub mod material;
+pub mod obj;
 pub mod render_mesh;
 pub mod render_object;
 pub mod render_pipeline;
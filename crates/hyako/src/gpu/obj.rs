@@ -0,0 +1,458 @@
+//! Wavefront OBJ import for quick test geometry. Each `o`/`g` group with faces becomes one root
+//! node holding one mesh, so OBJ files go through the same upload path as glTF scenes.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, Result, anyhow};
+use glam::{Quat, Vec2, Vec3, Vec4};
+use hyakou_core::{
+    geometry::{
+        mesh::Mesh,
+        node::{Node, NodeGraph, NodeId, NodeMetadata},
+        vertices::Vertex,
+    },
+    types::{import_diagnostic::ImportDiagnostic, transform::Transform},
+};
+use tracing::instrument;
+
+use super::{
+    glTF::{ImportedScene, NoRenderableMeshes, generate_normals, read_bytes},
+    load_progress::LoadStage,
+};
+
+#[derive(Debug, Clone)]
+pub struct ObjLoader;
+
+/// One face corner as zero-based indices into the file's `v`, `vt` and `vn` lists.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+#[derive(Debug, Default)]
+struct ObjGroup {
+    name: Option<String>,
+    /// Triangulated corners, three per triangle.
+    corners: Vec<Corner>,
+}
+
+impl ObjLoader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn load_from_path(&self, path: &Path) -> Result<ImportedScene> {
+        self.load_from_path_with_progress(path, &mut |_, _| {})
+            .await
+    }
+
+    /// Like [`Self::load_from_path`], reporting each [`LoadStage`] up to building vertices to
+    /// `progress`.
+    #[instrument(name = "ObjLoader::load_from_path", skip_all, fields(path = %path.display()))]
+    pub async fn load_from_path_with_progress(
+        &self,
+        path: &Path,
+        progress: &mut impl FnMut(LoadStage, f32),
+    ) -> Result<ImportedScene> {
+        progress(LoadStage::ReadingBytes, 0.0);
+        let bytes = read_bytes(path)
+            .await
+            .with_context(|| format!("Failed to read OBJ asset `{}`", path.display()))?;
+        progress(LoadStage::ReadingBytes, 1.0);
+        self.parse(&bytes, path.display().to_string(), progress)
+    }
+
+    pub fn load_from_bytes_with_label(
+        &self,
+        bytes: &[u8],
+        asset_label: impl Into<String>,
+    ) -> Result<ImportedScene> {
+        self.parse(bytes, asset_label.into(), &mut |_, _| {})
+    }
+
+    fn parse(
+        &self,
+        bytes: &[u8],
+        asset_label: String,
+        progress: &mut dyn FnMut(LoadStage, f32),
+    ) -> Result<ImportedScene> {
+        progress(LoadStage::Parsing, 0.0);
+        let source = std::str::from_utf8(bytes)
+            .map_err(|error| anyhow!("OBJ asset `{asset_label}` is not valid UTF-8: {error}"))?;
+
+        let mut positions = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut normals = Vec::new();
+        let mut groups = vec![ObjGroup::default()];
+        let mut skipped = Vec::new();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = line.split('#').next().unwrap_or_default();
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let describe = || format!("line {line_number} of OBJ asset `{asset_label}`");
+
+            match keyword {
+                "v" => positions.push(Vec3::from_array(parse_floats(tokens, 3, &describe)?)),
+                "vt" => {
+                    let [u, v] = parse_floats(tokens, 1, &describe)?;
+                    // OBJ puts the texture origin bottom left, glTF and wgpu top left.
+                    tex_coords.push(Vec2::new(u, 1.0 - v));
+                }
+                "vn" => normals.push(Vec3::from_array(parse_floats(tokens, 3, &describe)?)),
+                "f" => {
+                    let corners = tokens
+                        .map(|corner| {
+                            parse_corner(
+                                corner,
+                                [positions.len(), tex_coords.len(), normals.len()],
+                                &describe,
+                            )
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    if corners.len() < 3 {
+                        return Err(anyhow!(
+                            "Face with fewer than three corners on {}",
+                            describe()
+                        ));
+                    }
+                    let group = groups.last_mut().expect("there is always a current group");
+                    for pair in corners[1..].windows(2) {
+                        group.corners.extend([corners[0], pair[0], pair[1]]);
+                    }
+                }
+                "o" | "g" => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    let name = (!name.is_empty()).then_some(name);
+                    let group = groups.last_mut().expect("there is always a current group");
+                    if group.corners.is_empty() {
+                        group.name = name;
+                    } else {
+                        groups.push(ObjGroup {
+                            name,
+                            corners: Vec::new(),
+                        });
+                    }
+                }
+                "s" | "vp" => {}
+                other => {
+                    if !skipped.contains(&other) {
+                        skipped.push(other);
+                    }
+                }
+            }
+        }
+
+        progress(LoadStage::Parsing, 1.0);
+
+        let diagnostics = skipped
+            .into_iter()
+            .map(|keyword| {
+                ImportDiagnostic::warning(
+                    "obj statement",
+                    format!(
+                        "This OBJ contains `{keyword}` statements for asset `{asset_label}`. Hyakou currently imports faces with positions, texture coordinates and normals, but does not import `{keyword}` data."
+                    ),
+                    None,
+                    None,
+                )
+            })
+            .collect();
+
+        groups.retain(|group| !group.corners.is_empty());
+        let group_count = groups.len();
+        let nodes = groups
+            .into_iter()
+            .enumerate()
+            .map(|(index, group)| {
+                let node = Node {
+                    metadata: NodeMetadata::new(group.name.clone(), Some(index)),
+                    local_transform: Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE),
+                    meshes: vec![build_mesh(group, &positions, &tex_coords, &normals)],
+                    children_ids: vec![],
+                    parent_id: None,
+                };
+                progress(
+                    LoadStage::BuildingVertices,
+                    (index + 1) as f32 / group_count as f32,
+                );
+                node
+            })
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            return Err(NoRenderableMeshes { asset: asset_label }.into());
+        }
+
+        let root_ids = (0..nodes.len()).map(NodeId).collect();
+        Ok(ImportedScene::new(
+            NodeGraph::new(nodes, root_ids),
+            diagnostics,
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+}
+
+impl Default for ObjLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_floats<'a, const N: usize>(
+    tokens: impl Iterator<Item = &'a str>,
+    required: usize,
+    describe: &impl Fn() -> String,
+) -> Result<[f32; N]> {
+    let mut values = [0.0; N];
+    let mut count = 0;
+    for (value, token) in values.iter_mut().zip(tokens) {
+        *value = token
+            .parse()
+            .map_err(|error| anyhow!("Invalid number `{token}` on {}: {error}", describe()))?;
+        count += 1;
+    }
+    if count < required {
+        return Err(anyhow!(
+            "Expected {required} numbers on {}, got {count}",
+            describe()
+        ));
+    }
+    Ok(values)
+}
+
+/// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`, where negative indices count back from the
+/// elements defined so far.
+fn parse_corner(
+    corner: &str,
+    [position_count, tex_coord_count, normal_count]: [usize; 3],
+    describe: &impl Fn() -> String,
+) -> Result<Corner> {
+    let mut parts = corner.split('/');
+    let position =
+        resolve_index(parts.next(), "position", position_count, describe)?.ok_or_else(|| {
+            anyhow!(
+                "Face corner `{corner}` without a position on {}",
+                describe()
+            )
+        })?;
+    let tex_coord = resolve_index(
+        parts.next(),
+        "texture coordinate",
+        tex_coord_count,
+        describe,
+    )?;
+    let normal = resolve_index(parts.next(), "normal", normal_count, describe)?;
+    Ok((position, tex_coord, normal))
+}
+
+fn resolve_index(
+    token: Option<&str>,
+    element: &str,
+    count: usize,
+    describe: &impl Fn() -> String,
+) -> Result<Option<usize>> {
+    let Some(token) = token.filter(|token| !token.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = token.parse().map_err(|error| {
+        anyhow!(
+            "Invalid {element} index `{token}` on {}: {error}",
+            describe()
+        )
+    })?;
+    let resolved = match index {
+        1.. => index - 1,
+        ..0 => count as i64 + index,
+        0 => -1,
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(anyhow!(
+            "Face references {element} {index} on {}, but {count} are defined",
+            describe()
+        ));
+    }
+    Ok(Some(resolved as usize))
+}
+
+/// One vertex per distinct corner. Texture coordinates a corner lacks are zero, and normals a
+/// corner lacks are generated from the faces, like attributes missing from a glTF primitive.
+fn build_mesh(group: ObjGroup, positions: &[Vec3], tex_coords: &[Vec2], normals: &[Vec3]) -> Mesh {
+    let mut vertex_indices: HashMap<Corner, u32> = HashMap::new();
+    let mut corners = Vec::new();
+    let indices = group
+        .corners
+        .iter()
+        .map(|corner| {
+            *vertex_indices.entry(*corner).or_insert_with(|| {
+                corners.push(*corner);
+                (corners.len() - 1) as u32
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let vertex_positions = corners
+        .iter()
+        .map(|&(position, _, _)| positions[position])
+        .collect::<Vec<_>>();
+    let generated_normals = if corners.iter().any(|(_, _, normal)| normal.is_none()) {
+        generate_normals(&vertex_positions, &indices)
+    } else {
+        Vec::new()
+    };
+
+    let vertices = corners
+        .iter()
+        .enumerate()
+        .map(|(vertex, &(_, tex_coord, normal))| {
+            Vertex::new(
+                vertex_positions[vertex],
+                tex_coord.map_or(Vec2::ZERO, |index| tex_coords[index]),
+                normal.map_or_else(|| generated_normals[vertex], |index| normals[index]),
+                Vec4::ONE,
+            )
+        })
+        .collect();
+
+    Mesh {
+        name: group.name,
+        material_index: None,
+        vertices,
+        indices,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::util::test_support::assert_vec3_eq;
+
+    use super::*;
+    use crate::renderer::util::asset_root::AssetRoot;
+
+    fn load(source: &str) -> Result<ImportedScene> {
+        ObjLoader::new().load_from_bytes_with_label(source.as_bytes(), "test.obj")
+    }
+
+    #[test]
+    fn test_load_from_path_reads_cube_fixture() {
+        let path = AssetRoot::new().join("obj/test_fixtures/cube.obj").unwrap();
+        let scene = pollster::block_on(ObjLoader::new().load_from_path(&path)).unwrap();
+        let meshes = scene.node_graph.flatten();
+
+        assert_eq!(meshes.len(), 1);
+        assert_eq!(meshes[0].name.as_deref(), Some("Cube"));
+        assert_eq!(meshes[0].vertices.len(), 24, "4 corners per face");
+        assert_eq!(meshes[0].indices.len(), 36, "2 triangles per face");
+        assert!(scene.diagnostics.is_empty(), "{:?}", scene.diagnostics);
+        assert!(
+            meshes[0]
+                .vertices
+                .iter()
+                .all(|vertex| vertex.position.dot(vertex.normals) > 0.0),
+            "normals point outwards"
+        );
+    }
+
+    #[test]
+    fn test_negative_indices_count_back_from_the_latest_elements() {
+        let scene = load(
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\nf -3/-3/-1 -2/-2/-1 -1/-1/-1\n",
+        )
+        .unwrap();
+        let meshes = scene.node_graph.flatten();
+        let mesh = &meshes[0];
+
+        assert_eq!(mesh.indices, [0, 1, 2]);
+        assert_vec3_eq(mesh.vertices[1].position, Vec3::X, "second corner");
+        assert_eq!(
+            mesh.vertices[2].tex_coords,
+            Vec2::new(0.0, 0.0),
+            "flipped v"
+        );
+        assert_eq!(
+            mesh.vertices[0].tex_coords,
+            Vec2::new(0.0, 1.0),
+            "flipped v"
+        );
+    }
+
+    #[test]
+    fn test_missing_normals_and_tex_coords_are_filled_in() {
+        let scene = load("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n").unwrap();
+        let meshes = scene.node_graph.flatten();
+        let mesh = &meshes[0];
+
+        assert_eq!(
+            mesh.indices,
+            [0, 1, 2, 0, 2, 3],
+            "quad fanned into triangles"
+        );
+        for vertex in &mesh.vertices {
+            assert_vec3_eq(vertex.normals, Vec3::Z, "generated normal");
+            assert_eq!(vertex.tex_coords, Vec2::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_groups_become_separate_meshes() {
+        let scene = load(
+            "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\no First\nf 1 2 3\ng Second\nusemtl red\nf 1 3 2\n",
+        )
+        .unwrap();
+        let names = scene
+            .node_graph
+            .flatten()
+            .iter()
+            .map(|mesh| mesh.name.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, [Some("First".into()), Some("Second".into())]);
+        assert_eq!(scene.diagnostics.len(), 2, "mtllib and usemtl are reported");
+    }
+
+    #[test]
+    fn test_load_from_path_reports_progress_per_group() {
+        let path = AssetRoot::new().join("obj/test_fixtures/cube.obj").unwrap();
+        let mut stages = Vec::new();
+
+        pollster::block_on(
+            ObjLoader::new().load_from_path_with_progress(&path, &mut |stage, fraction| {
+                stages.push((stage, fraction))
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            stages,
+            [
+                (LoadStage::ReadingBytes, 0.0),
+                (LoadStage::ReadingBytes, 1.0),
+                (LoadStage::Parsing, 0.0),
+                (LoadStage::Parsing, 1.0),
+                (LoadStage::BuildingVertices, 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_and_faceless_files_are_rejected() {
+        let error = load("v 0 0 0\nv 1 0 0\nf 1 2 3\n")
+            .err()
+            .expect("out-of-range index is rejected");
+        assert!(
+            error
+                .to_string()
+                .contains("position 3 on line 3 of OBJ asset `test.obj`"),
+            "{error}"
+        );
+
+        let error = load("v 0 0 0\n").err().expect("faceless file is rejected");
+        assert!(
+            error.downcast_ref::<NoRenderableMeshes>().is_some(),
+            "{error}"
+        );
+    }
+}
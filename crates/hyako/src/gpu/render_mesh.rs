@@ -1,63 +1,145 @@
+use anyhow::{Result, anyhow};
+use glam::{Mat4, Vec3};
 use uuid::Uuid;
 use wgpu::{
-    BindGroup, BindGroupLayout, Buffer, BufferUsages, Device,
+    BindGroup, BindGroupLayout, Buffer, BufferUsages, Device, IndexFormat, Queue,
     util::{BufferInitDescriptor, DeviceExt},
 };
 
 use crate::{
     gpu::buffers::{model_matrix::ModelMatrixUniform, uniform::UniformBuffer},
-    gpu::material::GpuMaterial,
-    renderer::util::Concatable,
+    gpu::cpu_geometry::{CpuGeometry, CpuGeometryRetention},
+    gpu::material::{GpuMaterial, Material},
 };
 
 use hyakou_core::{
     Shared, SharedAccess,
     components::{LightType, mesh_node::MeshNode},
+    geometry::{
+        aabb::Aabb,
+        ray::{Ray, TriangleHit},
+        vertices::Vertex,
+    },
     shared,
     traits::BindGroupProvider,
     types::{
         ModelMatrixBindingMode,
         ids::{MeshId, UniformBufferId},
-        transform::Transform,
+        sync_cell::SyncCell,
+        transform::{MatrixCache, Transform},
     },
 };
-use std::rc::Rc;
+use std::sync::Arc;
+
+/// How a mesh's vertex and index buffers are going to be used. Only `Dynamic` buffers can be
+/// written after creation, which costs a `COPY_DST` usage most meshes never need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshUsage {
+    #[default]
+    Static,
+    Dynamic,
+}
+
+impl MeshUsage {
+    pub fn vertex_usages(self) -> BufferUsages {
+        self.with_copy_dst(BufferUsages::VERTEX)
+    }
+
+    pub fn index_usages(self) -> BufferUsages {
+        self.with_copy_dst(BufferUsages::INDEX)
+    }
+
+    fn with_copy_dst(self, usages: BufferUsages) -> BufferUsages {
+        match self {
+            Self::Static => usages,
+            Self::Dynamic => usages | BufferUsages::COPY_DST,
+        }
+    }
+}
+
+/// What a geometry update did. Anything that cached the old buffers or index count has to
+/// refresh when the matching flag is set.
+#[must_use]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GeometryUpdate {
+    pub reallocated: bool,
+    pub index_count_changed: bool,
+}
+
+/// Whether `bytes` of new geometry can go into the existing `buffer_size` bytes. Errors when they
+/// exceed the device's `max_buffer_size`.
+pub fn fits_in_place(bytes: u64, buffer_size: u64, max_buffer_size: u64) -> Result<bool> {
+    if bytes > max_buffer_size {
+        return Err(anyhow!(
+            "Geometry of {bytes} bytes exceeds the device buffer limit of {max_buffer_size} bytes"
+        ));
+    }
+    Ok(bytes <= buffer_size)
+}
+
+/// GPU index data for `indices`, narrowed to 16 bits when the largest index allows it.
+pub fn encode_indices(indices: &[u32]) -> (IndexFormat, Vec<u8>) {
+    let fits_u16 = indices.iter().all(|&index| u16::try_from(index).is_ok());
+    if fits_u16 {
+        let narrowed: Vec<u16> = indices.iter().map(|&index| index as u16).collect();
+        (
+            IndexFormat::Uint16,
+            bytemuck::cast_slice(&narrowed).to_vec(),
+        )
+    } else {
+        (IndexFormat::Uint32, bytemuck::cast_slice(indices).to_vec())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshHit {
+    pub mesh_id: MeshId,
+    pub distance: f32,
+    pub barycentric: Vec3,
+    pub triangle_index: usize,
+}
 
 #[derive(Debug, Clone)]
 pub struct RenderMesh {
     pub id: MeshId,
-    pub vertex_buffer: Buffer,
-    pub index_buffer: Buffer,
+    /// Shared with meshes instantiated from this one and with assets loaded from the same file.
+    pub vertex_buffer: Arc<Buffer>,
+    pub index_buffer: Arc<Buffer>,
     pub index_count: u32,
+    /// `Uint16` whenever every index fits, which halves index memory for most meshes.
+    pub index_format: IndexFormat,
+    pub usage: MeshUsage,
     pub light_type: LightType,
     pub transform: Shared<Transform>,
     pub model_uniform_buffer: Option<UniformBuffer>,
     pub model_bind_group: Option<BindGroup>,
-    pub material: Rc<GpuMaterial>,
+    model_matrix: MatrixCache,
+    /// What `model_uniform_buffer` holds since the last write; `None` until the first draw.
+    uploaded_model_matrix: SyncCell<Option<Mat4>>,
+    /// This mesh's own material; see [`GpuMaterial::upload_if_dirty`].
+    pub material: Shared<Material>,
+    pub gpu_material: Arc<GpuMaterial>,
+    /// Starts out [`CpuGeometryRetention::Full`]; see [`Self::retain_geometry`].
+    pub geometry: Arc<CpuGeometry>,
 }
 
 impl RenderMesh {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: &Device,
         mesh_node: MeshNode,
-        material: Rc<GpuMaterial>,
+        material: Shared<Material>,
+        gpu_material: Arc<GpuMaterial>,
         light_type: &LightType,
         label: Option<MeshId>,
+        usage: MeshUsage,
         model_binding_mode: ModelMatrixBindingMode,
         model_bind_group_layout: Option<&BindGroupLayout>,
     ) -> Self {
         let id = label.unwrap_or(MeshId(Uuid::new_v4().to_string()));
-        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Vertex Buffer: ".to_string().concat(&id)),
-            contents: bytemuck::cast_slice(&mesh_node.vertices),
-            usage: BufferUsages::VERTEX,
-        });
-
-        let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Index Buffer: ".to_string().concat(&id)),
-            contents: bytemuck::cast_slice(&mesh_node.indices),
-            usage: BufferUsages::INDEX,
-        });
+        let vertex_buffer = Self::create_vertex_buffer(device, &id, usage, &mesh_node.vertices);
+        let (index_format, index_bytes) = encode_indices(&mesh_node.indices);
+        let index_buffer = Self::create_index_buffer(device, &id, usage, &index_bytes);
         let transform: Shared<Transform> = shared(mesh_node.transform);
         let (model_uniform_buffer, model_bind_group) = Self::create_model_binding_resources(
             device,
@@ -73,10 +155,255 @@ impl RenderMesh {
             index_buffer,
             light_type: light_type.clone(),
             index_count: mesh_node.indices.len() as u32,
+            index_format,
+            usage,
             transform,
             model_uniform_buffer,
             model_bind_group,
+            model_matrix: MatrixCache::default(),
+            uploaded_model_matrix: SyncCell::new(None),
             material,
+            gpu_material,
+            geometry: Arc::new(CpuGeometry::new(
+                &mesh_node.vertices,
+                &mesh_node.indices,
+                CpuGeometryRetention::Full,
+            )),
+        }
+    }
+
+    /// The matrix of the current transform, only rebuilt after the transform changed.
+    pub fn model_matrix(&self) -> Mat4 {
+        self.transform
+            .read_shared(|transform| self.model_matrix.matrix(transform))
+    }
+
+    /// The local bounds moved by the current transform and refitted around their eight corners.
+    /// `None` for meshes without vertices.
+    pub fn world_bounds(&self) -> Option<Aabb> {
+        self.geometry
+            .local_aabb()
+            .map(|aabb| aabb.transformed(&self.model_matrix()))
+    }
+
+    /// Records `matrix` as the content of the model uniform and returns whether it differs from
+    /// what was written before, i.e. whether the buffer needs the write.
+    pub fn mark_model_matrix_uploaded(&self, matrix: Mat4) -> bool {
+        self.uploaded_model_matrix.replace(Some(matrix)) != Some(matrix)
+    }
+
+    /// Takes over the vertex and index data of `source`, e.g. a fresh import of the same file,
+    /// keeping this mesh's transform, material and model binding.
+    pub fn replace_geometry(&mut self, source: &RenderMesh) {
+        self.vertex_buffer = source.vertex_buffer.clone();
+        self.index_buffer = source.index_buffer.clone();
+        self.index_count = source.index_count;
+        self.index_format = source.index_format;
+        self.geometry = source.geometry.clone();
+    }
+
+    /// Drops CPU geometry beyond `retention`. Meshes instantiated earlier keep their own copy.
+    pub fn retain_geometry(&mut self, retention: CpuGeometryRetention) {
+        if retention < self.geometry.retention() {
+            self.geometry = Arc::new(self.geometry.trimmed(retention));
+        }
+    }
+
+    /// Recreates the vertex and index buffers from retained vertices, e.g. on a new device.
+    /// Returns false when the geometry was not retained in full.
+    pub fn reupload_geometry(&mut self, device: &Device) -> bool {
+        let (Some(vertices), Some(indices)) = (self.geometry.vertices(), self.geometry.indices())
+        else {
+            return false;
+        };
+        let (index_format, index_bytes) = encode_indices(indices);
+        self.vertex_buffer = Self::create_vertex_buffer(device, &self.id, self.usage, vertices);
+        self.index_buffer = Self::create_index_buffer(device, &self.id, self.usage, &index_bytes);
+        self.index_format = index_format;
+        self.index_count = indices.len() as u32;
+        true
+    }
+
+    /// Replaces the vertices. They are written in place when they fit and no other mesh shares the
+    /// vertex buffer, otherwise the buffer is reallocated. Meshes instantiated from this one, or
+    /// from the same file, keep the old buffer and geometry.
+    pub fn update_vertices(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        vertices: &[Vertex],
+    ) -> Result<GeometryUpdate> {
+        self.ensure_dynamic()?;
+        let bytes: &[u8] = bytemuck::cast_slice(vertices);
+        let in_place = fits_in_place(
+            bytes.len() as u64,
+            self.vertex_buffer.size(),
+            device.limits().max_buffer_size,
+        )? && Arc::strong_count(&self.vertex_buffer) == 1;
+        if in_place {
+            queue.write_buffer(&self.vertex_buffer, 0, bytes);
+        } else {
+            self.vertex_buffer = Self::create_vertex_buffer(device, &self.id, self.usage, vertices);
+        }
+
+        Arc::make_mut(&mut self.geometry).set_vertices(vertices);
+        Ok(GeometryUpdate {
+            reallocated: !in_place,
+            index_count_changed: false,
+        })
+    }
+
+    /// Replaces the indices, reallocating when they outgrow the buffer, need a wider format or the
+    /// buffer is shared with another mesh.
+    pub fn update_indices(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        indices: &[u32],
+    ) -> Result<GeometryUpdate> {
+        self.ensure_dynamic()?;
+        let (index_format, mut bytes) = encode_indices(indices);
+        // Buffer writes have to be a multiple of four bytes; an odd number of u16 indices is not.
+        bytes.resize(
+            bytes
+                .len()
+                .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize),
+            0,
+        );
+        let in_place = index_format == self.index_format
+            && fits_in_place(
+                bytes.len() as u64,
+                self.index_buffer.size(),
+                device.limits().max_buffer_size,
+            )?
+            && Arc::strong_count(&self.index_buffer) == 1;
+        if in_place {
+            queue.write_buffer(&self.index_buffer, 0, &bytes);
+        } else {
+            self.index_buffer = Self::create_index_buffer(device, &self.id, self.usage, &bytes);
+            self.index_format = index_format;
+        }
+
+        let index_count = indices.len() as u32;
+        let index_count_changed = index_count != self.index_count;
+        self.index_count = index_count;
+        Arc::make_mut(&mut self.geometry).set_indices(indices);
+        Ok(GeometryUpdate {
+            reallocated: !in_place,
+            index_count_changed,
+        })
+    }
+
+    fn ensure_dynamic(&self) -> Result<()> {
+        if self.usage != MeshUsage::Dynamic {
+            return Err(anyhow!(
+                "Mesh `{}` was created with static buffers and cannot be updated",
+                self.id.0
+            ));
+        }
+        Ok(())
+    }
+
+    fn create_vertex_buffer(
+        device: &Device,
+        id: &MeshId,
+        usage: MeshUsage,
+        vertices: &[Vertex],
+    ) -> Arc<Buffer> {
+        Arc::new(device.create_buffer_init(&BufferInitDescriptor {
+            label: Some(&format!("Vertex Buffer: {}", id.0)),
+            contents: bytemuck::cast_slice(vertices),
+            usage: usage.vertex_usages(),
+        }))
+    }
+
+    fn create_index_buffer(
+        device: &Device,
+        id: &MeshId,
+        usage: MeshUsage,
+        bytes: &[u8],
+    ) -> Arc<Buffer> {
+        Arc::new(device.create_buffer_init(&BufferInitDescriptor {
+            label: Some(&format!("Index Buffer: {}", id.0)),
+            contents: bytes,
+            usage: usage.index_usages(),
+        }))
+    }
+
+    pub fn raycast(&self, ray: &Ray) -> Option<MeshHit> {
+        self.raycast_with_culling(ray, false)
+    }
+
+    pub fn raycast_with_culling(&self, ray: &Ray, cull_back_faces: bool) -> Option<MeshHit> {
+        let local_ray = ray.to_local_space(&self.transform.read_shared(|t| *t))?;
+        let TriangleHit {
+            distance,
+            barycentric,
+            triangle_index,
+        } = self.geometry.raycast(&local_ray, cull_back_faces)?;
+
+        Some(MeshHit {
+            mesh_id: self.id.clone(),
+            distance,
+            barycentric,
+            triangle_index,
+        })
+    }
+
+    /// New instance sharing this mesh's GPU geometry and textures, with its own transform, model
+    /// binding and a copy of the material.
+    pub fn instantiate(
+        &self,
+        device: &Device,
+        id: MeshId,
+        transform: Transform,
+        model_binding_mode: ModelMatrixBindingMode,
+        model_bind_group_layout: Option<&BindGroupLayout>,
+    ) -> Self {
+        self.instantiate_with_shared(
+            device,
+            id,
+            shared(transform),
+            shared(self.material.read_shared(|material| material.clone())),
+            model_binding_mode,
+            model_bind_group_layout,
+        )
+    }
+
+    /// Like [`Self::instantiate`], but keeps driving an existing shared transform and material, so
+    /// animators, lights and material edits holding them stay attached.
+    pub fn instantiate_with_shared(
+        &self,
+        device: &Device,
+        id: MeshId,
+        transform: Shared<Transform>,
+        material: Shared<Material>,
+        model_binding_mode: ModelMatrixBindingMode,
+        model_bind_group_layout: Option<&BindGroupLayout>,
+    ) -> Self {
+        let (model_uniform_buffer, model_bind_group) = Self::create_model_binding_resources(
+            device,
+            &id,
+            transform.clone(),
+            model_binding_mode,
+            model_bind_group_layout,
+        );
+        let gpu_material = Arc::new(self.gpu_material.instantiate(
+            device,
+            &id.0,
+            &material.read_shared(|material| material.clone()),
+        ));
+
+        Self {
+            id,
+            transform,
+            model_uniform_buffer,
+            model_bind_group,
+            model_matrix: MatrixCache::default(),
+            uploaded_model_matrix: SyncCell::new(None),
+            material,
+            gpu_material,
+            ..self.clone()
         }
     }
 
@@ -106,3 +433,48 @@ impl RenderMesh {
         (Some(uniform_buffer), Some(bind_group))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_dynamic_meshes_get_copy_dst() {
+        assert_eq!(MeshUsage::default(), MeshUsage::Static);
+        assert_eq!(MeshUsage::Static.vertex_usages(), BufferUsages::VERTEX);
+        assert_eq!(
+            MeshUsage::Dynamic.index_usages(),
+            BufferUsages::INDEX | BufferUsages::COPY_DST
+        );
+    }
+
+    #[test]
+    fn test_geometry_updates_are_checked_against_the_buffer_limit() {
+        assert!(fits_in_place(64, 128, 1024).unwrap());
+        assert!(fits_in_place(128, 128, 1024).unwrap());
+        assert!(!fits_in_place(256, 128, 1024).unwrap());
+        assert!(fits_in_place(2048, 128, 1024).is_err());
+    }
+
+    #[test]
+    fn test_small_meshes_get_16_bit_indices() {
+        let indices = [0, 1, 2, 2, 1, u16::MAX as u32];
+
+        let (format, bytes) = encode_indices(&indices);
+
+        assert_eq!(format, IndexFormat::Uint16);
+        let expected: [u16; 6] = [0, 1, 2, 2, 1, u16::MAX];
+        assert_eq!(bytes, bytemuck::cast_slice::<u16, u8>(&expected));
+        assert_eq!(bytes.len() / 2, indices.len());
+    }
+
+    #[test]
+    fn test_indices_past_u16_keep_32_bits() {
+        let indices = [0, 1, u16::MAX as u32 + 1];
+
+        let (format, bytes) = encode_indices(&indices);
+
+        assert_eq!(format, IndexFormat::Uint32);
+        assert_eq!(bytes, bytemuck::cast_slice::<u32, u8>(&indices));
+    }
+}
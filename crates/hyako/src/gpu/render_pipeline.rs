@@ -1,8 +1,13 @@
 use hyakou_core::{geometry::vertices::Vertex, traits::BufferLayoutProvider};
 use wgpu::{
-    BlendState, ColorTargetState, ColorWrites, Device, FragmentState, MultisampleState,
-    PipelineCompilationOptions, PipelineLayout, PrimitiveState, RenderPipeline,
-    RenderPipelineDescriptor, ShaderModule, TextureFormat, VertexState,
+    BlendState, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState, Device, Face,
+    FragmentState, MultisampleState, PipelineCompilationOptions, PipelineLayout, PolygonMode,
+    PrimitiveState, RenderPipeline, RenderPipelineDescriptor, ShaderModule, TextureFormat,
+    VertexBufferLayout, VertexState,
+};
+
+use crate::gpu::buffers::{
+    billboard::BillboardInstance, glyph::GlyphInstance, instance::InstanceData,
 };
 
 pub fn create_render_pipeline(
@@ -12,6 +17,215 @@ pub fn create_render_pipeline(
     color_format: TextureFormat,
     shader_module: ShaderModule,
     depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[Vertex::vertex_buffer_layout()],
+        depth_format.map(|format| depth_stencil_state(format, true, CompareFunction::Less)),
+        None,
+        Some(BlendState::REPLACE),
+        PolygonMode::Fill,
+    )
+}
+
+/// Draws triangle edges only, for inspecting geometry. Back faces stay visible so the whole mesh
+/// shows; needs [`wgpu::Features::POLYGON_MODE_LINE`].
+pub fn create_wireframe_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+    depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[Vertex::vertex_buffer_layout()],
+        depth_format.map(|format| depth_stencil_state(format, true, CompareFunction::Less)),
+        None,
+        Some(BlendState::REPLACE),
+        PolygonMode::Line,
+    )
+}
+
+/// Overlays (e.g. gizmos) are drawn on top of the scene, so they neither test nor write depth.
+pub fn create_overlay_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+    depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[Vertex::vertex_buffer_layout()],
+        depth_format.map(|format| depth_stencil_state(format, false, CompareFunction::Always)),
+        None,
+        Some(BlendState::REPLACE),
+        PolygonMode::Fill,
+    )
+}
+
+/// Inverted-hull outlines draw only back faces and test depth without writing it, so closer
+/// geometry still hides them.
+pub fn create_outline_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+    depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[Vertex::vertex_buffer_layout()],
+        depth_format.map(|format| depth_stencil_state(format, false, CompareFunction::Less)),
+        Some(Face::Front),
+        Some(BlendState::REPLACE),
+        PolygonMode::Fill,
+    )
+}
+
+/// Same as [`create_render_pipeline`], but with a second, per-instance vertex buffer of
+/// [`InstanceData`], so one draw call renders every instance of a mesh.
+pub fn create_instanced_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+    depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[
+            Vertex::vertex_buffer_layout(),
+            InstanceData::vertex_buffer_layout(),
+        ],
+        depth_format.map(|format| depth_stencil_state(format, true, CompareFunction::Less)),
+        None,
+        Some(BlendState::REPLACE),
+        PolygonMode::Fill,
+    )
+}
+
+/// Camera-facing quads built in the vertex shader from a per-instance [`BillboardInstance`]
+/// buffer alone. They are depth tested like scene geometry, so meshes in front hide them.
+pub fn create_billboard_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+    depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[BillboardInstance::vertex_buffer_layout()],
+        depth_format.map(|format| depth_stencil_state(format, true, CompareFunction::Less)),
+        None,
+        Some(BlendState::REPLACE),
+        PolygonMode::Fill,
+    )
+}
+
+/// Screen-space text quads from a per-instance [`GlyphInstance`] buffer, blended over the finished
+/// frame without a depth attachment.
+pub fn create_text_overlay_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        color_format,
+        shader_module,
+        &[GlyphInstance::vertex_buffer_layout()],
+        None,
+        None,
+        Some(BlendState::ALPHA_BLENDING),
+        PolygonMode::Fill,
+    )
+}
+
+/// Writes object ids into an integer target, which cannot blend. Depth is tested and written like
+/// the scene pass so the closest mesh wins.
+pub fn create_picking_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    shader_module: ShaderModule,
+    depth_format: Option<TextureFormat>,
+) -> RenderPipeline {
+    build_render_pipeline(
+        device,
+        label,
+        pipeline_layout,
+        TextureFormat::R32Uint,
+        shader_module,
+        &[Vertex::vertex_buffer_layout()],
+        depth_format.map(|format| depth_stencil_state(format, true, CompareFunction::Less)),
+        None,
+        None,
+        PolygonMode::Fill,
+    )
+}
+
+fn depth_stencil_state(
+    format: TextureFormat,
+    depth_write_enabled: bool,
+    depth_compare: CompareFunction,
+) -> DepthStencilState {
+    DepthStencilState {
+        format,
+        depth_write_enabled: Some(depth_write_enabled),
+        depth_compare: Some(depth_compare),
+        stencil: wgpu::StencilState::default(),
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_render_pipeline(
+    device: &Device,
+    label: &str,
+    pipeline_layout: &PipelineLayout,
+    color_format: TextureFormat,
+    shader_module: ShaderModule,
+    buffers: &[VertexBufferLayout<'_>],
+    depth_stencil: Option<DepthStencilState>,
+    cull_mode: Option<Face>,
+    blend: Option<BlendState>,
+    polygon_mode: PolygonMode,
 ) -> RenderPipeline {
     device.create_render_pipeline(&RenderPipelineDescriptor {
         label: Some(label),
@@ -20,24 +234,18 @@ pub fn create_render_pipeline(
             module: &shader_module,
             entry_point: Some("vs_main"),
             compilation_options: PipelineCompilationOptions::default(),
-            buffers: &[Vertex::vertex_buffer_layout()],
+            buffers,
         },
         primitive: PrimitiveState {
             topology: wgpu::PrimitiveTopology::TriangleList,
             strip_index_format: None,
             front_face: wgpu::FrontFace::Ccw,
-            cull_mode: None,
+            cull_mode,
             unclipped_depth: false,
-            polygon_mode: wgpu::PolygonMode::Fill,
+            polygon_mode,
             conservative: false,
         },
-        depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
-            format,
-            depth_write_enabled: Some(true),
-            depth_compare: Some(wgpu::CompareFunction::Less),
-            stencil: wgpu::StencilState::default(),
-            bias: wgpu::DepthBiasState::default(),
-        }),
+        depth_stencil,
         multisample: MultisampleState {
             count: 1,
             mask: 0,
@@ -49,7 +257,7 @@ pub fn create_render_pipeline(
             compilation_options: PipelineCompilationOptions::default(),
             targets: &[Some(ColorTargetState {
                 format: color_format,
-                blend: Some(BlendState::REPLACE),
+                blend,
                 write_mask: ColorWrites::ALL,
             })],
         }),
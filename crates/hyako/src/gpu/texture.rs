@@ -1,10 +1,21 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::{borrow::Cow, sync::Arc};
+
+use anyhow::{Context, Result};
 use hyakou_core::types::Size;
+use image::RgbaImage;
 use wgpu::{
-    CompareFunction, Device, Extent3d, FilterMode, MipmapFilterMode, Sampler, SamplerDescriptor,
-    TextureDescriptor, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
-    util::{DeviceExt, TextureDataOrder},
+    COPY_BYTES_PER_ROW_ALIGNMENT, CompareFunction, Device, Extent3d, FilterMode, MipmapFilterMode,
+    Origin3d, Queue, Sampler, SamplerDescriptor, TexelCopyBufferLayout, TexelCopyTextureInfo,
+    TextureAspect, TextureDescriptor, TextureFormat, TextureUsages, TextureView,
+    TextureViewDescriptor,
 };
 
+use crate::gpu::material::default_sampler_descriptor;
+
+const RGBA8_BYTES_PER_PIXEL: u32 = 4;
+
 #[derive(Debug, Clone)]
 pub struct Texture {
     pub texture: wgpu::Texture,
@@ -12,6 +23,46 @@ pub struct Texture {
     pub sampler: Sampler,
 }
 
+/// 1x1 stand-ins bound wherever a material has no texture of its own, so textureless meshes go
+/// through the same pipeline and bind group layout as textured ones.
+#[derive(Debug, Clone)]
+pub struct FallbackTextures {
+    pub base_color: Arc<Texture>,
+    /// A flat tangent-space normal, `(0.5, 0.5, 1.0)`.
+    pub normal: Arc<Texture>,
+    pub emissive: Arc<Texture>,
+    /// White, so the material's metallic and roughness factors apply unchanged.
+    pub metallic_roughness: Arc<Texture>,
+}
+
+impl FallbackTextures {
+    pub fn new(device: &Device, queue: &Queue) -> Self {
+        let create = |label: &str, pixel: [u8; 4], srgb: bool| {
+            Arc::new(Texture::upload_rgba8(
+                label,
+                device,
+                queue,
+                1,
+                1,
+                &pixel,
+                color_format(srgb),
+                &default_sampler_descriptor(label),
+            ))
+        };
+
+        Self {
+            base_color: create("Fallback Base Color Texture", [255, 255, 255, 255], true),
+            normal: create("Fallback Normal Texture", [128, 128, 255, 255], false),
+            emissive: create("Fallback Emissive Texture", [0, 0, 0, 255], true),
+            metallic_roughness: create(
+                "Fallback Metallic Roughness Texture",
+                [255, 255, 255, 255],
+                false,
+            ),
+        }
+    }
+}
+
 impl Texture {
     pub const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
     pub const COLOR_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
@@ -31,7 +82,10 @@ impl Texture {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: Self::DEPTH_FORMAT,
-            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT,
+            // COPY_SRC lets the renderer read back the depth under the cursor.
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::COPY_SRC,
             view_formats: &[],
         };
         let texture = device.create_texture(&desc);
@@ -61,33 +115,104 @@ impl Texture {
     pub fn create_color_texture(
         label: &str,
         device: &Device,
-        queue: &wgpu::Queue,
+        queue: &Queue,
         width: u32,
         height: u32,
         rgba8_pixels: &[u8],
         sampler_descriptor: SamplerDescriptor<'_>,
     ) -> Texture {
-        let texture = device.create_texture_with_data(
+        Self::upload_rgba8(
+            label,
+            device,
             queue,
-            &TextureDescriptor {
-                label: Some(label),
-                size: Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: Self::COLOR_FORMAT,
-                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
-                view_formats: &[],
-            },
-            TextureDataOrder::LayerMajor,
+            width,
+            height,
             rgba8_pixels,
+            Self::COLOR_FORMAT,
+            &sampler_descriptor,
+        )
+    }
+
+    /// Decodes a PNG or JPEG and uploads it as RGBA8. Colour data such as albedo maps wants
+    /// `srgb`; normal, roughness and other data maps have to stay linear.
+    pub fn from_image_bytes(
+        device: &Device,
+        queue: &Queue,
+        bytes: &[u8],
+        srgb: bool,
+        label: &str,
+    ) -> Result<Texture> {
+        let image = decode_rgba8(bytes).with_context(|| format!("Failed to decode `{label}`"))?;
+        Ok(Self::upload_rgba8(
+            label,
+            device,
+            queue,
+            image.width(),
+            image.height(),
+            image.as_raw(),
+            color_format(srgb),
+            &default_sampler_descriptor(label),
+        ))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path(
+        device: &Device,
+        queue: &Queue,
+        path: impl AsRef<Path>,
+        srgb: bool,
+    ) -> Result<Texture> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read `{}`", path.display()))?;
+        Self::from_image_bytes(device, queue, &bytes, srgb, &path.display().to_string())
+    }
+
+    /// Uploads tightly packed RGBA8 pixels as a sampled texture in `format`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn upload_rgba8(
+        label: &str,
+        device: &Device,
+        queue: &Queue,
+        width: u32,
+        height: u32,
+        rgba8_pixels: &[u8],
+        format: TextureFormat,
+        sampler_descriptor: &SamplerDescriptor<'_>,
+    ) -> Texture {
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let (pixels, bytes_per_row) = pad_rows(rgba8_pixels, width, height);
+        queue.write_texture(
+            TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            &pixels,
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(height),
+            },
+            size,
         );
         let view = texture.create_view(&TextureViewDescriptor::default());
-        let sampler = device.create_sampler(&sampler_descriptor);
+        let sampler = device.create_sampler(sampler_descriptor);
 
         Texture {
             texture,
@@ -96,3 +221,120 @@ impl Texture {
         }
     }
 }
+
+pub fn color_format(srgb: bool) -> TextureFormat {
+    if srgb {
+        TextureFormat::Rgba8UnormSrgb
+    } else {
+        TextureFormat::Rgba8Unorm
+    }
+}
+
+/// Decodes any format the `image` crate was built with (PNG and JPEG) into RGBA8.
+pub fn decode_rgba8(bytes: &[u8]) -> Result<RgbaImage> {
+    Ok(image::load_from_memory(bytes)?.into_rgba8())
+}
+
+/// Row pitch of an RGBA8 upload, rounded up to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+pub fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * RGBA8_BYTES_PER_PIXEL;
+    unpadded.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT
+}
+
+/// Copies tightly packed rows into a buffer with the padded row pitch. Already aligned data is
+/// passed through untouched.
+fn pad_rows(pixels: &[u8], width: u32, height: u32) -> (Cow<'_, [u8]>, u32) {
+    let unpadded = (width * RGBA8_BYTES_PER_PIXEL) as usize;
+    let padded = padded_bytes_per_row(width);
+    if unpadded == padded as usize || height <= 1 {
+        return (Cow::Borrowed(pixels), unpadded as u32);
+    }
+
+    let mut padded_pixels = vec![0; padded as usize * height as usize];
+    for (source, target) in pixels
+        .chunks_exact(unpadded)
+        .zip(padded_pixels.chunks_exact_mut(padded as usize))
+    {
+        target[..unpadded].copy_from_slice(source);
+    }
+    (Cow::Owned(padded_pixels), padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, Rgba};
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+        wrappers::MockSurfaceProvider,
+    };
+
+    /// A 3x2 PNG with a distinct colour in every pixel.
+    fn tiny_png() -> Vec<u8> {
+        let image = RgbaImage::from_fn(3, 2, |x, y| Rgba([x as u8 * 80, y as u8 * 120, 7, 255]));
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_decode_rgba8_keeps_dimensions_and_pixels() {
+        let image = decode_rgba8(&tiny_png()).unwrap();
+
+        assert_eq!(image.dimensions(), (3, 2));
+        assert_eq!(image.get_pixel(2, 1), &Rgba([160, 120, 7, 255]));
+    }
+
+    #[test]
+    fn test_decode_rgba8_rejects_garbage() {
+        assert!(decode_rgba8(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_color_format_follows_srgb_flag() {
+        assert_eq!(color_format(true), TextureFormat::Rgba8UnormSrgb);
+        assert_eq!(color_format(false), TextureFormat::Rgba8Unorm);
+        assert_eq!(color_format(true), Texture::COLOR_FORMAT);
+    }
+
+    #[test]
+    fn test_rows_are_padded_to_copy_alignment() {
+        assert_eq!(padded_bytes_per_row(1), COPY_BYTES_PER_ROW_ALIGNMENT);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+
+        let pixels: Vec<u8> = (0..24).collect();
+        let (padded, bytes_per_row) = pad_rows(&pixels, 3, 2);
+
+        assert_eq!(bytes_per_row, 256);
+        assert_eq!(padded.len(), 512);
+        assert_eq!(&padded[..12], &pixels[..12]);
+        assert_eq!(&padded[256..268], &pixels[12..]);
+        assert!(padded[12..256].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_from_image_bytes_creates_texture_with_requested_format() {
+        if !gpu_tests_enabled("test_from_image_bytes_creates_texture_with_requested_format") {
+            return;
+        }
+
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let bytes = tiny_png();
+
+        let albedo =
+            Texture::from_image_bytes(&ctx.device, &ctx.queue, &bytes, true, "Albedo").unwrap();
+        let normal =
+            Texture::from_image_bytes(&ctx.device, &ctx.queue, &bytes, false, "Normal").unwrap();
+
+        assert_eq!(albedo.texture.width(), 3);
+        assert_eq!(albedo.texture.height(), 2);
+        assert_eq!(albedo.texture.format(), TextureFormat::Rgba8UnormSrgb);
+        assert_eq!(normal.texture.format(), TextureFormat::Rgba8Unorm);
+    }
+}
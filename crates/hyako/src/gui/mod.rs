@@ -56,6 +56,10 @@ impl EguiRenderer {
         self.state.on_window_event(&self.window, event).consumed
     }
 
+    pub fn wants_pointer_input(&self) -> bool {
+        self.context.egui_wants_pointer_input()
+    }
+
     pub fn render(&mut self, target: &mut FrameTarget<'_>, mut render_ui: impl FnMut(&mut Ui)) {
         let egui_input = self.state.take_egui_input(&self.window);
         let output = self.context.run_ui(egui_input, |ui| render_ui(ui));
@@ -1,2 +1,3 @@
 pub mod camera_panel;
 pub mod primitive_overlay;
+pub mod scene_panel;
@@ -0,0 +1,283 @@
+use egui::{Context, DragValue, Ui};
+use glam::{EulerRot, Quat, Vec3, Vec4};
+use hyakou_core::{Shared, SharedAccess, types::transform::Transform};
+use log::warn;
+
+use crate::{gpu::material::Material, renderer::SceneRenderer};
+
+/// Editable copy of a [`Transform`], with the rotation as XYZ Euler angles in degrees the way the
+/// number fields show it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformFields {
+    pub position: Vec3,
+    pub rotation_degrees: Vec3,
+    pub scale: Vec3,
+}
+
+impl TransformFields {
+    pub fn capture(transform: &Transform) -> Self {
+        let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+        Self {
+            position: transform.position,
+            rotation_degrees: Vec3::new(x.to_degrees(), y.to_degrees(), z.to_degrees()),
+            scale: transform.scale,
+        }
+    }
+
+    /// Writes the fields that differ from `captured` into `transform`. The rotation is only
+    /// rebuilt when its angles changed, so an untouched rotation does not drift through the Euler
+    /// round trip.
+    pub fn apply_changes(&self, captured: &Self, transform: &mut Transform) {
+        if self.position != captured.position {
+            transform.position = self.position;
+        }
+        if self.rotation_degrees != captured.rotation_degrees {
+            let radians = self.rotation_degrees * (std::f32::consts::PI / 180.0);
+            transform.rotation = Quat::from_euler(EulerRot::XYZ, radians.x, radians.y, radians.z);
+        }
+        if self.scale != captured.scale {
+            transform.scale = self.scale;
+        }
+    }
+}
+
+/// Writes the panel's edits back into the shared transform and returns whether anything changed.
+/// A transform locked elsewhere keeps its value and the edit is retried on the next frame.
+pub fn sync_transform(
+    transform: &Shared<Transform>,
+    captured: &TransformFields,
+    edited: &TransformFields,
+) -> bool {
+    if edited == captured {
+        return false;
+    }
+    match transform.try_write_shared(|transform| edited.apply_changes(captured, transform)) {
+        Ok(()) => true,
+        Err(lock_error) => {
+            warn!("Skipping transform edit because the transform is busy: {lock_error:?}");
+            false
+        }
+    }
+}
+
+/// The material factors the panel edits; textures and alpha settings stay as imported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialFields {
+    pub base_color: Vec4,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: Vec3,
+}
+
+impl MaterialFields {
+    pub fn capture(material: &Material) -> Self {
+        Self {
+            base_color: material.base_color,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emissive: material.emissive,
+        }
+    }
+
+    pub fn apply(&self, material: &mut Material) {
+        material.base_color = self.base_color;
+        material.metallic = self.metallic;
+        material.roughness = self.roughness;
+        material.emissive = self.emissive;
+    }
+}
+
+/// Scene hierarchy and inspector: every loaded asset with its visibility, and the transform,
+/// material or light color of the primary selection.
+pub struct ScenePanel {
+    open: bool,
+}
+
+impl ScenePanel {
+    pub fn new() -> Self {
+        Self { open: true }
+    }
+
+    pub fn show(&mut self, context: &Context, renderer: &mut SceneRenderer) {
+        egui::Window::new("Scene")
+            .open(&mut self.open)
+            .default_width(260.0)
+            .show(context, |ui| {
+                Self::show_assets(ui, renderer);
+                ui.separator();
+                Self::show_inspector(ui, renderer);
+            });
+    }
+
+    fn show_assets(ui: &mut Ui, renderer: &mut SceneRenderer) {
+        let mut ids: Vec<String> = renderer
+            .asset_manager
+            .get_all_assets()
+            .map(|mesh| mesh.id.0.clone())
+            .collect();
+        ids.sort();
+        ids.push(SceneRenderer::LIGHT_SELECTION_ID.to_string());
+
+        egui::ScrollArea::vertical()
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for id in ids {
+                    ui.horizontal(|ui| {
+                        if id != SceneRenderer::LIGHT_SELECTION_ID {
+                            let mut visible = renderer.asset_manager.is_visible(&id);
+                            if ui.checkbox(&mut visible, "").changed() {
+                                renderer.asset_manager.set_visible(&id, visible);
+                            }
+                        }
+                        let selected = renderer.selection().is_selected(&id);
+                        if ui.selectable_label(selected, &id).clicked() {
+                            renderer.selection_mut().select(id.clone());
+                        }
+                    });
+                }
+            });
+    }
+
+    fn show_inspector(ui: &mut Ui, renderer: &mut SceneRenderer) {
+        let Some(id) = renderer.selection().selected().map(str::to_owned) else {
+            ui.label("Nothing selected");
+            return;
+        };
+        ui.strong(&id);
+
+        if let Some(transform) = renderer.target_transform(&id) {
+            match transform.try_read_shared(TransformFields::capture) {
+                Ok(captured) => {
+                    let mut edited = captured;
+                    vec3_row(ui, "Position", &mut edited.position, 0.05);
+                    vec3_row(ui, "Rotation", &mut edited.rotation_degrees, 1.0);
+                    vec3_row(ui, "Scale", &mut edited.scale, 0.01);
+                    if sync_transform(&transform, &captured, &edited) {
+                        renderer.transform_edited();
+                    }
+                }
+                Err(_) => {
+                    ui.label("Transform is busy");
+                }
+            }
+        }
+
+        if id == SceneRenderer::LIGHT_SELECTION_ID {
+            let mut color = renderer.light().color().to_array();
+            ui.horizontal(|ui| {
+                ui.label("Color");
+                if ui.color_edit_button_rgb(&mut color).changed() {
+                    renderer.set_light_color(Vec3::from_array(color));
+                }
+            });
+            return;
+        }
+
+        let Ok(material) = renderer.asset_manager.material(&id) else {
+            return;
+        };
+        let captured = MaterialFields::capture(&material);
+        let mut edited = captured;
+        let mut base_color = edited.base_color.to_array();
+        let mut emissive = edited.emissive.to_array();
+        ui.horizontal(|ui| {
+            ui.label("Base color");
+            ui.color_edit_button_rgba_unmultiplied(&mut base_color);
+        });
+        ui.add(egui::Slider::new(&mut edited.metallic, 0.0..=1.0).text("Metallic"));
+        ui.add(egui::Slider::new(&mut edited.roughness, 0.0..=1.0).text("Roughness"));
+        ui.horizontal(|ui| {
+            ui.label("Emissive");
+            ui.color_edit_button_rgb(&mut emissive);
+        });
+        edited.base_color = Vec4::from_array(base_color);
+        edited.emissive = Vec3::from_array(emissive);
+        if edited == captured {
+            return;
+        }
+        if let Err(material_error) = renderer
+            .asset_manager
+            .set_material(&id, |material| edited.apply(material))
+        {
+            warn!("Failed to edit the material of `{id}`: {material_error}");
+        }
+    }
+}
+
+impl Default for ScenePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn vec3_row(ui: &mut Ui, label: &str, value: &mut Vec3, speed: f64) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(DragValue::new(&mut value.x).speed(speed).prefix("x "));
+        ui.add(DragValue::new(&mut value.y).speed(speed).prefix("y "));
+        ui.add(DragValue::new(&mut value.z).speed(speed).prefix("z "));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::{shared, util::test_support::assert_vec3_eq};
+
+    use super::*;
+
+    fn transform() -> Shared<Transform> {
+        shared(Transform::new(
+            Vec3::new(1.0, 2.0, 3.0),
+            Quat::from_rotation_y(0.3),
+            Vec3::ONE,
+        ))
+    }
+
+    #[test]
+    fn test_edited_fields_are_written_to_the_transform() {
+        let transform = transform();
+        let captured = transform.read_shared(TransformFields::capture);
+        let mut edited = captured;
+        edited.position.x = 5.0;
+        edited.rotation_degrees.y = 90.0;
+        edited.scale = Vec3::splat(2.0);
+
+        assert!(sync_transform(&transform, &captured, &edited));
+
+        let written = transform.read_shared(|transform| *transform);
+        assert_vec3_eq(written.position, Vec3::new(5.0, 2.0, 3.0), "position");
+        assert_vec3_eq(written.rotation * Vec3::X, Vec3::NEG_Z, "rotated x axis");
+        assert_vec3_eq(written.scale, Vec3::splat(2.0), "scale");
+    }
+
+    #[test]
+    fn test_untouched_fields_keep_their_exact_value() {
+        let transform = transform();
+        let before = transform.read_shared(|transform| *transform);
+        let captured = TransformFields::capture(&before);
+
+        assert!(!sync_transform(&transform, &captured, &captured));
+
+        let mut edited = captured;
+        edited.position.z = -1.0;
+        assert!(sync_transform(&transform, &captured, &edited));
+        let after = transform.read_shared(|transform| *transform);
+        assert_eq!(after.rotation, before.rotation);
+        assert_eq!(after.scale, before.scale);
+    }
+
+    #[test]
+    fn test_material_fields_leave_textures_alone() {
+        let mut material = Material::default();
+        material.double_sided = true;
+        let mut fields = MaterialFields::capture(&material);
+        fields.roughness = 0.25;
+        fields.base_color = Vec4::new(1.0, 0.0, 0.0, 1.0);
+
+        fields.apply(&mut material);
+
+        assert_eq!(material.roughness, 0.25);
+        assert_eq!(material.base_color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert!(material.double_sided);
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+use hyakou_core::types::{DeltaTime64, Size};
+use log::info;
+
+use crate::{
+    cli::CliArgs,
+    renderer::{
+        SceneRenderer, handlers::file_drop::import_models, offscreen::OffscreenTarget,
+        renderer_context::RenderContext, util::asset_root::AssetRoot,
+        wrappers::WinitSurfaceProvider,
+    },
+};
+
+/// Settings for a windowless run, parsed from `--headless` and its companion flags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadlessOptions {
+    pub frames: u32,
+    pub delta_seconds: DeltaTime64,
+    pub size: Size,
+    /// glTF files loaded into an otherwise empty scene.
+    pub models: Vec<PathBuf>,
+    /// Scene file replacing the built-in demo scene.
+    pub scene: Option<PathBuf>,
+    /// Where the last frame is written as PNG.
+    pub output: Option<PathBuf>,
+    pub asset_root: AssetRoot,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            frames: Self::DEFAULT_FRAMES,
+            delta_seconds: 1.0 / 60.0,
+            size: Size {
+                width: 1280,
+                height: 720,
+            },
+            models: Vec::new(),
+            scene: None,
+            output: None,
+            asset_root: AssetRoot::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessReport {
+    pub frames_rendered: u32,
+    pub output: Option<PathBuf>,
+}
+
+impl HeadlessOptions {
+    pub const DEFAULT_FRAMES: u32 = 60;
+
+    /// Returns `None` unless `--headless` is among `args` (program name excluded).
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        Ok(CliArgs::parse(args)?.headless_options())
+    }
+}
+
+/// Steps update and render for `options.frames` frames with a fixed delta, without a window or
+/// event loop.
+pub async fn run_headless(options: &HeadlessOptions) -> Result<HeadlessReport> {
+    if options.frames == 0 {
+        return Err(anyhow!("A headless run needs at least one frame"));
+    }
+
+    let ctx = RenderContext::new::<WinitSurfaceProvider>(None).await?;
+    let mut renderer = SceneRenderer::from_context(ctx, &options.asset_root).await?;
+    renderer.resize(options.size)?;
+    if let Some(scene) = options.scene.as_ref() {
+        renderer.load_scene_file(scene).await?;
+        info!("Loaded scene file {}", scene.display());
+    }
+    if !options.models.is_empty() {
+        renderer.clear_scene();
+        let mesh_ids = import_models(
+            &mut renderer.asset_manager,
+            &mut renderer.camera,
+            &options.models,
+        )
+        .await?;
+        info!("Loaded {} meshes", mesh_ids.len());
+    }
+
+    let target = OffscreenTarget::new(
+        &renderer.get_device(),
+        options.size,
+        renderer.color_format(),
+    );
+    for _ in 0..options.frames {
+        renderer.update(options.delta_seconds);
+        target.render(&mut renderer, 0.0);
+    }
+
+    if let Some(output) = options.output.as_ref() {
+        target
+            .capture_frame(&renderer.get_device(), renderer.get_queue())?
+            .save_png(output)?;
+        info!("Wrote last frame to {}", output.display());
+    }
+
+    Ok(HeadlessReport {
+        frames_rendered: options.frames,
+        output: options.output.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::test_harness::gpu_tests_enabled;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_without_headless_flag_no_options_are_returned() {
+        assert_eq!(HeadlessOptions::from_args(args(&[])).unwrap(), None);
+    }
+
+    #[test]
+    fn test_headless_flags_are_parsed() {
+        let options = HeadlessOptions::from_args(args(&[
+            "--headless",
+            "--frames",
+            "3",
+            "--size",
+            "640x480",
+            "--output",
+            "last.png",
+        ]))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(options.frames, 3);
+        assert_eq!(
+            options.size,
+            Size {
+                width: 640,
+                height: 480
+            }
+        );
+        assert_eq!(options.output, Some(PathBuf::from("last.png")));
+        assert_eq!(options.scene, None);
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_rejected() {
+        assert!(HeadlessOptions::from_args(args(&["--headless", "--frames"])).is_err());
+        assert!(HeadlessOptions::from_args(args(&["--headless", "--size", "0x10"])).is_err());
+        assert!(HeadlessOptions::from_args(args(&["--fast"])).is_err());
+    }
+
+    #[test]
+    fn test_headless_run_renders_three_frames() {
+        if !gpu_tests_enabled("test_headless_run_renders_three_frames") {
+            return;
+        }
+        let output = std::env::temp_dir().join("hyako_headless_test.png");
+        let options = HeadlessOptions {
+            frames: 3,
+            size: Size {
+                width: 64,
+                height: 48,
+            },
+            output: Some(output.clone()),
+            ..HeadlessOptions::default()
+        };
+
+        let report = pollster::block_on(run_headless(&options)).unwrap();
+
+        assert_eq!(report.frames_rendered, 3);
+        let (width, height) = image::image_dimensions(&output).unwrap();
+        assert_eq!((width, height), (64, 48));
+        let _ = std::fs::remove_file(output);
+    }
+}
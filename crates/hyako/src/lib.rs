@@ -1,5 +1,13 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
 pub mod flow;
 pub mod gpu;
+#[cfg(feature = "egui")]
 pub mod gui;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
+#[cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+pub mod profiling;
 pub mod renderer;
 pub mod state;
+pub mod window_config;
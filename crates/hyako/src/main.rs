@@ -1,26 +1,109 @@
-use hyako::state::AppState;
+use std::path::Path;
+
+use hyako::{
+    cli::CliArgs,
+    headless::{HeadlessOptions, run_headless},
+    renderer::settings::RendererSettings,
+    state::AppState,
+    window_config::WindowConfig,
+};
 use hyakou_core::events::Event;
-use log::debug;
+use log::{LevelFilter, debug, error, info};
 use winit::event_loop::EventLoop;
 
+/// Loaded instead of the built-in demo scene when present in the working directory and neither a
+/// scene file nor models are given on the command line.
+const SCENE_FILE: &str = "scene.ron";
+
 #[allow(unused)]
 fn main() {
-    let mut app_state = AppState::new().unwrap();
+    let cli = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(args_error) => {
+            eprintln!("{args_error}");
+            std::process::exit(2);
+        }
+    };
+    init_logger(cli.log_level);
+    #[cfg(feature = "profiling")]
+    let profiler = start_profiler();
+
+    if let Some(options) = cli.headless_options() {
+        let exit_code = start_headless(&options);
+        // `process::exit` skips destructors, and the trace is only written on drop.
+        #[cfg(feature = "profiling")]
+        drop(profiler);
+        std::process::exit(exit_code);
+    }
+
+    let renderer_settings = match RendererSettings::load_from_env() {
+        Ok(settings) => settings,
+        Err(config_error) => {
+            error!("{config_error}");
+            std::process::exit(2);
+        }
+    };
+    let mut window_config = WindowConfig::default().with_env_overrides();
+    if let Some(size) = cli.size {
+        window_config = window_config.with_size(size.width, size.height);
+    }
+    let mut app_state = AppState::new(window_config)
+        .unwrap()
+        .with_renderer_settings(renderer_settings);
+    if let Some(scene) = cli.scene {
+        app_state = app_state.with_scene_file(scene);
+    } else if !cli.models.is_empty() {
+        app_state = app_state.with_models(cli.models);
+    } else if Path::new(SCENE_FILE).exists() {
+        info!("Loading scene file {SCENE_FILE}");
+        app_state = app_state.with_scene_file(SCENE_FILE);
+    }
 
     #[cfg(any(target_family = "unix", target_family = "windows"))]
     start_app_os(&mut app_state);
 }
 
-#[allow(unused)]
-fn start_app_os(app_state: &mut AppState) {
-    let event_loop = EventLoop::<Event>::with_user_event().build().unwrap();
-
+fn init_logger(level: LevelFilter) {
     env_logger::Builder::new()
-        .filter_level(log::LevelFilter::Debug)
+        .filter_level(level)
         .filter_module("wgpu_hal::metal::device", log::LevelFilter::Error)
         .filter_module("naga", log::LevelFilter::Error)
         .try_init()
         .unwrap();
+}
+
+#[cfg(feature = "profiling")]
+fn start_profiler() -> Option<hyako::profiling::Profiler> {
+    match hyako::profiling::Profiler::start_from_env() {
+        Ok(profiler) => {
+            info!("Writing a trace to {}", profiler.path().display());
+            Some(profiler)
+        }
+        Err(profiler_error) => {
+            error!("Profiling disabled: {profiler_error:#}");
+            None
+        }
+    }
+}
+
+/// Runs without a window and returns the process exit code, so CI can gate on it.
+fn start_headless(options: &HeadlessOptions) -> i32 {
+    match pollster::block_on(run_headless(options)) {
+        Ok(report) => {
+            info!("Headless run rendered {} frames", report.frames_rendered);
+            0
+        }
+        Err(run_error) => {
+            error!("Headless run failed: {run_error:#}");
+            1
+        }
+    }
+}
+
+#[allow(unused)]
+fn start_app_os(app_state: &mut AppState) {
+    let event_loop = EventLoop::<Event>::with_user_event().build().unwrap();
+
     match event_loop.run_app(app_state) {
         Ok(_) => debug!("App exited successfully"),
         Err(e) => {
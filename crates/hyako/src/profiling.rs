@@ -0,0 +1,56 @@
+//! Records the `tracing` spans around update, encoding, submission and asset import into a Chrome
+//! trace file. Only built with the `profiling` feature; without a subscriber the spans cost next
+//! to nothing.
+//!
+//! ```no_run
+//! // cargo run -p hyako --features profiling
+//! let profiler = hyako::profiling::Profiler::start("hyakou-trace.json")?;
+//! // ... run the app ...
+//! drop(profiler); // the trace is written here
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Keeps the trace open; dropping it flushes the file.
+pub struct Profiler {
+    path: PathBuf,
+    _guard: FlushGuard,
+}
+
+impl Profiler {
+    pub const DEFAULT_TRACE_FILE: &str = "hyakou-trace.json";
+    pub const TRACE_FILE_ENV_VAR: &str = "HYAKOU_TRACE_FILE";
+
+    /// Installs the global subscriber, so this can only be called once per process.
+    pub fn start(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (chrome_layer, guard) = ChromeLayerBuilder::new()
+            .file(&path)
+            .include_args(true)
+            .build();
+        tracing_subscriber::registry()
+            .with(chrome_layer)
+            .try_init()
+            .context("a tracing subscriber is already installed")?;
+        Ok(Self {
+            path,
+            _guard: guard,
+        })
+    }
+
+    /// Writes to `HYAKOU_TRACE_FILE`, or [`Self::DEFAULT_TRACE_FILE`] when it is unset.
+    pub fn start_from_env() -> Result<Self> {
+        let path = std::env::var(Self::TRACE_FILE_ENV_VAR)
+            .unwrap_or_else(|_| Self::DEFAULT_TRACE_FILE.to_string());
+        Self::start(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppActions {
+    Quit,
+    ToggleFullscreen,
+    OpenWindow,
+    Screenshot,
+    ToggleStatsOverlay,
+}
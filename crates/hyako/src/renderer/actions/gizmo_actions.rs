@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GizmoActions {
+    Cancel,
+    TranslateMode,
+    RotateMode,
+    ScaleMode,
+}
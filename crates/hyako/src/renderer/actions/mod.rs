@@ -1,16 +1,67 @@
+pub mod app_actions;
 pub mod camera_actions;
+pub mod gizmo_actions;
+pub mod nudge_actions;
+pub mod selection_actions;
+pub mod simulation_actions;
 
+pub use app_actions::AppActions;
 pub use camera_actions::CameraActions;
+pub use gizmo_actions::GizmoActions;
+pub use nudge_actions::NudgeActions;
+pub use selection_actions::SelectionActions;
+pub use simulation_actions::SimulationActions;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Action {
+    App(AppActions),
     Camera(CameraActions),
+    Gizmo(GizmoActions),
+    Nudge(NudgeActions),
+    Selection(SelectionActions),
+    Simulation(SimulationActions),
 }
 
 impl Action {
+    pub fn as_app(&self) -> Option<&AppActions> {
+        match self {
+            Action::App(action) => Some(action),
+            _ => None,
+        }
+    }
+
     pub fn as_camera(&self) -> Option<&CameraActions> {
         match self {
             Action::Camera(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn as_gizmo(&self) -> Option<&GizmoActions> {
+        match self {
+            Action::Gizmo(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn as_nudge(&self) -> Option<&NudgeActions> {
+        match self {
+            Action::Nudge(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn as_selection(&self) -> Option<&SelectionActions> {
+        match self {
+            Action::Selection(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn as_simulation(&self) -> Option<&SimulationActions> {
+        match self {
+            Action::Simulation(action) => Some(action),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NudgeActions {
+    Left,
+    Right,
+    Forwards,
+    Backwards,
+    Up,
+    Down,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectionActions {
+    Delete,
+    Duplicate,
+    TogglePlacement,
+    /// Debug aid: steps the selection through the albedo sources.
+    CycleShadingSource,
+}
@@ -0,0 +1,180 @@
+use glam::{Mat4, Vec2, Vec3, Vec4Swizzles};
+use wgpu::{Device, TextureAspect};
+
+use crate::renderer::{frame::FrameTarget, texel_readback::TexelReadback};
+
+/// Everything needed to turn a copied depth value back into a world-space point.
+struct DepthSample {
+    texel: [u32; 2],
+    size_in_pixels: [u32; 2],
+    inverse_view_projection: Mat4,
+}
+
+/// The surface found under a probed texel; `position` is `None` where only the sky was drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfacePoint {
+    pub texel: [u32; 2],
+    pub position: Option<Vec3>,
+}
+
+/// Reads the scene depth under a texel back from the frame's depth texture and unprojects it, so
+/// clicks can land on the surface that was actually drawn there. Like [`super::id_picker::IdPicker`]
+/// the answer arrives a frame later.
+pub struct DepthProbe {
+    readback: TexelReadback<DepthSample>,
+    queued: Option<[u32; 2]>,
+    latest: Option<SurfacePoint>,
+}
+
+impl DepthProbe {
+    pub fn new(device: &Device) -> Self {
+        Self {
+            readback: TexelReadback::new(device, "Depth Probe Readback Buffer"),
+            queued: None,
+            latest: None,
+        }
+    }
+
+    /// Probes `texel` in the next frame, replacing any probe that has not been recorded yet.
+    pub fn request(&mut self, texel: [u32; 2]) {
+        self.queued = Some(texel);
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.queued.is_some() || !self.readback.is_idle()
+    }
+
+    /// The last resolved probe.
+    pub fn latest(&self) -> Option<SurfacePoint> {
+        self.latest
+    }
+
+    /// Copies the queued texel out of the frame's depth texture. Has to run after the scene pass,
+    /// with the view-projection that pass was drawn with.
+    pub fn record(&mut self, target: &mut FrameTarget<'_>, inverse_view_projection: Mat4) {
+        if !self.readback.is_idle() {
+            return;
+        }
+        let Some(texel) = self.queued.take() else {
+            return;
+        };
+        if texel[0] >= target.depth_texture.width() || texel[1] >= target.depth_texture.height() {
+            return;
+        }
+        self.readback.copy(
+            target.encoder,
+            target.depth_texture,
+            TextureAspect::DepthOnly,
+            texel,
+            DepthSample {
+                texel,
+                size_in_pixels: target.size_in_pixels,
+                inverse_view_projection,
+            },
+        );
+    }
+
+    /// Advances an in-flight probe without blocking and returns it once resolved.
+    pub fn poll(&mut self, device: &Device) -> Option<SurfacePoint> {
+        let (sample, texel) = self.readback.poll(device)?;
+        let depth = f32::from_ne_bytes(texel);
+        let point = SurfacePoint {
+            texel: sample.texel,
+            position: unproject_depth(
+                texel_to_ndc(sample.texel, sample.size_in_pixels),
+                depth,
+                sample.inverse_view_projection,
+            ),
+        };
+        self.latest = Some(point);
+        Some(point)
+    }
+}
+
+/// Normalized device coordinates of the centre of `texel`; y points up, unlike texel rows.
+pub fn texel_to_ndc(texel: [u32; 2], size_in_pixels: [u32; 2]) -> Vec2 {
+    let x = (texel[0] as f32 + 0.5) / size_in_pixels[0] as f32;
+    let y = (texel[1] as f32 + 0.5) / size_in_pixels[1] as f32;
+    Vec2::new(x * 2.0 - 1.0, 1.0 - y * 2.0)
+}
+
+/// World-space point at `ndc` and `depth`, or `None` when the depth was never written, i.e. the
+/// texel only shows the cleared sky.
+pub fn unproject_depth(ndc: Vec2, depth: f32, inverse_view_projection: Mat4) -> Option<Vec3> {
+    if !(0.0..1.0).contains(&depth) {
+        return None;
+    }
+    let clip = inverse_view_projection * ndc.extend(depth).extend(1.0);
+    (clip.w.abs() > f32::EPSILON).then(|| clip.xyz() / clip.w)
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::util::{float::vec3_approx_eq, test_support::assert_vec3_eq};
+
+    use super::*;
+
+    /// f32 depth in a 0.1..100 frustum loses precision quickly away from the near plane.
+    const UNPROJECT_TOLERANCE: f32 = 1e-3;
+
+    fn view_projection(eye: Vec3) -> Mat4 {
+        Mat4::perspective_rh(60f32.to_radians(), 16.0 / 9.0, 0.1, 100.0)
+            * Mat4::look_at_rh(eye, Vec3::ZERO, Vec3::Y)
+    }
+
+    fn project(view_projection: Mat4, point: Vec3) -> (Vec2, f32) {
+        let clip = view_projection * point.extend(1.0);
+        let ndc = clip.xyz() / clip.w;
+        (ndc.truncate(), ndc.z)
+    }
+
+    #[test]
+    fn test_texel_to_ndc_maps_texel_centres() {
+        assert_eq!(texel_to_ndc([0, 0], [2, 2]), Vec2::new(-0.5, 0.5));
+        assert_eq!(texel_to_ndc([1, 1], [2, 2]), Vec2::new(0.5, -0.5));
+        assert_eq!(texel_to_ndc([50, 50], [101, 101]), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_unproject_recovers_points_seen_by_the_camera() {
+        for eye in [Vec3::new(0.0, 0.0, 5.0), Vec3::new(3.0, 4.0, -6.0)] {
+            let view_projection = view_projection(eye);
+            for point in [
+                Vec3::ZERO,
+                Vec3::new(0.5, -0.25, 0.75),
+                Vec3::new(-1.0, 1.0, 0.0),
+            ] {
+                let (ndc, depth) = project(view_projection, point);
+
+                let unprojected = unproject_depth(ndc, depth, view_projection.inverse()).unwrap();
+
+                assert!(
+                    vec3_approx_eq(unprojected, point, UNPROJECT_TOLERANCE),
+                    "{point} seen from {eye}: got {unprojected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_centre_texel_lands_on_the_look_at_target() {
+        let view_projection = view_projection(Vec3::new(0.0, 2.0, 4.0));
+        let (_, depth) = project(view_projection, Vec3::ZERO);
+
+        let point = unproject_depth(
+            texel_to_ndc([50, 50], [101, 101]),
+            depth,
+            view_projection.inverse(),
+        )
+        .unwrap();
+
+        assert_vec3_eq(point, Vec3::ZERO, "centre texel");
+    }
+
+    #[test]
+    fn test_cleared_depth_is_the_sky() {
+        let inverse = view_projection(Vec3::new(0.0, 0.0, 5.0)).inverse();
+
+        assert!(unproject_depth(Vec2::ZERO, 1.0, inverse).is_none());
+    }
+}
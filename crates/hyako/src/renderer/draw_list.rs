@@ -0,0 +1,99 @@
+use hyakou_core::{components::LightType, types::handle::Handle};
+
+use crate::gpu::render_mesh::RenderMesh;
+
+/// The visible meshes of a frame, sorted by the pipeline that draws them. Rebuilt every frame into
+/// the same vectors, so a scene that does not grow draws without allocating.
+#[derive(Debug, Default)]
+pub struct DrawList {
+    lit: Vec<Handle<RenderMesh>>,
+    unlit: Vec<Handle<RenderMesh>>,
+}
+
+impl DrawList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rebuild(&mut self, visible: impl IntoIterator<Item = (Handle<RenderMesh>, LightType)>) {
+        self.lit.clear();
+        self.unlit.clear();
+        for (handle, light_type) in visible {
+            match light_type {
+                LightType::LIGHT => self.lit.push(handle),
+                LightType::NO_LIGHT => self.unlit.push(handle),
+            }
+        }
+    }
+
+    pub fn lit(&self) -> &[Handle<RenderMesh>] {
+        &self.lit
+    }
+
+    pub fn unlit(&self) -> &[Handle<RenderMesh>] {
+        &self.unlit
+    }
+
+    pub fn len(&self) -> usize {
+        self.lit.len() + self.unlit.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::types::handle::HandleAllocator;
+
+    use super::*;
+    use crate::renderer::test_harness::allocations::count_allocations;
+
+    fn handles(count: usize) -> Vec<Handle<RenderMesh>> {
+        let mut allocator = HandleAllocator::new();
+        (0..count).map(|_| allocator.allocate()).collect()
+    }
+
+    #[test]
+    fn test_meshes_are_split_by_light_type() {
+        let handles = handles(3);
+        let mut draw_list = DrawList::new();
+
+        draw_list.rebuild([
+            (handles[0], LightType::LIGHT),
+            (handles[1], LightType::NO_LIGHT),
+            (handles[2], LightType::LIGHT),
+        ]);
+
+        assert_eq!(draw_list.lit(), [handles[0], handles[2]]);
+        assert_eq!(draw_list.unlit(), [handles[1]]);
+        assert_eq!(draw_list.len(), 3);
+    }
+
+    #[test]
+    fn test_rebuilding_an_unchanged_scene_does_not_allocate() {
+        let handles = handles(64);
+        let visible = || {
+            handles.iter().enumerate().map(|(index, &handle)| {
+                let light_type = if index % 3 == 0 {
+                    LightType::NO_LIGHT
+                } else {
+                    LightType::LIGHT
+                };
+                (handle, light_type)
+            })
+        };
+        let mut draw_list = DrawList::new();
+        draw_list.rebuild(visible());
+
+        let ((), allocations) = count_allocations(|| {
+            for _ in 0..10 {
+                draw_list.rebuild(visible());
+            }
+        });
+
+        assert_eq!(allocations, 0);
+        assert_eq!(draw_list.len(), 64);
+    }
+}
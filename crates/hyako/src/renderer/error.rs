@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use hyakou_core::types::lock_busy::LockBusy;
+use thiserror::Error;
+
+use crate::renderer::scene_file::SceneLoadErrors;
+
+/// What the renderer's public API fails with, so callers can react per case instead of matching
+/// on messages. Binaries still collect it into `anyhow` at the top.
+#[derive(Debug, Error)]
+pub enum RendererError {
+    #[error(transparent)]
+    Import(#[from] ImportError),
+    /// The surface could not be created or configured, or handed out a frame status that cannot
+    /// be recovered by reconfiguring it.
+    #[error("surface error: {reason}")]
+    Surface { reason: String },
+    /// The file parsed as glTF, but none of its nodes hold a mesh.
+    #[error("asset `{asset}` contains no meshes")]
+    NoMeshes { asset: String },
+    #[error("asset `{id}` is not loaded")]
+    AssetNotFound { id: String },
+    #[error("asset `{id}` is not deletable")]
+    AssetNotDeletable { id: String },
+    /// A mesh id the new asset would get belongs to another asset or to a duplicate.
+    #[error("mesh id `{id}` is already used by another asset")]
+    MeshIdTaken { id: String },
+    /// The asset was uploaded as bytes, so there is no file to read it from again.
+    #[error("asset `{id}` was not loaded from a file")]
+    NotReloadable { id: String },
+    /// The mesh was uploaded with static buffers, or the new geometry exceeds the device limits.
+    #[error("cannot update the geometry of `{id}`: {reason}")]
+    GeometryUpdate { id: String, reason: String },
+    #[error("`{id}` is locked elsewhere")]
+    LockContention { id: String },
+    #[error("failed to get a GPU device: {reason}")]
+    DeviceRequest { reason: String },
+    #[error("failed to read `{}`: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("asset root not found: {reason}")]
+    AssetRootNotFound { reason: String },
+    #[error("invalid renderer config `{}`: {reason}", path.display())]
+    Config { path: PathBuf, reason: String },
+    #[error("invalid scene file `{}`: {reason}", path.display())]
+    SceneFile { path: PathBuf, reason: String },
+    #[error(transparent)]
+    Scene(#[from] SceneLoadErrors),
+}
+
+impl RendererError {
+    pub fn surface(reason: impl Into<String>) -> Self {
+        Self::Surface {
+            reason: reason.into(),
+        }
+    }
+
+    pub fn asset_not_found(id: impl Into<String>) -> Self {
+        Self::AssetNotFound { id: id.into() }
+    }
+
+    pub fn device_request(reason: impl std::fmt::Display) -> Self {
+        Self::DeviceRequest {
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl From<LockBusy> for RendererError {
+    fn from(error: LockBusy) -> Self {
+        Self::LockContention {
+            id: error.resource().to_string(),
+        }
+    }
+}
+
+/// A glTF asset that could not be turned into render meshes.
+#[derive(Debug, Error)]
+#[error("failed to import `{asset}`: {reason:#}")]
+pub struct ImportError {
+    asset: String,
+    reason: anyhow::Error,
+}
+
+impl ImportError {
+    pub fn new(asset: impl Into<String>, reason: anyhow::Error) -> Self {
+        Self {
+            asset: asset.into(),
+            reason,
+        }
+    }
+
+    pub fn asset(&self) -> &str {
+        &self.asset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_lock_busy_becomes_lock_contention() {
+        let error = RendererError::from(LockBusy::new("Light Uniform Buffer"));
+
+        assert!(matches!(
+            &error,
+            RendererError::LockContention { id } if id == "Light Uniform Buffer"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "`Light Uniform Buffer` is locked elsewhere"
+        );
+    }
+
+    #[test]
+    fn test_import_error_message_names_the_asset_and_reason() {
+        let error = RendererError::from(ImportError::new("Cube", anyhow!("missing normals")));
+
+        assert_eq!(
+            error.to_string(),
+            "failed to import `Cube`: missing normals"
+        );
+    }
+}
@@ -1,10 +1,14 @@
+use tracing::info_span;
 use wgpu::{CommandEncoder, Queue, SurfaceTexture, TextureView};
 
+use crate::gpu::texture::Texture;
+
 pub struct FrameTarget<'a> {
     pub encoder: &'a mut CommandEncoder,
     pub queue: &'a Queue,
     pub color_view: &'a TextureView,
     pub depth_view: &'a TextureView,
+    pub depth_texture: &'a wgpu::Texture,
     pub size_in_pixels: [u32; 2],
 }
 
@@ -14,6 +18,7 @@ pub struct SurfaceFrame {
     queue: Queue,
     color_view: TextureView,
     depth_view: TextureView,
+    depth_texture: wgpu::Texture,
     size_in_pixels: [u32; 2],
     should_reconfigure_surface: bool,
 }
@@ -24,7 +29,7 @@ impl SurfaceFrame {
         encoder: CommandEncoder,
         queue: Queue,
         color_view: TextureView,
-        depth_view: TextureView,
+        depth: &Texture,
         size_in_pixels: [u32; 2],
         should_reconfigure_surface: bool,
     ) -> Self {
@@ -33,7 +38,8 @@ impl SurfaceFrame {
             encoder,
             queue,
             color_view,
-            depth_view,
+            depth_view: depth.view.clone(),
+            depth_texture: depth.texture.clone(),
             size_in_pixels,
             should_reconfigure_surface,
         }
@@ -45,11 +51,13 @@ impl SurfaceFrame {
             queue: &self.queue,
             color_view: &self.color_view,
             depth_view: &self.depth_view,
+            depth_texture: &self.depth_texture,
             size_in_pixels: self.size_in_pixels,
         }
     }
 
     pub fn finish(self) -> bool {
+        let _span = info_span!("SurfaceFrame::submit").entered();
         self.queue.submit(std::iter::once(self.encoder.finish()));
         self.output.present();
         self.should_reconfigure_surface
@@ -0,0 +1,359 @@
+use bytemuck::bytes_of;
+use std::f32::consts::TAU;
+
+use glam::{Vec2, Vec3, Vec4};
+use hyakou_core::{
+    geometry::vertices::Vertex,
+    shared,
+    traits::BindGroupProvider,
+    types::{ids::UniformBufferId, transform::Transform},
+};
+use wgpu::{
+    BindGroup, Buffer, BufferUsages, CommandEncoder, Device, Operations, Queue,
+    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+    RenderPipeline, TextureView, include_wgsl,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+use crate::{
+    gpu::{
+        buffers::{model_matrix::ModelMatrixUniform, uniform::UniformBuffer},
+        render_pipeline::create_overlay_render_pipeline,
+        texture::Texture,
+    },
+    renderer::{
+        gizmo::{GizmoAxis, GizmoHandle, GizmoMode, TransformGizmo},
+        renderer_context::RenderContext,
+    },
+};
+
+const SHAFT_RADIUS: f32 = 0.02;
+const HEAD_RADIUS: f32 = 0.07;
+const HEAD_LENGTH: f32 = 0.25;
+const RING_THICKNESS: f32 = 0.02;
+const RING_SEGMENTS: usize = 64;
+
+struct GizmoMesh {
+    vertex_buffer: Buffer,
+    index_buffer: Buffer,
+    index_count: u32,
+}
+
+impl GizmoMesh {
+    fn new(device: &Device, label: &str, (vertices, indices): (Vec<Vertex>, Vec<u32>)) -> Self {
+        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some(&format!("{label} Vertex Buffer")),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some(&format!("{label} Index Buffer")),
+            contents: bytemuck::cast_slice(&indices),
+            usage: BufferUsages::INDEX,
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len() as u32,
+        }
+    }
+}
+
+pub struct GizmoRenderer {
+    pipeline: RenderPipeline,
+    translate_mesh: GizmoMesh,
+    rotate_mesh: GizmoMesh,
+    scale_mesh: GizmoMesh,
+    model_uniform_buffer: UniformBuffer,
+    model_bind_group: BindGroup,
+}
+
+impl GizmoRenderer {
+    pub fn new(ctx: &RenderContext) -> Self {
+        let model_bind_group_layout = ModelMatrixUniform::bind_group_layout(&ctx.device);
+        let pipeline_layout = ctx
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Gizmo Pipeline Layout"),
+                bind_group_layouts: &[
+                    Some(&ctx.camera_bind_group_layout),
+                    Some(&model_bind_group_layout),
+                ],
+                immediate_size: 0,
+            });
+        let pipeline = create_overlay_render_pipeline(
+            &ctx.device,
+            "gizmo render pass",
+            &pipeline_layout,
+            ctx.color_format(),
+            ctx.device
+                .create_shader_module(include_wgsl!("../../../assets/gizmo.wgsl")),
+            Some(Texture::DEPTH_FORMAT),
+        );
+
+        let translate_mesh =
+            GizmoMesh::new(&ctx.device, "Translate Gizmo", translate_handle_geometry());
+        let rotate_mesh = GizmoMesh::new(&ctx.device, "Rotate Gizmo", rotate_handle_geometry());
+        let scale_mesh = GizmoMesh::new(&ctx.device, "Scale Gizmo", scale_handle_geometry());
+
+        let model_uniform = ModelMatrixUniform::new(Transform::default().get_matrix());
+        let model_uniform_buffer = UniformBuffer::new(
+            UniformBufferId::new("Gizmo Model Matrix Buffer".to_string()),
+            &ctx.device,
+            bytes_of(&model_uniform),
+            shared(Transform::default()),
+        );
+        let model_bind_group = ModelMatrixUniform::bind_group(
+            &ctx.device,
+            &model_uniform_buffer,
+            &model_bind_group_layout,
+        );
+
+        Self {
+            pipeline,
+            translate_mesh,
+            rotate_mesh,
+            scale_mesh,
+            model_uniform_buffer,
+            model_bind_group,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &self,
+        encoder: &mut CommandEncoder,
+        queue: &Queue,
+        camera_bind_group: &BindGroup,
+        mode: GizmoMode,
+        handle_transform: &Transform,
+        view: &TextureView,
+        depth_view: &TextureView,
+    ) {
+        let model_uniform = ModelMatrixUniform::new(handle_transform.get_matrix());
+        queue.write_buffer(&self.model_uniform_buffer, 0, bytes_of(&model_uniform));
+
+        let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Gizmo Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            multiview_mask: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.model_bind_group, &[]);
+        let mesh = match mode {
+            GizmoMode::Translate => &self.translate_mesh,
+            GizmoMode::Rotate => &self.rotate_mesh,
+            GizmoMode::Scale => &self.scale_mesh,
+        };
+        render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+    }
+}
+
+pub fn translate_handle_geometry() -> (Vec<Vertex>, Vec<u32>) {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let shaft_length = TransformGizmo::ARROW_LENGTH - HEAD_LENGTH;
+
+    for axis in GizmoAxis::ALL {
+        push_box(
+            &mut vertices,
+            &mut indices,
+            axis.orient(0.0, -SHAFT_RADIUS, -SHAFT_RADIUS),
+            axis.orient(shaft_length, SHAFT_RADIUS, SHAFT_RADIUS),
+            axis.color(),
+        );
+        push_box(
+            &mut vertices,
+            &mut indices,
+            axis.orient(shaft_length, -HEAD_RADIUS, -HEAD_RADIUS),
+            axis.orient(TransformGizmo::ARROW_LENGTH, HEAD_RADIUS, HEAD_RADIUS),
+            axis.color(),
+        );
+    }
+
+    (vertices, indices)
+}
+
+pub fn scale_handle_geometry() -> (Vec<Vertex>, Vec<u32>) {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for axis in GizmoAxis::ALL {
+        let cube = TransformGizmo::scale_handle_bounds(GizmoHandle::Axis(axis));
+        push_box(
+            &mut vertices,
+            &mut indices,
+            axis.orient(0.0, -SHAFT_RADIUS, -SHAFT_RADIUS),
+            axis.orient(
+                TransformGizmo::ARROW_LENGTH - 2.0 * TransformGizmo::SCALE_HANDLE_HALF_SIZE,
+                SHAFT_RADIUS,
+                SHAFT_RADIUS,
+            ),
+            axis.color(),
+        );
+        push_box(
+            &mut vertices,
+            &mut indices,
+            cube.min,
+            cube.max,
+            axis.color(),
+        );
+    }
+
+    let center = TransformGizmo::scale_handle_bounds(GizmoHandle::Center);
+    push_box(
+        &mut vertices,
+        &mut indices,
+        center.min,
+        center.max,
+        Vec4::new(0.9, 0.9, 0.9, 1.0),
+    );
+
+    (vertices, indices)
+}
+
+/// Square-section tube per axis, lying in the plane perpendicular to that axis.
+pub fn rotate_handle_geometry() -> (Vec<Vertex>, Vec<u32>) {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let inner = TransformGizmo::RING_RADIUS - RING_THICKNESS;
+    let outer = TransformGizmo::RING_RADIUS + RING_THICKNESS;
+
+    for axis in GizmoAxis::ALL {
+        let base = vertices.len() as u32;
+        for segment in 0..RING_SEGMENTS {
+            let angle = segment as f32 / RING_SEGMENTS as f32 * TAU;
+            let (sin, cos) = angle.sin_cos();
+            for (radius, along) in [
+                (inner, -RING_THICKNESS),
+                (outer, -RING_THICKNESS),
+                (outer, RING_THICKNESS),
+                (inner, RING_THICKNESS),
+            ] {
+                vertices.push(Vertex::new(
+                    axis.orient(along, radius * cos, radius * sin),
+                    Vec2::ZERO,
+                    Vec3::ZERO,
+                    axis.color(),
+                ));
+            }
+        }
+
+        for segment in 0..RING_SEGMENTS {
+            let current = base + (segment * 4) as u32;
+            let next = base + (((segment + 1) % RING_SEGMENTS) * 4) as u32;
+            for side in 0..4 {
+                let (a, b) = (current + side, current + (side + 1) % 4);
+                let (c, d) = (next + side, next + (side + 1) % 4);
+                indices.extend([a, c, d, a, d, b]);
+            }
+        }
+    }
+
+    (vertices, indices)
+}
+
+pub fn push_box(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    min: Vec3,
+    max: Vec3,
+    color: Vec4,
+) {
+    const BOX_INDICES: [u32; 36] = [
+        0, 1, 3, 0, 3, 2, // -z
+        4, 6, 7, 4, 7, 5, // +z
+        0, 4, 5, 0, 5, 1, // -y
+        2, 3, 7, 2, 7, 6, // +y
+        0, 2, 6, 0, 6, 4, // -x
+        1, 5, 7, 1, 7, 3, // +x
+    ];
+
+    let base = vertices.len() as u32;
+    let (min, max) = (min.min(max), min.max(max));
+    for corner in 0..8 {
+        let position = Vec3::new(
+            if corner & 1 == 0 { min.x } else { max.x },
+            if corner & 2 == 0 { min.y } else { max.y },
+            if corner & 4 == 0 { min.z } else { max.z },
+        );
+        vertices.push(Vertex::new(position, Vec2::ZERO, Vec3::ZERO, color));
+    }
+    indices.extend(BOX_INDICES.iter().map(|index| base + index));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_handle_geometry_stays_within_arrow_length() {
+        let (vertices, indices) = translate_handle_geometry();
+
+        assert_eq!(vertices.len(), 3 * 2 * 8);
+        assert_eq!(indices.len(), 3 * 2 * 36);
+        assert!(
+            indices
+                .iter()
+                .all(|index| (*index as usize) < vertices.len())
+        );
+        assert!(
+            vertices
+                .iter()
+                .all(|vertex| { vertex.position.max_element() <= TransformGizmo::ARROW_LENGTH })
+        );
+    }
+
+    #[test]
+    fn test_rotate_handle_geometry_stays_on_ring() {
+        let (vertices, indices) = rotate_handle_geometry();
+
+        assert_eq!(vertices.len(), 3 * RING_SEGMENTS * 4);
+        assert_eq!(indices.len(), 3 * RING_SEGMENTS * 4 * 6);
+        assert!(
+            indices
+                .iter()
+                .all(|index| (*index as usize) < vertices.len())
+        );
+        assert!(vertices.iter().all(|vertex| {
+            vertex.position.length()
+                <= TransformGizmo::RING_RADIUS + RING_THICKNESS * 2.0_f32.sqrt() + 1e-4
+        }));
+    }
+
+    #[test]
+    fn test_scale_handle_geometry_has_axis_cubes_and_center() {
+        let (vertices, indices) = scale_handle_geometry();
+
+        assert_eq!(vertices.len(), (3 * 2 + 1) * 8);
+        assert_eq!(indices.len(), (3 * 2 + 1) * 36);
+        assert!(
+            vertices
+                .iter()
+                .all(|vertex| vertex.position.max_element() <= TransformGizmo::ARROW_LENGTH)
+        );
+    }
+}
@@ -0,0 +1,949 @@
+use glam::{Quat, Vec2, Vec3, Vec4};
+use hyakou_core::{
+    Shared, SharedAccess,
+    components::camera::{camera::Camera, data_structures::CameraProjection},
+    geometry::{
+        aabb::Aabb,
+        ray::{Ray, world_to_screen},
+    },
+    types::{Size, transform::Transform},
+    util::float::wrap_angle,
+};
+use log::warn;
+use winit::dpi::PhysicalPosition;
+
+use crate::renderer::handlers::{asset_handler::AssetHandler, selection::SelectionManager};
+
+pub mod gizmo_renderer;
+
+const PLANE_EPSILON: f32 = 1e-6;
+
+/// Resolves selected ids to the transforms a gizmo drag edits.
+pub trait GizmoTargets {
+    fn target_transform(&self, id: &str) -> Option<Shared<Transform>>;
+}
+
+impl GizmoTargets for AssetHandler {
+    fn target_transform(&self, id: &str) -> Option<Shared<Transform>> {
+        self.get_all_visible_assets()
+            .find(|mesh| mesh.id.0 == id)
+            .map(|mesh| mesh.transform.clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GizmoAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl GizmoAxis {
+    pub const ALL: [GizmoAxis; 3] = [GizmoAxis::X, GizmoAxis::Y, GizmoAxis::Z];
+
+    pub fn direction(self) -> Vec3 {
+        match self {
+            GizmoAxis::X => Vec3::X,
+            GizmoAxis::Y => Vec3::Y,
+            GizmoAxis::Z => Vec3::Z,
+        }
+    }
+
+    pub fn color(self) -> Vec4 {
+        match self {
+            GizmoAxis::X => Vec4::new(0.9, 0.2, 0.2, 1.0),
+            GizmoAxis::Y => Vec4::new(0.2, 0.9, 0.2, 1.0),
+            GizmoAxis::Z => Vec4::new(0.2, 0.4, 0.9, 1.0),
+        }
+    }
+
+    /// Maps a point given as `(along, u, v)` so that `along` follows this axis.
+    pub fn orient(self, along: f32, u: f32, v: f32) -> Vec3 {
+        match self {
+            GizmoAxis::X => Vec3::new(along, u, v),
+            GizmoAxis::Y => Vec3::new(v, along, u),
+            GizmoAxis::Z => Vec3::new(u, v, along),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GizmoMode {
+    #[default]
+    Translate,
+    Rotate,
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GizmoHandle {
+    Axis(GizmoAxis),
+    /// The uniform scale cube at the gizmo origin.
+    Center,
+}
+
+impl GizmoHandle {
+    pub fn axis(self) -> Option<GizmoAxis> {
+        match self {
+            GizmoHandle::Axis(axis) => Some(axis),
+            GizmoHandle::Center => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DragStart {
+    AxisOffset(f32),
+    ScreenAngle(f32),
+    ScreenPoint { cursor: Vec2, screen_axis: Vec2 },
+}
+
+#[derive(Debug, Clone)]
+struct GizmoDrag {
+    /// Every selected object with its transform at drag start; the primary selection comes last.
+    targets: Vec<(String, Transform)>,
+    handle: GizmoHandle,
+    /// Start transform of the primary selection, which the handles are attached to.
+    start_transform: Transform,
+    start: DragStart,
+}
+
+#[derive(Debug, Default)]
+pub struct TransformGizmo {
+    mode: GizmoMode,
+    drag: Option<GizmoDrag>,
+}
+
+impl TransformGizmo {
+    pub const ARROW_LENGTH: f32 = 1.5;
+    pub const ARROW_PICK_RADIUS: f32 = 0.12;
+    pub const RING_RADIUS: f32 = 1.2;
+    pub const RING_PICK_TOLERANCE: f32 = 0.1;
+    pub const ROTATION_SNAP_DEGREES: f32 = 15.0;
+    pub const SCALE_HANDLE_HALF_SIZE: f32 = 0.1;
+    pub const CENTER_HANDLE_HALF_SIZE: f32 = 0.15;
+    /// Fraction of the viewport height covered by an arrow, independent of camera distance.
+    pub const SCREEN_HEIGHT_FRACTION: f32 = 0.15;
+    pub const MIN_SCALE: f32 = 1e-3;
+    const CENTER_DRAG_PIXELS: f32 = 100.0;
+
+    pub fn new() -> Self {
+        Self {
+            mode: GizmoMode::Translate,
+            drag: None,
+        }
+    }
+
+    pub fn mode(&self) -> GizmoMode {
+        self.mode
+    }
+
+    /// Switching modes mid-drag is ignored so the running drag keeps its meaning.
+    pub fn set_mode(&mut self, mode: GizmoMode) {
+        if !self.is_dragging() {
+            self.mode = mode;
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    pub fn active_handle(&self) -> Option<GizmoHandle> {
+        self.drag.as_ref().map(|drag| drag.handle)
+    }
+
+    pub fn active_axis(&self) -> Option<GizmoAxis> {
+        self.active_handle().and_then(GizmoHandle::axis)
+    }
+
+    /// World-space transform of the handle meshes, scaled so they keep a constant screen size.
+    pub fn handle_transform(camera: &Camera, origin: Vec3) -> Transform {
+        Transform::new(
+            origin,
+            Quat::IDENTITY,
+            Vec3::splat(handle_scale(camera, origin)),
+        )
+    }
+
+    pub fn arrow_bounds(axis: GizmoAxis) -> Aabb {
+        Aabb::new(
+            axis.orient(0.0, -Self::ARROW_PICK_RADIUS, -Self::ARROW_PICK_RADIUS),
+            axis.orient(
+                Self::ARROW_LENGTH,
+                Self::ARROW_PICK_RADIUS,
+                Self::ARROW_PICK_RADIUS,
+            ),
+        )
+    }
+
+    pub fn scale_handle_bounds(handle: GizmoHandle) -> Aabb {
+        match handle {
+            GizmoHandle::Axis(axis) => {
+                let half = Self::SCALE_HANDLE_HALF_SIZE;
+                Aabb::new(
+                    axis.orient(Self::ARROW_LENGTH - 2.0 * half, -half, -half),
+                    axis.orient(Self::ARROW_LENGTH, half, half),
+                )
+            }
+            GizmoHandle::Center => Aabb::new(
+                Vec3::splat(-Self::CENTER_HANDLE_HALF_SIZE),
+                Vec3::splat(Self::CENTER_HANDLE_HALF_SIZE),
+            ),
+        }
+    }
+
+    pub fn pick_axis(ray: &Ray, handle_transform: &Transform) -> Option<GizmoAxis> {
+        GizmoAxis::ALL
+            .into_iter()
+            .filter_map(|axis| {
+                ray.intersect_transformed_aabb(&Self::arrow_bounds(axis), handle_transform)
+                    .map(|distance| (axis, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| axis)
+    }
+
+    pub fn pick_ring(ray: &Ray, handle_transform: &Transform) -> Option<GizmoAxis> {
+        let origin = handle_transform.position;
+        let radius = Self::RING_RADIUS * handle_transform.scale.x;
+        let tolerance = Self::RING_PICK_TOLERANCE * handle_transform.scale.x;
+        GizmoAxis::ALL
+            .into_iter()
+            .filter_map(|axis| {
+                let t = ray.intersect_plane(origin, axis.direction())?;
+                let hit_radius = ray.at(t).distance(origin);
+                ((hit_radius - radius).abs() <= tolerance).then_some((axis, t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| axis)
+    }
+
+    pub fn pick_scale_handle(ray: &Ray, handle_transform: &Transform) -> Option<GizmoHandle> {
+        GizmoAxis::ALL
+            .into_iter()
+            .map(GizmoHandle::Axis)
+            .chain([GizmoHandle::Center])
+            .filter_map(|handle| {
+                ray.intersect_transformed_aabb(&Self::scale_handle_bounds(handle), handle_transform)
+                    .map(|distance| (handle, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(handle, _)| handle)
+    }
+
+    pub fn pick_handle(&self, ray: &Ray, handle_transform: &Transform) -> Option<GizmoHandle> {
+        match self.mode {
+            GizmoMode::Translate => Self::pick_axis(ray, handle_transform).map(GizmoHandle::Axis),
+            GizmoMode::Rotate => Self::pick_ring(ray, handle_transform).map(GizmoHandle::Axis),
+            GizmoMode::Scale => Self::pick_scale_handle(ray, handle_transform),
+        }
+    }
+
+    pub fn begin_drag(
+        &mut self,
+        camera: &Camera,
+        viewport: Size,
+        selection: &SelectionManager,
+        scene: &impl GizmoTargets,
+        position: PhysicalPosition<f64>,
+    ) -> bool {
+        let targets: Vec<(String, Transform)> = selection
+            .iter()
+            .filter_map(|id| {
+                scene
+                    .target_transform(id)
+                    .map(|transform| (id.to_string(), transform.read_shared(|t| *t)))
+            })
+            .collect();
+        let Some((_, start_transform)) = targets.last().cloned() else {
+            return false;
+        };
+        let Ok(ray) = camera.screen_to_ray(position, viewport) else {
+            return false;
+        };
+
+        let handle_transform = Self::handle_transform(camera, start_transform.position);
+        let Some(handle) = self.pick_handle(&ray, &handle_transform) else {
+            return false;
+        };
+        let Some(start) = Self::drag_start(
+            self.mode,
+            handle,
+            camera,
+            viewport,
+            &handle_transform,
+            &ray,
+            position,
+        ) else {
+            return false;
+        };
+
+        self.drag = Some(GizmoDrag {
+            targets,
+            handle,
+            start_transform,
+            start,
+        });
+        true
+    }
+
+    fn drag_start(
+        mode: GizmoMode,
+        handle: GizmoHandle,
+        camera: &Camera,
+        viewport: Size,
+        handle_transform: &Transform,
+        ray: &Ray,
+        position: PhysicalPosition<f64>,
+    ) -> Option<DragStart> {
+        let origin = handle_transform.position;
+        match (mode, handle) {
+            (GizmoMode::Translate, GizmoHandle::Axis(axis)) => {
+                axis_offset_from_ray(ray, origin, axis.direction(), view_direction(camera))
+                    .map(DragStart::AxisOffset)
+            }
+            (GizmoMode::Rotate, GizmoHandle::Axis(_)) => {
+                screen_angle_around(camera, viewport, origin, position).map(DragStart::ScreenAngle)
+            }
+            (GizmoMode::Scale, handle) => {
+                let center_axis = Vec2::new(Self::CENTER_DRAG_PIXELS, -Self::CENTER_DRAG_PIXELS);
+                let screen_axis = handle
+                    .axis()
+                    .and_then(|axis| {
+                        let tip = origin
+                            + axis.direction() * Self::ARROW_LENGTH * handle_transform.scale.x;
+                        let axis_on_screen = world_to_screen(camera, tip, viewport)?
+                            - world_to_screen(camera, origin, viewport)?;
+                        (axis_on_screen.length_squared() > 1.0).then_some(axis_on_screen)
+                    })
+                    .unwrap_or(center_axis);
+
+                Some(DragStart::ScreenPoint {
+                    cursor: Vec2::new(position.x as f32, position.y as f32),
+                    screen_axis,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Holding Shift snaps rotations to [`Self::ROTATION_SNAP_DEGREES`] and makes scaling uniform.
+    pub fn update_drag(
+        &mut self,
+        camera: &Camera,
+        viewport: Size,
+        scene: &impl GizmoTargets,
+        position: PhysicalPosition<f64>,
+        shift_held: bool,
+    ) {
+        let Some(drag) = self.drag.as_ref() else {
+            return;
+        };
+
+        let updated = match drag.start {
+            DragStart::AxisOffset(start_offset) => {
+                Self::translated(drag, start_offset, camera, viewport, position)
+            }
+            DragStart::ScreenAngle(start_angle) => {
+                Self::rotated(drag, start_angle, camera, viewport, position, shift_held)
+            }
+            DragStart::ScreenPoint {
+                cursor,
+                screen_axis,
+            } => Some(Self::scaled(
+                drag,
+                cursor,
+                screen_axis,
+                position,
+                shift_held,
+            )),
+        };
+        let Some(updated) = updated else {
+            return;
+        };
+
+        for (target_id, target_start) in &drag.targets {
+            let target_updated =
+                apply_transform_delta(&drag.start_transform, &updated, target_start);
+            Self::write_target_transform(scene, target_id, |transform| {
+                *transform = target_updated;
+            });
+        }
+    }
+
+    fn translated(
+        drag: &GizmoDrag,
+        start_offset: f32,
+        camera: &Camera,
+        viewport: Size,
+        position: PhysicalPosition<f64>,
+    ) -> Option<Transform> {
+        let axis = drag.handle.axis()?.direction();
+        let ray = camera.screen_to_ray(position, viewport).ok()?;
+        let offset = axis_offset_from_ray(
+            &ray,
+            drag.start_transform.position,
+            axis,
+            view_direction(camera),
+        )?;
+
+        let mut transform = drag.start_transform;
+        transform.translate(axis * (offset - start_offset));
+        Some(transform)
+    }
+
+    fn rotated(
+        drag: &GizmoDrag,
+        start_angle: f32,
+        camera: &Camera,
+        viewport: Size,
+        position: PhysicalPosition<f64>,
+        snap: bool,
+    ) -> Option<Transform> {
+        let axis = drag.handle.axis()?.direction();
+        let angle = screen_angle_around(camera, viewport, drag.start_transform.position, position)?;
+        let mut delta = wrap_angle(angle - start_angle);
+        // Counter-clockwise on screen is a positive rotation only when the axis faces the viewer.
+        if axis.dot(view_direction(camera)) > 0.0 {
+            delta = -delta;
+        }
+        if snap {
+            delta = snap_angle(delta, Self::ROTATION_SNAP_DEGREES.to_radians());
+        }
+
+        let local_axis = drag.start_transform.rotation.inverse() * axis;
+        let mut transform = drag.start_transform;
+        transform.rotate(Quat::from_axis_angle(local_axis.normalize(), delta));
+        Some(transform)
+    }
+
+    fn scaled(
+        drag: &GizmoDrag,
+        start_cursor: Vec2,
+        screen_axis: Vec2,
+        position: PhysicalPosition<f64>,
+        uniform: bool,
+    ) -> Transform {
+        let cursor = Vec2::new(position.x as f32, position.y as f32);
+        let factor = scale_factor_from_drag(start_cursor, cursor, screen_axis);
+        let axis = if uniform { None } else { drag.handle.axis() };
+
+        let mut transform = drag.start_transform;
+        transform.scale = apply_scale_factor(drag.start_transform.scale, factor, axis);
+        transform
+    }
+
+    pub fn end_drag(&mut self) {
+        self.drag = None;
+    }
+
+    pub fn cancel_drag(&mut self, scene: &impl GizmoTargets) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+
+        for (target_id, target_start) in &drag.targets {
+            Self::write_target_transform(scene, target_id, |transform| {
+                *transform = *target_start;
+            });
+        }
+    }
+
+    fn write_target_transform(
+        scene: &impl GizmoTargets,
+        target_id: &str,
+        update: impl FnOnce(&mut Transform),
+    ) {
+        let Some(transform) = scene.target_transform(target_id) else {
+            warn!("Gizmo target `{target_id}` is no longer loaded");
+            return;
+        };
+
+        if let Err(lock_error) = transform.try_write_shared(update) {
+            warn!("Failed to update gizmo target `{target_id}`: {lock_error:?}");
+        }
+    }
+}
+
+/// Height of the visible world slice through `point`, in world units.
+pub fn visible_height_at(camera: &Camera, point: Vec3) -> f32 {
+    match camera.projection {
+        CameraProjection::Perspective => {
+            let depth = (point - camera.eye)
+                .dot(view_direction(camera))
+                .max(camera.znear);
+            2.0 * depth * (camera.fovy * 0.5).tan()
+        }
+        CameraProjection::Orthographic { height } => height,
+    }
+}
+
+/// Uniform scale that keeps the gizmo at [`TransformGizmo::SCREEN_HEIGHT_FRACTION`] of the screen.
+pub fn handle_scale(camera: &Camera, origin: Vec3) -> f32 {
+    let scale = visible_height_at(camera, origin) * TransformGizmo::SCREEN_HEIGHT_FRACTION
+        / TransformGizmo::ARROW_LENGTH;
+    if scale.is_finite() {
+        scale.max(TransformGizmo::MIN_SCALE)
+    } else {
+        1.0
+    }
+}
+
+/// Multiplier for a scale drag: moving the cursor by `screen_axis` doubles the scale.
+pub fn scale_factor_from_drag(start: Vec2, current: Vec2, screen_axis: Vec2) -> f32 {
+    let length_squared = screen_axis.length_squared();
+    if length_squared <= PLANE_EPSILON {
+        return 1.0;
+    }
+    1.0 + (current - start).dot(screen_axis) / length_squared
+}
+
+/// Applies `factor` to one component of `scale`, or to all of them when `axis` is `None`, without
+/// letting any component collapse to zero or flip sign.
+pub fn apply_scale_factor(scale: Vec3, factor: f32, axis: Option<GizmoAxis>) -> Vec3 {
+    let multiplier = match axis {
+        Some(axis) => Vec3::ONE + axis.direction() * (factor - 1.0),
+        None => Vec3::splat(factor),
+    };
+    (scale * multiplier).max(Vec3::splat(TransformGizmo::MIN_SCALE))
+}
+
+/// Applies the change from `primary_start` to `primary_updated` to another selected object: the
+/// same world translation, the same world rotation about its own origin, and the same scale ratio.
+pub fn apply_transform_delta(
+    primary_start: &Transform,
+    primary_updated: &Transform,
+    target_start: &Transform,
+) -> Transform {
+    let translation = primary_updated.position - primary_start.position;
+    let rotation = primary_updated.rotation * primary_start.rotation.inverse();
+    let scale_ratio = Vec3::select(
+        primary_start
+            .scale
+            .abs()
+            .cmpge(Vec3::splat(TransformGizmo::MIN_SCALE)),
+        primary_updated.scale / primary_start.scale,
+        Vec3::ONE,
+    );
+
+    let mut transform = *target_start;
+    transform.translate(translation);
+    transform.rotation = (rotation * target_start.rotation).normalize();
+    transform.scale =
+        (target_start.scale * scale_ratio).max(Vec3::splat(TransformGizmo::MIN_SCALE));
+    transform
+}
+
+/// Counter-clockwise angle of `cursor` around `center` with screen y pointing up.
+pub fn screen_angle(center: Vec2, cursor: Vec2) -> Option<f32> {
+    let offset = cursor - center;
+    (offset.length_squared() > PLANE_EPSILON).then(|| (center.y - cursor.y).atan2(offset.x))
+}
+
+pub fn screen_angle_around(
+    camera: &Camera,
+    viewport: Size,
+    origin: Vec3,
+    cursor: PhysicalPosition<f64>,
+) -> Option<f32> {
+    let center = world_to_screen(camera, origin, viewport)?;
+    screen_angle(center, Vec2::new(cursor.x as f32, cursor.y as f32))
+}
+
+pub fn snap_angle(angle: f32, increment: f32) -> f32 {
+    if increment <= 0.0 {
+        return angle;
+    }
+    (angle / increment).round() * increment
+}
+
+pub fn view_direction(camera: &Camera) -> Vec3 {
+    (camera.target - camera.eye).normalize_or_zero()
+}
+
+/// Normal of the plane that contains `axis` and faces the camera as much as possible.
+pub fn axis_drag_plane_normal(axis: Vec3, view_direction: Vec3) -> Option<Vec3> {
+    let normal = axis.cross(view_direction.cross(axis));
+    (normal.length_squared() > PLANE_EPSILON).then(|| normal.normalize())
+}
+
+/// Intersects `ray` with the axis drag plane through `origin` and returns the signed distance of
+/// the hit along `axis`.
+pub fn axis_offset_from_ray(
+    ray: &Ray,
+    origin: Vec3,
+    axis: Vec3,
+    view_direction: Vec3,
+) -> Option<f32> {
+    let normal = axis_drag_plane_normal(axis, view_direction)?;
+    let t = ray.intersect_plane(origin, normal)?;
+    Some((ray.at(t) - origin).dot(axis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyakou_core::types::camera::{Pitch, Yaw};
+    use std::f32::consts::PI;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn unit_handle(origin: Vec3) -> Transform {
+        Transform::new(origin, Quat::IDENTITY, Vec3::ONE)
+    }
+
+    fn camera_at(eye: Vec3) -> Camera {
+        Camera::new(
+            eye,
+            Vec3::ZERO,
+            Vec3::Y,
+            16.0 / 9.0,
+            45.0_f32.to_radians(),
+            0.1,
+            100.0,
+            Yaw::new(0.0),
+            Pitch::new(0.0),
+            20.0,
+            1.0,
+            0.5,
+        )
+    }
+
+    fn assert_offset(actual: Option<f32>, expected: f32, message: &str) {
+        let actual = actual.unwrap_or_else(|| panic!("{message}: expected an offset"));
+        assert!(
+            (actual - expected).abs() < EPSILON,
+            "{message}: expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_axis_offset_follows_x_axis() {
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let ray = Ray::new(eye, Vec3::new(2.0, 0.5, 0.0) - eye);
+
+        assert_offset(
+            axis_offset_from_ray(&ray, Vec3::ZERO, GizmoAxis::X.direction(), Vec3::NEG_Z),
+            2.0,
+            "x axis",
+        );
+    }
+
+    #[test]
+    fn test_axis_offset_follows_y_axis() {
+        let eye = Vec3::new(0.0, 0.0, 10.0);
+        let ray = Ray::new(eye, Vec3::new(0.5, -3.0, 0.0) - eye);
+
+        assert_offset(
+            axis_offset_from_ray(&ray, Vec3::ZERO, GizmoAxis::Y.direction(), Vec3::NEG_Z),
+            -3.0,
+            "y axis",
+        );
+    }
+
+    #[test]
+    fn test_axis_offset_follows_z_axis_from_side_view() {
+        let eye = Vec3::new(10.0, 0.0, 0.0);
+        let ray = Ray::new(eye, Vec3::new(0.0, 0.0, 4.0) - eye);
+
+        assert_offset(
+            axis_offset_from_ray(&ray, Vec3::ZERO, GizmoAxis::Z.direction(), Vec3::NEG_X),
+            4.0,
+            "z axis",
+        );
+    }
+
+    #[test]
+    fn test_axis_offset_is_relative_to_origin() {
+        let origin = Vec3::new(5.0, 1.0, 0.0);
+        let eye = Vec3::new(5.0, 1.0, 10.0);
+        let ray = Ray::new(eye, Vec3::new(6.5, 1.0, 0.0) - eye);
+
+        assert_offset(
+            axis_offset_from_ray(&ray, origin, GizmoAxis::X.direction(), Vec3::NEG_Z),
+            1.5,
+            "translated origin",
+        );
+    }
+
+    #[test]
+    fn test_axis_drag_plane_rejects_axis_parallel_to_view() {
+        assert!(axis_drag_plane_normal(Vec3::Z, Vec3::NEG_Z).is_none());
+    }
+
+    #[test]
+    fn test_pick_axis_selects_hit_arrow() {
+        let ray = Ray::new(Vec3::new(1.0, 0.0, 10.0), Vec3::NEG_Z);
+
+        assert_eq!(
+            TransformGizmo::pick_axis(&ray, &unit_handle(Vec3::ZERO)),
+            Some(GizmoAxis::X)
+        );
+        assert!(
+            TransformGizmo::pick_axis(
+                &Ray::new(Vec3::new(1.0, 1.0, 10.0), Vec3::NEG_Z),
+                &unit_handle(Vec3::ZERO)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_pick_ring_selects_ring_under_ray() {
+        let ray = Ray::new(
+            Vec3::new(TransformGizmo::RING_RADIUS, 0.0, 10.0),
+            Vec3::NEG_Z,
+        );
+
+        assert_eq!(
+            TransformGizmo::pick_ring(&ray, &unit_handle(Vec3::ZERO)),
+            Some(GizmoAxis::Z)
+        );
+        assert!(
+            TransformGizmo::pick_ring(
+                &Ray::new(Vec3::new(0.2, 0.3, 10.0), Vec3::NEG_Z),
+                &unit_handle(Vec3::ZERO)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_screen_angle_is_counter_clockwise_with_y_up() {
+        let center = Vec2::new(100.0, 100.0);
+        let cases = [
+            ("right", Vec2::new(150.0, 100.0), 0.0),
+            ("up", Vec2::new(100.0, 50.0), PI / 2.0),
+            ("left", Vec2::new(50.0, 100.0), PI),
+            ("down", Vec2::new(100.0, 150.0), -PI / 2.0),
+        ];
+
+        for (name, cursor, expected) in cases {
+            assert_offset(screen_angle(center, cursor), expected, name);
+        }
+    }
+
+    #[test]
+    fn test_screen_angle_rejects_cursor_on_center() {
+        assert!(screen_angle(Vec2::ONE, Vec2::ONE).is_none());
+    }
+
+    #[test]
+    fn test_wrap_angle_keeps_shortest_delta() {
+        assert!((wrap_angle(1.5 * PI) + 0.5 * PI).abs() < EPSILON);
+        assert!((wrap_angle(-1.5 * PI) - 0.5 * PI).abs() < EPSILON);
+        assert!((wrap_angle(0.25) - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_snap_angle_rounds_to_nearest_increment() {
+        let increment = 15.0_f32.to_radians();
+        let cases = [
+            (7.0, 0.0),
+            (8.0, 15.0),
+            (22.0, 15.0),
+            (23.0, 30.0),
+            (-8.0, -15.0),
+        ];
+
+        for (degrees, expected) in cases {
+            let snapped = snap_angle(f32::to_radians(degrees), increment).to_degrees();
+            assert!(
+                (snapped - expected).abs() < 1e-3,
+                "{degrees} degrees: expected {expected}, got {snapped}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_mode_is_ignored_while_dragging() {
+        let mut gizmo = TransformGizmo::new();
+        gizmo.set_mode(GizmoMode::Rotate);
+        assert_eq!(gizmo.mode(), GizmoMode::Rotate);
+
+        gizmo.drag = Some(GizmoDrag {
+            targets: vec![("Cube_0".to_string(), Transform::default())],
+            handle: GizmoHandle::Axis(GizmoAxis::Y),
+            start_transform: Transform::default(),
+            start: DragStart::ScreenAngle(0.0),
+        });
+        gizmo.set_mode(GizmoMode::Translate);
+
+        assert_eq!(gizmo.mode(), GizmoMode::Rotate);
+    }
+
+    #[test]
+    fn test_pick_scale_handle_prefers_axis_cubes_and_center() {
+        let handle = unit_handle(Vec3::ZERO);
+        let tip = TransformGizmo::ARROW_LENGTH - TransformGizmo::SCALE_HANDLE_HALF_SIZE;
+
+        assert_eq!(
+            TransformGizmo::pick_scale_handle(
+                &Ray::new(Vec3::new(tip, 0.0, 10.0), Vec3::NEG_Z),
+                &handle
+            ),
+            Some(GizmoHandle::Axis(GizmoAxis::X))
+        );
+        // From behind, so the Z cube isn't in front of the center handle.
+        assert_eq!(
+            TransformGizmo::pick_scale_handle(
+                &Ray::new(Vec3::new(0.05, 0.05, -10.0), Vec3::Z),
+                &handle
+            ),
+            Some(GizmoHandle::Center)
+        );
+        assert!(
+            TransformGizmo::pick_scale_handle(
+                &Ray::new(Vec3::new(0.7, 0.0, 10.0), Vec3::NEG_Z),
+                &handle
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_scale_factor_follows_drag_projected_on_screen_axis() {
+        let start = Vec2::new(100.0, 100.0);
+        let screen_axis = Vec2::new(50.0, 0.0);
+
+        let cases = [
+            ("along axis", Vec2::new(150.0, 100.0), 2.0),
+            ("half way back", Vec2::new(75.0, 100.0), 0.5),
+            ("perpendicular", Vec2::new(100.0, 40.0), 1.0),
+        ];
+        for (name, current, expected) in cases {
+            assert_offset(
+                Some(scale_factor_from_drag(start, current, screen_axis)),
+                expected,
+                name,
+            );
+        }
+        assert_eq!(scale_factor_from_drag(start, start, Vec2::ZERO), 1.0);
+    }
+
+    #[test]
+    fn test_apply_scale_factor_is_per_axis_or_uniform() {
+        let scale = Vec3::new(1.0, 2.0, 3.0);
+
+        assert_eq!(
+            apply_scale_factor(scale, 2.0, Some(GizmoAxis::Y)),
+            Vec3::new(1.0, 4.0, 3.0)
+        );
+        assert_eq!(
+            apply_scale_factor(scale, 2.0, None),
+            Vec3::new(2.0, 4.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn test_apply_scale_factor_clamps_to_min_scale() {
+        let min = Vec3::splat(TransformGizmo::MIN_SCALE);
+
+        assert_eq!(apply_scale_factor(Vec3::ONE, 0.0, None), min);
+        assert_eq!(
+            apply_scale_factor(Vec3::ONE, -3.0, Some(GizmoAxis::X)),
+            Vec3::new(TransformGizmo::MIN_SCALE, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_handle_scale_grows_linearly_with_distance() {
+        let near = handle_scale(&camera_at(Vec3::new(0.0, 0.0, 5.0)), Vec3::ZERO);
+        let far = handle_scale(&camera_at(Vec3::new(0.0, 0.0, 20.0)), Vec3::ZERO);
+
+        assert!((far / near - 4.0).abs() < EPSILON, "near {near}, far {far}");
+    }
+
+    #[test]
+    fn test_handle_scale_keeps_constant_screen_length() {
+        let viewport = Size {
+            width: 1600,
+            height: 900,
+        };
+        let screen_length = |eye: Vec3| {
+            let camera = camera_at(eye);
+            let scale = handle_scale(&camera, Vec3::ZERO);
+            let tip = Vec3::X * TransformGizmo::ARROW_LENGTH * scale;
+            let origin = world_to_screen(&camera, Vec3::ZERO, viewport).unwrap();
+            world_to_screen(&camera, tip, viewport)
+                .unwrap()
+                .distance(origin)
+        };
+
+        let near = screen_length(Vec3::new(0.0, 0.0, 4.0));
+        let far = screen_length(Vec3::new(0.0, 0.0, 40.0));
+        let expected = viewport.height as f32 * TransformGizmo::SCREEN_HEIGHT_FRACTION;
+
+        assert!(
+            (near - expected).abs() < 0.5,
+            "near {near}, expected {expected}"
+        );
+        assert!(
+            (far - expected).abs() < 0.5,
+            "far {far}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn test_handle_scale_uses_orthographic_height() {
+        let mut camera = camera_at(Vec3::new(0.0, 0.0, 10.0));
+        camera.projection = CameraProjection::Orthographic { height: 6.0 };
+
+        let near = handle_scale(&camera, Vec3::ZERO);
+        let far = handle_scale(&camera, Vec3::new(0.0, 0.0, -50.0));
+
+        assert_offset(
+            Some(near),
+            6.0 * TransformGizmo::SCREEN_HEIGHT_FRACTION / TransformGizmo::ARROW_LENGTH,
+            "orthographic",
+        );
+        assert_offset(Some(far), near, "orthographic depth");
+    }
+
+    #[test]
+    fn test_bulk_translation_applies_identical_delta() {
+        let primary_start = Transform::new(Vec3::new(1.0, 0.0, 0.0), Quat::IDENTITY, Vec3::ONE);
+        let mut primary_updated = primary_start;
+        primary_updated.translate(Vec3::new(0.0, 2.0, -1.0));
+        let secondary_start = Transform::new(
+            Vec3::new(-4.0, 1.0, 3.0),
+            Quat::from_rotation_y(0.5),
+            Vec3::splat(2.0),
+        );
+
+        let secondary_updated =
+            apply_transform_delta(&primary_start, &primary_updated, &secondary_start);
+
+        assert!(
+            (secondary_updated.position - secondary_start.position)
+                .abs_diff_eq(Vec3::new(0.0, 2.0, -1.0), EPSILON)
+        );
+        assert!(
+            secondary_updated
+                .rotation
+                .abs_diff_eq(secondary_start.rotation, EPSILON)
+        );
+        assert_eq!(secondary_updated.scale, secondary_start.scale);
+    }
+
+    #[test]
+    fn test_bulk_rotation_and_scale_keep_each_origin() {
+        let primary_start = Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE);
+        let mut primary_updated = primary_start;
+        primary_updated.rotate(Quat::from_rotation_z(PI / 2.0));
+        primary_updated.scale = Vec3::new(2.0, 1.0, 1.0);
+        let secondary_start =
+            Transform::new(Vec3::new(5.0, 0.0, 0.0), Quat::IDENTITY, Vec3::splat(0.5));
+
+        let secondary_updated =
+            apply_transform_delta(&primary_start, &primary_updated, &secondary_start);
+
+        assert_eq!(secondary_updated.position, secondary_start.position);
+        assert!(
+            (secondary_updated.rotation * Vec3::X).abs_diff_eq(Vec3::Y, EPSILON),
+            "rotation should follow the primary"
+        );
+        assert_eq!(secondary_updated.scale, Vec3::new(1.0, 0.5, 0.5));
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+
+use wgpu::{AdapterInfo, DownlevelFlags, Features, FeaturesWGPU, FeaturesWebGPU, Limits};
+
+/// Optional GPU functionality that features check before relying on it, instead of testing raw
+/// feature bits at each call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Wireframe rendering through `PolygonMode::Line`.
+    PolygonModeLine,
+    TimestampQueries,
+    /// Immediate data, the successor of push constants.
+    PushConstants,
+    /// 16x anisotropic texture filtering.
+    Anisotropy16,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 4] = [
+        Capability::PolygonModeLine,
+        Capability::TimestampQueries,
+        Capability::PushConstants,
+        Capability::Anisotropy16,
+    ];
+
+    pub fn is_supported(self, features: Features, downlevel_flags: DownlevelFlags) -> bool {
+        match self {
+            Capability::PolygonModeLine => features
+                .features_wgpu
+                .contains(FeaturesWGPU::POLYGON_MODE_LINE),
+            Capability::TimestampQueries => features
+                .features_webgpu
+                .contains(FeaturesWebGPU::TIMESTAMP_QUERY),
+            Capability::PushConstants => features
+                .features_webgpu
+                .contains(FeaturesWebGPU::IMMEDIATES),
+            Capability::Anisotropy16 => {
+                downlevel_flags.contains(DownlevelFlags::ANISOTROPIC_FILTERING)
+            }
+        }
+    }
+}
+
+/// What the adapter is and what the device was actually granted. Logged at startup so bug
+/// reports say which GPU, driver and backend were in use.
+#[derive(Debug, Clone)]
+pub struct GpuInfo {
+    pub adapter: AdapterInfo,
+    pub features: Features,
+    pub limits: Limits,
+    pub downlevel_flags: DownlevelFlags,
+}
+
+impl GpuInfo {
+    pub fn supports(&self, capability: Capability) -> bool {
+        capability.is_supported(self.features, self.downlevel_flags)
+    }
+}
+
+impl fmt::Display for GpuInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({:?}, {}), driver {} {}, max texture {}",
+            self.adapter.name,
+            self.adapter.device_type,
+            self.adapter.backend.to_str(),
+            self.adapter.driver,
+            self.adapter.driver_info,
+            self.limits.max_texture_dimension_2d
+        )?;
+        let supported: Vec<String> = Capability::ALL
+            .into_iter()
+            .filter(|capability| self.supports(*capability))
+            .map(|capability| format!("{capability:?}"))
+            .collect();
+        if !supported.is_empty() {
+            write!(f, ", supports {}", supported.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Optional features that are requested whenever the adapter offers them, so the matching
+/// [`Capability`] can be used without recreating the device.
+pub(crate) fn optional_features(adapter_features: Features) -> Features {
+    let mut features = Features::default();
+    if adapter_features
+        .features_wgpu
+        .contains(FeaturesWGPU::POLYGON_MODE_LINE)
+    {
+        features.features_wgpu |= FeaturesWGPU::POLYGON_MODE_LINE;
+    }
+    if adapter_features
+        .features_webgpu
+        .contains(FeaturesWebGPU::TIMESTAMP_QUERY)
+    {
+        features.features_webgpu |= FeaturesWebGPU::TIMESTAMP_QUERY;
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(wgpu: FeaturesWGPU, webgpu: FeaturesWebGPU) -> Features {
+        Features {
+            features_wgpu: wgpu,
+            features_webgpu: webgpu,
+        }
+    }
+
+    #[test]
+    fn test_capabilities_map_to_their_feature_bits() {
+        let none = Features::default();
+        let line = features(FeaturesWGPU::POLYGON_MODE_LINE, FeaturesWebGPU::empty());
+        let timestamps = features(FeaturesWGPU::empty(), FeaturesWebGPU::TIMESTAMP_QUERY);
+        let immediates = features(FeaturesWGPU::empty(), FeaturesWebGPU::IMMEDIATES);
+        let no_flags = DownlevelFlags::empty();
+
+        for capability in Capability::ALL {
+            assert!(!capability.is_supported(none, no_flags), "{capability:?}");
+        }
+        assert!(Capability::PolygonModeLine.is_supported(line, no_flags));
+        assert!(!Capability::TimestampQueries.is_supported(line, no_flags));
+        assert!(Capability::TimestampQueries.is_supported(timestamps, no_flags));
+        assert!(!Capability::PushConstants.is_supported(timestamps, no_flags));
+        assert!(Capability::PushConstants.is_supported(immediates, no_flags));
+        assert!(Capability::Anisotropy16.is_supported(none, DownlevelFlags::ANISOTROPIC_FILTERING));
+    }
+
+    #[test]
+    fn test_optional_features_only_request_what_the_adapter_offers() {
+        let offered = features(
+            FeaturesWGPU::POLYGON_MODE_LINE,
+            FeaturesWebGPU::TIMESTAMP_QUERY | FeaturesWebGPU::IMMEDIATES,
+        );
+
+        let requested = optional_features(offered);
+
+        assert!(Capability::PolygonModeLine.is_supported(requested, DownlevelFlags::empty()));
+        assert!(Capability::TimestampQueries.is_supported(requested, DownlevelFlags::empty()));
+        assert!(!Capability::PushConstants.is_supported(requested, DownlevelFlags::empty()));
+        assert_eq!(optional_features(Features::default()), Features::default());
+    }
+}
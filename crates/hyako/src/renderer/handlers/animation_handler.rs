@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use hyakou_core::{
+    animations::Animator,
+    types::{DeltaTime64, handle::Handle},
+};
+use log::error;
+
+use crate::gpu::render_mesh::RenderMesh;
+
+/// Owns the scene's animators, keyed by the handle of the mesh they move. Steps taken while paused are dropped rather than saved up, so the
+/// first step after resuming advances by its own delta instead of the whole pause.
+#[derive(Default)]
+pub struct AnimationHandler {
+    animators: HashMap<Handle<RenderMesh>, Animator>,
+    paused: bool,
+}
+
+impl AnimationHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, mesh: Handle<RenderMesh>, animator: Animator) {
+        self.animators.insert(mesh, animator);
+    }
+
+    pub fn remove(&mut self, mesh: Handle<RenderMesh>) -> Option<Animator> {
+        self.animators.remove(&mesh)
+    }
+
+    pub fn get(&self, mesh: Handle<RenderMesh>) -> Option<&Animator> {
+        self.animators.get(&mesh)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<RenderMesh>, &Animator)> {
+        self.animators
+            .iter()
+            .map(|(&mesh, animator)| (mesh, animator))
+    }
+
+    pub fn clear(&mut self) {
+        self.animators.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.animators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.animators.is_empty()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn toggle_pause(&mut self) -> bool {
+        self.paused = !self.paused;
+        self.paused
+    }
+
+    /// Advances every animator by `delta_time`. Returns false without touching them while paused.
+    pub fn play_all(&mut self, delta_time: DeltaTime64) -> bool {
+        if self.paused {
+            return false;
+        }
+
+        self.animators.values_mut().for_each(|animator| {
+            if let Err(animator_error) = animator.play(delta_time) {
+                error!("{:?}", animator_error)
+            }
+        });
+        true
+    }
+
+    /// Restarts every animator and advances it straight to `time`, e.g. to render a fixed moment
+    /// of the scene. Unlike [`Self::play_all`] this also works while paused.
+    pub fn seek_all(&mut self, time: DeltaTime64) {
+        self.animators.values_mut().for_each(|animator| {
+            animator.reset();
+            if let Err(animator_error) = animator.play(time) {
+                error!("{:?}", animator_error)
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::{
+        animations::{Animation, NEUTRAL_SPEED},
+        types::{DeltaTime, handle::HandleAllocator, ids::MeshId, transform::Transform},
+    };
+
+    use super::*;
+
+    const FRAME: DeltaTime64 = 1.0 / 60.0;
+    const EPSILON: f64 = 1e-9;
+
+    struct StillAnimation {
+        id: MeshId,
+    }
+
+    impl Animation for StillAnimation {
+        fn get_id(&self) -> &MeshId {
+            &self.id
+        }
+
+        fn animate(&mut self, _t: Option<&Transform>, _delta: DeltaTime) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn handler_with_animator(id: &str) -> (AnimationHandler, Handle<RenderMesh>) {
+        let mesh = HandleAllocator::new().allocate();
+        let mut handler = AnimationHandler::new();
+        handler.insert(
+            mesh,
+            Animator::new(
+                NEUTRAL_SPEED,
+                Box::new(StillAnimation {
+                    id: MeshId(id.to_string()),
+                }),
+            )
+            .unwrap(),
+        );
+        (handler, mesh)
+    }
+
+    fn elapsed(handler: &AnimationHandler, mesh: Handle<RenderMesh>) -> f64 {
+        handler.get(mesh).unwrap().get_elapsed_time()
+    }
+
+    #[test]
+    fn test_paused_steps_do_not_advance_animators() {
+        let (mut handler, id) = handler_with_animator("cube");
+
+        assert!(handler.toggle_pause());
+        for _ in 0..120 {
+            assert!(!handler.play_all(FRAME));
+        }
+
+        assert_eq!(elapsed(&handler, id), 0.0);
+    }
+
+    #[test]
+    fn test_resume_frame_advances_by_a_single_step() {
+        let (mut handler, id) = handler_with_animator("cube");
+        handler.play_all(FRAME);
+        handler.set_paused(true);
+        for _ in 0..300 {
+            handler.play_all(FRAME);
+        }
+
+        handler.set_paused(false);
+        assert!(handler.play_all(FRAME));
+
+        assert!((elapsed(&handler, id) - 2.0 * FRAME).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_seek_lands_on_the_same_time_however_far_animators_ran() {
+        let (mut handler, id) = handler_with_animator("cube");
+        for _ in 0..90 {
+            handler.play_all(FRAME);
+        }
+        handler.set_paused(true);
+
+        handler.seek_all(0.5);
+
+        assert!((elapsed(&handler, id) - 0.5).abs() < EPSILON);
+    }
+}
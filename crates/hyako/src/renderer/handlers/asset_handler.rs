@@ -1,24 +1,49 @@
 use std::{
-    collections::{HashMap, HashSet, hash_set::Iter},
-    path::Path,
-    rc::Rc,
-    sync::Arc,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        mpsc::{Receiver, TryRecvError, channel},
+    },
+    time::SystemTime,
 };
 
-use anyhow::{Result, anyhow};
-use glam::Vec4;
+use anyhow::anyhow;
+use glam::Vec3;
+use log::warn;
+use tracing::instrument;
 use wgpu::{BindGroupLayout, Device, Queue};
 
-use crate::gpu::{
-    glTF::{GLTFLoader, ImportedAlphaMode, ImportedMaterial, ImportedScene},
-    material::{GpuMaterial, default_sampler_descriptor, sampler_descriptor_from_imported_sampler},
-    render_mesh::RenderMesh,
-    texture::Texture,
+use crate::{
+    gpu::{
+        cpu_geometry::CpuGeometryRetention,
+        glTF::{
+            GLTFLoader, ImportedMaterial, ImportedScene, ImportedTextureRef, NoRenderableMeshes,
+        },
+        load_progress::LoadStage,
+        material::{
+            GpuMaterial, Material, default_sampler_descriptor,
+            sampler_descriptor_from_imported_sampler,
+        },
+        obj::ObjLoader,
+        render_mesh::{GeometryUpdate, MeshUsage, RenderMesh},
+        texture::{FallbackTextures, Texture, color_format},
+    },
+    renderer::error::{ImportError, RendererError},
 };
 
 use hyakou_core::{
-    components::{LightType, mesh_node::MeshNode},
-    types::{ModelMatrixBindingMode, ids::MeshId},
+    SharedAccess,
+    animations::{Animator, NEUTRAL_SPEED, keyframes::KeyframeAnimation},
+    components::LightType,
+    geometry::{aabb::Aabb, mesh::Mesh, vertices::Vertex},
+    shared,
+    types::{
+        ModelMatrixBindingMode,
+        handle::{Handle, SlotMap},
+        ids::MeshId,
+        transform::Transform,
+    },
 };
 
 #[derive(Debug)]
@@ -28,9 +53,134 @@ pub struct AssetHandler {
     model_binding_mode: ModelMatrixBindingMode,
     model_bind_group_layout: Option<BindGroupLayout>,
     material_bind_group_layout: BindGroupLayout,
+    fallback_textures: Arc<FallbackTextures>,
     gltf_loader: GLTFLoader,
-    memory_loaded_assets: HashMap<String, Rc<RenderMesh>>,
-    visible_assets: HashSet<String>,
+    memory_loaded_assets: SlotMap<RenderMesh, Arc<RenderMesh>>,
+    /// Name lookup for the string-keyed API; per-frame paths go through handles.
+    handles_by_id: HashMap<String, Handle<RenderMesh>>,
+    visible_assets: HashSet<Handle<RenderMesh>>,
+    non_deletable_assets: HashSet<String>,
+    sources: HashMap<String, AssetSource>,
+    duplicate_sources: HashMap<String, String>,
+    geometry_retention: CpuGeometryRetention,
+    pending_loads: Vec<PendingLoad>,
+    finished_loads: HashMap<LoadTicket, Result<String, RendererError>>,
+    next_load_ticket: u64,
+    imported_animators: Vec<(Handle<RenderMesh>, Animator)>,
+}
+
+/// Identifies a load started with [`AssetHandler::add_from_path_async`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoadTicket(u64);
+
+/// Where a load started with [`AssetHandler::add_from_path_async`] stands.
+#[derive(Debug)]
+pub enum LoadStatus {
+    Loading,
+    /// Uploaded under the contained id.
+    Ready(String),
+    Failed(RendererError),
+}
+
+/// A file read and parsed off the render thread, waiting for its GPU upload.
+#[derive(Debug)]
+struct PendingLoad {
+    ticket: LoadTicket,
+    id: String,
+    light_type: LightType,
+    path: PathBuf,
+    geometry_key: Option<GeometryKey>,
+    imported: Receiver<Result<ImportedScene, RendererError>>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AssetStats {
+    pub meshes: usize,
+    /// Retained CPU geometry, counting geometry shared between duplicates once.
+    pub cpu_geometry_bytes: usize,
+}
+
+/// A loaded asset as imported, before any meshes were duplicated from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadedAsset<'a> {
+    pub id: &'a str,
+    pub light_type: LightType,
+    /// None for assets uploaded as bytes.
+    pub path: Option<&'a Path>,
+}
+
+/// What is known about one loaded mesh, read from CPU-side data only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetInfo {
+    pub id: String,
+    pub vertex_count: usize,
+    pub index_count: u32,
+    pub triangle_count: u32,
+    /// Bounds in the mesh's own space, computed from its vertices when it was uploaded.
+    pub local_aabb: Option<Aabb>,
+    /// The file the mesh, or the mesh it was duplicated from, was loaded from. None for assets
+    /// uploaded as bytes.
+    pub source_path: Option<PathBuf>,
+    pub light_type: LightType,
+    pub visible: bool,
+}
+
+/// Which files [`AssetHandler::add_directory`] picks up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryImport {
+    /// Extensions without the dot, matched case-insensitively.
+    pub extensions: Vec<String>,
+    pub recursive: bool,
+}
+
+impl Default for DirectoryImport {
+    fn default() -> Self {
+        Self {
+            extensions: vec!["gltf".to_string(), "glb".to_string()],
+            recursive: false,
+        }
+    }
+}
+
+/// CPU-side import kept per uploaded asset so its GPU resources can be rebuilt.
+struct AssetSource {
+    light_type: LightType,
+    usage: MeshUsage,
+    /// Shared by assets added from the same unchanged file.
+    scene: Arc<ImportedScene>,
+    path: Option<PathBuf>,
+    geometry_key: Option<GeometryKey>,
+}
+
+/// A file on disk as it was when read, so assets added from it while it stays unchanged can
+/// share GPU geometry instead of parsing and uploading it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GeometryKey {
+    path: PathBuf,
+    modified: SystemTime,
+}
+
+impl GeometryKey {
+    /// `None` when the file cannot be resolved on disk, e.g. on the web.
+    fn of(path: &Path) -> Option<Self> {
+        let path = std::fs::canonicalize(path).ok()?;
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        Some(Self { path, modified })
+    }
+}
+
+impl std::fmt::Debug for AssetSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetSource")
+            .field("light_type", &self.light_type)
+            .field("usage", &self.usage)
+            .field("path", &self.path)
+            .field("materials", &self.scene.materials.len())
+            .field("images", &self.scene.images.len())
+            .finish_non_exhaustive()
+    }
 }
 
 impl AssetHandler {
@@ -40,236 +190,2246 @@ impl AssetHandler {
         model_binding_mode: ModelMatrixBindingMode,
         model_bind_group_layout: Option<BindGroupLayout>,
         material_bind_group_layout: BindGroupLayout,
+        fallback_textures: Arc<FallbackTextures>,
     ) -> AssetHandler {
         AssetHandler {
-            memory_loaded_assets: HashMap::new(),
+            memory_loaded_assets: SlotMap::new(),
+            handles_by_id: HashMap::new(),
             gltf_loader: GLTFLoader::new(),
             visible_assets: HashSet::new(),
+            non_deletable_assets: HashSet::new(),
+            sources: HashMap::new(),
+            duplicate_sources: HashMap::new(),
+            geometry_retention: CpuGeometryRetention::default(),
+            pending_loads: Vec::new(),
+            finished_loads: HashMap::new(),
+            next_load_ticket: 0,
+            imported_animators: Vec::new(),
             device,
             queue,
             model_binding_mode,
             model_bind_group_layout,
             material_bind_group_layout,
+            fallback_textures,
         }
     }
 
+    pub fn geometry_retention(&self) -> CpuGeometryRetention {
+        self.geometry_retention
+    }
+
+    /// CPU geometry kept for meshes uploaded from now on. Already loaded meshes keep theirs.
+    pub fn set_geometry_retention(&mut self, retention: CpuGeometryRetention) {
+        self.geometry_retention = retention;
+    }
+
     pub async fn upload_from_bytes(
         &mut self,
         id: String,
         light_type: LightType,
         bytes: Vec<u8>,
-    ) -> Result<()> {
-        let imported_scene = self.gltf_loader.load_from_bytes(bytes).await?;
-        self.upload_imported_scene(id, light_type, imported_scene);
+    ) -> Result<(), RendererError> {
+        let imported_scene = self
+            .gltf_loader
+            .load_from_bytes(bytes)
+            .await
+            .map_err(|error| ImportError::new(id.as_str(), error))?;
+        self.upload_imported_scene(id, light_type, MeshUsage::Static, imported_scene)?;
         Ok(())
     }
 
+    /// Uploads the meshes of `imported_scene` as `{id}_{index}`, in [`NodeGraph::flatten`] order,
+    /// and returns them in that order. Meshes uploaded as [`MeshUsage::Dynamic`] can be edited
+    /// later with [`Self::update_vertices`] and [`Self::update_indices`].
+    ///
+    /// [`NodeGraph::flatten`]: hyakou_core::geometry::node::NodeGraph::flatten
     pub fn upload_imported_scene(
         &mut self,
         id: String,
         light_type: LightType,
+        usage: MeshUsage,
         imported_scene: ImportedScene,
-    ) -> Option<Rc<RenderMesh>> {
-        let fallback_texture = Rc::new(Texture::create_color_texture(
-            "Fallback Material Texture",
-            &self.device,
-            &self.queue,
-            1,
-            1,
-            &[255, 255, 255, 255],
-            default_sampler_descriptor("Fallback Material Sampler"),
-        ));
-        let uploaded_textures = self.upload_textures(&imported_scene, fallback_texture.clone());
-        let uploaded_materials = self.upload_materials(
-            &imported_scene.materials,
-            &uploaded_textures,
-            fallback_texture.clone(),
-        );
-        let default_material = Rc::new(GpuMaterial::new(
-            &self.device,
-            &self.material_bind_group_layout,
-            "Default Material",
-            &Self::default_imported_material(),
-            fallback_texture,
-        ));
-        let mesh_nodes = imported_scene.node_graph.flatten();
-
-        self.upload_mesh_node_as_asset(
+    ) -> Result<Vec<Arc<RenderMesh>>, RendererError> {
+        let asset = id.clone();
+        let meshes = self.insert_imported_scene(
             id,
             light_type,
-            mesh_nodes,
-            &uploaded_materials,
-            &default_material,
-        )
+            usage,
+            imported_scene,
+            None,
+            None,
+            &mut |_, _| {},
+        )?;
+        if meshes.is_empty() {
+            return Err(RendererError::NoMeshes { asset });
+        }
+        Ok(meshes)
     }
 
-    pub async fn add_from_path(
+    fn insert_imported_scene(
         &mut self,
         id: String,
         light_type: LightType,
+        usage: MeshUsage,
+        imported_scene: ImportedScene,
+        path: Option<PathBuf>,
+        geometry_key: Option<GeometryKey>,
+        progress: &mut dyn FnMut(LoadStage, f32),
+    ) -> Result<Vec<Arc<RenderMesh>>, RendererError> {
+        self.ensure_mesh_ids_free(&id, imported_scene.node_graph.mesh_paths().len())?;
+        let meshes = self.build_scene_meshes(&id, &light_type, usage, &imported_scene, progress);
+        let source = AssetSource {
+            light_type,
+            usage,
+            scene: Arc::new(imported_scene),
+            path,
+            geometry_key,
+        };
+        Ok(self.insert_scene_meshes(id, source, meshes))
+    }
+
+    /// Errors when one of the `mesh_count` ids `{id}_{index}` is taken by a duplicate or another
+    /// asset. Meshes of an earlier import of `id` itself are replaced by the new import instead.
+    fn ensure_mesh_ids_free(&self, id: &str, mesh_count: usize) -> Result<(), RendererError> {
+        let own_count = self
+            .sources
+            .get(id)
+            .map_or(0, |source| source.scene.node_graph.mesh_paths().len());
+        let taken = (0..mesh_count)
+            .map(|idx| (idx, format!("{id}_{idx}")))
+            .find(|(idx, mesh_id)| {
+                self.handles_by_id.contains_key(mesh_id)
+                    && (*idx >= own_count || self.duplicate_sources.contains_key(mesh_id))
+            });
+        match taken {
+            Some((_, mesh_id)) => Err(RendererError::MeshIdTaken { id: mesh_id }),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds asset `id` built from the meshes of an asset already loaded with the same `usage` from
+    /// the same unchanged file. The new meshes share its vertex, index and texture buffers but
+    /// start out with the imported transforms and materials. `None` when no such asset is fully
+    /// loaded.
+    fn add_from_loaded_geometry(
+        &mut self,
+        id: &str,
+        light_type: LightType,
+        usage: MeshUsage,
         path: &Path,
-    ) -> Result<Rc<RenderMesh>> {
-        let imported_scene = self.gltf_loader.load_from_path(path).await?;
-        self.upload_imported_scene(id, light_type, imported_scene)
-            .ok_or_else(|| {
-                anyhow!(
-                    "glTF asset `{}` produced no renderable meshes",
-                    path.display()
-                )
+        geometry_key: &GeometryKey,
+    ) -> Option<Result<Vec<Arc<RenderMesh>>, RendererError>> {
+        let (scene, templates) = self
+            .sources
+            .iter()
+            .filter(|(_, source)| {
+                source.usage == usage && source.geometry_key.as_ref() == Some(geometry_key)
+            })
+            .find_map(|(source_id, source)| {
+                let templates = self.asset_meshes(source_id);
+                (templates.len() == source.scene.node_graph.flatten().len())
+                    .then(|| (source.scene.clone(), templates))
+            })?;
+        if let Err(taken) = self.ensure_mesh_ids_free(id, templates.len()) {
+            return Some(Err(taken));
+        }
+
+        let meshes = scene
+            .node_graph
+            .flatten()
+            .into_iter()
+            .zip(&templates)
+            .enumerate()
+            .map(|(idx, (node, template))| {
+                let (_, material) = node_material(&scene, &node);
+                let mut mesh = template.instantiate_with_shared(
+                    &self.device,
+                    MeshId(format!("{id}_{idx}")),
+                    shared(node.transform),
+                    shared(material),
+                    self.model_binding_mode,
+                    self.model_bind_group_layout.as_ref(),
+                );
+                mesh.light_type = light_type;
+                mesh
             })
+            .collect();
+        let source = AssetSource {
+            light_type,
+            usage,
+            scene,
+            path: Some(path.to_path_buf()),
+            geometry_key: Some(geometry_key.clone()),
+        };
+        Some(Ok(self.insert_scene_meshes(id.to_string(), source, meshes)))
     }
 
-    fn upload_mesh_node_as_asset(
+    /// Stores `meshes` and the import they were built from under asset `id`. Nothing is stored
+    /// when there are no meshes.
+    fn insert_scene_meshes(
+        &mut self,
+        id: String,
+        source: AssetSource,
+        meshes: Vec<RenderMesh>,
+    ) -> Vec<Arc<RenderMesh>> {
+        if meshes.is_empty() {
+            return Vec::new();
+        }
+
+        let meshes: Vec<Arc<RenderMesh>> = meshes.into_iter().map(Arc::new).collect();
+        for mesh in &meshes {
+            self.insert_mesh(mesh.clone());
+        }
+        self.queue_imported_animators(&id, &source.scene);
+        self.sources.insert(id, source);
+        meshes
+    }
+
+    fn queue_imported_animators(&mut self, id: &str, imported_scene: &ImportedScene) {
+        for animation in &imported_scene.animations {
+            let Some(handle) = self.handle(&format!("{id}_{}", animation.mesh_index)) else {
+                continue;
+            };
+            let Some(mesh) = self.get_by_handle(handle) else {
+                continue;
+            };
+            let keyframes = KeyframeAnimation::new(
+                mesh.id.clone(),
+                mesh.transform.clone(),
+                animation.path.clone(),
+            );
+            match Animator::new(NEUTRAL_SPEED, Box::new(keyframes)) {
+                Ok(animator) => self.imported_animators.push((handle, animator)),
+                Err(animator_error) => {
+                    warn!("Skipping the imported animation of `{id}`: {animator_error}")
+                }
+            }
+        }
+    }
+
+    /// Animators for the glTF animations of meshes uploaded since the last call, one per animated
+    /// mesh, for the renderer to play.
+    pub fn take_imported_animators(&mut self) -> Vec<(Handle<RenderMesh>, Animator)> {
+        std::mem::take(&mut self.imported_animators)
+    }
+
+    /// Stores `mesh` as visible. A mesh re-imported under a loaded id keeps that id's handle.
+    fn insert_mesh(&mut self, mesh: Arc<RenderMesh>) -> Handle<RenderMesh> {
+        let handle = match self.handles_by_id.get(mesh.id.as_str()) {
+            Some(&handle) => {
+                self.memory_loaded_assets
+                    .get_mut(handle)
+                    .map(|slot| *slot = mesh)
+                    .expect("indexed handles are alive");
+                handle
+            }
+            None => {
+                let id = mesh.id.0.clone();
+                let handle = self.memory_loaded_assets.insert(mesh);
+                self.handles_by_id.insert(id, handle);
+                handle
+            }
+        };
+        self.visible_assets.insert(handle);
+        handle
+    }
+
+    /// Loads the file at `path` as asset `id` and returns its meshes, stored as `{id}_{index}` in
+    /// [`NodeGraph::flatten`] order. Loading again under the same id replaces the asset's meshes;
+    /// an id taken by another asset's mesh or a duplicate is an error.
+    ///
+    /// [`NodeGraph::flatten`]: hyakou_core::geometry::node::NodeGraph::flatten
+    pub async fn add_from_path(
+        &mut self,
+        id: String,
+        light_type: LightType,
+        path: &Path,
+    ) -> Result<Vec<Arc<RenderMesh>>, RendererError> {
+        self.add_from_path_with_progress(id, light_type, MeshUsage::Static, path, |_, _| {})
+            .await
+    }
+
+    /// Like [`Self::add_from_path`] with buffers created for `usage`, calling `progress` with each
+    /// [`LoadStage`] and the fraction of it done as the load advances. An asset that shares the
+    /// geometry of one already loaded from the same file only reports finished uploading.
+    #[instrument(name = "AssetHandler::add_from_path", skip_all, fields(id = %id))]
+    pub async fn add_from_path_with_progress(
         &mut self,
         id: String,
         light_type: LightType,
-        mesh_nodes: Vec<MeshNode>,
-        materials: &[Rc<GpuMaterial>],
-        default_material: &Rc<GpuMaterial>,
-    ) -> Option<Rc<RenderMesh>> {
-        let base_id = id;
-        let mut render_mesh: Option<Rc<RenderMesh>> = None;
-
-        for (idx, node) in mesh_nodes.into_iter().enumerate() {
-            let mesh_id = format!("{base_id}_{idx}");
-            let material = node
-                .material_index
-                .and_then(|material_index| materials.get(material_index).cloned())
-                .unwrap_or_else(|| default_material.clone());
-            let next_mesh = Rc::new(RenderMesh::new(
+        usage: MeshUsage,
+        path: &Path,
+        mut progress: impl FnMut(LoadStage, f32),
+    ) -> Result<Vec<Arc<RenderMesh>>, RendererError> {
+        let geometry_key = GeometryKey::of(path);
+        if let Some(added) = geometry_key
+            .as_ref()
+            .and_then(|key| self.add_from_loaded_geometry(&id, light_type, usage, path, key))
+        {
+            if added.is_ok() {
+                progress(LoadStage::UploadingBuffers, 1.0);
+            }
+            return added;
+        }
+
+        let imported_scene = import_from_path(&self.gltf_loader, path, &mut progress)
+            .await
+            .map_err(|error| import_failure(path, error))?;
+        let meshes = self.insert_imported_scene(
+            id,
+            light_type,
+            usage,
+            imported_scene,
+            Some(path.to_path_buf()),
+            geometry_key,
+            &mut progress,
+        )?;
+        if meshes.is_empty() {
+            return Err(RendererError::NoMeshes {
+                asset: path.display().to_string(),
+            });
+        }
+        Ok(meshes)
+    }
+
+    /// Loads every file in `dir` that `import` matches, in path order, each as an asset named
+    /// after its file stem. Stems seen earlier in the same call get a `_{n}` suffix. A file that
+    /// fails to load is reported in its place and the rest are still loaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[instrument(name = "AssetHandler::add_directory", skip_all, fields(dir = %dir.display()))]
+    pub async fn add_directory(
+        &mut self,
+        dir: &Path,
+        light_type: LightType,
+        import: &DirectoryImport,
+    ) -> Vec<Result<String, RendererError>> {
+        let mut files = Vec::new();
+        let mut results = Vec::new();
+        collect_files(dir, import, &mut files, &mut results);
+        files.sort();
+
+        let mut used_ids = HashSet::new();
+        for path in files {
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                results.push(Err(import_failure(
+                    &path,
+                    anyhow!("the file name is not valid UTF-8"),
+                )));
+                continue;
+            };
+            let id = std::iter::once(stem.to_string())
+                .chain((2..).map(|n| format!("{stem}_{n}")))
+                .find(|candidate| !used_ids.contains(candidate))
+                .expect("import id space is unbounded");
+            used_ids.insert(id.clone());
+            let loaded = self.add_from_path(id.clone(), light_type, &path).await;
+            results.push(loaded.map(|_| id));
+        }
+        results
+    }
+
+    /// Parses the file asset `id` was loaded from again and swaps the new geometry into its
+    /// meshes, keeping their transforms, materials and visibility. Meshes the file gained are
+    /// added, meshes it lost are unloaded. On failure the loaded meshes stay as they were.
+    #[instrument(name = "AssetHandler::reload", skip_all, fields(id = %id))]
+    pub async fn reload(&mut self, id: &str) -> Result<(), RendererError> {
+        let source = self
+            .sources
+            .get(id)
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        let path = source
+            .path
+            .clone()
+            .ok_or_else(|| RendererError::NotReloadable { id: id.to_string() })?;
+        let light_type = source.light_type;
+        let usage = source.usage;
+        let previous_count = source.scene.node_graph.mesh_paths().len();
+
+        let geometry_key = GeometryKey::of(&path);
+        let imported_scene = import_from_path(&self.gltf_loader, &path, &mut |_, _| {})
+            .await
+            .map_err(|error| import_failure(&path, error))?;
+        self.ensure_mesh_ids_free(id, imported_scene.node_graph.mesh_paths().len())?;
+        let meshes =
+            self.build_scene_meshes(id, &light_type, usage, &imported_scene, &mut |_, _| {});
+        if meshes.is_empty() {
+            return Err(RendererError::NoMeshes {
+                asset: path.display().to_string(),
+            });
+        }
+
+        let reloaded_count = meshes.len();
+        for mesh in meshes {
+            let handle = self.handle(mesh.id.as_str());
+            match handle.and_then(|handle| self.memory_loaded_assets.get_mut(handle)) {
+                Some(slot) => {
+                    let mut updated = RenderMesh::clone(slot);
+                    updated.replace_geometry(&mesh);
+                    *slot = Arc::new(updated);
+                }
+                None => {
+                    self.insert_mesh(Arc::new(mesh));
+                }
+            }
+        }
+        for idx in reloaded_count..previous_count {
+            if let Some(handle) = self.handles_by_id.remove(&format!("{id}_{idx}")) {
+                self.memory_loaded_assets.remove(handle);
+                self.visible_assets.remove(&handle);
+            }
+        }
+        if let Some(source) = self.sources.get_mut(id) {
+            source.scene = Arc::new(imported_scene);
+            source.geometry_key = geometry_key;
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::add_from_path`], but reads and parses the file on a background thread, or a
+    /// spawned future on the web, so large files do not stall rendering. The meshes are uploaded
+    /// by the [`Self::poll_pending`] call that finds the parsed file.
+    pub fn add_from_path_async(
+        &mut self,
+        id: String,
+        light_type: LightType,
+        path: &Path,
+    ) -> LoadTicket {
+        let ticket = LoadTicket(self.next_load_ticket);
+        self.next_load_ticket += 1;
+
+        let geometry_key = GeometryKey::of(path);
+        if let Some(added) = geometry_key.as_ref().and_then(|key| {
+            self.add_from_loaded_geometry(&id, light_type, MeshUsage::Static, path, key)
+        }) {
+            self.finished_loads.insert(ticket, added.map(|_| id));
+            return ticket;
+        }
+
+        let (sender, imported) = channel();
+        let loader = self.gltf_loader.clone();
+        let load_path = path.to_path_buf();
+        let load = async move {
+            let result = import_from_path(&loader, &load_path, &mut |_, _| {})
+                .await
+                .map_err(|error| import_failure(&load_path, error));
+            // The handler may be gone by now, then nobody waits for the result.
+            let _ = sender.send(result);
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(spawn_error) = std::thread::Builder::new()
+            .name(format!("asset loader {id}"))
+            .spawn(move || pollster::block_on(load))
+        {
+            self.finished_loads.insert(
+                ticket,
+                Err(ImportError::new(path.display().to_string(), spawn_error.into()).into()),
+            );
+            return ticket;
+        }
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(load);
+
+        self.pending_loads.push(PendingLoad {
+            ticket,
+            id,
+            light_type,
+            path: path.to_path_buf(),
+            geometry_key,
+            imported,
+        });
+        ticket
+    }
+
+    /// Uploads every asset whose background load finished since the last call and returns how
+    /// many loads completed, successfully or not. Called once per frame.
+    pub fn poll_pending(&mut self) -> usize {
+        if self.pending_loads.is_empty() {
+            return 0;
+        }
+        let mut completed = 0;
+        for load in std::mem::take(&mut self.pending_loads) {
+            let outcome = match load.imported.try_recv() {
+                Ok(Ok(imported_scene)) => self
+                    .insert_imported_scene(
+                        load.id.clone(),
+                        load.light_type,
+                        MeshUsage::Static,
+                        imported_scene,
+                        Some(load.path.clone()),
+                        load.geometry_key,
+                        &mut |_, _| {},
+                    )
+                    .and_then(|meshes| {
+                        if meshes.is_empty() {
+                            Err(RendererError::NoMeshes {
+                                asset: load.path.display().to_string(),
+                            })
+                        } else {
+                            Ok(load.id)
+                        }
+                    }),
+                Ok(Err(load_error)) => Err(load_error),
+                Err(TryRecvError::Empty) => {
+                    self.pending_loads.push(load);
+                    continue;
+                }
+                Err(TryRecvError::Disconnected) => Err(ImportError::new(
+                    load.path.display().to_string(),
+                    anyhow!("the loader stopped before finishing"),
+                )
+                .into()),
+            };
+            if let Err(load_error) = &outcome {
+                warn!("Failed to load `{}`: {load_error}", load.path.display());
+            }
+            self.finished_loads.insert(load.ticket, outcome);
+            completed += 1;
+        }
+        completed
+    }
+
+    pub fn has_pending_loads(&self) -> bool {
+        !self.pending_loads.is_empty()
+    }
+
+    /// `None` for a ticket this handler did not issue. A finished load is reported once: returning
+    /// `Ready` or `Failed` hands over its outcome, and the ticket is unknown afterwards.
+    pub fn load_status(&mut self, ticket: LoadTicket) -> Option<LoadStatus> {
+        if let Some(outcome) = self.finished_loads.remove(&ticket) {
+            return Some(match outcome {
+                Ok(id) => LoadStatus::Ready(id),
+                Err(load_error) => LoadStatus::Failed(load_error),
+            });
+        }
+        self.pending_loads
+            .iter()
+            .any(|load| load.ticket == ticket)
+            .then_some(LoadStatus::Loading)
+    }
+
+    /// Moves every loaded asset onto a new device, e.g. after the old one was lost. Geometry,
+    /// textures and materials are uploaded again from the retained imports, while each mesh keeps
+    /// its shared transform, material, visibility and deletability.
+    pub fn rebuild_gpu_resources(
+        &mut self,
+        device: Arc<Device>,
+        queue: Queue,
+        model_binding_mode: ModelMatrixBindingMode,
+        model_bind_group_layout: Option<BindGroupLayout>,
+        material_bind_group_layout: BindGroupLayout,
+        fallback_textures: Arc<FallbackTextures>,
+    ) {
+        self.device = device;
+        self.queue = queue;
+        self.model_binding_mode = model_binding_mode;
+        self.model_bind_group_layout = model_bind_group_layout;
+        self.material_bind_group_layout = material_bind_group_layout;
+        self.fallback_textures = fallback_textures;
+
+        let templates: HashMap<String, RenderMesh> = self
+            .sources
+            .iter()
+            .flat_map(|(id, source)| {
+                self.build_scene_meshes(
+                    id,
+                    &source.light_type,
+                    source.usage,
+                    &source.scene,
+                    &mut |_, _| {},
+                )
+            })
+            .map(|mesh| (mesh.id.0.clone(), mesh))
+            .collect();
+
+        // Rebuilt meshes go back into their old slots, so handles held elsewhere stay valid.
+        let previous: Vec<(Handle<RenderMesh>, Arc<RenderMesh>)> = self
+            .memory_loaded_assets
+            .iter()
+            .map(|(handle, mesh)| (handle, mesh.clone()))
+            .collect();
+        for (handle, old_mesh) in previous {
+            let id = &old_mesh.id.0;
+            let template_id = self.duplicate_sources.get(id).unwrap_or(id);
+            let Some(template) = templates.get(template_id) else {
+                warn!("Dropping `{id}` after device loss: no retained source to rebuild it from");
+                self.memory_loaded_assets.remove(handle);
+                self.handles_by_id.remove(id);
+                self.visible_assets.remove(&handle);
+                continue;
+            };
+
+            let mut rebuilt = template.instantiate_with_shared(
                 &self.device,
-                node,
-                material,
-                &light_type,
-                Some(MeshId(mesh_id.clone())),
+                old_mesh.id.clone(),
+                old_mesh.transform.clone(),
+                old_mesh.material.clone(),
                 self.model_binding_mode,
                 self.model_bind_group_layout.as_ref(),
-            ));
-            self.memory_loaded_assets
-                .insert(mesh_id.clone(), next_mesh.clone());
-            self.visible_assets.insert(mesh_id);
-            render_mesh = Some(next_mesh);
+            );
+            // Full geometry carries in-place edits the retained import does not know about.
+            if old_mesh.geometry.vertices().is_some() {
+                rebuilt.geometry = old_mesh.geometry.clone();
+                rebuilt.reupload_geometry(&self.device);
+            }
+            if let Some(slot) = self.memory_loaded_assets.get_mut(handle) {
+                *slot = Arc::new(rebuilt);
+            }
         }
-
-        render_mesh
     }
 
-    fn upload_textures(
+    fn build_scene_meshes(
         &self,
+        id: &str,
+        light_type: &LightType,
+        usage: MeshUsage,
         imported_scene: &ImportedScene,
-        fallback_texture: Rc<Texture>,
-    ) -> Vec<Rc<Texture>> {
-        imported_scene
-            .textures
-            .iter()
-            .map(|texture| {
-                let Some(image) = imported_scene.images.get(texture.image_index) else {
-                    return fallback_texture.clone();
-                };
-
-                let sampler_descriptor = texture
-                    .sampler_index
-                    .and_then(|sampler_index| imported_scene.samplers.get(sampler_index))
-                    .map(|sampler| {
-                        sampler_descriptor_from_imported_sampler(
-                            sampler,
-                            sampler
-                                .name
-                                .as_deref()
-                                .unwrap_or("Imported Texture Sampler"),
-                        )
-                    })
+        progress: &mut dyn FnMut(LoadStage, f32),
+    ) -> Vec<RenderMesh> {
+        let base_color_textures =
+            self.upload_textures(imported_scene, true, |material| material.base_color_texture);
+        let metallic_roughness_textures = self.upload_textures(imported_scene, false, |material| {
+            material.metallic_roughness_texture
+        });
+        let material_textures = self.material_textures(
+            &imported_scene.materials,
+            &base_color_textures,
+            &metallic_roughness_textures,
+        );
+
+        let nodes = imported_scene.node_graph.flatten();
+        let mesh_count = nodes.len();
+        nodes
+            .into_iter()
+            .enumerate()
+            .map(|(idx, node)| {
+                let mesh_id = MeshId(format!("{id}_{idx}"));
+                let (imported_material, material) = node_material(imported_scene, &node);
+                let (texture, metallic_roughness_texture) = imported_material
+                    .and_then(|imported| material_textures.get(imported.index).cloned())
                     .unwrap_or_else(|| {
-                        default_sampler_descriptor("Default Imported Texture Sampler")
+                        (
+                            self.fallback_textures.base_color.clone(),
+                            self.fallback_textures.metallic_roughness.clone(),
+                        )
                     });
+                // Every mesh gets its own material uniform, so editing one never tints another
+                // mesh that was imported with the same glTF material.
+                let gpu_material = Arc::new(GpuMaterial::new(
+                    &self.device,
+                    &self.material_bind_group_layout,
+                    &mesh_id.0,
+                    &material,
+                    texture,
+                    metallic_roughness_texture,
+                ));
+                let mut mesh = RenderMesh::new(
+                    &self.device,
+                    node,
+                    shared(material),
+                    gpu_material,
+                    light_type,
+                    Some(mesh_id),
+                    usage,
+                    self.model_binding_mode,
+                    self.model_bind_group_layout.as_ref(),
+                );
+                mesh.retain_geometry(self.geometry_retention);
+                progress(
+                    LoadStage::UploadingBuffers,
+                    (idx + 1) as f32 / mesh_count as f32,
+                );
+                mesh
+            })
+            .collect()
+    }
+
+    /// Uploads the textures `slot` picks from any material, keyed by texture index. Colour maps
+    /// are sampled as sRGB, data maps like metallic-roughness have to stay linear.
+    fn upload_textures(
+        &self,
+        imported_scene: &ImportedScene,
+        srgb: bool,
+        slot: impl Fn(&ImportedMaterial) -> Option<ImportedTextureRef>,
+    ) -> HashMap<usize, Arc<Texture>> {
+        let mut uploaded = HashMap::new();
+        for texture_ref in imported_scene.materials.iter().filter_map(slot) {
+            if uploaded.contains_key(&texture_ref.texture_index) {
+                continue;
+            }
+            let Some(texture) = imported_scene.textures.get(texture_ref.texture_index) else {
+                continue;
+            };
+            let Some(image) = imported_scene.images.get(texture.image_index) else {
+                continue;
+            };
+
+            let sampler_descriptor = texture
+                .sampler_index
+                .and_then(|sampler_index| imported_scene.samplers.get(sampler_index))
+                .map(|sampler| {
+                    sampler_descriptor_from_imported_sampler(
+                        sampler,
+                        sampler
+                            .name
+                            .as_deref()
+                            .unwrap_or("Imported Texture Sampler"),
+                    )
+                })
+                .unwrap_or_else(|| default_sampler_descriptor("Default Imported Texture Sampler"));
 
-                Rc::new(Texture::create_color_texture(
+            uploaded.insert(
+                texture_ref.texture_index,
+                Arc::new(Texture::upload_rgba8(
                     texture.name.as_deref().unwrap_or("Imported Texture"),
                     &self.device,
                     &self.queue,
                     image.width,
                     image.height,
                     &image.pixels_rgba8,
-                    sampler_descriptor,
-                ))
-            })
-            .collect()
+                    color_format(srgb),
+                    &sampler_descriptor,
+                )),
+            );
+        }
+        uploaded
     }
 
-    fn upload_materials(
+    /// Base color and metallic-roughness texture of every imported material, in material order.
+    fn material_textures(
         &self,
         imported_materials: &[ImportedMaterial],
-        uploaded_textures: &[Rc<Texture>],
-        fallback_texture: Rc<Texture>,
-    ) -> Vec<Rc<GpuMaterial>> {
+        base_color_textures: &HashMap<usize, Arc<Texture>>,
+        metallic_roughness_textures: &HashMap<usize, Arc<Texture>>,
+    ) -> Vec<(Arc<Texture>, Arc<Texture>)> {
+        let pick = |texture_ref: Option<ImportedTextureRef>,
+                    uploaded: &HashMap<usize, Arc<Texture>>,
+                    fallback: &Arc<Texture>| {
+            texture_ref
+                .and_then(|texture_ref| uploaded.get(&texture_ref.texture_index).cloned())
+                .unwrap_or_else(|| fallback.clone())
+        };
+
         imported_materials
             .iter()
             .map(|material| {
-                let texture = material
-                    .base_color_texture
-                    .and_then(|texture_ref| {
-                        uploaded_textures.get(texture_ref.texture_index).cloned()
-                    })
-                    .unwrap_or_else(|| fallback_texture.clone());
-
-                Rc::new(GpuMaterial::new(
-                    &self.device,
-                    &self.material_bind_group_layout,
-                    material.name.as_deref().unwrap_or("Imported Material"),
-                    material,
-                    texture,
-                ))
+                (
+                    pick(
+                        material.base_color_texture,
+                        base_color_textures,
+                        &self.fallback_textures.base_color,
+                    ),
+                    pick(
+                        material.metallic_roughness_texture,
+                        metallic_roughness_textures,
+                        &self.fallback_textures.metallic_roughness,
+                    ),
+                )
             })
             .collect()
     }
 
-    fn default_imported_material() -> ImportedMaterial {
-        ImportedMaterial {
-            index: usize::MAX,
-            name: None,
-            base_color_factor: Vec4::ONE,
-            base_color_texture: None,
-            alpha_mode: ImportedAlphaMode::Opaque,
-            alpha_cutoff: None,
-        }
+    /// A copy of the material of mesh `id`.
+    pub fn material(&self, id: &str) -> Result<Material, RendererError> {
+        let mesh = self
+            .handle(id)
+            .and_then(|handle| self.get_by_handle(handle))
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        mesh.material
+            .try_read_shared(Material::clone)
+            .map_err(|_| RendererError::LockContention { id: id.to_string() })
     }
 
-    pub fn get(&self, id: String) -> &RenderMesh {
-        match self.memory_loaded_assets.get(&id) {
-            Some(asset) => asset,
-            None => {
-                panic!("Asset not found!")
+    /// Edits the material of mesh `id`, e.g. to tint the selection. The change is uploaded with
+    /// the next frame's uniforms.
+    pub fn set_material(
+        &self,
+        id: &str,
+        edit: impl FnOnce(&mut Material),
+    ) -> Result<(), RendererError> {
+        let mesh = self
+            .handle(id)
+            .and_then(|handle| self.get_by_handle(handle))
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        mesh.material
+            .try_write_shared(|material| {
+                edit(material);
+                material.mark_dirty();
+            })
+            .map_err(|_| RendererError::LockContention { id: id.to_string() })
+    }
+
+    /// Replaces the vertices of mesh `id`, which has to be uploaded as [`MeshUsage::Dynamic`].
+    /// Duplicates and assets sharing its buffers keep their geometry.
+    pub fn update_vertices(
+        &mut self,
+        id: &str,
+        vertices: &[Vertex],
+    ) -> Result<GeometryUpdate, RendererError> {
+        self.update_geometry(id, |mesh, device, queue| {
+            mesh.update_vertices(device, queue, vertices)
+        })
+    }
+
+    /// Replaces the indices of mesh `id`, like [`Self::update_vertices`].
+    pub fn update_indices(
+        &mut self,
+        id: &str,
+        indices: &[u32],
+    ) -> Result<GeometryUpdate, RendererError> {
+        self.update_geometry(id, |mesh, device, queue| {
+            mesh.update_indices(device, queue, indices)
+        })
+    }
+
+    fn update_geometry(
+        &mut self,
+        id: &str,
+        update: impl FnOnce(&mut RenderMesh, &Device, &Queue) -> anyhow::Result<GeometryUpdate>,
+    ) -> Result<GeometryUpdate, RendererError> {
+        let mesh = self
+            .handles_by_id
+            .get(id)
+            .and_then(|&handle| self.memory_loaded_assets.get_mut(handle))
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        // Whoever still holds the previous `Arc` keeps drawing the old geometry.
+        update(Arc::make_mut(mesh), &self.device, &self.queue).map_err(|reason| {
+            RendererError::GeometryUpdate {
+                id: id.to_string(),
+                reason: format!("{reason:#}"),
             }
+        })
+    }
+
+    pub fn get(&self, id: String) -> Result<&RenderMesh, RendererError> {
+        self.handle(&id)
+            .and_then(|handle| self.get_by_handle(handle))
+            .map(|asset| asset.as_ref())
+            .ok_or(RendererError::AssetNotFound { id })
+    }
+
+    pub fn handle(&self, id: &str) -> Option<Handle<RenderMesh>> {
+        self.handles_by_id.get(id).copied()
+    }
+
+    /// `None` once the asset behind `handle` was removed, even if its slot was reused since.
+    pub fn get_by_handle(&self, handle: Handle<RenderMesh>) -> Option<&Arc<RenderMesh>> {
+        self.memory_loaded_assets.get(handle)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.handles_by_id.contains_key(id)
+    }
+
+    pub fn is_deletable(&self, id: &str) -> bool {
+        !self.non_deletable_assets.contains(id)
+    }
+
+    pub fn set_deletable(&mut self, id: &str, deletable: bool) {
+        if deletable {
+            self.non_deletable_assets.remove(id);
+        } else {
+            self.non_deletable_assets.insert(id.to_string());
+        }
+    }
+
+    /// Unloads the mesh `id`; its GPU buffers are freed once the returned mesh and any other
+    /// clones are dropped. Animators may keep driving its transform, which no longer draws. The
+    /// retained import of an asset is released with the last mesh built from it.
+    pub fn remove(&mut self, id: &str) -> Result<Arc<RenderMesh>, RendererError> {
+        if !self.is_deletable(id) {
+            return Err(RendererError::AssetNotDeletable { id: id.to_string() });
+        }
+
+        let handle = self
+            .handles_by_id
+            .remove(id)
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        let mesh = self
+            .memory_loaded_assets
+            .remove(handle)
+            .expect("indexed handles are alive");
+        self.visible_assets.remove(&handle);
+        self.duplicate_sources.remove(id);
+        self.release_unused_sources();
+        Ok(mesh)
+    }
+
+    /// Drops the imports that neither a loaded mesh nor a duplicate of one is built from anymore.
+    fn release_unused_sources(&mut self) {
+        let templates: HashSet<&str> = self
+            .handles_by_id
+            .keys()
+            .map(|id| self.duplicate_sources.get(id).unwrap_or(id).as_str())
+            .collect();
+        let unused: Vec<String> = self
+            .sources
+            .iter()
+            .filter(|(id, source)| {
+                !(0..source.scene.node_graph.flatten().len())
+                    .any(|idx| templates.contains(format!("{id}_{idx}").as_str()))
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in unused {
+            self.sources.remove(&id);
+        }
+    }
+
+    /// Adds a copy of `id` moved by `offset`. The copy shares GPU geometry and textures with the
+    /// source but owns its transform and material.
+    pub fn duplicate(&mut self, id: &str, offset: Vec3) -> Result<Arc<RenderMesh>, RendererError> {
+        let source = self
+            .handle(id)
+            .and_then(|handle| self.memory_loaded_assets.get(handle))
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        let duplicate_id =
+            unique_duplicate_id(id, |candidate| self.handles_by_id.contains_key(candidate));
+        let mut transform = source.transform.read_shared(|t| *t);
+        transform.translate(offset);
+
+        let duplicate = Arc::new(source.instantiate(
+            &self.device,
+            MeshId(duplicate_id.clone()),
+            transform,
+            self.model_binding_mode,
+            self.model_bind_group_layout.as_ref(),
+        ));
+        let template_id = self
+            .duplicate_sources
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string());
+        self.duplicate_sources
+            .insert(duplicate_id.clone(), template_id);
+        self.insert_mesh(duplicate.clone());
+        Ok(duplicate)
+    }
+
+    /// Every imported asset, sorted by id.
+    pub fn loaded_assets(&self) -> Vec<LoadedAsset<'_>> {
+        let mut assets: Vec<LoadedAsset<'_>> = self
+            .sources
+            .iter()
+            .map(|(id, source)| LoadedAsset {
+                id,
+                light_type: source.light_type,
+                path: source.path.as_deref(),
+            })
+            .collect();
+        assets.sort_by_key(|asset| asset.id);
+        assets
+    }
+
+    /// `None` when no mesh `id` is loaded.
+    pub fn info(&self, id: &str) -> Option<AssetInfo> {
+        let handle = self.handle(id)?;
+        let mesh = self.get_by_handle(handle)?;
+        Some(AssetInfo {
+            id: id.to_string(),
+            vertex_count: mesh.geometry.vertex_count(),
+            index_count: mesh.index_count,
+            triangle_count: mesh.index_count / 3,
+            local_aabb: mesh.geometry.local_aabb(),
+            source_path: self
+                .source_of_mesh(id)
+                .and_then(|source| source.path.clone()),
+            light_type: mesh.light_type,
+            visible: self.visible_assets.contains(&handle),
+        })
+    }
+
+    /// [`Self::info`] of every loaded mesh, sorted by id.
+    pub fn list_info(&self) -> Vec<AssetInfo> {
+        let mut ids: Vec<&str> = self.handles_by_id.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids.into_iter().filter_map(|id| self.info(id)).collect()
+    }
+
+    /// The import mesh `id` was built from, following duplicates back to their template.
+    fn source_of_mesh(&self, id: &str) -> Option<&AssetSource> {
+        let template_id = self.duplicate_sources.get(id).map_or(id, String::as_str);
+        self.sources.iter().find_map(|(asset_id, source)| {
+            let idx: usize = template_id
+                .strip_prefix(asset_id.as_str())?
+                .strip_prefix('_')?
+                .parse()
+                .ok()?;
+            (idx < source.scene.node_graph.flatten().len()).then_some(source)
+        })
+    }
+
+    /// Meshes imported for the asset `id`, in import order. Duplicates made from them are not
+    /// included.
+    pub fn asset_meshes(&self, id: &str) -> Vec<Arc<RenderMesh>> {
+        let Some(source) = self.sources.get(id) else {
+            return Vec::new();
+        };
+        (0..source.scene.node_graph.flatten().len())
+            .filter_map(|idx| self.handle(&format!("{id}_{idx}")))
+            .filter_map(|handle| self.get_by_handle(handle).cloned())
+            .collect()
+    }
+
+    /// Moves every mesh imported for asset `id` as if they were children of `parent`, so the
+    /// layout of the glTF node hierarchy is kept while the asset moves as a whole.
+    pub fn place_asset(&self, id: &str, parent: &Transform) -> Result<(), RendererError> {
+        if !self.sources.contains_key(id) {
+            return Err(RendererError::asset_not_found(id));
+        }
+        for mesh in self.asset_meshes(id) {
+            mesh.transform
+                .write_shared(|transform| *transform = transform.parented_to(parent));
+        }
+        Ok(())
+    }
+
+    /// Unloads every asset, including the non-deletable ones.
+    pub fn clear(&mut self) {
+        let handles: Vec<Handle<RenderMesh>> = self
+            .memory_loaded_assets
+            .iter()
+            .map(|(handle, _)| handle)
+            .collect();
+        for handle in handles {
+            self.memory_loaded_assets.remove(handle);
+        }
+        self.handles_by_id.clear();
+        self.visible_assets.clear();
+        self.non_deletable_assets.clear();
+        self.sources.clear();
+        self.duplicate_sources.clear();
+        self.imported_animators.clear();
+    }
+
+    pub fn stats(&self) -> AssetStats {
+        let mut geometries = HashSet::new();
+        let cpu_geometry_bytes = self
+            .memory_loaded_assets
+            .iter()
+            .filter(|(_, mesh)| geometries.insert(Arc::as_ptr(&mesh.geometry)))
+            .map(|(_, mesh)| mesh.geometry.byte_size())
+            .sum();
+        AssetStats {
+            meshes: self.memory_loaded_assets.len(),
+            cpu_geometry_bytes,
         }
     }
 
     pub fn get_all_loaded_asset_ids(&self) -> Vec<String> {
-        self.memory_loaded_assets.clone().into_keys().collect()
+        self.handles_by_id.keys().cloned().collect()
     }
 
-    pub fn get_visible_asset_ids(&self) -> Iter<'_, std::string::String> {
-        self.visible_assets.iter()
+    pub fn get_visible_asset_ids(&self) -> impl Iterator<Item = &str> {
+        self.get_all_visible_assets().map(|mesh| mesh.id.as_str())
     }
 
-    pub fn toggle_visibility(&mut self, id: String) {
-        let asset_id = self.visible_assets.iter().find(|elem| elem.eq(&&id));
-        if asset_id.is_some() {
-            self.visible_assets.remove(&id);
+    pub fn is_visible(&self, id: &str) -> bool {
+        self.handle(id)
+            .is_some_and(|handle| self.visible_assets.contains(&handle))
+    }
+
+    pub fn set_visible(&mut self, id: &str, visible: bool) {
+        let Some(handle) = self.handle(id) else {
+            warn!("Cannot change visibility of `{id}`: asset is not loaded");
+            return;
+        };
+        if visible {
+            self.visible_assets.insert(handle);
         } else {
-            self.visible_assets.insert(id);
+            self.visible_assets.remove(&handle);
+        }
+    }
+
+    pub fn toggle_visibility(&mut self, id: String) {
+        let Some(handle) = self.handle(&id) else {
+            warn!("Cannot toggle visibility of `{id}`: asset is not loaded");
+            return;
+        };
+        if !self.visible_assets.remove(&handle) {
+            self.visible_assets.insert(handle);
         }
     }
 
+    pub fn get_all_assets(&self) -> impl Iterator<Item = &Arc<RenderMesh>> {
+        self.memory_loaded_assets.iter().map(|(_, mesh)| mesh)
+    }
+
+    pub fn get_all_visible_assets(&self) -> impl Iterator<Item = &Arc<RenderMesh>> {
+        self.visible_assets
+            .iter()
+            .filter_map(|&handle| self.memory_loaded_assets.get(handle))
+    }
+
+    pub fn get_visible(&self, id: &str) -> Option<&Arc<RenderMesh>> {
+        self.handle(id)
+            .filter(|handle| self.visible_assets.contains(handle))
+            .and_then(|handle| self.memory_loaded_assets.get(handle))
+    }
+
+    pub fn get_all_visible_handles(
+        &self,
+    ) -> impl Iterator<Item = (Handle<RenderMesh>, &Arc<RenderMesh>)> {
+        self.visible_assets.iter().filter_map(|&handle| {
+            self.memory_loaded_assets
+                .get(handle)
+                .map(|mesh| (handle, mesh))
+        })
+    }
+
     pub fn get_all_visible_assets_with_modifier(
         &mut self,
         light_type: &LightType,
-    ) -> impl Iterator<Item = &Rc<RenderMesh>> {
-        self.get_visible_asset_ids()
-            .map(|id| self.memory_loaded_assets.get(id).unwrap())
+    ) -> impl Iterator<Item = &Arc<RenderMesh>> {
+        self.get_all_visible_assets()
             .filter(move |rm| rm.light_type.eq(&light_type))
     }
 
-    pub fn get_visible_asset_by_id(&mut self, id: &str) -> &mut Rc<RenderMesh> {
-        self.memory_loaded_assets.get_mut(id).unwrap()
+    pub fn get_visible_asset_by_id(
+        &mut self,
+        id: &str,
+    ) -> Result<&mut Arc<RenderMesh>, RendererError> {
+        let handle = self
+            .handle(id)
+            .ok_or_else(|| RendererError::asset_not_found(id))?;
+        self.memory_loaded_assets
+            .get_mut(handle)
+            .ok_or_else(|| RendererError::asset_not_found(id))
+    }
+}
+
+/// The imported material `node` references, if any, and the material it starts out with.
+fn node_material<'a>(
+    imported_scene: &'a ImportedScene,
+    node: &Mesh,
+) -> (Option<&'a ImportedMaterial>, Material) {
+    let imported_material = node
+        .material_index
+        .and_then(|material_index| imported_scene.materials.get(material_index));
+    let mut material = imported_material.map(Material::from).unwrap_or_default();
+    material.has_vertex_colors = node.has_vertex_colors();
+    (imported_material, material)
+}
+
+/// Reads `path` with the importer its extension names; anything but `.obj` is read as glTF.
+async fn import_from_path(
+    gltf_loader: &GLTFLoader,
+    path: &Path,
+    progress: &mut impl FnMut(LoadStage, f32),
+) -> anyhow::Result<ImportedScene> {
+    let is_obj = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("obj"));
+    if is_obj {
+        ObjLoader::new()
+            .load_from_path_with_progress(path, progress)
+            .await
+    } else {
+        gltf_loader
+            .load_from_path_with_progress(path, progress)
+            .await
+    }
+}
+
+/// Read failures stay [`RendererError::Io`] and mesh-less files [`RendererError::NoMeshes`], so
+/// callers can tell a missing or empty file from a broken one.
+fn import_failure(path: &Path, error: anyhow::Error) -> RendererError {
+    let error = match error.downcast::<std::io::Error>() {
+        Ok(source) => {
+            return RendererError::Io {
+                path: path.to_path_buf(),
+                source,
+            };
+        }
+        Err(error) => error,
+    };
+    match error.downcast::<NoRenderableMeshes>() {
+        Ok(_) => RendererError::NoMeshes {
+            asset: path.display().to_string(),
+        },
+        Err(error) => ImportError::new(path.display().to_string(), error).into(),
+    }
+}
+
+/// Adds the files under `dir` that `import` matches to `files`, descending into subdirectories
+/// when it is recursive. Directories that cannot be read are reported in `errors`.
+#[cfg(not(target_arch = "wasm32"))]
+fn collect_files(
+    dir: &Path,
+    import: &DirectoryImport,
+    files: &mut Vec<PathBuf>,
+    errors: &mut Vec<Result<String, RendererError>>,
+) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(source) => {
+            errors.push(Err(RendererError::Io {
+                path: dir.to_path_buf(),
+                source,
+            }));
+            return;
+        }
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(source) => {
+                errors.push(Err(RendererError::Io {
+                    path: dir.to_path_buf(),
+                    source,
+                }));
+                continue;
+            }
+        };
+        if path.is_dir() {
+            if import.recursive {
+                collect_files(&path, import, files, errors);
+            }
+        } else if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                import
+                    .extensions
+                    .iter()
+                    .any(|wanted| extension.eq_ignore_ascii_case(wanted))
+            })
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// First `{source}_copy_{n}` id that is not taken yet.
+pub fn unique_duplicate_id(source: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    (1..)
+        .map(|n| format!("{source}_copy_{n}"))
+        .find(|candidate| !is_taken(candidate))
+        .expect("duplicate id space is unbounded")
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec4;
+    use hyakou_core::{
+        animations::{Animator, NEUTRAL_SPEED, trajectory::linear::LinearTrajectory},
+        components::LightType,
+        geometry::ray::Ray,
+        same_shared,
+        util::test_support::assert_vec3_eq,
+    };
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext,
+        test_harness::{RenderHarness, cube_scene, gpu_tests_enabled},
+        util::asset_root::AssetRoot,
+        wrappers::MockSurfaceProvider,
+    };
+
+    fn gpu_asset_handler(test: &str) -> Option<(RenderContext, AssetHandler)> {
+        if !gpu_tests_enabled(test) {
+            return None;
+        }
+
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let asset_handler = AssetHandler::new(
+            ctx.device.clone(),
+            ctx.queue.clone(),
+            ctx.model_binding_mode,
+            ctx.model_bind_group_layout.clone(),
+            ctx.material_bind_group_layout.clone(),
+            ctx.fallback_textures(),
+        );
+        Some((ctx, asset_handler))
+    }
+
+    #[test]
+    fn test_deleting_selected_asset_drops_frame_draw_count() {
+        let Some(mut harness) =
+            RenderHarness::new("test_deleting_selected_asset_drops_frame_draw_count")
+        else {
+            return;
+        };
+        let deleted = harness.add(
+            "Deleted",
+            LightType::NO_LIGHT,
+            cube_scene(Vec4::ONE),
+            Vec3::new(-1.0, 0.0, 0.0),
+        );
+        harness.add(
+            "Kept",
+            LightType::NO_LIGHT,
+            cube_scene(Vec4::ONE),
+            Vec3::new(1.0, 0.0, 0.0),
+        );
+        harness.render_frames(1);
+        let draws_before = harness.renderer.last_draw_count();
+
+        harness
+            .renderer
+            .selection_mut()
+            .select(deleted.id.0.clone());
+        assert!(harness.renderer.delete_selected());
+        harness.render_frames(1);
+
+        assert_eq!(draws_before, 2);
+        assert_eq!(harness.renderer.last_draw_count(), draws_before - 1);
+        assert!(!harness.renderer.asset_manager.contains(&deleted.id.0));
+        assert!(harness.renderer.selection().selected().is_none());
+    }
+
+    #[test]
+    fn test_dynamic_mesh_updates_show_in_the_next_frame() {
+        let Some(mut harness) =
+            RenderHarness::new("test_dynamic_mesh_updates_show_in_the_next_frame")
+        else {
+            return;
+        };
+        let scene = cube_scene(Vec4::ONE);
+        let node = scene.node_graph.flatten().remove(0);
+        let moved: Vec<Vertex> = node
+            .vertices
+            .iter()
+            .map(|vertex| Vertex {
+                position: vertex.position + Vec3::new(10.0, 0.0, 0.0),
+                ..*vertex
+            })
+            .collect();
+        let assets = &mut harness.renderer.asset_manager;
+        assets
+            .upload_imported_scene(
+                "Dynamic".to_string(),
+                LightType::NO_LIGHT,
+                MeshUsage::Dynamic,
+                scene,
+            )
+            .unwrap();
+        assert_ne!(harness.render().center(), [0, 0, 0]);
+
+        let assets = &mut harness.renderer.asset_manager;
+        let moved_update = assets.update_vertices("Dynamic_0", &moved).unwrap();
+        let index_update = assets
+            .update_indices("Dynamic_0", &node.indices[..6])
+            .unwrap();
+
+        assert!(!moved_update.reallocated);
+        assert!(index_update.index_count_changed);
+        let mesh = assets.get("Dynamic_0".to_string()).unwrap();
+        assert_eq!(mesh.index_count, 6);
+        assert_vec3_eq(
+            mesh.world_bounds().unwrap().center(),
+            Vec3::new(10.0, 0.0, 0.0),
+            "updated bounds",
+        );
+        assert_eq!(harness.render().center(), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_updating_a_duplicate_leaves_its_source_unchanged() {
+        let Some(mut harness) =
+            RenderHarness::new("test_updating_a_duplicate_leaves_its_source_unchanged")
+        else {
+            return;
+        };
+        let assets = &mut harness.renderer.asset_manager;
+        let source = assets
+            .upload_imported_scene(
+                "Source".to_string(),
+                LightType::NO_LIGHT,
+                MeshUsage::Dynamic,
+                cube_scene(Vec4::ONE),
+            )
+            .unwrap()
+            .remove(0);
+        let duplicate = assets
+            .duplicate("Source_0", Vec3::new(10.0, 0.0, 0.0))
+            .unwrap();
+        let collapsed = vec![Vertex::default(); source.geometry.vertex_count()];
+
+        let update = assets
+            .update_vertices(duplicate.id.as_str(), &collapsed)
+            .unwrap();
+
+        let updated = assets.get(duplicate.id.0.clone()).unwrap();
+        let kept = assets.get("Source_0".to_string()).unwrap();
+        assert!(update.reallocated);
+        assert!(!Arc::ptr_eq(&updated.vertex_buffer, &kept.vertex_buffer));
+        assert!(Arc::ptr_eq(&kept.vertex_buffer, &source.vertex_buffer));
+        assert_eq!(kept.geometry.positions(), source.geometry.positions());
+        assert_ne!(updated.geometry.positions(), kept.geometry.positions());
+        // The source still draws as a cube in the middle of the frame.
+        assert_ne!(harness.render().center(), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_static_meshes_reject_geometry_updates() {
+        let Some(mut harness) = RenderHarness::new("test_static_meshes_reject_geometry_updates")
+        else {
+            return;
+        };
+        harness.add(
+            "Static",
+            LightType::NO_LIGHT,
+            cube_scene(Vec4::ONE),
+            Vec3::ZERO,
+        );
+        let assets = &mut harness.renderer.asset_manager;
+
+        assert!(matches!(
+            assets.update_indices("Static_0", &[0, 1, 2]),
+            Err(RendererError::GeometryUpdate { id, .. }) if id == "Static_0"
+        ));
+        assert!(matches!(
+            assets.update_indices("Missing_0", &[0, 1, 2]),
+            Err(RendererError::AssetNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_refuses_non_deletable_asset() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_remove_refuses_non_deletable_asset")
+        else {
+            return;
+        };
+        pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+        ))
+        .unwrap();
+
+        asset_handler.set_deletable("Cube_0", false);
+        assert!(matches!(
+            asset_handler.remove("Cube_0"),
+            Err(RendererError::AssetNotDeletable { .. })
+        ));
+        assert!(asset_handler.contains("Cube_0"));
+
+        asset_handler.set_deletable("Cube_0", true);
+        assert!(asset_handler.remove("Cube_0").is_ok());
+        assert!(asset_handler.remove("Cube_0").is_err());
+    }
+
+    #[test]
+    fn test_removed_asset_handle_stays_stale_after_its_slot_is_reused() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_removed_asset_handle_stays_stale_after_its_slot_is_reused")
+        else {
+            return;
+        };
+        pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+        ))
+        .unwrap();
+        let cube = asset_handler.handle("Cube_0").unwrap();
+
+        asset_handler.remove("Cube_0").unwrap();
+        pollster::block_on(
+            asset_handler.add_from_path(
+                "Suzanne".to_string(),
+                LightType::NO_LIGHT,
+                AssetRoot::new()
+                    .join("gltf/Suzanne.gltf")
+                    .unwrap()
+                    .as_path(),
+            ),
+        )
+        .unwrap();
+        let suzanne = asset_handler.handle("Suzanne_0").unwrap();
+
+        assert_eq!(suzanne.index(), cube.index());
+        assert!(asset_handler.get_by_handle(cube).is_none());
+        assert_eq!(
+            asset_handler.get_by_handle(suzanne).unwrap().id,
+            MeshId("Suzanne_0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_removed_asset_can_be_added_again_while_its_animator_lives() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_removed_asset_can_be_added_again_while_its_animator_lives")
+        else {
+            return;
+        };
+        let path = AssetRoot::new().join("gltf/Cube.gltf").unwrap();
+        let cube = pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::LIGHT,
+            &path,
+        ))
+        .unwrap()
+        .remove(0);
+        let mut animator = Animator::new(
+            NEUTRAL_SPEED,
+            Box::new(
+                LinearTrajectory::new_deconstructed_mesh(
+                    cube.id.clone(),
+                    cube.transform.clone(),
+                    Vec3::ZERO,
+                    0.0,
+                    0.0,
+                    1.0,
+                    1.0,
+                    true,
+                    false,
+                )
+                .unwrap(),
+            ),
+        )
+        .unwrap();
+
+        let removed = asset_handler.remove("Cube_0").unwrap();
+        drop(removed);
+        drop(cube);
+
+        assert!(!asset_handler.contains("Cube_0"));
+        assert_eq!(asset_handler.get_all_visible_assets().count(), 0);
+        assert!(asset_handler.loaded_assets().is_empty());
+        animator.play(0.5).unwrap();
+
+        let readded = pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::LIGHT,
+            &path,
+        ))
+        .unwrap()
+        .remove(0);
+        let imported_position = readded.transform.read_shared(|t| t.position);
+        animator.play(0.25).unwrap();
+
+        assert_eq!(readded.id, MeshId("Cube_0".to_string()));
+        assert!(asset_handler.is_visible("Cube_0"));
+        assert_eq!(
+            readded.transform.read_shared(|t| t.position),
+            imported_position
+        );
+        assert_eq!(asset_handler.loaded_assets().len(), 1);
+    }
+
+    #[test]
+    fn test_add_from_path_loads_obj_files_by_extension() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_add_from_path_loads_obj_files_by_extension")
+        else {
+            return;
+        };
+
+        let mesh = pollster::block_on(
+            asset_handler.add_from_path(
+                "Cube".to_string(),
+                LightType::NO_LIGHT,
+                AssetRoot::new()
+                    .join("obj/test_fixtures/cube.obj")
+                    .unwrap()
+                    .as_path(),
+            ),
+        )
+        .unwrap()
+        .remove(0);
+
+        assert_eq!(mesh.id.as_str(), "Cube_0");
+        assert_eq!(mesh.index_count, 36);
+        assert!(asset_handler.contains("Cube_0"));
+    }
+
+    #[test]
+    fn test_asset_handler_and_render_meshes_can_cross_threads() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+
+        assert_send::<AssetHandler>();
+        assert_send::<RenderMesh>();
+        assert_sync::<RenderMesh>();
+        assert_send::<Arc<RenderMesh>>();
+    }
+
+    #[test]
+    fn test_every_primitive_gets_its_own_mesh_id() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_every_primitive_gets_its_own_mesh_id")
+        else {
+            return;
+        };
+        let path = AssetRoot::new()
+            .join("gltf/test_fixtures/two_primitives.gltf")
+            .unwrap();
+        let add = |asset_handler: &mut AssetHandler, id: &str| {
+            pollster::block_on(asset_handler.add_from_path(
+                id.to_string(),
+                LightType::NO_LIGHT,
+                path.as_path(),
+            ))
+        };
+
+        let meshes = add(&mut asset_handler, "Two").unwrap();
+        let ids: Vec<&str> = meshes.iter().map(|mesh| mesh.id.as_str()).collect();
+        assert_eq!(ids, ["Two_0", "Two_1"]);
+        assert!(asset_handler.contains("Two_0"));
+        assert!(asset_handler.contains("Two_1"));
+        assert_eq!(asset_handler.get_all_assets().count(), 2);
+
+        let reimported = add(&mut asset_handler, "Two").unwrap();
+        assert_eq!(reimported.len(), 2);
+        assert_eq!(
+            asset_handler.get_all_assets().count(),
+            2,
+            "replaced, not added"
+        );
+
+        let copy = asset_handler.duplicate("Two_0", Vec3::X).unwrap();
+        assert_eq!(copy.id.as_str(), "Two_0_copy_1");
+        assert!(matches!(
+            add(&mut asset_handler, "Two_0_copy"),
+            Err(RendererError::MeshIdTaken { id }) if id == "Two_0_copy_1"
+        ));
+        assert!(!asset_handler.contains("Two_0_copy_0"));
+        assert!(same_shared(
+            &asset_handler
+                .get("Two_0_copy_1".to_string())
+                .unwrap()
+                .transform,
+            &copy.transform
+        ));
+    }
+
+    #[test]
+    fn test_add_from_path_with_progress_reports_stages_in_order() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_add_from_path_with_progress_reports_stages_in_order")
+        else {
+            return;
+        };
+        let path = AssetRoot::new()
+            .join("gltf/test_fixtures/two_primitives.gltf")
+            .unwrap();
+        let mut load = |id: &str| {
+            let mut reports = Vec::new();
+            pollster::block_on(asset_handler.add_from_path_with_progress(
+                id.to_string(),
+                LightType::NO_LIGHT,
+                MeshUsage::Static,
+                &path,
+                |stage, fraction| reports.push((stage, fraction)),
+            ))
+            .unwrap();
+            reports
+        };
+
+        let first = load("Two");
+        let shared = load("Again");
+
+        let stages: Vec<LoadStage> = first.iter().map(|(stage, _)| *stage).collect();
+        assert!(stages.is_sorted(), "{first:?}");
+        assert_eq!(stages.first(), Some(&LoadStage::ReadingBytes));
+        assert_eq!(
+            first[first.len() - 4..],
+            [
+                (LoadStage::BuildingVertices, 0.5),
+                (LoadStage::BuildingVertices, 1.0),
+                (LoadStage::UploadingBuffers, 0.5),
+                (LoadStage::UploadingBuffers, 1.0),
+            ]
+        );
+        assert_eq!(shared, [(LoadStage::UploadingBuffers, 1.0)]);
+    }
+
+    #[test]
+    fn test_add_directory_loads_each_model_and_reports_failures_per_file() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_add_directory_loads_each_model_and_reports_failures_per_file")
+        else {
+            return;
+        };
+        let model = std::fs::read(
+            AssetRoot::new()
+                .join("gltf/test_fixtures/vertex_colors_data_uri.gltf")
+                .unwrap(),
+        )
+        .unwrap();
+        let dir =
+            std::env::temp_dir().join(format!("hyako_asset_directory_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("Model.gltf"), &model).unwrap();
+        std::fs::write(dir.join("Broken.GLTF"), b"{ not json").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"not a model").unwrap();
+        std::fs::write(dir.join("nested/Model.gltf"), &model).unwrap();
+
+        let flat = pollster::block_on(asset_handler.add_directory(
+            &dir,
+            LightType::LIGHT,
+            &DirectoryImport::default(),
+        ));
+        let recursive = pollster::block_on(asset_handler.add_directory(
+            &dir,
+            LightType::LIGHT,
+            &DirectoryImport {
+                recursive: true,
+                ..DirectoryImport::default()
+            },
+        ));
+        let missing = pollster::block_on(asset_handler.add_directory(
+            &dir.join("missing"),
+            LightType::LIGHT,
+            &DirectoryImport::default(),
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(flat.len(), 2, "notes.txt is skipped");
+        assert!(matches!(&flat[0], Err(RendererError::Import(_))));
+        assert_eq!(flat[1].as_ref().unwrap(), "Model");
+        assert_eq!(recursive.len(), 3);
+        assert_eq!(recursive[1].as_ref().unwrap(), "Model");
+        assert_eq!(recursive[2].as_ref().unwrap(), "Model_2");
+        assert!(asset_handler.contains("Model_0"));
+        assert!(asset_handler.contains("Model_2_0"));
+        assert!(matches!(
+            missing.as_slice(),
+            [Err(RendererError::Io { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_missing_and_broken_assets_report_specific_errors() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_missing_and_broken_assets_report_specific_errors")
+        else {
+            return;
+        };
+
+        let missing_file = pollster::block_on(
+            asset_handler.add_from_path(
+                "Missing".to_string(),
+                LightType::NO_LIGHT,
+                AssetRoot::new()
+                    .join("gltf/DoesNotExist.gltf")
+                    .unwrap()
+                    .as_path(),
+            ),
+        );
+        let broken_bytes = pollster::block_on(asset_handler.upload_from_bytes(
+            "Broken".to_string(),
+            LightType::NO_LIGHT,
+            b"not a gltf file".to_vec(),
+        ));
+
+        assert!(matches!(missing_file, Err(RendererError::Io { .. })));
+        assert!(matches!(
+            broken_bytes,
+            Err(RendererError::Import(error)) if error.asset() == "Broken"
+        ));
+        assert!(matches!(
+            asset_handler.get("Missing_0".to_string()),
+            Err(RendererError::AssetNotFound { id }) if id == "Missing_0"
+        ));
+        assert!(!asset_handler.contains("Missing_0"));
+        assert!(matches!(
+            asset_handler.get_visible_asset_by_id("Missing_0"),
+            Err(RendererError::AssetNotFound { id }) if id == "Missing_0"
+        ));
+        assert!(matches!(
+            asset_handler.remove("Missing_0"),
+            Err(RendererError::AssetNotFound { .. })
+        ));
+        assert!(matches!(
+            asset_handler.duplicate("Missing_0", Vec3::X),
+            Err(RendererError::AssetNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_corrupt_and_mesh_less_files_report_specific_errors() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_corrupt_and_mesh_less_files_report_specific_errors")
+        else {
+            return;
+        };
+        let dir = std::env::temp_dir().join(format!("hyako_asset_errors_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let corrupt_path = dir.join("Corrupt.gltf");
+        std::fs::write(&corrupt_path, b"{ not json").unwrap();
+        let empty_path = dir.join("Empty.gltf");
+        std::fs::write(
+            &empty_path,
+            br#"{
+                "asset": { "version": "2.0" },
+                "scene": 0,
+                "scenes": [{ "nodes": [0] }],
+                "nodes": [{ "name": "EmptyNode" }]
+            }"#,
+        )
+        .unwrap();
+
+        let corrupt = pollster::block_on(asset_handler.add_from_path(
+            "Corrupt".to_string(),
+            LightType::LIGHT,
+            &corrupt_path,
+        ));
+        let empty = pollster::block_on(asset_handler.add_from_path(
+            "Empty".to_string(),
+            LightType::LIGHT,
+            &empty_path,
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            corrupt,
+            Err(RendererError::Import(error)) if error.asset().ends_with("Corrupt.gltf")
+        ));
+        assert!(matches!(
+            empty,
+            Err(RendererError::NoMeshes { asset }) if asset.ends_with("Empty.gltf")
+        ));
+        assert!(asset_handler.get_all_loaded_asset_ids().is_empty());
+    }
+
+    #[test]
+    fn test_async_loads_finish_on_poll() {
+        let Some((_ctx, mut asset_handler)) = gpu_asset_handler("test_async_loads_finish_on_poll")
+        else {
+            return;
+        };
+        let assets = AssetRoot::new();
+        let cube = asset_handler.add_from_path_async(
+            "Cube".to_string(),
+            LightType::LIGHT,
+            &assets.join("gltf/Cube.gltf").unwrap(),
+        );
+        let missing = asset_handler.add_from_path_async(
+            "Missing".to_string(),
+            LightType::LIGHT,
+            &assets.join("gltf/DoesNotExist.gltf").unwrap(),
+        );
+        // Nothing is uploaded before a poll finds the parsed file.
+        assert!(matches!(
+            asset_handler.load_status(cube),
+            Some(LoadStatus::Loading)
+        ));
+        assert!(!asset_handler.contains("Cube_0"));
+
+        let mut completed = 0;
+        for _ in 0..500 {
+            completed += asset_handler.poll_pending();
+            if !asset_handler.has_pending_loads() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        assert_eq!(completed, 2);
+        assert!(matches!(
+            asset_handler.load_status(cube),
+            Some(LoadStatus::Ready(id)) if id == "Cube"
+        ));
+        assert!(asset_handler.contains("Cube_0"));
+        assert!(matches!(
+            asset_handler.load_status(missing),
+            Some(LoadStatus::Failed(RendererError::Io { .. }))
+        ));
+        // Reporting a finished load forgets it.
+        assert!(asset_handler.load_status(cube).is_none());
+        assert!(asset_handler.load_status(missing).is_none());
+        assert!(asset_handler.load_status(LoadTicket(99)).is_none());
+    }
+
+    #[test]
+    fn test_unique_duplicate_id_skips_taken_ids() {
+        let taken = HashSet::from(["Cube_0_copy_1".to_string(), "Cube_0_copy_2".to_string()]);
+
+        assert_eq!(
+            unique_duplicate_id("Cube_0", |id| taken.contains(id)),
+            "Cube_0_copy_3"
+        );
+        assert_eq!(
+            unique_duplicate_id("Suzanne_0", |_| false),
+            "Suzanne_0_copy_1"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_has_independent_transform() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_duplicate_has_independent_transform")
+        else {
+            return;
+        };
+        let cube = pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+        ))
+        .unwrap()
+        .remove(0);
+        let original_position = cube.transform.read_shared(|t| t.position);
+
+        let first = asset_handler.duplicate("Cube_0", Vec3::X).unwrap();
+        let second = asset_handler.duplicate(&first.id.0, Vec3::X).unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(
+            second.transform.read_shared(|t| t.position),
+            original_position + Vec3::X * 2.0
+        );
+
+        first
+            .transform
+            .write_shared(|t| t.translate(Vec3::new(0.0, 5.0, 0.0)));
+        assert_eq!(
+            cube.transform.read_shared(|t| t.position),
+            original_position
+        );
+        assert_eq!(asset_handler.get_all_visible_assets().count(), 3);
+    }
+
+    #[test]
+    fn test_adding_the_same_file_twice_shares_gpu_geometry() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_adding_the_same_file_twice_shares_gpu_geometry")
+        else {
+            return;
+        };
+        let path = AssetRoot::new().join("gltf/Cube.gltf").unwrap();
+        let add = |asset_handler: &mut AssetHandler, id: &str| {
+            pollster::block_on(asset_handler.add_from_path(
+                id.to_string(),
+                LightType::NO_LIGHT,
+                path.as_path(),
+            ))
+            .unwrap()
+            .remove(0)
+        };
+        let first = add(&mut asset_handler, "First");
+        let second = add(&mut asset_handler, "Second");
+
+        assert_ne!(first.id, second.id);
+        assert!(Arc::ptr_eq(&first.vertex_buffer, &second.vertex_buffer));
+        assert!(Arc::ptr_eq(&first.index_buffer, &second.index_buffer));
+        assert!(!same_shared(&first.transform, &second.transform));
+        first
+            .transform
+            .write_shared(|t| t.translate(Vec3::new(0.0, 5.0, 0.0)));
+        assert_ne!(
+            first.transform.read_shared(|t| t.position),
+            second.transform.read_shared(|t| t.position)
+        );
+
+        asset_handler.remove("First_0").unwrap();
+        let third = add(&mut asset_handler, "Third");
+        assert!(
+            Arc::ptr_eq(&second.vertex_buffer, &third.vertex_buffer),
+            "still cached while Second is loaded"
+        );
+
+        asset_handler.remove("Second_0").unwrap();
+        asset_handler.remove("Third_0").unwrap();
+        let fourth = add(&mut asset_handler, "Fourth");
+        assert!(
+            !Arc::ptr_eq(&first.vertex_buffer, &fourth.vertex_buffer),
+            "evicted with the last mesh"
+        );
+    }
+
+    #[test]
+    fn test_info_describes_loaded_meshes_and_their_duplicates() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_info_describes_loaded_meshes_and_their_duplicates")
+        else {
+            return;
+        };
+        asset_handler.set_geometry_retention(CpuGeometryRetention::AabbOnly);
+        let path = AssetRoot::new().join("gltf/Cube.gltf").unwrap();
+        pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            path.as_path(),
+        ))
+        .unwrap();
+        let copy = asset_handler.duplicate("Cube_0", Vec3::X).unwrap();
+        asset_handler.set_visible(&copy.id.0, false);
+
+        let info = asset_handler.info("Cube_0").unwrap();
+        assert_eq!(info.vertex_count, 36);
+        assert_eq!(info.index_count, 36);
+        assert_eq!(info.triangle_count, 12);
+        let bounds = info.local_aabb.unwrap();
+        assert_vec3_eq(bounds.min, Vec3::splat(-1.0), "bounds min");
+        assert_vec3_eq(bounds.max, Vec3::splat(1.0), "bounds max");
+        assert_eq!(info.source_path.as_deref(), Some(path.as_path()));
+        assert_eq!(info.light_type, LightType::NO_LIGHT);
+        assert!(info.visible);
+
+        let copy_info = asset_handler.info(&copy.id.0).unwrap();
+        assert_eq!(copy_info.source_path.as_deref(), Some(path.as_path()));
+        assert!(!copy_info.visible);
+        assert!(asset_handler.info("Missing").is_none());
+        assert_eq!(
+            asset_handler
+                .list_info()
+                .iter()
+                .map(|info| info.id.as_str())
+                .collect::<Vec<_>>(),
+            ["Cube_0", copy.id.0.as_str()]
+        );
+    }
+
+    #[test]
+    fn test_world_bounds_follow_the_mesh_transform() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_world_bounds_follow_the_mesh_transform")
+        else {
+            return;
+        };
+        let cube = pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+        ))
+        .unwrap()
+        .remove(0);
+        let bounds = cube.world_bounds().unwrap();
+        assert_vec3_eq(bounds.min, Vec3::splat(-1.0), "imported min");
+        assert_vec3_eq(bounds.max, Vec3::splat(1.0), "imported max");
+
+        cube.transform.write_shared(|transform| {
+            *transform = Transform::new(
+                Vec3::new(10.0, 0.0, 0.0),
+                glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+                Vec3::new(1.0, 2.0, 3.0),
+            )
+        });
+        let bounds = cube.world_bounds().unwrap();
+
+        assert_vec3_eq(bounds.min, Vec3::new(7.0, -2.0, -1.0), "moved min");
+        assert_vec3_eq(bounds.max, Vec3::new(13.0, 2.0, 1.0), "moved max");
+    }
+
+    #[test]
+    fn test_placing_an_asset_moves_its_meshes_as_one_hierarchy() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_placing_an_asset_moves_its_meshes_as_one_hierarchy")
+        else {
+            return;
+        };
+        pollster::block_on(
+            asset_handler.add_from_path(
+                "Hierarchy".to_string(),
+                LightType::NO_LIGHT,
+                AssetRoot::new()
+                    .join("gltf/test_fixtures/scene_hierarchy.gltf")
+                    .unwrap()
+                    .as_path(),
+            ),
+        )
+        .unwrap();
+        let position = |id: &str| {
+            asset_handler
+                .get(id.to_string())
+                .unwrap()
+                .transform
+                .read_shared(|t| t.position)
+        };
+        assert_eq!(position("Hierarchy_0"), Vec3::new(10.0, 0.0, 0.0));
+        assert_eq!(position("Hierarchy_1"), Vec3::new(12.0, 0.0, 0.0));
+
+        let parent = Transform::new(
+            Vec3::new(0.0, 0.0, 5.0),
+            glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            Vec3::ONE,
+        );
+        asset_handler.place_asset("Hierarchy", &parent).unwrap();
+
+        assert!(position("Hierarchy_0").abs_diff_eq(Vec3::new(0.0, 0.0, -5.0), 1e-5));
+        assert!(position("Hierarchy_1").abs_diff_eq(Vec3::new(0.0, 0.0, -7.0), 1e-5));
+        assert!(matches!(
+            asset_handler.place_asset("Missing", &parent),
+            Err(RendererError::AssetNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_imported_animations_queue_one_animator_per_animated_mesh() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_imported_animations_queue_one_animator_per_animated_mesh")
+        else {
+            return;
+        };
+        let triangle = pollster::block_on(
+            asset_handler.add_from_path(
+                "Spinner".to_string(),
+                LightType::NO_LIGHT,
+                AssetRoot::new()
+                    .join("gltf/test_fixtures/animated_hierarchy.gltf")
+                    .unwrap()
+                    .as_path(),
+            ),
+        )
+        .unwrap()
+        .remove(0);
+
+        let mut animators = asset_handler.take_imported_animators();
+        assert_eq!(animators.len(), 1);
+        assert!(asset_handler.take_imported_animators().is_empty());
+        let (handle, animator) = &mut animators[0];
+        assert_eq!(Some(*handle), asset_handler.handle("Spinner_0"));
+
+        // Three quarters into the one second clip: the step translation already moved to x = 3.
+        animator.play(0.75).unwrap();
+        let expected = glam::Quat::from_rotation_y(0.75 * std::f32::consts::FRAC_PI_2)
+            * Vec3::new(3.0, 0.0, 0.0);
+        assert!(
+            triangle
+                .transform
+                .read_shared(|t| t.position)
+                .abs_diff_eq(expected, 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_set_material_edits_only_the_target_mesh() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_set_material_edits_only_the_target_mesh")
+        else {
+            return;
+        };
+        pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+        ))
+        .unwrap();
+        let copy = asset_handler.duplicate("Cube_0", Vec3::X).unwrap();
+        let original = asset_handler.material("Cube_0").unwrap();
+
+        let red = glam::Vec4::new(1.0, 0.0, 0.0, 1.0);
+        asset_handler
+            .set_material(&copy.id.0, |material| {
+                material.base_color = red;
+                material.roughness = 0.2;
+            })
+            .unwrap();
+
+        let edited = asset_handler.material(&copy.id.0).unwrap();
+        assert_eq!(edited.base_color, red);
+        assert_eq!(edited.roughness, 0.2);
+        assert!(edited.is_dirty());
+        assert_eq!(
+            asset_handler.material("Cube_0").unwrap().base_color,
+            original.base_color
+        );
+        assert!(matches!(
+            asset_handler.set_material("Missing", |_| {}),
+            Err(RendererError::AssetNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reload_swaps_geometry_and_keeps_transform_and_visibility() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_reload_swaps_geometry_and_keeps_transform_and_visibility")
+        else {
+            return;
+        };
+        asset_handler.set_geometry_retention(CpuGeometryRetention::Full);
+        let fixture = |name: &str| {
+            std::fs::read(
+                AssetRoot::new()
+                    .join("gltf/test_fixtures")
+                    .unwrap()
+                    .join(name),
+            )
+            .unwrap()
+        };
+        let dir = std::env::temp_dir().join(format!("hyako_asset_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Model.gltf");
+        std::fs::write(&path, fixture("vertex_colors_data_uri.gltf")).unwrap();
+        let original = pollster::block_on(asset_handler.add_from_path(
+            "Model".to_string(),
+            LightType::NO_LIGHT,
+            &path,
+        ))
+        .unwrap()
+        .remove(0);
+        original.transform.write_shared(|t| t.translate(Vec3::Y));
+        asset_handler.set_visible("Model_0", false);
+        let first_color = |asset_handler: &AssetHandler| {
+            asset_handler
+                .get("Model_0".to_string())
+                .unwrap()
+                .geometry
+                .vertices()
+                .unwrap()[0]
+                .colors
+        };
+        assert_eq!(
+            first_color(&asset_handler),
+            glam::Vec4::new(1.0, 0.0, 0.0, 1.0)
+        );
+
+        // The re-exported file drops its vertex colors.
+        std::fs::write(&path, fixture("animated_hierarchy.gltf")).unwrap();
+        pollster::block_on(asset_handler.reload("Model")).unwrap();
+
+        let reloaded = asset_handler.get("Model_0".to_string()).unwrap();
+        assert!(same_shared(&reloaded.transform, &original.transform));
+        assert_eq!(first_color(&asset_handler), glam::Vec4::ONE);
+        assert!(!asset_handler.is_visible("Model_0"));
+
+        std::fs::write(&path, b"{ not json").unwrap();
+        let broken = pollster::block_on(asset_handler.reload("Model"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(broken, Err(RendererError::Import(_))));
+        assert_eq!(first_color(&asset_handler), glam::Vec4::ONE);
+        assert!(matches!(
+            pollster::block_on(asset_handler.reload("Missing")),
+            Err(RendererError::AssetNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_geometry_retention_policy_limits_picking_and_memory() {
+        let Some((_ctx, mut asset_handler)) =
+            gpu_asset_handler("test_geometry_retention_policy_limits_picking_and_memory")
+        else {
+            return;
+        };
+        let load = |asset_handler: &mut AssetHandler, id: &str| {
+            pollster::block_on(asset_handler.add_from_path(
+                id.to_string(),
+                LightType::NO_LIGHT,
+                AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+            ))
+            .unwrap()
+            .remove(0)
+        };
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z);
+
+        let picked = load(&mut asset_handler, "Picked");
+        asset_handler.duplicate("Picked_0", Vec3::X).unwrap();
+        let picking_bytes = asset_handler.stats().cpu_geometry_bytes;
+        asset_handler.set_geometry_retention(CpuGeometryRetention::AabbOnly);
+        let bounded = load(&mut asset_handler, "Bounded");
+
+        assert_eq!(
+            picked.geometry.retention(),
+            CpuGeometryRetention::PositionsAndIndicesOnly
+        );
+        assert!(picked.geometry.vertices().is_none());
+        assert!(picked.raycast(&ray).is_some());
+        assert_eq!(bounded.geometry.retention(), CpuGeometryRetention::AabbOnly);
+        assert!(bounded.geometry.local_aabb().is_some());
+        assert!(bounded.raycast(&ray).is_none());
+        assert!(picking_bytes > 0);
+        assert_eq!(
+            asset_handler.stats(),
+            AssetStats {
+                meshes: 3,
+                cpu_geometry_bytes: picking_bytes,
+            }
+        );
     }
 }
@@ -56,6 +56,7 @@ impl CameraMovementHandler {
                         Self::calculate_pan_offset(delta_x, delta_y, &axes, camera.sensitivity);
                     camera.eye += offset;
                     camera.target += offset;
+                    camera.mark_changed();
                 }
                 CameraMode::ORBIT => {
                     let yaw_delta = mouse_delta.delta_position.x() as f32;
@@ -84,6 +85,11 @@ impl CameraMovementHandler {
                 CameraActions::SlowModifier => self.is_slow_modifier_pressed = is_pressed,
                 CameraActions::Drag => self.is_mouse_dragging = is_pressed,
             },
+            Action::App(_)
+            | Action::Gizmo(_)
+            | Action::Nudge(_)
+            | Action::Selection(_)
+            | Action::Simulation(_) => {}
         }
     }
 
@@ -94,6 +100,7 @@ impl CameraMovementHandler {
         delta_time: DeltaTime,
     ) {
         camera.eye = transition.advance(delta_time).to_vec();
+        camera.mark_changed();
     }
 
     pub fn update_camera_with_keyboard(
@@ -109,6 +116,9 @@ impl CameraMovementHandler {
     }
 
     fn update_camera_with_movement(&self, camera: &mut Camera, mode: &CameraMode, movement: &Vec3) {
+        if *movement == Vec3::ZERO {
+            return;
+        }
         match mode {
             CameraMode::ORBIT => camera.eye += movement,
             _ => {
@@ -116,6 +126,7 @@ impl CameraMovementHandler {
                 camera.target += movement;
             }
         }
+        camera.mark_changed();
     }
 
     fn get_axes(&self, camera: &Camera, mode: &CameraMode) -> CameraAxes {
@@ -166,6 +177,7 @@ impl CameraMovementHandler {
 
         let forward = calculate_direction_vector(*camera.yaw, *camera.pitch);
         camera.eye = camera.target - forward * orbit_radius;
+        camera.mark_changed();
     }
 
     fn movement_calculcation(
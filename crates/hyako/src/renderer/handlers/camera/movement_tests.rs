@@ -191,6 +191,20 @@ fn test_update_camera_no_movement_when_no_keys_pressed() {
     assert_eq!(camera.eye, initial_eye);
 }
 
+#[test]
+fn test_keyboard_update_marks_camera_changed_only_when_it_moves() {
+    let mut camera = create_test_camera();
+    let mut controller = CameraMovementHandler::new();
+    let idle = camera.generation();
+
+    controller.update_camera_with_keyboard(&mut camera, &CameraMode::ORBIT, 0.1);
+    assert_eq!(camera.generation(), idle);
+
+    controller.is_forward_pressed = true;
+    controller.update_camera_with_keyboard(&mut camera, &CameraMode::ORBIT, 0.1);
+    assert_ne!(camera.generation(), idle);
+}
+
 #[test]
 fn test_update_camera_forward_stops_when_too_close_to_target() {
     let mut camera = Camera::new(
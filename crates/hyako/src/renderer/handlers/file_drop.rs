@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use glam::Vec3;
+use hyakou_core::{
+    SharedAccess,
+    components::{
+        LightType,
+        camera::{camera::Camera, data_structures::CameraProjection},
+    },
+    geometry::aabb::Aabb,
+};
+
+use crate::{
+    gpu::render_mesh::RenderMesh,
+    renderer::{gizmo::view_direction, handlers::asset_handler::AssetHandler},
+};
+
+const IMPORTABLE_EXTENSIONS: [&str; 3] = ["gltf", "glb", "obj"];
+/// Closest a dropped asset is placed to the camera, so tiny models stay clickable.
+const MIN_DROP_DISTANCE: f32 = 2.0;
+/// Gap between models laid out side by side by [`import_models`].
+const MODEL_SPACING: f32 = 0.5;
+
+pub fn is_importable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMPORTABLE_EXTENSIONS
+                .iter()
+                .any(|importable| extension.eq_ignore_ascii_case(importable))
+        })
+}
+
+/// Asset id derived from the file stem, suffixed with `_{n}` when meshes of that name are already
+/// loaded. Uploaded meshes are stored as `{id}_{index}`, so only the first mesh id is checked.
+pub fn asset_id_from_path(path: &Path, is_taken: impl Fn(&str) -> bool) -> Result<String> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| anyhow!("`{}` has no usable file name", path.display()))?;
+
+    Ok(std::iter::once(stem.to_string())
+        .chain((2..).map(|n| format!("{stem}_{n}")))
+        .find(|candidate| !is_taken(&format!("{candidate}_0")))
+        .expect("import id space is unbounded"))
+}
+
+/// Imports a dropped glTF file and moves it in front of the camera, returning the new mesh ids.
+pub async fn import_dropped_file(
+    asset_manager: &mut AssetHandler,
+    camera: &Camera,
+    path: &Path,
+) -> Result<Vec<String>> {
+    let imported = import_file(asset_manager, path).await?;
+    let meshes = meshes_by_id(asset_manager, &imported)?;
+    place_in_front_of_camera(camera, &meshes);
+    Ok(imported)
+}
+
+/// Imports `paths` side by side along the X axis, centred on the origin, and frames the camera on
+/// all of them. Returns the new mesh ids.
+pub async fn import_models(
+    asset_manager: &mut AssetHandler,
+    camera: &mut Camera,
+    paths: &[PathBuf],
+) -> Result<Vec<String>> {
+    let mut models = Vec::with_capacity(paths.len());
+    for path in paths {
+        models.push(import_file(asset_manager, path).await?);
+    }
+
+    let mut row_end = 0.0;
+    for model in &models {
+        let meshes = meshes_by_id(asset_manager, model)?;
+        let Some(bounds) = world_bounds(&meshes) else {
+            continue;
+        };
+        let center = bounds.center();
+        translate(
+            &meshes,
+            Vec3::new(row_end - bounds.min.x, -center.y, -center.z),
+        );
+        row_end += bounds.extents().x + MODEL_SPACING;
+    }
+
+    let imported: Vec<String> = models.into_iter().flatten().collect();
+    let meshes = meshes_by_id(asset_manager, &imported)?;
+    if let Some(bounds) = world_bounds(&meshes) {
+        let offset = -bounds.center();
+        translate(&meshes, offset);
+        focus_camera_on(camera, &Aabb::new(bounds.min + offset, bounds.max + offset))?;
+    }
+    Ok(imported)
+}
+
+async fn import_file(asset_manager: &mut AssetHandler, path: &Path) -> Result<Vec<String>> {
+    if !is_importable(path) {
+        return Err(anyhow!(
+            "`{}` is not a .gltf, .glb or .obj file",
+            path.display()
+        ));
+    }
+
+    let id = asset_id_from_path(path, |candidate| asset_manager.contains(candidate))?;
+    let meshes = asset_manager
+        .add_from_path(id, LightType::LIGHT, path)
+        .await?;
+
+    Ok(meshes.iter().map(|mesh| mesh.id.0.clone()).collect())
+}
+
+fn meshes_by_id<'a>(
+    asset_manager: &'a AssetHandler,
+    ids: &[String],
+) -> Result<Vec<&'a RenderMesh>> {
+    let meshes = ids
+        .iter()
+        .map(|mesh_id| asset_manager.get(mesh_id.clone()))
+        .collect::<Result<_, _>>()?;
+    Ok(meshes)
+}
+
+fn translate(meshes: &[&RenderMesh], offset: Vec3) {
+    for mesh in meshes {
+        mesh.transform
+            .write_shared(|transform| transform.translate(offset));
+    }
+}
+
+/// Translates `meshes` together so their combined bounds sit centred on the view direction.
+pub fn place_in_front_of_camera(camera: &Camera, meshes: &[&RenderMesh]) {
+    let Some(bounds) = world_bounds(meshes) else {
+        return;
+    };
+
+    translate(meshes, drop_point(camera, &bounds) - bounds.center());
+}
+
+/// Point in front of the camera far enough away for a sphere around `bounds` to fit the view.
+pub fn drop_point(camera: &Camera, bounds: &Aabb) -> Vec3 {
+    let distance = (fit_distance(camera, bounds) + camera.znear).max(MIN_DROP_DISTANCE);
+    camera.eye + view_direction(camera) * distance
+}
+
+/// Moves the camera along its current view direction until `bounds` fill the view, looking at
+/// their center.
+pub fn focus_camera_on(camera: &mut Camera, bounds: &Aabb) -> Result<()> {
+    let direction = match view_direction(camera) {
+        Vec3::ZERO => Vec3::NEG_Z,
+        direction => direction,
+    };
+    let distance = (fit_distance(camera, bounds) + camera.znear).max(MIN_DROP_DISTANCE);
+    let center = bounds.center();
+    camera.look_at(center - direction * distance, center)
+}
+
+/// Distance at which a sphere around `bounds` fits the view.
+fn fit_distance(camera: &Camera, bounds: &Aabb) -> f32 {
+    let radius = bounds.extents().length() * 0.5;
+    match camera.projection {
+        CameraProjection::Perspective => radius / (camera.fovy * 0.5).tan(),
+        CameraProjection::Orthographic { .. } => radius,
+    }
+}
+
+/// Combined world-space bounds of `meshes`; meshes without local bounds are skipped.
+pub fn world_bounds(meshes: &[&RenderMesh]) -> Option<Aabb> {
+    let corners: Vec<Vec3> = meshes
+        .iter()
+        .filter_map(|mesh| mesh.world_bounds())
+        .flat_map(|aabb| [aabb.min, aabb.max])
+        .collect();
+    Aabb::from_points(corners)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use hyakou_core::types::camera::{Pitch, Yaw};
+
+    use super::*;
+    use crate::renderer::{
+        renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+        util::asset_root::AssetRoot, wrappers::MockSurfaceProvider,
+    };
+
+    fn camera() -> Camera {
+        Camera::new(
+            Vec3::new(0.0, 2.0, 5.0),
+            Vec3::ZERO,
+            Vec3::Y,
+            16.0 / 9.0,
+            45.0_f32.to_radians(),
+            0.1,
+            100.0,
+            Yaw::new(0.0),
+            Pitch::new(0.0),
+            20.0,
+            1.0,
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_is_importable_accepts_gltf_glb_and_obj_only() {
+        assert!(is_importable(Path::new("models/Cube.gltf")));
+        assert!(is_importable(Path::new("models/monkey.GLB")));
+        assert!(is_importable(Path::new("models/cube.obj")));
+        assert!(!is_importable(Path::new("models/Cube.bin")));
+        assert!(!is_importable(Path::new("models/Cube")));
+    }
+
+    #[test]
+    fn test_asset_id_from_path_suffixes_taken_stems() {
+        let path = PathBuf::from("models/Cube.gltf");
+        assert_eq!(asset_id_from_path(&path, |_| false).unwrap(), "Cube");
+        assert_eq!(
+            asset_id_from_path(&path, |id| id == "Cube_0" || id == "Cube_2_0").unwrap(),
+            "Cube_3"
+        );
+        assert!(asset_id_from_path(Path::new(".."), |_| false).is_err());
+    }
+
+    #[test]
+    fn test_drop_point_backs_off_for_larger_bounds() {
+        let camera = camera();
+        let small = Aabb::new(-Vec3::splat(0.5), Vec3::splat(0.5));
+        let large = Aabb::new(-Vec3::splat(20.0), Vec3::splat(20.0));
+
+        let view = view_direction(&camera);
+        let small_distance = (drop_point(&camera, &small) - camera.eye).dot(view);
+        let large_distance = (drop_point(&camera, &large) - camera.eye).dot(view);
+
+        assert!(small_distance >= MIN_DROP_DISTANCE - 1e-5);
+        assert!(large_distance > small_distance);
+    }
+
+    #[test]
+    fn test_focus_camera_on_centers_bounds_in_view() {
+        let mut camera = camera();
+        let view_before = view_direction(&camera);
+        let bounds = Aabb::new(Vec3::new(9.0, -1.0, -1.0), Vec3::new(11.0, 1.0, 1.0));
+
+        focus_camera_on(&mut camera, &bounds).unwrap();
+
+        assert_eq!(camera.target, bounds.center());
+        assert!(view_direction(&camera).abs_diff_eq(view_before, 1e-5));
+        assert!(camera.eye.distance(bounds.center()) >= MIN_DROP_DISTANCE - 1e-5);
+    }
+
+    #[test]
+    fn test_dropped_files_import_in_front_of_camera() {
+        if !gpu_tests_enabled("test_dropped_files_import_in_front_of_camera") {
+            return;
+        }
+
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut asset_handler = AssetHandler::new(
+            ctx.device.clone(),
+            ctx.queue.clone(),
+            ctx.model_binding_mode,
+            ctx.model_bind_group_layout.clone(),
+            ctx.material_bind_group_layout.clone(),
+            ctx.fallback_textures(),
+        );
+        let camera = camera();
+        let fixtures = AssetRoot::new().join("gltf").unwrap();
+
+        let mut imported = Vec::new();
+        for file in ["Cube.gltf", "monkey.glb", "Cube.gltf"] {
+            imported.extend(
+                pollster::block_on(import_dropped_file(
+                    &mut asset_handler,
+                    &camera,
+                    &fixtures.join(file),
+                ))
+                .unwrap(),
+            );
+        }
+        assert!(asset_handler.contains("Cube_0"));
+        assert!(asset_handler.contains("Cube_2_0"));
+
+        let missing = pollster::block_on(import_dropped_file(
+            &mut asset_handler,
+            &camera,
+            &fixtures.join("missing.gltf"),
+        ));
+        assert!(missing.is_err());
+
+        let view = view_direction(&camera);
+        for mesh_id in imported {
+            let mesh = asset_handler.get(mesh_id).unwrap();
+            let position = mesh.transform.read_shared(|transform| transform.position);
+            assert!((position - camera.eye).dot(view) > 0.0);
+            assert_eq!(mesh.light_type, LightType::LIGHT);
+        }
+    }
+
+    #[test]
+    fn test_models_are_laid_out_side_by_side_and_framed() {
+        if !gpu_tests_enabled("test_models_are_laid_out_side_by_side_and_framed") {
+            return;
+        }
+
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut asset_handler = AssetHandler::new(
+            ctx.device.clone(),
+            ctx.queue.clone(),
+            ctx.model_binding_mode,
+            ctx.model_bind_group_layout.clone(),
+            ctx.material_bind_group_layout.clone(),
+            ctx.fallback_textures(),
+        );
+        let mut camera = camera();
+        let fixtures = AssetRoot::new().join("gltf").unwrap();
+        let paths = [fixtures.join("Cube.gltf"), fixtures.join("Cube.gltf")];
+
+        let imported =
+            pollster::block_on(import_models(&mut asset_handler, &mut camera, &paths)).unwrap();
+
+        let first = meshes_by_id(&asset_handler, &imported[..1]).unwrap();
+        let second = meshes_by_id(&asset_handler, &imported[1..]).unwrap();
+        let first_bounds = world_bounds(&first).unwrap();
+        let second_bounds = world_bounds(&second).unwrap();
+        assert!(second_bounds.min.x >= first_bounds.max.x);
+        let all = meshes_by_id(&asset_handler, &imported).unwrap();
+        let center = world_bounds(&all).unwrap().center();
+        assert!(center.abs_diff_eq(Vec3::ZERO, 1e-4));
+        assert!(camera.target.abs_diff_eq(Vec3::ZERO, 1e-4));
+    }
+}
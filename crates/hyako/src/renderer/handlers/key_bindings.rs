@@ -4,7 +4,10 @@ use log::{trace, warn};
 use smallvec::{SmallVec, smallvec};
 use winit::keyboard::KeyCode;
 
-use crate::renderer::actions::{Action, CameraActions};
+use crate::renderer::actions::{
+    Action, AppActions, CameraActions, GizmoActions, NudgeActions, SelectionActions,
+    SimulationActions,
+};
 
 const MAX_KEY_BIND_COUNT: usize = 5;
 
@@ -68,9 +71,106 @@ impl KeyBindingMap {
             ),
             Action::Camera(CameraActions::SlowModifier),
         );
+        binding.insert(Self::default_quit_binding(), Action::App(AppActions::Quit));
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::F11]),
+            Action::App(AppActions::ToggleFullscreen),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::F1]),
+            Action::App(AppActions::ToggleStatsOverlay),
+        );
+        binding.insert(
+            Self::default_screenshot_binding(),
+            Action::App(AppActions::Screenshot),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::Digit1]),
+            Action::Gizmo(GizmoActions::TranslateMode),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::Digit2]),
+            Action::Gizmo(GizmoActions::RotateMode),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::Digit3]),
+            Action::Gizmo(GizmoActions::ScaleMode),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::Delete]),
+            Action::Selection(SelectionActions::Delete),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::KeyG]),
+            Action::Selection(SelectionActions::TogglePlacement),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::KeyM]),
+            Action::Selection(SelectionActions::CycleShadingSource),
+        );
+        binding.insert(
+            KeyBinding::new(smallvec![], smallvec![KeyCode::KeyP]),
+            Action::Simulation(SimulationActions::TogglePause),
+        );
+        for control in [KeyCode::ControlLeft, KeyCode::ControlRight] {
+            binding.insert(
+                KeyBinding::new(smallvec![control], smallvec![KeyCode::KeyD]),
+                Action::Selection(SelectionActions::Duplicate),
+            );
+            binding.insert(
+                KeyBinding::new(smallvec![control], smallvec![KeyCode::KeyN]),
+                Action::App(AppActions::OpenWindow),
+            );
+        }
+        for (key, action) in [
+            (KeyCode::ArrowLeft, NudgeActions::Left),
+            (KeyCode::ArrowRight, NudgeActions::Right),
+            (KeyCode::ArrowUp, NudgeActions::Forwards),
+            (KeyCode::ArrowDown, NudgeActions::Backwards),
+            (KeyCode::PageUp, NudgeActions::Up),
+            (KeyCode::PageDown, NudgeActions::Down),
+        ] {
+            for modifiers in [
+                smallvec![KeyCode::AltLeft],
+                smallvec![KeyCode::AltRight],
+                smallvec![KeyCode::AltLeft, KeyCode::ShiftLeft],
+                smallvec![KeyCode::AltLeft, KeyCode::ShiftRight],
+                smallvec![KeyCode::AltRight, KeyCode::ShiftLeft],
+                smallvec![KeyCode::AltRight, KeyCode::ShiftRight],
+            ] {
+                binding.insert(
+                    KeyBinding::new(modifiers, smallvec![key]),
+                    Action::Nudge(action),
+                );
+            }
+        }
         Self { binding }
     }
 
+    pub fn default_quit_binding() -> KeyBinding {
+        KeyBinding::new(smallvec![], smallvec![KeyCode::Escape])
+    }
+
+    pub fn default_screenshot_binding() -> KeyBinding {
+        KeyBinding::new(smallvec![], smallvec![KeyCode::F12])
+    }
+
+    /// Moves the quit action to `key_binding`, replacing whatever it was bound to before.
+    pub fn set_quit_binding(&mut self, key_binding: KeyBinding) {
+        self.rebind_action(Action::App(AppActions::Quit), key_binding);
+    }
+
+    /// Moves the screenshot action to `key_binding`, replacing whatever it was bound to before.
+    pub fn set_screenshot_binding(&mut self, key_binding: KeyBinding) {
+        self.rebind_action(Action::App(AppActions::Screenshot), key_binding);
+    }
+
+    fn rebind_action(&mut self, action: Action, key_binding: KeyBinding) {
+        self.binding
+            .retain(|_, bound_action| *bound_action != action);
+        self.add_binding(key_binding, action);
+    }
+
     pub fn add_binding(&mut self, key_bindings: KeyBinding, action: Action) {
         if self.get_binding(&key_bindings).is_some() {
             warn!("The binding is already in use!");
@@ -101,6 +201,7 @@ impl KeyBindingMap {
         pressed_modifiers: &HashSet<KeyCode>,
     ) -> Vec<Action> {
         let mut active_actions = Vec::new();
+        let mut chord_matched = false;
 
         for key in pressed_keys {
             let modifiers_vec: SmallVec<[KeyCode; MAX_KEY_BIND_COUNT]> =
@@ -111,6 +212,7 @@ impl KeyBindingMap {
 
             if let Some(action) = self.binding.get(&combined_binding) {
                 active_actions.push(*action);
+                chord_matched = true;
             } else {
                 let key_only_binding = KeyBinding::new(smallvec![], key_vec);
                 if let Some(action) = self.binding.get(&key_only_binding) {
@@ -119,7 +221,8 @@ impl KeyBindingMap {
             }
         }
 
-        if !pressed_modifiers.is_empty() {
+        // Modifiers that completed a chord do not also trigger their own binding.
+        if !pressed_modifiers.is_empty() && !chord_matched {
             let modifiers_vec: SmallVec<[KeyCode; MAX_KEY_BIND_COUNT]> =
                 pressed_modifiers.iter().cloned().collect();
             let modifier_binding = KeyBinding::new(modifiers_vec, smallvec![]);
@@ -274,4 +377,118 @@ mod tests {
         let actions = binding_map.resolve_active_actions(&pressed_keys, &pressed_modifiers);
         assert!(actions.contains(&action));
     }
+
+    #[test]
+    fn test_alt_arrow_returns_nudge_action_without_camera_actions() {
+        let binding_map = KeyBindingMap::initialize();
+        let pressed_keys = HashSet::from([KeyCode::ArrowUp]);
+        let pressed_modifiers = HashSet::from([KeyCode::AltLeft, KeyCode::ShiftLeft]);
+
+        let actions = binding_map.resolve_active_actions(&pressed_keys, &pressed_modifiers);
+
+        assert_eq!(actions, vec![Action::Nudge(NudgeActions::Forwards)]);
+    }
+
+    #[test]
+    fn test_arrow_without_alt_does_not_nudge() {
+        let binding_map = KeyBindingMap::initialize();
+        let pressed_keys = HashSet::from([KeyCode::PageUp]);
+
+        let actions = binding_map.resolve_active_actions(&pressed_keys, &HashSet::new());
+
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_ctrl_d_duplicates_without_camera_actions() {
+        let binding_map = KeyBindingMap::initialize();
+        let pressed_keys = HashSet::from([KeyCode::KeyD]);
+        let pressed_modifiers = HashSet::from([KeyCode::ControlLeft]);
+
+        let actions = binding_map.resolve_active_actions(&pressed_keys, &pressed_modifiers);
+
+        assert_eq!(
+            actions,
+            vec![Action::Selection(SelectionActions::Duplicate)]
+        );
+    }
+
+    #[test]
+    fn test_escape_resolves_to_quit() {
+        let binding_map = KeyBindingMap::initialize();
+        let pressed_keys = HashSet::from([KeyCode::Escape]);
+
+        let actions = binding_map.resolve_active_actions(&pressed_keys, &HashSet::new());
+
+        assert_eq!(actions, vec![Action::App(AppActions::Quit)]);
+    }
+
+    #[test]
+    fn test_set_quit_binding_replaces_default() {
+        let mut binding_map = KeyBindingMap::initialize();
+        let ctrl_q = KeyBinding::new(smallvec![KeyCode::ControlLeft], smallvec![KeyCode::KeyQ]);
+
+        binding_map.set_quit_binding(ctrl_q.clone());
+
+        assert_eq!(
+            binding_map.get_binding(&ctrl_q),
+            Some(&Action::App(AppActions::Quit))
+        );
+        assert!(
+            binding_map
+                .get_binding(&KeyBindingMap::default_quit_binding())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_f11_resolves_to_toggle_fullscreen() {
+        let binding_map = KeyBindingMap::initialize();
+        let pressed_keys = HashSet::from([KeyCode::F11]);
+
+        let actions = binding_map.resolve_active_actions(&pressed_keys, &HashSet::new());
+
+        assert_eq!(actions, vec![Action::App(AppActions::ToggleFullscreen)]);
+    }
+
+    #[test]
+    fn test_f1_resolves_to_toggle_stats_overlay() {
+        let binding_map = KeyBindingMap::initialize();
+        let pressed_keys = HashSet::from([KeyCode::F1]);
+
+        let actions = binding_map.resolve_active_actions(&pressed_keys, &HashSet::new());
+
+        assert_eq!(actions, vec![Action::App(AppActions::ToggleStatsOverlay)]);
+    }
+
+    #[test]
+    fn test_f12_takes_screenshot_and_can_be_rebound() {
+        let mut binding_map = KeyBindingMap::initialize();
+        let screenshot = Some(&Action::App(AppActions::Screenshot));
+        assert_eq!(
+            binding_map.get_binding(&KeyBindingMap::default_screenshot_binding()),
+            screenshot
+        );
+
+        let f9 = KeyBinding::new(smallvec![], smallvec![KeyCode::F9]);
+        binding_map.set_screenshot_binding(f9.clone());
+
+        assert_eq!(binding_map.get_binding(&f9), screenshot);
+        assert!(
+            binding_map
+                .get_binding(&KeyBindingMap::default_screenshot_binding())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_ctrl_n_opens_window() {
+        let binding_map = KeyBindingMap::initialize();
+        let ctrl_n = KeyBinding::new(smallvec![KeyCode::ControlRight], smallvec![KeyCode::KeyN]);
+
+        assert_eq!(
+            binding_map.get_binding(&ctrl_n),
+            Some(&Action::App(AppActions::OpenWindow))
+        );
+    }
 }
@@ -85,6 +85,10 @@ impl KeyboardHandler {
         events
     }
 
+    pub fn key_bindings_mut(&mut self) -> &mut KeyBindingMap {
+        &mut self.key_bindings
+    }
+
     pub fn get_pressed_keys(&self) -> &HashSet<KeyCode> {
         &self.pressed_keys
     }
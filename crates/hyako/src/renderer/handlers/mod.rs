@@ -1,10 +1,18 @@
+pub mod animation_handler;
 pub mod asset_handler;
 pub mod camera;
+pub mod file_drop;
 pub mod key_bindings;
 pub mod keyboard_handler;
 pub mod mouse_bindings;
 pub mod mouse_handler;
+pub mod nudge_handler;
+pub mod placement;
+pub mod pointer_handler;
 pub mod resource_handler;
+pub mod selection;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod source_watcher;
 
 use crate::renderer::actions::Action;
 
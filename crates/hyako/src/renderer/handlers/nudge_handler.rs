@@ -0,0 +1,195 @@
+use glam::Vec3;
+use hyakou_core::{SharedAccess, components::camera::camera::Camera};
+
+use crate::{
+    gpu::render_mesh::RenderMesh,
+    renderer::{actions::NudgeActions, handlers::selection::SelectionManager},
+};
+
+#[derive(Debug)]
+pub struct NudgeHandler {
+    step: f32,
+}
+
+impl Default for NudgeHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NudgeHandler {
+    pub const DEFAULT_STEP: f32 = 0.1;
+    pub const FAST_MULTIPLIER: f32 = 10.0;
+
+    pub fn new() -> Self {
+        Self {
+            step: Self::DEFAULT_STEP,
+        }
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.abs();
+    }
+
+    pub fn offset(&self, camera: &Camera, action: NudgeActions, fast: bool) -> Vec3 {
+        let (right, forward) = ground_axes(camera);
+        let direction = match action {
+            NudgeActions::Left => -right,
+            NudgeActions::Right => right,
+            NudgeActions::Forwards => forward,
+            NudgeActions::Backwards => -forward,
+            NudgeActions::Up => Vec3::Y,
+            NudgeActions::Down => Vec3::NEG_Y,
+        };
+        let step = if fast {
+            self.step * Self::FAST_MULTIPLIER
+        } else {
+            self.step
+        };
+        direction * step
+    }
+
+    /// Moves every selected mesh and returns the applied offset, or `None` when nothing was moved.
+    pub fn nudge<'a>(
+        &self,
+        camera: &Camera,
+        selection: &SelectionManager,
+        meshes: impl IntoIterator<Item = &'a RenderMesh>,
+        action: NudgeActions,
+        fast: bool,
+    ) -> Option<Vec3> {
+        if selection.is_empty() {
+            return None;
+        }
+        let offset = self.offset(camera, action, fast);
+        let moved = meshes
+            .into_iter()
+            .filter(|mesh| selection.is_selected(&mesh.id.0))
+            .filter(|mesh| {
+                mesh.transform
+                    .try_write_shared(|transform| transform.translate(offset))
+                    .is_ok()
+            })
+            .count();
+        (moved > 0).then_some(offset)
+    }
+}
+
+/// Camera right and forward flattened onto the ground plane, falling back to the world axes when
+/// the camera looks straight up or down.
+pub fn ground_axes(camera: &Camera) -> (Vec3, Vec3) {
+    let view = camera.target - camera.eye;
+    let flat = Vec3::new(view.x, 0.0, view.z);
+    let forward = if flat.length_squared() > f32::EPSILON {
+        flat.normalize()
+    } else {
+        Vec3::new(camera.up.x, 0.0, camera.up.z)
+            .try_normalize()
+            .map(|up| if view.y < 0.0 { up } else { -up })
+            .unwrap_or(Vec3::NEG_Z)
+    };
+    (forward.cross(Vec3::Y).normalize(), forward)
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::types::camera::{Pitch, Yaw};
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn camera_looking(eye: Vec3, target: Vec3) -> Camera {
+        Camera::new(
+            eye,
+            target,
+            Vec3::Y,
+            16.0 / 9.0,
+            45.0_f32.to_radians(),
+            0.1,
+            100.0,
+            Yaw::new(0.0),
+            Pitch::new(0.0),
+            20.0,
+            1.0,
+            0.5,
+        )
+    }
+
+    fn assert_vec_eq(actual: Vec3, expected: Vec3) {
+        assert!(
+            actual.abs_diff_eq(expected, EPSILON),
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_ground_axes_follow_camera_heading() {
+        let camera = camera_looking(Vec3::new(0.0, 5.0, 10.0), Vec3::ZERO);
+
+        let (right, forward) = ground_axes(&camera);
+
+        assert_vec_eq(forward, Vec3::NEG_Z);
+        assert_vec_eq(right, Vec3::X);
+    }
+
+    #[test]
+    fn test_ground_axes_ignore_camera_pitch() {
+        let camera = camera_looking(Vec3::new(10.0, 8.0, 0.0), Vec3::new(0.0, -3.0, 0.0));
+
+        let (right, forward) = ground_axes(&camera);
+
+        assert_vec_eq(forward, Vec3::NEG_X);
+        assert_vec_eq(right, Vec3::NEG_Z);
+        assert_eq!(forward.y, 0.0);
+    }
+
+    #[test]
+    fn test_ground_axes_fall_back_when_looking_straight_down() {
+        let mut camera = camera_looking(Vec3::new(0.0, 10.0, 0.0), Vec3::ZERO);
+        camera.up = Vec3::NEG_Z;
+
+        let (right, forward) = ground_axes(&camera);
+
+        assert_vec_eq(forward, Vec3::NEG_Z);
+        assert_vec_eq(right, Vec3::X);
+    }
+
+    #[test]
+    fn test_offset_uses_step_and_fast_multiplier() {
+        let camera = camera_looking(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+        let mut handler = NudgeHandler::new();
+
+        assert_vec_eq(
+            handler.offset(&camera, NudgeActions::Right, false),
+            Vec3::X * NudgeHandler::DEFAULT_STEP,
+        );
+        assert_vec_eq(
+            handler.offset(&camera, NudgeActions::Forwards, true),
+            Vec3::NEG_Z * NudgeHandler::DEFAULT_STEP * NudgeHandler::FAST_MULTIPLIER,
+        );
+
+        handler.set_step(0.5);
+        assert_vec_eq(
+            handler.offset(&camera, NudgeActions::Down, false),
+            Vec3::new(0.0, -0.5, 0.0),
+        );
+    }
+
+    #[test]
+    fn test_nudge_without_selection_does_nothing() {
+        let camera = camera_looking(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+        let handler = NudgeHandler::new();
+        let selection = SelectionManager::new();
+
+        assert!(
+            handler
+                .nudge(&camera, &selection, [], NudgeActions::Left, false)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use glam::Vec3;
+use hyakou_core::geometry::ray::Ray;
+
+/// What a placement click acts on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PlacementSource {
+    /// Moves the current selection onto the clicked point.
+    #[default]
+    Selection,
+    /// Spawns a copy of the loaded asset with this id at the clicked point.
+    Asset(String),
+}
+
+/// Click-to-place state: while active, left clicks resolve to a point on the ground plane instead
+/// of selecting.
+#[derive(Debug, Default)]
+pub struct PlacementHandler {
+    active: bool,
+    source: PlacementSource,
+    ground_height: f32,
+}
+
+impl PlacementHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    pub fn toggle(&mut self) -> bool {
+        self.active = !self.active;
+        self.active
+    }
+
+    pub fn source(&self) -> &PlacementSource {
+        &self.source
+    }
+
+    pub fn set_source(&mut self, source: PlacementSource) {
+        self.source = source;
+    }
+
+    pub fn ground_height(&self) -> f32 {
+        self.ground_height
+    }
+
+    pub fn set_ground_height(&mut self, ground_height: f32) {
+        self.ground_height = ground_height;
+    }
+
+    /// Where `ray` meets the horizontal ground plane, or `None` for rays running parallel to it or
+    /// pointing away from it.
+    pub fn ground_point(&self, ray: &Ray) -> Option<Vec3> {
+        ray.intersect_plane(Vec3::Y * self.ground_height, Vec3::Y)
+            .map(|distance| ray.at(distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    #[test]
+    fn test_ground_point_hits_plane_below_camera() {
+        let placement = PlacementHandler::new();
+        let ray = Ray::new(Vec3::new(1.0, 5.0, 5.0), Vec3::new(0.0, -1.0, -1.0));
+
+        let point = placement.ground_point(&ray).unwrap();
+
+        assert!((point - Vec3::new(1.0, 0.0, 0.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn test_ground_point_follows_ground_height() {
+        let mut placement = PlacementHandler::new();
+        placement.set_ground_height(2.0);
+        let ray = Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::NEG_Y);
+
+        let point = placement.ground_point(&ray).unwrap();
+
+        assert!((point - Vec3::new(0.0, 2.0, 0.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn test_ground_point_rejects_parallel_ray() {
+        let placement = PlacementHandler::new();
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 1.0));
+
+        assert!(placement.ground_point(&ray).is_none());
+    }
+
+    #[test]
+    fn test_ground_point_rejects_plane_behind_camera() {
+        let placement = PlacementHandler::new();
+        let looking_up = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, -1.0));
+        let below_ground = Ray::new(Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, -1.0, -1.0));
+
+        assert!(placement.ground_point(&looking_up).is_none());
+        assert!(placement.ground_point(&below_ground).is_none());
+    }
+
+    #[test]
+    fn test_toggle_flips_active_state() {
+        let mut placement = PlacementHandler::new();
+
+        assert!(placement.toggle());
+        assert!(!placement.toggle());
+        assert!(!placement.is_active());
+    }
+}
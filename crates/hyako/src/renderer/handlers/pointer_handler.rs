@@ -0,0 +1,365 @@
+use std::collections::BTreeSet;
+
+use hyakou_core::types::mouse_delta::MouseButton;
+use smallvec::{SmallVec, smallvec};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{Force, TouchPhase},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+    /// Reported by winit as touches, but handled exactly like a mouse with the left button held
+    /// while the pen is down.
+    Pen,
+}
+
+impl PointerKind {
+    /// winit only reports an altitude angle for styluses.
+    pub fn from_touch_force(force: Option<Force>) -> Self {
+        match force {
+            Some(Force::Calibrated {
+                altitude_angle: Some(_),
+                ..
+            }) => Self::Pen,
+            _ => Self::Touch,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerId {
+    Mouse,
+    Touch(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pointer {
+    pub id: PointerId,
+    pub kind: PointerKind,
+    /// Physical pixels, the same space as the surface size.
+    pub position: PhysicalPosition<f64>,
+    pub pressed_buttons: BTreeSet<MouseButton>,
+}
+
+impl Pointer {
+    fn new(id: PointerId, kind: PointerKind, position: PhysicalPosition<f64>) -> Self {
+        Self {
+            id,
+            kind,
+            position,
+            pressed_buttons: BTreeSet::new(),
+        }
+    }
+
+    pub fn is_pressed(&self, button: MouseButton) -> bool {
+        self.pressed_buttons.contains(&button)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerEvent {
+    Pressed {
+        pointer: Pointer,
+        button: MouseButton,
+    },
+    Moved {
+        pointer: Pointer,
+        dx: f64,
+        dy: f64,
+    },
+    Released {
+        pointer: Pointer,
+        button: MouseButton,
+    },
+    /// The platform took the pointer away, e.g. a touch turned into a browser scroll. Nothing that
+    /// started with it should complete.
+    Cancelled {
+        pointer: Pointer,
+    },
+}
+
+/// Folds winit's cursor, mouse button and touch streams into one pointer abstraction, so selection
+/// and camera drags treat a mouse, a finger and a pen the same way. A touch or pen contact acts as
+/// the left button; while one contact is active, further fingers are ignored.
+#[derive(Debug)]
+pub struct PointerHandler {
+    mouse: Pointer,
+    contact: Option<Pointer>,
+}
+
+impl PointerHandler {
+    pub fn new() -> Self {
+        Self {
+            mouse: Pointer::new(
+                PointerId::Mouse,
+                PointerKind::Mouse,
+                PhysicalPosition::new(0.0, 0.0),
+            ),
+            contact: None,
+        }
+    }
+
+    pub fn mouse(&self) -> &Pointer {
+        &self.mouse
+    }
+
+    pub fn contact(&self) -> Option<&Pointer> {
+        self.contact.as_ref()
+    }
+
+    pub fn cursor_moved(&mut self, position: PhysicalPosition<f64>) -> SmallVec<[PointerEvent; 2]> {
+        smallvec![move_pointer(&mut self.mouse, position)]
+    }
+
+    pub fn mouse_button(
+        &mut self,
+        button: MouseButton,
+        pressed: bool,
+    ) -> SmallVec<[PointerEvent; 2]> {
+        smallvec![press_pointer(&mut self.mouse, button, pressed)]
+    }
+
+    pub fn touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: PhysicalPosition<f64>,
+        kind: PointerKind,
+    ) -> SmallVec<[PointerEvent; 2]> {
+        let pointer_id = PointerId::Touch(id);
+        if phase == TouchPhase::Started {
+            if self.contact.is_some() {
+                return SmallVec::new();
+            }
+            let mut contact = Pointer::new(pointer_id, kind, position);
+            let pressed = press_pointer(&mut contact, MouseButton::Left, true);
+            self.contact = Some(contact);
+            return smallvec![pressed];
+        }
+
+        let Some(contact) = self
+            .contact
+            .as_mut()
+            .filter(|contact| contact.id == pointer_id)
+        else {
+            return SmallVec::new();
+        };
+        match phase {
+            TouchPhase::Started | TouchPhase::Moved => smallvec![move_pointer(contact, position)],
+            TouchPhase::Ended => {
+                let mut events = SmallVec::new();
+                if contact.position != position {
+                    events.push(move_pointer(contact, position));
+                }
+                events.push(press_pointer(contact, MouseButton::Left, false));
+                self.contact = None;
+                events
+            }
+            TouchPhase::Cancelled => {
+                contact.pressed_buttons.clear();
+                let pointer = contact.clone();
+                self.contact = None;
+                smallvec![PointerEvent::Cancelled { pointer }]
+            }
+        }
+    }
+}
+
+impl Default for PointerHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn move_pointer(pointer: &mut Pointer, position: PhysicalPosition<f64>) -> PointerEvent {
+    let dx = position.x - pointer.position.x;
+    let dy = position.y - pointer.position.y;
+    pointer.position = position;
+    PointerEvent::Moved {
+        pointer: pointer.clone(),
+        dx,
+        dy,
+    }
+}
+
+fn press_pointer(pointer: &mut Pointer, button: MouseButton, pressed: bool) -> PointerEvent {
+    if pressed {
+        pointer.pressed_buttons.insert(button);
+        PointerEvent::Pressed {
+            pointer: pointer.clone(),
+            button,
+        }
+    } else {
+        pointer.pressed_buttons.remove(&button);
+        PointerEvent::Released {
+            pointer: pointer.clone(),
+            button,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTACT_KINDS: [PointerKind; 2] = [PointerKind::Touch, PointerKind::Pen];
+
+    fn at(x: f64, y: f64) -> PhysicalPosition<f64> {
+        PhysicalPosition::new(x, y)
+    }
+
+    /// Buttons and positions only, so the same gesture can be compared across pointer kinds.
+    fn summary(events: &[PointerEvent]) -> Vec<(&'static str, Option<MouseButton>, (f64, f64))> {
+        events
+            .iter()
+            .map(|event| match event {
+                PointerEvent::Pressed { pointer, button } => {
+                    ("pressed", Some(*button), pointer.position.into())
+                }
+                PointerEvent::Moved { pointer, .. } => ("moved", None, pointer.position.into()),
+                PointerEvent::Released { pointer, button } => {
+                    ("released", Some(*button), pointer.position.into())
+                }
+                PointerEvent::Cancelled { pointer } => ("cancelled", None, pointer.position.into()),
+            })
+            .collect()
+    }
+
+    fn mouse_drag(handler: &mut PointerHandler) -> Vec<PointerEvent> {
+        let mut events = Vec::new();
+        events.extend(handler.cursor_moved(at(10.0, 20.0)));
+        events.extend(handler.mouse_button(MouseButton::Left, true));
+        events.extend(handler.cursor_moved(at(30.0, 25.0)));
+        events.extend(handler.mouse_button(MouseButton::Left, false));
+        events
+    }
+
+    fn contact_drag(handler: &mut PointerHandler, kind: PointerKind) -> Vec<PointerEvent> {
+        let mut events = Vec::new();
+        events.extend(handler.touch(7, TouchPhase::Started, at(10.0, 20.0), kind));
+        events.extend(handler.touch(7, TouchPhase::Moved, at(30.0, 25.0), kind));
+        events.extend(handler.touch(7, TouchPhase::Ended, at(30.0, 25.0), kind));
+        events
+    }
+
+    #[test]
+    fn test_contact_drag_matches_a_left_button_mouse_drag() {
+        let mouse_events = mouse_drag(&mut PointerHandler::new());
+
+        for kind in CONTACT_KINDS {
+            let contact_events = contact_drag(&mut PointerHandler::new(), kind);
+
+            // A mouse reports its position before the press, a contact with it.
+            assert_eq!(
+                summary(&contact_events),
+                summary(&mouse_events[1..]),
+                "{kind:?}"
+            );
+            assert!(contact_events.iter().all(
+                |event| matches!(event, PointerEvent::Pressed { pointer, .. }
+                        | PointerEvent::Moved { pointer, .. }
+                        | PointerEvent::Released { pointer, .. }
+                        if pointer.kind == kind && pointer.id == PointerId::Touch(7))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_moves_report_deltas_and_held_buttons() {
+        for kind in CONTACT_KINDS {
+            let mut handler = PointerHandler::new();
+            handler.touch(1, TouchPhase::Started, at(10.0, 10.0), kind);
+
+            let events = handler.touch(1, TouchPhase::Moved, at(13.0, 6.0), kind);
+
+            let [PointerEvent::Moved { pointer, dx, dy }] = events.as_slice() else {
+                panic!("expected a single move for {kind:?}, got {events:?}");
+            };
+            assert_eq!((*dx, *dy), (3.0, -4.0));
+            assert!(pointer.is_pressed(MouseButton::Left));
+        }
+    }
+
+    #[test]
+    fn test_lifting_at_a_new_position_moves_before_releasing() {
+        for kind in CONTACT_KINDS {
+            let mut handler = PointerHandler::new();
+            handler.touch(1, TouchPhase::Started, at(0.0, 0.0), kind);
+
+            let events = handler.touch(1, TouchPhase::Ended, at(2.0, 0.0), kind);
+
+            assert_eq!(
+                summary(&events),
+                vec![
+                    ("moved", None, (2.0, 0.0)),
+                    ("released", Some(MouseButton::Left), (2.0, 0.0)),
+                ]
+            );
+            assert!(handler.contact().is_none());
+        }
+    }
+
+    #[test]
+    fn test_additional_contacts_are_ignored_until_the_first_one_lifts() {
+        let mut handler = PointerHandler::new();
+        handler.touch(1, TouchPhase::Started, at(0.0, 0.0), PointerKind::Touch);
+
+        assert!(
+            handler
+                .touch(2, TouchPhase::Started, at(50.0, 50.0), PointerKind::Touch)
+                .is_empty()
+        );
+        assert!(
+            handler
+                .touch(2, TouchPhase::Moved, at(60.0, 50.0), PointerKind::Touch)
+                .is_empty()
+        );
+
+        handler.touch(1, TouchPhase::Ended, at(0.0, 0.0), PointerKind::Touch);
+        let events = handler.touch(3, TouchPhase::Started, at(5.0, 5.0), PointerKind::Touch);
+        assert_eq!(
+            summary(&events),
+            vec![("pressed", Some(MouseButton::Left), (5.0, 5.0))]
+        );
+    }
+
+    #[test]
+    fn test_cancelled_contact_releases_nothing() {
+        for kind in CONTACT_KINDS {
+            let mut handler = PointerHandler::new();
+            handler.touch(4, TouchPhase::Started, at(1.0, 1.0), kind);
+
+            let events = handler.touch(4, TouchPhase::Cancelled, at(1.0, 1.0), kind);
+
+            let [PointerEvent::Cancelled { pointer }] = events.as_slice() else {
+                panic!("expected a cancel for {kind:?}, got {events:?}");
+            };
+            assert!(pointer.pressed_buttons.is_empty());
+            assert!(handler.contact().is_none());
+        }
+    }
+
+    #[test]
+    fn test_styluses_are_detected_by_their_altitude_angle() {
+        let pen = Force::Calibrated {
+            force: 0.5,
+            max_possible_force: 1.0,
+            altitude_angle: Some(1.2),
+        };
+        let finger = Force::Calibrated {
+            force: 0.5,
+            max_possible_force: 1.0,
+            altitude_angle: None,
+        };
+
+        assert_eq!(PointerKind::from_touch_force(Some(pen)), PointerKind::Pen);
+        assert_eq!(
+            PointerKind::from_touch_force(Some(finger)),
+            PointerKind::Touch
+        );
+        assert_eq!(PointerKind::from_touch_force(None), PointerKind::Touch);
+    }
+}
@@ -1,18 +1,104 @@
 use std::collections::HashMap;
 
-use anyhow::Result;
 use wgpu::BindGroup;
 
 use hyakou_core::types::ids::UniformResourceId;
 
-#[derive(Default)]
-pub struct ResourceHandler {
-    resource_map: HashMap<String, BindGroup>,
+/// Bind groups shared by every pass, keyed by the id of the uniform resource they bind, so pass
+/// recording fetches them by id instead of through loose renderer fields.
+pub struct ResourceHandler<R = BindGroup> {
+    resource_map: HashMap<String, R>,
 }
 
-impl ResourceHandler {
-    pub fn insert(&mut self, id: Box<dyn UniformResourceId>, bind_group: BindGroup) -> Result<()> {
-        self.resource_map.insert(id.get().to_owned(), bind_group);
-        Ok(())
+impl<R> ResourceHandler<R> {
+    pub fn new() -> Self {
+        Self {
+            resource_map: HashMap::new(),
+        }
+    }
+
+    /// Stores `resource` under `id` and returns whatever was stored there before.
+    pub fn insert(&mut self, id: &dyn UniformResourceId, resource: R) -> Option<R> {
+        self.resource_map.insert(id.get().to_owned(), resource)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&R> {
+        self.resource_map.get(id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<R> {
+        self.resource_map.remove(id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.resource_map.contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.resource_map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resource_map.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &R)> {
+        self.resource_map
+            .iter()
+            .map(|(id, resource)| (id.as_str(), resource))
+    }
+}
+
+impl<R> Default for ResourceHandler<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::types::ids::UniformBufferId;
+
+    use super::*;
+
+    fn id(value: &str) -> UniformBufferId {
+        UniformBufferId::new(value.to_string())
+    }
+
+    #[test]
+    fn test_inserted_resources_are_found_by_id() {
+        let mut resources = ResourceHandler::new();
+
+        assert_eq!(resources.insert(&id("Camera"), 1), None);
+        assert_eq!(resources.insert(&id("Light"), 2), None);
+
+        assert_eq!(resources.get("Camera"), Some(&1));
+        assert!(resources.contains("Light"));
+        assert!(!resources.contains("Shadow"));
+        assert_eq!(resources.len(), 2);
+
+        let mut entries = resources.iter().collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, vec![("Camera", &1), ("Light", &2)]);
+    }
+
+    #[test]
+    fn test_duplicate_insert_replaces_and_returns_the_old_resource() {
+        let mut resources = ResourceHandler::new();
+        resources.insert(&id("Camera"), 1);
+
+        assert_eq!(resources.insert(&id("Camera"), 5), Some(1));
+        assert_eq!(resources.get("Camera"), Some(&5));
+        assert_eq!(resources.len(), 1);
+    }
+
+    #[test]
+    fn test_removed_resources_are_gone() {
+        let mut resources = ResourceHandler::new();
+        resources.insert(&id("Light"), 2);
+
+        assert_eq!(resources.remove("Light"), Some(2));
+        assert_eq!(resources.remove("Light"), None);
+        assert!(resources.is_empty());
     }
 }
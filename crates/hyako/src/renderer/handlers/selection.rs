@@ -0,0 +1,410 @@
+use hyakou_core::geometry::ray::Ray;
+
+use crate::gpu::render_mesh::{MeshHit, RenderMesh};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionEvent {
+    Changed {
+        primary: String,
+        selected: Vec<String>,
+    },
+    Cleared,
+}
+
+/// How a click finds the mesh under the cursor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PickingBackend {
+    /// Intersects a ray with the CPU copy of every mesh; answers immediately.
+    #[default]
+    Raycast,
+    /// Reads the mesh id under the cursor back from an id render target, a frame later. Does not
+    /// need CPU geometry and stays fast for dense meshes.
+    GpuId,
+}
+
+/// Ordered set of selected asset ids. The most recently added id is the primary selection, the
+/// rest are secondary.
+#[derive(Debug, Default)]
+pub struct SelectionManager {
+    selected: Vec<String>,
+    pending_events: Vec<SelectionEvent>,
+    backend: PickingBackend,
+}
+
+impl SelectionManager {
+    pub fn new() -> Self {
+        Self {
+            selected: Vec::new(),
+            pending_events: Vec::new(),
+            backend: PickingBackend::default(),
+        }
+    }
+
+    pub fn backend(&self) -> PickingBackend {
+        self.backend
+    }
+
+    pub fn set_backend(&mut self, backend: PickingBackend) {
+        self.backend = backend;
+    }
+
+    /// The primary selection.
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.last().map(String::as_str)
+    }
+
+    pub fn primary(&self) -> Option<&str> {
+        self.selected()
+    }
+
+    pub fn secondary(&self) -> impl Iterator<Item = &str> {
+        let secondary_count = self.selected.len().saturating_sub(1);
+        self.selected[..secondary_count].iter().map(String::as_str)
+    }
+
+    /// All selected ids in selection order, ending with the primary.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.selected.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    pub fn is_selected(&self, id: &str) -> bool {
+        self.selected.iter().any(|selected| selected == id)
+    }
+
+    /// Replaces the selection with `id`.
+    pub fn select(&mut self, id: String) {
+        self.select_all([id]);
+    }
+
+    /// Replaces the selection with `ids`; the last one becomes primary.
+    pub fn select_all(&mut self, ids: impl IntoIterator<Item = String>) {
+        let mut selected: Vec<String> = Vec::new();
+        for id in ids {
+            selected.retain(|existing| existing != &id);
+            selected.push(id);
+        }
+        self.replace(selected);
+    }
+
+    /// Adds `id` as the new primary, or removes it when it is already selected.
+    pub fn toggle(&mut self, id: String) {
+        let mut selected = self.selected.clone();
+        if selected.contains(&id) {
+            selected.retain(|existing| existing != &id);
+        } else {
+            selected.push(id);
+        }
+        self.replace(selected);
+    }
+
+    pub fn clear(&mut self) {
+        self.replace(Vec::new());
+    }
+
+    /// Replaces the selection with the closest mesh hit by `ray`, clearing it when nothing is hit.
+    pub fn select_closest<'a>(
+        &mut self,
+        ray: &Ray,
+        meshes: impl IntoIterator<Item = &'a RenderMesh>,
+    ) -> Option<&str> {
+        let hit = Self::pick_closest(ray, meshes).map(|hit| hit.mesh_id.0);
+        self.apply_pick(hit, false)
+    }
+
+    /// Toggles the closest mesh hit by `ray`; missing everything keeps the selection as is.
+    pub fn toggle_closest<'a>(
+        &mut self,
+        ray: &Ray,
+        meshes: impl IntoIterator<Item = &'a RenderMesh>,
+    ) -> Option<&str> {
+        let hit = Self::pick_closest(ray, meshes).map(|hit| hit.mesh_id.0);
+        self.apply_pick(hit, true)
+    }
+
+    /// Applies a click that hit `id`, whichever backend found it. A plain click replaces the
+    /// selection or clears it on a miss; an additive one toggles the hit and ignores misses.
+    pub fn apply_pick(&mut self, id: Option<String>, additive: bool) -> Option<&str> {
+        match (id, additive) {
+            (Some(id), false) => self.select(id),
+            (Some(id), true) => self.toggle(id),
+            (None, false) => self.clear(),
+            (None, true) => {}
+        }
+        self.selected()
+    }
+
+    pub fn pick_closest<'a>(
+        ray: &Ray,
+        meshes: impl IntoIterator<Item = &'a RenderMesh>,
+    ) -> Option<MeshHit> {
+        meshes
+            .into_iter()
+            .filter_map(|mesh| mesh.raycast(ray))
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    pub fn retain_loaded(&mut self, is_loaded: impl Fn(&str) -> bool) {
+        if self.selected.iter().all(|id| is_loaded(id)) {
+            return;
+        }
+
+        let selected = self
+            .selected
+            .iter()
+            .filter(|id| is_loaded(id))
+            .cloned()
+            .collect();
+        self.replace(selected);
+    }
+
+    /// Events queued since the last call, oldest first.
+    pub fn take_events(&mut self) -> Vec<SelectionEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    fn replace(&mut self, selected: Vec<String>) {
+        if selected == self.selected {
+            return;
+        }
+
+        self.selected = selected;
+        let event = match self.selected.last() {
+            Some(primary) => SelectionEvent::Changed {
+                primary: primary.clone(),
+                selected: self.selected.clone(),
+            },
+            None => SelectionEvent::Cleared,
+        };
+        self.pending_events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use glam::Vec3;
+    use hyakou_core::{
+        SharedAccess,
+        components::{LightType, camera::camera::Camera},
+        types::camera::{Pitch, Yaw},
+    };
+    use winit::dpi::PhysicalPosition;
+
+    use super::*;
+    use crate::renderer::{
+        handlers::asset_handler::AssetHandler, renderer_context::RenderContext,
+        test_harness::gpu_tests_enabled, util::asset_root::AssetRoot,
+        wrappers::MockSurfaceProvider,
+    };
+
+    fn create_test_camera(ctx: &RenderContext) -> Camera {
+        Camera::new(
+            Vec3::new(0.0, 0.0, 15.0),
+            Vec3::ZERO,
+            Vec3::Y,
+            Camera::aspect_ratio_from_size(ctx.size),
+            45.0_f32.to_radians(),
+            0.1,
+            1000.0,
+            Yaw::new(-PI / 2.0),
+            Pitch::new(0.0),
+            20.0,
+            0.001,
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_new_manager_has_no_selection() {
+        let manager = SelectionManager::new();
+
+        assert!(manager.selected().is_none());
+    }
+
+    #[test]
+    fn test_select_replaces_previous_selection() {
+        let mut manager = SelectionManager::new();
+
+        manager.select("Cube_0".to_string());
+        manager.select("Suzanne_0".to_string());
+
+        assert_eq!(manager.selected(), Some("Suzanne_0"));
+        assert!(!manager.is_selected("Cube_0"));
+    }
+
+    #[test]
+    fn test_select_closest_in_empty_scene_clears_selection() {
+        let mut manager = SelectionManager::new();
+        manager.select("Cube_0".to_string());
+
+        let selected = manager.select_closest(&Ray::new(Vec3::ZERO, Vec3::NEG_Z), []);
+
+        assert!(selected.is_none());
+        assert!(manager.selected().is_none());
+    }
+
+    #[test]
+    fn test_retain_loaded_clears_removed_asset() {
+        let mut manager = SelectionManager::new();
+        manager.select("Cube_0".to_string());
+
+        manager.retain_loaded(|id| id == "Cube_0");
+        assert_eq!(manager.selected(), Some("Cube_0"));
+
+        manager.retain_loaded(|_| false);
+        assert!(manager.selected().is_none());
+    }
+
+    #[test]
+    fn test_toggle_adds_and_removes_members() {
+        let mut manager = SelectionManager::new();
+
+        manager.select("Cube_0".to_string());
+        manager.toggle("Suzanne_0".to_string());
+        manager.toggle("Cone_0".to_string());
+
+        assert_eq!(manager.primary(), Some("Cone_0"));
+        assert_eq!(
+            manager.secondary().collect::<Vec<_>>(),
+            vec!["Cube_0", "Suzanne_0"]
+        );
+
+        manager.toggle("Suzanne_0".to_string());
+        assert!(!manager.is_selected("Suzanne_0"));
+        assert_eq!(manager.len(), 2);
+
+        manager.toggle("Cone_0".to_string());
+        assert_eq!(manager.primary(), Some("Cube_0"));
+        assert_eq!(manager.secondary().count(), 0);
+    }
+
+    #[test]
+    fn test_select_replaces_whole_set() {
+        let mut manager = SelectionManager::new();
+        manager.select_all(["Cube_0".to_string(), "Suzanne_0".to_string()]);
+
+        manager.select("Cone_0".to_string());
+
+        assert_eq!(manager.iter().collect::<Vec<_>>(), vec!["Cone_0"]);
+    }
+
+    #[test]
+    fn test_toggle_closest_miss_keeps_selection() {
+        let mut manager = SelectionManager::new();
+        manager.select_all(["Cube_0".to_string(), "Suzanne_0".to_string()]);
+
+        manager.toggle_closest(&Ray::new(Vec3::ZERO, Vec3::NEG_Z), []);
+
+        assert_eq!(manager.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_pick_matches_click_semantics() {
+        let mut manager = SelectionManager::new();
+
+        manager.apply_pick(Some("Cube_0".to_string()), false);
+        manager.apply_pick(Some("Suzanne_0".to_string()), true);
+        assert_eq!(manager.len(), 2);
+
+        manager.apply_pick(None, true);
+        assert_eq!(manager.len(), 2);
+        manager.apply_pick(Some("Suzanne_0".to_string()), true);
+        assert_eq!(manager.iter().collect::<Vec<_>>(), vec!["Cube_0"]);
+
+        assert!(manager.apply_pick(None, false).is_none());
+    }
+
+    #[test]
+    fn test_changes_queue_selection_events() {
+        let mut manager = SelectionManager::new();
+
+        manager.select("Cube_0".to_string());
+        manager.select("Cube_0".to_string());
+        manager.toggle("Suzanne_0".to_string());
+        manager.clear();
+        manager.clear();
+
+        assert_eq!(
+            manager.take_events(),
+            vec![
+                SelectionEvent::Changed {
+                    primary: "Cube_0".to_string(),
+                    selected: vec!["Cube_0".to_string()],
+                },
+                SelectionEvent::Changed {
+                    primary: "Suzanne_0".to_string(),
+                    selected: vec!["Cube_0".to_string(), "Suzanne_0".to_string()],
+                },
+                SelectionEvent::Cleared,
+            ]
+        );
+        assert!(manager.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_click_selects_cube_and_empty_space_clears() {
+        if !gpu_tests_enabled("test_click_selects_cube_and_empty_space_clears") {
+            return;
+        }
+
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut asset_handler = AssetHandler::new(
+            ctx.device.clone(),
+            ctx.queue.clone(),
+            ctx.model_binding_mode,
+            ctx.model_bind_group_layout.clone(),
+            ctx.material_bind_group_layout.clone(),
+            ctx.fallback_textures(),
+        );
+        let cube = pollster::block_on(asset_handler.add_from_path(
+            "Cube".to_string(),
+            LightType::NO_LIGHT,
+            AssetRoot::new().join("gltf/Cube.gltf").unwrap().as_path(),
+        ))
+        .unwrap()
+        .remove(0);
+        cube.transform
+            .write_shared(|t| t.translate(Vec3::new(3.0, 0.0, 0.0)));
+
+        let camera = create_test_camera(&ctx);
+        let cube_ndc = camera
+            .build_view_proj_matrix()
+            .project_point3(Vec3::new(3.0, 0.0, 0.0));
+        let click = PhysicalPosition::new(
+            ((cube_ndc.x + 1.0) * 0.5 * ctx.size.width as f32) as f64,
+            ((1.0 - cube_ndc.y) * 0.5 * ctx.size.height as f32) as f64,
+        );
+
+        let mut manager = SelectionManager::new();
+        let ray = camera.screen_to_ray(click, ctx.size).unwrap();
+        let selected = manager
+            .select_closest(
+                &ray,
+                asset_handler
+                    .get_all_visible_assets()
+                    .map(|mesh| mesh.as_ref()),
+            )
+            .map(str::to_string);
+        assert_eq!(selected.as_deref(), Some(cube.id.0.as_str()));
+
+        let ray = camera
+            .screen_to_ray(PhysicalPosition::new(0.0, 0.0), ctx.size)
+            .unwrap();
+        manager.select_closest(
+            &ray,
+            asset_handler
+                .get_all_visible_assets()
+                .map(|mesh| mesh.as_ref()),
+        );
+        assert!(manager.selected().is_none());
+    }
+}
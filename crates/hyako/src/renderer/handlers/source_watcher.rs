@@ -0,0 +1,159 @@
+//! Notices when the files assets were loaded from change on disk by polling their modification
+//! times, so edited models can be reloaded without restarting.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
+
+#[derive(Debug)]
+pub struct SourceWatcher {
+    interval: Duration,
+    last_poll: Option<Instant>,
+    modified: HashMap<String, SystemTime>,
+}
+
+impl SourceWatcher {
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_poll: None,
+            modified: HashMap::new(),
+        }
+    }
+
+    /// Ids among `sources` whose file changed since the previous poll. A file seen for the first
+    /// time is only recorded, and a file that cannot be read right now, e.g. halfway through being
+    /// saved, is checked again next time. Polls at most once per interval; calls in between
+    /// report nothing.
+    pub fn poll<'a>(
+        &mut self,
+        now: Instant,
+        sources: impl IntoIterator<Item = (&'a str, &'a Path)>,
+    ) -> Vec<String> {
+        if self
+            .last_poll
+            .is_some_and(|last_poll| now.duration_since(last_poll) < self.interval)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+
+        let mut changed = Vec::new();
+        let mut modified = HashMap::new();
+        for (id, path) in sources {
+            let Ok(current) = std::fs::metadata(path).and_then(|metadata| metadata.modified())
+            else {
+                if let Some(&previous) = self.modified.get(id) {
+                    modified.insert(id.to_string(), previous);
+                }
+                continue;
+            };
+            if self
+                .modified
+                .get(id)
+                .is_some_and(|&previous| previous != current)
+            {
+                changed.push(id.to_string());
+            }
+            modified.insert(id.to_string(), current);
+        }
+        self.modified = modified;
+        changed
+    }
+}
+
+impl Default for SourceWatcher {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, path::PathBuf};
+
+    use super::*;
+
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "hyako_source_watcher_{}_{name}",
+                std::process::id()
+            ));
+            std::fs::write(&path, b"{}").unwrap();
+            Self(path)
+        }
+
+        fn touch(&self, modified: SystemTime) {
+            File::options()
+                .write(true)
+                .open(&self.0)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_reports_files_modified_after_they_were_first_seen() {
+        let file = TempFile::new("modified.gltf");
+        let other = TempFile::new("untouched.gltf");
+        let sources = || [("Model", file.0.as_path()), ("Other", other.0.as_path())];
+        let mut watcher = SourceWatcher::new(Duration::ZERO);
+        let start = Instant::now();
+
+        assert!(watcher.poll(start, sources()).is_empty());
+        file.touch(SystemTime::now() + Duration::from_secs(5));
+
+        assert_eq!(watcher.poll(start, sources()), ["Model"]);
+        assert!(watcher.poll(start, sources()).is_empty());
+    }
+
+    #[test]
+    fn test_polls_at_most_once_per_interval() {
+        let file = TempFile::new("interval.gltf");
+        let sources = || [("Model", file.0.as_path())];
+        let mut watcher = SourceWatcher::new(Duration::from_secs(1));
+        let start = Instant::now();
+        watcher.poll(start, sources());
+        file.touch(SystemTime::now() + Duration::from_secs(5));
+
+        assert!(
+            watcher
+                .poll(start + Duration::from_millis(500), sources())
+                .is_empty()
+        );
+        assert_eq!(
+            watcher.poll(start + Duration::from_secs(1), sources()),
+            ["Model"]
+        );
+    }
+
+    #[test]
+    fn test_unreadable_files_are_retried_without_losing_their_time() {
+        let file = TempFile::new("vanishing.gltf");
+        let path = file.0.clone();
+        let mut watcher = SourceWatcher::new(Duration::ZERO);
+        let start = Instant::now();
+        watcher.poll(start, [("Model", path.as_path())]);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.poll(start, [("Model", path.as_path())]).is_empty());
+
+        std::fs::write(&path, b"{}").unwrap();
+        file.touch(SystemTime::now() + Duration::from_secs(5));
+        assert_eq!(watcher.poll(start, [("Model", path.as_path())]), ["Model"]);
+    }
+}
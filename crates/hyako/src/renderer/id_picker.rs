@@ -0,0 +1,298 @@
+use bytemuck::bytes_of;
+use glam::Mat4;
+use hyakou_core::{
+    shared,
+    traits::BindGroupProvider,
+    types::{Size, handle::Handle, ids::UniformBufferId, transform::Transform},
+};
+use wgpu::{
+    BindGroup, BindGroupLayout, Device, Extent3d, Operations, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPipeline, TextureAspect,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
+    TextureViewDescriptor, include_wgsl,
+};
+use winit::dpi::PhysicalPosition;
+
+use crate::{
+    gpu::{
+        buffers::{picking::PickingUniform, uniform::UniformBuffer},
+        render_mesh::RenderMesh,
+        render_pipeline::create_picking_render_pipeline,
+        texture::Texture,
+    },
+    renderer::{
+        frame::FrameTarget, renderer_context::RenderContext, texel_readback::TexelReadback,
+    },
+};
+
+/// A click waiting for the id under `texel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickRequest {
+    pub texel: [u32; 2],
+    pub additive: bool,
+}
+
+/// The mesh found under a [`PickRequest`], `None` for the background or a mesh removed since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickResult {
+    pub handle: Option<Handle<RenderMesh>>,
+    pub additive: bool,
+}
+
+struct IdTarget {
+    texture: wgpu::Texture,
+    view: TextureView,
+    depth: Texture,
+    size: [u32; 2],
+}
+
+/// GPU picking: renders every visible mesh's position in the frame's mesh list into an `R32Uint`
+/// target, restricted by a scissor to the clicked texel, and reads that texel back. The answer
+/// arrives a frame later through [`Self::poll`].
+pub struct IdPicker {
+    pipeline: RenderPipeline,
+    picking_bind_group_layout: BindGroupLayout,
+    slots: Vec<(UniformBuffer, BindGroup)>,
+    target: Option<IdTarget>,
+    /// Carries the frame's mesh list, so the id decodes against the meshes that were drawn.
+    readback: TexelReadback<(PickRequest, Vec<Handle<RenderMesh>>)>,
+    queued: Option<PickRequest>,
+}
+
+impl IdPicker {
+    const TARGET_LABEL: &str = "Picking Id Texture";
+    const DEPTH_LABEL: &str = "Picking Depth Texture";
+
+    pub fn new(ctx: &RenderContext) -> Self {
+        let picking_bind_group_layout = PickingUniform::bind_group_layout(&ctx.device);
+        let pipeline_layout = ctx
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Picking Pipeline Layout"),
+                bind_group_layouts: &[
+                    Some(&ctx.camera_bind_group_layout),
+                    Some(&picking_bind_group_layout),
+                ],
+                immediate_size: 0,
+            });
+        let pipeline = create_picking_render_pipeline(
+            &ctx.device,
+            "picking render pass",
+            &pipeline_layout,
+            ctx.device
+                .create_shader_module(include_wgsl!("../../assets/picking.wgsl")),
+            Some(Texture::DEPTH_FORMAT),
+        );
+        Self {
+            pipeline,
+            picking_bind_group_layout,
+            slots: Vec::new(),
+            target: None,
+            readback: TexelReadback::new(&ctx.device, "Picking Readback Buffer"),
+            queued: None,
+        }
+    }
+
+    /// Queues a pick for the next frame. A click that arrives while an earlier one is still in
+    /// flight replaces any other queued click.
+    pub fn request(&mut self, request: PickRequest) {
+        self.queued = Some(request);
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.queued.is_some() || !self.readback.is_idle()
+    }
+
+    /// Records the picking pass for the queued click, if any, into the frame's encoder.
+    pub fn record<'a>(
+        &mut self,
+        device: &Device,
+        target: &mut FrameTarget<'_>,
+        camera_bind_group: &BindGroup,
+        meshes: impl IntoIterator<Item = (Handle<RenderMesh>, &'a RenderMesh)>,
+    ) {
+        if !self.readback.is_idle() {
+            return;
+        }
+        let Some(request) = self.queued.take() else {
+            return;
+        };
+        let [x, y] = request.texel;
+        if x >= target.size_in_pixels[0] || y >= target.size_in_pixels[1] {
+            return;
+        }
+
+        let (handles, meshes): (Vec<_>, Vec<_>) = meshes.into_iter().unzip();
+        while self.slots.len() < meshes.len() {
+            let slot = self.create_slot(device);
+            self.slots.push(slot);
+        }
+        for (index, (mesh, (uniform_buffer, _))) in meshes.iter().zip(&self.slots).enumerate() {
+            let picking_uniform = PickingUniform::new(mesh.model_matrix(), object_id(index));
+            target
+                .queue
+                .write_buffer(uniform_buffer, 0, bytes_of(&picking_uniform));
+        }
+
+        let id_target = Self::ensure_target(&mut self.target, device, target.size_in_pixels);
+        {
+            let mut render_pass = target.encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("Picking Render Pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &id_target.view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                multiview_mask: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &id_target.depth.view,
+                    depth_ops: Some(Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_scissor_rect(x, y, 1, 1);
+            render_pass.set_bind_group(0, camera_bind_group, &[]);
+            for (mesh, (_, bind_group)) in meshes.iter().zip(&self.slots) {
+                render_pass.set_bind_group(1, bind_group, &[]);
+                render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                render_pass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+                render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+            }
+        }
+
+        self.readback.copy(
+            target.encoder,
+            &id_target.texture,
+            TextureAspect::All,
+            request.texel,
+            (request, handles),
+        );
+    }
+
+    /// Advances an in-flight pick without blocking; see [`TexelReadback::poll`].
+    pub fn poll(&mut self, device: &Device) -> Option<PickResult> {
+        let ((request, handles), texel) = self.readback.poll(device)?;
+        Some(PickResult {
+            handle: decode_object_id(u32::from_ne_bytes(texel), &handles),
+            additive: request.additive,
+        })
+    }
+
+    fn ensure_target<'t>(
+        id_target: &'t mut Option<IdTarget>,
+        device: &Device,
+        size: [u32; 2],
+    ) -> &'t IdTarget {
+        if id_target.as_ref().is_none_or(|target| target.size != size) {
+            let extent = Size {
+                width: size[0],
+                height: size[1],
+            };
+            let texture = device.create_texture(&TextureDescriptor {
+                label: Some(Self::TARGET_LABEL),
+                size: Extent3d {
+                    width: size[0],
+                    height: size[1],
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::R32Uint,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&TextureViewDescriptor::default());
+            *id_target = Some(IdTarget {
+                texture,
+                view,
+                depth: Texture::create_depth_texture(Self::DEPTH_LABEL, device, &extent),
+                size,
+            });
+        }
+        id_target.as_ref().expect("target was just created")
+    }
+
+    fn create_slot(&self, device: &Device) -> (UniformBuffer, BindGroup) {
+        let picking_uniform = PickingUniform::new(Mat4::IDENTITY, 0);
+        let uniform_buffer = UniformBuffer::new(
+            UniformBufferId::new(format!("Picking Buffer {}", self.slots.len())),
+            device,
+            bytes_of(&picking_uniform),
+            shared(Transform::default()),
+        );
+        let bind_group =
+            PickingUniform::bind_group(device, &uniform_buffer, &self.picking_bind_group_layout);
+        (uniform_buffer, bind_group)
+    }
+}
+
+/// The texel under `position` on a target of `size` pixels, `None` outside of it.
+pub fn cursor_texel(position: PhysicalPosition<f64>, size: Size) -> Option<[u32; 2]> {
+    let inside = (0.0..size.width as f64).contains(&position.x)
+        && (0.0..size.height as f64).contains(&position.y);
+    inside.then_some([position.x as u32, position.y as u32])
+}
+
+/// The id the mesh at `index` of a frame's mesh list writes; zero stays free for the background.
+fn object_id(index: usize) -> u32 {
+    index as u32 + 1
+}
+
+fn decode_object_id<T>(object_id: u32, handles: &[Handle<T>]) -> Option<Handle<T>> {
+    let index = object_id.checked_sub(1)?;
+    handles.get(index as usize).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::types::handle::SlotMap;
+
+    use super::*;
+
+    const SIZE: Size = Size {
+        width: 800,
+        height: 600,
+    };
+
+    #[test]
+    fn test_cursor_texel_floors_positions_inside_the_target() {
+        assert_eq!(
+            cursor_texel(PhysicalPosition::new(10.7, 599.9), SIZE),
+            Some([10, 599])
+        );
+        assert_eq!(
+            cursor_texel(PhysicalPosition::new(0.0, 0.0), SIZE),
+            Some([0, 0])
+        );
+    }
+
+    #[test]
+    fn test_cursor_texel_rejects_positions_outside_the_target() {
+        assert!(cursor_texel(PhysicalPosition::new(-0.5, 10.0), SIZE).is_none());
+        assert!(cursor_texel(PhysicalPosition::new(800.0, 10.0), SIZE).is_none());
+        assert!(cursor_texel(PhysicalPosition::new(10.0, 600.0), SIZE).is_none());
+    }
+
+    #[test]
+    fn test_object_ids_round_trip_through_the_frame_handles() {
+        let mut slots: SlotMap<&str, &str> = SlotMap::new();
+        let handles = vec![slots.insert("a"), slots.insert("b")];
+
+        assert_eq!(decode_object_id(object_id(1), &handles), Some(handles[1]));
+        assert_eq!(decode_object_id(object_id(0), &handles), Some(handles[0]));
+        assert_eq!(decode_object_id(0, &handles), None);
+        assert_eq!(decode_object_id(object_id(2), &handles), None);
+    }
+}
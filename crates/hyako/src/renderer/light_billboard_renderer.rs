@@ -0,0 +1,195 @@
+use glam::Vec3;
+use hyakou_core::{SharedAccess, components::light::LightSource, geometry::ray::Ray};
+use wgpu::{
+    BindGroup, Buffer, BufferAddress, BufferDescriptor, BufferUsages, Device, Operations,
+    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+    RenderPipeline, include_wgsl,
+};
+
+use crate::{
+    gpu::{
+        buffers::billboard::BillboardInstance, render_pipeline::create_billboard_render_pipeline,
+        texture::Texture,
+    },
+    renderer::{frame::FrameTarget, renderer_context::RenderContext},
+};
+
+/// Draws a small camera-facing disc at every light, tinted with the light's color, so lights that
+/// are not attached to a mesh can still be seen and picked.
+pub struct LightBillboardRenderer {
+    pipeline: RenderPipeline,
+    instance_buffer: Buffer,
+    capacity: usize,
+}
+
+impl LightBillboardRenderer {
+    /// Half size of a marker in world units. Picking uses a sphere of the same radius.
+    pub const MARKER_RADIUS: f32 = 0.15;
+    const INSTANCE_BUFFER_LABEL: &str = "Light Billboard Instance Buffer";
+
+    pub fn new(ctx: &RenderContext) -> Self {
+        let pipeline_layout = ctx
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Light Billboard Pipeline Layout"),
+                bind_group_layouts: &[Some(&ctx.camera_bind_group_layout)],
+                immediate_size: 0,
+            });
+        let pipeline = create_billboard_render_pipeline(
+            &ctx.device,
+            "light billboard render pass",
+            &pipeline_layout,
+            ctx.color_format(),
+            ctx.device
+                .create_shader_module(include_wgsl!("../../assets/light_billboard.wgsl")),
+            Some(Texture::DEPTH_FORMAT),
+        );
+
+        Self {
+            pipeline,
+            instance_buffer: Self::create_instance_buffer(&ctx.device, 1),
+            capacity: 1,
+        }
+    }
+
+    /// Records a single pass with one marker per light; lights whose transform is locked elsewhere
+    /// are skipped for this frame.
+    pub fn draw<'a>(
+        &mut self,
+        device: &Device,
+        target: &mut FrameTarget<'_>,
+        camera_bind_group: &BindGroup,
+        lights: impl IntoIterator<Item = &'a LightSource>,
+    ) {
+        let instances: Vec<BillboardInstance> = lights
+            .into_iter()
+            .filter_map(|light| {
+                let position = light_position(light)?;
+                Some(BillboardInstance::new(
+                    position,
+                    Self::MARKER_RADIUS,
+                    light.color().extend(1.0),
+                ))
+            })
+            .collect();
+        if instances.is_empty() {
+            return;
+        }
+
+        if instances.len() > self.capacity {
+            self.capacity = instances.len().next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, self.capacity);
+        }
+        target
+            .queue
+            .write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+
+        let mut render_pass = target.encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Light Billboard Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target.color_view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            multiview_mask: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: target.depth_view,
+                depth_ops: Some(Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+        render_pass.draw(0..6, 0..instances.len() as u32);
+    }
+
+    fn create_instance_buffer(device: &Device, capacity: usize) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(Self::INSTANCE_BUFFER_LABEL),
+            size: (capacity * size_of::<BillboardInstance>()) as BufferAddress,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+/// Index of the closest light whose marker `ray` hits, with the hit distance.
+pub fn pick_light<'a>(
+    ray: &Ray,
+    lights: impl IntoIterator<Item = &'a LightSource>,
+) -> Option<(usize, f32)> {
+    lights
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, light)| {
+            let distance = ray.intersect_sphere(
+                light_position(light)?,
+                LightBillboardRenderer::MARKER_RADIUS,
+            )?;
+            Some((index, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+fn light_position(light: &LightSource) -> Option<Vec3> {
+    light
+        .transform
+        .try_read_shared(|transform| transform.position)
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+    use hyakou_core::{shared, types::transform::Transform};
+
+    use super::*;
+
+    fn light_at(position: Vec3) -> LightSource {
+        LightSource::new(
+            shared(Transform::new(position, Quat::IDENTITY, Vec3::ONE)),
+            Vec3::ONE,
+        )
+    }
+
+    #[test]
+    fn test_ray_through_a_marker_picks_its_light() {
+        let lights = [light_at(Vec3::new(2.0, 0.0, 0.0)), light_at(Vec3::ZERO)];
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z);
+
+        let (index, distance) = pick_light(&ray, &lights).unwrap();
+
+        assert_eq!(index, 1);
+        assert!((distance - (10.0 - LightBillboardRenderer::MARKER_RADIUS)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_closest_of_overlapping_markers_wins() {
+        let lights = [light_at(Vec3::ZERO), light_at(Vec3::new(0.0, 0.0, 5.0))];
+        let ray = Ray::new(Vec3::new(0.05, 0.0, 10.0), Vec3::NEG_Z);
+
+        assert_eq!(pick_light(&ray, &lights).map(|(index, _)| index), Some(1));
+    }
+
+    #[test]
+    fn test_ray_beside_every_marker_picks_nothing() {
+        let lights = [light_at(Vec3::ZERO)];
+        let ray = Ray::new(
+            Vec3::new(LightBillboardRenderer::MARKER_RADIUS * 2.0, 0.0, 10.0),
+            Vec3::NEG_Z,
+        );
+
+        assert!(pick_light(&ray, &lights).is_none());
+    }
+}
@@ -1,53 +1,117 @@
-use std::{collections::HashMap, f32::consts::PI, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::PI,
+    path::Path,
+    sync::Arc,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+use crate::renderer::handlers::source_watcher::SourceWatcher;
 
 use crate::{
     gpu::{
         buffers::{
-            camera_buffer::CameraUniform, model_matrix::ModelMatrixUniform, uniform::UniformBuffer,
+            camera_buffer::CameraUniform, model_matrix::ModelMatrixUniform, staging::UploadBelt,
+            uniform::UniformBuffer,
         },
+        material::ShadingSource,
         render_mesh::RenderMesh,
     },
     renderer::{
+        actions::NudgeActions,
+        depth_probe::DepthProbe,
+        draw_list::DrawList,
+        error::RendererError,
         frame::FrameTarget,
-        handlers::{asset_handler::AssetHandler, camera::CameraHandler},
+        gizmo::{GizmoMode, GizmoTargets, TransformGizmo, gizmo_renderer::GizmoRenderer},
+        gpu_info::GpuInfo,
+        handlers::{
+            animation_handler::AnimationHandler,
+            asset_handler::AssetHandler,
+            camera::CameraHandler,
+            nudge_handler::NudgeHandler,
+            placement::{PlacementHandler, PlacementSource},
+            resource_handler::ResourceHandler,
+            selection::{PickingBackend, SelectionEvent, SelectionManager},
+        },
+        id_picker::{IdPicker, PickRequest, cursor_texel},
+        light_billboard_renderer::{LightBillboardRenderer, pick_light},
+        offscreen::{OffscreenTarget, PendingCapture},
+        outline_renderer::OutlineRenderer,
         renderer_context::RenderContext,
-        wrappers::WinitSurfaceProvider,
+        scene_file::{
+            SceneAnimator, SceneAsset, SceneCamera, SceneEntryError, SceneFile, SceneLight,
+            SceneLoadErrors, SceneMesh, SceneTransform,
+        },
+        settings::{RendererSettings, SettingsChange},
+        stats::{FrameStats, FrameStatsHistory, RendererStats},
+        text_overlay::{OverlayLines, TextOverlayRenderer},
+        util::asset_root::AssetRoot,
+        view::ViewState,
+        wrappers::{SurfaceProvider, WinitSurfaceProvider},
     },
 };
-use anyhow::Result;
 use bytemuck::bytes_of;
-use glam::Vec3;
+use glam::{Quat, Vec3};
 use hyakou_core::{
-    SharedAccess,
-    animations::{Animation, Animator, NEUTRAL_SPEED, trajectory::linear::LinearTrajectory},
+    Shared, SharedAccess,
+    animations::{Animator, NEUTRAL_SPEED, trajectory::linear::LinearTrajectory},
     components::{
         LightType,
         camera::{camera::Camera, data_structures::CameraMode},
         light::LightSource,
     },
-    shared,
+    same_shared, shared,
     traits::BindGroupProvider,
     types::{
-        DeltaTime64, ModelMatrixBindingMode, Size, TransformBuffer,
+        DeltaTime64, ModelMatrixBindingMode, QueueWriter, Size, TransformBuffer,
         camera::{Pitch, Yaw},
-        ids::{MeshId, UniformBufferId},
+        ids::UniformBufferId,
+        lock_busy::LockBusy,
         transform::Transform,
+        viewport::Viewport,
     },
 };
-use log::{error, warn};
+use log::{debug, error, warn};
+use tracing::instrument;
 use wgpu::{
-    BindGroup, Color, CommandEncoder, Device, Operations, Queue, RenderPassColorAttachment,
+    BindGroup, CommandEncoder, Device, Operations, Queue, RenderPassColorAttachment,
     RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPipeline, SurfaceConfiguration,
-    TextureView,
+    TextureFormat, TextureView,
+};
+use winit::{
+    dpi::PhysicalPosition,
+    window::{Window, WindowId},
 };
-use winit::window::Window;
 
 pub mod actions;
+pub mod depth_probe;
+pub mod draw_list;
+pub mod error;
 pub mod frame;
+pub mod gizmo;
+pub mod gpu_info;
 pub mod handlers;
+pub mod id_picker;
+pub mod light_billboard_renderer;
+pub mod offscreen;
+pub mod outline_renderer;
 pub mod renderer_context;
+pub mod scene_file;
+pub mod settings;
+pub mod stats;
 pub mod surface_frame_controller;
+#[cfg(test)]
+pub(crate) mod test_harness;
+pub mod texel_readback;
+pub mod text_overlay;
 pub mod util;
+pub mod view;
 pub mod wrappers;
 
 pub struct SceneRenderer {
@@ -55,26 +119,73 @@ pub struct SceneRenderer {
     pub camera: Camera,
     camera_uniform: CameraUniform,
     camera_uniform_buffer: UniformBuffer,
-    camera_bind_group: BindGroup,
     light: LightSource,
     light_uniform_buffer: UniformBuffer,
-    light_bind_group: BindGroup,
-    animators: HashMap<MeshId, Animator>,
+    resources: ResourceHandler,
+    animation_handler: AnimationHandler,
     pub camera_handler: CameraHandler,
     pub asset_manager: AssetHandler,
+    selection: SelectionManager,
+    nudge_handler: NudgeHandler,
+    placement: PlacementHandler,
+    gizmo: TransformGizmo,
+    gizmo_renderer: GizmoRenderer,
+    outline_renderer: OutlineRenderer,
+    light_billboard_renderer: LightBillboardRenderer,
+    id_picker: IdPicker,
+    depth_probe: DepthProbe,
+    draw_list: DrawList,
+    text_overlay_renderer: TextOverlayRenderer,
+    overlay_lines: OverlayLines,
+    settings: RendererSettings,
+    last_draw_count: usize,
+    /// Uniform buffer writes since the last recorded frame.
+    uniform_uploads: usize,
+    upload_belt: UploadBelt,
+    /// CPU time spent on uploads since the last recorded frame.
+    upload_seconds: f64,
+    interpolation_alpha: f64,
+    stats_history: FrameStatsHistory,
+    stats: RendererStats,
+    last_render_at: Option<Instant>,
+    views: HashMap<WindowId, ViewState>,
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    source_watcher: SourceWatcher,
 }
 
 impl SceneRenderer {
-    pub async fn new(window: Arc<Window>) -> Result<Self> {
-        const CAMERA_SPEED_UNITS_PER_SECOND: f32 = 20.0;
-        const CAMERA_SENSITIVITY: f32 = 0.001;
-        let ctx = RenderContext::new(Some(WinitSurfaceProvider {
-            window: window.clone(),
-        }))
-        .await
-        .unwrap();
+    const CAMERA_RESOURCE_ID: &str = "Camera";
+    const LIGHT_RESOURCE_ID: &str = "Light Uniform Buffer";
+    /// Selection id of the scene light, picked through its billboard marker.
+    pub const LIGHT_SELECTION_ID: &str = "Light";
 
-        let assets_dir = util::get_relative_path();
+    pub async fn new(
+        window: Arc<Window>,
+        transparent: bool,
+        settings: RendererSettings,
+        asset_root: &AssetRoot,
+    ) -> Result<Self, RendererError> {
+        let ctx = RenderContext::with_settings(
+            Some(WinitSurfaceProvider {
+                window,
+                transparent,
+            }),
+            &settings,
+        )
+        .await?;
+        let mut renderer = Self::from_context(ctx, asset_root).await?;
+        renderer.settings = settings;
+        renderer.apply_runtime_settings();
+        Ok(renderer)
+    }
+
+    /// Builds the demo scene from the glTF files under `asset_root` on an existing context.
+    pub(crate) async fn from_context(
+        ctx: RenderContext,
+        asset_root: &AssetRoot,
+    ) -> Result<Self, RendererError> {
+        let settings = RendererSettings::default();
+        let assets_dir = asset_root.resolve()?;
 
         let mut asset_handler = AssetHandler::new(
             ctx.device.clone(),
@@ -82,36 +193,67 @@ impl SceneRenderer {
             ctx.model_binding_mode,
             ctx.model_bind_group_layout.clone(),
             ctx.material_bind_group_layout.clone(),
+            ctx.fallback_textures(),
         );
-        let _suzanne_mesh = asset_handler
+        // Loaded by name rather than with `add_directory`: the web build can't list directories,
+        // the two meshes take different light types, and `scenes/demo.ron` names these files.
+        // A demo asset that fails to load is left out; the renderer still draws whatever loaded.
+        if let Err(load_error) = asset_handler
             .add_from_path(
                 "Suzanne".to_string(),
                 LightType::LIGHT,
-                assets_dir.join("assets/gltf/Suzanne.gltf").as_path(),
+                assets_dir.join("gltf/Suzanne.gltf").as_path(),
             )
-            .await?;
-        let cube_light_mesh = asset_handler
+            .await
+        {
+            error!("Skipping the Suzanne demo mesh: {load_error}");
+        }
+        let cube_light_mesh = match asset_handler
             .add_from_path(
                 "Cube".to_string(),
                 LightType::NO_LIGHT,
-                assets_dir.join("assets/gltf/Cube.gltf").as_path(),
+                assets_dir.join("gltf/Cube.gltf").as_path(),
             )
-            .await?;
-        cube_light_mesh
-            .transform
-            .try_write_shared(|t| t.translate(Vec3::new(0.0, 1.0, 1.0)))?;
-        let light = LightSource::new(cube_light_mesh.transform.clone(), Vec3::new(1.0, 1.0, 1.0));
+            .await
+        {
+            Ok(cube_meshes) => cube_meshes.into_iter().next(),
+            Err(load_error) => {
+                error!("Skipping the light cube, the light is left without a mesh: {load_error}");
+                None
+            }
+        };
+        // Without the cube the light keeps a transform of its own.
+        let light_transform = cube_light_mesh.as_ref().map_or_else(
+            || shared(Transform::default()),
+            |mesh| mesh.transform.clone(),
+        );
+        light_transform
+            .try_write_shared(|t| t.translate(Vec3::new(0.0, 1.0, 1.0)))
+            .map_err(|_| RendererError::LockContention {
+                id: Self::LIGHT_RESOURCE_ID.to_string(),
+            })?;
+        let light = LightSource::new(light_transform.clone(), Vec3::new(1.0, 1.0, 1.0));
+        let mut resources = ResourceHandler::new();
         let light_uniform_buffer = UniformBuffer::new(
-            UniformBufferId::new("Light Uniform Buffer".to_string()),
+            UniformBufferId::new(Self::LIGHT_RESOURCE_ID.to_string()),
             &ctx.device,
-            bytes_of(&light.to_gpu().unwrap()),
-            cube_light_mesh.transform.clone(),
+            bytes_of(
+                &light
+                    .to_gpu()
+                    .ok_or_else(|| RendererError::LockContention {
+                        id: Self::LIGHT_RESOURCE_ID.to_string(),
+                    })?,
+            ),
+            light_transform,
         );
 
-        let light_bind_group = LightSource::bind_group(
-            &ctx.device,
-            &light_uniform_buffer,
-            &LightSource::bind_group_layout(&ctx.device),
+        resources.insert(
+            light_uniform_buffer.id(),
+            LightSource::bind_group(
+                &ctx.device,
+                &light_uniform_buffer,
+                &LightSource::bind_group_layout(&ctx.device),
+            ),
         );
 
         let aspect = Camera::aspect_ratio_from_size(ctx.size);
@@ -120,13 +262,13 @@ impl SceneRenderer {
             Vec3::new(0.0, 0.0, 0.0),
             Vec3::Y,
             aspect,
-            45.0_f32.to_radians(),
+            settings.fov_degrees.to_radians(),
             0.1,
             1000.0,
             Yaw::new(-PI / 2.0),
             Pitch::new(0.0),
-            CAMERA_SPEED_UNITS_PER_SECOND,
-            CAMERA_SENSITIVITY,
+            settings.camera_speed,
+            settings.camera_sensitivity,
             0.5,
         );
 
@@ -134,35 +276,50 @@ impl SceneRenderer {
         camera_uniform.update(&camera);
 
         let camera_uniform_buffer = UniformBuffer::new(
-            UniformBufferId::new("Camera".to_string()),
+            UniformBufferId::new(Self::CAMERA_RESOURCE_ID.to_string()),
             &ctx.device,
             bytemuck::bytes_of(&camera_uniform),
             shared(Transform::default()),
         );
-        let camera_bind_group = CameraUniform::bind_group(
-            &ctx.device,
-            &camera_uniform_buffer,
-            &ctx.camera_bind_group_layout,
+        resources.insert(
+            camera_uniform_buffer.id(),
+            CameraUniform::bind_group(
+                &ctx.device,
+                &camera_uniform_buffer,
+                &ctx.camera_bind_group_layout,
+            ),
         );
 
-        let test_trajectory = LinearTrajectory::new_deconstructed_mesh(
-            cube_light_mesh.id.clone(),
-            cube_light_mesh.transform.clone(),
-            Vec3::new(0.0, 1.0, 0.0),
-            f32::to_radians(0.0),
-            f32::to_radians(0.0),
-            3.0,
-            3.0,
-            true,
-            true,
-        )
-        .unwrap();
+        let gizmo_renderer = GizmoRenderer::new(&ctx);
+        let outline_renderer = OutlineRenderer::new(&ctx);
+        let light_billboard_renderer = LightBillboardRenderer::new(&ctx);
+        let id_picker = IdPicker::new(&ctx);
+        let depth_probe = DepthProbe::new(&ctx.device);
+        let text_overlay_renderer = TextOverlayRenderer::new(&ctx);
 
-        let mut animators = HashMap::<MeshId, Animator>::new();
-        animators.insert(
-            test_trajectory.get_id().clone(),
-            Animator::new(NEUTRAL_SPEED, Box::new(test_trajectory)).unwrap(),
-        );
+        let mut animation_handler = AnimationHandler::new();
+        let cube_light =
+            cube_light_mesh.and_then(|mesh| Some((asset_handler.handle(&mesh.id)?, mesh)));
+        if let Some((cube_light_handle, cube_light_mesh)) = cube_light {
+            let test_trajectory = LinearTrajectory::new_deconstructed_mesh(
+                cube_light_mesh.id.clone(),
+                cube_light_mesh.transform.clone(),
+                Vec3::new(0.0, 1.0, 0.0),
+                f32::to_radians(0.0),
+                f32::to_radians(0.0),
+                3.0,
+                3.0,
+                true,
+                true,
+            )
+            .unwrap();
+            animation_handler.insert(
+                cube_light_handle,
+                Animator::new(NEUTRAL_SPEED, Box::new(test_trajectory)).unwrap(),
+            );
+        }
+
+        let upload_belt = UploadBelt::new((*ctx.device).clone(), UploadBelt::DEFAULT_CHUNK_SIZE);
 
         Ok(Self {
             ctx,
@@ -170,40 +327,144 @@ impl SceneRenderer {
             camera_uniform,
             camera,
             camera_uniform_buffer,
-            camera_bind_group,
             light,
             light_uniform_buffer,
-            light_bind_group,
-            animators,
+            resources,
+            animation_handler,
             camera_handler: CameraHandler::new(CameraMode::ORBIT),
+            selection: SelectionManager::new(),
+            nudge_handler: NudgeHandler::new(),
+            placement: PlacementHandler::new(),
+            gizmo: TransformGizmo::new(),
+            gizmo_renderer,
+            outline_renderer,
+            light_billboard_renderer,
+            id_picker,
+            depth_probe,
+            draw_list: DrawList::new(),
+            text_overlay_renderer,
+            overlay_lines: OverlayLines::new(),
+            settings,
+            last_draw_count: 0,
+            uniform_uploads: 0,
+            upload_belt,
+            upload_seconds: 0.0,
+            interpolation_alpha: 0.0,
+            stats_history: FrameStatsHistory::default(),
+            stats: RendererStats::default(),
+            last_render_at: None,
+            views: HashMap::new(),
+            #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+            source_watcher: SourceWatcher::default(),
         })
     }
 
+    #[instrument(name = "SceneRenderer::update", skip_all)]
     pub fn update(&mut self, delta_time: DeltaTime64) {
+        self.poll_picking();
+        self.depth_probe.poll(&self.ctx.device);
+        self.asset_manager.poll_pending();
+        #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+        self.reload_changed_assets();
+        self.adopt_imported_animators();
+        let asset_manager = &self.asset_manager;
+        self.selection
+            .retain_loaded(|id| id == Self::LIGHT_SELECTION_ID || asset_manager.contains(id));
         self.camera_handler
             .update(&mut self.camera, delta_time as f32);
-        self.animators.values_mut().for_each(|animator| {
-            if let Err(animator_error) = animator.play(delta_time) {
-                error!("{:?}", animator_error)
+        // The camera keeps flying while paused; only the scene itself is frozen.
+        let simulated = self.animation_handler.play_all(delta_time);
+
+        // Nothing moves the light while paused, so its buffer is still current.
+        if simulated {
+            self.light.mark_changed();
+        }
+        self.upload_uniforms();
+    }
+
+    /// Writes the light and camera uniforms whose CPU-side state changed since the last upload.
+    /// Both go through the upload belt, which is submitted once at the end.
+    fn upload_uniforms(&mut self) {
+        let started_at = Instant::now();
+        let light_generation = self.light.generation();
+        if self.light_uniform_buffer.is_stale(light_generation) {
+            let light = &self.light;
+            match self
+                .light_uniform_buffer
+                .upload_with(&self.upload_belt, |transform| light.to_gpu_with(transform))
+            {
+                Ok(()) => {
+                    self.light_uniform_buffer.mark_uploaded(light_generation);
+                    self.uniform_uploads += 1;
+                }
+                Err(error) if error.is::<LockBusy>() => {
+                    warn!("Skipping light buffer - Transform in Light is still locked");
+                }
+                Err(error) => error!("Failed to upload the light uniform: {error}"),
             }
-        });
+        }
 
-        self.camera_uniform.update(&self.camera);
-        if let Some(gpu_light_source) = self.light.to_gpu() {
-            self.light_uniform_buffer
-                .update_buffer_transform(&self.ctx.queue, bytes_of(&gpu_light_source))
-                .unwrap()
-        } else {
-            warn!("Skipping light buffer - Transform in Light is still locked");
+        let camera_generation = self.camera.generation();
+        if self.camera_uniform_buffer.is_stale(camera_generation) {
+            self.camera_uniform.update(&self.camera);
+            self.upload_belt.write_buffer(
+                &self.camera_uniform_buffer,
+                0,
+                bytes_of(&self.camera_uniform),
+            );
+            self.camera_uniform_buffer.mark_uploaded(camera_generation);
+            self.uniform_uploads += 1;
         }
-        self.ctx.queue.write_buffer(
-            &self.camera_uniform_buffer,
-            0,
-            bytes_of(&self.camera_uniform),
+
+        for mesh in self.asset_manager.get_all_assets() {
+            match mesh
+                .gpu_material
+                .upload_if_dirty(&self.upload_belt, &mesh.material)
+            {
+                Ok(true) => self.uniform_uploads += 1,
+                Ok(false) => {}
+                Err(error) if error.is::<LockBusy>() => {
+                    warn!("Skipping material buffer - Material is still locked");
+                }
+                Err(error) => error!("Failed to upload the material uniform: {error}"),
+            }
+        }
+
+        self.upload_belt.submit(&self.ctx.queue);
+        self.upload_seconds += started_at.elapsed().as_secs_f64();
+    }
+
+    /// Records the scene pass. `interpolation_alpha` is how far the frame sits between the last
+    /// simulation step and the next one.
+    #[instrument(name = "SceneRenderer::render_scene", skip_all)]
+    pub fn render_scene(&mut self, target: &mut FrameTarget<'_>, interpolation_alpha: f64) {
+        self.interpolation_alpha = interpolation_alpha;
+        let Some(camera_bind_group) = self.resources.get(Self::CAMERA_RESOURCE_ID).cloned() else {
+            error!("Camera bind group missing, skipping the scene pass");
+            return;
+        };
+        self.last_draw_count = self.draw_scene(target, &camera_bind_group);
+        self.id_picker.record(
+            &self.ctx.device,
+            target,
+            &camera_bind_group,
+            self.asset_manager
+                .get_all_visible_handles()
+                .map(|(handle, mesh)| (handle, mesh.as_ref())),
         );
+        self.depth_probe
+            .record(target, self.camera_uniform.inverse_view_projection_matrix);
+        self.record_frame_stats(Instant::now());
+        if self.settings.show_stats_overlay {
+            self.overlay_lines
+                .fill_from_stats(&self.stats, self.selection.selected());
+            self.text_overlay_renderer
+                .draw(&self.ctx.device, target, &self.overlay_lines);
+        }
     }
 
-    pub fn render_scene(&mut self, target: &mut FrameTarget<'_>) {
+    /// Draws the shared scene as seen through `camera_bind_group` and returns the mesh draw count.
+    fn draw_scene(&mut self, target: &mut FrameTarget<'_>, camera_bind_group: &BindGroup) -> usize {
         {
             target.encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Main Command Buffer"),
@@ -212,12 +473,7 @@ impl SceneRenderer {
                     depth_slice: None,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(Color {
-                            r: 0.3,
-                            g: 0.2,
-                            b: 0.8,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(self.settings.clear_color()),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -235,39 +491,207 @@ impl SceneRenderer {
             });
         }
 
-        self.asset_manager
-            .get_all_visible_assets_with_modifier(&LightType::LIGHT)
-            .for_each(|elem| {
+        self.rebuild_draw_list();
+        let Some(light_bind_group) = self.resources.get(Self::LIGHT_RESOURCE_ID) else {
+            error!("Light bind group missing, skipping scene meshes");
+            return 0;
+        };
+        let mut draw_count = 0;
+        let wireframe_pipeline = self
+            .settings
+            .wireframe
+            .then_some(self.ctx.wireframe_render_pipeline.as_ref())
+            .flatten();
+        let lit_pipeline = if let Some(wireframe_pipeline) = wireframe_pipeline {
+            wireframe_pipeline
+        } else if self.settings.pbr_shading {
+            &self.ctx.pbr_render_pipeline
+        } else {
+            &self.ctx.light_render_pipeline
+        };
+        let unlit_pipeline = wireframe_pipeline.unwrap_or(&self.ctx.no_light_render_pipeline);
+        for (handles, pipeline) in [
+            (self.draw_list.lit(), lit_pipeline),
+            (self.draw_list.unlit(), unlit_pipeline),
+        ] {
+            for mesh in handles
+                .iter()
+                .filter_map(|&handle| self.asset_manager.get_by_handle(handle))
+            {
+                draw_count += 1;
                 Self::record_scene_pass_command_encoder(
                     target.encoder,
-                    elem,
-                    &self.ctx.light_render_pipeline,
+                    mesh,
+                    pipeline,
                     target.queue,
                     self.ctx.model_binding_mode,
-                    &self.camera_bind_group,
-                    &self.light_bind_group,
+                    camera_bind_group,
+                    light_bind_group,
                     target.color_view,
                     target.depth_view,
                 );
-            });
+            }
+        }
 
-        self.asset_manager
-            .get_all_visible_assets_with_modifier(&LightType::NO_LIGHT)
-            .for_each(|elem| {
-                Self::record_scene_pass_command_encoder(
-                    target.encoder,
-                    elem,
-                    &self.ctx.no_light_render_pipeline,
-                    target.queue,
-                    self.ctx.model_binding_mode,
-                    &self.camera_bind_group,
-                    &self.light_bind_group,
-                    target.color_view,
-                    target.depth_view,
-                );
-            });
+        if self.settings.show_light_gizmos {
+            self.light_billboard_renderer.draw(
+                &self.ctx.device,
+                target,
+                camera_bind_group,
+                [&self.light],
+            );
+        }
+
+        let asset_manager = &self.asset_manager;
+        self.outline_renderer.draw(
+            &self.ctx.device,
+            target,
+            camera_bind_group,
+            self.selection
+                .iter()
+                .filter_map(|selected_id| asset_manager.get_visible(selected_id))
+                .map(|mesh| mesh.as_ref()),
+            &self.settings,
+        );
+
+        if let Some(handle_transform) = self.selected_gizmo_transform() {
+            self.gizmo_renderer.draw(
+                target.encoder,
+                target.queue,
+                camera_bind_group,
+                self.gizmo.mode(),
+                &handle_transform,
+                target.color_view,
+                target.depth_view,
+            );
+        }
+
+        draw_count
+    }
+
+    /// Opens an additional view of the scene in `window`, starting from the main camera's pose.
+    pub fn add_view(&mut self, window: Arc<Window>) -> Result<(), RendererError> {
+        let camera = Camera::new(
+            self.camera.eye,
+            self.camera.target,
+            self.camera.up,
+            self.camera.aspect,
+            self.camera.fovy,
+            self.camera.znear,
+            self.camera.zfar,
+            self.camera.yaw,
+            self.camera.pitch,
+            self.camera.speed,
+            self.camera.sensitivity,
+            self.camera.smoothing_factor,
+        );
+        let window_id = window.id();
+        let view = ViewState::new(&self.ctx, window, camera)?;
+        self.views.insert(window_id, view);
+        Ok(())
+    }
+
+    pub fn remove_view(&mut self, window_id: WindowId) -> bool {
+        self.views.remove(&window_id).is_some()
+    }
+
+    pub fn view_mut(&mut self, window_id: WindowId) -> Option<&mut ViewState> {
+        self.views.get_mut(&window_id)
+    }
+
+    pub fn view_count(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn resize_view(&mut self, window_id: WindowId, size: Size) {
+        if let Some(view) = self.views.get_mut(&window_id) {
+            view.resize(&self.ctx.device, size);
+        }
+    }
+
+    /// Renders the shared scene into the view for `window_id` using that view's camera.
+    pub fn render_view(&mut self, window_id: WindowId) -> Result<(), RendererError> {
+        let Some(mut view) = self.views.remove(&window_id) else {
+            return Ok(());
+        };
+        let render_result = self.render_into_view(&mut view);
+        self.views.insert(window_id, view);
+        render_result
     }
 
+    fn render_into_view(&mut self, view: &mut ViewState) -> Result<(), RendererError> {
+        view.update_camera(&self.ctx.queue);
+        let Some(mut frame) = view.begin_frame(&self.ctx.device, &self.ctx.queue)? else {
+            return Ok(());
+        };
+
+        {
+            let mut target = frame.target();
+            self.draw_scene(&mut target, view.camera_bind_group());
+        }
+
+        if frame.finish() {
+            view.reconfigure(&self.ctx.device);
+        }
+        Ok(())
+    }
+
+    /// Rolling frame statistics, refreshed at the end of every [`Self::render_scene`] call.
+    pub fn stats(&self) -> RendererStats {
+        self.stats
+    }
+
+    /// Starts a fresh measurement window, e.g. around a benchmarked section.
+    pub fn reset_stats(&mut self) {
+        self.stats_history.clear();
+        self.stats = RendererStats::default();
+        self.last_render_at = None;
+    }
+
+    fn record_frame_stats(&mut self, now: Instant) {
+        let uniform_uploads = std::mem::take(&mut self.uniform_uploads);
+        let upload_seconds = std::mem::take(&mut self.upload_seconds);
+        let Some(last_render_at) = self.last_render_at.replace(now) else {
+            return;
+        };
+        self.stats_history.push(FrameStats {
+            frame_seconds: now.duration_since(last_render_at).as_secs_f64(),
+            draw_calls: self.last_draw_count,
+            culled_objects: 0,
+            gpu_ms: None,
+            uniform_uploads,
+            upload_ms: upload_seconds * 1000.0,
+        });
+        self.stats = self.stats_history.summary();
+    }
+
+    fn selected_gizmo_transform(&self) -> Option<Transform> {
+        let selected = self.selection.selected()?;
+        let transform = SceneGizmoTargets {
+            asset_manager: &self.asset_manager,
+            light: &self.light,
+        }
+        .target_transform(selected)?;
+        Some(TransformGizmo::handle_transform(
+            &self.camera,
+            transform.read_shared(|t| t.position),
+        ))
+    }
+
+    fn rebuild_draw_list(&mut self) {
+        self.draw_list.rebuild(
+            self.asset_manager
+                .get_all_visible_handles()
+                .map(|(handle, mesh)| (handle, mesh.light_type)),
+        );
+    }
+
+    #[instrument(
+        name = "SceneRenderer::record_scene_pass",
+        level = "debug",
+        skip_all,
+        fields(mesh = %render_mesh.id.0)
+    )]
     fn record_scene_pass_command_encoder(
         encoder: &mut CommandEncoder,
         render_mesh: &RenderMesh,
@@ -310,13 +734,10 @@ impl SceneRenderer {
         render_pass.set_bind_group(0, camera_bind_group, &[]);
         render_pass.set_bind_group(
             Self::material_bind_group_index(model_binding_mode),
-            &render_mesh.material.bind_group,
+            &render_mesh.gpu_material.bind_group,
             &[],
         );
-        render_pass.set_index_buffer(
-            render_mesh.index_buffer.slice(..),
-            wgpu::IndexFormat::Uint32,
-        );
+        render_pass.set_index_buffer(render_mesh.index_buffer.slice(..), render_mesh.index_format);
         render_pass.draw_indexed(0..render_mesh.index_count, 0, 0..1);
     }
 
@@ -326,13 +747,12 @@ impl SceneRenderer {
         queue: &Queue,
         model_binding_mode: ModelMatrixBindingMode,
     ) {
-        let model_matrix = render_mesh.transform.read_shared(|t| t.get_matrix());
+        let model_matrix = render_mesh.model_matrix();
         match model_binding_mode {
             ModelMatrixBindingMode::Immediate => {
                 render_pass.set_immediates(0, bytes_of(&model_matrix));
             }
             ModelMatrixBindingMode::Uniform => {
-                let model_uniform = ModelMatrixUniform::new(model_matrix);
                 let model_uniform_buffer = render_mesh.model_uniform_buffer.as_ref().expect(
                     "Uniform model binding mode requires a model uniform buffer on RenderMesh",
                 );
@@ -340,12 +760,842 @@ impl SceneRenderer {
                     .model_bind_group
                     .as_ref()
                     .expect("Uniform model binding mode requires a model bind group on RenderMesh");
-                queue.write_buffer(model_uniform_buffer, 0, bytes_of(&model_uniform));
+                // Static meshes keep the matrix written on an earlier frame.
+                if render_mesh.mark_model_matrix_uploaded(model_matrix) {
+                    let model_uniform = ModelMatrixUniform::new(model_matrix);
+                    queue.write_buffer(model_uniform_buffer, 0, bytes_of(&model_uniform));
+                }
                 render_pass.set_bind_group(2, model_bind_group, &[]);
             }
         }
     }
 
+    pub fn selection(&self) -> &SelectionManager {
+        &self.selection
+    }
+
+    pub fn selection_mut(&mut self) -> &mut SelectionManager {
+        &mut self.selection
+    }
+
+    pub fn light(&self) -> &LightSource {
+        &self.light
+    }
+
+    pub fn set_light_color(&mut self, color: Vec3) {
+        self.light.update_color(color);
+    }
+
+    /// Shared transform of a loaded mesh, or of the light for [`Self::LIGHT_SELECTION_ID`].
+    pub fn target_transform(&self, id: &str) -> Option<Shared<Transform>> {
+        SceneGizmoTargets {
+            asset_manager: &self.asset_manager,
+            light: &self.light,
+        }
+        .target_transform(id)
+    }
+
+    /// Call after writing a transform obtained from [`Self::target_transform`]. The light may
+    /// share its transform with the edited mesh, so its uniform is refreshed either way.
+    pub fn transform_edited(&mut self) {
+        self.light.mark_changed();
+    }
+
+    /// Applies a finished GPU pick to the selection. Called from [`Self::update`], so the result
+    /// of a click lands one frame after the frame that recorded its picking pass.
+    fn poll_picking(&mut self) {
+        let Some(result) = self.id_picker.poll(&self.ctx.device) else {
+            return;
+        };
+        let id = result
+            .handle
+            .and_then(|handle| self.asset_manager.get_by_handle(handle))
+            .map(|mesh| mesh.id.0.clone());
+        self.selection.apply_pick(id, result.additive);
+    }
+
+    pub fn picking_backend(&self) -> PickingBackend {
+        self.selection.backend()
+    }
+
+    pub fn set_picking_backend(&mut self, backend: PickingBackend) {
+        self.selection.set_backend(backend);
+    }
+
+    /// Picks the mesh or light marker under `position`. A plain click replaces the selection, an
+    /// additive click toggles the hit object in or out of it. With [`PickingBackend::GpuId`] a mesh
+    /// hit only reaches the selection a frame later, so the current selection is returned.
+    pub fn select_at_screen_position(
+        &mut self,
+        position: PhysicalPosition<f64>,
+        additive: bool,
+    ) -> Option<&str> {
+        if self.selection.backend() == PickingBackend::GpuId {
+            return self.request_gpu_pick(position, additive);
+        }
+        let ray = match self
+            .camera
+            .screen_to_ray(position, self.ctx.viewport().physical_size())
+        {
+            Ok(ray) => ray,
+            Err(ray_error) => {
+                warn!("Failed to build selection ray: {ray_error:?}");
+                if !additive {
+                    self.selection.clear();
+                }
+                return None;
+            }
+        };
+
+        let light_hit = self
+            .settings
+            .show_light_gizmos
+            .then(|| pick_light(&ray, [&self.light]))
+            .flatten();
+        if let Some((_, light_distance)) = light_hit {
+            let mesh_distance = SelectionManager::pick_closest(
+                &ray,
+                self.asset_manager
+                    .get_all_visible_assets()
+                    .map(|mesh| mesh.as_ref()),
+            )
+            .map_or(f32::INFINITY, |hit| hit.distance);
+            if light_distance <= mesh_distance {
+                let id = Self::LIGHT_SELECTION_ID.to_string();
+                if additive {
+                    self.selection.toggle(id);
+                } else {
+                    self.selection.select(id);
+                }
+                return self.selection.selected();
+            }
+        }
+
+        let meshes = self
+            .asset_manager
+            .get_all_visible_assets()
+            .map(|mesh| mesh.as_ref());
+        if additive {
+            self.selection.toggle_closest(&ray, meshes)
+        } else {
+            self.selection.select_closest(&ray, meshes)
+        }
+    }
+
+    fn request_gpu_pick(
+        &mut self,
+        position: PhysicalPosition<f64>,
+        additive: bool,
+    ) -> Option<&str> {
+        let size = self.ctx.viewport().physical_size();
+        let light_hit = self.settings.show_light_gizmos
+            && self
+                .camera
+                .screen_to_ray(position, size)
+                .is_ok_and(|ray| pick_light(&ray, [&self.light]).is_some());
+        if light_hit {
+            let id = Self::LIGHT_SELECTION_ID.to_string();
+            if additive {
+                self.selection.toggle(id);
+            } else {
+                self.selection.select(id);
+            }
+            return self.selection.selected();
+        }
+
+        match cursor_texel(position, size) {
+            Some(texel) => self.id_picker.request(PickRequest { texel, additive }),
+            None if !additive => self.selection.clear(),
+            None => {}
+        }
+        self.selection.selected()
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.gizmo.end_drag();
+        self.selection.clear();
+    }
+
+    pub fn take_selection_events(&mut self) -> Vec<SelectionEvent> {
+        self.selection.take_events()
+    }
+
+    pub fn settings(&self) -> &RendererSettings {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut RendererSettings {
+        &mut self.settings
+    }
+
+    /// Applies everything in `settings` that can change while running. Restart-only fields keep
+    /// their current value and are listed in the result.
+    pub fn apply_settings(&mut self, settings: &RendererSettings) -> SettingsChange {
+        let restart_required = self.settings.restart_required_changes(settings);
+        self.settings = settings.with_restart_fields_from(&self.settings);
+        self.apply_runtime_settings();
+        SettingsChange { restart_required }
+    }
+
+    /// Pushes the runtime part of `self.settings` into the camera and surfaces. The clear color
+    /// and outline are read from the settings every frame.
+    fn apply_runtime_settings(&mut self) {
+        if let Err(error) = self.camera.set_fov_degrees(self.settings.fov_degrees) {
+            warn!("Keeping the current field of view: {error:#}");
+        }
+        self.camera.speed = self.settings.camera_speed;
+        self.camera.sensitivity = self.settings.camera_sensitivity;
+
+        let present_mode = self.settings.present_mode();
+        if self.ctx.present_mode() != present_mode {
+            self.ctx.set_present_mode(present_mode);
+            for view in self.views.values_mut() {
+                view.set_present_mode(&self.ctx.device, self.ctx.present_mode());
+            }
+        }
+    }
+
+    /// Number of scene meshes drawn by the last [`Self::render_scene`] call.
+    pub fn last_draw_count(&self) -> usize {
+        self.last_draw_count
+    }
+
+    /// Interpolation alpha handed to the last [`Self::render_scene`] call.
+    pub fn interpolation_alpha(&self) -> f64 {
+        self.interpolation_alpha
+    }
+
+    /// Removes every selected asset along with its animator, keeping the light alive if an asset
+    /// carried it. Non-deletable assets are skipped and stay selected.
+    pub fn delete_selected(&mut self) -> bool {
+        let selected: Vec<String> = self.selection.iter().map(str::to_string).collect();
+        if selected.is_empty() {
+            return false;
+        }
+
+        self.gizmo.end_drag();
+        let mut kept = Vec::new();
+        for id in selected {
+            let handle = self.asset_manager.handle(&id);
+            let mesh = match self.asset_manager.remove(&id) {
+                Ok(mesh) => mesh,
+                Err(remove_error) => {
+                    warn!("Refusing to delete `{id}`: {remove_error}");
+                    kept.push(id);
+                    continue;
+                }
+            };
+
+            if let Some(handle) = handle {
+                self.animation_handler.remove(handle);
+            }
+            if same_shared(&self.light.transform, &mesh.transform) {
+                let standalone_transform = mesh.transform.read_shared(|t| *t);
+                self.light.transform = shared(standalone_transform);
+            }
+        }
+
+        let deleted_any = kept.len() < self.selection.len();
+        self.selection.select_all(kept);
+        deleted_any
+    }
+
+    /// Copies every selected asset next to its original and selects the copies, so repeated
+    /// duplicates fan out in a row.
+    pub fn duplicate_selected(&mut self) -> bool {
+        const DUPLICATE_OFFSET: Vec3 = Vec3::new(1.0, 0.0, 0.0);
+
+        let selected: Vec<String> = self.selection.iter().map(str::to_string).collect();
+        let duplicates: Vec<String> = selected
+            .iter()
+            .filter_map(|id| self.duplicate_asset(id, DUPLICATE_OFFSET))
+            .collect();
+
+        if duplicates.is_empty() {
+            return false;
+        }
+        self.selection.select_all(duplicates);
+        true
+    }
+
+    /// Moves every selected mesh to the next [`ShadingSource`]. The selection follows the first
+    /// mesh's source, so mixed selections line up after one step.
+    pub fn cycle_selected_shading_source(&mut self) -> Option<ShadingSource> {
+        let first = self.selection.iter().next()?;
+        let next = match self.asset_manager.material(first) {
+            Ok(material) => material.shading_source.next(),
+            Err(material_error) => {
+                warn!("Failed to read the material of `{first}`: {material_error}");
+                return None;
+            }
+        };
+        for id in self.selection.iter() {
+            if let Err(material_error) = self
+                .asset_manager
+                .set_material(id, |material| material.shading_source = next)
+            {
+                warn!("Failed to change the shading source of `{id}`: {material_error}");
+            }
+        }
+        debug!("Shading source: {}", next.label());
+        Some(next)
+    }
+
+    /// Copies `id` and its animator, returning the id of the copy.
+    fn duplicate_asset(&mut self, id: &str, offset: Vec3) -> Option<String> {
+        let duplicate = match self.asset_manager.duplicate(id, offset) {
+            Ok(duplicate) => duplicate,
+            Err(duplicate_error) => {
+                warn!("Failed to duplicate `{id}`: {duplicate_error}");
+                return None;
+            }
+        };
+
+        let copy = self.asset_manager.handle(&duplicate.id);
+        let animator = self
+            .asset_manager
+            .handle(id)
+            .and_then(|source| self.animation_handler.get(source))
+            .and_then(|animator| {
+                animator.duplicate(duplicate.id.clone(), duplicate.transform.clone(), offset)
+            });
+        if let (Some(copy), Some(animator)) = (copy, animator) {
+            self.animation_handler.insert(copy, animator);
+        }
+        Some(duplicate.id.0.clone())
+    }
+
+    /// Replaces the scene with the one described by the scene file at `path`. Entries that fail to
+    /// load are skipped and reported together once the rest of the scene is in place.
+    pub async fn load_scene_file(&mut self, path: &Path) -> Result<(), RendererError> {
+        let scene = SceneFile::read(path).await?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        let errors = self.load_scene(&scene, base_dir).await;
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(SceneLoadErrors {
+            path: path.to_path_buf(),
+            errors,
+        }
+        .into())
+    }
+
+    /// Clears the current scene and instantiates `scene`, resolving relative asset paths against
+    /// `base_dir`. Returns the entries that could not be instantiated.
+    pub async fn load_scene(&mut self, scene: &SceneFile, base_dir: &Path) -> Vec<SceneEntryError> {
+        self.clear_scene();
+        let mut errors = Vec::new();
+
+        let mut asset_ids = HashSet::new();
+        for asset in &scene.assets {
+            let entry = format!("asset `{}`", asset.id);
+            if !asset_ids.insert(asset.id.as_str()) {
+                errors.push(SceneEntryError::new(
+                    entry,
+                    "the id is used by an earlier asset",
+                ));
+                continue;
+            }
+            if let Err(import_error) = self
+                .asset_manager
+                .add_from_path(
+                    asset.id.clone(),
+                    asset.light_type.into(),
+                    &base_dir.join(&asset.path),
+                )
+                .await
+            {
+                errors.push(SceneEntryError::new(entry, import_error));
+                continue;
+            }
+            if let Err(place_error) = self
+                .asset_manager
+                .place_asset(&asset.id, &asset.transform.to_transform())
+            {
+                errors.push(SceneEntryError::new(entry, place_error));
+                continue;
+            }
+            let meshes = self.asset_manager.asset_meshes(&asset.id);
+            for scene_mesh in &asset.meshes {
+                let Some(mesh) = meshes.iter().find(|mesh| mesh.id.0 == scene_mesh.id) else {
+                    errors.push(SceneEntryError::new(
+                        format!("mesh `{}`", scene_mesh.id),
+                        format!("asset `{}` has no such mesh", asset.id),
+                    ));
+                    continue;
+                };
+                if let Some(transform) = scene_mesh.transform {
+                    mesh.transform
+                        .write_shared(|current| *current = transform.to_transform());
+                }
+                self.asset_manager
+                    .set_visible(&scene_mesh.id, scene_mesh.visible);
+            }
+        }
+
+        let light = self.scene_light(&scene.lights, &mut errors);
+        if let Err(light_error) = self.set_light(light) {
+            errors.push(SceneEntryError::new("light", light_error));
+        }
+
+        if let Some(camera) = &scene.camera {
+            self.camera_handler
+                .state
+                .stop_camera_animation(&self.camera.id);
+            if let Err(camera_error) = self.camera.look_at(camera.eye, camera.target) {
+                errors.push(SceneEntryError::new("camera", camera_error));
+            }
+            if let Some(Err(fov_error)) = camera
+                .fov_degrees
+                .map(|degrees| self.camera.set_fov_degrees(degrees))
+            {
+                errors.push(SceneEntryError::new("camera", fov_error));
+            }
+        }
+
+        // Animators from the scene file replace the ones imported with their assets.
+        self.adopt_imported_animators();
+        for animator in &scene.animators {
+            let entry = format!("animator for `{}`", animator.target);
+            if let Err(animator_error) = self.add_scene_animator(animator) {
+                errors.push(SceneEntryError::new(entry, animator_error));
+            }
+        }
+        errors
+    }
+
+    /// Writes the current scene to `path`: imported assets with their current transforms and
+    /// visibility, the light, the camera and every animator that can be described. Assets
+    /// uploaded as bytes and duplicated meshes have no file to point at and are skipped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_scene(&self, path: &Path) -> Result<(), RendererError> {
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        self.scene_snapshot(base_dir).write(path)
+    }
+
+    /// The current scene as a scene file whose asset paths are relative to `base_dir` where
+    /// possible.
+    pub fn scene_snapshot(&self, base_dir: &Path) -> SceneFile {
+        let mut exported_meshes = HashSet::new();
+        let mut assets = Vec::new();
+        for asset in self.asset_manager.loaded_assets() {
+            let Some(path) = asset.path else {
+                warn!(
+                    "Not exporting `{}`: it was uploaded as bytes and has no source file",
+                    asset.id
+                );
+                continue;
+            };
+            let meshes = self
+                .asset_manager
+                .asset_meshes(asset.id)
+                .iter()
+                .map(|mesh| {
+                    exported_meshes.insert(mesh.id.0.clone());
+                    SceneMesh {
+                        id: mesh.id.0.clone(),
+                        transform: Some(
+                            mesh.transform
+                                .read_shared(|transform| SceneTransform::from(transform)),
+                        ),
+                        visible: self.asset_manager.is_visible(&mesh.id),
+                    }
+                })
+                .collect();
+            assets.push(SceneAsset {
+                id: asset.id.to_string(),
+                path: path.strip_prefix(base_dir).unwrap_or(path).to_path_buf(),
+                light_type: asset.light_type.into(),
+                transform: SceneTransform::default(),
+                meshes,
+            });
+        }
+        for mesh in self.asset_manager.get_all_assets() {
+            if !exported_meshes.contains(mesh.id.as_str()) {
+                warn!("Not exporting duplicated mesh `{}`", mesh.id.as_str());
+            }
+        }
+
+        let attached_to = self
+            .asset_manager
+            .get_all_assets()
+            .find(|mesh| same_shared(&self.light.transform, &mesh.transform))
+            .map(|mesh| mesh.id.0.clone())
+            .filter(|id| exported_meshes.contains(id));
+        let light = SceneLight {
+            position: self.light.transform.read_shared(|t| t.position),
+            color: self.light.color(),
+            attached_to,
+        };
+
+        let mut animators: Vec<SceneAnimator> = self
+            .animation_handler
+            .iter()
+            .filter_map(|(handle, animator)| {
+                let mesh = self.asset_manager.get_by_handle(handle)?;
+                if !exported_meshes.contains(mesh.id.as_str()) {
+                    return None;
+                }
+                let Some(descriptor) = animator.descriptor() else {
+                    warn!(
+                        "Not exporting the animator of `{}`: its animation cannot be described",
+                        mesh.id.as_str()
+                    );
+                    return None;
+                };
+                Some(SceneAnimator {
+                    target: mesh.id.0.clone(),
+                    speed_multiplier: animator.get_speed_multiplier(),
+                    trajectory: descriptor.into(),
+                })
+            })
+            .collect();
+        animators.sort_by(|a, b| a.target.cmp(&b.target));
+
+        SceneFile {
+            assets,
+            lights: vec![light],
+            camera: Some(SceneCamera {
+                eye: self.camera.eye,
+                target: self.camera.target,
+                fov_degrees: Some(self.camera.fovy.to_degrees()),
+            }),
+            animators,
+        }
+    }
+
+    /// Unloads every asset and animator. The light stays where it was.
+    pub fn clear_scene(&mut self) {
+        self.gizmo.end_drag();
+        self.selection.clear();
+        self.animation_handler.clear();
+        let standalone_transform = self.light.transform.read_shared(|t| *t);
+        self.light.transform = shared(standalone_transform);
+        self.asset_manager.clear();
+    }
+
+    /// The single light the renderer drives. Without one in the file the current light stays.
+    fn scene_light(&self, lights: &[SceneLight], errors: &mut Vec<SceneEntryError>) -> LightSource {
+        for (index, _) in lights.iter().enumerate().skip(1) {
+            errors.push(SceneEntryError::new(
+                format!("light {index}"),
+                "only one light source is supported",
+            ));
+        }
+        let Some(light) = lights.first() else {
+            return self.light.clone();
+        };
+
+        let standalone = || shared(Transform::new(light.position, Quat::IDENTITY, Vec3::ONE));
+        let transform = match &light.attached_to {
+            Some(id) => match self.scene_target(id) {
+                Some(mesh) => mesh.transform.clone(),
+                None => {
+                    errors.push(SceneEntryError::new(
+                        "light",
+                        RendererError::asset_not_found(id),
+                    ));
+                    standalone()
+                }
+            },
+            None => standalone(),
+        };
+        LightSource::new(transform, light.color)
+    }
+
+    /// Reloads the assets whose file changed on disk. A file that no longer parses keeps the
+    /// loaded version on screen.
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    fn reload_changed_assets(&mut self) {
+        let loaded = self.asset_manager.loaded_assets();
+        let changed = self.source_watcher.poll(
+            Instant::now(),
+            loaded
+                .iter()
+                .filter_map(|asset| asset.path.map(|path| (asset.id, path))),
+        );
+        for id in changed {
+            match pollster::block_on(self.asset_manager.reload(&id)) {
+                Ok(()) => log::info!("Reloaded asset `{id}`"),
+                Err(reload_error) => {
+                    error!(
+                        "Failed to reload asset `{id}`, keeping the loaded version: {reload_error}"
+                    )
+                }
+            }
+        }
+    }
+
+    /// Starts playing the glTF animations of assets uploaded since the last call.
+    fn adopt_imported_animators(&mut self) {
+        for (mesh, animator) in self.asset_manager.take_imported_animators() {
+            self.animation_handler.insert(mesh, animator);
+        }
+    }
+
+    fn add_scene_animator(&mut self, animator: &SceneAnimator) -> anyhow::Result<()> {
+        let mesh = self
+            .scene_target(&animator.target)
+            .ok_or_else(|| RendererError::asset_not_found(&animator.target))?;
+        let handle = self
+            .asset_manager
+            .handle(&mesh.id)
+            .ok_or_else(|| RendererError::asset_not_found(mesh.id.as_str()))?;
+        let animation = animator
+            .trajectory
+            .build(mesh.id.clone(), mesh.transform.clone())?;
+        self.animation_handler
+            .insert(handle, Animator::new(animator.speed_multiplier, animation)?);
+        Ok(())
+    }
+
+    /// The mesh a scene entry refers to: the last mesh of the asset `id`, or the mesh `id` itself.
+    fn scene_target(&self, id: &str) -> Option<Arc<RenderMesh>> {
+        self.asset_manager.asset_meshes(id).pop().or_else(|| {
+            self.asset_manager
+                .handle(id)
+                .and_then(|handle| self.asset_manager.get_by_handle(handle))
+                .cloned()
+        })
+    }
+
+    /// Swaps in `light` together with a uniform buffer that follows its transform.
+    fn set_light(&mut self, light: LightSource) -> Result<(), RendererError> {
+        let gpu_light = light
+            .to_gpu()
+            .ok_or_else(|| RendererError::LockContention {
+                id: Self::LIGHT_RESOURCE_ID.to_string(),
+            })?;
+        self.light_uniform_buffer = UniformBuffer::new(
+            UniformBufferId::new(Self::LIGHT_RESOURCE_ID.to_string()),
+            &self.ctx.device,
+            bytes_of(&gpu_light),
+            light.transform.clone(),
+        );
+        self.resources.insert(
+            self.light_uniform_buffer.id(),
+            LightSource::bind_group(
+                &self.ctx.device,
+                &self.light_uniform_buffer,
+                &LightSource::bind_group_layout(&self.ctx.device),
+            ),
+        );
+        self.light = light;
+        Ok(())
+    }
+
+    /// Shows or hides the stats overlay and returns whether it is now shown.
+    pub fn toggle_stats_overlay(&mut self) -> bool {
+        self.settings.show_stats_overlay = !self.settings.show_stats_overlay;
+        self.settings.show_stats_overlay
+    }
+
+    pub fn overlay_lines(&self) -> &OverlayLines {
+        &self.overlay_lines
+    }
+
+    /// Freezes animations and the light while the camera keeps responding to input.
+    pub fn toggle_simulation_pause(&mut self) -> bool {
+        self.animation_handler.toggle_pause()
+    }
+
+    pub fn set_simulation_paused(&mut self, paused: bool) {
+        self.animation_handler.set_paused(paused);
+    }
+
+    pub fn is_simulation_paused(&self) -> bool {
+        self.animation_handler.is_paused()
+    }
+
+    /// Moves every animation to `time` seconds after its start, whether or not the simulation is
+    /// paused, e.g. to render the same moment every run.
+    pub fn seek_animations(&mut self, time: DeltaTime64) {
+        self.animation_handler.seek_all(time);
+        self.light.mark_changed();
+    }
+
+    pub fn placement(&self) -> &PlacementHandler {
+        &self.placement
+    }
+
+    pub fn placement_mut(&mut self) -> &mut PlacementHandler {
+        &mut self.placement
+    }
+
+    pub fn toggle_placement_mode(&mut self) -> bool {
+        let active = self.placement.toggle();
+        debug!(
+            "Placement mode {}",
+            if active { "enabled" } else { "disabled" }
+        );
+        active
+    }
+
+    /// Reads the scene depth under `position` back in the next frame; see
+    /// [`Self::world_position_under_cursor`].
+    pub fn request_world_position(&mut self, position: PhysicalPosition<f64>) {
+        if let Some(texel) = cursor_texel(position, self.ctx.viewport().physical_size()) {
+            self.depth_probe.request(texel);
+        }
+    }
+
+    /// World-space surface point under the position last passed to
+    /// [`Self::request_world_position`], resolved a frame after the request. `None` until then and
+    /// wherever the cursor is over the sky.
+    pub fn world_position_under_cursor(&self) -> Option<Vec3> {
+        self.depth_probe.latest()?.position
+    }
+
+    /// The resolved surface point under `position`, if the last depth probe was taken there.
+    fn surface_point_at(&self, position: PhysicalPosition<f64>) -> Option<Vec3> {
+        let texel = cursor_texel(position, self.ctx.viewport().physical_size())?;
+        self.depth_probe
+            .latest()
+            .filter(|point| point.texel == texel)?
+            .position
+    }
+
+    /// Resolves a placement click to the surface under the cursor, falling back to the ground
+    /// plane where no depth probe was taken or only the sky was hit, and either moves the
+    /// selection there, keeping the selected objects' relative layout, or spawns the configured
+    /// asset there.
+    pub fn place_at_screen_position(&mut self, position: PhysicalPosition<f64>) -> bool {
+        let point = match self.surface_point_at(position) {
+            Some(point) => point,
+            None => {
+                let ray = match self
+                    .camera
+                    .screen_to_ray(position, self.ctx.viewport().physical_size())
+                {
+                    Ok(ray) => ray,
+                    Err(ray_error) => {
+                        warn!("Failed to build placement ray: {ray_error:?}");
+                        return false;
+                    }
+                };
+                let Some(point) = self.placement.ground_point(&ray) else {
+                    debug!("Placement click missed the ground plane");
+                    return false;
+                };
+                point
+            }
+        };
+
+        self.gizmo.end_drag();
+        match self.placement.source().clone() {
+            PlacementSource::Selection => {
+                let Some(primary) = self.selection.selected() else {
+                    return false;
+                };
+                let Some(primary_position) = self
+                    .asset_manager
+                    .get_all_visible_assets()
+                    .find(|mesh| mesh.id.0 == primary)
+                    .map(|mesh| mesh.transform.read_shared(|t| t.position))
+                else {
+                    return false;
+                };
+
+                let offset = point - primary_position;
+                for mesh in self
+                    .asset_manager
+                    .get_all_visible_assets()
+                    .filter(|mesh| self.selection.is_selected(&mesh.id.0))
+                {
+                    mesh.transform.write_shared(|t| t.translate(offset));
+                }
+                true
+            }
+            PlacementSource::Asset(id) => {
+                let Ok(source) = self.asset_manager.get(id.clone()) else {
+                    warn!("Placement asset `{id}` is not loaded");
+                    return false;
+                };
+                let source_position = source.transform.read_shared(|t| t.position);
+                let Some(spawned) = self.duplicate_asset(&id, point - source_position) else {
+                    return false;
+                };
+                self.selection.select(spawned);
+                true
+            }
+        }
+    }
+
+    pub fn nudge_handler_mut(&mut self) -> &mut NudgeHandler {
+        &mut self.nudge_handler
+    }
+
+    pub fn nudge_selected(&mut self, action: NudgeActions, fast: bool) -> bool {
+        if self.gizmo.is_dragging() {
+            return false;
+        }
+
+        self.nudge_handler
+            .nudge(
+                &self.camera,
+                &self.selection,
+                self.asset_manager
+                    .get_all_visible_assets()
+                    .map(|mesh| mesh.as_ref()),
+                action,
+                fast,
+            )
+            .is_some()
+    }
+
+    pub fn gizmo(&self) -> &TransformGizmo {
+        &self.gizmo
+    }
+
+    pub fn begin_gizmo_drag(&mut self, position: PhysicalPosition<f64>) -> bool {
+        self.gizmo.begin_drag(
+            &self.camera,
+            self.ctx.viewport().physical_size(),
+            &self.selection,
+            &SceneGizmoTargets {
+                asset_manager: &self.asset_manager,
+                light: &self.light,
+            },
+            position,
+        )
+    }
+
+    pub fn set_gizmo_mode(&mut self, mode: GizmoMode) {
+        self.gizmo.set_mode(mode);
+    }
+
+    pub fn update_gizmo_drag(&mut self, position: PhysicalPosition<f64>, shift_held: bool) {
+        self.gizmo.update_drag(
+            &self.camera,
+            self.ctx.viewport().physical_size(),
+            &SceneGizmoTargets {
+                asset_manager: &self.asset_manager,
+                light: &self.light,
+            },
+            position,
+            shift_held,
+        );
+        // Nothing else tells the light uniform that the gizmo moved the light.
+        if self.selection.is_selected(Self::LIGHT_SELECTION_ID) {
+            self.light.mark_changed();
+        }
+    }
+
+    pub fn end_gizmo_drag(&mut self) {
+        self.gizmo.end_drag();
+    }
+
+    pub fn cancel_gizmo_drag(&mut self) {
+        self.gizmo.cancel_drag(&SceneGizmoTargets {
+            asset_manager: &self.asset_manager,
+            light: &self.light,
+        });
+        if self.selection.is_selected(Self::LIGHT_SELECTION_ID) {
+            self.light.mark_changed();
+        }
+    }
+
     pub fn material_bind_group_index(model_binding_mode: ModelMatrixBindingMode) -> u32 {
         match model_binding_mode {
             ModelMatrixBindingMode::Immediate => 2,
@@ -361,6 +1611,28 @@ impl SceneRenderer {
         &self.ctx.queue
     }
 
+    /// Renders the scene once more into an offscreen copy of the main view and queues its
+    /// readback. The returned capture can be waited on from another thread.
+    pub fn begin_capture(&mut self) -> PendingCapture {
+        let interpolation_alpha = self.interpolation_alpha;
+        let target = OffscreenTarget::new(&self.ctx.device, self.ctx.size, self.color_format());
+        target.render(self, interpolation_alpha);
+        target.begin_capture(&self.ctx.device, &self.ctx.queue)
+    }
+
+    /// Format the scene pipelines render into; offscreen targets have to use the same one.
+    pub fn color_format(&self) -> TextureFormat {
+        self.ctx.color_format()
+    }
+
+    pub fn backend(&self) -> wgpu::Backend {
+        self.ctx.backend()
+    }
+
+    pub fn gpu_info(&self) -> &GpuInfo {
+        self.ctx.gpu_info()
+    }
+
     pub fn get_surface_configuration(&self) -> &SurfaceConfiguration {
         self.ctx.surface_configuration.as_ref().unwrap()
     }
@@ -369,9 +1641,600 @@ impl SceneRenderer {
         &mut self.ctx
     }
 
-    pub(crate) fn set_camera_aspect_from_size(&mut self, size: Size) {
+    pub fn has_surface(&self) -> bool {
+        self.ctx.surface.is_some()
+    }
+
+    /// Drops the surface on suspend. Scene state, pipelines and GPU resources are kept.
+    pub fn suspend_surface(&mut self) {
+        self.ctx.suspend_surface();
+    }
+
+    /// Recreates the surface for `window` after a resume, reusing the existing device.
+    pub fn recreate_surface(&mut self, window: Arc<Window>) -> Result<(), RendererError> {
+        let provider = WinitSurfaceProvider {
+            window,
+            transparent: self.ctx.is_transparent(),
+        };
+        self.recreate_surface_from(&provider)
+    }
+
+    fn recreate_surface_from<T: SurfaceProvider>(
+        &mut self,
+        provider: &T,
+    ) -> Result<(), RendererError> {
+        self.ctx.recreate_surface(provider)?;
+        self.set_camera_aspect_from_size(self.ctx.size);
+        Ok(())
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.ctx.viewport()
+    }
+
+    /// Applies a new DPI scale factor. The physical size follows through the regular resize.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.ctx.set_scale_factor(scale_factor);
+    }
+
+    pub fn is_device_lost(&self) -> bool {
+        self.ctx.is_device_lost()
+    }
+
+    /// Replaces a lost device with a fresh one for `window`. Transforms, camera, animators and
+    /// selection survive; every GPU resource is uploaded again.
+    pub async fn recover_device(&mut self, window: Arc<Window>) -> Result<(), RendererError> {
+        let transparent = self.ctx.is_transparent();
+        self.ctx.suspend_surface();
+        let ctx = RenderContext::with_settings(
+            Some(WinitSurfaceProvider {
+                window,
+                transparent,
+            }),
+            &self.settings,
+        )
+        .await?;
+        self.rebuild_gpu_resources(ctx)
+    }
+
+    /// Moves the scene onto `ctx`, re-uploading meshes and uniform buffers from CPU-side state.
+    pub(crate) fn rebuild_gpu_resources(
+        &mut self,
+        ctx: RenderContext,
+    ) -> Result<(), RendererError> {
+        self.upload_belt = UploadBelt::new((*ctx.device).clone(), UploadBelt::DEFAULT_CHUNK_SIZE);
+        self.asset_manager.rebuild_gpu_resources(
+            ctx.device.clone(),
+            ctx.queue.clone(),
+            ctx.model_binding_mode,
+            ctx.model_bind_group_layout.clone(),
+            ctx.material_bind_group_layout.clone(),
+            ctx.fallback_textures(),
+        );
+
+        self.camera_uniform.update(&self.camera);
+        self.camera_uniform_buffer = UniformBuffer::new(
+            UniformBufferId::new(Self::CAMERA_RESOURCE_ID.to_string()),
+            &ctx.device,
+            bytes_of(&self.camera_uniform),
+            shared(Transform::default()),
+        );
+        self.resources.insert(
+            self.camera_uniform_buffer.id(),
+            CameraUniform::bind_group(
+                &ctx.device,
+                &self.camera_uniform_buffer,
+                &ctx.camera_bind_group_layout,
+            ),
+        );
+
+        self.gizmo_renderer = GizmoRenderer::new(&ctx);
+        self.outline_renderer = OutlineRenderer::new(&ctx);
+        self.light_billboard_renderer = LightBillboardRenderer::new(&ctx);
+        self.id_picker = IdPicker::new(&ctx);
+        self.depth_probe = DepthProbe::new(&ctx.device);
+        self.text_overlay_renderer = TextOverlayRenderer::new(&ctx);
+        self.ctx = ctx;
+        self.set_light(self.light.clone())?;
+        self.set_camera_aspect_from_size(self.ctx.size);
+
+        let views = std::mem::take(&mut self.views);
+        for (window_id, view) in views {
+            let (window, camera) = view.into_parts();
+            match ViewState::new(&self.ctx, window, camera) {
+                Ok(view) => {
+                    self.views.insert(window_id, view);
+                }
+                Err(error) => warn!("Dropping view {window_id:?} after device loss: {error}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Reconfigures the surface, depth texture and camera aspect for a new window size. Zero-area
+    /// sizes, as reported while minimized, are skipped.
+    pub fn resize(&mut self, size: Size) -> Result<(), RendererError> {
+        if size.is_zero() {
+            debug!(
+                "Skipping resize to zero-area size {}x{}",
+                size.width, size.height
+            );
+            return Ok(());
+        }
+
+        self.set_camera_aspect_from_size(size);
+        self.ctx.resize(size)
+    }
+
+    fn set_camera_aspect_from_size(&mut self, size: Size) {
         if !size.is_zero() {
             self.camera.set_aspect_from_size(size);
         }
     }
 }
+
+/// The gizmo's view of the scene: loaded meshes plus the light, picked through its billboard.
+struct SceneGizmoTargets<'a> {
+    asset_manager: &'a AssetHandler,
+    light: &'a LightSource,
+}
+
+impl GizmoTargets for SceneGizmoTargets<'_> {
+    fn target_transform(&self, id: &str) -> Option<Shared<Transform>> {
+        if id == SceneRenderer::LIGHT_SELECTION_ID {
+            return Some(self.light.transform.clone());
+        }
+        self.asset_manager.target_transform(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use glam::Vec4;
+    use hyakou_core::util::test_support::{assert_quat_eq, assert_vec3_eq};
+
+    use super::*;
+    use crate::renderer::{
+        test_harness::{
+            RenderHarness, allocations::count_allocations, cube_scene, gpu_tests_enabled,
+            sphere_scene, textured_quad_scene,
+        },
+        wrappers::MockSurfaceProvider,
+    };
+
+    #[test]
+    fn suspend_and_failed_resume_preserve_scene_state() {
+        if !gpu_tests_enabled("suspend_and_failed_resume_preserve_scene_state") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        renderer.camera.eye = Vec3::new(3.0, 4.0, 5.0);
+        renderer.update(0.5);
+        let device = renderer.get_device();
+        let eye = renderer.camera.eye;
+        let asset_ids: HashSet<String> = renderer
+            .asset_manager
+            .get_all_loaded_asset_ids()
+            .into_iter()
+            .collect();
+        let animator_count = renderer.animation_handler.len();
+
+        renderer.suspend_surface();
+        assert!(!renderer.has_surface());
+
+        let mut provider = MockSurfaceProvider::new();
+        provider.expect_create_surface().returning(|_| None);
+        provider.expect_get_size().never();
+        assert!(renderer.recreate_surface_from(&provider).is_err());
+
+        assert!(Arc::ptr_eq(&device, &renderer.get_device()));
+        assert_eq!(
+            renderer
+                .asset_manager
+                .get_all_loaded_asset_ids()
+                .into_iter()
+                .collect::<HashSet<_>>(),
+            asset_ids
+        );
+        assert_eq!(renderer.animation_handler.len(), animator_count);
+        assert_eq!(renderer.camera.eye, eye);
+    }
+
+    #[test]
+    fn rough_spheres_spread_the_highlight_wider_than_smooth_ones() {
+        let Some(mut harness) =
+            RenderHarness::new("rough_spheres_spread_the_highlight_wider_than_smooth_ones")
+        else {
+            return;
+        };
+        harness.look_at(Vec3::new(0.0, 0.0, 4.0), Vec3::ZERO);
+
+        // Metals have no diffuse term, so everything above the ambient floor is specular.
+        let mut highlight_pixels = |roughness: f32| {
+            harness.renderer.clear_scene();
+            harness.place_light(Vec3::new(1.5, 1.5, 3.0));
+            harness.add(
+                "Sphere",
+                LightType::LIGHT,
+                sphere_scene(1.0, roughness),
+                Vec3::ZERO,
+            );
+            let frame = harness.render();
+            let peak = frame.brightness().max().unwrap();
+            assert!(peak > 0, "roughness {roughness} rendered nothing");
+            frame
+                .brightness()
+                .filter(|value| *value as u32 * 2 > peak as u32)
+                .count()
+        };
+
+        let smooth = highlight_pixels(0.15);
+        let rough = highlight_pixels(0.8);
+
+        assert!(
+            rough > smooth * 2,
+            "rough highlight covers {rough} pixels, smooth {smooth}"
+        );
+    }
+
+    #[test]
+    fn textured_quad_shows_its_sampled_texels() {
+        let Some(mut harness) = RenderHarness::new("textured_quad_shows_its_sampled_texels") else {
+            return;
+        };
+        harness.look_at(Vec3::new(0.0, 0.0, 2.5), Vec3::ZERO);
+        harness.place_light(Vec3::new(0.0, 0.0, 3.0));
+        harness.add("Quad", LightType::LIGHT, textured_quad_scene(), Vec3::ZERO);
+
+        let frame = harness.render();
+
+        let [red, green, _] = frame.pixel(32, 64).map(u32::from);
+        assert!(red > green * 2, "left half is {:?}", frame.pixel(32, 64));
+        let [red, green, _] = frame.pixel(96, 64).map(u32::from);
+        assert!(green > red * 2, "right half is {:?}", frame.pixel(96, 64));
+    }
+
+    #[test]
+    fn gpu_picking_resolves_the_clicked_mesh_a_frame_later() {
+        let Some(mut harness) =
+            RenderHarness::new("gpu_picking_resolves_the_clicked_mesh_a_frame_later")
+        else {
+            return;
+        };
+        harness.renderer.set_picking_backend(PickingBackend::GpuId);
+        let quad = harness.add("Quad", LightType::LIGHT, textured_quad_scene(), Vec3::ZERO);
+        harness.render();
+
+        let mut click = |x: f64, y: f64| {
+            let renderer = &mut harness.renderer;
+            let before = renderer.selection.selected().map(str::to_owned);
+            let immediate = renderer
+                .select_at_screen_position(PhysicalPosition::new(x, y), false)
+                .map(str::to_owned);
+            assert_eq!(immediate, before, "the pick only lands after a frame");
+            harness.render_until(|renderer| renderer.id_picker.is_busy());
+            harness.renderer.selection.selected().map(str::to_owned)
+        };
+
+        assert_eq!(click(64.0, 64.0), Some(quad.id.0.clone()));
+        assert_eq!(click(2.0, 2.0), None);
+    }
+
+    #[test]
+    fn depth_probe_finds_the_surface_under_the_cursor() {
+        let Some(mut harness) =
+            RenderHarness::new("depth_probe_finds_the_surface_under_the_cursor")
+        else {
+            return;
+        };
+        harness.add("Quad", LightType::LIGHT, textured_quad_scene(), Vec3::ZERO);
+        harness.render();
+
+        let mut probe = |x: f64, y: f64| {
+            harness
+                .renderer
+                .request_world_position(PhysicalPosition::new(x, y));
+            harness.render_until(|renderer| renderer.depth_probe.is_busy());
+            harness.renderer.world_position_under_cursor()
+        };
+
+        let surface = probe(64.0, 64.0).expect("the quad covers the centre");
+        assert!(surface.z.abs() < 1e-2, "quad surface at {surface}");
+        assert!(
+            surface.truncate().length() < 0.1,
+            "quad surface at {surface}"
+        );
+        assert_eq!(probe(2.0, 2.0), None);
+    }
+
+    #[test]
+    fn uniforms_are_only_uploaded_after_a_change() {
+        if !gpu_tests_enabled("uniforms_are_only_uploaded_after_a_change") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        renderer.set_simulation_paused(true);
+        renderer.update(1.0 / 60.0);
+        assert_eq!(renderer.uniform_uploads, 2);
+        renderer.uniform_uploads = 0;
+
+        renderer.update(1.0 / 60.0);
+        assert_eq!(renderer.uniform_uploads, 0);
+
+        renderer.camera.move_camera(5.0, 0.0);
+        renderer.update(1.0 / 60.0);
+        assert_eq!(renderer.uniform_uploads, 1);
+    }
+
+    #[test]
+    fn steady_state_frames_update_and_collect_draws_without_allocating() {
+        let Some(mut harness) =
+            RenderHarness::new("steady_state_frames_update_and_collect_draws_without_allocating")
+        else {
+            return;
+        };
+        for x in [-1.5, 0.0, 1.5] {
+            harness.add(
+                &format!("Cube {x}"),
+                LightType::LIGHT,
+                cube_scene(Vec4::ONE),
+                Vec3::new(x, 0.0, 0.0),
+            );
+        }
+        harness.add(
+            "Marker",
+            LightType::NO_LIGHT,
+            cube_scene(Vec4::ONE),
+            Vec3::Y,
+        );
+        // Warm up buffers, uploads and the draw list's capacity.
+        harness.render_frames(3);
+
+        let renderer = &mut harness.renderer;
+        let ((), allocations) = count_allocations(|| {
+            renderer.update(1.0 / 60.0);
+            renderer.rebuild_draw_list();
+        });
+
+        assert_eq!(allocations, 0);
+        assert_eq!(renderer.draw_list.len(), 4);
+
+        // wgpu allocates while encoding each pass, so recording can't be allocation free; it
+        // has to cost the same every frame rather than grow with what the renderer draws.
+        let first = harness.record_scene_pass();
+        let second = harness.record_scene_pass();
+        assert_eq!(first.0, 4);
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn rebuilding_gpu_resources_keeps_transforms_and_animators() {
+        if !gpu_tests_enabled("rebuilding_gpu_resources_keeps_transforms_and_animators") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        let duplicate_id = renderer
+            .duplicate_asset("Suzanne_0", Vec3::new(2.0, 0.0, 0.0))
+            .unwrap();
+        renderer.camera.eye = Vec3::new(3.0, 4.0, 5.0);
+        renderer.update(0.5);
+
+        let old_device = renderer.get_device();
+        let light_transform = renderer.light.transform.clone();
+        let transforms: HashMap<String, Transform> = renderer
+            .asset_manager
+            .get_all_loaded_asset_ids()
+            .into_iter()
+            .map(|id| {
+                let transform = renderer
+                    .asset_manager
+                    .get(id.clone())
+                    .unwrap()
+                    .transform
+                    .read_shared(|t| *t);
+                (id, transform)
+            })
+            .collect();
+        let animator_count = renderer.animation_handler.len();
+
+        let new_ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        renderer.rebuild_gpu_resources(new_ctx).unwrap();
+
+        assert!(!Arc::ptr_eq(&old_device, &renderer.get_device()));
+        assert!(renderer.asset_manager.contains(&duplicate_id));
+        assert_eq!(
+            renderer.asset_manager.get_all_loaded_asset_ids().len(),
+            transforms.len()
+        );
+        for (id, transform) in &transforms {
+            let rebuilt = renderer.asset_manager.get(id.clone()).unwrap();
+            assert_eq!(
+                rebuilt.transform.read_shared(|t| t.position),
+                transform.position
+            );
+        }
+        assert!(same_shared(
+            &renderer
+                .asset_manager
+                .get("Cube_0".to_string())
+                .unwrap()
+                .transform,
+            &light_transform
+        ));
+        assert_eq!(renderer.animation_handler.len(), animator_count);
+        assert_eq!(renderer.camera.eye, Vec3::new(3.0, 4.0, 5.0));
+        assert!(!renderer.is_device_lost());
+    }
+
+    #[test]
+    fn demo_assets_that_fail_to_load_are_skipped() {
+        if !gpu_tests_enabled("demo_assets_that_fail_to_load_are_skipped") {
+            return;
+        }
+        // Suzanne is corrupt and the light cube is missing altogether.
+        let root = std::env::temp_dir().join(format!("hyako_broken_assets_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("gltf")).unwrap();
+        std::fs::write(root.join("gltf/Suzanne.gltf"), b"not a gltf file").unwrap();
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+
+        let renderer = pollster::block_on(SceneRenderer::from_context(
+            ctx,
+            &AssetRoot::with_override(root.clone()),
+        ));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let mut renderer = renderer.unwrap();
+        assert!(renderer.asset_manager.get_all_loaded_asset_ids().is_empty());
+        assert_vec3_eq(
+            renderer.light().transform.read_shared(|t| t.position),
+            Vec3::new(0.0, 1.0, 1.0),
+            "Light without its cube",
+        );
+        renderer.update(1.0 / 60.0);
+    }
+
+    #[test]
+    fn loading_the_demo_scene_file_matches_the_built_in_scene() {
+        if !gpu_tests_enabled("loading_the_demo_scene_file_matches_the_built_in_scene") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        let demo_ids: HashSet<String> = renderer
+            .asset_manager
+            .get_all_loaded_asset_ids()
+            .into_iter()
+            .collect();
+        let scene_path = AssetRoot::new().join("scenes/demo.ron").unwrap();
+
+        pollster::block_on(renderer.load_scene_file(&scene_path)).unwrap();
+
+        assert_eq!(
+            renderer
+                .asset_manager
+                .get_all_loaded_asset_ids()
+                .into_iter()
+                .collect::<HashSet<_>>(),
+            demo_ids
+        );
+        assert_eq!(renderer.animation_handler.len(), 1);
+        let cube = renderer.asset_manager.get("Cube_0".to_string()).unwrap();
+        assert!(same_shared(&renderer.light.transform, &cube.transform));
+        assert_vec3_eq(
+            cube.transform.read_shared(|t| t.position),
+            Vec3::new(0.0, 1.0, 1.0),
+            "light cube position",
+        );
+        assert_eq!(renderer.camera.eye, Vec3::new(0.0, 0.0, 15.0));
+    }
+
+    #[test]
+    fn scene_entries_that_fail_are_reported_together() {
+        if !gpu_tests_enabled("scene_entries_that_fail_are_reported_together") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        let scene = SceneFile::from_ron_str(
+            r#"(
+                assets: [
+                    (id: "Cube", path: "gltf/Cube.gltf"),
+                    (id: "Missing", path: "gltf/Missing.gltf"),
+                    (id: "Cube", path: "gltf/Suzanne.gltf"),
+                ],
+                lights: [(attached_to: Some("Missing"))],
+                animators: [
+                    (target: "Cube", trajectory: Circular(radius: 1.0, speed: 1.0)),
+                    (target: "Missing", trajectory: Circular(radius: 1.0, speed: 1.0)),
+                    (target: "Cube_0", trajectory: Circular(radius: 0.0, speed: 1.0)),
+                ],
+            )"#,
+        )
+        .unwrap();
+
+        let errors =
+            pollster::block_on(renderer.load_scene(&scene, &AssetRoot::new().resolve().unwrap()));
+
+        let entries: Vec<&str> = errors.iter().map(|error| error.entry.as_str()).collect();
+        assert_eq!(
+            entries,
+            [
+                "asset `Missing`",
+                "asset `Cube`",
+                "light",
+                "animator for `Missing`",
+                "animator for `Cube_0`",
+            ]
+        );
+        assert_eq!(
+            renderer.asset_manager.get_all_loaded_asset_ids(),
+            ["Cube_0"]
+        );
+        assert_eq!(renderer.animation_handler.len(), 1);
+        assert!(renderer.light.to_gpu().is_some());
+    }
+
+    #[test]
+    fn exported_scene_reloads_with_the_same_transforms() {
+        if !gpu_tests_enabled("exported_scene_reloads_with_the_same_transforms") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        let demo_path = AssetRoot::new().join("scenes/demo.ron").unwrap();
+        pollster::block_on(renderer.load_scene_file(&demo_path)).unwrap();
+        let suzanne = renderer.asset_manager.get("Suzanne_0".to_string()).unwrap();
+        suzanne.transform.write_shared(|t| {
+            t.translate(Vec3::new(2.0, -1.0, 0.5));
+            t.rotate(Quat::from_rotation_y(0.75));
+        });
+        renderer.asset_manager.set_visible("Suzanne_0", false);
+        let transforms = |renderer: &SceneRenderer| -> Vec<(String, Transform)> {
+            let mut transforms: Vec<(String, Transform)> = renderer
+                .asset_manager
+                .get_all_assets()
+                .map(|mesh| (mesh.id.0.clone(), mesh.transform.read_shared(|t| *t)))
+                .collect();
+            transforms.sort_by(|a, b| a.0.cmp(&b.0));
+            transforms
+        };
+        let before = transforms(&renderer);
+        let export_path = std::env::temp_dir().join(format!(
+            "hyako_export_{}.ron",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+
+        renderer.export_scene(&export_path).unwrap();
+        renderer.clear_scene();
+        let reloaded = pollster::block_on(renderer.load_scene_file(&export_path));
+        std::fs::remove_file(&export_path).unwrap();
+        reloaded.unwrap();
+
+        let after = transforms(&renderer);
+        assert_eq!(before.len(), after.len());
+        for ((id, expected), (reloaded_id, actual)) in before.iter().zip(&after) {
+            assert_eq!(id, reloaded_id);
+            assert_vec3_eq(actual.position, expected.position, id);
+            assert_quat_eq(actual.rotation, expected.rotation, id);
+            assert_vec3_eq(actual.scale, expected.scale, id);
+        }
+        assert!(!renderer.asset_manager.is_visible("Suzanne_0"));
+        assert_eq!(renderer.animation_handler.len(), 1);
+        let cube = renderer.asset_manager.get("Cube_0".to_string()).unwrap();
+        assert!(same_shared(&renderer.light.transform, &cube.transform));
+    }
+}
@@ -0,0 +1,253 @@
+use std::{path::Path, sync::mpsc::channel};
+
+use anyhow::{Context, Result, anyhow};
+use hyakou_core::types::Size;
+use wgpu::{
+    Buffer, BufferDescriptor, BufferUsages, COPY_BYTES_PER_ROW_ALIGNMENT, CommandEncoder,
+    CommandEncoderDescriptor, Device, Extent3d, MapMode, PollType, Queue, TexelCopyBufferInfo,
+    TexelCopyBufferLayout, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    TextureView, TextureViewDescriptor,
+};
+
+use crate::{
+    gpu::texture::Texture,
+    renderer::{SceneRenderer, frame::FrameTarget},
+};
+
+/// A color and depth target that frames can be rendered into without a window, e.g. for headless
+/// runs and screenshots.
+pub struct OffscreenTarget {
+    color_texture: wgpu::Texture,
+    color_view: TextureView,
+    depth_texture: Texture,
+    format: TextureFormat,
+    size: Size,
+}
+
+/// Tightly packed RGBA8 pixels read back from the GPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl OffscreenTarget {
+    const COLOR_LABEL: &str = "Offscreen Color Texture";
+    const DEPTH_LABEL: &str = "Offscreen Depth Texture";
+    const BYTES_PER_PIXEL: u32 = 4;
+
+    /// `format` has to match the format the renderer's pipelines were built for.
+    pub fn new(device: &Device, size: Size, format: TextureFormat) -> Self {
+        let size = size.clamp_size_for_gpu();
+        let color_texture = device.create_texture(&TextureDescriptor {
+            label: Some(Self::COLOR_LABEL),
+            size: Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&TextureViewDescriptor::default());
+
+        Self {
+            color_texture,
+            color_view,
+            depth_texture: Texture::create_depth_texture(Self::DEPTH_LABEL, device, &size),
+            format,
+            size,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Points a frame at this target, recording into `encoder`.
+    pub fn frame_target<'a>(
+        &'a self,
+        encoder: &'a mut CommandEncoder,
+        queue: &'a Queue,
+    ) -> FrameTarget<'a> {
+        FrameTarget {
+            encoder,
+            queue,
+            color_view: &self.color_view,
+            depth_view: &self.depth_texture.view,
+            depth_texture: &self.depth_texture.texture,
+            size_in_pixels: [self.size.width, self.size.height],
+        }
+    }
+
+    /// Renders one frame of the scene and submits it.
+    pub fn render(&self, renderer: &mut SceneRenderer, interpolation_alpha: f64) {
+        let device = renderer.get_device();
+        let queue = renderer.get_queue().clone();
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Offscreen Render Encoder"),
+        });
+
+        renderer.render_scene(
+            &mut self.frame_target(&mut encoder, &queue),
+            interpolation_alpha,
+        );
+
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Copies the last rendered frame back to the CPU. Blocks until the GPU is done with it.
+    pub fn capture_frame(&self, device: &Device, queue: &Queue) -> Result<CapturedFrame> {
+        self.begin_capture(device, queue).wait(device)
+    }
+
+    /// Queues a copy of the last rendered frame into a readback buffer. Waiting on the returned
+    /// capture can happen on another thread, away from the frame loop.
+    pub fn begin_capture(&self, device: &Device, queue: &Queue) -> PendingCapture {
+        let unpadded_bytes_per_row = self.size.width * Self::BYTES_PER_PIXEL;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT)
+            * COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("Offscreen Readback Buffer"),
+            size: u64::from(padded_bytes_per_row) * u64::from(self.size.height),
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Offscreen Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            self.color_texture.as_image_copy(),
+            TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(self.size.height),
+                },
+            },
+            self.color_texture.size(),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        PendingCapture {
+            buffer,
+            size: self.size,
+            padded_bytes_per_row,
+            swap_red_and_blue: matches!(
+                self.format,
+                TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+            ),
+        }
+    }
+}
+
+/// A frame copy that was submitted but not read back yet.
+pub struct PendingCapture {
+    buffer: Buffer,
+    size: Size,
+    padded_bytes_per_row: u32,
+    swap_red_and_blue: bool,
+}
+
+impl PendingCapture {
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Blocks until the copy has finished and returns the frame as RGBA8.
+    pub fn wait(self, device: &Device) -> Result<CapturedFrame> {
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device
+            .poll(PollType::wait_indefinitely())
+            .context("Failed to wait for the frame readback")?;
+        receiver
+            .recv()
+            .map_err(|_| anyhow!("Frame readback was dropped before it completed"))?
+            .context("Failed to map the frame readback buffer")?;
+
+        let rgba = {
+            let mapped = slice.get_mapped_range();
+            unpad_rows(
+                &mapped,
+                self.padded_bytes_per_row as usize,
+                (self.size.width * OffscreenTarget::BYTES_PER_PIXEL) as usize,
+            )
+        };
+        self.buffer.unmap();
+
+        let mut frame = CapturedFrame {
+            width: self.size.width,
+            height: self.size.height,
+            rgba,
+        };
+        if self.swap_red_and_blue {
+            frame.swap_red_and_blue();
+        }
+        Ok(frame)
+    }
+}
+
+impl CapturedFrame {
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        image::save_buffer(
+            path,
+            &self.rgba,
+            self.width,
+            self.height,
+            image::ExtendedColorType::Rgba8,
+        )
+        .with_context(|| format!("Failed to write frame to `{}`", path.display()))
+    }
+
+    fn swap_red_and_blue(&mut self) {
+        for pixel in self.rgba.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+}
+
+/// Drops the row padding wgpu requires for buffer copies.
+fn unpad_rows(padded: &[u8], padded_bytes_per_row: usize, bytes_per_row: usize) -> Vec<u8> {
+    padded
+        .chunks_exact(padded_bytes_per_row)
+        .flat_map(|row| &row[..bytes_per_row])
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unpad_rows_keeps_only_pixel_bytes() {
+        let padded = [1, 2, 3, 0, 0, 4, 5, 6, 0, 0];
+
+        assert_eq!(unpad_rows(&padded, 5, 3), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_bgra_frame_is_converted_to_rgba() {
+        let mut frame = CapturedFrame {
+            width: 2,
+            height: 1,
+            rgba: vec![10, 20, 30, 255, 40, 50, 60, 128],
+        };
+
+        frame.swap_red_and_blue();
+
+        assert_eq!(frame.rgba, vec![30, 20, 10, 255, 60, 50, 40, 128]);
+    }
+}
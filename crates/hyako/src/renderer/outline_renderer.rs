@@ -0,0 +1,135 @@
+use bytemuck::bytes_of;
+use glam::{Mat4, Vec4};
+use hyakou_core::{
+    shared,
+    traits::BindGroupProvider,
+    types::{ids::UniformBufferId, transform::Transform},
+};
+use wgpu::{
+    BindGroup, BindGroupLayout, Device, Operations, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPipeline, include_wgsl,
+};
+
+use crate::{
+    gpu::{
+        buffers::{outline::OutlineUniform, uniform::UniformBuffer},
+        render_mesh::RenderMesh,
+        render_pipeline::create_outline_render_pipeline,
+        texture::Texture,
+    },
+    renderer::{frame::FrameTarget, renderer_context::RenderContext, settings::RendererSettings},
+};
+
+/// Draws selection outlines as inflated back-face shells around meshes.
+pub struct OutlineRenderer {
+    pipeline: RenderPipeline,
+    outline_bind_group_layout: BindGroupLayout,
+    /// One uniform per outlined mesh, since queued buffer writes only land at submit time.
+    slots: Vec<(UniformBuffer, BindGroup)>,
+}
+
+impl OutlineRenderer {
+    pub fn new(ctx: &RenderContext) -> Self {
+        let outline_bind_group_layout = OutlineUniform::bind_group_layout(&ctx.device);
+        let pipeline_layout = ctx
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Outline Pipeline Layout"),
+                bind_group_layouts: &[
+                    Some(&ctx.camera_bind_group_layout),
+                    Some(&outline_bind_group_layout),
+                ],
+                immediate_size: 0,
+            });
+        let pipeline = create_outline_render_pipeline(
+            &ctx.device,
+            "outline render pass",
+            &pipeline_layout,
+            ctx.color_format(),
+            ctx.device
+                .create_shader_module(include_wgsl!("../../assets/outline.wgsl")),
+            Some(Texture::DEPTH_FORMAT),
+        );
+
+        Self {
+            pipeline,
+            outline_bind_group_layout,
+            slots: Vec::new(),
+        }
+    }
+
+    /// Records a single pass outlining `meshes`; nothing is recorded when there are none.
+    pub fn draw<'a>(
+        &mut self,
+        device: &Device,
+        target: &mut FrameTarget<'_>,
+        camera_bind_group: &BindGroup,
+        meshes: impl IntoIterator<Item = &'a RenderMesh>,
+        settings: &RendererSettings,
+    ) {
+        let meshes: Vec<&RenderMesh> = meshes.into_iter().collect();
+        if meshes.is_empty() {
+            return;
+        }
+
+        while self.slots.len() < meshes.len() {
+            self.slots.push(self.create_slot(device));
+        }
+        for (mesh, (uniform_buffer, _)) in meshes.iter().zip(&self.slots) {
+            let outline_uniform = OutlineUniform::new(
+                mesh.model_matrix(),
+                settings.outline_color,
+                settings.outline_thickness,
+            );
+            target
+                .queue
+                .write_buffer(uniform_buffer, 0, bytes_of(&outline_uniform));
+        }
+
+        let mut render_pass = target.encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Outline Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target.color_view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            multiview_mask: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: target.depth_view,
+                depth_ops: Some(Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        for (mesh, (_, bind_group)) in meshes.iter().zip(&self.slots) {
+            render_pass.set_bind_group(1, bind_group, &[]);
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+            render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+    }
+
+    fn create_slot(&self, device: &Device) -> (UniformBuffer, BindGroup) {
+        let outline_uniform = OutlineUniform::new(Mat4::IDENTITY, Vec4::ZERO, 0.0);
+        let uniform_buffer = UniformBuffer::new(
+            UniformBufferId::new(format!("Outline Buffer {}", self.slots.len())),
+            device,
+            bytes_of(&outline_uniform),
+            shared(Transform::default()),
+        );
+        let bind_group =
+            OutlineUniform::bind_group(device, &uniform_buffer, &self.outline_bind_group_layout);
+        (uniform_buffer, bind_group)
+    }
+}
@@ -1,34 +1,63 @@
-use std::sync::Arc;
+use std::{
+    cell::OnceCell,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
-use anyhow::{Result, anyhow};
 use hyakou_core::{
     components::light::LightSource,
     traits::BindGroupProvider,
-    types::{ModelMatrixBindingMode, Size},
+    types::{ModelMatrixBindingMode, Size, viewport::Viewport},
 };
-use log::warn;
+use log::{debug, error, info, warn};
 use wgpu::{
-    Backends, BindGroupLayout, Device, DeviceDescriptor, ExperimentalFeatures, Features,
-    FeaturesWebGPU, Instance, InstanceDescriptor, InstanceFlags, Limits, MemoryHints, Queue,
-    RenderPipeline, RequestAdapterOptions, Surface, SurfaceConfiguration, TextureFormat,
-    TextureUsages, include_wgsl,
+    Adapter, Backend, Backends, BindGroupLayout, CompositeAlphaMode, Device, DeviceDescriptor,
+    ExperimentalFeatures, Features, FeaturesWebGPU, Instance, InstanceDescriptor, InstanceFlags,
+    Limits, MemoryHints, PresentMode, Queue, RenderPipeline, RequestAdapterOptions, Surface,
+    SurfaceConfiguration, TextureFormat, TextureUsages, include_wgsl,
 };
 
 use crate::{
     gpu::{
-        buffers::camera_buffer::CameraUniform, buffers::model_matrix::ModelMatrixUniform,
-        material::GpuMaterial, render_pipeline::create_render_pipeline, texture::Texture,
+        buffers::camera_buffer::CameraUniform,
+        buffers::model_matrix::ModelMatrixUniform,
+        buffers::pool::BufferPool,
+        material::GpuMaterial,
+        render_pipeline::{
+            create_instanced_render_pipeline, create_render_pipeline,
+            create_wireframe_render_pipeline,
+        },
+        texture::{FallbackTextures, Texture},
+    },
+    renderer::{
+        error::RendererError,
+        gpu_info::{Capability, GpuInfo, optional_features},
+        settings::RendererSettings,
+        wrappers::SurfaceProvider,
     },
-    renderer::wrappers::SurfaceProvider,
 };
 
 pub struct RenderContext {
     pub instance: Instance,
+    adapter: Adapter,
+    gpu_info: GpuInfo,
+    transparent: bool,
+    present_mode: PresentMode,
+    device_lost: Arc<AtomicBool>,
+    scale_factor: f64,
     pub surface: Option<Surface<'static>>,
     pub surface_configuration: Option<SurfaceConfiguration>,
     pub device: Arc<Device>,
     pub light_render_pipeline: RenderPipeline,
+    /// Metallic-roughness shading for lit meshes, see `RendererSettings::pbr_shading`.
+    pub pbr_render_pipeline: RenderPipeline,
     pub no_light_render_pipeline: RenderPipeline,
+    /// Edges-only variant of the light pipeline, see `RendererSettings::wireframe`. `None` when
+    /// the adapter cannot draw lines.
+    pub wireframe_render_pipeline: Option<RenderPipeline>,
+    pub instanced_render_pipeline: RenderPipeline,
     pub size: Size,
     pub camera_bind_group_layout: BindGroupLayout,
     pub light_bind_group_layout: BindGroupLayout,
@@ -36,6 +65,8 @@ pub struct RenderContext {
     pub material_bind_group_layout: BindGroupLayout,
     pub model_binding_mode: ModelMatrixBindingMode,
     pub depth_texture: Texture,
+    fallback_textures: OnceCell<Arc<FallbackTextures>>,
+    pub buffer_pool: BufferPool,
     pub queue: Queue,
 }
 
@@ -43,25 +74,34 @@ impl RenderContext {
     const IMMEDIATE_MODEL_MATRIX_SIZE: u32 = 64;
     const DEPTH_TEXTURE_LABEL: &str = "Depth Texture";
 
-    pub async fn new<T>(provider: Option<T>) -> Result<Self>
+    pub async fn new<T>(provider: Option<T>) -> Result<Self, RendererError>
     where
         T: SurfaceProvider,
     {
-        #[cfg(target_os = "macos")]
-        let backends = Backends::METAL;
-
-        #[cfg(target_arch = "wasm32")]
-        let backends = Backends::all();
+        Self::with_settings(provider, &RendererSettings::default()).await
+    }
 
-        #[cfg(all(not(target_os = "macos"), not(target_arch = "wasm32")))]
-        let backends = Backends::PRIMARY;
-        // #[cfg(target_os = "linux")]
-        // let backends = Backends::PRIMARY;
+    /// Creates the context with the backend and present mode from `settings`.
+    pub async fn with_settings<T>(
+        provider: Option<T>,
+        settings: &RendererSettings,
+    ) -> Result<Self, RendererError>
+    where
+        T: SurfaceProvider,
+    {
+        let backends = match settings.backend.backends() {
+            Some(backends) => backends,
+            None => default_backends().await,
+        };
+        if settings.msaa_samples != 1 {
+            warn!(
+                "Ignoring msaa_samples = {}, multisampling is not supported yet",
+                settings.msaa_samples
+            );
+        }
+        let present_mode = settings.present_mode();
 
-        let mut instance_descriptor = InstanceDescriptor::new_without_display_handle();
-        instance_descriptor.backends = backends;
-        instance_descriptor.flags = InstanceFlags::debugging();
-        let instance = wgpu::Instance::new(instance_descriptor);
+        let instance = create_instance(backends);
 
         let surface = match provider.as_ref() {
             Some(prov) => prov.create_surface(&instance),
@@ -74,11 +114,19 @@ impl RenderContext {
                 force_fallback_adapter: false,
                 compatible_surface: surface.as_ref(),
             })
-            .await?;
-
-        let model_binding_mode = select_model_binding_mode(&adapter);
-        let required_features = required_features_for(model_binding_mode);
-        let required_limits = required_limits_for(model_binding_mode);
+            .await
+            .map_err(RendererError::device_request)?;
+        let backend = adapter.get_info().backend;
+
+        let model_binding_mode = select_model_binding_mode(
+            backend,
+            adapter
+                .features()
+                .features_webgpu
+                .contains(FeaturesWebGPU::IMMEDIATES),
+        );
+        let required_features = required_features_for(model_binding_mode, adapter.features());
+        let required_limits = required_limits_for(backend, model_binding_mode, adapter.limits());
 
         let (device, queue) = adapter
             .request_device(&DeviceDescriptor {
@@ -89,10 +137,33 @@ impl RenderContext {
                 memory_hints: MemoryHints::MemoryUsage,
                 trace: wgpu::Trace::Off,
             })
-            .await?;
-
+            .await
+            .map_err(RendererError::device_request)?;
+
+        let gpu_info = GpuInfo {
+            adapter: adapter.get_info(),
+            features: device.features(),
+            limits: device.limits(),
+            downlevel_flags: adapter.get_downlevel_capabilities().flags,
+        };
+        info!("Using GPU {gpu_info}");
+
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let lost_flag = device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            error!("GPU device lost ({reason:?}): {message}");
+            lost_flag.store(true, Ordering::SeqCst);
+        });
         let device = Arc::new(device);
 
+        let transparent = provider
+            .as_ref()
+            .is_some_and(|provider| provider.is_transparent());
+        let scale_factor = provider
+            .as_ref()
+            .map_or(Viewport::DEFAULT_SCALE_FACTOR, |provider| {
+                provider.scale_factor()
+            });
         let size = if provider.is_some() {
             provider.unwrap().get_size()
         } else {
@@ -103,9 +174,14 @@ impl RenderContext {
         };
 
         let surface_configuration = match surface.as_ref() {
-            Some(surface_ref) => {
-                init_surface_configuration(Some(surface_ref), adapter, size, &device)
-            }
+            Some(surface_ref) => init_surface_configuration(
+                Some(surface_ref),
+                &adapter,
+                size,
+                &device,
+                transparent,
+                present_mode,
+            ),
             None => None,
         };
 
@@ -170,15 +246,67 @@ impl RenderContext {
             Some(TextureFormat::Depth32Float),
         );
 
+        let pbr_render_pipeline = create_render_pipeline(
+            &device,
+            "pbr render pass",
+            &render_pipeline_layout,
+            format,
+            create_pbr_shader_module(&device, model_binding_mode),
+            Some(TextureFormat::Depth32Float),
+        );
+
+        let wireframe_render_pipeline = gpu_info.supports(Capability::PolygonModeLine).then(|| {
+            create_wireframe_render_pipeline(
+                &device,
+                "wireframe render pass",
+                &render_pipeline_layout,
+                format,
+                create_light_shader_module(&device, model_binding_mode),
+                Some(TextureFormat::Depth32Float),
+            )
+        });
+
+        // Instances carry their own model matrix, so this layout never has a model group or
+        // immediates, whatever the model binding mode is.
+        let instanced_render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Instanced Render Pipeline Layout"),
+                bind_group_layouts: &[
+                    Some(&camera_bind_group_layout),
+                    Some(&light_bind_group_layout),
+                    Some(&material_bind_group_layout),
+                ],
+                immediate_size: 0,
+            });
+        let instanced_render_pipeline = create_instanced_render_pipeline(
+            &device,
+            "instanced render pass",
+            &instanced_render_pipeline_layout,
+            format,
+            device.create_shader_module(include_wgsl!("../../assets/vertex_instanced.wgsl")),
+            Some(TextureFormat::Depth32Float),
+        );
+
         Ok(Self {
             instance,
+            adapter,
+            gpu_info,
+            transparent,
+            present_mode,
+            device_lost,
+            scale_factor,
             surface,
             surface_configuration,
             device,
             light_render_pipeline,
+            pbr_render_pipeline,
             no_light_render_pipeline,
+            wireframe_render_pipeline,
+            instanced_render_pipeline,
             size,
             depth_texture,
+            fallback_textures: OnceCell::new(),
+            buffer_pool: BufferPool::default(),
             light_bind_group_layout,
             camera_bind_group_layout,
             model_bind_group_layout,
@@ -188,9 +316,148 @@ impl RenderContext {
         })
     }
 
-    pub fn resize(&mut self, size: Size) -> Result<()> {
-        self.size = size;
+    pub fn color_format(&self) -> TextureFormat {
+        self.surface_configuration
+            .as_ref()
+            .map(|configuration| configuration.format)
+            .unwrap_or(TextureFormat::Bgra8UnormSrgb)
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// Reconfigures the main surface, and every surface created afterwards, with `present_mode`.
+    /// The `Auto*` modes are supported everywhere; others fall back to FIFO if the surface lacks
+    /// them.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) {
+        let present_mode = self.supported_present_mode(present_mode);
+        self.present_mode = present_mode;
+        let (Some(surface), Some(surface_configuration)) =
+            (self.surface.as_ref(), self.surface_configuration.as_mut())
+        else {
+            return;
+        };
+        surface_configuration.present_mode = present_mode;
+        if !self.size.is_zero() {
+            surface.configure(&self.device, surface_configuration);
+        }
+    }
+
+    fn supported_present_mode(&self, present_mode: PresentMode) -> PresentMode {
+        let Some(surface) = self.surface.as_ref() else {
+            return present_mode;
+        };
+        resolve_present_mode(
+            &surface.get_capabilities(&self.adapter).present_modes,
+            present_mode,
+        )
+    }
+
+    /// Physical surface size and DPI scale factor. Picking and cursor math use this so they agree
+    /// with the surface the frame is rendered into.
+    pub fn viewport(&self) -> Viewport {
+        Viewport::new(self.size, self.scale_factor)
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = Viewport::new(self.size, scale_factor).scale_factor();
+    }
+
+    /// The graphics API the adapter was obtained for, e.g. WebGPU or the WebGL2 fallback on the
+    /// web.
+    pub fn backend(&self) -> Backend {
+        self.gpu_info.adapter.backend
+    }
+
+    /// Adapter, granted features and limits of the device this context renders with.
+    pub fn gpu_info(&self) -> &GpuInfo {
+        &self.gpu_info
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.gpu_info.supports(capability)
+    }
+
+    /// Created on first use and shared by every material after that.
+    pub fn fallback_textures(&self) -> Arc<FallbackTextures> {
+        self.fallback_textures
+            .get_or_init(|| Arc::new(FallbackTextures::new(&self.device, &self.queue)))
+            .clone()
+    }
+
+    /// Set by the device-lost callback. A lost device never comes back, so the whole context has
+    /// to be rebuilt.
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::SeqCst)
+    }
+
+    /// Releases the surface while the app is suspended. Device, queue and pipelines stay alive,
+    /// and the last configuration is kept so the surface can be recreated with the same format.
+    pub fn suspend_surface(&mut self) {
+        if self.surface.take().is_some() {
+            debug!("Released render surface for suspend");
+        }
+    }
+
+    /// Creates a new surface for `provider` on the existing device, e.g. after a resume.
+    pub fn recreate_surface<T>(&mut self, provider: &T) -> Result<(), RendererError>
+    where
+        T: SurfaceProvider,
+    {
+        let (surface, surface_configuration, size) = self.create_configured_surface(provider)?;
+
+        self.surface = Some(surface);
+        self.surface_configuration = Some(surface_configuration);
+        self.set_scale_factor(provider.scale_factor());
+        if !size.is_zero() {
+            self.size = size;
+            self.depth_texture =
+                Texture::create_depth_texture(Self::DEPTH_TEXTURE_LABEL, &self.device, &self.size);
+        }
+        Ok(())
+    }
+
+    /// Creates and configures a surface for `provider` that the existing pipelines can render
+    /// into. Fails if the surface prefers a different format than the pipelines were built for.
+    pub fn create_configured_surface<T>(
+        &self,
+        provider: &T,
+    ) -> Result<(Surface<'static>, SurfaceConfiguration, Size), RendererError>
+    where
+        T: SurfaceProvider,
+    {
+        let surface = provider.create_surface(&self.instance).ok_or_else(|| {
+            RendererError::surface("the surface provider could not create a surface")
+        })?;
+        let size = provider.get_size();
+        let surface_configuration = init_surface_configuration(
+            Some(&surface),
+            &self.adapter,
+            size,
+            &self.device,
+            self.transparent,
+            self.present_mode,
+        )
+        .ok_or_else(|| RendererError::surface("the new surface could not be configured"))?;
+
+        let pipeline_format = self.color_format();
+        if surface_configuration.format != pipeline_format {
+            return Err(RendererError::surface(format!(
+                "the surface uses {:?}, but pipelines were built for {:?}",
+                surface_configuration.format, pipeline_format
+            )));
+        }
+
+        Ok((surface, surface_configuration, size))
+    }
 
+    /// Zero-area sizes (a minimized window) are ignored so the last usable size is kept.
+    pub fn resize(&mut self, size: Size) -> Result<(), RendererError> {
         if size.is_zero() {
             warn!(
                 "Ignoring resize because wgpu surfaces cannot be configured with zero width or height: {}x{}",
@@ -199,6 +466,8 @@ impl RenderContext {
             return Ok(());
         }
 
+        self.size = size;
+
         let Some(surface) = self.surface.as_ref() else {
             self.depth_texture =
                 Texture::create_depth_texture(Self::DEPTH_TEXTURE_LABEL, &self.device, &self.size);
@@ -206,8 +475,8 @@ impl RenderContext {
         };
 
         let Some(surface_configuration) = self.surface_configuration.as_mut() else {
-            return Err(anyhow!(
-                "Cannot resize render surface because the surface configuration is missing"
+            return Err(RendererError::surface(
+                "cannot resize the render surface because its configuration is missing",
             ));
         };
 
@@ -221,39 +490,90 @@ impl RenderContext {
     }
 }
 
-fn select_model_binding_mode(adapter: &wgpu::Adapter) -> ModelMatrixBindingMode {
+async fn default_backends() -> Backends {
+    #[cfg(target_os = "macos")]
+    let backends = Backends::METAL;
+
     #[cfg(target_arch = "wasm32")]
-    {
-        let _ = adapter;
-        ModelMatrixBindingMode::Uniform
+    let backends = select_web_backends().await;
+
+    #[cfg(all(not(target_os = "macos"), not(target_arch = "wasm32")))]
+    let backends = Backends::PRIMARY;
+
+    backends
+}
+
+fn resolve_present_mode(available: &[PresentMode], requested: PresentMode) -> PresentMode {
+    match requested {
+        PresentMode::AutoVsync | PresentMode::AutoNoVsync => requested,
+        _ if available.contains(&requested) => requested,
+        _ => {
+            warn!("Present mode {requested:?} is not supported by the surface, using Fifo");
+            PresentMode::Fifo
+        }
     }
+}
 
-    #[cfg(not(target_arch = "wasm32"))]
+fn create_instance(backends: Backends) -> Instance {
+    let mut instance_descriptor = InstanceDescriptor::new_without_display_handle();
+    instance_descriptor.backends = backends;
+    instance_descriptor.flags = InstanceFlags::debugging();
+    Instance::new(instance_descriptor)
+}
+
+/// WebGPU when the browser hands out an adapter, WebGL2 otherwise. Probes without a surface, since
+/// a canvas that gave out a `webgpu` context can no longer be used for `webgl2`.
+#[cfg(target_arch = "wasm32")]
+async fn select_web_backends() -> Backends {
+    let probe = create_instance(Backends::BROWSER_WEBGPU);
+    match probe
+        .request_adapter(&RequestAdapterOptions::default())
+        .await
     {
-        let supported_features = adapter.features();
-        if supported_features
-            .features_webgpu
-            .contains(FeaturesWebGPU::IMMEDIATES)
-        {
-            ModelMatrixBindingMode::Immediate
-        } else {
-            ModelMatrixBindingMode::Uniform
+        Ok(_) => Backends::BROWSER_WEBGPU,
+        Err(error) => {
+            warn!("WebGPU is unavailable ({error}), falling back to WebGL2");
+            Backends::GL
         }
     }
 }
 
-fn required_features_for(model_binding_mode: ModelMatrixBindingMode) -> Features {
-    if model_binding_mode == ModelMatrixBindingMode::Immediate {
-        Features {
-            features_webgpu: FeaturesWebGPU::IMMEDIATES,
-            ..Default::default()
-        }
+/// Immediates are only used on native backends that support them; WebGPU in the browser and
+/// WebGL2 upload model matrices through uniforms.
+fn select_model_binding_mode(
+    backend: Backend,
+    supports_immediates: bool,
+) -> ModelMatrixBindingMode {
+    let browser_backend = matches!(backend, Backend::BrowserWebGpu | Backend::Gl);
+    if supports_immediates && !browser_backend {
+        ModelMatrixBindingMode::Immediate
     } else {
-        Features::default()
+        ModelMatrixBindingMode::Uniform
+    }
+}
+
+fn required_features_for(
+    model_binding_mode: ModelMatrixBindingMode,
+    adapter_features: Features,
+) -> Features {
+    let mut features = optional_features(adapter_features);
+    if model_binding_mode == ModelMatrixBindingMode::Immediate {
+        features.features_webgpu |= FeaturesWebGPU::IMMEDIATES;
     }
+    features
 }
 
-fn required_limits_for(model_binding_mode: ModelMatrixBindingMode) -> Limits {
+/// GL only guarantees the WebGL2 downlevel limits; texture sizes are raised to what the adapter
+/// actually offers so HiDPI canvases still fit.
+fn required_limits_for(
+    backend: Backend,
+    model_binding_mode: ModelMatrixBindingMode,
+    adapter_limits: Limits,
+) -> Limits {
+    if backend == Backend::Gl {
+        return Limits::downlevel_webgl2_defaults().using_resolution(adapter_limits);
+    }
+
     if model_binding_mode == ModelMatrixBindingMode::Immediate {
         Limits {
             max_immediate_size: RenderContext::IMMEDIATE_MODEL_MATRIX_SIZE,
@@ -278,6 +598,20 @@ fn create_light_shader_module(
     }
 }
 
+fn create_pbr_shader_module(
+    device: &Device,
+    model_binding_mode: ModelMatrixBindingMode,
+) -> wgpu::ShaderModule {
+    match model_binding_mode {
+        ModelMatrixBindingMode::Immediate => {
+            device.create_shader_module(include_wgsl!("../../assets/pbr.wgsl"))
+        }
+        ModelMatrixBindingMode::Uniform => {
+            device.create_shader_module(include_wgsl!("../../assets/pbr_uniform.wgsl"))
+        }
+    }
+}
+
 fn create_no_light_shader_module(
     device: &Device,
     model_binding_mode: ModelMatrixBindingMode,
@@ -292,15 +626,36 @@ fn create_no_light_shader_module(
     }
 }
 
+/// Transparent windows need an alpha mode that blends with the desktop; opaque ones prefer
+/// `Opaque`. Falls back to the first supported mode.
+fn select_alpha_mode(available: &[CompositeAlphaMode], transparent: bool) -> CompositeAlphaMode {
+    let preferred: &[CompositeAlphaMode] = if transparent {
+        &[
+            CompositeAlphaMode::PreMultiplied,
+            CompositeAlphaMode::PostMultiplied,
+            CompositeAlphaMode::Inherit,
+        ]
+    } else {
+        &[CompositeAlphaMode::Opaque]
+    };
+    preferred
+        .iter()
+        .copied()
+        .find(|mode| available.contains(mode))
+        .unwrap_or(available[0])
+}
+
 fn init_surface_configuration(
     surface: Option<&Surface<'static>>,
-    adapter: wgpu::Adapter,
+    adapter: &Adapter,
     size: Size,
     device: &Device,
+    transparent: bool,
+    present_mode: PresentMode,
 ) -> Option<wgpu::wgt::SurfaceConfiguration<Vec<wgpu::TextureFormat>>> {
     let surface_configuration = match surface {
         Some(surface) => {
-            let capabilities = surface.get_capabilities(&adapter);
+            let capabilities = surface.get_capabilities(adapter);
             let format = capabilities
                 .formats
                 .iter()
@@ -315,9 +670,9 @@ fn init_surface_configuration(
                 format,
                 width: configured_size.width,
                 height: configured_size.height,
-                present_mode: capabilities.present_modes[0],
+                present_mode: resolve_present_mode(&capabilities.present_modes, present_mode),
                 desired_maximum_frame_latency: 2,
-                alpha_mode: capabilities.alpha_modes[0],
+                alpha_mode: select_alpha_mode(&capabilities.alpha_modes, transparent),
                 view_formats: vec![],
             };
 
@@ -334,17 +689,180 @@ fn init_surface_configuration(
 
 #[cfg(test)]
 mod tests {
-    use crate::renderer::{renderer_context::RenderContext, wrappers::MockSurfaceProvider};
+    use std::sync::Arc;
+
+    use hyakou_core::types::{ModelMatrixBindingMode, Size};
+    use wgpu::{Backend, CompositeAlphaMode, Limits, PresentMode, TextureFormat};
+
+    use super::{
+        required_limits_for, resolve_present_mode, select_alpha_mode, select_model_binding_mode,
+    };
+
+    use crate::{
+        gpu::material::{GpuMaterial, Material},
+        renderer::{
+            gpu_info::Capability, renderer_context::RenderContext, test_harness::gpu_tests_enabled,
+            wrappers::MockSurfaceProvider,
+        },
+    };
 
     #[test]
     fn create_context() {
-        if std::env::var("HYAKOU_RUN_GPU_TESTS").ok().as_deref() != Some("1") {
-            eprintln!(
-                "Skipping GPU-dependent test create_context; set HYAKOU_RUN_GPU_TESTS=1 to enable."
-            );
+        if !gpu_tests_enabled("create_context") {
             return;
         }
         let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None));
         assert!(ctx.is_ok());
     }
+
+    #[test]
+    fn gpu_info_reports_the_granted_device_features() {
+        if !gpu_tests_enabled("gpu_info_reports_the_granted_device_features") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let gpu_info = ctx.gpu_info();
+
+        assert_eq!(gpu_info.features, ctx.device.features());
+        assert_eq!(gpu_info.adapter.backend, ctx.backend());
+        assert_eq!(
+            ctx.supports(Capability::PushConstants),
+            ctx.model_binding_mode == ModelMatrixBindingMode::Immediate
+        );
+    }
+
+    #[test]
+    fn fallback_textures_are_shared_and_bind_for_textureless_materials() {
+        if !gpu_tests_enabled("fallback_textures_are_shared_and_bind_for_textureless_materials") {
+            return;
+        }
+        let ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let fallbacks = ctx.fallback_textures();
+        let material = GpuMaterial::new(
+            &ctx.device,
+            &ctx.material_bind_group_layout,
+            "Textureless Material",
+            &Material::default(),
+            fallbacks.base_color.clone(),
+            fallbacks.metallic_roughness.clone(),
+        );
+
+        assert!(Arc::ptr_eq(&fallbacks, &ctx.fallback_textures()));
+        assert!(Arc::ptr_eq(&material.texture, &fallbacks.base_color));
+        assert_eq!(fallbacks.normal.texture.format(), TextureFormat::Rgba8Unorm);
+        assert_eq!(fallbacks.emissive.texture.width(), 1);
+    }
+
+    #[test]
+    fn resize_updates_size_and_ignores_zero_area() {
+        if !gpu_tests_enabled("resize_updates_size_and_ignores_zero_area") {
+            return;
+        }
+        let mut ctx = pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)).unwrap();
+        let resized = Size {
+            width: 640,
+            height: 360,
+        };
+
+        ctx.resize(resized).unwrap();
+        assert_eq!(ctx.size, resized);
+        assert_eq!(ctx.depth_texture.texture.width(), 640);
+        assert_eq!(ctx.depth_texture.texture.height(), 360);
+
+        ctx.resize(Size {
+            width: 0,
+            height: 360,
+        })
+        .unwrap();
+        assert_eq!(ctx.size, resized);
+    }
+
+    #[test]
+    fn select_alpha_mode_prefers_blending_modes_for_transparent_windows() {
+        let available = [
+            CompositeAlphaMode::Opaque,
+            CompositeAlphaMode::PostMultiplied,
+        ];
+
+        assert_eq!(
+            select_alpha_mode(&available, true),
+            CompositeAlphaMode::PostMultiplied
+        );
+        assert_eq!(
+            select_alpha_mode(&available, false),
+            CompositeAlphaMode::Opaque
+        );
+        assert_eq!(
+            select_alpha_mode(&[CompositeAlphaMode::Opaque], true),
+            CompositeAlphaMode::Opaque
+        );
+    }
+
+    #[test]
+    fn resolve_present_mode_falls_back_to_fifo_for_unsupported_modes() {
+        let available = [PresentMode::Fifo, PresentMode::Immediate];
+
+        assert_eq!(
+            resolve_present_mode(&available, PresentMode::AutoNoVsync),
+            PresentMode::AutoNoVsync
+        );
+        assert_eq!(
+            resolve_present_mode(&available, PresentMode::Immediate),
+            PresentMode::Immediate
+        );
+        assert_eq!(
+            resolve_present_mode(&available, PresentMode::Mailbox),
+            PresentMode::Fifo
+        );
+    }
+
+    #[test]
+    fn browser_backends_never_use_immediates() {
+        assert_eq!(
+            select_model_binding_mode(Backend::Vulkan, true),
+            ModelMatrixBindingMode::Immediate
+        );
+        assert_eq!(
+            select_model_binding_mode(Backend::Vulkan, false),
+            ModelMatrixBindingMode::Uniform
+        );
+        assert_eq!(
+            select_model_binding_mode(Backend::BrowserWebGpu, true),
+            ModelMatrixBindingMode::Uniform
+        );
+        assert_eq!(
+            select_model_binding_mode(Backend::Gl, true),
+            ModelMatrixBindingMode::Uniform
+        );
+    }
+
+    #[test]
+    fn webgl2_fallback_requests_downlevel_limits_up_to_the_adapter_resolution() {
+        let adapter_limits = Limits {
+            max_texture_dimension_2d: 4096,
+            ..Limits::downlevel_webgl2_defaults()
+        };
+
+        let limits =
+            required_limits_for(Backend::Gl, ModelMatrixBindingMode::Uniform, adapter_limits);
+
+        assert_eq!(limits.max_texture_dimension_2d, 4096);
+        assert_eq!(limits.max_immediate_size, 0);
+        assert!(limits.max_texture_dimension_2d < Limits::default().max_texture_dimension_2d);
+        assert_eq!(
+            limits.max_storage_buffers_per_shader_stage,
+            Limits::downlevel_webgl2_defaults().max_storage_buffers_per_shader_stage
+        );
+    }
+
+    #[test]
+    fn webgpu_keeps_default_limits() {
+        let limits = required_limits_for(
+            Backend::BrowserWebGpu,
+            ModelMatrixBindingMode::Uniform,
+            Limits::default(),
+        );
+
+        assert_eq!(limits, Limits::default());
+    }
 }
@@ -0,0 +1,474 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use glam::{Quat, Vec3};
+use hyakou_core::{
+    Shared,
+    animations::{
+        Animation, NEUTRAL_SPEED, TrajectoryDescriptor,
+        trajectory::{circular::CircularTrajectory, linear::LinearTrajectory},
+    },
+    components::LightType,
+    types::{ids::MeshId, transform::Transform},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::renderer::error::RendererError;
+
+/// Declarative description of a scene: the assets to import, the light, the camera's initial pose
+/// and the animators moving the assets. Stored as RON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct SceneFile {
+    pub assets: Vec<SceneAsset>,
+    pub lights: Vec<SceneLight>,
+    pub camera: Option<SceneCamera>,
+    pub animators: Vec<SceneAnimator>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneAsset {
+    pub id: String,
+    /// glTF file, relative to the scene file unless absolute.
+    pub path: PathBuf,
+    #[serde(default)]
+    pub light_type: SceneLightType,
+    /// Places the whole asset; node transforms from the file are kept underneath it.
+    #[serde(default)]
+    pub transform: SceneTransform,
+    /// Per-mesh state applied after `transform`, e.g. meshes moved after import.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub meshes: Vec<SceneMesh>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneMesh {
+    pub id: String,
+    /// Replaces the placed transform of the mesh.
+    #[serde(default)]
+    pub transform: Option<SceneTransform>,
+    #[serde(default = "visible")]
+    pub visible: bool,
+}
+
+fn visible() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SceneLightType {
+    #[default]
+    Light,
+    NoLight,
+}
+
+impl From<SceneLightType> for LightType {
+    fn from(light_type: SceneLightType) -> Self {
+        match light_type {
+            SceneLightType::Light => LightType::LIGHT,
+            SceneLightType::NoLight => LightType::NO_LIGHT,
+        }
+    }
+}
+
+impl From<LightType> for SceneLightType {
+    fn from(light_type: LightType) -> Self {
+        match light_type {
+            LightType::LIGHT => SceneLightType::Light,
+            LightType::NO_LIGHT => SceneLightType::NoLight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct SceneTransform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for SceneTransform {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl SceneTransform {
+    pub fn to_transform(&self) -> Transform {
+        Transform::new(self.translation, self.rotation, self.scale)
+    }
+}
+
+impl From<&Transform> for SceneTransform {
+    fn from(transform: &Transform) -> Self {
+        Self {
+            translation: transform.position,
+            rotation: transform.rotation,
+            scale: transform.scale,
+        }
+    }
+}
+
+/// The renderer drives a single light. It either follows an asset or stands at `position`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct SceneLight {
+    pub attached_to: Option<String>,
+    pub position: Vec3,
+    pub color: Vec3,
+}
+
+impl Default for SceneLight {
+    fn default() -> Self {
+        Self {
+            attached_to: None,
+            position: Vec3::ZERO,
+            color: Vec3::ONE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneCamera {
+    pub eye: Vec3,
+    pub target: Vec3,
+    #[serde(default)]
+    pub fov_degrees: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneAnimator {
+    /// Asset id, or the id of one of its meshes.
+    pub target: String,
+    #[serde(default = "neutral_speed")]
+    pub speed_multiplier: f32,
+    pub trajectory: SceneTrajectory,
+}
+
+fn neutral_speed() -> f32 {
+    NEUTRAL_SPEED
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SceneTrajectory {
+    Linear {
+        start_position: Vec3,
+        yaw_degrees: f32,
+        pitch_degrees: f32,
+        distance: f32,
+        speed: f32,
+        looping: bool,
+        reversing: bool,
+    },
+    Circular {
+        radius: f32,
+        speed: f32,
+    },
+}
+
+impl From<TrajectoryDescriptor> for SceneTrajectory {
+    fn from(descriptor: TrajectoryDescriptor) -> Self {
+        match descriptor {
+            TrajectoryDescriptor::Linear {
+                start_position,
+                yaw_radians,
+                pitch_radians,
+                distance,
+                speed,
+                looping,
+                reversing,
+            } => SceneTrajectory::Linear {
+                start_position,
+                yaw_degrees: yaw_radians.to_degrees(),
+                pitch_degrees: pitch_radians.to_degrees(),
+                distance,
+                speed,
+                looping,
+                reversing,
+            },
+            TrajectoryDescriptor::Circular { radius, speed } => {
+                SceneTrajectory::Circular { radius, speed }
+            }
+        }
+    }
+}
+
+impl SceneTrajectory {
+    pub fn build(
+        &self,
+        id: MeshId,
+        transform: Shared<Transform>,
+    ) -> anyhow::Result<Box<dyn Animation>> {
+        Ok(match *self {
+            SceneTrajectory::Linear {
+                start_position,
+                yaw_degrees,
+                pitch_degrees,
+                distance,
+                speed,
+                looping,
+                reversing,
+            } => Box::new(LinearTrajectory::new_deconstructed_mesh(
+                id,
+                transform,
+                start_position,
+                yaw_degrees.to_radians(),
+                pitch_degrees.to_radians(),
+                distance,
+                speed,
+                looping,
+                reversing,
+            )?),
+            SceneTrajectory::Circular { radius, speed } => Box::new(
+                CircularTrajectory::new_deconstructed_mesh(id, transform, radius, speed)?,
+            ),
+        })
+    }
+}
+
+impl SceneFile {
+    pub fn from_ron_str(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write(&self, path: &Path) -> Result<(), RendererError> {
+        let source = self
+            .to_ron_string()
+            .map_err(|error| RendererError::SceneFile {
+                path: path.to_path_buf(),
+                reason: error.to_string(),
+            })?;
+        std::fs::write(path, source).map_err(|source| RendererError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Reads and parses `path`. Entries are not checked until the scene is loaded.
+    pub async fn read(path: &Path) -> Result<Self, RendererError> {
+        let scene_file_error = |reason: String| RendererError::SceneFile {
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let source = std::fs::read_to_string(path).map_err(|source| RendererError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        #[cfg(target_arch = "wasm32")]
+        let source = {
+            let bytes = crate::gpu::glTF::read_bytes(path)
+                .await
+                .map_err(|error| scene_file_error(format!("{error:#}")))?;
+            String::from_utf8(bytes).map_err(|error| scene_file_error(error.to_string()))?
+        };
+
+        Self::from_ron_str(&source).map_err(|error| scene_file_error(error.to_string()))
+    }
+}
+
+/// One scene entry that could not be instantiated, e.g. `asset `Cube``.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneEntryError {
+    pub entry: String,
+    pub reason: String,
+}
+
+impl SceneEntryError {
+    pub fn new(entry: impl Into<String>, reason: impl fmt::Display) -> Self {
+        Self {
+            entry: entry.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SceneEntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.entry, self.reason)
+    }
+}
+
+/// Every entry of a scene file that failed to load. The other entries are in the scene.
+#[derive(Debug, Error)]
+pub struct SceneLoadErrors {
+    pub path: PathBuf,
+    pub errors: Vec<SceneEntryError>,
+}
+
+impl fmt::Display for SceneLoadErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entries of `{}` failed to load",
+            self.errors.len(),
+            self.path.display()
+        )?;
+        for error in &self.errors {
+            write!(f, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::util::test_support::{assert_quat_eq, assert_vec3_eq};
+
+    use super::*;
+
+    const DEMO_SCENE: &str = include_str!("../../assets/scenes/demo.ron");
+
+    #[test]
+    fn test_demo_scene_parses() {
+        let scene = SceneFile::from_ron_str(DEMO_SCENE).unwrap();
+
+        let ids: Vec<&str> = scene.assets.iter().map(|asset| asset.id.as_str()).collect();
+        assert_eq!(ids, ["Suzanne", "Cube"]);
+        assert_eq!(scene.assets[1].light_type, SceneLightType::NoLight);
+        assert_eq!(scene.lights[0].attached_to.as_deref(), Some("Cube"));
+        assert_eq!(scene.animators[0].target, "Cube");
+        assert!(matches!(
+            scene.animators[0].trajectory,
+            SceneTrajectory::Linear { distance, .. } if distance == 3.0
+        ));
+        assert_eq!(scene.camera.unwrap().fov_degrees, Some(45.0));
+    }
+
+    #[test]
+    fn test_omitted_fields_fall_back_to_defaults() {
+        let scene = SceneFile::from_ron_str(
+            r#"(
+                assets: [(id: "Box", path: "box.gltf")],
+                animators: [(target: "Box", trajectory: Circular(radius: 2.0, speed: 1.0))],
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(scene.assets[0].light_type, SceneLightType::Light);
+        assert_eq!(scene.assets[0].transform, SceneTransform::default());
+        assert_eq!(scene.animators[0].speed_multiplier, NEUTRAL_SPEED);
+        assert!(scene.lights.is_empty());
+        assert!(scene.camera.is_none());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        assert!(SceneFile::from_ron_str("(assets: [(id: \"Box\")])").is_err());
+        assert!(SceneFile::from_ron_str("(lights: [(colour: (1.0, 1.0, 1.0))])").is_err());
+    }
+
+    #[test]
+    fn test_scene_survives_a_ron_round_trip() {
+        let mut scene = SceneFile::from_ron_str(DEMO_SCENE).unwrap();
+        scene.assets[0].meshes.push(SceneMesh {
+            id: "Suzanne_0".to_string(),
+            transform: Some(SceneTransform {
+                translation: Vec3::new(1.0, 2.0, 3.0),
+                rotation: Quat::from_rotation_x(0.5),
+                scale: Vec3::splat(0.5),
+            }),
+            visible: false,
+        });
+        scene.animators.push(SceneAnimator {
+            target: "Suzanne_0".to_string(),
+            speed_multiplier: 2.0,
+            trajectory: SceneTrajectory::Circular {
+                radius: 4.0,
+                speed: 1.5,
+            },
+        });
+
+        let source = scene.to_ron_string().unwrap();
+
+        assert_eq!(SceneFile::from_ron_str(&source).unwrap(), scene);
+    }
+
+    #[test]
+    fn test_trajectory_descriptors_are_stored_in_degrees() {
+        let trajectory = SceneTrajectory::from(TrajectoryDescriptor::Linear {
+            start_position: Vec3::Y,
+            yaw_radians: std::f32::consts::FRAC_PI_2,
+            pitch_radians: 0.0,
+            distance: 3.0,
+            speed: 3.0,
+            looping: true,
+            reversing: true,
+        });
+
+        assert!(matches!(
+            trajectory,
+            SceneTrajectory::Linear { yaw_degrees, .. } if (yaw_degrees - 90.0).abs() < 1e-4
+        ));
+    }
+
+    #[test]
+    fn test_place_moves_a_node_with_its_asset() {
+        let placement = SceneTransform {
+            translation: Vec3::new(0.0, 1.0, 0.0),
+            rotation: Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            scale: Vec3::splat(2.0),
+        };
+        let node = Transform::new(Vec3::new(1.0, 0.0, 0.0), Quat::IDENTITY, Vec3::ONE);
+
+        let placed = node.parented_to(&placement.to_transform());
+
+        assert_vec3_eq(placed.position, Vec3::new(0.0, 1.0, -2.0), "position");
+        assert_quat_eq(placed.rotation, placement.rotation, "rotation");
+        assert_vec3_eq(placed.scale, Vec3::splat(2.0), "scale");
+    }
+
+    #[test]
+    fn test_invalid_trajectory_is_reported() {
+        let trajectory = SceneTrajectory::Circular {
+            radius: 0.0,
+            speed: 1.0,
+        };
+
+        assert!(
+            trajectory
+                .build(
+                    MeshId("Box_0".to_string()),
+                    hyakou_core::shared(Transform::default())
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_load_errors_list_every_entry() {
+        let errors = SceneLoadErrors {
+            path: PathBuf::from("scene.ron"),
+            errors: vec![
+                SceneEntryError::new("asset `Box`", "file not found"),
+                SceneEntryError::new("animator for `Box`", "asset `Box` is not loaded"),
+            ],
+        };
+
+        assert_eq!(
+            errors.to_string(),
+            "2 entries of `scene.ron` failed to load\n  asset `Box`: file not found\n  animator for `Box`: asset `Box` is not loaded"
+        );
+    }
+}
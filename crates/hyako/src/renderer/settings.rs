@@ -0,0 +1,298 @@
+use std::path::{Path, PathBuf};
+
+use glam::Vec4;
+use serde::{Deserialize, Serialize};
+use wgpu::{Backends, Color, PresentMode};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::renderer::error::RendererError;
+
+/// Everything about the renderer a user can tune, loadable from TOML. Fields missing from the file
+/// keep their defaults. Most of them can change while running through
+/// [`SceneRenderer::apply_settings`](crate::renderer::SceneRenderer::apply_settings); the ones in
+/// [`Self::RESTART_FIELDS`] only take effect when the renderer is created.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RendererSettings {
+    /// Linear RGBA the scene pass clears to.
+    pub clear_color: Vec4,
+    /// Waits for vertical blank when presenting; off lets the frame rate run free.
+    pub vsync: bool,
+    pub fov_degrees: f32,
+    /// World units per second while flying the camera.
+    pub camera_speed: f32,
+    /// Radians per pixel of mouse movement.
+    pub camera_sensitivity: f32,
+    pub outline_color: Vec4,
+    /// World units the selection outline extends past the mesh surface.
+    pub outline_thickness: f32,
+    /// Shades lit meshes with the metallic-roughness model; off falls back to the original
+    /// lighting for comparison.
+    pub pbr_shading: bool,
+    /// Draws a pickable marker at every light.
+    pub show_light_gizmos: bool,
+    /// Draws frame rate, draw calls and the selection in the top-left corner.
+    pub show_stats_overlay: bool,
+    /// Draws scene meshes as triangle edges. Ignored when the adapter cannot draw lines.
+    pub wireframe: bool,
+    /// Samples per pixel. Multisampling is not implemented yet, so anything but 1 is ignored.
+    pub msaa_samples: u32,
+    pub backend: BackendPreference,
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        Self {
+            clear_color: Vec4::new(0.3, 0.2, 0.8, 1.0),
+            vsync: true,
+            fov_degrees: 45.0,
+            camera_speed: 20.0,
+            camera_sensitivity: 0.001,
+            outline_color: Vec4::new(1.0, 0.6, 0.1, 1.0),
+            outline_thickness: 0.03,
+            pbr_shading: true,
+            show_light_gizmos: true,
+            show_stats_overlay: false,
+            wireframe: false,
+            msaa_samples: 1,
+            backend: BackendPreference::Auto,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendPreference {
+    /// The platform default: Metal on macOS, WebGPU with a WebGL2 fallback on the web and the
+    /// primary backends elsewhere.
+    #[default]
+    Auto,
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+    WebGpu,
+}
+
+impl BackendPreference {
+    /// None for [`Self::Auto`], which leaves the choice to the platform.
+    pub fn backends(self) -> Option<Backends> {
+        match self {
+            BackendPreference::Auto => None,
+            BackendPreference::Vulkan => Some(Backends::VULKAN),
+            BackendPreference::Metal => Some(Backends::METAL),
+            BackendPreference::Dx12 => Some(Backends::DX12),
+            BackendPreference::Gl => Some(Backends::GL),
+            BackendPreference::WebGpu => Some(Backends::BROWSER_WEBGPU),
+        }
+    }
+}
+
+/// What [`SceneRenderer::apply_settings`](crate::renderer::SceneRenderer::apply_settings) could
+/// not apply while running.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsChange {
+    /// Changed fields that keep their old value until the renderer is recreated.
+    pub restart_required: Vec<&'static str>,
+}
+
+impl SettingsChange {
+    pub fn needs_restart(&self) -> bool {
+        !self.restart_required.is_empty()
+    }
+}
+
+impl RendererSettings {
+    pub const CONFIG_ENV_VAR: &str = "HYAKOU_CONFIG";
+    pub const DEFAULT_CONFIG_FILE: &str = "hyakou.toml";
+    /// Fields baked into the device and pipelines when the renderer is created.
+    pub const RESTART_FIELDS: [&str; 2] = ["msaa_samples", "backend"];
+
+    pub fn from_toml_str(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// Reads the config file at `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &Path) -> Result<Self, RendererError> {
+        let source = std::fs::read_to_string(path).map_err(|source| RendererError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml_str(&source).map_err(|error| RendererError::Config {
+            path: path.to_path_buf(),
+            reason: error.to_string(),
+        })
+    }
+
+    /// Loads the file named by `HYAKOU_CONFIG`, or `./hyakou.toml` if it exists. Without either
+    /// the defaults apply.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_env() -> Result<Self, RendererError> {
+        let path = Self::config_path(|key| std::env::var(key).ok(), |path| path.exists());
+        match path {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// An explicitly configured path wins even if it does not exist, so a typo is reported
+    /// instead of silently falling back to the defaults.
+    pub fn config_path(
+        lookup: impl Fn(&str) -> Option<String>,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Option<PathBuf> {
+        if let Some(path) = lookup(Self::CONFIG_ENV_VAR).filter(|path| !path.trim().is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+        let default_path = PathBuf::from(Self::DEFAULT_CONFIG_FILE);
+        exists(&default_path).then_some(default_path)
+    }
+
+    /// Restart-only fields that differ between `self` and `other`.
+    pub fn restart_required_changes(&self, other: &RendererSettings) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.msaa_samples != other.msaa_samples {
+            changed.push(Self::RESTART_FIELDS[0]);
+        }
+        if self.backend != other.backend {
+            changed.push(Self::RESTART_FIELDS[1]);
+        }
+        changed
+    }
+
+    /// `self` with the restart-only fields taken from `active`.
+    pub fn with_restart_fields_from(self, active: &RendererSettings) -> Self {
+        Self {
+            msaa_samples: active.msaa_samples,
+            backend: active.backend,
+            ..self
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    pub fn clear_color(&self) -> Color {
+        Color {
+            r: self.clear_color.x as f64,
+            g: self.clear_color.y as f64,
+            b: self.clear_color.z as f64,
+            a: self.clear_color.w as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_is_the_default() {
+        assert_eq!(
+            RendererSettings::from_toml_str("").unwrap(),
+            RendererSettings::default()
+        );
+    }
+
+    #[test]
+    fn test_config_overrides_only_the_given_fields() {
+        let settings = RendererSettings::from_toml_str(
+            r#"
+            clear_color = [0.0, 0.0, 0.0, 1.0]
+            vsync = false
+            fov_degrees = 60.0
+            pbr_shading = false
+            show_light_gizmos = false
+            backend = "vulkan"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.clear_color, Vec4::new(0.0, 0.0, 0.0, 1.0));
+        assert!(!settings.vsync);
+        assert_eq!(settings.fov_degrees, 60.0);
+        assert!(!settings.pbr_shading);
+        assert!(!settings.show_light_gizmos);
+        assert_eq!(settings.backend, BackendPreference::Vulkan);
+        assert_eq!(
+            settings.camera_speed,
+            RendererSettings::default().camera_speed
+        );
+        assert_eq!(settings.present_mode(), PresentMode::AutoNoVsync);
+    }
+
+    #[test]
+    fn test_unknown_or_mistyped_fields_are_rejected() {
+        assert!(RendererSettings::from_toml_str("fov = 60.0").is_err());
+        assert!(RendererSettings::from_toml_str("vsync = \"yes\"").is_err());
+        assert!(RendererSettings::from_toml_str("backend = \"directx\"").is_err());
+    }
+
+    #[test]
+    fn test_only_msaa_and_backend_need_a_restart() {
+        let active = RendererSettings::default();
+        let runtime = RendererSettings {
+            clear_color: Vec4::ONE,
+            vsync: false,
+            fov_degrees: 70.0,
+            camera_speed: 5.0,
+            camera_sensitivity: 0.01,
+            outline_color: Vec4::ZERO,
+            outline_thickness: 0.1,
+            pbr_shading: false,
+            show_light_gizmos: false,
+            show_stats_overlay: true,
+            wireframe: true,
+            ..active
+        };
+        let restart = RendererSettings {
+            msaa_samples: 4,
+            backend: BackendPreference::Gl,
+            ..active
+        };
+
+        assert!(active.restart_required_changes(&runtime).is_empty());
+        assert_eq!(
+            active.restart_required_changes(&restart),
+            ["msaa_samples", "backend"]
+        );
+    }
+
+    #[test]
+    fn test_restart_fields_keep_their_active_values() {
+        let active = RendererSettings::default();
+        let requested = RendererSettings {
+            fov_degrees: 70.0,
+            msaa_samples: 4,
+            backend: BackendPreference::Gl,
+            ..active
+        };
+
+        let applied = requested.with_restart_fields_from(&active);
+
+        assert_eq!(applied.fov_degrees, 70.0);
+        assert_eq!(applied.msaa_samples, 1);
+        assert_eq!(applied.backend, BackendPreference::Auto);
+    }
+
+    #[test]
+    fn test_config_path_prefers_the_environment_variable() {
+        let lookup = |key: &str| (key == "HYAKOU_CONFIG").then(|| "custom.toml".to_string());
+
+        assert_eq!(
+            RendererSettings::config_path(lookup, |_| true),
+            Some(PathBuf::from("custom.toml"))
+        );
+        assert_eq!(
+            RendererSettings::config_path(|_| None, |_| true),
+            Some(PathBuf::from("hyakou.toml"))
+        );
+        assert_eq!(RendererSettings::config_path(|_| None, |_| false), None);
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+/// Measurements for a single rendered frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    pub frame_seconds: f64,
+    pub draw_calls: usize,
+    pub culled_objects: usize,
+    /// GPU time for the frame, when the device supports timestamp queries.
+    pub gpu_ms: Option<f64>,
+    /// Light and camera uniform writes since the previous frame; zero while nothing moves.
+    pub uniform_uploads: usize,
+    /// CPU time spent staging and submitting buffer uploads since the previous frame.
+    pub upload_ms: f64,
+}
+
+/// Rolling summary over the most recent frames. Frame times are in milliseconds, while draw
+/// calls, culled objects, GPU time, uniform uploads and upload time come from the latest frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RendererStats {
+    pub frame_count: usize,
+    pub average_frame_ms: f64,
+    pub min_frame_ms: f64,
+    pub max_frame_ms: f64,
+    pub fps: f64,
+    pub draw_calls: usize,
+    pub culled_objects: usize,
+    pub gpu_ms: Option<f64>,
+    pub uniform_uploads: usize,
+    pub upload_ms: f64,
+}
+
+/// Ring buffer of the last `capacity` frames.
+#[derive(Debug)]
+pub struct FrameStatsHistory {
+    frames: VecDeque<FrameStats>,
+    capacity: usize,
+}
+
+impl Default for FrameStatsHistory {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl FrameStatsHistory {
+    pub const DEFAULT_CAPACITY: usize = 120;
+
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, frame: FrameStats) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn summary(&self) -> RendererStats {
+        let Some(latest) = self.frames.back() else {
+            return RendererStats::default();
+        };
+
+        let frame_ms = self.frames.iter().map(|frame| frame.frame_seconds * 1000.0);
+        let total_ms: f64 = frame_ms.clone().sum();
+        let average_frame_ms = total_ms / self.frames.len() as f64;
+
+        RendererStats {
+            frame_count: self.frames.len(),
+            average_frame_ms,
+            min_frame_ms: frame_ms.clone().fold(f64::INFINITY, f64::min),
+            max_frame_ms: frame_ms.fold(0.0, f64::max),
+            fps: if average_frame_ms > 0.0 {
+                1000.0 / average_frame_ms
+            } else {
+                0.0
+            },
+            draw_calls: latest.draw_calls,
+            culled_objects: latest.culled_objects,
+            gpu_ms: latest.gpu_ms,
+            uniform_uploads: latest.uniform_uploads,
+            upload_ms: latest.upload_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn frame(frame_seconds: f64, draw_calls: usize) -> FrameStats {
+        FrameStats {
+            frame_seconds,
+            draw_calls,
+            ..FrameStats::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_history_summarizes_to_zero() {
+        assert_eq!(
+            FrameStatsHistory::default().summary(),
+            RendererStats::default()
+        );
+    }
+
+    #[test]
+    fn test_summary_aggregates_frame_times_and_keeps_latest_counts() {
+        let mut history = FrameStatsHistory::new(8);
+        history.push(frame(0.010, 3));
+        history.push(frame(0.030, 4));
+        history.push(FrameStats {
+            frame_seconds: 0.020,
+            draw_calls: 5,
+            culled_objects: 2,
+            gpu_ms: Some(1.5),
+            uniform_uploads: 1,
+            upload_ms: 0.25,
+        });
+
+        let stats = history.summary();
+
+        assert_eq!(stats.frame_count, 3);
+        assert!((stats.average_frame_ms - 20.0).abs() < EPSILON);
+        assert!((stats.min_frame_ms - 10.0).abs() < EPSILON);
+        assert!((stats.max_frame_ms - 30.0).abs() < EPSILON);
+        assert!((stats.fps - 50.0).abs() < EPSILON);
+        assert_eq!(stats.draw_calls, 5);
+        assert_eq!(stats.culled_objects, 2);
+        assert_eq!(stats.gpu_ms, Some(1.5));
+        assert_eq!(stats.uniform_uploads, 1);
+        assert_eq!(stats.upload_ms, 0.25);
+    }
+
+    #[test]
+    fn test_full_history_drops_oldest_frame() {
+        let mut history = FrameStatsHistory::new(2);
+        history.push(frame(0.100, 1));
+        history.push(frame(0.010, 1));
+        history.push(frame(0.020, 1));
+
+        let stats = history.summary();
+
+        assert_eq!(history.len(), 2);
+        assert!((stats.max_frame_ms - 20.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_clear_resets_summary() {
+        let mut history = FrameStatsHistory::new(0);
+        history.push(frame(0.016, 1));
+        history.clear();
+
+        assert!(history.is_empty());
+        assert_eq!(history.summary(), RendererStats::default());
+    }
+}
@@ -1,10 +1,8 @@
-use anyhow::{Result, anyhow};
 use hyakou_core::types::Size;
 use log::warn;
 use wgpu::{CommandEncoderDescriptor, TextureViewDescriptor};
-use winit::window::Window;
 
-use crate::renderer::{frame::SurfaceFrame, renderer_context::RenderContext};
+use crate::renderer::{error::RendererError, frame::SurfaceFrame, renderer_context::RenderContext};
 
 pub struct SurfaceFrameController;
 
@@ -15,23 +13,23 @@ impl SurfaceFrameController {
 
     pub fn begin_frame(
         &mut self,
-        window: &Window,
         ctx: &mut RenderContext,
-    ) -> Result<Option<SurfaceFrame>> {
-        window.request_redraw();
+    ) -> Result<Option<SurfaceFrame>, RendererError> {
         if ctx.surface_configuration.is_none() || ctx.size.is_zero() {
             return Ok(None);
         }
-
-        let (output, should_reconfigure_surface) =
-            match ctx.surface.as_ref().unwrap().get_current_texture() {
-                wgpu::CurrentSurfaceTexture::Success(output) => (output, false),
-                wgpu::CurrentSurfaceTexture::Suboptimal(output) => (output, true),
-                surface_status => {
-                    self.handle_surface_acquisition_status(ctx, surface_status)?;
-                    return Ok(None);
-                }
-            };
+        let Some(surface) = ctx.surface.as_ref() else {
+            return Ok(None);
+        };
+
+        let (output, should_reconfigure_surface) = match surface.get_current_texture() {
+            wgpu::CurrentSurfaceTexture::Success(output) => (output, false),
+            wgpu::CurrentSurfaceTexture::Suboptimal(output) => (output, true),
+            surface_status => {
+                self.handle_surface_acquisition_status(ctx, surface_status)?;
+                return Ok(None);
+            }
+        };
 
         let view = output
             .texture
@@ -41,7 +39,6 @@ impl SurfaceFrameController {
             .create_command_encoder(&CommandEncoderDescriptor {
                 label: Some("Rendering Encoder"),
             });
-        let depth_view = ctx.depth_texture.view.clone();
         let size_in_pixels = [ctx.size.width, ctx.size.height];
 
         Ok(Some(SurfaceFrame::new(
@@ -49,24 +46,26 @@ impl SurfaceFrameController {
             encoder,
             ctx.queue.clone(),
             view,
-            depth_view,
+            &ctx.depth_texture,
             size_in_pixels,
             should_reconfigure_surface,
         )))
     }
 
-    pub fn finish_frame(&mut self, ctx: &mut RenderContext, frame: SurfaceFrame) -> Result<()> {
-        if frame.finish() {
+    pub fn finish_frame(
+        &mut self,
+        ctx: &mut RenderContext,
+        frame: SurfaceFrame,
+    ) -> Result<(), RendererError> {
+        let should_reconfigure_surface = frame.finish();
+        ctx.buffer_pool.end_frame();
+        if should_reconfigure_surface {
             ctx.resize(ctx.size)?;
         }
 
         Ok(())
     }
 
-    pub fn resize(&mut self, ctx: &mut RenderContext, size: Size) -> Result<()> {
-        ctx.resize(size)
-    }
-
     pub fn size_from_dimensions(width: f64, height: f64) -> Size {
         Size {
             width: width.max(0.0).round() as u32,
@@ -78,7 +77,7 @@ impl SurfaceFrameController {
         &mut self,
         ctx: &mut RenderContext,
         surface_status: wgpu::CurrentSurfaceTexture,
-    ) -> Result<()> {
+    ) -> Result<(), RendererError> {
         match surface_status {
             wgpu::CurrentSurfaceTexture::Timeout => {
                 warn!("Timed out while acquiring the next surface texture; skipping frame");
@@ -92,8 +91,8 @@ impl SurfaceFrameController {
                 warn!("Recovering renderer surface after acquisition status: {surface_status:?}");
                 ctx.resize(ctx.size)
             }
-            wgpu::CurrentSurfaceTexture::Validation => Err(anyhow!(
-                "Validation error while acquiring the next surface texture"
+            wgpu::CurrentSurfaceTexture::Validation => Err(RendererError::surface(
+                "validation error while acquiring the next surface texture",
             )),
             wgpu::CurrentSurfaceTexture::Success(_)
             | wgpu::CurrentSurfaceTexture::Suboptimal(_) => Ok(()),
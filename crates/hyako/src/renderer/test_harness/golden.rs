@@ -0,0 +1,251 @@
+//! Golden-image comparison: harness frames are checked against PNGs under `tests/golden`, loosely
+//! enough to absorb rounding differences between drivers.
+//!
+//! Set `HYAKOU_UPDATE_GOLDENS=1` to write the current frames as the new goldens instead. A missing
+//! golden fails the comparison, so a new test has to record and commit its image that way.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::renderer::{offscreen::CapturedFrame, test_harness::Frame};
+
+/// How far a frame may drift from its golden before the comparison fails.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenTolerance {
+    /// Largest per-channel difference a pixel may have and still count as matching.
+    pub channel: u8,
+    /// Share of pixels, in `0.0..=1.0`, that may exceed `channel` before the frame fails.
+    pub max_differing_fraction: f32,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            max_differing_fraction: 0.001,
+        }
+    }
+}
+
+/// The outcome of comparing two equally sized RGBA images.
+pub struct Comparison {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    /// The expected image dimmed, with every differing pixel in red.
+    pub diff: CapturedFrame,
+}
+
+impl Comparison {
+    pub fn differing_fraction(&self) -> f32 {
+        self.differing_pixels as f32 / self.total_pixels.max(1) as f32
+    }
+
+    pub fn passes(&self, tolerance: GoldenTolerance) -> bool {
+        self.differing_fraction() <= tolerance.max_differing_fraction
+    }
+}
+
+/// Compares RGBA pixels channel by channel, ignoring alpha, which the scene pass always writes as
+/// opaque.
+pub fn compare(
+    expected: &CapturedFrame,
+    actual: &CapturedFrame,
+    channel: u8,
+) -> Result<Comparison> {
+    if (expected.width, expected.height) != (actual.width, actual.height) {
+        return Err(anyhow!(
+            "Golden is {}x{} but the frame is {}x{}",
+            expected.width,
+            expected.height,
+            actual.width,
+            actual.height
+        ));
+    }
+
+    let mut differing_pixels = 0;
+    let mut diff = Vec::with_capacity(expected.rgba.len());
+    for (expected, actual) in expected
+        .rgba
+        .chunks_exact(4)
+        .zip(actual.rgba.chunks_exact(4))
+    {
+        let differs = expected[..3]
+            .iter()
+            .zip(&actual[..3])
+            .any(|(expected, actual)| expected.abs_diff(*actual) > channel);
+        if differs {
+            differing_pixels += 1;
+            diff.extend([255, 0, 0, 255]);
+        } else {
+            diff.extend([expected[0] / 4, expected[1] / 4, expected[2] / 4, 255]);
+        }
+    }
+
+    Ok(Comparison {
+        differing_pixels,
+        total_pixels: (expected.width * expected.height) as usize,
+        diff: CapturedFrame {
+            width: expected.width,
+            height: expected.height,
+            rgba: diff,
+        },
+    })
+}
+
+/// Compares `frame` against `tests/golden/<name>.png`. On a mismatch, or when the golden is
+/// missing, the frame and any diff image are written next to the build output as
+/// `golden/<name>.actual.png` and `.diff.png`.
+#[track_caller]
+pub fn assert_golden(name: &str, frame: &Frame, tolerance: GoldenTolerance) {
+    let golden_path = golden_dir().join(format!("{name}.png"));
+    if update_requested() {
+        std::fs::create_dir_all(golden_dir()).unwrap();
+        frame.0.save_png(&golden_path).unwrap();
+        eprintln!("Wrote golden image {}", golden_path.display());
+        return;
+    }
+    if !golden_path.exists() {
+        let actual_path = write_failure_image(name, "actual", &frame.0);
+        panic!(
+            "golden `{name}` is missing at {}; frame written to {}, record it with \
+             HYAKOU_UPDATE_GOLDENS=1",
+            golden_path.display(),
+            actual_path.display()
+        );
+    }
+
+    let expected = load_png(&golden_path).unwrap();
+    let comparison = match compare(&expected, &frame.0, tolerance.channel) {
+        Ok(comparison) => comparison,
+        Err(size_error) => {
+            let actual_path = write_failure_image(name, "actual", &frame.0);
+            panic!(
+                "golden `{name}` no longer matches: {size_error}; frame written to {}",
+                actual_path.display()
+            );
+        }
+    };
+    if !comparison.passes(tolerance) {
+        let actual_path = write_failure_image(name, "actual", &frame.0);
+        let diff_path = write_failure_image(name, "diff", &comparison.diff);
+        panic!(
+            "golden `{name}` differs in {} of {} pixels ({:.3}%, {:.3}% allowed); see {} and {}",
+            comparison.differing_pixels,
+            comparison.total_pixels,
+            comparison.differing_fraction() * 100.0,
+            tolerance.max_differing_fraction * 100.0,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn update_requested() -> bool {
+    std::env::var("HYAKOU_UPDATE_GOLDENS").ok().as_deref() == Some("1")
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn failure_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target"))
+        .join("golden")
+}
+
+/// Goldens are decoded to RGBA whatever they were saved as, matching [`CapturedFrame`].
+fn load_png(path: &Path) -> Result<CapturedFrame> {
+    let image = image::open(path)
+        .with_context(|| format!("Failed to read golden `{}`", path.display()))?
+        .to_rgba8();
+    Ok(CapturedFrame {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+    })
+}
+
+fn write_failure_image(name: &str, kind: &str, frame: &CapturedFrame) -> PathBuf {
+    let dir = failure_dir();
+    let path = dir.join(format!("{name}.{kind}.png"));
+    if let Err(write_error) = std::fs::create_dir_all(&dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| frame.save_png(&path))
+    {
+        eprintln!("Could not keep the failing {kind} image: {write_error}");
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(pixels: &[[u8; 4]], width: u32) -> CapturedFrame {
+        CapturedFrame {
+            width,
+            height: pixels.len() as u32 / width,
+            rgba: pixels.concat(),
+        }
+    }
+
+    #[test]
+    fn test_differences_within_the_channel_tolerance_match() {
+        let expected = frame(&[[100, 100, 100, 255], [0, 0, 0, 255]], 2);
+        let actual = frame(&[[102, 98, 100, 255], [0, 2, 0, 255]], 2);
+
+        let comparison = compare(&expected, &actual, 2).unwrap();
+
+        assert_eq!(comparison.differing_pixels, 0);
+    }
+
+    #[test]
+    fn test_differing_pixels_are_counted_and_marked_red() {
+        let expected = frame(&[[100, 100, 100, 255], [40, 40, 40, 255]], 2);
+        let actual = frame(&[[100, 100, 100, 255], [40, 44, 40, 255]], 2);
+
+        let comparison = compare(&expected, &actual, 2).unwrap();
+
+        assert_eq!(comparison.differing_pixels, 1);
+        assert_eq!(comparison.diff.rgba[4..], [255, 0, 0, 255]);
+        assert_eq!(comparison.diff.rgba[..4], [25, 25, 25, 255]);
+    }
+
+    #[test]
+    fn test_alpha_is_ignored() {
+        let expected = frame(&[[10, 20, 30, 255]], 1);
+        let actual = frame(&[[10, 20, 30, 0]], 1);
+
+        assert_eq!(compare(&expected, &actual, 0).unwrap().differing_pixels, 0);
+    }
+
+    #[test]
+    fn test_fraction_threshold_decides_the_outcome() {
+        let expected = frame(&[[0, 0, 0, 255]; 4], 2);
+        let mut actual = expected.clone();
+        actual.rgba[0] = 255;
+        let comparison = compare(&expected, &actual, 0).unwrap();
+
+        let loose = GoldenTolerance {
+            channel: 0,
+            max_differing_fraction: 0.25,
+        };
+        let strict = GoldenTolerance {
+            max_differing_fraction: 0.2,
+            ..loose
+        };
+        assert!(comparison.passes(loose));
+        assert!(!comparison.passes(strict));
+    }
+
+    #[test]
+    fn test_size_mismatch_is_an_error() {
+        let expected = frame(&[[0, 0, 0, 255]; 4], 2);
+        let actual = frame(&[[0, 0, 0, 255]; 4], 4);
+
+        assert!(compare(&expected, &actual, 0).is_err());
+    }
+}
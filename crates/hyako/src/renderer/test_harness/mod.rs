@@ -0,0 +1,575 @@
+//! Headless end-to-end rendering for tests: a [`SceneRenderer`] on a surfaceless context that
+//! draws into an [`OffscreenTarget`], procedural primitives to fill it with, and pixel readback.
+
+pub mod allocations;
+pub mod golden;
+
+use std::{f32::consts::PI, sync::Arc};
+
+use glam::{Quat, Vec2, Vec3, Vec4};
+use hyakou_core::{
+    SharedAccess,
+    animations::{Animation, Animator, NEUTRAL_SPEED},
+    components::{LightType, light::LightSource},
+    geometry::{
+        mesh::Mesh,
+        node::{Node, NodeGraph, NodeId, NodeMetadata},
+        vertices::Vertex,
+    },
+    shared,
+    types::{DeltaTime64, Size, transform::Transform},
+};
+
+use crate::{
+    gpu::{
+        glTF::{
+            ImportedAlphaMode, ImportedImage, ImportedMagFilter, ImportedMaterial,
+            ImportedMinFilter, ImportedSampler, ImportedScene, ImportedTexture, ImportedTextureRef,
+            ImportedWrapMode,
+        },
+        render_mesh::{MeshUsage, RenderMesh},
+    },
+    renderer::{
+        SceneRenderer,
+        offscreen::{CapturedFrame, OffscreenTarget},
+        renderer_context::RenderContext,
+        test_harness::allocations::count_allocations,
+        util::asset_root::AssetRoot,
+        wrappers::MockSurfaceProvider,
+    },
+};
+use wgpu::CommandEncoderDescriptor;
+
+pub const HARNESS_SIZE: Size = Size {
+    width: 128,
+    height: 128,
+};
+
+/// Whether GPU-dependent tests are enabled with `HYAKOU_RUN_GPU_TESTS=1`; says so when skipping
+/// `test`.
+pub fn gpu_tests_enabled(test: &str) -> bool {
+    if std::env::var("HYAKOU_RUN_GPU_TESTS").ok().as_deref() == Some("1") {
+        return true;
+    }
+    skip_gpu_test(test, "set HYAKOU_RUN_GPU_TESTS=1 to enable.");
+    false
+}
+
+fn skip_gpu_test(test: &str, reason: impl std::fmt::Display) {
+    eprintln!("Skipping GPU-dependent test {test}; {reason}");
+}
+
+/// An empty scene with a white light behind the camera, seen from +Z against a black sky.
+pub struct RenderHarness {
+    pub renderer: SceneRenderer,
+    target: OffscreenTarget,
+}
+
+impl RenderHarness {
+    /// `None`, after saying why, unless GPU tests are enabled with `HYAKOU_RUN_GPU_TESTS=1` and an
+    /// adapter is available.
+    pub fn new(test_name: &str) -> Option<Self> {
+        if !gpu_tests_enabled(test_name) {
+            return None;
+        }
+        let ctx = match pollster::block_on(RenderContext::new::<MockSurfaceProvider>(None)) {
+            Ok(ctx) => ctx,
+            Err(context_error) => {
+                skip_gpu_test(test_name, format_args!("no adapter: {context_error}"));
+                return None;
+            }
+        };
+        let mut renderer =
+            pollster::block_on(SceneRenderer::from_context(ctx, &AssetRoot::new())).unwrap();
+        renderer.resize(HARNESS_SIZE).unwrap();
+        renderer.clear_scene();
+        renderer.settings.clear_color = Vec4::new(0.0, 0.0, 0.0, 1.0);
+        renderer.settings.show_light_gizmos = false;
+        let target = OffscreenTarget::new(
+            &renderer.get_device(),
+            HARNESS_SIZE,
+            renderer.color_format(),
+        );
+
+        let mut harness = Self { renderer, target };
+        harness.place_light(Vec3::new(0.0, 0.0, 10.0));
+        harness.look_at(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO);
+        Some(harness)
+    }
+
+    /// Like [`Self::new`] with wireframe rendering on, or `None` if the adapter cannot draw lines.
+    pub fn wireframe(test_name: &str) -> Option<Self> {
+        let mut harness = Self::new(test_name)?;
+        if harness.renderer.ctx.wireframe_render_pipeline.is_none() {
+            skip_gpu_test(test_name, "the adapter cannot draw lines.");
+            return None;
+        }
+        harness.renderer.settings.wireframe = true;
+        Some(harness)
+    }
+
+    pub fn look_at(&mut self, eye: Vec3, target: Vec3) {
+        self.renderer.camera.look_at(eye, target).unwrap();
+    }
+
+    /// Replaces the light with a white one at `position`.
+    pub fn place_light(&mut self, position: Vec3) {
+        self.renderer
+            .set_light(LightSource::new(
+                shared(Transform::new(position, Quat::IDENTITY, Vec3::ONE)),
+                Vec3::ONE,
+            ))
+            .unwrap();
+    }
+
+    /// Uploads `scene` under `id` and moves its mesh to `position`.
+    pub fn add(
+        &mut self,
+        id: &str,
+        light_type: LightType,
+        scene: ImportedScene,
+        position: Vec3,
+    ) -> Arc<RenderMesh> {
+        let mesh = self
+            .renderer
+            .asset_manager
+            .upload_imported_scene(id.to_string(), light_type, MeshUsage::Static, scene)
+            .unwrap()
+            .pop()
+            .unwrap();
+        mesh.transform.write_shared(|t| t.position = position);
+        mesh
+    }
+
+    /// Drives `mesh` with `animation` at neutral speed.
+    pub fn animate(&mut self, mesh: &RenderMesh, animation: Box<dyn Animation>) {
+        let handle = self.renderer.asset_manager.handle(&mesh.id).unwrap();
+        self.renderer
+            .animation_handler
+            .insert(handle, Animator::new(NEUTRAL_SPEED, animation).unwrap());
+    }
+
+    /// Pauses the simulation with every animation at `time`, so later frames all show that moment.
+    pub fn freeze_at(&mut self, time: DeltaTime64) {
+        self.renderer.set_simulation_paused(true);
+        self.renderer.seek_animations(time);
+    }
+
+    /// Updates one frame and records its scene pass, returning the draw count and how many
+    /// allocations recording made. Creating and submitting the encoder aren't counted.
+    pub fn record_scene_pass(&mut self) -> (usize, usize) {
+        self.renderer.update(1.0 / 60.0);
+        let device = self.renderer.get_device();
+        let queue = self.renderer.get_queue().clone();
+        let camera_bind_group = self
+            .renderer
+            .resources
+            .get(SceneRenderer::CAMERA_RESOURCE_ID)
+            .cloned()
+            .unwrap();
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Harness Scene Pass Encoder"),
+        });
+        let recorded = {
+            let mut target = self.target.frame_target(&mut encoder, &queue);
+            count_allocations(|| self.renderer.draw_scene(&mut target, &camera_bind_group))
+        };
+        queue.submit(std::iter::once(encoder.finish()));
+        recorded
+    }
+
+    /// Updates and renders `frames` frames and reads the last one back.
+    pub fn render_frames(&mut self, frames: usize) -> Frame {
+        assert!(frames > 0);
+        for _ in 0..frames {
+            self.renderer.update(1.0 / 60.0);
+            self.target.render(&mut self.renderer, 0.0);
+        }
+        Frame(
+            self.target
+                .capture_frame(&self.renderer.get_device(), self.renderer.get_queue())
+                .unwrap(),
+        )
+    }
+
+    pub fn render(&mut self) -> Frame {
+        self.render_frames(1)
+    }
+
+    /// Renders one frame, then keeps updating until `busy` clears, for readbacks that resolve in
+    /// a later update.
+    pub fn render_until(&mut self, busy: impl Fn(&SceneRenderer) -> bool) {
+        self.target.render(&mut self.renderer, 0.0);
+        for _ in 0..10 {
+            self.renderer.update(0.0);
+            if !busy(&self.renderer) {
+                return;
+            }
+            self.renderer
+                .get_device()
+                .poll(wgpu::PollType::wait_indefinitely())
+                .unwrap();
+        }
+        panic!("readback never completed");
+    }
+}
+
+/// A frame read back from the harness target.
+pub struct Frame(pub CapturedFrame);
+
+impl Frame {
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let offset = ((y * self.0.width + x) * 4) as usize;
+        [
+            self.0.rgba[offset],
+            self.0.rgba[offset + 1],
+            self.0.rgba[offset + 2],
+        ]
+    }
+
+    pub fn center(&self) -> [u8; 3] {
+        self.pixel(self.0.width / 2, self.0.height / 2)
+    }
+
+    /// The brightest channel of every pixel, row by row.
+    pub fn brightness(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0
+            .rgba
+            .chunks_exact(4)
+            .map(|pixel| pixel[0].max(pixel[1]).max(pixel[2]))
+    }
+
+    #[track_caller]
+    pub fn assert_pixel(&self, x: u32, y: u32, expected: [u8; 3], tolerance: u8) {
+        let actual = self.pixel(x, y);
+        let close = actual
+            .iter()
+            .zip(expected)
+            .all(|(actual, expected)| actual.abs_diff(expected) <= tolerance);
+        assert!(
+            close,
+            "pixel ({x}, {y}) is {actual:?}, expected {expected:?}"
+        );
+    }
+}
+
+fn single_mesh_scene(
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    material: ImportedMaterial,
+) -> ImportedScene {
+    let node = Node {
+        metadata: NodeMetadata::default(),
+        local_transform: Transform::default(),
+        meshes: vec![Mesh::new(None, Some(0), vertices, indices)],
+        children_ids: Vec::new(),
+        parent_id: None,
+    };
+    ImportedScene::new(
+        NodeGraph::new(vec![node], vec![NodeId(0)]),
+        Vec::new(),
+        vec![material],
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    )
+}
+
+fn material(base_color: Vec4, metallic: f32, roughness: f32) -> ImportedMaterial {
+    ImportedMaterial {
+        index: 0,
+        name: None,
+        base_color_factor: base_color,
+        base_color_texture: None,
+        metallic_factor: metallic,
+        roughness_factor: roughness,
+        metallic_roughness_texture: None,
+        emissive_factor: Vec3::ZERO,
+        alpha_mode: ImportedAlphaMode::Opaque,
+        alpha_cutoff: None,
+        double_sided: false,
+    }
+}
+
+/// A unit cube around the origin with per-face normals and a rough dielectric `base_color`.
+pub fn cube_scene(base_color: Vec4) -> ImportedScene {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    for normal in [
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Y,
+        Vec3::NEG_Y,
+        Vec3::Z,
+        Vec3::NEG_Z,
+    ] {
+        // Two axes spanning the face, ordered so the corners wind counter-clockwise from outside.
+        let u = normal.any_orthonormal_vector();
+        let v = normal.cross(u);
+        let first = vertices.len() as u32;
+        for (s, t) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            let position = (normal + u * s + v * t) * 0.5;
+            vertices.push(Vertex::new(
+                position,
+                Vec2::new((s + 1.0) / 2.0, (1.0 - t) / 2.0),
+                normal,
+                Vec4::ONE,
+            ));
+        }
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+    single_mesh_scene(vertices, indices, material(base_color, 0.0, 1.0))
+}
+
+/// A white unit UV sphere with a single metallic-roughness material.
+pub fn sphere_scene(metallic: f32, roughness: f32) -> ImportedScene {
+    const RINGS: u32 = 32;
+    const SEGMENTS: u32 = 64;
+    let mut vertices = Vec::new();
+    for ring in 0..=RINGS {
+        let theta = ring as f32 / RINGS as f32 * PI;
+        for segment in 0..=SEGMENTS {
+            let phi = segment as f32 / SEGMENTS as f32 * 2.0 * PI;
+            let normal = Vec3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            vertices.push(Vertex::new(normal, Vec2::ZERO, normal, Vec4::ONE));
+        }
+    }
+    let mut indices = Vec::new();
+    for ring in 0..RINGS {
+        for segment in 0..SEGMENTS {
+            let current = ring * (SEGMENTS + 1) + segment;
+            let below = current + SEGMENTS + 1;
+            indices.extend([current, current + 1, below, current + 1, below + 1, below]);
+        }
+    }
+    single_mesh_scene(vertices, indices, material(Vec4::ONE, metallic, roughness))
+}
+
+/// A 2x2 quad facing +Z whose left half samples a red texel and right half a green one.
+pub fn textured_quad_scene() -> ImportedScene {
+    let corner = |x: f32, y: f32| {
+        Vertex::new(
+            Vec3::new(x, y, 0.0),
+            Vec2::new((x + 1.0) / 2.0, (1.0 - y) / 2.0),
+            Vec3::Z,
+            Vec4::ONE,
+        )
+    };
+    let mut material = material(Vec4::ONE, 0.0, 1.0);
+    material.base_color_texture = Some(ImportedTextureRef {
+        texture_index: 0,
+        tex_coord: 0,
+    });
+    let mut scene = single_mesh_scene(
+        vec![
+            corner(-1.0, -1.0),
+            corner(1.0, -1.0),
+            corner(1.0, 1.0),
+            corner(-1.0, 1.0),
+        ],
+        vec![0, 1, 2, 0, 2, 3],
+        material,
+    );
+    scene.images = vec![ImportedImage {
+        index: 0,
+        name: None,
+        width: 2,
+        height: 1,
+        pixels_rgba8: vec![255, 0, 0, 255, 0, 255, 0, 255],
+    }];
+    scene.textures = vec![ImportedTexture {
+        index: 0,
+        name: None,
+        image_index: 0,
+        sampler_index: Some(0),
+    }];
+    scene.samplers = vec![ImportedSampler {
+        index: 0,
+        name: None,
+        mag_filter: ImportedMagFilter::Nearest,
+        min_filter: ImportedMinFilter::Nearest,
+        wrap_s: ImportedWrapMode::ClampToEdge,
+        wrap_t: ImportedWrapMode::ClampToEdge,
+    }];
+    scene
+}
+
+#[cfg(test)]
+mod tests {
+    use hyakou_core::animations::trajectory::linear::LinearTrajectory;
+
+    use super::{
+        golden::{GoldenTolerance, assert_golden},
+        *,
+    };
+
+    const RED: Vec4 = Vec4::new(1.0, 0.0, 0.0, 1.0);
+    const GREEN: Vec4 = Vec4::new(0.0, 1.0, 0.0, 1.0);
+
+    #[test]
+    fn empty_scene_shows_the_clear_color() {
+        let Some(mut harness) = RenderHarness::new("empty_scene_shows_the_clear_color") else {
+            return;
+        };
+        harness.renderer.settings.clear_color = Vec4::new(0.0, 1.0, 0.0, 1.0);
+
+        let frame = harness.render();
+
+        frame.assert_pixel(0, 0, [0, 255, 0], 0);
+        frame.assert_pixel(64, 64, [0, 255, 0], 0);
+        frame.assert_pixel(127, 127, [0, 255, 0], 0);
+    }
+
+    #[test]
+    fn near_cube_is_drawn_in_front_of_the_far_one() {
+        let Some(mut harness) = RenderHarness::new("near_cube_is_drawn_in_front_of_the_far_one")
+        else {
+            return;
+        };
+        harness.add("Near", LightType::LIGHT, cube_scene(RED), Vec3::ZERO);
+        harness.add(
+            "Far",
+            LightType::LIGHT,
+            cube_scene(GREEN),
+            Vec3::new(0.0, 0.0, -3.0),
+        );
+
+        let [red, green, _] = harness.render().center();
+
+        assert!(
+            u32::from(red) > u32::from(green) * 2,
+            "centre is {red}, {green}"
+        );
+        assert!(red > 0, "the near cube was not lit");
+    }
+
+    #[test]
+    fn unlit_meshes_show_the_light_color_regardless_of_its_position() {
+        let Some(mut harness) =
+            RenderHarness::new("unlit_meshes_show_the_light_color_regardless_of_its_position")
+        else {
+            return;
+        };
+        harness.add("Lit", LightType::LIGHT, cube_scene(Vec4::ONE), Vec3::X);
+        harness.add(
+            "Unlit",
+            LightType::NO_LIGHT,
+            cube_scene(Vec4::ONE),
+            Vec3::NEG_X,
+        );
+        // x 32 falls on the unlit cube, x 96 on the lit one.
+        let lit_from_front = harness.render().pixel(96, 64);
+
+        // Behind the cubes only the ambient term reaches their front faces.
+        harness.place_light(Vec3::new(0.0, 0.0, -10.0));
+        let frame = harness.render();
+
+        frame.assert_pixel(32, 64, [255, 255, 255], 0);
+        let lit_from_behind = frame.pixel(96, 64);
+        assert!(
+            u32::from(lit_from_behind[0]) * 2 < u32::from(lit_from_front[0]),
+            "lit cube went from {lit_from_front:?} to {lit_from_behind:?}"
+        );
+    }
+
+    #[test]
+    fn hidden_meshes_are_not_drawn() {
+        let Some(mut harness) = RenderHarness::new("hidden_meshes_are_not_drawn") else {
+            return;
+        };
+        let cube = harness.add("Cube", LightType::LIGHT, cube_scene(RED), Vec3::ZERO);
+        let id = cube.id.0.clone();
+        assert_ne!(harness.render().center(), [0, 0, 0]);
+
+        harness.renderer.asset_manager.set_visible(&id, false);
+        assert_eq!(harness.render().center(), [0, 0, 0]);
+
+        harness.renderer.asset_manager.set_visible(&id, true);
+        assert_ne!(harness.render().center(), [0, 0, 0]);
+    }
+
+    /// A red cube half way up its animated path next to a smooth white sphere.
+    #[test]
+    fn lit_scene_matches_its_golden() {
+        let Some(mut harness) = RenderHarness::new("lit_scene_matches_its_golden") else {
+            return;
+        };
+        let cube = harness.add("Cube", LightType::LIGHT, cube_scene(RED), Vec3::ZERO);
+        harness.add(
+            "Sphere",
+            LightType::LIGHT,
+            sphere_scene(0.0, 0.3),
+            Vec3::new(1.0, 0.0, -1.0),
+        );
+        let start = Vec3::new(-1.0, -0.5, 0.0);
+        let rise = LinearTrajectory::new_deconstructed_mesh(
+            cube.id.clone(),
+            cube.transform.clone(),
+            start,
+            0.0,
+            f32::to_radians(90.0),
+            1.0,
+            1.0,
+            true,
+            false,
+        )
+        .unwrap();
+        harness.animate(&cube, Box::new(rise));
+        harness.freeze_at(0.5);
+
+        let frame = harness.render_frames(3);
+
+        assert!(
+            (cube.transform.read_shared(|t| t.position) - Vec3::new(-1.0, 0.0, 0.0)).length()
+                < 1e-4
+        );
+        assert_golden("lit_scene", &frame, GoldenTolerance::default());
+    }
+
+    #[test]
+    fn unlit_scene_matches_its_golden() {
+        let Some(mut harness) = RenderHarness::new("unlit_scene_matches_its_golden") else {
+            return;
+        };
+        harness.renderer.settings.clear_color = Vec4::new(0.1, 0.1, 0.2, 1.0);
+        harness.add("Cube", LightType::NO_LIGHT, cube_scene(GREEN), Vec3::ZERO);
+        harness.look_at(Vec3::new(2.0, 2.0, 3.0), Vec3::ZERO);
+
+        let frame = harness.render();
+
+        assert_golden("unlit_scene", &frame, GoldenTolerance::default());
+    }
+
+    #[test]
+    fn wireframe_scene_matches_its_golden() {
+        let Some(mut harness) = RenderHarness::wireframe("wireframe_scene_matches_its_golden")
+        else {
+            return;
+        };
+        harness.add("Cube", LightType::LIGHT, cube_scene(Vec4::ONE), Vec3::ZERO);
+        harness.add(
+            "Marker",
+            LightType::NO_LIGHT,
+            cube_scene(Vec4::ONE),
+            Vec3::new(1.2, 0.8, -1.0),
+        );
+        harness.look_at(Vec3::new(2.0, 2.0, 3.0), Vec3::ZERO);
+
+        let frame = harness.render();
+
+        // Only edges are drawn, so most of the frame still shows the sky.
+        let sky = frame.brightness().filter(|&value| value == 0).count();
+        assert!(sky * 2 > frame.brightness().count(), "{sky} sky pixels");
+        // Line rasterization varies more between drivers than filled triangles.
+        let tolerance = GoldenTolerance {
+            channel: 8,
+            max_differing_fraction: 0.01,
+        };
+        assert_golden("wireframe_scene", &frame, tolerance);
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::mpsc::{Receiver, TryRecvError, channel};
+
+use log::warn;
+use wgpu::{
+    Buffer, BufferAddress, BufferAsyncError, BufferDescriptor, BufferUsages,
+    COPY_BYTES_PER_ROW_ALIGNMENT, CommandEncoder, Device, Extent3d, MapMode, Origin3d, PollType,
+    TexelCopyBufferInfo, TexelCopyBufferLayout, TexelCopyTextureInfo, Texture, TextureAspect,
+};
+
+enum ReadbackState<P> {
+    Idle,
+    /// The copy was recorded into a frame that may not be submitted yet.
+    Recorded(P),
+    Mapping(P, Receiver<Result<(), BufferAsyncError>>),
+}
+
+/// Copies a single 4-byte texel into a mappable buffer and hands it back once the GPU is done,
+/// without ever blocking on the device. `P` travels along with the copy, so callers can interpret
+/// the texel against the state of the frame that recorded it.
+pub struct TexelReadback<P> {
+    label: &'static str,
+    buffer: Buffer,
+    state: ReadbackState<P>,
+}
+
+impl<P> TexelReadback<P> {
+    pub fn new(device: &Device, label: &'static str) -> Self {
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some(label),
+            size: COPY_BYTES_PER_ROW_ALIGNMENT as BufferAddress,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        Self {
+            label,
+            buffer,
+            state: ReadbackState::Idle,
+        }
+    }
+
+    /// Whether a new copy can be recorded; only one texel is in flight at a time.
+    pub fn is_idle(&self) -> bool {
+        matches!(self.state, ReadbackState::Idle)
+    }
+
+    /// Records the copy of `texel` from `texture` into `encoder`. Ignored while another copy is
+    /// still in flight.
+    pub fn copy(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        texture: &Texture,
+        aspect: TextureAspect,
+        texel: [u32; 2],
+        payload: P,
+    ) {
+        if !self.is_idle() {
+            return;
+        }
+        encoder.copy_texture_to_buffer(
+            TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: texel[0],
+                    y: texel[1],
+                    z: 0,
+                },
+                aspect,
+            },
+            TexelCopyBufferInfo {
+                buffer: &self.buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.state = ReadbackState::Recorded(payload);
+    }
+
+    /// Advances the copy without blocking. Has to run after the frame holding the copy was
+    /// submitted, e.g. from the next update, and returns the texel once the buffer is mapped.
+    pub fn poll(&mut self, device: &Device) -> Option<(P, [u8; 4])> {
+        let (payload, mapped) = match std::mem::replace(&mut self.state, ReadbackState::Idle) {
+            ReadbackState::Idle => return None,
+            ReadbackState::Recorded(payload) => {
+                let (sender, mapped) = channel();
+                self.buffer
+                    .slice(..)
+                    .map_async(MapMode::Read, move |result| {
+                        let _ = sender.send(result);
+                    });
+                (payload, mapped)
+            }
+            ReadbackState::Mapping(payload, mapped) => (payload, mapped),
+        };
+
+        if let Err(poll_error) = device.poll(PollType::Poll) {
+            warn!(
+                "Failed to poll the device for `{}`: {poll_error:?}",
+                self.label
+            );
+        }
+        match mapped.try_recv() {
+            Ok(Ok(())) => {
+                let texel = {
+                    let bytes = self.buffer.slice(..).get_mapped_range();
+                    [bytes[0], bytes[1], bytes[2], bytes[3]]
+                };
+                self.buffer.unmap();
+                Some((payload, texel))
+            }
+            Ok(Err(map_error)) => {
+                warn!("Failed to map `{}`: {map_error:?}", self.label);
+                None
+            }
+            Err(TryRecvError::Empty) => {
+                self.state = ReadbackState::Mapping(payload, mapped);
+                None
+            }
+            Err(TryRecvError::Disconnected) => {
+                warn!("`{}` was dropped before its copy completed", self.label);
+                None
+            }
+        }
+    }
+}
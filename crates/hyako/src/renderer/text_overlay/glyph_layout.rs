@@ -0,0 +1,150 @@
+use glam::Vec2;
+
+/// A monospace atlas of fixed-size cells laid out row-major, one per character from `first` to
+/// `last`. Glyphs are white with their coverage in alpha.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitmapFont {
+    pub cell_size: Vec2,
+    pub columns: u32,
+    pub first: char,
+    pub last: char,
+    pub atlas_size: Vec2,
+}
+
+impl BitmapFont {
+    /// Printable ASCII baked from `NotoSans-Bold.ttf` into `assets/fonts/overlay_atlas.png`.
+    pub const OVERLAY: BitmapFont = BitmapFont {
+        cell_size: Vec2::new(12.0, 20.0),
+        columns: 16,
+        first: ' ',
+        last: '~',
+        atlas_size: Vec2::new(192.0, 120.0),
+    };
+    /// Drawn in place of characters outside the baked range.
+    pub const FALLBACK: char = '?';
+
+    fn cell_index(&self, character: char) -> u32 {
+        let character = if (self.first..=self.last).contains(&character) {
+            character
+        } else {
+            Self::FALLBACK
+        };
+        character as u32 - self.first as u32
+    }
+
+    /// Normalized top-left and bottom-right texture coordinates of `character`'s cell.
+    pub fn uv_rect(&self, character: char) -> (Vec2, Vec2) {
+        let index = self.cell_index(character);
+        let cell = Vec2::new((index % self.columns) as f32, (index / self.columns) as f32);
+        let min = cell * self.cell_size / self.atlas_size;
+        (min, min + self.cell_size / self.atlas_size)
+    }
+}
+
+/// One character cell on screen, in pixels with y growing downwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphQuad {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+}
+
+/// Lays `text` out from `origin` with a fixed advance of one cell times `scale`. `\n` returns to
+/// `origin.x` one line lower; other whitespace advances without producing a quad.
+pub fn layout_text(font: &BitmapFont, text: &str, origin: Vec2, scale: f32) -> Vec<GlyphQuad> {
+    let advance = font.cell_size * scale;
+    let mut pen = origin;
+    let mut quads = Vec::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\n' => {
+                pen = Vec2::new(origin.x, pen.y + advance.y);
+                continue;
+            }
+            '\r' => continue,
+            character if character.is_whitespace() => {}
+            character => {
+                let (uv_min, uv_max) = font.uv_rect(character);
+                quads.push(GlyphQuad {
+                    position: pen,
+                    size: advance,
+                    uv_min,
+                    uv_max,
+                });
+            }
+        }
+        pen.x += advance.x;
+    }
+    quads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: BitmapFont = BitmapFont::OVERLAY;
+
+    fn positions(quads: &[GlyphQuad]) -> Vec<Vec2> {
+        quads.iter().map(|quad| quad.position).collect()
+    }
+
+    #[test]
+    fn test_glyphs_advance_by_one_cell() {
+        let quads = layout_text(&FONT, "abc", Vec2::new(4.0, 8.0), 1.0);
+
+        assert_eq!(
+            positions(&quads),
+            [
+                Vec2::new(4.0, 8.0),
+                Vec2::new(16.0, 8.0),
+                Vec2::new(28.0, 8.0)
+            ]
+        );
+        assert!(quads.iter().all(|quad| quad.size == FONT.cell_size));
+    }
+
+    #[test]
+    fn test_newline_returns_to_the_origin_one_line_down() {
+        let quads = layout_text(&FONT, "ab\ncd\r\ne", Vec2::new(4.0, 8.0), 2.0);
+
+        assert_eq!(
+            positions(&quads),
+            [
+                Vec2::new(4.0, 8.0),
+                Vec2::new(28.0, 8.0),
+                Vec2::new(4.0, 48.0),
+                Vec2::new(28.0, 48.0),
+                Vec2::new(4.0, 88.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spaces_advance_without_a_quad() {
+        let quads = layout_text(&FONT, "a b", Vec2::ZERO, 1.0);
+
+        assert_eq!(positions(&quads), [Vec2::ZERO, Vec2::new(24.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_uv_rect_addresses_the_cell_row_major() {
+        // 'A' is cell 33: column 1 of row 2.
+        let (min, max) = FONT.uv_rect('A');
+
+        assert_eq!(min, Vec2::new(12.0 / 192.0, 40.0 / 120.0));
+        assert_eq!(max, Vec2::new(24.0 / 192.0, 60.0 / 120.0));
+        assert_eq!(FONT.uv_rect('~').1, Vec2::new(180.0 / 192.0, 1.0));
+    }
+
+    #[test]
+    fn test_characters_outside_the_atlas_use_the_fallback() {
+        let quads = layout_text(&FONT, "é", Vec2::ZERO, 1.0);
+
+        assert_eq!(quads.len(), 1);
+        assert_eq!(
+            (quads[0].uv_min, quads[0].uv_max),
+            FONT.uv_rect(BitmapFont::FALLBACK)
+        );
+    }
+}
@@ -0,0 +1,259 @@
+pub mod glyph_layout;
+
+use glam::{Vec2, Vec4};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferAddress, BufferDescriptor,
+    BufferUsages, Device, Operations, RenderPassColorAttachment, RenderPassDescriptor,
+    RenderPipeline, SamplerBindingType, ShaderStages, TextureSampleType, TextureViewDimension,
+    include_wgsl,
+};
+
+use crate::{
+    gpu::{
+        buffers::glyph::GlyphInstance, render_pipeline::create_text_overlay_render_pipeline,
+        texture::Texture,
+    },
+    renderer::{
+        frame::FrameTarget,
+        renderer_context::RenderContext,
+        stats::RendererStats,
+        text_overlay::glyph_layout::{BitmapFont, layout_text},
+    },
+};
+
+const OVERLAY_ATLAS: &[u8] = include_bytes!("../../../assets/fonts/overlay_atlas.png");
+
+/// Lines of text drawn in the top-left corner of the viewport.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OverlayLines {
+    lines: Vec<String>,
+}
+
+impl OverlayLines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    pub fn push(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Replaces the lines with the frame rate, draw counts and the selected id.
+    pub fn fill_from_stats(&mut self, stats: &RendererStats, selected: Option<&str>) {
+        self.clear();
+        self.push(format!("FPS {:.1}", stats.fps));
+        self.push(format!("Frame {:.2} ms", stats.average_frame_ms));
+        self.push(format!("Draw calls {}", stats.draw_calls));
+        self.push(format!("Culled {}", stats.culled_objects));
+        self.push(format!("Selected {}", selected.unwrap_or("-")));
+    }
+}
+
+/// Draws [`OverlayLines`] with the baked bitmap font in its own pass after everything else, so
+/// the text stays on top of the scene and its gizmos.
+pub struct TextOverlayRenderer {
+    pipeline: RenderPipeline,
+    atlas_bind_group: BindGroup,
+    instance_buffer: Buffer,
+    capacity: usize,
+}
+
+impl TextOverlayRenderer {
+    /// Pixels between the viewport corner and the first glyph.
+    pub const MARGIN: f32 = 8.0;
+    pub const TEXT_COLOR: Vec4 = Vec4::new(1.0, 1.0, 1.0, 0.9);
+    const INSTANCE_BUFFER_LABEL: &str = "Text Overlay Instance Buffer";
+
+    pub fn new(ctx: &RenderContext) -> Self {
+        let atlas = Texture::from_image_bytes(
+            &ctx.device,
+            &ctx.queue,
+            OVERLAY_ATLAS,
+            false,
+            "Overlay Font Atlas",
+        )
+        .expect("embedded overlay atlas decodes");
+        let bind_group_layout = ctx
+            .device
+            .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("Text Overlay Bind Group Layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: true },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+        let atlas_bind_group = ctx.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Text Overlay Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&atlas.view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&atlas.sampler),
+                },
+            ],
+        });
+        let pipeline_layout = ctx
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Text Overlay Pipeline Layout"),
+                bind_group_layouts: &[Some(&bind_group_layout)],
+                immediate_size: 0,
+            });
+        let pipeline = create_text_overlay_render_pipeline(
+            &ctx.device,
+            "text overlay render pass",
+            &pipeline_layout,
+            ctx.color_format(),
+            ctx.device
+                .create_shader_module(include_wgsl!("../../../assets/text_overlay.wgsl")),
+        );
+
+        Self {
+            pipeline,
+            atlas_bind_group,
+            instance_buffer: Self::create_instance_buffer(&ctx.device, 1),
+            capacity: 1,
+        }
+    }
+
+    pub fn draw(&mut self, device: &Device, target: &mut FrameTarget<'_>, lines: &OverlayLines) {
+        let [width, height] = target.size_in_pixels;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let target_size = Vec2::new(width as f32, height as f32);
+        let instances: Vec<GlyphInstance> = layout_text(
+            &BitmapFont::OVERLAY,
+            &lines.text(),
+            Vec2::splat(Self::MARGIN),
+            1.0,
+        )
+        .iter()
+        .map(|quad| GlyphInstance::new(quad, target_size, Self::TEXT_COLOR))
+        .collect();
+        if instances.is_empty() {
+            return;
+        }
+
+        if instances.len() > self.capacity {
+            self.capacity = instances.len().next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, self.capacity);
+        }
+        target
+            .queue
+            .write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+
+        let mut render_pass = target.encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Text Overlay Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target.color_view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            multiview_mask: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            depth_stencil_attachment: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.atlas_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+        render_pass.draw(0..6, 0..instances.len() as u32);
+    }
+
+    fn create_instance_buffer(device: &Device, capacity: usize) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(Self::INSTANCE_BUFFER_LABEL),
+            size: (capacity * size_of::<GlyphInstance>()) as BufferAddress,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::texture::decode_rgba8;
+
+    #[test]
+    fn test_embedded_atlas_matches_the_font_metrics() {
+        let atlas = decode_rgba8(OVERLAY_ATLAS).unwrap();
+        let font = BitmapFont::OVERLAY;
+
+        assert_eq!(
+            Vec2::new(atlas.width() as f32, atlas.height() as f32),
+            font.atlas_size
+        );
+        let cells = font.last as u32 - font.first as u32 + 1;
+        assert!(cells.div_ceil(font.columns) as f32 * font.cell_size.y <= font.atlas_size.y);
+    }
+
+    #[test]
+    fn test_stats_fill_one_line_each() {
+        let stats = RendererStats {
+            fps: 59.94,
+            average_frame_ms: 16.683,
+            draw_calls: 12,
+            culled_objects: 3,
+            ..Default::default()
+        };
+        let mut lines = OverlayLines::new();
+        lines.push("stale");
+
+        lines.fill_from_stats(&stats, Some("cube"));
+
+        assert_eq!(
+            lines.lines(),
+            [
+                "FPS 59.9",
+                "Frame 16.68 ms",
+                "Draw calls 12",
+                "Culled 3",
+                "Selected cube"
+            ]
+        );
+        lines.fill_from_stats(&stats, None);
+        assert_eq!(lines.lines().last().unwrap(), "Selected -");
+    }
+}
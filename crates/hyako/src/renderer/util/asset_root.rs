@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+
+use crate::renderer::error::RendererError;
+
+/// Finds the `assets` directory the built-in scene and shaders ship in. Checked in order: an
+/// explicit override, `HYAKOU_ASSET_ROOT`, an `assets` directory next to the executable, and the
+/// crate's own `assets` directory for development builds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetRoot {
+    override_dir: Option<PathBuf>,
+}
+
+/// Where a resolved asset root came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetRootSource {
+    Override,
+    Environment,
+    Executable,
+    Manifest,
+}
+
+impl AssetRoot {
+    pub const ENV_VAR: &str = "HYAKOU_ASSET_ROOT";
+    pub const DIR_NAME: &str = "assets";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_override(path: impl Into<PathBuf>) -> Self {
+        Self {
+            override_dir: Some(path.into()),
+        }
+    }
+
+    /// `relative` inside the resolved root, e.g. `gltf/Cube.gltf`.
+    pub fn join(&self, relative: impl AsRef<Path>) -> Result<PathBuf, RendererError> {
+        Ok(self.resolve()?.join(relative))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn resolve(&self) -> Result<PathBuf, RendererError> {
+        self.resolve_with(
+            |key| std::env::var(key).ok(),
+            std::env::current_exe().ok().as_deref(),
+            Path::is_dir,
+        )
+        .map(|(root, _)| root)
+    }
+
+    /// The web build fetches assets relative to the page, so there is nothing to probe.
+    #[cfg(target_arch = "wasm32")]
+    pub fn resolve(&self) -> Result<PathBuf, RendererError> {
+        Ok(self
+            .override_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(Self::DIR_NAME)))
+    }
+
+    /// Explicitly configured roots have to exist; a wrong path is reported instead of silently
+    /// falling through to a different asset directory.
+    pub fn resolve_with(
+        &self,
+        lookup: impl Fn(&str) -> Option<String>,
+        executable: Option<&Path>,
+        is_dir: impl Fn(&Path) -> bool,
+    ) -> Result<(PathBuf, AssetRootSource), RendererError> {
+        let configured = self
+            .override_dir
+            .clone()
+            .map(|dir| (dir, AssetRootSource::Override))
+            .or_else(|| {
+                lookup(Self::ENV_VAR)
+                    .filter(|dir| !dir.trim().is_empty())
+                    .map(|dir| (PathBuf::from(dir), AssetRootSource::Environment))
+            });
+        if let Some((dir, source)) = configured {
+            return if is_dir(&dir) {
+                Ok((dir, source))
+            } else {
+                Err(RendererError::AssetRootNotFound {
+                    reason: format!(
+                        "{source:?} asset root `{}` is not a directory",
+                        dir.display()
+                    ),
+                })
+            };
+        }
+
+        let candidates = executable
+            .and_then(Path::parent)
+            .map(|dir| (dir.join(Self::DIR_NAME), AssetRootSource::Executable))
+            .into_iter()
+            .chain(std::iter::once((
+                Path::new(env!("CARGO_MANIFEST_DIR")).join(Self::DIR_NAME),
+                AssetRootSource::Manifest,
+            )));
+        let mut searched = Vec::new();
+        for (dir, source) in candidates {
+            if is_dir(&dir) {
+                return Ok((dir, source));
+            }
+            searched.push(dir.display().to_string());
+        }
+        Err(RendererError::AssetRootNotFound {
+            reason: format!(
+                "set {} or place `{}` next to the executable (searched {})",
+                Self::ENV_VAR,
+                Self::DIR_NAME,
+                searched.join(", ")
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::*;
+
+    /// A fresh directory tree with `bin/assets` and `custom` in it.
+    fn temp_tree() -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("hyako_asset_root_{nanos}"));
+        fs::create_dir_all(root.join("bin/assets")).unwrap();
+        fs::create_dir_all(root.join("custom")).unwrap();
+        root
+    }
+
+    fn exists(path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    #[test]
+    fn test_override_wins_over_everything_else() {
+        let tree = temp_tree();
+        let lookup = |_: &str| Some(tree.join("bin/assets").display().to_string());
+
+        let resolved = AssetRoot::with_override(tree.join("custom"))
+            .resolve_with(lookup, Some(&tree.join("bin/hyako")), exists)
+            .unwrap();
+
+        assert_eq!(resolved, (tree.join("custom"), AssetRootSource::Override));
+        fs::remove_dir_all(tree).unwrap();
+    }
+
+    #[test]
+    fn test_environment_variable_comes_before_the_executable() {
+        let tree = temp_tree();
+        let lookup = |key: &str| {
+            (key == AssetRoot::ENV_VAR).then(|| tree.join("custom").display().to_string())
+        };
+
+        let resolved = AssetRoot::new()
+            .resolve_with(lookup, Some(&tree.join("bin/hyako")), exists)
+            .unwrap();
+
+        assert_eq!(
+            resolved,
+            (tree.join("custom"), AssetRootSource::Environment)
+        );
+        fs::remove_dir_all(tree).unwrap();
+    }
+
+    #[test]
+    fn test_assets_next_to_the_executable_are_found() {
+        let tree = temp_tree();
+
+        let resolved = AssetRoot::new()
+            .resolve_with(|_| None, Some(&tree.join("bin/hyako")), exists)
+            .unwrap();
+
+        assert_eq!(
+            resolved,
+            (tree.join("bin/assets"), AssetRootSource::Executable)
+        );
+        fs::remove_dir_all(tree).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_directory_is_the_last_resort() {
+        let tree = temp_tree();
+
+        let resolved = AssetRoot::new()
+            .resolve_with(|_| None, Some(&tree.join("custom/hyako")), exists)
+            .unwrap();
+
+        assert_eq!(
+            resolved,
+            (
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"),
+                AssetRootSource::Manifest
+            )
+        );
+        fs::remove_dir_all(tree).unwrap();
+    }
+
+    #[test]
+    fn test_missing_roots_are_errors() {
+        let tree = temp_tree();
+        let missing = tree.join("missing");
+
+        let configured = AssetRoot::with_override(&missing).resolve_with(|_| None, None, exists);
+        let nothing_found = AssetRoot::new().resolve_with(|_| None, None, |_| false);
+
+        assert!(
+            matches!(configured, Err(RendererError::AssetRootNotFound { reason }) if reason.contains("missing"))
+        );
+        assert!(
+            matches!(nothing_found, Err(RendererError::AssetRootNotFound { reason }) if reason.contains(AssetRoot::ENV_VAR))
+        );
+        fs::remove_dir_all(tree).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use bytemuck::bytes_of;
+use hyakou_core::{
+    components::camera::camera::Camera,
+    shared,
+    traits::BindGroupProvider,
+    types::{Size, ids::UniformBufferId, transform::Transform},
+};
+use log::warn;
+use wgpu::{
+    BindGroup, CommandEncoderDescriptor, Device, PresentMode, Queue, Surface, SurfaceConfiguration,
+    TextureViewDescriptor,
+};
+use winit::window::Window;
+
+use crate::{
+    gpu::{
+        buffers::{camera_buffer::CameraUniform, uniform::UniformBuffer},
+        texture::Texture,
+    },
+    renderer::{
+        error::RendererError, frame::SurfaceFrame, renderer_context::RenderContext,
+        wrappers::WinitSurfaceProvider,
+    },
+};
+
+/// An additional window looking at the shared scene. It owns its surface, depth buffer and
+/// camera, while device, pipelines and assets come from the renderer's [`RenderContext`].
+pub struct ViewState {
+    window: Arc<Window>,
+    surface: Surface<'static>,
+    surface_configuration: SurfaceConfiguration,
+    depth_texture: Texture,
+    size: Size,
+    pub camera: Camera,
+    camera_uniform: CameraUniform,
+    camera_uniform_buffer: UniformBuffer,
+    camera_bind_group: BindGroup,
+}
+
+impl ViewState {
+    const DEPTH_TEXTURE_LABEL: &str = "View Depth Texture";
+
+    pub fn new(
+        ctx: &RenderContext,
+        window: Arc<Window>,
+        mut camera: Camera,
+    ) -> Result<Self, RendererError> {
+        let provider = WinitSurfaceProvider {
+            window: window.clone(),
+            transparent: ctx.is_transparent(),
+        };
+        let (surface, surface_configuration, size) = ctx.create_configured_surface(&provider)?;
+        camera.set_aspect_from_size(size);
+
+        let mut camera_uniform = CameraUniform::new();
+        camera_uniform.update(&camera);
+        let camera_uniform_buffer = UniformBuffer::new(
+            UniformBufferId::new(format!("View Camera {:?}", window.id())),
+            &ctx.device,
+            bytes_of(&camera_uniform),
+            shared(Transform::default()),
+        );
+        let camera_bind_group = CameraUniform::bind_group(
+            &ctx.device,
+            &camera_uniform_buffer,
+            &ctx.camera_bind_group_layout,
+        );
+
+        Ok(Self {
+            window,
+            surface,
+            surface_configuration,
+            depth_texture: Texture::create_depth_texture(
+                Self::DEPTH_TEXTURE_LABEL,
+                &ctx.device,
+                &size,
+            ),
+            size,
+            camera,
+            camera_uniform,
+            camera_uniform_buffer,
+            camera_bind_group,
+        })
+    }
+
+    /// Tears the view down to what survives a device rebuild: its window and camera.
+    pub fn into_parts(self) -> (Arc<Window>, Camera) {
+        (self.window, self.camera)
+    }
+
+    pub fn window(&self) -> &Arc<Window> {
+        &self.window
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn camera_bind_group(&self) -> &BindGroup {
+        &self.camera_bind_group
+    }
+
+    /// Zero-area sizes are ignored, matching the main surface.
+    pub fn resize(&mut self, device: &Device, size: Size) {
+        if size.is_zero() {
+            return;
+        }
+        self.size = size;
+        self.camera.set_aspect_from_size(size);
+        self.reconfigure(device);
+    }
+
+    pub fn update_camera(&mut self, queue: &Queue) {
+        self.camera_uniform.update(&self.camera);
+        queue.write_buffer(
+            &self.camera_uniform_buffer,
+            0,
+            bytes_of(&self.camera_uniform),
+        );
+    }
+
+    pub fn begin_frame(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Option<SurfaceFrame>, RendererError> {
+        if self.size.is_zero() {
+            return Ok(None);
+        }
+
+        let (output, should_reconfigure_surface) = match self.surface.get_current_texture() {
+            wgpu::CurrentSurfaceTexture::Success(output) => (output, false),
+            wgpu::CurrentSurfaceTexture::Suboptimal(output) => (output, true),
+            wgpu::CurrentSurfaceTexture::Outdated | wgpu::CurrentSurfaceTexture::Lost => {
+                self.reconfigure(device);
+                return Ok(None);
+            }
+            wgpu::CurrentSurfaceTexture::Validation => {
+                return Err(RendererError::surface(
+                    "validation error while acquiring the next view surface texture",
+                ));
+            }
+            surface_status => {
+                warn!("Skipping view frame after surface status {surface_status:?}");
+                return Ok(None);
+            }
+        };
+
+        let view = output
+            .texture
+            .create_view(&TextureViewDescriptor::default());
+        let encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("View Rendering Encoder"),
+        });
+
+        Ok(Some(SurfaceFrame::new(
+            output,
+            encoder,
+            queue.clone(),
+            view,
+            &self.depth_texture,
+            [self.size.width, self.size.height],
+            should_reconfigure_surface,
+        )))
+    }
+
+    pub fn set_present_mode(&mut self, device: &Device, present_mode: PresentMode) {
+        self.surface_configuration.present_mode = present_mode;
+        self.reconfigure(device);
+    }
+
+    pub fn reconfigure(&mut self, device: &Device) {
+        let configured_size = self.size.clamp_size_for_gpu();
+        self.surface_configuration.width = configured_size.width;
+        self.surface_configuration.height = configured_size.height;
+        self.surface.configure(device, &self.surface_configuration);
+        self.depth_texture =
+            Texture::create_depth_texture(Self::DEPTH_TEXTURE_LABEL, device, &self.size);
+    }
+}
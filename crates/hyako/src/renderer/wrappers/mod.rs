@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use hyakou_core::types::Size;
+use hyakou_core::types::{Size, viewport::Viewport};
 use mockall::automock;
 use wgpu::{Instance, Surface};
 use winit::window::Window;
@@ -9,10 +9,19 @@ use winit::window::Window;
 pub trait SurfaceProvider {
     fn create_surface(&self, instance: &Instance) -> Option<Surface<'static>>;
     fn get_size(&self) -> Size;
+    /// Whether the surface should composite with what is behind the window.
+    fn is_transparent(&self) -> bool {
+        false
+    }
+    /// Physical pixels per logical pixel of the target window.
+    fn scale_factor(&self) -> f64 {
+        Viewport::DEFAULT_SCALE_FACTOR
+    }
 }
 
 pub struct WinitSurfaceProvider {
     pub window: Arc<Window>,
+    pub transparent: bool,
 }
 
 impl SurfaceProvider for WinitSurfaceProvider {
@@ -30,4 +39,12 @@ impl SurfaceProvider for WinitSurfaceProvider {
             height: size.height,
         }
     }
+
+    fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.window.scale_factor()
+    }
 }
@@ -1,4 +1,4 @@
-use std::{io::Result, sync::Arc};
+use std::{io::Result, path::PathBuf, sync::Arc};
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
@@ -7,51 +7,139 @@ use web_time::Instant;
 
 #[cfg(target_arch = "wasm32")]
 use wgpu::web_sys::HtmlCanvasElement;
-#[cfg(not(target_arch = "wasm32"))]
-use winit::dpi::PhysicalSize;
 #[cfg(target_arch = "wasm32")]
 use winit::platform::web::WindowAttributesExtWebSys;
+#[cfg(target_arch = "wasm32")]
+use winit::window::WindowAttributes;
 use winit::{
     application::ApplicationHandler,
+    dpi::PhysicalSize,
     event::{DeviceEvent, ElementState, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow},
     keyboard::PhysicalKey,
-    window::{Window, WindowAttributes},
+    window::{Window, WindowId},
 };
 
 use hyakou_core::{
-    Shared,
+    Shared, SharedAccess,
     events::Event,
-    types::{DeltaTime64, mouse_delta::MouseButton},
+    types::{DeltaTime64, mouse_delta::MouseButton, viewport::Viewport},
 };
 
+#[cfg(target_arch = "wasm32")]
+use log::warn;
+use log::{debug, error, info};
+
+#[cfg(target_arch = "wasm32")]
+use crate::flow::ListenerRegistry;
 use crate::{
-    flow::{FlowController, FlowHandle, RendererCommand},
-    renderer::SceneRenderer,
+    flow::{
+        Clock, FixedTimestep, FlowController, FlowHandle, FramePacing, RedrawDecision,
+        RendererCommand, SystemClock, TitleStats, WindowViews,
+    },
+    renderer::{
+        SceneRenderer, handlers::pointer_handler::PointerKind, settings::RendererSettings,
+        stats::RendererStats, util::asset_root::AssetRoot,
+    },
+    window_config::WindowConfig,
 };
 
 pub struct AppState {
-    window: Option<Arc<Window>>,
+    windows: WindowViews<Arc<Window>>,
     #[cfg(target_arch = "wasm32")]
     html_canvas_element: Option<HtmlCanvasElement>,
     flow_controller: FlowController,
     flow_handle: FlowHandle,
+    clock: Box<dyn Clock>,
     last_frame_time: Instant,
+    max_frame_delta: DeltaTime64,
+    last_frame_clamped: bool,
+    fixed_timestep: FixedTimestep,
+    started_at: Instant,
+    frames_rendered: u64,
+    frame_pacing: FramePacing,
+    title_stats: TitleStats,
+    show_title_stats: bool,
+    window_config: WindowConfig,
+    #[cfg(not(target_arch = "wasm32"))]
+    scene_file: Option<PathBuf>,
+    #[cfg(not(target_arch = "wasm32"))]
+    models: Vec<PathBuf>,
+    renderer_settings: RendererSettings,
+    asset_root: AssetRoot,
+    scale_factor: f64,
+}
+
+/// Time elapsed since the previous frame, capped at [`AppState::max_frame_delta`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameDelta {
+    pub seconds: DeltaTime64,
+    /// The real gap exceeded the cap, e.g. after a debugger pause or a dragged window.
+    pub clamped: bool,
 }
 
 impl AppState {
-    const MIN_TIME_IN_SECONDS: f64 = 0.05;
+    pub const MAX_FRAME_DELTA_SECONDS: DeltaTime64 = 0.1;
 
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn new() -> Result<Self> {
+    pub fn new(window_config: WindowConfig) -> Result<Self> {
         let (flow_controller, flow_handle) = FlowController::new_pair();
         Ok(Self {
-            window: None,
+            windows: WindowViews::new(),
             flow_controller,
             flow_handle,
+            clock: Box::new(SystemClock),
             last_frame_time: Instant::now(),
+            max_frame_delta: Self::MAX_FRAME_DELTA_SECONDS,
+            last_frame_clamped: false,
+            fixed_timestep: FixedTimestep::default(),
+            started_at: Instant::now(),
+            frames_rendered: 0,
+            frame_pacing: FramePacing::new(),
+            title_stats: TitleStats::new(window_config.title.clone()),
+            show_title_stats: true,
+            window_config,
+            #[cfg(not(target_arch = "wasm32"))]
+            scene_file: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            models: Vec::new(),
+            renderer_settings: RendererSettings::default(),
+            asset_root: AssetRoot::new(),
+            scale_factor: Viewport::DEFAULT_SCALE_FACTOR,
         })
     }
 
+    pub fn with_renderer_settings(mut self, settings: RendererSettings) -> Self {
+        self.renderer_settings = settings;
+        self
+    }
+
+    /// Directory the built-in scene is loaded from, instead of the one [`AssetRoot`] finds.
+    pub fn with_asset_root(mut self, path: impl Into<PathBuf>) -> Self {
+        self.asset_root = AssetRoot::with_override(path);
+        self
+    }
+
+    /// Applies the runtime part of `settings` to the running renderer.
+    pub fn apply_renderer_settings(&mut self, settings: RendererSettings) {
+        self.renderer_settings = settings;
+        self.send_and_drain(RendererCommand::ApplySettings(settings));
+    }
+
+    /// Scene file that replaces the built-in demo scene once the renderer is up.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_scene_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.scene_file = Some(path.into());
+        self
+    }
+
+    /// glTF files that replace the built-in demo scene once the renderer is up.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_models(mut self, paths: Vec<PathBuf>) -> Self {
+        self.models = paths;
+        self
+    }
+
     pub fn get_renderer(&self) -> Shared<Option<SceneRenderer>> {
         self.flow_controller.get_renderer()
     }
@@ -60,27 +148,279 @@ impl AppState {
     pub fn from_canvas_ref(
         canvas_ref: HtmlCanvasElement,
         upload_status_callback: Shared<Option<js_sys::Function>>,
+        asset_status_listeners: Shared<ListenerRegistry<js_sys::Function>>,
+        selection_listeners: Shared<ListenerRegistry<js_sys::Function>>,
     ) -> Result<Self> {
-        let (flow_controller, flow_handle) = FlowController::new_pair(upload_status_callback);
+        let window_config = WindowConfig::default();
+        let (flow_controller, flow_handle) = FlowController::new_pair(
+            upload_status_callback,
+            asset_status_listeners,
+            selection_listeners,
+        );
+        // winit schedules web redraws with requestAnimationFrame; one render per animation frame.
+        let mut frame_pacing = FramePacing::new();
+        frame_pacing.set_coalesce_redraws(true);
         Ok(Self {
-            window: None,
+            windows: WindowViews::new(),
             html_canvas_element: Some(canvas_ref),
             flow_controller,
             flow_handle,
+            clock: Box::new(SystemClock),
             last_frame_time: Instant::now(),
+            max_frame_delta: Self::MAX_FRAME_DELTA_SECONDS,
+            last_frame_clamped: false,
+            fixed_timestep: FixedTimestep::default(),
+            started_at: Instant::now(),
+            frames_rendered: 0,
+            frame_pacing,
+            title_stats: TitleStats::new(window_config.title.clone()),
+            show_title_stats: true,
+            window_config,
+            scale_factor: Viewport::DEFAULT_SCALE_FACTOR,
+            renderer_settings: RendererSettings::default(),
+            asset_root: AssetRoot::new(),
         })
     }
 
-    fn get_and_update_last_frame_time(&mut self) -> f64 {
-        let now = Instant::now();
-        let delta_time = self.get_last_frame_time(now);
+    /// Shows FPS, frame time and draw calls in the window title; disable for a plain title.
+    pub fn set_show_title_stats(&mut self, show: bool) {
+        self.show_title_stats = show;
+        if show {
+            return;
+        }
+        if let Some(window) = self.windows.primary() {
+            window.set_title(self.title_stats.base_title());
+        }
+    }
+
+    /// Physical pixels per logical pixel of the primary window.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Frame statistics of the scene renderer, once it exists.
+    pub fn renderer_stats(&self) -> Option<RendererStats> {
+        self.get_renderer()
+            .try_read_shared(|renderer| renderer.as_ref().map(SceneRenderer::stats))
+            .ok()
+            .flatten()
+    }
+
+    pub fn reset_renderer_stats(&self) {
+        let _ = self.get_renderer().try_write_shared(|renderer| {
+            if let Some(renderer) = renderer.as_mut() {
+                renderer.reset_stats();
+            }
+        });
+    }
+
+    pub fn frame_pacing_mut(&mut self) -> &mut FramePacing {
+        &mut self.frame_pacing
+    }
+
+    pub fn max_frame_delta(&self) -> DeltaTime64 {
+        self.max_frame_delta
+    }
+
+    /// Longest frame delta handed to the simulation; slower frames are slowed down instead.
+    pub fn set_max_frame_delta(&mut self, seconds: DeltaTime64) {
+        self.max_frame_delta = seconds.max(0.0);
+    }
+
+    pub fn simulation_step_seconds(&self) -> DeltaTime64 {
+        self.fixed_timestep.step_seconds()
+    }
+
+    /// Rate of the fixed simulation update, independent of how often frames are presented.
+    pub fn set_simulation_rate(&mut self, rate_hz: f64) {
+        self.fixed_timestep.set_rate(rate_hz);
+    }
+
+    /// Whether the most recent frame delta was capped, so callers can skip rather than replay
+    /// a long pause.
+    pub fn last_frame_was_clamped(&self) -> bool {
+        self.last_frame_clamped
+    }
+
+    /// Replaces the time source for frame deltas and pacing and restarts the frame clock on it.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.last_frame_time = clock.now();
+        self.started_at = self.last_frame_time;
+        self.clock = Box::new(clock);
+    }
+
+    /// Starts a frame at `now` and returns the time since the previous one. On the web the clock
+    /// is `performance.now()`, the time base of the animation frame that triggered the redraw.
+    pub fn begin_frame(&mut self, now: Instant) -> FrameDelta {
+        let frame_delta = self.frame_delta_since_last(now);
         self.last_frame_time = now;
-        delta_time
+        self.last_frame_clamped = frame_delta.clamped;
+        if frame_delta.clamped {
+            debug!(
+                "Frame delta exceeded {}s; clamping to keep the simulation stable",
+                self.max_frame_delta
+            );
+        }
+        frame_delta
+    }
+
+    fn get_and_update_last_frame_time(&mut self) -> DeltaTime64 {
+        let now = self.clock.now();
+        self.begin_frame(now).seconds
+    }
+
+    fn get_last_frame_time(&self, now: Instant) -> DeltaTime64 {
+        self.frame_delta_since_last(now).seconds
+    }
+
+    fn frame_delta_since_last(&self, now: Instant) -> FrameDelta {
+        let elapsed = now.duration_since(self.last_frame_time).as_secs_f64();
+        FrameDelta {
+            seconds: elapsed.min(self.max_frame_delta),
+            clamped: elapsed > self.max_frame_delta,
+        }
+    }
+
+    /// Restarts the frame clock after a pause so the first frame back is not one huge delta.
+    fn restart_frame_clock_on_resume(&mut self) {
+        if self.frame_pacing.take_resumed() {
+            self.last_frame_time = self.clock.now();
+        }
+    }
+
+    /// Runs the simulation steps owed since the last frame, then renders once with the leftover
+    /// time as interpolation alpha.
+    fn advance_and_render(&mut self) -> DeltaTime64 {
+        let delta = self.get_and_update_last_frame_time();
+        // After a long gap, e.g. a backgrounded tab or a debugger pause, continue with a single
+        // step instead of catching up, so animations resume where they stopped.
+        let simulated = if self.last_frame_clamped {
+            self.fixed_timestep.reset();
+            self.fixed_timestep.step_seconds()
+        } else {
+            delta
+        };
+        let fixed_steps = self.fixed_timestep.advance(simulated);
+        for _ in 0..fixed_steps.steps {
+            self.flow_handle.send(RendererCommand::Update {
+                dt: self.fixed_timestep.step_seconds(),
+            });
+        }
+        self.send_and_drain(RendererCommand::Redraw {
+            interpolation_alpha: fixed_steps.alpha,
+        });
+        delta
+    }
+
+    fn update_title_stats(&mut self, frame_seconds: DeltaTime64) {
+        if !self.show_title_stats {
+            return;
+        }
+        let Some(window) = self.windows.primary() else {
+            return;
+        };
+
+        let (draw_calls, paused) = self
+            .get_renderer()
+            .try_read_shared(|renderer| {
+                renderer
+                    .as_ref()
+                    .map(|renderer| (renderer.last_draw_count(), renderer.is_simulation_paused()))
+            })
+            .ok()
+            .flatten()
+            .unwrap_or((0, false));
+        let pause_title = self.title_stats.set_paused(paused);
+        let frame_title =
+            self.title_stats
+                .record_frame(self.clock.now(), frame_seconds, draw_calls);
+        if let Some(title) = frame_title.or(pause_title) {
+            window.set_title(&title);
+        }
     }
 
-    fn get_last_frame_time(&mut self, now: Instant) -> DeltaTime64 {
-        let delta = now.duration_since(self.last_frame_time);
-        delta.as_secs_f64().min(Self::MIN_TIME_IN_SECONDS)
+    fn log_frame_stats(&self) {
+        let elapsed = self
+            .clock
+            .now()
+            .duration_since(self.started_at)
+            .as_secs_f64();
+        let average_fps = if elapsed > 0.0 {
+            self.frames_rendered as f64 / elapsed
+        } else {
+            0.0
+        };
+        info!(
+            "Rendered {} frames in {elapsed:.1}s ({average_fps:.1} fps average)",
+            self.frames_rendered
+        );
+    }
+
+    fn send_resize(&mut self, size: PhysicalSize<u32>) {
+        self.send_and_drain(RendererCommand::Resize {
+            width: f64::from(size.width),
+            height: f64::from(size.height),
+        });
+    }
+
+    /// Opens another view of the scene. It starts from the main camera and renders alongside the
+    /// primary window until it is closed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_secondary_window(&mut self, event_loop: &ActiveEventLoop) {
+        let title = format!("{} - View {}", self.window_config.title, self.windows.len());
+        let window_attributes = self
+            .window_config
+            .window_attributes()
+            .with_title(title)
+            .with_maximized(false);
+        let window = match event_loop.create_window(window_attributes) {
+            Ok(window) => Arc::new(window),
+            Err(window_error) => {
+                error!("Failed to open additional window: {window_error:?}");
+                return;
+            }
+        };
+
+        self.windows.add_secondary(window.id(), window.clone());
+        self.send_and_drain(RendererCommand::ViewCreated {
+            window: window.clone(),
+        });
+        window.request_redraw();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn open_secondary_window(&mut self, _event_loop: &ActiveEventLoop) {
+        warn!("Additional windows are not supported on the web");
+    }
+
+    /// Secondary windows only render; closing one drops its view without ending the app.
+    fn handle_secondary_window_event(&mut self, window_id: WindowId, event: WindowEvent) {
+        let Some(window) = self.windows.get(window_id).cloned() else {
+            return;
+        };
+
+        match event {
+            WindowEvent::CloseRequested | WindowEvent::Destroyed => {
+                self.windows.remove(window_id);
+                self.send_and_drain(RendererCommand::ViewClosed { window_id });
+            }
+            WindowEvent::Resized(size) => self.send_view_resize(window_id, size),
+            WindowEvent::ScaleFactorChanged { .. } => {
+                self.send_view_resize(window_id, window.inner_size());
+            }
+            WindowEvent::RedrawRequested => {
+                self.send_and_drain(RendererCommand::RedrawView { window_id });
+            }
+            _ => {}
+        }
+    }
+
+    fn send_view_resize(&mut self, window_id: WindowId, size: PhysicalSize<u32>) {
+        self.send_and_drain(RendererCommand::ViewResized {
+            window_id,
+            width: f64::from(size.width),
+            height: f64::from(size.height),
+        });
     }
 
     fn send_and_drain(&mut self, command: RendererCommand) {
@@ -90,26 +430,61 @@ impl AppState {
 }
 
 impl ApplicationHandler<Event> for AppState {
+    /// The window and renderer are created on the first resume only. Later resumes keep the scene
+    /// and just recreate the surface that [`Self::suspended`] released.
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        if let Some(window) = self.windows.primary().cloned() {
+            self.frame_pacing.set_suspended(false);
+            self.restart_frame_clock_on_resume();
+            self.send_and_drain(RendererCommand::Resumed);
+            window.request_redraw();
+            return;
+        }
+
         #[cfg(not(target_arch = "wasm32"))]
-        let window_attributes =
-            WindowAttributes::default().with_inner_size(PhysicalSize::new(1920, 1080));
+        let window_attributes = self.window_config.window_attributes();
 
+        // The canvas element owns its size and title on the web.
         #[cfg(target_arch = "wasm32")]
-        let window_attributes =
-            WindowAttributes::default().with_canvas(self.html_canvas_element.clone());
+        let window_attributes = WindowAttributes::default()
+            .with_canvas(self.html_canvas_element.clone())
+            .with_transparent(self.window_config.transparent);
 
-        let window = event_loop
-            .create_window(window_attributes)
-            .map(Arc::new)
-            .unwrap();
+        let window = match event_loop.create_window(window_attributes) {
+            Ok(window) => Arc::new(window),
+            Err(window_error) => {
+                error!("Failed to create the main window: {window_error:?}");
+                event_loop.exit();
+                return;
+            }
+        };
 
-        self.send_and_drain(RendererCommand::WindowCreated(window.clone()));
+        self.scale_factor = window.scale_factor();
+        self.send_and_drain(RendererCommand::WindowCreated {
+            window: window.clone(),
+            transparent: self.window_config.transparent,
+            settings: self.renderer_settings,
+            asset_root: self.asset_root.clone(),
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = self.scene_file.take() {
+            self.send_and_drain(RendererCommand::LoadSceneFile { path });
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.models.is_empty() {
+            let paths = std::mem::take(&mut self.models);
+            self.send_and_drain(RendererCommand::LoadModels { paths });
+        }
 
-        self.window = Some(window.clone());
+        self.windows.set_primary(window.id(), window.clone());
         window.request_redraw();
     }
 
+    fn suspended(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.frame_pacing.set_suspended(true);
+        self.send_and_drain(RendererCommand::Suspended);
+    }
+
     fn user_event(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop, event: Event) {
         match event {
             Event::AnimateCamera(request) => {
@@ -118,6 +493,21 @@ impl ApplicationHandler<Event> for AppState {
             Event::StopCameraAnimation => {
                 self.send_and_drain(RendererCommand::StopCameraAnimation);
             }
+            Event::SetCamera { eye, target } => {
+                self.send_and_drain(RendererCommand::SetCamera { eye, target });
+            }
+            Event::SetCameraFov { degrees } => {
+                self.send_and_drain(RendererCommand::SetCameraFov { degrees });
+            }
+            Event::FocusAsset(id) => {
+                self.send_and_drain(RendererCommand::FocusAsset(id));
+            }
+            Event::SelectAsset(id) => {
+                self.send_and_drain(RendererCommand::SelectAsset(id));
+            }
+            Event::ClearSelection => {
+                self.send_and_drain(RendererCommand::ClearSelection);
+            }
             Event::AssetUpload(asset_information, light_type) => {
                 self.send_and_drain(RendererCommand::AssetUploadRequested {
                     id: asset_information.id(),
@@ -139,24 +529,69 @@ impl ApplicationHandler<Event> for AppState {
                 });
             }
             Event::Resize(width, height) => {
-                let dt = self.get_and_update_last_frame_time();
-                self.send_and_drain(RendererCommand::Resize { dt, width, height });
+                self.send_and_drain(RendererCommand::Resize { width, height });
+            }
+            Event::AddAsset {
+                name,
+                bytes,
+                light_type,
+                reply,
+            } => {
+                self.send_and_drain(RendererCommand::AddAssetRequested {
+                    name,
+                    asset_type: light_type,
+                    bytes,
+                    reply,
+                });
             }
         }
     }
 
     fn window_event(
         &mut self,
-        _event_loop: &winit::event_loop::ActiveEventLoop,
-        _window_id: winit::window::WindowId,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        window_id: WindowId,
         event: WindowEvent,
     ) {
+        if !self.windows.is_primary(window_id) {
+            self.handle_secondary_window_event(window_id, event);
+            return;
+        }
+
         let egui_consumed = self.flow_controller.handle_egui_window_event(&event);
 
         match event {
+            WindowEvent::CloseRequested => {
+                event_loop.exit();
+            }
+            WindowEvent::Resized(size) => {
+                self.frame_pacing
+                    .set_minimized(size.width == 0 || size.height == 0);
+                self.restart_frame_clock_on_resume();
+                self.send_resize(size);
+            }
+            WindowEvent::Occluded(occluded) => {
+                self.frame_pacing.set_occluded(occluded);
+                self.restart_frame_clock_on_resume();
+            }
+            WindowEvent::Focused(focused) => {
+                self.frame_pacing.set_focused(focused);
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = scale_factor;
+                self.send_and_drain(RendererCommand::ScaleFactorChanged { scale_factor });
+                if let Some(window) = self.windows.primary().cloned() {
+                    self.send_resize(window.inner_size());
+                }
+            }
             WindowEvent::RedrawRequested => {
-                let delta = self.get_and_update_last_frame_time();
-                self.send_and_drain(RendererCommand::Redraw { dt: delta });
+                self.frame_pacing.frame_rendered();
+                let delta = self.advance_and_render();
+                if self.flow_controller.take_device_lost() {
+                    self.send_and_drain(RendererCommand::RecoverDevice);
+                }
+                self.frames_rendered += 1;
+                self.update_title_stats(delta);
             }
             WindowEvent::CursorEntered { .. } => {
                 if egui_consumed {
@@ -173,6 +608,18 @@ impl ApplicationHandler<Event> for AppState {
                     y: position.y,
                 });
             }
+            WindowEvent::Touch(touch) => {
+                if egui_consumed {
+                    return;
+                }
+                self.send_and_drain(RendererCommand::Touch {
+                    id: touch.id,
+                    phase: touch.phase,
+                    x: touch.location.x,
+                    y: touch.location.y,
+                    kind: PointerKind::from_touch_force(touch.force),
+                });
+            }
             WindowEvent::CursorLeft { .. } => {
                 if egui_consumed {
                     return;
@@ -190,20 +637,67 @@ impl ApplicationHandler<Event> for AppState {
                     key,
                     pressed: event.state == ElementState::Pressed,
                 });
+                if self.flow_controller.take_exit_request() {
+                    event_loop.exit();
+                }
+                if self.flow_controller.take_window_request() {
+                    self.open_secondary_window(event_loop);
+                }
+            }
+            WindowEvent::HoveredFile(path) => {
+                debug!("File hovering over window: {}", path.display());
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.send_and_drain(RendererCommand::FileDropped { path });
             }
             _ => {}
         }
     }
 
+    /// Frames are driven from here: input events only update state, and the single render per
+    /// loop iteration happens in the `RedrawRequested` arm.
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        if self.windows.primary().is_none() {
+            return;
+        }
+
+        match self.frame_pacing.poll(self.clock.now()) {
+            RedrawDecision::Redraw => {
+                event_loop.set_control_flow(ControlFlow::Wait);
+                self.windows
+                    .iter()
+                    .for_each(|(_, window)| window.request_redraw());
+            }
+            RedrawDecision::WaitUntil(deadline) => {
+                event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
+            }
+            RedrawDecision::Wait => event_loop.set_control_flow(ControlFlow::Wait),
+        }
+    }
+
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.log_frame_stats();
+        self.flow_controller.shutdown();
+        self.windows.clear();
+    }
+
     fn device_event(
         &mut self,
         _event_loop: &winit::event_loop::ActiveEventLoop,
         _device_id: winit::event::DeviceId,
         event: DeviceEvent,
     ) {
+        // Device events bypass egui, so motion and presses it is already handling through the
+        // window are dropped. Releases always go through so a drag can end over a panel.
+        let egui_has_pointer = self.flow_controller.egui_wants_pointer_input();
         match event {
+            DeviceEvent::MouseMotion { .. } if egui_has_pointer => {}
+            DeviceEvent::Button {
+                state: ElementState::Pressed,
+                ..
+            } if egui_has_pointer => {}
             DeviceEvent::MouseMotion { delta } => {
-                let dt = self.get_last_frame_time(Instant::now()) as f32;
+                let dt = self.get_last_frame_time(self.clock.now()) as f32;
                 self.send_and_drain(RendererCommand::MouseMotion {
                     dx: delta.0,
                     dy: delta.1,
@@ -230,31 +724,75 @@ impl ApplicationHandler<Event> for AppState {
 
 #[cfg(test)]
 mod tests {
-    use std::{thread::sleep, time::Duration};
+    use std::time::Duration;
 
     use super::*;
+    use crate::flow::ManualClock;
 
-    fn setup() -> AppState {
-        AppState::new().unwrap()
+    fn setup() -> (AppState, ManualClock) {
+        let clock = ManualClock::new();
+        let mut state = AppState::new(WindowConfig::default()).unwrap();
+        state.set_clock(clock.clone());
+        (state, clock)
     }
 
     #[test]
-    fn test_clamping_strategy() {
-        let mut state = setup();
+    fn test_frame_delta_is_capped_at_max() {
+        let (mut state, clock) = setup();
+        state.set_max_frame_delta(0.01);
+        clock.advance(Duration::from_millis(30));
+
         let actual = state.get_and_update_last_frame_time();
-        assert!(actual > 0.0);
-        assert!(actual <= AppState::MIN_TIME_IN_SECONDS);
-        sleep(Duration::from_secs(1));
+
+        assert_eq!(actual, 0.01);
+        assert!(state.last_frame_was_clamped());
+    }
+
+    #[test]
+    fn test_frame_delta_below_max_is_reported_unclamped() {
+        let (mut state, clock) = setup();
+        state.get_and_update_last_frame_time();
+        clock.advance(Duration::from_millis(16));
+
         let actual = state.get_and_update_last_frame_time();
-        assert!(actual <= AppState::MIN_TIME_IN_SECONDS);
+
+        assert!((actual - 0.016).abs() < 1e-9);
+        assert!(!state.last_frame_was_clamped());
     }
 
     #[test]
-    fn test_accurate_calculation() {
-        let mut state = setup();
+    fn test_default_max_frame_delta_allows_low_frame_rates() {
+        let (state, _) = setup();
+
+        assert_eq!(state.max_frame_delta(), AppState::MAX_FRAME_DELTA_SECONDS);
+        assert!(state.max_frame_delta() > 1.0 / 20.0);
+    }
+
+    #[test]
+    fn test_resize_does_not_consume_frame_delta() {
+        let (mut state, clock) = setup();
         state.get_and_update_last_frame_time();
-        sleep(Duration::from_millis(16));
-        let second_delta = state.get_and_update_last_frame_time();
-        assert!(second_delta >= 0.015 && second_delta <= AppState::MIN_TIME_IN_SECONDS);
+        clock.advance(Duration::from_millis(16));
+
+        state.send_resize(PhysicalSize::new(800, 600));
+        let delta = state.get_and_update_last_frame_time();
+
+        assert!((delta - 0.016).abs() < 1e-9);
+        assert_eq!(state.frames_rendered, 0);
+    }
+
+    #[test]
+    fn test_resuming_from_occlusion_restarts_frame_clock() {
+        let (mut state, clock) = setup();
+        state.get_and_update_last_frame_time();
+
+        state.frame_pacing.set_occluded(true);
+        clock.advance(Duration::from_millis(30));
+        state.frame_pacing.set_occluded(false);
+        state.restart_frame_clock_on_resume();
+        let delta = state.get_and_update_last_frame_time();
+
+        assert_eq!(delta, 0.0);
+        assert!(!state.last_frame_was_clamped());
     }
 }
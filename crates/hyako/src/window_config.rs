@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use log::warn;
+use winit::{dpi::PhysicalSize, window::WindowAttributes};
+
+/// How the app window is created. Embedders can build one in code, and the `HYAKOU_WINDOW_*`
+/// environment variables override individual fields through [`WindowConfig::with_env_overrides`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowConfig {
+    pub title: String,
+    pub size: PhysicalSize<u32>,
+    pub min_size: Option<PhysicalSize<u32>>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub maximized: bool,
+    /// Also selects a non-opaque surface alpha mode, so the window background can show through.
+    pub transparent: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: Self::DEFAULT_TITLE.to_string(),
+            size: PhysicalSize::new(1920, 1080),
+            min_size: None,
+            resizable: true,
+            decorations: true,
+            maximized: false,
+            transparent: false,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub const DEFAULT_TITLE: &str = "Hyakou";
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = PhysicalSize::new(width, height);
+        self
+    }
+
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some(PhysicalSize::new(width, height));
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn with_maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn with_env_overrides(self) -> Self {
+        self.with_overrides(|key| std::env::var(key).ok())
+    }
+
+    /// Applies overrides looked up by `HYAKOU_WINDOW_*` key. Unparsable values are ignored.
+    pub fn with_overrides(mut self, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(title) = lookup("HYAKOU_WINDOW_TITLE") {
+            self.title = title;
+        }
+        if let Some(width) = parse_override(&lookup, "HYAKOU_WINDOW_WIDTH") {
+            self.size.width = width;
+        }
+        if let Some(height) = parse_override(&lookup, "HYAKOU_WINDOW_HEIGHT") {
+            self.size.height = height;
+        }
+        if let Some(resizable) = parse_override(&lookup, "HYAKOU_WINDOW_RESIZABLE") {
+            self.resizable = resizable;
+        }
+        if let Some(decorations) = parse_override(&lookup, "HYAKOU_WINDOW_DECORATIONS") {
+            self.decorations = decorations;
+        }
+        if let Some(maximized) = parse_override(&lookup, "HYAKOU_WINDOW_MAXIMIZED") {
+            self.maximized = maximized;
+        }
+        if let Some(transparent) = parse_override(&lookup, "HYAKOU_WINDOW_TRANSPARENT") {
+            self.transparent = transparent;
+        }
+        self
+    }
+
+    pub fn window_attributes(&self) -> WindowAttributes {
+        let mut attributes = WindowAttributes::default()
+            .with_title(self.title.clone())
+            .with_inner_size(self.size)
+            .with_resizable(self.resizable)
+            .with_decorations(self.decorations)
+            .with_maximized(self.maximized)
+            .with_transparent(self.transparent);
+        if let Some(min_size) = self.min_size {
+            attributes = attributes.with_min_inner_size(min_size);
+        }
+        attributes
+    }
+}
+
+fn parse_override<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>, key: &str) -> Option<T> {
+    let value = lookup(key)?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            warn!("Ignoring {key}={value:?}: not a valid value");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::dpi::Size;
+
+    use super::*;
+
+    #[test]
+    fn test_default_config_builds_resizable_decorated_window() {
+        let attributes = WindowConfig::default().window_attributes();
+
+        assert_eq!(attributes.title, WindowConfig::DEFAULT_TITLE);
+        assert_eq!(
+            attributes.inner_size,
+            Some(Size::Physical(PhysicalSize::new(1920, 1080)))
+        );
+        assert_eq!(attributes.min_inner_size, None);
+        assert!(attributes.resizable);
+        assert!(attributes.decorations);
+        assert!(!attributes.maximized);
+        assert!(!attributes.transparent);
+    }
+
+    #[test]
+    fn test_builder_config_is_applied_to_attributes() {
+        let attributes = WindowConfig::default()
+            .with_title("Embedded")
+            .with_size(800, 600)
+            .with_min_size(320, 240)
+            .with_resizable(false)
+            .with_decorations(false)
+            .with_maximized(true)
+            .with_transparent(true)
+            .window_attributes();
+
+        assert_eq!(attributes.title, "Embedded");
+        assert_eq!(
+            attributes.inner_size,
+            Some(Size::Physical(PhysicalSize::new(800, 600)))
+        );
+        assert_eq!(
+            attributes.min_inner_size,
+            Some(Size::Physical(PhysicalSize::new(320, 240)))
+        );
+        assert!(!attributes.resizable);
+        assert!(!attributes.decorations);
+        assert!(attributes.maximized);
+        assert!(attributes.transparent);
+    }
+
+    #[test]
+    fn test_overrides_replace_fields_and_skip_invalid_values() {
+        let config = WindowConfig::default().with_overrides(|key| match key {
+            "HYAKOU_WINDOW_TITLE" => Some("From env".to_string()),
+            "HYAKOU_WINDOW_WIDTH" => Some("1280".to_string()),
+            "HYAKOU_WINDOW_HEIGHT" => Some("tall".to_string()),
+            "HYAKOU_WINDOW_TRANSPARENT" => Some("true".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(config.title, "From env");
+        assert_eq!(config.size, PhysicalSize::new(1280, 1080));
+        assert!(config.transparent);
+        assert!(config.resizable);
+    }
+}
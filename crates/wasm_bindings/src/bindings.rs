@@ -1,52 +1,112 @@
-use hyako::{renderer::Renderer, state::AppState};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use hyako::{flow::ListenerRegistry, renderer::SceneRenderer, state::AppState};
 use hyakou_core::{
     Shared, SharedAccess,
-    components::{LightType, camera::data_structures::CameraMode},
-    events::Event,
+    components::{
+        LightType,
+        camera::{camera::Camera, data_structures::CameraMode},
+    },
+    events::{AssetReply, Event},
     shared,
     types::shared::{AssetBundleInformation, AssetInformation, Coordinates3},
 };
-use js_sys::{Array, BigInt, Reflect, Uint8Array};
+use js_sys::{Array, BigInt, Promise, Reflect, Uint8Array};
 use strum::VariantArray;
 use wasm_bindgen::{JsValue, prelude::wasm_bindgen};
 
-#[cfg(target_arch = "wasm32")]
-use web_sys::HtmlCanvasElement;
 use winit::event_loop::{EventLoop, EventLoopProxy};
 
 #[cfg(target_arch = "wasm32")]
 use winit::platform::web::EventLoopExtWebSys;
 
-use crate::{CameraAnimationOptions, CameraAnimationStateDO, CameraDO};
+use crate::{
+    CameraAnimationOptions, CameraAnimationStateDO, CameraDO,
+    canvas::{CanvasResizeObserver, resolve_canvas},
+    logging::ensure_logging,
+};
 
 #[wasm_bindgen]
 pub struct Hyako {
     app_state: Option<AppState>,
-    renderer: Shared<Option<Renderer>>,
+    renderer: Shared<Option<SceneRenderer>>,
     event_loop: Option<EventLoop<Event>>,
     event_loop_proxy: EventLoopProxy<Event>,
     upload_status_callback: Shared<Option<js_sys::Function>>,
+    asset_status_listeners: Shared<ListenerRegistry<js_sys::Function>>,
+    selection_listeners: Shared<ListenerRegistry<js_sys::Function>>,
+    _resize_observer: CanvasResizeObserver,
+}
+
+/// Handle for one registered listener. Disposing it is safe more than once and after the app
+/// itself was freed.
+#[wasm_bindgen]
+pub struct ListenerSubscription {
+    listeners: Weak<RefCell<ListenerRegistry<js_sys::Function>>>,
+    id: u64,
+}
+
+impl ListenerSubscription {
+    fn register(
+        listeners: &Shared<ListenerRegistry<js_sys::Function>>,
+        callback: js_sys::Function,
+    ) -> Self {
+        Self {
+            id: listeners.write_shared(|listeners| listeners.register(callback)),
+            listeners: Rc::downgrade(listeners),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ListenerSubscription {
+    pub fn dispose(&self) {
+        if let Some(listeners) = self.listeners.upgrade() {
+            listeners.write_shared(|listeners| {
+                listeners.unregister(self.id);
+            });
+        }
+    }
 }
 
 #[wasm_bindgen]
 impl Hyako {
+    /// `canvas` is a `<canvas>` element, the id of one, or `undefined` to append a new canvas to
+    /// the page body.
     #[cfg(target_arch = "wasm32")]
     #[wasm_bindgen(constructor)]
-    pub fn new(canvas_ref: HtmlCanvasElement) -> Result<Hyako, JsValue> {
-        console_error_panic_hook::set_once();
-        let _ = console_log::init_with_level(log::Level::Debug);
+    pub fn new(canvas: JsValue) -> Result<Hyako, JsValue> {
+        ensure_logging();
+        let canvas_ref = resolve_canvas(&canvas)?;
         let event_loop = match EventLoop::<Event>::with_user_event().build() {
             Ok(event_loop) => event_loop,
             Err(error) => return Err(JsValue::from_str(&error.to_string())),
         };
         log::info!("Event loop initialized!");
         let upload_status_callback: Shared<Option<js_sys::Function>> = shared(None);
-        let app_state = match AppState::from_canvas_ref(canvas_ref, upload_status_callback.clone())
-        {
+        let asset_status_listeners = shared(ListenerRegistry::new());
+        let selection_listeners = shared(ListenerRegistry::new());
+        let app_state = match AppState::from_canvas_ref(
+            canvas_ref.clone(),
+            upload_status_callback.clone(),
+            asset_status_listeners.clone(),
+            selection_listeners.clone(),
+        ) {
             Ok(app_state) => app_state,
             Err(error) => return Err(JsValue::from_str(&error.to_string())),
         };
         let event_loop_proxy = event_loop.create_proxy();
+        let resize_proxy = event_loop_proxy.clone();
+        let resize_observer = CanvasResizeObserver::observe(canvas_ref, move |width, height| {
+            if let Err(error) =
+                resize_proxy.send_event(Event::Resize(f64::from(width), f64::from(height)))
+            {
+                log::warn!("Failed to forward canvas resize: {error}");
+            }
+        })?;
         let renderer = app_state.get_renderer();
         Ok(Hyako {
             app_state: Some(app_state),
@@ -54,6 +114,9 @@ impl Hyako {
             event_loop: Some(event_loop),
             event_loop_proxy,
             upload_status_callback,
+            asset_status_listeners,
+            selection_listeners,
+            _resize_observer: resize_observer,
         })
     }
 
@@ -96,6 +159,37 @@ impl Hyako {
         self.send_event(Event::StopCameraAnimation)
     }
 
+    /// Places the camera at `eye` looking at `target`. Throws for non-finite coordinates or a view
+    /// direction that is zero or parallel to the camera's up vector.
+    #[wasm_bindgen]
+    pub fn set_camera(&self, eye: Coordinates3, target: Coordinates3) -> Result<(), JsValue> {
+        let (eye, target) = (eye.to_vec(), target.to_vec());
+        let up = self.read_renderer(|renderer| renderer.camera.up)?;
+        Camera::view_direction_between(eye, target, up)
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+
+        self.send_event(Event::SetCamera { eye, target })
+    }
+
+    /// Moves the camera so the mesh `id` fills the view. Throws for unknown ids.
+    #[wasm_bindgen]
+    pub fn focus_asset(&self, id: String) -> Result<(), JsValue> {
+        if !self.read_renderer(|renderer| renderer.asset_manager.contains(&id))? {
+            return Err(JsValue::from_str(&format!("No asset with id `{id}`")));
+        }
+
+        self.send_event(Event::FocusAsset(id))
+    }
+
+    /// Sets the vertical field of view in degrees.
+    #[wasm_bindgen]
+    pub fn set_fov(&self, degrees: f32) -> Result<(), JsValue> {
+        Camera::fovy_from_degrees(degrees)
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+
+        self.send_event(Event::SetCameraFov { degrees })
+    }
+
     #[wasm_bindgen]
     pub fn upload_file(
         &self,
@@ -106,6 +200,45 @@ impl Hyako {
         self.send_event(Event::AssetUpload(file, light_type))
     }
 
+    /// Imports a glTF/GLB file from its bytes. The promise resolves with the ids of the created
+    /// meshes, or rejects with the import error message.
+    #[wasm_bindgen]
+    pub fn add_asset(&self, name: String, bytes: Uint8Array, lit: bool) -> Promise {
+        let light_type = if lit {
+            LightType::LIGHT
+        } else {
+            LightType::NO_LIGHT
+        };
+        let mut request = Some((name, bytes.to_vec()));
+
+        Promise::new(&mut |resolve, reject| {
+            let Some((name, bytes)) = request.take() else {
+                return;
+            };
+            let reply_reject = reject.clone();
+            let reply: AssetReply = Box::new(move |result| {
+                let _ = match result {
+                    Ok(ids) => resolve.call1(
+                        &JsValue::UNDEFINED,
+                        &ids.into_iter().map(JsValue::from).collect::<Array>(),
+                    ),
+                    Err(error) => {
+                        reply_reject.call1(&JsValue::UNDEFINED, &JsValue::from_str(&error))
+                    }
+                };
+            });
+
+            if let Err(error) = self.send_event(Event::AddAsset {
+                name,
+                bytes,
+                light_type,
+                reply,
+            }) {
+                let _ = reject.call1(&JsValue::UNDEFINED, &error);
+            }
+        })
+    }
+
     #[wasm_bindgen]
     pub fn upload_asset_bundle(
         &self,
@@ -188,6 +321,52 @@ impl Hyako {
             .try_write_shared(|slot| *slot = Some(callback));
     }
 
+    /// Calls `callback` with `{ id, status, message, stage, progress }` whenever an upload starts
+    /// loading (`"loading"`), is added to the scene (`"ready"`) or fails (`"failed"`, with the
+    /// importer error as `message`). While a file upload is parsed, further `"loading"` events
+    /// carry the `stage` it reached and the `progress` of that stage from 0 to 1. Every
+    /// registered callback is called; dispose the returned subscription to remove one.
+    #[wasm_bindgen]
+    pub fn on_asset_status(&self, callback: js_sys::Function) -> ListenerSubscription {
+        ListenerSubscription::register(&self.asset_status_listeners, callback)
+    }
+
+    /// Calls `callback` with the primary selected asset id, or `null` when the selection was
+    /// cleared, once per change, whether it came from a click, a shortcut or [`Self::select`].
+    #[wasm_bindgen]
+    pub fn on_selection_changed(&self, callback: js_sys::Function) -> ListenerSubscription {
+        ListenerSubscription::register(&self.selection_listeners, callback)
+    }
+
+    /// Selects the asset `id`, replacing the current selection. Throws for unknown ids.
+    #[wasm_bindgen]
+    pub fn select(&self, id: String) -> Result<(), JsValue> {
+        if !self.read_renderer(|renderer| renderer.asset_manager.contains(&id))? {
+            return Err(JsValue::from_str(&format!("No asset with id `{id}`")));
+        }
+
+        self.send_event(Event::SelectAsset(id))
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_selection(&self) -> Result<(), JsValue> {
+        self.send_event(Event::ClearSelection)
+    }
+
+    /// The graphics API in use: `"webgpu"`, or `"gl"` when the browser only offers WebGL2.
+    #[wasm_bindgen]
+    pub fn backend(&self) -> Result<String, JsValue> {
+        self.read_renderer(|renderer| renderer.backend().to_str().to_string())
+    }
+
+    fn read_renderer<T>(&self, read: impl FnOnce(&SceneRenderer) -> T) -> Result<T, JsValue> {
+        self.renderer
+            .try_read_shared(|renderer| renderer.as_ref().map(read))
+            .ok()
+            .flatten()
+            .ok_or_else(|| JsValue::from_str("Renderer missing or not initialized"))
+    }
+
     fn send_event(&self, event: Event) -> Result<(), JsValue> {
         match self.event_loop_proxy.send_event(event) {
             Ok(_) => Ok(()),
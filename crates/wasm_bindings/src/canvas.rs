@@ -0,0 +1,227 @@
+#[cfg(target_arch = "wasm32")]
+use std::{cell::RefCell, rc::Rc};
+
+#[cfg(target_arch = "wasm32")]
+use js_sys::Array;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+#[cfg(target_arch = "wasm32")]
+use web_sys::{Document, HtmlCanvasElement, ResizeObserver};
+
+/// Pixel size of a canvas backing store for a CSS size, so the surface matches the physical
+/// pixels on HiDPI screens.
+pub fn backing_size(client_width: i32, client_height: i32, device_pixel_ratio: f64) -> (u32, u32) {
+    let ratio = if device_pixel_ratio > 0.0 && device_pixel_ratio.is_finite() {
+        device_pixel_ratio
+    } else {
+        1.0
+    };
+    let scale = |css_pixels: i32| (f64::from(css_pixels.max(0)) * ratio).round() as u32;
+    (scale(client_width), scale(client_height))
+}
+
+/// Collects canvas size changes and hands out at most one new backing size per animation frame,
+/// so a stream of resize notifications only reconfigures the surface once per frame.
+#[derive(Debug, Default)]
+pub struct ResizeDebouncer {
+    pending: Option<(u32, u32)>,
+    applied: Option<(u32, u32)>,
+}
+
+impl ResizeDebouncer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the canvas' CSS size. Returns true when nothing was pending yet, i.e. when the
+    /// caller has to schedule a frame callback that calls [`Self::take`].
+    pub fn observe(
+        &mut self,
+        client_width: i32,
+        client_height: i32,
+        device_pixel_ratio: f64,
+    ) -> bool {
+        let schedule = self.pending.is_none();
+        self.pending = Some(backing_size(
+            client_width,
+            client_height,
+            device_pixel_ratio,
+        ));
+        schedule
+    }
+
+    /// The latest observed backing size, unless it is empty or already applied.
+    pub fn take(&mut self) -> Option<(u32, u32)> {
+        let size = self.pending.take()?;
+        if size.0 == 0 || size.1 == 0 || self.applied == Some(size) {
+            return None;
+        }
+        self.applied = Some(size);
+        Some(size)
+    }
+}
+
+/// Keeps a canvas' backing store at its CSS size × devicePixelRatio. Stops observing when
+/// dropped.
+#[cfg(target_arch = "wasm32")]
+pub struct CanvasResizeObserver {
+    observer: ResizeObserver,
+    _on_resize: Closure<dyn FnMut(Array)>,
+    _on_frame: Rc<Closure<dyn FnMut()>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl CanvasResizeObserver {
+    /// Once per animation frame after the canvas was resized or zoomed, updates its `width` and
+    /// `height` attributes and calls `resize` with the new physical size.
+    pub fn observe(
+        canvas: HtmlCanvasElement,
+        resize: impl Fn(u32, u32) + 'static,
+    ) -> Result<Self, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No browser window"))?;
+        let debouncer = Rc::new(RefCell::new(ResizeDebouncer::new()));
+
+        let on_frame = Rc::new(Closure::<dyn FnMut()>::new({
+            let canvas = canvas.clone();
+            let debouncer = debouncer.clone();
+            move || {
+                let Some((width, height)) = debouncer.borrow_mut().take() else {
+                    return;
+                };
+                canvas.set_width(width);
+                canvas.set_height(height);
+                resize(width, height);
+            }
+        }));
+
+        let on_resize = Closure::<dyn FnMut(Array)>::new({
+            let canvas = canvas.clone();
+            let on_frame = on_frame.clone();
+            move |_entries: Array| {
+                let schedule = debouncer.borrow_mut().observe(
+                    canvas.client_width(),
+                    canvas.client_height(),
+                    window.device_pixel_ratio(),
+                );
+                if schedule {
+                    if let Err(error) =
+                        window.request_animation_frame((*on_frame).as_ref().unchecked_ref())
+                    {
+                        log::warn!("Failed to schedule canvas resize: {error:?}");
+                    }
+                }
+            }
+        });
+
+        let observer = ResizeObserver::new(on_resize.as_ref().unchecked_ref())?;
+        observer.observe(&canvas);
+        Ok(Self {
+            observer,
+            _on_resize: on_resize,
+            _on_frame: on_frame,
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for CanvasResizeObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// Finds the canvas to render into: a canvas element, the id of one, or nothing, in which case a
+/// new canvas is appended to `document.body`.
+#[cfg(target_arch = "wasm32")]
+pub fn resolve_canvas(target: &JsValue) -> Result<HtmlCanvasElement, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No browser window"))?;
+    let document = window
+        .document()
+        .ok_or_else(|| JsValue::from_str("Window has no document"))?;
+
+    let canvas = if target.is_undefined() || target.is_null() {
+        append_canvas(&document)?
+    } else if let Some(id) = target.as_string() {
+        document
+            .get_element_by_id(&id)
+            .ok_or_else(|| JsValue::from_str(&format!("No element with id `{id}`")))?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| JsValue::from_str(&format!("Element `{id}` is not a <canvas>")))?
+    } else {
+        target
+            .clone()
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| JsValue::from_str("Expected a <canvas> element, a canvas id or nothing"))?
+    };
+
+    let (width, height) = backing_size(
+        canvas.client_width(),
+        canvas.client_height(),
+        window.device_pixel_ratio(),
+    );
+    if width > 0 && height > 0 {
+        canvas.set_width(width);
+        canvas.set_height(height);
+    }
+    Ok(canvas)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn append_canvas(document: &Document) -> Result<HtmlCanvasElement, JsValue> {
+    let body = document
+        .body()
+        .ok_or_else(|| JsValue::from_str("Document has no <body> to append a canvas to"))?;
+    let canvas = document
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| JsValue::from_str("Failed to create a <canvas> element"))?;
+    canvas.style().set_property("width", "100%")?;
+    canvas.style().set_property("height", "100%")?;
+    body.append_child(&canvas)?;
+    Ok(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_backing_size_scales_by_device_pixel_ratio() {
+        assert_eq!(backing_size(800, 600, 1.0), (800, 600));
+        assert_eq!(backing_size(800, 600, 1.5), (1200, 900));
+        assert_eq!(backing_size(800, 600, 2.0), (1600, 1200));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_backing_size_ignores_invalid_ratio_and_negative_sizes() {
+        assert_eq!(backing_size(800, 600, 0.0), (800, 600));
+        assert_eq!(backing_size(-5, 600, f64::NAN), (0, 600));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_resize_stream_is_applied_once_per_frame_with_the_latest_size() {
+        let mut debouncer = ResizeDebouncer::new();
+
+        assert!(debouncer.observe(800, 600, 1.0));
+        assert!(!debouncer.observe(810, 600, 1.0));
+        assert!(!debouncer.observe(820, 610, 2.0));
+
+        assert_eq!(debouncer.take(), Some((1640, 1220)));
+        assert_eq!(debouncer.take(), None);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_unchanged_and_empty_sizes_are_not_applied() {
+        let mut debouncer = ResizeDebouncer::new();
+        debouncer.observe(800, 600, 1.5);
+        assert_eq!(debouncer.take(), Some((1200, 900)));
+
+        assert!(debouncer.observe(800, 600, 1.5));
+        assert_eq!(debouncer.take(), None);
+
+        debouncer.observe(0, 600, 1.0);
+        assert_eq!(debouncer.take(), None);
+    }
+}
@@ -11,11 +11,13 @@ use wasm_bindgen::{JsValue, prelude::wasm_bindgen};
 
 #[cfg(target_arch = "wasm32")]
 pub mod bindings;
+pub mod canvas;
+pub mod logging;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod bindings {}
 
-pub use hyakou_core::types::upload_status::UploadStatusEvent;
+pub use hyakou_core::types::{asset_status::AssetStatusEvent, upload_status::UploadStatusEvent};
 #[wasm_bindgen(typescript_custom_section)]
 const CAMERA_ANIMATION_TYPES: &str = r#"
 export type CameraAnimationEasingName = "linear" | "ease-in" | "ease-out" | "ease-in-out";
@@ -137,6 +139,11 @@ impl CameraDO {
     pub fn get_camera_id(&self) -> Id {
         self.id.clone()
     }
+
+    #[wasm_bindgen(getter, js_name = fovDegrees)]
+    pub fn fov_degrees(&self) -> f32 {
+        self.fovy.to_degrees()
+    }
 }
 
 fn parse_camera_animation_easing(value: Option<&str>) -> Result<CameraAnimationEasing, String> {
@@ -228,5 +235,6 @@ mod tests {
         assert_eq!(camera_do.speed, 20.0);
         assert_eq!(camera_do.sensitivity, 0.5);
         assert_eq!(camera_do.smoothing_factor, 0.25);
+        assert!((camera_do.fov_degrees() - 45.0).abs() < 1e-4);
     }
 }
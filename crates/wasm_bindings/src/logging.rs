@@ -0,0 +1,109 @@
+use log::LevelFilter;
+
+#[cfg(target_arch = "wasm32")]
+use std::cell::RefCell;
+
+#[cfg(target_arch = "wasm32")]
+use log::{Level, Log, Metadata, Record};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsValue, prelude::wasm_bindgen};
+
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// Parses a level name like `"warn"` or `"DEBUG"`; `None` and blank strings mean
+/// [`DEFAULT_LOG_LEVEL`].
+pub fn parse_log_level(value: Option<&str>) -> Result<LevelFilter, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(DEFAULT_LOG_LEVEL);
+    }
+    value.parse().map_err(|_| {
+        format!("Unsupported log level `{value}`, expected off, error, warn, info, debug or trace")
+    })
+}
+
+/// Sets the log level and, optionally, a `sink(level, message)` callback that receives every warn
+/// and error record, e.g. to show import failures in the page. Calling it again only replaces the
+/// level and sink.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn init_logging(level: Option<String>, sink: Option<js_sys::Function>) -> Result<(), JsValue> {
+    let level = parse_log_level(level.as_deref()).map_err(|error| JsValue::from_str(&error))?;
+    console_error_panic_hook::set_once();
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+    LOG_SINK.with(|current| *current.borrow_mut() = sink);
+    Ok(())
+}
+
+/// Installs the logger at [`DEFAULT_LOG_LEVEL`] unless [`init_logging`] already ran.
+#[cfg(target_arch = "wasm32")]
+pub fn ensure_logging() {
+    console_error_panic_hook::set_once();
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(DEFAULT_LOG_LEVEL);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static LOG_SINK: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+#[cfg(target_arch = "wasm32")]
+static LOGGER: WebLogger = WebLogger;
+
+/// Writes to the browser console and forwards warnings and errors to the JS sink, if any.
+#[cfg(target_arch = "wasm32")]
+struct WebLogger;
+
+#[cfg(target_arch = "wasm32")]
+impl Log for WebLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        console_log::log(record);
+
+        if record.level() > Level::Warn {
+            return;
+        }
+        // Cloned out so a sink that logs or replaces itself does not hit a borrowed RefCell.
+        let Some(sink) = LOG_SINK.with(|sink| sink.borrow().clone()) else {
+            return;
+        };
+        let _ = sink.call2(
+            &JsValue::NULL,
+            &JsValue::from_str(&record.level().as_str().to_lowercase()),
+            &JsValue::from_str(&record.args().to_string()),
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_log_level_names_are_parsed_case_insensitively() {
+        assert_eq!(parse_log_level(Some("warn")), Ok(LevelFilter::Warn));
+        assert_eq!(parse_log_level(Some("DEBUG")), Ok(LevelFilter::Debug));
+        assert_eq!(parse_log_level(Some(" error ")), Ok(LevelFilter::Error));
+        assert_eq!(parse_log_level(Some("off")), Ok(LevelFilter::Off));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_missing_log_level_uses_default_and_unknown_is_rejected() {
+        assert_eq!(parse_log_level(None), Ok(DEFAULT_LOG_LEVEL));
+        assert_eq!(parse_log_level(Some("")), Ok(DEFAULT_LOG_LEVEL));
+        assert!(parse_log_level(Some("verbose")).is_err());
+    }
+}
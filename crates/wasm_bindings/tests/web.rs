@@ -7,3 +7,272 @@ fn camera_animation_options_accepts_known_easing() {
 
     assert!(options.is_ok());
 }
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use hyako_wasm_bindings::bindings::Hyako;
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    use hyako::{
+        flow::{Clock, ListenerRegistry, ManualClock},
+        state::AppState,
+    };
+    use hyako_wasm_bindings::{canvas::resolve_canvas, logging::init_logging};
+    use hyakou_core::{
+        shared,
+        types::shared::{AssetInformation, Coordinates3},
+    };
+    use js_sys::{Array, Function, Promise, Reflect, Uint8Array};
+    use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const MONKEY_GLB: &[u8] = include_bytes!("../../hyako/assets/gltf/monkey.glb");
+
+    async fn next_frame() {
+        let promise = Promise::new(&mut |resolve, _| {
+            web_sys::window()
+                .unwrap()
+                .request_animation_frame(&resolve)
+                .unwrap();
+        });
+        JsFuture::from(promise).await.unwrap();
+    }
+
+    async fn ready_hyako() -> Hyako {
+        let mut hyako = Hyako::new(JsValue::UNDEFINED).unwrap();
+        hyako.start_rendering().unwrap();
+        for _ in 0..600 {
+            if hyako.is_renderer_ready().unwrap() {
+                break;
+            }
+            next_frame().await;
+        }
+        assert!(hyako.is_renderer_ready().unwrap());
+        hyako
+    }
+
+    #[wasm_bindgen_test]
+    async fn add_asset_resolves_with_created_mesh_ids() {
+        let hyako = ready_hyako().await;
+
+        let ids = JsFuture::from(hyako.add_asset(
+            "monkey.glb".to_string(),
+            Uint8Array::from(MONKEY_GLB),
+            true,
+        ))
+        .await
+        .unwrap();
+
+        let ids = Array::from(&ids);
+        assert!(ids.length() > 0);
+        assert!(
+            ids.iter()
+                .all(|id| id.as_string().unwrap().starts_with("monkey"))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn camera_settings_round_trip_through_get_camera() {
+        let hyako = ready_hyako().await;
+        let eye = Coordinates3::new(4.0, 3.0, 6.0);
+        let target = Coordinates3::new(1.0, 0.0, -2.0);
+
+        hyako.set_camera(eye, target).unwrap();
+        hyako.set_fov(60.0).unwrap();
+
+        let camera = hyako.get_camera().unwrap();
+        assert_eq!(camera.eye, eye);
+        assert_eq!(camera.target, target);
+        assert!((camera.fov_degrees() - 60.0).abs() < 1e-3);
+    }
+
+    #[wasm_bindgen_test]
+    async fn invalid_camera_settings_are_rejected() {
+        let hyako = ready_hyako().await;
+        let before = hyako.get_camera().unwrap();
+
+        let nan = Coordinates3::new(f32::NAN, 0.0, 0.0);
+        assert!(hyako.set_camera(nan, before.target).is_err());
+        assert!(hyako.set_camera(before.eye, before.eye).is_err());
+        assert!(hyako.set_fov(0.0).is_err());
+        assert!(hyako.focus_asset("missing".to_string()).is_err());
+
+        let after = hyako.get_camera().unwrap();
+        assert_eq!(after.eye, before.eye);
+        assert_eq!(after.target, before.target);
+        assert_eq!(after.fovy, before.fovy);
+    }
+
+    fn status_field(event: &JsValue, field: &str) -> Option<String> {
+        Reflect::get(event, &JsValue::from_str(field))
+            .unwrap()
+            .as_string()
+    }
+
+    #[wasm_bindgen_test]
+    async fn truncated_upload_reports_failure_to_status_listeners() {
+        let hyako = ready_hyako().await;
+        let events: Rc<RefCell<Vec<(String, String, Option<String>)>>> = Rc::default();
+        let recorded = events.clone();
+        let listener = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            recorded.borrow_mut().push((
+                status_field(&event, "id").unwrap(),
+                status_field(&event, "status").unwrap(),
+                status_field(&event, "message"),
+            ));
+        });
+        let subscription =
+            hyako.on_asset_status(listener.as_ref().unchecked_ref::<Function>().clone());
+        let other = hyako.on_asset_status(Function::new_no_args(""));
+        other.dispose();
+
+        let truncated = MONKEY_GLB[..MONKEY_GLB.len() / 2].to_vec();
+        let size = truncated.len() as u64;
+        hyako
+            .upload_file(
+                AssetInformation::new(
+                    "truncated-monkey".to_string(),
+                    truncated,
+                    "monkey.glb".to_string(),
+                    size,
+                    0,
+                ),
+                None,
+            )
+            .unwrap();
+        for _ in 0..600 {
+            if events
+                .borrow()
+                .iter()
+                .any(|(_, status, _)| status == "failed")
+            {
+                break;
+            }
+            next_frame().await;
+        }
+
+        let events = events.borrow();
+        assert_eq!(events[0].0, "truncated-monkey");
+        assert_eq!(events[0].1, "loading");
+        let (id, _, message) = events
+            .iter()
+            .find(|(_, status, _)| status == "failed")
+            .expect("truncated bytes should fail to load");
+        assert_eq!(id, "truncated-monkey");
+        assert!(
+            message
+                .as_deref()
+                .is_some_and(|message| !message.is_empty())
+        );
+
+        subscription.dispose();
+        subscription.dispose();
+    }
+
+    #[wasm_bindgen_test]
+    fn consecutive_animation_frames_produce_sane_deltas() {
+        let canvas = resolve_canvas(&JsValue::UNDEFINED).unwrap();
+        let mut state = AppState::from_canvas_ref(
+            canvas,
+            shared(None),
+            shared(ListenerRegistry::new()),
+            shared(ListenerRegistry::new()),
+        )
+        .unwrap();
+        let clock = ManualClock::new();
+        state.set_clock(clock.clone());
+        let frame = Duration::from_secs_f64(1.0 / 60.0);
+
+        clock.advance(frame);
+        let first = state.begin_frame(clock.now());
+        clock.advance(frame);
+        let second = state.begin_frame(clock.now());
+
+        for delta in [first, second] {
+            assert!((delta.seconds - frame.as_secs_f64()).abs() < 1e-6);
+            assert!(!delta.clamped);
+        }
+
+        // A backgrounded tab gets no animation frames for a while.
+        clock.advance(Duration::from_secs(5));
+        let resumed = state.begin_frame(clock.now());
+        assert!(resumed.clamped);
+        assert_eq!(resumed.seconds, state.max_frame_delta());
+    }
+
+    async fn wait_frames(count: usize) {
+        for _ in 0..count {
+            next_frame().await;
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn logging_can_be_reinitialized_and_forwards_warnings_to_the_sink() {
+        let records = Array::new();
+        let sink = Function::new_with_args("level, message", "this.push([level, message]);")
+            .bind(&records);
+
+        init_logging(Some("info".to_string()), None).unwrap();
+        init_logging(Some("warn".to_string()), Some(sink)).unwrap();
+        log::info!("filtered out");
+        log::warn!("model has no normals");
+        init_logging(Some("warn".to_string()), None).unwrap();
+        log::error!("not forwarded after the sink was removed");
+
+        assert_eq!(records.length(), 1);
+        let record = Array::from(&records.get(0));
+        assert_eq!(record.get(0).as_string().as_deref(), Some("warn"));
+        assert_eq!(
+            record.get(1).as_string().as_deref(),
+            Some("model has no normals")
+        );
+        assert!(init_logging(Some("loud".to_string()), None).is_err());
+        init_logging(Some("debug".to_string()), None).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn backend_reports_webgpu_or_the_webgl2_fallback() {
+        let hyako = ready_hyako().await;
+
+        let backend = hyako.backend().unwrap();
+
+        assert!(backend == "webgpu" || backend == "gl", "{backend}");
+    }
+
+    #[wasm_bindgen_test]
+    async fn selection_listeners_fire_once_per_change() {
+        let hyako = ready_hyako().await;
+        let ids = JsFuture::from(hyako.add_asset(
+            "monkey.glb".to_string(),
+            Uint8Array::from(MONKEY_GLB),
+            true,
+        ))
+        .await
+        .unwrap();
+        let mesh_id = Array::from(&ids).get(0).as_string().unwrap();
+
+        let changes: Rc<RefCell<Vec<Option<String>>>> = Rc::default();
+        let recorded = changes.clone();
+        let listener = Closure::<dyn FnMut(JsValue)>::new(move |selected: JsValue| {
+            recorded.borrow_mut().push(selected.as_string());
+        });
+        let subscription =
+            hyako.on_selection_changed(listener.as_ref().unchecked_ref::<Function>().clone());
+
+        hyako.select(mesh_id.clone()).unwrap();
+        wait_frames(3).await;
+        hyako.select(mesh_id.clone()).unwrap();
+        wait_frames(3).await;
+        hyako.clear_selection().unwrap();
+        wait_frames(3).await;
+        hyako.clear_selection().unwrap();
+        wait_frames(3).await;
+
+        assert_eq!(*changes.borrow(), vec![Some(mesh_id), None]);
+        assert!(hyako.select("missing".to_string()).is_err());
+        subscription.dispose();
+    }
+}